@@ -26,7 +26,7 @@ pub struct MonteCarloReport {
     /// 列表数据。
     pub simulations: Vec<SimulationResult>,
 }
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct Stats {
     pub p95: f64, // 95% worse case (for DD) or best case
     pub p50: f64, // Median
@@ -38,6 +38,18 @@ pub struct Stats {
     /// 最大。
     pub max: f64,
 }
+/// 打乱交易顺序后，某个交易序号处的权益分位。
+#[derive(Debug, Serialize, Clone)]
+pub struct EquityBand {
+    /// 交易序号，0 表示期初资金。
+    pub trade_index: usize,
+    /// 5% 分位权益。
+    pub p05: f64,
+    /// 中位数权益。
+    pub p50: f64,
+    /// 95% 分位权益。
+    pub p95: f64,
+}
 pub struct MonteCarloAnalyzer {
     /// initialcapital。
     initial_capital: f64,
@@ -66,6 +78,36 @@ impl MonteCarloAnalyzer {
             simulations,
         }
     }
+    /// 按交易序号统计打乱顺序后的权益分位带，供回测报告绘制置信区间。
+    ///
+    /// 所有排列的最终权益相同，分位带只反映路径顺序带来的中途波动。
+    pub fn equity_bands(&self, pnls: &[f64], iterations: usize) -> Vec<EquityBand> {
+        let mut rng = thread_rng();
+        let mut equity_by_index = vec![Vec::with_capacity(iterations); pnls.len() + 1];
+        let mut shuffled_pnls = pnls.to_vec();
+        for _ in 0..iterations {
+            shuffled_pnls.shuffle(&mut rng);
+            let mut equity = self.initial_capital;
+            equity_by_index[0].push(equity);
+            for (index, pnl) in shuffled_pnls.iter().enumerate() {
+                equity += pnl;
+                equity_by_index[index + 1].push(equity);
+            }
+        }
+        equity_by_index
+            .iter()
+            .enumerate()
+            .map(|(trade_index, values)| {
+                let stats = Self::calculate_stats(values);
+                EquityBand {
+                    trade_index,
+                    p05: stats.p05,
+                    p50: stats.p50,
+                    p95: stats.p95,
+                }
+            })
+            .collect()
+    }
     /// 计算 量化核心 指标，保持公式和边界处理集中可审计。
     fn calculate_metrics(&self, pnls: &[f64]) -> SimulationResult {
         let mut current_capital = self.initial_capital;
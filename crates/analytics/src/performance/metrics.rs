@@ -6,14 +6,25 @@
 //! - 绝对收益率 (Total Return): 总收益
 //! - 最大回撤 (Maximum Drawdown): 风险指标
 //! - 波动率 (Volatility): 风险指标
-use chrono::NaiveDateTime;
+//!
+//! 扩展指标只统计平仓记录（`close_type` 非空），入场记录不参与:
+//! - 索提诺比率 / 卡玛比率: 下行风险与回撤调整后收益
+//! - 盈亏因子 / R 期望: 单笔交易质量
+//! - 平均持仓时长 / 持仓暴露: 资金占用
+//! - 最长回撤持续时间 / 连胜连亏 / 尾部比率: 路径风险
+use chrono::{Local, NaiveDateTime, TimeZone};
 use rust_quant_strategies::strategy_common::TradeRecord;
+use serde::Serialize;
 /// 无风险利率 (年化 2%)
 const RISK_FREE_RATE: f64 = 0.02;
 /// 一年的天数 (用于年化计算)
 const DAYS_PER_YEAR: f64 = 365.0;
+/// 分母为 0 时比率类指标的上限，与夏普比率的限制保持一致
+const RATIO_CAP: f64 = 100.0;
+const MS_PER_HOUR: f64 = 1000.0 * 60.0 * 60.0;
+const MS_PER_DAY: f64 = MS_PER_HOUR * 24.0;
 /// 绩效指标计算结果
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize)]
 pub struct PerformanceMetrics {
     /// 夏普比率
     pub sharpe_ratio: f64,
@@ -25,6 +36,26 @@ pub struct PerformanceMetrics {
     pub max_drawdown: f64,
     /// 波动率 (年化)
     pub volatility: f64,
+    /// 索提诺比率: (年化收益率 - 无风险利率) / 年化下行波动率
+    pub sortino_ratio: f64,
+    /// 卡玛比率: 年化收益率 / 最大回撤
+    pub calmar_ratio: f64,
+    /// 盈亏因子: 总盈利 / 总亏损绝对值
+    pub profit_factor: f64,
+    /// R 期望: 有初始风险的平仓记录 `net_profit_r` 均值；没有 R 口径时为空
+    pub expectancy_r: Option<f64>,
+    /// 平均持仓时长 (小时)
+    pub average_hold_hours: f64,
+    /// 持仓暴露: 有持仓的时间占回测区间的比例 (0~1)
+    pub exposure: f64,
+    /// 最长回撤持续时间 (天): 从权益高点到重新创新高，未恢复时截至最后一笔平仓
+    pub longest_drawdown_days: f64,
+    /// 最大连胜笔数
+    pub max_win_streak: u32,
+    /// 最大连亏笔数
+    pub max_loss_streak: u32,
+    /// 尾部比率: 单笔收益率 95 分位 / 5 分位绝对值
+    pub tail_ratio: f64,
 }
 /// 绩效计算器
 pub struct PerformanceCalculator {
@@ -71,12 +102,33 @@ impl PerformanceCalculator {
         let (max_drawdown, equity_curve) = self.calculate_max_drawdown();
         let volatility = self.calculate_volatility(&equity_curve, actual_trading_days);
         let sharpe_ratio = self.calculate_sharpe_ratio(annual_return, volatility);
+        let closes: Vec<&TradeRecord> = self
+            .trade_records
+            .iter()
+            .filter(|record| !record.close_type.is_empty())
+            .collect();
+        let close_returns = self.calculate_close_returns(&closes);
+        let (max_win_streak, max_loss_streak) = Self::calculate_streaks(&closes);
         PerformanceMetrics {
             sharpe_ratio,
             annual_return,
             total_return,
             max_drawdown,
             volatility,
+            sortino_ratio: Self::calculate_sortino_ratio(
+                annual_return,
+                &close_returns,
+                actual_trading_days,
+            ),
+            calmar_ratio: Self::calculate_calmar_ratio(annual_return, max_drawdown),
+            profit_factor: Self::calculate_profit_factor(&closes),
+            expectancy_r: Self::calculate_expectancy_r(&closes),
+            average_hold_hours: Self::calculate_average_hold_hours(&closes),
+            exposure: self.calculate_exposure(&closes),
+            longest_drawdown_days: self.calculate_longest_drawdown_days(&closes),
+            max_win_streak,
+            max_loss_streak,
+            tail_ratio: Self::calculate_tail_ratio(&close_returns),
         }
     }
     /// 计算绝对收益率
@@ -249,6 +301,232 @@ impl PerformanceCalculator {
         }
         (annual_return - RISK_FREE_RATE) / volatility
     }
+    /// 平仓记录相对平仓前权益的单笔收益率
+    fn calculate_close_returns(&self, closes: &[&TradeRecord]) -> Vec<f64> {
+        let mut returns = Vec::with_capacity(closes.len());
+        let mut running_equity = self.initial_fund;
+        for record in closes {
+            if running_equity > 0.0 {
+                returns.push(record.profit_loss / running_equity);
+            }
+            running_equity += record.profit_loss;
+        }
+        returns
+    }
+    /// 计算索提诺比率
+    /// 公式: (年化收益率 - 无风险利率) / (下行偏差 * sqrt(每年交易次数))
+    /// 下行偏差只累计亏损交易的收益率平方，盈利交易按 0 计入样本数
+    fn calculate_sortino_ratio(annual_return: f64, returns: &[f64], trading_days: f64) -> f64 {
+        if returns.is_empty() {
+            return 0.0;
+        }
+        let downside_variance =
+            returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / returns.len() as f64;
+        let trading_years = trading_days / DAYS_PER_YEAR;
+        let trades_per_year = if trading_years > 0.0 {
+            returns.len() as f64 / trading_years
+        } else {
+            returns.len() as f64
+        };
+        let downside_deviation = downside_variance.sqrt() * trades_per_year.sqrt();
+        if downside_deviation <= 0.0 {
+            return if annual_return > RISK_FREE_RATE {
+                RATIO_CAP
+            } else {
+                0.0
+            };
+        }
+        (annual_return - RISK_FREE_RATE) / downside_deviation
+    }
+    /// 计算卡玛比率
+    /// 公式: 年化收益率 / 最大回撤
+    fn calculate_calmar_ratio(annual_return: f64, max_drawdown: f64) -> f64 {
+        if max_drawdown <= 0.0 {
+            return if annual_return > 0.0 { RATIO_CAP } else { 0.0 };
+        }
+        (annual_return / max_drawdown).min(RATIO_CAP)
+    }
+    /// 计算盈亏因子
+    /// 公式: 盈利交易盈亏之和 / 亏损交易盈亏之和的绝对值
+    fn calculate_profit_factor(closes: &[&TradeRecord]) -> f64 {
+        let gross_profit: f64 = closes
+            .iter()
+            .map(|r| r.profit_loss)
+            .filter(|pnl| *pnl > 0.0)
+            .sum();
+        let gross_loss: f64 = closes
+            .iter()
+            .map(|r| r.profit_loss)
+            .filter(|pnl| *pnl < 0.0)
+            .sum::<f64>()
+            .abs();
+        if gross_loss <= 0.0 {
+            return if gross_profit > 0.0 { RATIO_CAP } else { 0.0 };
+        }
+        (gross_profit / gross_loss).min(RATIO_CAP)
+    }
+    /// 计算 R 期望，只统计带 `net_profit_r` 的平仓记录
+    fn calculate_expectancy_r(closes: &[&TradeRecord]) -> Option<f64> {
+        let r_values: Vec<f64> = closes
+            .iter()
+            .filter_map(|r| r.net_profit_r)
+            .filter(|r| r.is_finite())
+            .collect();
+        if r_values.is_empty() {
+            return None;
+        }
+        Some(r_values.iter().sum::<f64>() / r_values.len() as f64)
+    }
+    /// 计算平均持仓时长 (小时)，时间无法解析的记录不参与
+    fn calculate_average_hold_hours(closes: &[&TradeRecord]) -> f64 {
+        let hold_hours: Vec<f64> = closes
+            .iter()
+            .filter_map(|r| Self::holding_interval(r))
+            .map(|(open, close)| (close - open) as f64 / MS_PER_HOUR)
+            .collect();
+        if hold_hours.is_empty() {
+            return 0.0;
+        }
+        hold_hours.iter().sum::<f64>() / hold_hours.len() as f64
+    }
+    /// 计算持仓暴露
+    /// 合并所有平仓记录的 [开仓, 平仓] 区间（分批平仓共用开仓时间，不会重复计入），
+    /// 除以 K 线区间；K 线区间无效时改用首笔开仓到末笔平仓
+    fn calculate_exposure(&self, closes: &[&TradeRecord]) -> f64 {
+        let mut intervals: Vec<(i64, i64)> = closes
+            .iter()
+            .filter_map(|r| Self::holding_interval(r))
+            .collect();
+        if intervals.is_empty() {
+            return 0.0;
+        }
+        intervals.sort_unstable();
+        let (range_start, range_end) = if self.end_time > self.start_time {
+            (self.start_time, self.end_time)
+        } else {
+            let first_open = intervals[0].0;
+            let last_close = intervals.iter().map(|(_, close)| *close).max().unwrap_or(0);
+            (first_open, last_close)
+        };
+        if range_end <= range_start {
+            return 0.0;
+        }
+        let mut covered = 0i64;
+        let mut current: Option<(i64, i64)> = None;
+        for (open, close) in intervals {
+            let (open, close) = (open.max(range_start), close.min(range_end));
+            if close <= open {
+                continue;
+            }
+            current = match current {
+                Some((start, end)) if open <= end => Some((start, end.max(close))),
+                Some((start, end)) => {
+                    covered += end - start;
+                    Some((open, close))
+                }
+                None => Some((open, close)),
+            };
+        }
+        if let Some((start, end)) = current {
+            covered += end - start;
+        }
+        (covered as f64 / (range_end - range_start) as f64).clamp(0.0, 1.0)
+    }
+    /// 计算最长回撤持续时间 (天)，基于平仓权益
+    fn calculate_longest_drawdown_days(&self, closes: &[&TradeRecord]) -> f64 {
+        let Some(mut peak_time) = closes
+            .first()
+            .and_then(|r| Self::parse_timestamp_ms(&r.open_position_time))
+        else {
+            return 0.0;
+        };
+        let mut equity = self.initial_fund;
+        let mut peak_equity = self.initial_fund;
+        let mut underwater_since: Option<i64> = None;
+        let mut last_time = peak_time;
+        let mut longest_ms = 0i64;
+        for record in closes {
+            equity += record.profit_loss;
+            let Some(close_time) = record
+                .close_position_time
+                .as_deref()
+                .and_then(Self::parse_timestamp_ms)
+            else {
+                continue;
+            };
+            last_time = last_time.max(close_time);
+            if equity >= peak_equity {
+                if let Some(since) = underwater_since.take() {
+                    longest_ms = longest_ms.max(close_time - since);
+                }
+                peak_equity = equity;
+                peak_time = close_time;
+            } else if underwater_since.is_none() {
+                underwater_since = Some(peak_time);
+            }
+        }
+        if let Some(since) = underwater_since {
+            longest_ms = longest_ms.max(last_time - since);
+        }
+        longest_ms.max(0) as f64 / MS_PER_DAY
+    }
+    /// 计算最大连胜、连亏笔数；盈亏为 0 的交易同时打断两种连续
+    fn calculate_streaks(closes: &[&TradeRecord]) -> (u32, u32) {
+        let (mut wins, mut losses) = (0u32, 0u32);
+        let (mut max_wins, mut max_losses) = (0u32, 0u32);
+        for record in closes {
+            if record.profit_loss > 0.0 {
+                wins += 1;
+                losses = 0;
+            } else if record.profit_loss < 0.0 {
+                losses += 1;
+                wins = 0;
+            } else {
+                wins = 0;
+                losses = 0;
+            }
+            max_wins = max_wins.max(wins);
+            max_losses = max_losses.max(losses);
+        }
+        (max_wins, max_losses)
+    }
+    /// 计算尾部比率
+    /// 公式: |单笔收益率 95 分位| / |单笔收益率 5 分位|，样本不足 2 笔时返回 0
+    fn calculate_tail_ratio(returns: &[f64]) -> f64 {
+        if returns.len() < 2 {
+            return 0.0;
+        }
+        let mut sorted = returns.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let right_tail = Self::percentile(&sorted, 0.95).abs();
+        let left_tail = Self::percentile(&sorted, 0.05).abs();
+        if left_tail <= f64::EPSILON {
+            return if right_tail > 0.0 { RATIO_CAP } else { 0.0 };
+        }
+        (right_tail / left_tail).min(RATIO_CAP)
+    }
+    /// 已排序样本的线性插值分位数
+    fn percentile(sorted: &[f64], quantile: f64) -> f64 {
+        let position = quantile * (sorted.len() - 1) as f64;
+        let lower = position.floor() as usize;
+        let upper = position.ceil() as usize;
+        let weight = position - lower as f64;
+        sorted[lower] + (sorted[upper] - sorted[lower]) * weight
+    }
+    /// 平仓记录的持仓区间 (毫秒时间戳)
+    fn holding_interval(record: &TradeRecord) -> Option<(i64, i64)> {
+        let open = Self::parse_timestamp_ms(&record.open_position_time)?;
+        let close = Self::parse_timestamp_ms(record.close_position_time.as_deref()?)?;
+        (close >= open).then_some((open, close))
+    }
+    /// 交易时间按本地时区写出（见 `mill_time_to_datetime`），这里按同一时区还原为毫秒时间戳
+    fn parse_timestamp_ms(s: &str) -> Option<i64> {
+        let datetime = Self::parse_datetime(s)?;
+        Local
+            .from_local_datetime(&datetime)
+            .earliest()
+            .map(|dt| dt.timestamp_millis())
+    }
 }
 /// 便捷函数：计算回测绩效指标
 /// # 参数
@@ -308,6 +586,7 @@ mod tests {
             initial_stop_price: None,
             initial_risk_amount: None,
             net_profit_r: None,
+            funding_fee: None,
         }
     }
     #[test]
@@ -369,5 +648,72 @@ mod tests {
         let metrics = calculate_performance_metrics(100.0, 100.0, &trades, start_time, end_time);
         assert_eq!(metrics.total_return, 0.0);
         assert_eq!(metrics.max_drawdown, 0.0);
+        assert_eq!(metrics.expectancy_r, None);
+        assert_eq!(metrics.exposure, 0.0);
+        assert_eq!((metrics.max_win_streak, metrics.max_loss_streak), (0, 0));
+    }
+    /// 按本地时区把测试时间转为毫秒时间戳，与交易记录的时间口径一致。
+    fn local_ms(s: &str) -> i64 {
+        PerformanceCalculator::parse_timestamp_ms(s).unwrap()
+    }
+    #[test]
+    fn test_profit_factor_streaks_and_expectancy_use_close_records_only() {
+        let r_values = [2.0, 1.0, -1.0, -1.0, -1.0, 4.0];
+        let pnls = [10.0, 5.0, -5.0, -3.0, -2.0, 20.0];
+        let mut trades = Vec::new();
+        for (index, (pnl, r)) in pnls.iter().zip(r_values).enumerate() {
+            let open = format!("2024-01-{:02} 00:00:00", index * 2 + 1);
+            let close = format!("2024-01-{:02} 12:00:00", index * 2 + 1);
+            let mut entry = create_test_trade_record_with_time(0.0, &open, &open);
+            entry.close_type = String::new();
+            trades.push(entry);
+            let mut exit = create_test_trade_record_with_time(*pnl, &open, &close);
+            exit.net_profit_r = Some(r);
+            trades.push(exit);
+        }
+        let metrics = calculate_performance_metrics(
+            100.0,
+            125.0,
+            &trades,
+            local_ms("2024-01-01 00:00:00"),
+            local_ms("2024-01-13 00:00:00"),
+        );
+        // 总盈利 35 / 总亏损 10
+        assert!((metrics.profit_factor - 3.5).abs() < 1e-9);
+        assert!((metrics.expectancy_r.unwrap() - 4.0 / 6.0).abs() < 1e-9);
+        assert_eq!((metrics.max_win_streak, metrics.max_loss_streak), (2, 3));
+        assert!((metrics.average_hold_hours - 12.0).abs() < 1e-9);
+        // 6 笔各持仓 12 小时，共 3 天 / 12 天
+        assert!((metrics.exposure - 0.25).abs() < 1e-9);
+        assert!(metrics.sortino_ratio > 0.0);
+        assert!(metrics.tail_ratio > 0.0);
+    }
+    #[test]
+    fn test_longest_drawdown_duration_until_new_high() {
+        let trades = vec![
+            create_test_trade_record_with_time(10.0, "2024-01-01 00:00:00", "2024-01-02 00:00:00"),
+            create_test_trade_record_with_time(-5.0, "2024-01-03 00:00:00", "2024-01-04 00:00:00"),
+            create_test_trade_record_with_time(2.0, "2024-01-05 00:00:00", "2024-01-06 00:00:00"),
+            create_test_trade_record_with_time(4.0, "2024-01-08 00:00:00", "2024-01-09 00:00:00"),
+            create_test_trade_record_with_time(-1.0, "2024-01-10 00:00:00", "2024-01-11 00:00:00"),
+        ];
+        let metrics = calculate_performance_metrics(100.0, 110.0, &trades, 0, 0);
+        // 1 月 2 日创新高 110，1 月 9 日回到 111；之后未恢复的回撤只持续 2 天
+        assert!((metrics.longest_drawdown_days - 7.0).abs() < 1e-9);
+        assert_eq!((metrics.max_win_streak, metrics.max_loss_streak), (2, 1));
+    }
+    #[test]
+    fn test_ratio_caps_without_losses() {
+        let trades = vec![
+            create_test_trade_record_with_time(10.0, "2024-01-01", "2024-01-10"),
+            create_test_trade_record_with_time(5.0, "2024-01-15", "2024-01-20"),
+        ];
+        let end_time = 30 * 24 * 60 * 60 * 1000i64;
+        let metrics = calculate_performance_metrics(100.0, 115.0, &trades, 0, end_time);
+        assert_eq!(metrics.profit_factor, RATIO_CAP);
+        assert_eq!(metrics.sortino_ratio, RATIO_CAP);
+        assert_eq!(metrics.calmar_ratio, RATIO_CAP);
+        assert!(metrics.tail_ratio > 1.0);
+        assert_eq!(metrics.longest_drawdown_days, 0.0);
     }
 }
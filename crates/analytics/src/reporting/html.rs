@@ -0,0 +1,434 @@
+//! 回测报告 HTML 渲染
+//!
+//! 输出单文件 HTML：图表为内联 SVG，不依赖外部脚本或样式；完整报告 JSON 嵌在
+//! `<script type="application/json" id="report-data">` 中，便于下载后再做二次分析。
+use super::tear_sheet::{BacktestReport, HistogramBucket, MonthlyReturn};
+use std::fmt::Write;
+const CHART_WIDTH: f64 = 960.0;
+const CHART_HEIGHT: f64 = 260.0;
+const CHART_PADDING: f64 = 36.0;
+/// 交易列表最多渲染的行数；完整列表仍保留在嵌入的 JSON 中。
+const MAX_TRADE_ROWS: usize = 500;
+const STYLE: &str = "body{font-family:-apple-system,'Segoe UI','PingFang SC',sans-serif;margin:24px;color:#1f2933;background:#fafbfc}\
+h1{font-size:22px}h2{font-size:17px;margin-top:32px;border-bottom:1px solid #d9e2ec;padding-bottom:4px}\
+table{border-collapse:collapse;font-size:13px;margin-top:8px}th,td{border:1px solid #d9e2ec;padding:4px 8px;text-align:right}\
+th{background:#f0f4f8}td.text{text-align:left}.cards{display:flex;flex-wrap:wrap;gap:12px}\
+.card{background:#fff;border:1px solid #d9e2ec;border-radius:6px;padding:8px 14px;min-width:120px}\
+.card .label{font-size:12px;color:#627d98}.card .value{font-size:18px;font-weight:600}\
+svg{background:#fff;border:1px solid #d9e2ec}.pos{color:#1f7a4d}.neg{color:#b42318}";
+/// 渲染自包含的 HTML 报告。
+pub fn render_backtest_report_html(report: &BacktestReport) -> String {
+    let mut html = String::new();
+    let title = escape_html(&report.title);
+    let _ = write!(
+        html,
+        "<!DOCTYPE html><html lang=\"zh-CN\"><head><meta charset=\"utf-8\"><title>{title}</title><style>{STYLE}</style></head><body><h1>{title}</h1>"
+    );
+    render_summary(&mut html, report);
+    html.push_str("<h2>权益曲线</h2>");
+    let equity: Vec<f64> = report.equity_curve.iter().map(|p| p.equity).collect();
+    html.push_str(&line_chart(&[("#2f6fde", &equity)], None));
+    html.push_str("<h2>回撤</h2>");
+    let drawdown: Vec<f64> = report
+        .equity_curve
+        .iter()
+        .map(|p| -p.drawdown * 100.0)
+        .collect();
+    html.push_str(&line_chart(&[("#b42318", &drawdown)], None));
+    render_monthly_heat_map(&mut html, &report.monthly_returns);
+    html.push_str("<h2>R 倍数分布</h2>");
+    html.push_str(&histogram_chart(&report.r_multiple_histogram));
+    render_monte_carlo(&mut html, report);
+    render_close_types(&mut html, report);
+    render_filtered_signals(&mut html, report);
+    render_trades(&mut html, report);
+    let _ = write!(
+        html,
+        "<script type=\"application/json\" id=\"report-data\">{}</script></body></html>",
+        embedded_json(report)
+    );
+    html
+}
+fn render_summary(html: &mut String, report: &BacktestReport) {
+    let summary = &report.summary;
+    let performance = &summary.performance;
+    let cards = [
+        ("期初资金", format!("{:.2}", report.initial_fund)),
+        ("期末资金", format!("{:.2}", report.final_fund)),
+        ("总收益率", percent(performance.total_return)),
+        ("年化收益率", percent(performance.annual_return)),
+        ("夏普比率", format!("{:.2}", performance.sharpe_ratio)),
+        ("索提诺比率", format!("{:.2}", performance.sortino_ratio)),
+        ("卡玛比率", format!("{:.2}", performance.calmar_ratio)),
+        ("盈亏因子", format!("{:.2}", performance.profit_factor)),
+        ("最大回撤", percent(performance.max_drawdown)),
+        (
+            "最长回撤天数",
+            format!("{:.1}", performance.longest_drawdown_days),
+        ),
+        ("平仓回撤", percent(summary.closed_equity_max_drawdown)),
+        ("波动率", percent(performance.volatility)),
+        ("平仓笔数", summary.trades.to_string()),
+        ("胜率", percent(summary.win_rate)),
+        (
+            "最大连胜/连亏",
+            format!(
+                "{}/{}",
+                performance.max_win_streak, performance.max_loss_streak
+            ),
+        ),
+        ("持仓暴露", percent(performance.exposure)),
+        (
+            "平均持仓小时",
+            format!("{:.1}", performance.average_hold_hours),
+        ),
+        ("尾部比率", format!("{:.2}", performance.tail_ratio)),
+        (
+            "平均 R",
+            summary
+                .average_r
+                .map(|r| format!("{r:.2}"))
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        ("资金费", format!("{:.4}", summary.total_funding_fee)),
+    ];
+    html.push_str("<h2>概览</h2><div class=\"cards\">");
+    for (label, value) in cards {
+        let _ = write!(
+            html,
+            "<div class=\"card\"><div class=\"label\">{label}</div><div class=\"value\">{}</div></div>",
+            escape_html(&value)
+        );
+    }
+    html.push_str("</div>");
+}
+/// 月度收益热力图：行是年份，列是月份，颜色深浅按当月收益率绝对值缩放。
+fn render_monthly_heat_map(html: &mut String, months: &[MonthlyReturn]) {
+    html.push_str("<h2>月度收益</h2>");
+    if months.is_empty() {
+        html.push_str("<p>无平仓记录</p>");
+        return;
+    }
+    let max_abs = months
+        .iter()
+        .map(|m| m.return_rate.abs())
+        .fold(0.0, f64::max)
+        .max(f64::EPSILON);
+    html.push_str("<table><tr><th>年份</th>");
+    for month in 1..=12 {
+        let _ = write!(html, "<th>{month}月</th>");
+    }
+    html.push_str("<th>合计盈亏</th></tr>");
+    let mut years: Vec<i32> = months.iter().map(|m| m.year).collect();
+    years.dedup();
+    for year in years {
+        let _ = write!(html, "<tr><td class=\"text\">{year}</td>");
+        for month in 1..=12 {
+            match months.iter().find(|m| m.year == year && m.month == month) {
+                Some(m) => {
+                    let alpha = (m.return_rate.abs() / max_abs).clamp(0.1, 1.0);
+                    let rgb = if m.return_rate >= 0.0 {
+                        "31,122,77"
+                    } else {
+                        "180,35,24"
+                    };
+                    let _ = write!(
+                        html,
+                        "<td style=\"background:rgba({rgb},{alpha:.2})\" title=\"{} 笔\">{}</td>",
+                        m.trades,
+                        percent(m.return_rate)
+                    );
+                }
+                None => html.push_str("<td></td>"),
+            }
+        }
+        let year_pnl: f64 = months
+            .iter()
+            .filter(|m| m.year == year)
+            .map(|m| m.pnl)
+            .sum();
+        let _ = write!(html, "<td>{}</td></tr>", signed(year_pnl, 2));
+    }
+    html.push_str("</table>");
+}
+fn render_monte_carlo(html: &mut String, report: &BacktestReport) {
+    html.push_str("<h2>蒙特卡洛置信带</h2>");
+    let Some(monte_carlo) = &report.monte_carlo else {
+        html.push_str("<p>平仓记录不足或未开启模拟</p>");
+        return;
+    };
+    let p05: Vec<f64> = monte_carlo.equity_bands.iter().map(|b| b.p05).collect();
+    let p50: Vec<f64> = monte_carlo.equity_bands.iter().map(|b| b.p50).collect();
+    let p95: Vec<f64> = monte_carlo.equity_bands.iter().map(|b| b.p95).collect();
+    let actual: Vec<f64> = report.equity_curve.iter().map(|p| p.equity).collect();
+    html.push_str(&line_chart(
+        &[("#9aa5b1", &p50), ("#2f6fde", &actual)],
+        Some((&p05, &p95)),
+    ));
+    let _ = write!(
+        html,
+        "<table><tr><th class=\"text\">{} 次模拟</th><th>P05</th><th>P50</th><th>P95</th></tr>\
+<tr><td class=\"text\">最大回撤</td><td>{}</td><td>{}</td><td>{}</td></tr>\
+<tr><td class=\"text\">总盈亏</td><td>{}</td><td>{}</td><td>{}</td></tr></table>",
+        monte_carlo.iterations,
+        percent(monte_carlo.max_drawdown.p05),
+        percent(monte_carlo.max_drawdown.p50),
+        percent(monte_carlo.max_drawdown.p95),
+        signed(monte_carlo.profit.p05, 2),
+        signed(monte_carlo.profit.p50, 2),
+        signed(monte_carlo.profit.p95, 2),
+    );
+}
+fn render_close_types(html: &mut String, report: &BacktestReport) {
+    html.push_str("<h2>平仓类型</h2><table><tr><th class=\"text\">平仓类型</th><th>笔数</th><th>胜率</th><th>盈亏</th><th>平均 R</th></tr>");
+    for stats in &report.close_type_breakdown {
+        let _ = write!(
+            html,
+            "<tr><td class=\"text\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&stats.close_type),
+            stats.trades,
+            percent(stats.win_rate),
+            signed(stats.pnl, 2),
+            optional(stats.average_r, 2),
+        );
+    }
+    html.push_str("</table>");
+}
+fn render_filtered_signals(html: &mut String, report: &BacktestReport) {
+    let summary = &report.filtered_signals;
+    let _ = write!(
+        html,
+        "<h2>被过滤信号反事实</h2><p>共 {} 条，放行后盈利 {} / 亏损 {} / 持平 {}，模拟盈亏合计 {}</p>",
+        summary.signals,
+        summary.wins,
+        summary.losses,
+        summary.break_even,
+        signed(summary.total_pnl, 2),
+    );
+    if summary.by_reason.is_empty() {
+        return;
+    }
+    html.push_str("<table><tr><th class=\"text\">过滤原因</th><th>信号数</th><th>盈利</th><th>亏损</th><th>模拟盈亏</th></tr>");
+    for reason in &summary.by_reason {
+        let _ = write!(
+            html,
+            "<tr><td class=\"text\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&reason.reason),
+            reason.signals,
+            reason.wins,
+            reason.losses,
+            signed(reason.pnl, 2),
+        );
+    }
+    html.push_str("</table>");
+}
+fn render_trades(html: &mut String, report: &BacktestReport) {
+    let _ = write!(
+        html,
+        "<h2>交易列表（{} 笔）</h2><table><tr><th class=\"text\">方向</th><th class=\"text\">开仓时间</th><th class=\"text\">平仓时间</th><th>开仓价</th><th>平仓价</th><th>数量</th><th>盈亏</th><th>R</th><th class=\"text\">平仓类型</th></tr>",
+        report.trades.len()
+    );
+    for trade in report.trades.iter().take(MAX_TRADE_ROWS) {
+        let _ = write!(
+            html,
+            "<tr><td class=\"text\">{}</td><td class=\"text\">{}</td><td class=\"text\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"text\">{}</td></tr>",
+            escape_html(trade.side.as_deref().unwrap_or("-")),
+            escape_html(&trade.open_time),
+            escape_html(trade.close_time.as_deref().unwrap_or("-")),
+            trade.open_price,
+            optional(trade.close_price, 4),
+            trade.quantity,
+            signed(trade.profit_loss, 4),
+            optional(trade.net_profit_r, 2),
+            escape_html(&trade.close_type),
+        );
+    }
+    html.push_str("</table>");
+    if report.trades.len() > MAX_TRADE_ROWS {
+        let _ = write!(
+            html,
+            "<p>仅显示前 {MAX_TRADE_ROWS} 笔，完整列表见 report-data JSON</p>"
+        );
+    }
+}
+/// 折线图；`band` 为 (下沿, 上沿) 时先绘制填充区域。
+fn line_chart(series: &[(&str, &[f64])], band: Option<(&[f64], &[f64])>) -> String {
+    let values = series
+        .iter()
+        .flat_map(|(_, values)| values.iter())
+        .chain(
+            band.iter()
+                .flat_map(|(lower, upper)| lower.iter().chain(upper.iter())),
+        )
+        .copied()
+        .filter(|value| value.is_finite());
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    let mut svg = svg_open();
+    if !min.is_finite() {
+        svg.push_str("</svg>");
+        return svg;
+    }
+    let (min, max) = if (max - min).abs() < f64::EPSILON {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    };
+    let len = series
+        .iter()
+        .map(|(_, values)| values.len())
+        .chain(band.iter().map(|(lower, _)| lower.len()))
+        .max()
+        .unwrap_or(0);
+    let x = |index: usize| {
+        CHART_PADDING + index as f64 * (CHART_WIDTH - 2.0 * CHART_PADDING) / (len.max(2) - 1) as f64
+    };
+    let y = |value: f64| {
+        CHART_HEIGHT
+            - CHART_PADDING
+            - (value - min) / (max - min) * (CHART_HEIGHT - 2.0 * CHART_PADDING)
+    };
+    axis_labels(&mut svg, min, max);
+    if let Some((lower, upper)) = band {
+        let mut points: Vec<String> = upper
+            .iter()
+            .enumerate()
+            .map(|(i, v)| format!("{:.1},{:.1}", x(i), y(*v)))
+            .collect();
+        points.extend(
+            lower
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(i, v)| format!("{:.1},{:.1}", x(i), y(*v))),
+        );
+        let _ = write!(
+            svg,
+            "<polygon points=\"{}\" fill=\"#2f6fde\" fill-opacity=\"0.15\" stroke=\"none\"/>",
+            points.join(" ")
+        );
+    }
+    for (color, values) in series {
+        let points: Vec<String> = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| format!("{:.1},{:.1}", x(i), y(*v)))
+            .collect();
+        let _ = write!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"1.5\"/>",
+            points.join(" ")
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+/// R 倍数柱状图，负 R 桶为红色、非负 R 桶为绿色。
+fn histogram_chart(buckets: &[HistogramBucket]) -> String {
+    let mut svg = svg_open();
+    let max_count = buckets.iter().map(|b| b.count).max().unwrap_or(0).max(1) as f64;
+    let slot = (CHART_WIDTH - 2.0 * CHART_PADDING) / buckets.len().max(1) as f64;
+    let plot_height = CHART_HEIGHT - 2.0 * CHART_PADDING;
+    for (index, bucket) in buckets.iter().enumerate() {
+        let height = bucket.count as f64 / max_count * plot_height;
+        let x = CHART_PADDING + index as f64 * slot;
+        let color = if bucket.lower < 0.0 {
+            "#b42318"
+        } else {
+            "#1f7a4d"
+        };
+        let _ = write!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{height:.1}\" fill=\"{color}\"><title>[{}, {}) {} 笔</title></rect>\
+<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"10\" text-anchor=\"middle\">{}</text>",
+            x + 1.0,
+            CHART_HEIGHT - CHART_PADDING - height,
+            (slot - 2.0).max(1.0),
+            bucket.lower,
+            bucket.upper,
+            bucket.count,
+            x + slot / 2.0,
+            CHART_HEIGHT - CHART_PADDING + 14.0,
+            bucket.lower,
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+fn svg_open() -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{CHART_WIDTH}\" height=\"{CHART_HEIGHT}\" viewBox=\"0 0 {CHART_WIDTH} {CHART_HEIGHT}\">"
+    )
+}
+fn axis_labels(svg: &mut String, min: f64, max: f64) {
+    let _ = write!(
+        svg,
+        "<text x=\"4\" y=\"{:.1}\" font-size=\"10\">{max:.2}</text><text x=\"4\" y=\"{:.1}\" font-size=\"10\">{min:.2}</text>",
+        CHART_PADDING,
+        CHART_HEIGHT - CHART_PADDING,
+    );
+}
+/// 嵌入 `<script>` 的 JSON 需要转义 `</`，防止交易备注等字段提前闭合标签。
+fn embedded_json(report: &BacktestReport) -> String {
+    serde_json::to_string(report)
+        .unwrap_or_else(|_| "{}".to_string())
+        .replace("</", "<\\/")
+}
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+fn percent(value: f64) -> String {
+    format!("{:.2}%", value * 100.0)
+}
+fn signed(value: f64, decimals: usize) -> String {
+    let class = if value >= 0.0 { "pos" } else { "neg" };
+    format!("<span class=\"{class}\">{value:.decimals$}</span>")
+}
+fn optional(value: Option<f64>, decimals: usize) -> String {
+    value
+        .map(|value| format!("{value:.decimals$}"))
+        .unwrap_or_else(|| "-".to_string())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporting::{build_backtest_report, BacktestReportConfig};
+    #[test]
+    fn html_report_is_self_contained_and_escapes_user_text() {
+        let config = BacktestReportConfig::default()
+            .with_title("Vegas <BTC> </script>")
+            .with_monte_carlo_iterations(0);
+        let report = build_backtest_report(config, &[], &[]);
+        let html = render_backtest_report_html(&report);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>Vegas &lt;BTC&gt; &lt;/script&gt;</h1>"));
+        assert!(html.contains("<script type=\"application/json\" id=\"report-data\">"));
+        // 整个文档只允许嵌入数据自身的一个 </script>。
+        assert_eq!(html.matches("</script>").count(), 1);
+        assert!(!html.contains("src=\"http"));
+        for section in [
+            "权益曲线",
+            "回撤",
+            "月度收益",
+            "R 倍数分布",
+            "蒙特卡洛置信带",
+            "平仓类型",
+            "被过滤信号反事实",
+            "交易列表",
+        ] {
+            assert!(html.contains(section), "missing section {section}");
+        }
+    }
+}
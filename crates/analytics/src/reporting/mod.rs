@@ -1 +1,9 @@
-
+//! 回测报告
+//!
+//! 把 `PerformanceCalculator`、`MonteCarloAnalyzer` 和回测交易记录汇总成一份报告：
+//! - [`BacktestReport`]: 机器可读的 JSON 结构
+//! - [`render_backtest_report_html`]: 自包含的 HTML 报告
+mod html;
+mod tear_sheet;
+pub use html::render_backtest_report_html;
+pub use tear_sheet::*;
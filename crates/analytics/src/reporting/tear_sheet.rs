@@ -0,0 +1,648 @@
+//! 回测报告数据
+//!
+//! 只从平仓记录（`close_type` 非空）推导权益、月度收益、R 倍数和平仓类型，
+//! 入场记录仅用于回填交易方向，避免同一笔交易的开仓和平仓被重复计入。
+use crate::monte_carlo::{EquityBand, MonteCarloAnalyzer, Stats};
+use crate::performance::{calculate_performance_metrics, PerformanceMetrics};
+use rust_quant_strategies::framework::backtest::types::FilteredSignal;
+use rust_quant_strategies::strategy_common::{BackTestResult, TradeRecord};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+/// R 倍数直方图下边界，小于该值的样本计入第一个桶。
+const R_HISTOGRAM_MIN: f64 = -3.0;
+/// R 倍数直方图上边界，大于等于该值的样本计入最后一个桶。
+const R_HISTOGRAM_MAX: f64 = 5.0;
+/// R 倍数直方图桶宽。
+const R_HISTOGRAM_BUCKET_WIDTH: f64 = 0.5;
+/// 默认蒙特卡洛模拟次数。
+const DEFAULT_MONTE_CARLO_ITERATIONS: usize = 1000;
+/// 与 `BacktestService` 落库口径一致的默认期初资金。
+const DEFAULT_INITIAL_FUND: f64 = 100.0;
+/// 报告生成参数
+#[derive(Debug, Clone)]
+pub struct BacktestReportConfig {
+    /// 报告标题。
+    pub title: String,
+    /// 期初资金。
+    pub initial_fund: f64,
+    /// 期末资金；为空时按期初资金加平仓盈亏推导。
+    pub final_fund: Option<f64>,
+    /// 回测开始时间 (毫秒时间戳)。
+    pub start_time: i64,
+    /// 回测结束时间 (毫秒时间戳)。
+    pub end_time: i64,
+    /// 蒙特卡洛模拟次数；为 0 时不生成置信带。
+    pub monte_carlo_iterations: usize,
+}
+impl Default for BacktestReportConfig {
+    fn default() -> Self {
+        Self {
+            title: "回测报告".to_string(),
+            initial_fund: DEFAULT_INITIAL_FUND,
+            final_fund: None,
+            start_time: 0,
+            end_time: 0,
+            monte_carlo_iterations: DEFAULT_MONTE_CARLO_ITERATIONS,
+        }
+    }
+}
+impl BacktestReportConfig {
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+    pub fn with_initial_fund(mut self, initial_fund: f64) -> Self {
+        self.initial_fund = initial_fund;
+        self
+    }
+    pub fn with_final_fund(mut self, final_fund: f64) -> Self {
+        self.final_fund = Some(final_fund);
+        self
+    }
+    pub fn with_time_range(mut self, start_time: i64, end_time: i64) -> Self {
+        self.start_time = start_time;
+        self.end_time = end_time;
+        self
+    }
+    pub fn with_monte_carlo_iterations(mut self, iterations: usize) -> Self {
+        self.monte_carlo_iterations = iterations;
+        self
+    }
+}
+/// 汇总指标
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportSummary {
+    /// 与 `back_test_log` 同口径的绩效指标。
+    pub performance: PerformanceMetrics,
+    /// 平仓记录数。
+    pub trades: usize,
+    /// 盈利笔数。
+    pub wins: usize,
+    /// 亏损笔数。
+    pub losses: usize,
+    /// 胜率。
+    pub win_rate: f64,
+    /// 平仓盈亏合计。
+    pub total_pnl: f64,
+    /// 资金费合计，正数表示支付。
+    pub total_funding_fee: f64,
+    /// 有初始风险的平仓记录平均 R 倍数。
+    pub average_r: Option<f64>,
+    /// 按平仓权益计算的最大回撤，不含持仓浮亏估算。
+    pub closed_equity_max_drawdown: f64,
+}
+/// 权益曲线上的一个点
+#[derive(Debug, Clone, Serialize)]
+pub struct EquityPoint {
+    /// 交易序号，0 表示期初资金。
+    pub trade_index: usize,
+    /// 平仓时间；期初点为空。
+    pub time: Option<String>,
+    /// 平仓后权益。
+    pub equity: f64,
+    /// 相对此前权益高点的回撤比例。
+    pub drawdown: f64,
+}
+/// 月度收益
+#[derive(Debug, Clone, Serialize)]
+pub struct MonthlyReturn {
+    pub year: i32,
+    pub month: u32,
+    /// 当月平仓盈亏。
+    pub pnl: f64,
+    /// 当月盈亏除以月初权益。
+    pub return_rate: f64,
+    /// 当月平仓笔数。
+    pub trades: usize,
+}
+/// R 倍数直方图桶，区间为 `[lower, upper)`，首尾桶同时承接越界样本
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+}
+/// 按平仓类型汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct CloseTypeStats {
+    pub close_type: String,
+    pub trades: usize,
+    pub wins: usize,
+    pub pnl: f64,
+    pub win_rate: f64,
+    /// 该类型有初始风险记录的平均 R 倍数。
+    pub average_r: Option<f64>,
+}
+/// 报告中的单笔平仓
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportTrade {
+    /// 交易方向；找不到入场记录时为空。
+    pub side: Option<String>,
+    pub open_time: String,
+    pub close_time: Option<String>,
+    pub open_price: f64,
+    pub close_price: Option<f64>,
+    pub quantity: f64,
+    pub profit_loss: f64,
+    pub net_profit_r: Option<f64>,
+    pub close_type: String,
+    pub full_close: bool,
+    pub funding_fee: Option<f64>,
+}
+/// 单个过滤原因的反事实盈亏
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterReasonStats {
+    pub reason: String,
+    pub signals: usize,
+    pub wins: usize,
+    pub losses: usize,
+    /// 若未被过滤的模拟盈亏合计；一条信号有多个原因时在每个原因下都计入。
+    pub pnl: f64,
+}
+/// 被过滤信号的反事实汇总
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FilteredSignalSummary {
+    pub signals: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub break_even: usize,
+    /// 若全部放行的模拟盈亏合计。
+    pub total_pnl: f64,
+    /// 按模拟盈亏升序排列，最先出现的是过滤最有价值的原因。
+    pub by_reason: Vec<FilterReasonStats>,
+}
+/// 蒙特卡洛置信带
+#[derive(Debug, Clone, Serialize)]
+pub struct MonteCarloSection {
+    pub iterations: usize,
+    pub max_drawdown: Stats,
+    pub profit: Stats,
+    pub equity_bands: Vec<EquityBand>,
+}
+/// 回测报告，既是 JSON 输出结构，也是 HTML 渲染的唯一数据来源
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub title: String,
+    /// 生成时间 (毫秒时间戳)。
+    pub generated_at: i64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub initial_fund: f64,
+    pub final_fund: f64,
+    pub summary: ReportSummary,
+    pub equity_curve: Vec<EquityPoint>,
+    pub monthly_returns: Vec<MonthlyReturn>,
+    pub r_multiple_histogram: Vec<HistogramBucket>,
+    pub close_type_breakdown: Vec<CloseTypeStats>,
+    pub filtered_signals: FilteredSignalSummary,
+    /// 平仓记录少于 2 笔或未开启模拟时为空。
+    pub monte_carlo: Option<MonteCarloSection>,
+    pub trades: Vec<ReportTrade>,
+}
+impl BacktestReport {
+    /// 从回测引擎结果生成报告，期末资金取 `BackTestResult::funds`。
+    pub fn from_backtest_result(config: BacktestReportConfig, result: &BackTestResult) -> Self {
+        build_backtest_report(
+            config.with_final_fund(result.funds),
+            &result.trade_records,
+            &result.filtered_signals,
+        )
+    }
+}
+/// 生成回测报告
+/// # 参数
+/// * `config` - 报告参数
+/// * `trade_records` - 回测交易记录，包含入场和平仓记录
+/// * `filtered_signals` - 被过滤的信号
+pub fn build_backtest_report(
+    config: BacktestReportConfig,
+    trade_records: &[TradeRecord],
+    filtered_signals: &[FilteredSignal],
+) -> BacktestReport {
+    let closes: Vec<&TradeRecord> = trade_records
+        .iter()
+        .filter(|record| !record.close_type.is_empty())
+        .collect();
+    let total_pnl: f64 = closes.iter().map(|record| record.profit_loss).sum();
+    let final_fund = config.final_fund.unwrap_or(config.initial_fund + total_pnl);
+    let performance = calculate_performance_metrics(
+        config.initial_fund,
+        final_fund,
+        trade_records,
+        config.start_time,
+        config.end_time,
+    );
+    let equity_curve = equity_curve(config.initial_fund, &closes);
+    let wins = closes.iter().filter(|r| r.profit_loss > 0.0).count();
+    let losses = closes.iter().filter(|r| r.profit_loss < 0.0).count();
+    let summary = ReportSummary {
+        performance,
+        trades: closes.len(),
+        wins,
+        losses,
+        win_rate: ratio(wins, closes.len()),
+        total_pnl,
+        total_funding_fee: closes.iter().filter_map(|r| r.funding_fee).sum(),
+        average_r: average(closes.iter().filter_map(|r| r.net_profit_r)),
+        closed_equity_max_drawdown: equity_curve
+            .iter()
+            .map(|point| point.drawdown)
+            .fold(0.0, f64::max),
+    };
+    let pnls: Vec<f64> = closes.iter().map(|record| record.profit_loss).collect();
+    let monte_carlo = if config.monte_carlo_iterations > 0 && pnls.len() >= 2 {
+        let analyzer = MonteCarloAnalyzer::new(config.initial_fund);
+        let simulation = analyzer.simulate(&pnls, config.monte_carlo_iterations);
+        Some(MonteCarloSection {
+            iterations: config.monte_carlo_iterations,
+            max_drawdown: simulation.max_drawdown_stats,
+            profit: simulation.profit_stats,
+            equity_bands: analyzer.equity_bands(&pnls, config.monte_carlo_iterations),
+        })
+    } else {
+        None
+    };
+    BacktestReport {
+        generated_at: chrono::Utc::now().timestamp_millis(),
+        start_time: config.start_time,
+        end_time: config.end_time,
+        initial_fund: config.initial_fund,
+        final_fund,
+        summary,
+        monthly_returns: monthly_returns(config.initial_fund, &closes),
+        r_multiple_histogram: r_multiple_histogram(&closes),
+        close_type_breakdown: close_type_breakdown(&closes),
+        filtered_signals: filtered_signal_summary(filtered_signals),
+        monte_carlo,
+        trades: report_trades(trade_records, &closes),
+        equity_curve,
+        title: config.title,
+    }
+}
+/// 平仓权益曲线与回撤。
+fn equity_curve(initial_fund: f64, closes: &[&TradeRecord]) -> Vec<EquityPoint> {
+    let mut points = Vec::with_capacity(closes.len() + 1);
+    let mut equity = initial_fund;
+    let mut peak = initial_fund;
+    points.push(EquityPoint {
+        trade_index: 0,
+        time: None,
+        equity,
+        drawdown: 0.0,
+    });
+    for (index, record) in closes.iter().enumerate() {
+        equity += record.profit_loss;
+        peak = peak.max(equity);
+        let drawdown = if peak > 0.0 {
+            (peak - equity) / peak
+        } else {
+            0.0
+        };
+        points.push(EquityPoint {
+            trade_index: index + 1,
+            time: record.close_position_time.clone(),
+            equity,
+            drawdown,
+        });
+    }
+    points
+}
+/// 按平仓月份汇总收益；无法解析平仓时间的记录只影响权益基数，不单独成月。
+fn monthly_returns(initial_fund: f64, closes: &[&TradeRecord]) -> Vec<MonthlyReturn> {
+    let mut months: BTreeMap<(i32, u32), MonthlyReturn> = BTreeMap::new();
+    let mut equity = initial_fund;
+    for record in closes {
+        if let Some((year, month)) = record
+            .close_position_time
+            .as_deref()
+            .and_then(parse_year_month)
+        {
+            let entry = months.entry((year, month)).or_insert(MonthlyReturn {
+                year,
+                month,
+                pnl: 0.0,
+                // 暂存月初权益，循环结束后换算为收益率。
+                return_rate: equity,
+                trades: 0,
+            });
+            entry.pnl += record.profit_loss;
+            entry.trades += 1;
+        }
+        equity += record.profit_loss;
+    }
+    months
+        .into_values()
+        .map(|mut month| {
+            let opening_equity = month.return_rate;
+            month.return_rate = if opening_equity > 0.0 {
+                month.pnl / opening_equity
+            } else {
+                0.0
+            };
+            month
+        })
+        .collect()
+}
+/// 解析 `YYYY-MM...` 格式时间的年月。
+fn parse_year_month(time: &str) -> Option<(i32, u32)> {
+    let year = time.get(0..4)?.parse::<i32>().ok()?;
+    if time.get(4..5)? != "-" {
+        return None;
+    }
+    let month = time.get(5..7)?.parse::<u32>().ok()?;
+    (1..=12).contains(&month).then_some((year, month))
+}
+/// 固定区间的 R 倍数直方图，空桶也保留，便于多次回测直接对比。
+fn r_multiple_histogram(closes: &[&TradeRecord]) -> Vec<HistogramBucket> {
+    let bucket_count = ((R_HISTOGRAM_MAX - R_HISTOGRAM_MIN) / R_HISTOGRAM_BUCKET_WIDTH) as usize;
+    let mut buckets: Vec<HistogramBucket> = (0..bucket_count)
+        .map(|index| {
+            let lower = R_HISTOGRAM_MIN + index as f64 * R_HISTOGRAM_BUCKET_WIDTH;
+            HistogramBucket {
+                lower,
+                upper: lower + R_HISTOGRAM_BUCKET_WIDTH,
+                count: 0,
+            }
+        })
+        .collect();
+    for r in closes
+        .iter()
+        .filter_map(|record| record.net_profit_r)
+        .filter(|r| r.is_finite())
+    {
+        let index = ((r - R_HISTOGRAM_MIN) / R_HISTOGRAM_BUCKET_WIDTH)
+            .floor()
+            .clamp(0.0, (bucket_count - 1) as f64) as usize;
+        buckets[index].count += 1;
+    }
+    buckets
+}
+/// 按平仓类型汇总，按笔数降序。
+fn close_type_breakdown(closes: &[&TradeRecord]) -> Vec<CloseTypeStats> {
+    let mut groups: BTreeMap<&str, Vec<&TradeRecord>> = BTreeMap::new();
+    for record in closes {
+        groups
+            .entry(record.close_type.as_str())
+            .or_default()
+            .push(record);
+    }
+    let mut stats: Vec<CloseTypeStats> = groups
+        .into_iter()
+        .map(|(close_type, records)| {
+            let wins = records.iter().filter(|r| r.profit_loss > 0.0).count();
+            CloseTypeStats {
+                close_type: close_type.to_string(),
+                trades: records.len(),
+                wins,
+                pnl: records.iter().map(|r| r.profit_loss).sum(),
+                win_rate: ratio(wins, records.len()),
+                average_r: average(records.iter().filter_map(|r| r.net_profit_r)),
+            }
+        })
+        .collect();
+    stats.sort_by_key(|stats| std::cmp::Reverse(stats.trades));
+    stats
+}
+/// 汇总被过滤信号若放行后的模拟盈亏。
+fn filtered_signal_summary(signals: &[FilteredSignal]) -> FilteredSignalSummary {
+    let mut summary = FilteredSignalSummary {
+        signals: signals.len(),
+        ..FilteredSignalSummary::default()
+    };
+    let mut by_reason: BTreeMap<&str, FilterReasonStats> = BTreeMap::new();
+    for signal in signals {
+        let outcome = signal.trade_result.to_ascii_uppercase();
+        let (is_win, is_loss) = (outcome == "WIN", outcome == "LOSS");
+        if is_win {
+            summary.wins += 1;
+        } else if is_loss {
+            summary.losses += 1;
+        } else {
+            summary.break_even += 1;
+        }
+        summary.total_pnl += signal.final_pnl;
+        for reason in &signal.filter_reasons {
+            let entry = by_reason
+                .entry(reason.as_str())
+                .or_insert_with(|| FilterReasonStats {
+                    reason: reason.clone(),
+                    signals: 0,
+                    wins: 0,
+                    losses: 0,
+                    pnl: 0.0,
+                });
+            entry.signals += 1;
+            entry.wins += usize::from(is_win);
+            entry.losses += usize::from(is_loss);
+            entry.pnl += signal.final_pnl;
+        }
+    }
+    summary.by_reason = by_reason.into_values().collect();
+    summary.by_reason.sort_by(|a, b| {
+        a.pnl
+            .partial_cmp(&b.pnl)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    summary
+}
+/// 平仓记录转为交易列表，方向取同一开仓时间的入场记录。
+fn report_trades(trade_records: &[TradeRecord], closes: &[&TradeRecord]) -> Vec<ReportTrade> {
+    let sides: HashMap<&str, &str> = trade_records
+        .iter()
+        .filter(|record| record.close_type.is_empty())
+        .map(|record| {
+            (
+                record.open_position_time.as_str(),
+                record.option_type.as_str(),
+            )
+        })
+        .collect();
+    closes
+        .iter()
+        .map(|record| ReportTrade {
+            side: sides
+                .get(record.open_position_time.as_str())
+                .map(|side| side.to_string()),
+            open_time: record.open_position_time.clone(),
+            close_time: record.close_position_time.clone(),
+            open_price: record.open_price,
+            close_price: record.close_price,
+            quantity: record.quantity,
+            profit_loss: record.profit_loss,
+            net_profit_r: record.net_profit_r,
+            close_type: record.close_type.clone(),
+            full_close: record.full_close,
+            funding_fee: record.funding_fee,
+        })
+        .collect()
+}
+fn ratio(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), value| {
+        (sum + value, count + 1)
+    });
+    (count > 0).then(|| sum / count as f64)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn entry(open_time: &str, side: &str) -> TradeRecord {
+        TradeRecord {
+            option_type: side.to_string(),
+            open_position_time: open_time.to_string(),
+            signal_open_position_time: None,
+            close_position_time: Some(open_time.to_string()),
+            open_price: 100.0,
+            signal_status: 1,
+            close_price: None,
+            profit_loss: 0.0,
+            quantity: 1.0,
+            full_close: false,
+            close_type: String::new(),
+            win_num: 0,
+            loss_num: 0,
+            signal_value: None,
+            signal_result: None,
+            stop_loss_source: None,
+            stop_loss_update_history: None,
+            initial_stop_price: Some(95.0),
+            initial_risk_amount: Some(5.0),
+            net_profit_r: None,
+            funding_fee: None,
+        }
+    }
+    fn exit(open_time: &str, close_time: &str, pnl: f64, close_type: &str) -> TradeRecord {
+        TradeRecord {
+            option_type: "close".to_string(),
+            close_position_time: Some(close_time.to_string()),
+            close_price: Some(100.0 + pnl),
+            profit_loss: pnl,
+            full_close: true,
+            close_type: close_type.to_string(),
+            net_profit_r: Some(pnl / 5.0),
+            funding_fee: Some(0.1),
+            ..entry(open_time, "close")
+        }
+    }
+    fn filtered(reasons: &[&str], pnl: f64, result: &str) -> FilteredSignal {
+        FilteredSignal {
+            ts: 0,
+            inst_id: "BTC-USDT-SWAP".to_string(),
+            direction: "long".to_string(),
+            signal_price: 100.0,
+            filter_reasons: reasons.iter().map(|r| r.to_string()).collect(),
+            indicator_snapshot: "{}".to_string(),
+            theoretical_profit: pnl.max(0.0),
+            theoretical_loss: pnl.min(0.0),
+            final_pnl: pnl,
+            trade_result: result.to_string(),
+            signal_value: None,
+        }
+    }
+    fn sample_records() -> Vec<TradeRecord> {
+        vec![
+            entry("2024-01-03 08:00:00", "long"),
+            exit("2024-01-03 08:00:00", "2024-01-05 08:00:00", 10.0, "止盈"),
+            entry("2024-01-20 08:00:00", "short"),
+            exit("2024-01-20 08:00:00", "2024-02-02 08:00:00", -22.0, "止损"),
+            entry("2024-02-10 08:00:00", "long"),
+            exit("2024-02-10 08:00:00", "2024-02-12 08:00:00", 30.0, "止盈"),
+        ]
+    }
+    #[test]
+    fn report_uses_close_records_for_equity_and_drawdown() {
+        let config = BacktestReportConfig::default().with_monte_carlo_iterations(0);
+        let report = build_backtest_report(config, &sample_records(), &[]);
+        assert_eq!(report.summary.trades, 3);
+        assert_eq!(report.summary.wins, 2);
+        assert_eq!(report.summary.losses, 1);
+        assert!((report.final_fund - 118.0).abs() < 1e-9);
+        let equities: Vec<f64> = report.equity_curve.iter().map(|p| p.equity).collect();
+        assert_eq!(equities, vec![100.0, 110.0, 88.0, 118.0]);
+        assert!((report.summary.closed_equity_max_drawdown - 0.2).abs() < 1e-9);
+        assert!((report.summary.total_funding_fee - 0.3).abs() < 1e-9);
+        assert!(report.monte_carlo.is_none());
+        let sides: Vec<_> = report.trades.iter().map(|t| t.side.as_deref()).collect();
+        assert_eq!(sides, vec![Some("long"), Some("short"), Some("long")]);
+    }
+    #[test]
+    fn monthly_returns_use_month_opening_equity() {
+        let config = BacktestReportConfig::default().with_monte_carlo_iterations(0);
+        let report = build_backtest_report(config, &sample_records(), &[]);
+        assert_eq!(report.monthly_returns.len(), 2);
+        let january = &report.monthly_returns[0];
+        assert_eq!((january.year, january.month, january.trades), (2024, 1, 1));
+        assert!((january.return_rate - 0.1).abs() < 1e-9);
+        let february = &report.monthly_returns[1];
+        assert_eq!(
+            (february.year, february.month, february.trades),
+            (2024, 2, 2)
+        );
+        assert!((february.pnl - 8.0).abs() < 1e-9);
+        assert!((february.return_rate - 8.0 / 110.0).abs() < 1e-9);
+    }
+    #[test]
+    fn r_histogram_clamps_outliers_into_edge_buckets() {
+        let config = BacktestReportConfig::default().with_monte_carlo_iterations(0);
+        let report = build_backtest_report(config, &sample_records(), &[]);
+        let histogram = &report.r_multiple_histogram;
+        assert_eq!(histogram.len(), 16);
+        // R = 2.0, -4.4 (低于下界), 6.0 (高于上界)
+        assert_eq!(histogram[0].count, 1);
+        assert_eq!(histogram[10].count, 1);
+        assert_eq!(histogram[15].count, 1);
+        assert_eq!(histogram.iter().map(|b| b.count).sum::<usize>(), 3);
+    }
+    #[test]
+    fn close_type_and_filtered_signal_breakdowns() {
+        let filtered_signals = vec![
+            filtered(&["rsi", "volume"], -5.0, "LOSS"),
+            filtered(&["rsi"], 3.0, "WIN"),
+            filtered(&["trend"], 0.0, "BREAK_EVEN"),
+        ];
+        let config = BacktestReportConfig::default().with_monte_carlo_iterations(0);
+        let report = build_backtest_report(config, &sample_records(), &filtered_signals);
+        let take_profit = &report.close_type_breakdown[0];
+        assert_eq!(take_profit.close_type, "止盈");
+        assert_eq!((take_profit.trades, take_profit.wins), (2, 2));
+        assert!((take_profit.pnl - 40.0).abs() < 1e-9);
+        let summary = &report.filtered_signals;
+        assert_eq!(
+            (
+                summary.signals,
+                summary.wins,
+                summary.losses,
+                summary.break_even
+            ),
+            (3, 1, 1, 1)
+        );
+        assert!((summary.total_pnl + 2.0).abs() < 1e-9);
+        let reasons: Vec<_> = summary
+            .by_reason
+            .iter()
+            .map(|r| r.reason.as_str())
+            .collect();
+        assert_eq!(reasons, vec!["volume", "rsi", "trend"]);
+    }
+    #[test]
+    fn monte_carlo_bands_share_start_and_final_equity() {
+        let config = BacktestReportConfig::default().with_monte_carlo_iterations(200);
+        let report = build_backtest_report(config, &sample_records(), &[]);
+        let monte_carlo = report.monte_carlo.expect("monte carlo section");
+        assert_eq!(monte_carlo.equity_bands.len(), 4);
+        let first = &monte_carlo.equity_bands[0];
+        let last = &monte_carlo.equity_bands[3];
+        assert_eq!((first.p05, first.p95), (100.0, 100.0));
+        assert!((last.p05 - 118.0).abs() < 1e-9 && (last.p95 - 118.0).abs() < 1e-9);
+        assert!(monte_carlo
+            .equity_bands
+            .iter()
+            .all(|band| band.p05 <= band.p50 && band.p50 <= band.p95));
+    }
+}
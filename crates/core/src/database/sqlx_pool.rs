@@ -1,6 +1,7 @@
 //! SQLx 数据库连接池管理
 //!
 //! 使用 sqlx 替代 rbatis，提供类型安全的数据库访问
+use crate::metrics;
 use once_cell::sync::OnceCell;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
@@ -60,6 +61,7 @@ pub async fn init_db_pool() -> anyhow::Result<()> {
     DB_POOL
         .set(pool)
         .map_err(|_| anyhow::anyhow!("数据库连接池已初始化"))?;
+    metrics::global().register_collector("sqlx_pool", |_| record_db_pool_metrics());
     info!("✓ 数据库连接池初始化成功");
     Ok(())
 }
@@ -69,6 +71,17 @@ pub fn get_db_pool() -> &'static PgPool {
         .get()
         .expect("数据库连接池未初始化，请先调用 init_db_pool()")
 }
+/// 把全局连接池的占用情况写入指标；连接池未初始化时不写入。
+pub fn record_db_pool_metrics() {
+    if let Some(pool) = DB_POOL.get() {
+        metrics::set_db_pool_stats(
+            "quant_core",
+            pool.size(),
+            pool.num_idle(),
+            pool.options().get_max_connections(),
+        );
+    }
+}
 /// 关闭数据库连接池
 pub async fn close_db_pool() -> anyhow::Result<()> {
     if let Some(pool) = DB_POOL.get() {
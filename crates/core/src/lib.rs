@@ -1,8 +1,9 @@
 //! # Rust Quant Core
 //!
-//! 核心基础设施：配置、数据库、缓存、日志
+//! 核心基础设施：配置、数据库、缓存、日志、指标
 pub mod cache;
 pub mod config;
 pub mod database;
 pub mod error;
 pub mod logger;
+pub mod metrics;
@@ -0,0 +1,24 @@
+//! 运行指标
+//!
+//! 进程级 [`MetricsRegistry`] 汇总行情、策略、执行和数据库连接池指标，
+//! 由 internal server 的 `/metrics` 路由或 `QUANT_METRICS_ADDR` 独立监听以 Prometheus 文本格式输出。
+pub mod quant_core;
+pub mod registry;
+pub mod server;
+use once_cell::sync::Lazy;
+// 重新导出
+pub use quant_core::*;
+pub use registry::{
+    MetricKind, MetricsCollector, MetricsRegistry, DEFAULT_LATENCY_BUCKETS,
+    PROMETHEUS_TEXT_CONTENT_TYPE,
+};
+pub use server::{metrics_addr_from_env, serve_metrics, spawn_metrics_server_from_env};
+static GLOBAL_REGISTRY: Lazy<MetricsRegistry> = Lazy::new(MetricsRegistry::new);
+/// 进程级指标注册表。
+pub fn global() -> &'static MetricsRegistry {
+    &GLOBAL_REGISTRY
+}
+/// 渲染进程级注册表的 Prometheus 文本。
+pub fn render() -> String {
+    global().render()
+}
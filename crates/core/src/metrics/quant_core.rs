@@ -0,0 +1,126 @@
+//! quant_core 各 worker 共用的业务指标
+//!
+//! 指标名和标签集中在这里定义，调用方只传业务维度，避免同一指标在不同 crate 中拼写不一致。
+use super::global;
+use std::time::Duration;
+pub const WEBSOCKET_MESSAGE_LAG_SECONDS: &str = "quant_core_websocket_message_lag_seconds";
+pub const WEBSOCKET_MISSED_TRIGGERS: &str = "quant_core_websocket_missed_triggers";
+pub const STRATEGY_EXECUTION_DURATION_SECONDS: &str =
+    "quant_core_strategy_execution_duration_seconds";
+pub const STRATEGY_SIGNALS_TOTAL: &str = "quant_core_strategy_signals_total";
+pub const EXECUTION_TASK_LEASES_TOTAL: &str = "quant_core_execution_task_leases_total";
+pub const EXECUTION_ORDER_PLACEMENTS_TOTAL: &str = "quant_core_execution_order_placements_total";
+pub const EXECUTION_TASK_FAILURES_TOTAL: &str = "quant_core_execution_task_failures_total";
+pub const RECONCILIATION_MISMATCHES_TOTAL: &str = "quant_core_reconciliation_mismatches_total";
+pub const DB_POOL_CONNECTIONS: &str = "quant_core_db_pool_connections";
+pub const DB_POOL_MAX_CONNECTIONS: &str = "quant_core_db_pool_max_connections";
+pub const DB_POOL_UTILISATION_RATIO: &str = "quant_core_db_pool_utilisation_ratio";
+/// 写入单个 K 线订阅目标距最后一条业务消息的秒数。
+pub fn set_websocket_message_lag(symbol: &str, timeframe: &str, lag_seconds: f64) {
+    global().set_gauge(
+        WEBSOCKET_MESSAGE_LAG_SECONDS,
+        "Seconds since the last business WebSocket candle message per symbol and timeframe",
+        &[("symbol", symbol), ("timeframe", timeframe)],
+        lag_seconds,
+    );
+}
+/// 写入单个订阅目标在本进程内累计的漏触发次数。
+pub fn set_websocket_missed_triggers(symbol: &str, timeframe: &str, missed: u64) {
+    global().set_gauge(
+        WEBSOCKET_MISSED_TRIGGERS,
+        "Confirmed candles that expired without a strategy trigger in this process",
+        &[("symbol", symbol), ("timeframe", timeframe)],
+        missed as f64,
+    );
+}
+/// 记录一次策略分析耗时。
+pub fn record_strategy_execution_latency(strategy: &str, elapsed: Duration) {
+    global().observe_histogram(
+        STRATEGY_EXECUTION_DURATION_SECONDS,
+        "Strategy analysis latency per confirmed candle",
+        &[("strategy", strategy)],
+        elapsed.as_secs_f64(),
+    );
+}
+/// 记录一次策略信号；`side` 取 buy / sell。
+pub fn record_strategy_signal(strategy: &str, side: &str) {
+    global().inc_counter(
+        STRATEGY_SIGNALS_TOTAL,
+        "Strategy signals emitted by live strategies",
+        &[("strategy", strategy), ("side", side)],
+        1.0,
+    );
+}
+/// 记录 worker 一次租约拿到的任务数；`lane` 区分执行与确认通道。
+pub fn record_execution_task_leases(lane: &str, count: usize) {
+    global().inc_counter(
+        EXECUTION_TASK_LEASES_TOTAL,
+        "Execution tasks leased by execution workers",
+        &[("lane", lane)],
+        count as f64,
+    );
+}
+/// 记录一次已被交易所接受的下单。
+pub fn record_execution_order_placement(exchange: &str) {
+    global().inc_counter(
+        EXECUTION_ORDER_PLACEMENTS_TOTAL,
+        "Exchange orders placed by execution workers",
+        &[("exchange", exchange)],
+        1.0,
+    );
+}
+/// 记录一次执行任务失败，按最终执行状态区分失败阶段。
+pub fn record_execution_task_failure(exchange: &str, execution_status: &str) {
+    global().inc_counter(
+        EXECUTION_TASK_FAILURES_TOTAL,
+        "Execution tasks reported with a failed status",
+        &[
+            ("exchange", exchange),
+            ("execution_status", execution_status),
+        ],
+        1.0,
+    );
+}
+/// 记录一次交易所对账不一致。
+pub fn record_reconciliation_mismatch(issue_type: &str) {
+    global().inc_counter(
+        RECONCILIATION_MISMATCHES_TOTAL,
+        "Exchange reconciliation mismatches reported by execution workers",
+        &[("issue_type", issue_type)],
+        1.0,
+    );
+}
+/// 写入连接池占用；`size` 为当前已建立连接数，`idle` 为其中空闲连接数。
+pub fn set_db_pool_stats(pool: &str, size: u32, idle: usize, max_connections: u32) {
+    let registry = global();
+    let active = (size as usize).saturating_sub(idle);
+    registry.set_gauge(
+        DB_POOL_CONNECTIONS,
+        "Database pool connections by state",
+        &[("pool", pool), ("state", "active")],
+        active as f64,
+    );
+    registry.set_gauge(
+        DB_POOL_CONNECTIONS,
+        "Database pool connections by state",
+        &[("pool", pool), ("state", "idle")],
+        idle as f64,
+    );
+    registry.set_gauge(
+        DB_POOL_MAX_CONNECTIONS,
+        "Configured maximum database pool connections",
+        &[("pool", pool)],
+        f64::from(max_connections),
+    );
+    let utilisation = if max_connections == 0 {
+        0.0
+    } else {
+        active as f64 / f64::from(max_connections)
+    };
+    registry.set_gauge(
+        DB_POOL_UTILISATION_RATIO,
+        "Active database connections divided by the configured maximum",
+        &[("pool", pool)],
+        utilisation,
+    );
+}
@@ -0,0 +1,353 @@
+//! 进程内指标注册表
+//!
+//! 只实现 Prometheus 文本格式所需的 counter / gauge / histogram，避免为少量运行指标引入额外依赖。
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+/// Prometheus 文本暴露格式的 Content-Type。
+pub const PROMETHEUS_TEXT_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+/// 延迟类直方图的默认分桶（秒），覆盖毫秒级指标计算到分钟级外部调用。
+pub const DEFAULT_LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+/// 渲染前执行的采集回调，用于把连接池、运行态快照等“拉取型”数据写成 gauge。
+pub type MetricsCollector = Arc<dyn Fn(&MetricsRegistry) + Send + Sync>;
+type LabelSet = Vec<(String, String)>;
+/// 指标类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// 单调递增计数。
+    Counter,
+    /// 可升可降的瞬时值。
+    Gauge,
+    /// 分桶累计分布。
+    Histogram,
+}
+impl MetricKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+#[derive(Debug, Clone)]
+struct HistogramState {
+    /// 分桶上界（不含 +Inf）。
+    buckets: Vec<f64>,
+    /// 每个分桶自身的观测次数，渲染时再累加为 Prometheus 的累计值。
+    bucket_counts: Vec<u64>,
+    /// 观测值之和。
+    sum: f64,
+    /// 观测次数。
+    count: u64,
+}
+#[derive(Debug, Clone)]
+enum SeriesValue {
+    Scalar(f64),
+    Histogram(HistogramState),
+}
+#[derive(Debug, Clone)]
+struct MetricFamily {
+    help: String,
+    kind: MetricKind,
+    series: BTreeMap<LabelSet, SeriesValue>,
+}
+/// 线程安全的指标注册表；同名指标首次写入时登记类型和说明，之后类型不一致的写入会被忽略。
+#[derive(Default)]
+pub struct MetricsRegistry {
+    families: Mutex<BTreeMap<String, MetricFamily>>,
+    collectors: Mutex<BTreeMap<String, MetricsCollector>>,
+}
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 计数器增加 `value`；负数和非有限值不会写入，保证 counter 单调。
+    pub fn inc_counter(&self, name: &str, help: &str, labels: &[(&str, &str)], value: f64) {
+        if !value.is_finite() || value < 0.0 {
+            return;
+        }
+        self.with_series(name, help, MetricKind::Counter, labels, |series| {
+            if let SeriesValue::Scalar(current) = series {
+                *current += value;
+            }
+        });
+    }
+    /// 设置 gauge 当前值。
+    pub fn set_gauge(&self, name: &str, help: &str, labels: &[(&str, &str)], value: f64) {
+        self.with_series(name, help, MetricKind::Gauge, labels, |series| {
+            if let SeriesValue::Scalar(current) = series {
+                *current = value;
+            }
+        });
+    }
+    /// 记录一次直方图观测，使用 [`DEFAULT_LATENCY_BUCKETS`] 分桶。
+    pub fn observe_histogram(&self, name: &str, help: &str, labels: &[(&str, &str)], value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.with_series(name, help, MetricKind::Histogram, labels, |series| {
+            if let SeriesValue::Histogram(state) = series {
+                if let Some(index) = state.buckets.iter().position(|bound| value <= *bound) {
+                    state.bucket_counts[index] += 1;
+                }
+                state.sum += value;
+                state.count += 1;
+            }
+        });
+    }
+    /// 清空某个指标的全部序列；采集回调在重写快照型 gauge 前调用，避免已下线的目标残留。
+    pub fn clear(&self, name: &str) {
+        if let Some(family) = self
+            .families
+            .lock()
+            .expect("metrics registry poisoned")
+            .get_mut(name)
+        {
+            family.series.clear();
+        }
+    }
+    /// 注册采集回调；同一 key 重复注册时替换旧回调。
+    pub fn register_collector<F>(&self, key: &str, collector: F)
+    where
+        F: Fn(&MetricsRegistry) + Send + Sync + 'static,
+    {
+        self.collectors
+            .lock()
+            .expect("metrics collectors poisoned")
+            .insert(key.to_string(), Arc::new(collector));
+    }
+    /// 移除采集回调。
+    pub fn unregister_collector(&self, key: &str) {
+        self.collectors
+            .lock()
+            .expect("metrics collectors poisoned")
+            .remove(key);
+    }
+    /// 读取单个 counter/gauge 序列的当前值，主要供测试和健康检查使用。
+    pub fn scalar_value(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        let families = self.families.lock().expect("metrics registry poisoned");
+        match families.get(name)?.series.get(&label_set(labels))? {
+            SeriesValue::Scalar(value) => Some(*value),
+            SeriesValue::Histogram(_) => None,
+        }
+    }
+    /// 先执行采集回调，再按 Prometheus 文本格式输出全部指标。
+    pub fn render(&self) -> String {
+        // 回调内部会再次写注册表，必须在释放 collectors 锁之后执行。
+        let collectors: Vec<MetricsCollector> = self
+            .collectors
+            .lock()
+            .expect("metrics collectors poisoned")
+            .values()
+            .cloned()
+            .collect();
+        for collector in collectors {
+            collector(self);
+        }
+        let families = self.families.lock().expect("metrics registry poisoned");
+        let mut output = String::new();
+        for (name, family) in families.iter() {
+            let _ = writeln!(output, "# HELP {} {}", name, escape_help(&family.help));
+            let _ = writeln!(output, "# TYPE {} {}", name, family.kind.as_str());
+            for (labels, value) in &family.series {
+                match value {
+                    SeriesValue::Scalar(value) => {
+                        let _ = writeln!(
+                            output,
+                            "{}{} {}",
+                            name,
+                            format_labels(labels, None),
+                            format_value(*value)
+                        );
+                    }
+                    SeriesValue::Histogram(state) => {
+                        let mut cumulative = 0_u64;
+                        for (bound, count) in state.buckets.iter().zip(&state.bucket_counts) {
+                            cumulative += count;
+                            let _ = writeln!(
+                                output,
+                                "{}_bucket{} {}",
+                                name,
+                                format_labels(labels, Some(&format_value(*bound))),
+                                cumulative
+                            );
+                        }
+                        let _ = writeln!(
+                            output,
+                            "{}_bucket{} {}",
+                            name,
+                            format_labels(labels, Some("+Inf")),
+                            state.count
+                        );
+                        let _ = writeln!(
+                            output,
+                            "{}_sum{} {}",
+                            name,
+                            format_labels(labels, None),
+                            format_value(state.sum)
+                        );
+                        let _ = writeln!(
+                            output,
+                            "{}_count{} {}",
+                            name,
+                            format_labels(labels, None),
+                            state.count
+                        );
+                    }
+                }
+            }
+        }
+        output
+    }
+    fn with_series(
+        &self,
+        name: &str,
+        help: &str,
+        kind: MetricKind,
+        labels: &[(&str, &str)],
+        update: impl FnOnce(&mut SeriesValue),
+    ) {
+        let mut families = self.families.lock().expect("metrics registry poisoned");
+        let family = families
+            .entry(name.to_string())
+            .or_insert_with(|| MetricFamily {
+                help: help.to_string(),
+                kind,
+                series: BTreeMap::new(),
+            });
+        if family.kind != kind {
+            return;
+        }
+        let series = family
+            .series
+            .entry(label_set(labels))
+            .or_insert_with(|| match kind {
+                MetricKind::Counter | MetricKind::Gauge => SeriesValue::Scalar(0.0),
+                MetricKind::Histogram => SeriesValue::Histogram(HistogramState {
+                    buckets: DEFAULT_LATENCY_BUCKETS.to_vec(),
+                    bucket_counts: vec![0; DEFAULT_LATENCY_BUCKETS.len()],
+                    sum: 0.0,
+                    count: 0,
+                }),
+            });
+        update(series);
+    }
+}
+/// 标签按名称排序，保证同一组标签无论传入顺序如何都落到同一序列。
+fn label_set(labels: &[(&str, &str)]) -> LabelSet {
+    let mut set: LabelSet = labels
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    set.sort();
+    set
+}
+fn format_labels(labels: &LabelSet, le: Option<&str>) -> String {
+    if labels.is_empty() && le.is_none() {
+        return String::new();
+    }
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{}\"", le));
+    }
+    format!("{{{}}}", parts.join(","))
+}
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn renders_counters_and_gauges_in_prometheus_text_format() {
+        let registry = MetricsRegistry::new();
+        registry.inc_counter(
+            "quant_core_strategy_signals_total",
+            "signals",
+            &[("strategy", "vegas"), ("side", "buy")],
+            1.0,
+        );
+        registry.inc_counter(
+            "quant_core_strategy_signals_total",
+            "signals",
+            &[("side", "buy"), ("strategy", "vegas")],
+            2.0,
+        );
+        registry.inc_counter("quant_core_strategy_signals_total", "signals", &[], -5.0);
+        registry.set_gauge(
+            "quant_core_db_pool_connections",
+            "pool",
+            &[("state", "idle")],
+            3.0,
+        );
+        let rendered = registry.render();
+        assert!(rendered.contains("# TYPE quant_core_strategy_signals_total counter"));
+        assert!(rendered
+            .contains("quant_core_strategy_signals_total{side=\"buy\",strategy=\"vegas\"} 3"));
+        assert!(!rendered.contains("quant_core_strategy_signals_total -5"));
+        assert!(rendered.contains("quant_core_db_pool_connections{state=\"idle\"} 3"));
+    }
+    #[test]
+    fn renders_cumulative_histogram_buckets() {
+        let registry = MetricsRegistry::new();
+        for value in [0.004, 0.2, 120.0] {
+            registry.observe_histogram(
+                "latency_seconds",
+                "latency",
+                &[("strategy", "vegas")],
+                value,
+            );
+        }
+        let rendered = registry.render();
+        assert!(rendered.contains("latency_seconds_bucket{strategy=\"vegas\",le=\"0.005\"} 1"));
+        assert!(rendered.contains("latency_seconds_bucket{strategy=\"vegas\",le=\"0.25\"} 2"));
+        assert!(rendered.contains("latency_seconds_bucket{strategy=\"vegas\",le=\"60\"} 2"));
+        assert!(rendered.contains("latency_seconds_bucket{strategy=\"vegas\",le=\"+Inf\"} 3"));
+        assert!(rendered.contains("latency_seconds_count{strategy=\"vegas\"} 3"));
+    }
+    #[test]
+    fn collectors_refresh_snapshot_gauges_before_render() {
+        let registry = MetricsRegistry::new();
+        registry.set_gauge("lag_seconds", "lag", &[("symbol", "OLD")], 1.0);
+        registry.register_collector("lag", |registry| {
+            registry.clear("lag_seconds");
+            registry.set_gauge("lag_seconds", "lag", &[("symbol", "ETH\"1")], 2.5);
+        });
+        let rendered = registry.render();
+        assert!(!rendered.contains("OLD"));
+        assert!(rendered.contains("lag_seconds{symbol=\"ETH\\\"1\"} 2.5"));
+        registry.set_gauge("lag_seconds", "lag", &[("symbol", "OLD")], 1.0);
+        registry.unregister_collector("lag");
+        assert!(registry.render().contains("OLD"));
+    }
+    #[test]
+    fn ignores_writes_with_mismatched_kind() {
+        let registry = MetricsRegistry::new();
+        registry.set_gauge("mixed", "mixed", &[], 4.0);
+        registry.inc_counter("mixed", "mixed", &[], 1.0);
+        assert_eq!(registry.scalar_value("mixed", &[]), Some(4.0));
+    }
+}
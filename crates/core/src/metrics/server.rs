@@ -0,0 +1,84 @@
+//! 独立 `/metrics` 监听
+//!
+//! internal server 进程直接在自身路由上暴露指标；行情、策略、执行等 worker 进程没有 HTTP 服务，
+//! 设置 `QUANT_METRICS_ADDR` 后由这里起一个只读监听供 Prometheus 抓取。
+use super::{global, PROMETHEUS_TEXT_CONTENT_TYPE};
+use anyhow::{Context, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{info, warn};
+const METRICS_ADDR_ENV: &str = "QUANT_METRICS_ADDR";
+const MAX_REQUEST_HEADER_BYTES: usize = 8 * 1024;
+/// 读取 `QUANT_METRICS_ADDR`；未设置或为空时不启动独立监听。
+pub fn metrics_addr_from_env() -> Option<String> {
+    std::env::var(METRICS_ADDR_ENV)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+/// 在 `addr` 上持续提供 `GET /metrics`，其余路径返回 404。
+pub async fn serve_metrics(addr: &str) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("绑定 metrics 监听失败: {addr}"))?;
+    info!(addr = %addr, "quant_core metrics endpoint started");
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        tokio::spawn(async move {
+            if let Err(error) = handle_metrics_connection(stream).await {
+                warn!(peer = %peer, error = %error, "处理 metrics 请求失败");
+            }
+        });
+    }
+}
+/// 按环境变量在后台启动独立监听；监听失败只记录日志，不影响 worker 主流程。
+pub fn spawn_metrics_server_from_env() {
+    let Some(addr) = metrics_addr_from_env() else {
+        return;
+    };
+    tokio::spawn(async move {
+        if let Err(error) = serve_metrics(&addr).await {
+            warn!(addr = %addr, error = %error, "quant_core metrics endpoint stopped");
+        }
+    });
+}
+async fn handle_metrics_connection(mut stream: TcpStream) -> Result<()> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0_u8; 1024];
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if buffer.len() > MAX_REQUEST_HEADER_BYTES {
+            anyhow::bail!("metrics request header too large");
+        }
+    }
+    let request_line = String::from_utf8_lossy(&buffer)
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+    let route = path.split_once('?').map(|(route, _)| route).unwrap_or(path);
+    let (status, content_type, body) = if method == "GET" && route == "/metrics" {
+        ("200 OK", PROMETHEUS_TEXT_CONTENT_TYPE, global().render())
+    } else {
+        (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "not found\n".to_string(),
+        )
+    };
+    let header = format!(
+        "HTTP/1.1 {status}\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
@@ -54,6 +54,9 @@ pub struct BacktestLog {
     pub max_drawdown: Option<f64>,
     /// 波动率(年化)
     pub volatility: Option<f64>,
+    /// 累计资金费（正数为净支付）
+    #[serde(default)]
+    pub total_funding_fee: f64,
 }
 impl BacktestLog {
     #[allow(clippy::too_many_arguments)]
@@ -97,6 +100,7 @@ impl BacktestLog {
             total_return: None,
             max_drawdown: None,
             volatility: None,
+            total_funding_fee: 0.0,
         }
     }
 }
@@ -249,4 +253,30 @@ pub struct BacktestPerformanceMetrics {
     /// 波动率 (Annualized Volatility)
     /// 计算公式: 日收益率标准差 * sqrt(365)
     pub volatility: f64,
+    /// 索提诺比率 (Sortino Ratio)
+    /// 计算公式: (年化收益率 - 无风险利率) / 年化下行波动率
+    pub sortino_ratio: f64,
+    /// 卡玛比率 (Calmar Ratio)
+    /// 计算公式: 年化收益率 / 最大回撤
+    pub calmar_ratio: f64,
+    /// 盈亏因子 (Profit Factor)
+    /// 计算公式: 总盈利 / 总亏损绝对值
+    pub profit_factor: f64,
+    /// R 期望 (Expectancy in R)
+    /// 计算公式: 平仓记录 net_profit_r 均值；没有 R 口径时为空
+    pub expectancy_r: Option<f64>,
+    /// 平均持仓时长 (小时)
+    pub average_hold_hours: f64,
+    /// 持仓暴露 (Exposure)
+    /// 计算公式: 有持仓时间 / 回测区间时长
+    pub exposure: f64,
+    /// 最长回撤持续时间 (天)
+    pub longest_drawdown_days: f64,
+    /// 最大连胜笔数
+    pub max_win_streak: i32,
+    /// 最大连亏笔数
+    pub max_loss_streak: i32,
+    /// 尾部比率 (Tail Ratio)
+    /// 计算公式: |单笔收益率 95 分位| / |单笔收益率 5 分位|
+    pub tail_ratio: f64,
 }
@@ -0,0 +1,363 @@
+//! 交易所行情与账户的标准化数据模型
+//!
+//! 各交易所适配器统一输出 OKX 形态的 JSON（字符串数值、`instId`/`totalEq` 等字段），
+//! 这里集中把该形态解析为强类型领域对象，调用方不再各自重复解析交易所 JSON。
+use crate::entities::Candle;
+use crate::enums::Timeframe;
+use crate::value_objects::{Price, Volume};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 标准化 Ticker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ticker {
+    /// 交易对 (如 "BTC-USDT-SWAP")
+    pub symbol: String,
+    /// 最新成交价
+    pub last: f64,
+    /// 最新成交数量
+    pub last_size: Option<f64>,
+    /// 买一价
+    pub bid_price: Option<f64>,
+    /// 买一数量
+    pub bid_size: Option<f64>,
+    /// 卖一价
+    pub ask_price: Option<f64>,
+    /// 卖一数量
+    pub ask_size: Option<f64>,
+    /// 24 小时开盘价
+    pub open_24h: Option<f64>,
+    /// 24 小时最高价
+    pub high_24h: Option<f64>,
+    /// 24 小时最低价
+    pub low_24h: Option<f64>,
+    /// 24 小时成交量 (对应 OKX `vol24h`，合约为张数)
+    pub volume_24h: Option<f64>,
+    /// 24 小时币种成交量 (对应 OKX `volCcy24h`)
+    pub volume_ccy_24h: Option<f64>,
+    /// 行情时间戳 (毫秒)
+    pub timestamp: i64,
+}
+impl Ticker {
+    /// 从 OKX 形态的 Ticker JSON 解析；数组输入取第一条
+    pub fn from_exchange_json(value: &Value) -> Result<Self> {
+        let value = first_object(value).ok_or_else(|| anyhow!("Ticker 数据为空"))?;
+        let symbol = text_field(value, "instId").ok_or_else(|| anyhow!("Ticker 缺少 instId"))?;
+        Ok(Self {
+            last: required_f64(value, "last")?,
+            last_size: optional_f64(value, "lastSz"),
+            bid_price: optional_f64(value, "bidPx"),
+            bid_size: optional_f64(value, "bidSz"),
+            ask_price: optional_f64(value, "askPx"),
+            ask_size: optional_f64(value, "askSz"),
+            open_24h: optional_f64(value, "open24h"),
+            high_24h: optional_f64(value, "high24h"),
+            low_24h: optional_f64(value, "low24h"),
+            volume_24h: optional_f64(value, "vol24h"),
+            volume_ccy_24h: optional_f64(value, "volCcy24h"),
+            timestamp: required_i64(value, "ts")?,
+            symbol,
+        })
+    }
+    /// 买卖价差；任一侧缺失时返回 None
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.ask_price? - self.bid_price?)
+    }
+}
+/// 单币种余额明细
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyBalance {
+    /// 币种 (如 "USDT")
+    pub currency: String,
+    /// 币种权益
+    pub equity: f64,
+    /// 现金余额
+    pub cash_balance: f64,
+    /// 可用余额
+    pub available: f64,
+    /// 冻结余额
+    pub frozen: f64,
+    /// 未实现盈亏
+    pub unrealized_pnl: f64,
+}
+/// 账户余额快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    /// 账户总权益 (美元计价)
+    pub total_equity: f64,
+    /// 各币种明细
+    pub currencies: Vec<CurrencyBalance>,
+}
+impl BalanceSnapshot {
+    /// 从 OKX 形态的余额 JSON (`[{totalEq, details: [...]}]`) 解析
+    pub fn from_exchange_json(value: &Value) -> Result<Self> {
+        let Some(value) = first_object(value) else {
+            return Ok(Self {
+                total_equity: 0.0,
+                currencies: Vec::new(),
+            });
+        };
+        let currencies = value
+            .get("details")
+            .and_then(Value::as_array)
+            .map(|details| {
+                details
+                    .iter()
+                    .map(|detail| {
+                        Ok(CurrencyBalance {
+                            currency: text_field(detail, "ccy")
+                                .ok_or_else(|| anyhow!("余额明细缺少 ccy"))?,
+                            equity: optional_f64(detail, "eq").unwrap_or(0.0),
+                            cash_balance: optional_f64(detail, "cashBal").unwrap_or(0.0),
+                            available: optional_f64(detail, "availBal").unwrap_or(0.0),
+                            frozen: optional_f64(detail, "frozenBal").unwrap_or(0.0),
+                            unrealized_pnl: optional_f64(detail, "upl").unwrap_or(0.0),
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Self {
+            total_equity: optional_f64(value, "totalEq").unwrap_or(0.0),
+            currencies,
+        })
+    }
+    /// 查找指定币种余额
+    pub fn currency(&self, currency: &str) -> Option<&CurrencyBalance> {
+        self.currencies
+            .iter()
+            .find(|balance| balance.currency.eq_ignore_ascii_case(currency))
+    }
+}
+/// 持仓量数据点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenInterestPoint {
+    /// 交易对或币种；交易所未返回时为请求参数
+    pub symbol: String,
+    /// 时间戳 (毫秒)
+    pub timestamp: i64,
+    /// 持仓量 (美元计价)
+    pub open_interest: f64,
+    /// 同周期成交额 (美元计价)；为空时表示交易所未返回该指标
+    pub volume: Option<f64>,
+}
+impl OpenInterestPoint {
+    /// 从 OKX 形态的持仓量数据解析，兼容 `[ts, oi, vol]` 数组和 `{ts, oi, vol}` 对象两种行格式
+    pub fn from_exchange_json(symbol: &str, value: &Value) -> Result<Vec<Self>> {
+        let rows = match value {
+            Value::Array(rows) => rows.as_slice(),
+            Value::Null => return Ok(Vec::new()),
+            other => std::slice::from_ref(other),
+        };
+        rows.iter()
+            .map(|row| {
+                let (ts, oi, vol) = match row {
+                    Value::Array(values) => (
+                        values.first().and_then(value_text),
+                        values.get(1).and_then(value_text),
+                        values.get(2).and_then(value_text),
+                    ),
+                    _ => (
+                        text_field(row, "ts"),
+                        text_field(row, "oi"),
+                        text_field(row, "vol"),
+                    ),
+                };
+                Ok(Self {
+                    symbol: symbol.to_string(),
+                    timestamp: parse_i64(ts.as_deref(), "ts")?,
+                    open_interest: parse_f64(oi.as_deref(), "oi")?,
+                    volume: vol.and_then(|vol| vol.parse::<f64>().ok()),
+                })
+            })
+            .collect()
+    }
+}
+impl Candle {
+    /// 从 OKX 形态的K线行解析
+    ///
+    /// 兼容 `[ts,o,h,l,c,vol,volCcy,volCcyQuote,confirm]` 数组和字段对象两种格式；
+    /// 成交量与K线仓储一致取 `volCcy`，缺失时回退到 `vol`。
+    pub fn from_exchange_json(symbol: &str, timeframe: Timeframe, value: &Value) -> Result<Self> {
+        let (ts, o, h, l, c, vol, vol_ccy, confirm) = match value {
+            Value::Array(values) => {
+                let at = |index: usize| values.get(index).and_then(value_text);
+                (at(0), at(1), at(2), at(3), at(4), at(5), at(6), at(8))
+            }
+            _ => (
+                text_field(value, "ts"),
+                text_field(value, "o"),
+                text_field(value, "h"),
+                text_field(value, "l"),
+                text_field(value, "c"),
+                text_field(value, "vol").or_else(|| text_field(value, "v")),
+                text_field(value, "volCcy").or_else(|| text_field(value, "vol_ccy")),
+                text_field(value, "confirm"),
+            ),
+        };
+        let price = |raw: Option<String>, field: &str| -> Result<Price> {
+            Price::new(parse_f64(raw.as_deref(), field)?)
+                .map_err(|error| anyhow!("K线 {} 无效: {}", field, error))
+        };
+        let volume = parse_f64(vol_ccy.or(vol).as_deref(), "volume")?;
+        let mut candle = Candle::new(
+            symbol.to_string(),
+            timeframe,
+            parse_i64(ts.as_deref(), "ts")?,
+            price(o, "open")?,
+            price(h, "high")?,
+            price(l, "low")?,
+            price(c, "close")?,
+            Volume::new(volume).map_err(|error| anyhow!("K线成交量无效: {}", error))?,
+        );
+        // 未返回确认标记时按已确认处理，与历史K线接口语义一致
+        if confirm.as_deref().unwrap_or("1") == "1" {
+            candle.confirm();
+        }
+        Ok(candle)
+    }
+}
+/// 取数组首个元素或对象本身
+fn first_object(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(values) => values.first(),
+        Value::Null => None,
+        other => Some(other),
+    }
+}
+/// 字符串或数字统一转为文本，空字符串视为缺失
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) if !text.trim().is_empty() => Some(text.trim().to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+fn text_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(value_text)
+}
+fn optional_f64(value: &Value, key: &str) -> Option<f64> {
+    text_field(value, key).and_then(|text| text.parse::<f64>().ok())
+}
+fn required_f64(value: &Value, key: &str) -> Result<f64> {
+    parse_f64(text_field(value, key).as_deref(), key)
+}
+fn required_i64(value: &Value, key: &str) -> Result<i64> {
+    parse_i64(text_field(value, key).as_deref(), key)
+}
+fn parse_f64(raw: Option<&str>, field: &str) -> Result<f64> {
+    let raw = raw.ok_or_else(|| anyhow!("缺少字段 {}", field))?;
+    raw.parse::<f64>()
+        .with_context(|| format!("解析 {} 失败: {}", field, raw))
+}
+fn parse_i64(raw: Option<&str>, field: &str) -> Result<i64> {
+    let raw = raw.ok_or_else(|| anyhow!("缺少字段 {}", field))?;
+    raw.parse::<i64>()
+        .with_context(|| format!("解析 {} 失败: {}", field, raw))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn ticker_parses_okx_shaped_payload() {
+        let value = json!([{
+            "instType": "SWAP",
+            "instId": "BTC-USDT-SWAP",
+            "last": "65000.5",
+            "lastSz": "0.01",
+            "askPx": "65001",
+            "askSz": "3",
+            "bidPx": "65000",
+            "bidSz": "2",
+            "open24h": "64000",
+            "high24h": "66000",
+            "low24h": "63000",
+            "volCcy24h": "1200.5",
+            "vol24h": "120050",
+            "ts": "1717000000000"
+        }]);
+        let ticker = Ticker::from_exchange_json(&value).unwrap();
+        assert_eq!(ticker.symbol, "BTC-USDT-SWAP");
+        assert_eq!(ticker.last, 65000.5);
+        assert_eq!(ticker.spread(), Some(1.0));
+        assert_eq!(ticker.volume_ccy_24h, Some(1200.5));
+        assert_eq!(ticker.timestamp, 1_717_000_000_000);
+    }
+
+    #[test]
+    fn ticker_treats_empty_quotes_as_missing() {
+        let value = json!({"instId": "ETH-USDT", "last": "3000", "bidPx": "", "ts": 1});
+        let ticker = Ticker::from_exchange_json(&value).unwrap();
+        assert_eq!(ticker.bid_price, None);
+        assert_eq!(ticker.spread(), None);
+        assert!(Ticker::from_exchange_json(&json!([])).is_err());
+    }
+
+    #[test]
+    fn candle_parses_array_and_object_rows() {
+        let row = json!([
+            "1717000000000",
+            "100",
+            "110",
+            "90",
+            "105",
+            "12",
+            "1260",
+            "1260",
+            "0"
+        ]);
+        let candle = Candle::from_exchange_json("BTC-USDT-SWAP", Timeframe::H1, &row).unwrap();
+        assert_eq!(candle.timestamp, 1_717_000_000_000);
+        assert_eq!(candle.close.value(), 105.0);
+        assert_eq!(candle.volume.value(), 1260.0);
+        assert!(!candle.confirmed);
+
+        let object = json!({"ts": "1717000000000", "o": "100", "h": "110", "l": "90", "c": "105", "vol": "12"});
+        let candle = Candle::from_exchange_json("BTC-USDT-SWAP", Timeframe::H1, &object).unwrap();
+        assert_eq!(candle.volume.value(), 12.0);
+        assert!(candle.confirmed);
+
+        let bad = json!(["1717000000000", "0", "110", "90", "105", "12"]);
+        assert!(Candle::from_exchange_json("BTC-USDT-SWAP", Timeframe::H1, &bad).is_err());
+    }
+
+    #[test]
+    fn balance_snapshot_parses_details() {
+        let value = json!([{
+            "totalEq": "1500.5",
+            "details": [
+                {"ccy": "USDT", "eq": "1000", "cashBal": "990", "availBal": "800", "frozenBal": "190", "upl": "10"},
+                {"ccy": "BTC", "eq": "0.01", "cashBal": "0.01", "availBal": "0.01", "frozenBal": "0", "upl": ""}
+            ]
+        }]);
+        let snapshot = BalanceSnapshot::from_exchange_json(&value).unwrap();
+        assert_eq!(snapshot.total_equity, 1500.5);
+        assert_eq!(snapshot.currencies.len(), 2);
+        let usdt = snapshot.currency("usdt").unwrap();
+        assert_eq!(usdt.available, 800.0);
+        assert_eq!(usdt.unrealized_pnl, 10.0);
+        assert_eq!(snapshot.currency("BTC").unwrap().unrealized_pnl, 0.0);
+        assert!(BalanceSnapshot::from_exchange_json(&json!([]))
+            .unwrap()
+            .currencies
+            .is_empty());
+    }
+
+    #[test]
+    fn open_interest_parses_array_and_object_rows() {
+        let arrays = json!([["1717000000000", "5000000", "120000"]]);
+        let points = OpenInterestPoint::from_exchange_json("BTC", &arrays).unwrap();
+        assert_eq!(points[0].open_interest, 5_000_000.0);
+        assert_eq!(points[0].volume, Some(120_000.0));
+
+        let objects = json!([{"ts": "1717000000000", "oi": "42", "vol": ""}]);
+        let points = OpenInterestPoint::from_exchange_json("ETH", &objects).unwrap();
+        assert_eq!(points[0].symbol, "ETH");
+        assert_eq!(points[0].volume, None);
+        assert!(OpenInterestPoint::from_exchange_json("ETH", &json!([{"ts": "x"}])).is_err());
+    }
+}
@@ -12,6 +12,7 @@ pub mod exchange_symbol_listing_event;
 pub mod external_market_snapshot;
 pub mod filtered_signal_log;
 pub mod funding_rate;
+pub mod market_data;
 pub mod order;
 pub mod position;
 pub mod strategy_config;
@@ -30,9 +31,13 @@ pub use exchange_symbol_listing_event::ExchangeSymbolListingEvent;
 pub use external_market_snapshot::ExternalMarketSnapshot;
 pub use filtered_signal_log::FilteredSignalLog;
 pub use funding_rate::FundingRate;
+pub use market_data::{BalanceSnapshot, CurrencyBalance, OpenInterestPoint, Ticker};
 pub use order::{Order, OrderError};
 pub use position::{MarginMode, Position, PositionError, PositionStatus};
-pub use strategy_config::{BasicRiskConfig, StrategyConfig};
+pub use strategy_config::{
+    AtrTrailingStopRuleConfig, BasicRiskConfig, ChandelierExitRuleConfig, RealtimeRiskRulesConfig,
+    StrategyConfig, TakeProfitLadderStep, TimeExitRuleConfig,
+};
 pub use swap_order::SwapOrder;
 pub mod fund_flow;
 pub use fund_flow::{
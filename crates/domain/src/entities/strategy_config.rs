@@ -136,6 +136,85 @@ pub struct BasicRiskConfig {
     pub max_hold_time: Option<i64>,
     /// 最大杠杆倍数 (可选)
     pub max_leverage: Option<f64>,
+    /// 实盘实时风控规则（ATR 跟踪止损、吊灯止损、时间退出、分批止盈）
+    #[serde(default)]
+    pub realtime_rules: Option<RealtimeRiskRulesConfig>,
+}
+/// 实盘实时风控规则选择；未配置的规则不启用
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RealtimeRiskRulesConfig {
+    /// ATR 跟踪止损
+    #[serde(default)]
+    pub atr_trailing_stop: Option<AtrTrailingStopRuleConfig>,
+    /// 吊灯止损（Chandelier Exit）
+    #[serde(default)]
+    pub chandelier_exit: Option<ChandelierExitRuleConfig>,
+    /// 持仓 N 根K线后的时间退出
+    #[serde(default)]
+    pub time_exit: Option<TimeExitRuleConfig>,
+    /// 分批止盈阶梯，按 `r_multiple` 升序触发
+    #[serde(default)]
+    pub take_profit_ladder: Vec<TakeProfitLadderStep>,
+}
+impl RealtimeRiskRulesConfig {
+    /// 是否启用了任意规则
+    pub fn is_empty(&self) -> bool {
+        self.atr_trailing_stop.is_none()
+            && self.chandelier_exit.is_none()
+            && self.time_exit.is_none()
+            && self.take_profit_ladder.is_empty()
+    }
+}
+/// ATR 跟踪止损：浮盈达到 `activation_r` 后，止损跟随持仓期最有利价格 - `multiplier` × ATR
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AtrTrailingStopRuleConfig {
+    /// ATR 周期
+    #[serde(default = "default_rule_atr_period")]
+    pub atr_period: usize,
+    /// ATR 乘数
+    pub multiplier: f64,
+    /// 启动跟踪所需的浮盈 R 倍数（0 表示开仓即跟踪）
+    #[serde(default)]
+    pub activation_r: f64,
+}
+/// 吊灯止损：止损 = 最近 `lookback` 根K线最高价（空头为最低价）∓ `multiplier` × ATR
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChandelierExitRuleConfig {
+    /// 极值回看K线数
+    #[serde(default = "default_chandelier_lookback")]
+    pub lookback: usize,
+    /// ATR 周期
+    #[serde(default = "default_rule_atr_period")]
+    pub atr_period: usize,
+    /// ATR 乘数
+    #[serde(default = "default_chandelier_multiplier")]
+    pub multiplier: f64,
+}
+/// 时间退出：持仓满 `max_bars` 根确认K线且浮盈未达到 `min_profit_r` 时全部平仓
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeExitRuleConfig {
+    /// 最大持仓K线数
+    pub max_bars: usize,
+    /// 免于时间退出的最低浮盈 R 倍数；None 表示到期无条件平仓
+    #[serde(default)]
+    pub min_profit_r: Option<f64>,
+}
+/// 分批止盈阶梯的一档
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TakeProfitLadderStep {
+    /// 触发的浮盈 R 倍数
+    pub r_multiple: f64,
+    /// 平仓比例，按当前剩余仓位计算（与回测 `tiered_take_profit_level_*_close_ratio` 口径一致）
+    pub close_ratio: f64,
+}
+fn default_rule_atr_period() -> usize {
+    14
+}
+fn default_chandelier_lookback() -> usize {
+    22
+}
+fn default_chandelier_multiplier() -> f64 {
+    3.0
 }
 impl Default for BasicRiskConfig {
     /// 提供默认参数，保证 回测与策略研究 在未显式配置时仍有稳定初始值。
@@ -148,6 +227,7 @@ impl Default for BasicRiskConfig {
             is_used_signal_k_line_stop_loss: None,
             max_hold_time: None,
             max_leverage: None,
+            realtime_rules: None,
         }
     }
 }
@@ -191,4 +271,27 @@ mod tests {
         config.stop();
         assert_eq!(config.status, StrategyStatus::Stopped);
     }
+    #[test]
+    fn test_basic_risk_config_parses_realtime_rules() {
+        let legacy: BasicRiskConfig =
+            serde_json::from_value(json!({"max_loss_percent": 0.02})).unwrap();
+        assert!(legacy.realtime_rules.is_none());
+        let risk: BasicRiskConfig = serde_json::from_value(json!({
+            "max_loss_percent": 0.02,
+            "realtime_rules": {
+                "atr_trailing_stop": {"multiplier": 2.0, "activation_r": 1.0},
+                "chandelier_exit": {},
+                "time_exit": {"max_bars": 48, "min_profit_r": 1.0},
+                "take_profit_ladder": [{"r_multiple": 1.5, "close_ratio": 0.5}]
+            }
+        }))
+        .unwrap();
+        let rules = risk.realtime_rules.unwrap();
+        assert_eq!(rules.atr_trailing_stop.as_ref().unwrap().atr_period, 14);
+        assert_eq!(rules.chandelier_exit.as_ref().unwrap().lookback, 22);
+        assert_eq!(rules.chandelier_exit.as_ref().unwrap().multiplier, 3.0);
+        assert_eq!(rules.time_exit.as_ref().unwrap().max_bars, 48);
+        assert_eq!(rules.take_profit_ladder.len(), 1);
+        assert!(!rules.is_empty());
+    }
 }
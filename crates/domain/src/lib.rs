@@ -44,8 +44,8 @@ pub mod value_objects;
 // 实体
 pub use entities::{
     BacktestDetail, BacktestLog, BacktestWinRateStats, BasicRiskConfig, Candle, ExchangeApiConfig,
-    MarginMode, Order, OrderError, Position, PositionError, PositionStatus, StrategyApiConfig,
-    StrategyConfig,
+    MarginMode, Order, OrderError, Position, PositionError, PositionStatus,
+    RealtimeRiskRulesConfig, StrategyApiConfig, StrategyConfig,
 };
 // 值对象
 pub use value_objects::{
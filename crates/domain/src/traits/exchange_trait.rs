@@ -2,6 +2,8 @@
 //!
 //! 定义交易所的统一接口，支持多交易所扩展
 //! 遵循依赖倒置原则：services层依赖接口，infrastructure层实现接口
+use crate::entities::{BalanceSnapshot, Candle, OpenInterestPoint, Ticker};
+use crate::enums::Timeframe;
 use anyhow::Result;
 use async_trait::async_trait;
 /// 交易所市场数据接口
@@ -51,6 +53,49 @@ pub trait ExchangeMarketData: Send + Sync {
         timeframe: &str,
         limit: Option<usize>,
     ) -> Result<Vec<serde_json::Value>>;
+    /// 获取标准化Ticker
+    ///
+    /// 默认实现解析 `fetch_ticker` 返回的 OKX 形态 JSON，适配器可覆盖为直接映射
+    async fn fetch_ticker_normalized(&self, symbol: &str) -> Result<Ticker> {
+        let value = self.fetch_ticker(symbol).await?;
+        Ticker::from_exchange_json(&value)
+    }
+    /// 批量获取标准化Ticker
+    async fn fetch_tickers_normalized(&self, inst_type: &str) -> Result<Vec<Ticker>> {
+        self.fetch_tickers(inst_type)
+            .await?
+            .iter()
+            .map(Ticker::from_exchange_json)
+            .collect()
+    }
+    /// 获取标准化历史K线，参数语义与 `fetch_candles` 一致
+    async fn fetch_candles_normalized(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: Option<i64>,
+        end: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Candle>> {
+        self.fetch_candles(symbol, timeframe.as_str(), start, end, limit)
+            .await?
+            .iter()
+            .map(|row| Candle::from_exchange_json(symbol, timeframe, row))
+            .collect()
+    }
+    /// 获取标准化最新K线
+    async fn fetch_latest_candles_normalized(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        limit: Option<usize>,
+    ) -> Result<Vec<Candle>> {
+        self.fetch_latest_candles(symbol, timeframe.as_str(), limit)
+            .await?
+            .iter()
+            .map(|row| Candle::from_exchange_json(symbol, timeframe, row))
+            .collect()
+    }
 }
 /// 交易所账户接口
 #[async_trait]
@@ -74,6 +119,11 @@ pub trait ExchangeAccount: Send + Sync {
         &self,
         currencies: Option<&[String]>,
     ) -> Result<serde_json::Value>;
+    /// 获取标准化账户余额快照
+    async fn fetch_balance_snapshot(&self, currency: Option<&str>) -> Result<BalanceSnapshot> {
+        let value = self.fetch_balance(currency).await?;
+        BalanceSnapshot::from_exchange_json(&value)
+    }
 }
 /// 交易所合约接口
 #[async_trait]
@@ -97,6 +147,19 @@ pub trait ExchangeContracts: Send + Sync {
         end: Option<i64>,
         period: Option<&str>,
     ) -> Result<serde_json::Value>;
+    /// 获取标准化持仓量序列
+    async fn fetch_open_interest_points(
+        &self,
+        inst_id: Option<&str>,
+        begin: Option<i64>,
+        end: Option<i64>,
+        period: Option<&str>,
+    ) -> Result<Vec<OpenInterestPoint>> {
+        let value = self
+            .fetch_open_interest_volume(inst_id, begin, end, period)
+            .await?;
+        OpenInterestPoint::from_exchange_json(inst_id.unwrap_or_default(), &value)
+    }
 }
 /// 交易所公共数据接口
 #[async_trait]
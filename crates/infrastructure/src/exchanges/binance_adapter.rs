@@ -0,0 +1,729 @@
+//! Binance U 本位合约适配器
+//!
+//! 单币种行情、K 线与账户余额经 `crypto_exc_all` 的 Binance 支持获取，签名与代理由 SDK 统一处理；
+//! SDK 未覆盖的全市场 Ticker 与持仓量历史只读取无需签名的公共 fapi 接口。
+//! 返回值统一整理为 OKX 形状的 JSON，保证现有 K 线、Ticker、余额解析逻辑无需区分交易所。
+use super::okx_adapter::ensure_legacy_signed_read_only_allowed;
+use super::rest_support::{
+    compact_symbol_from_inst_id, currency_selected, non_empty_env, string_at, string_field,
+    swap_inst_id_from_compact_symbol, HttpRestTransport, RestTransport,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use crypto_exc_all::{
+    Balance, BinanceExchangeConfig, Candle, CandleQuery, CryptoSdk, ExchangeId, Instrument,
+    SdkConfig, Ticker,
+};
+use rust_quant_domain::traits::{
+    ExchangeAccount, ExchangeContracts, ExchangeMarketData, ExchangePublicData,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+
+const DEFAULT_BINANCE_FAPI_BASE_URL: &str = "https://fapi.binance.com";
+/// 公共行情场景下 SDK 需要的占位凭证，不会用于签名请求
+const PUBLIC_MARKET_ONLY: &str = "public-market-only";
+/// Binance 单次 K 线请求上限
+const BINANCE_MAX_KLINE_LIMIT: usize = 1500;
+/// Binance 单次持仓量历史请求上限
+const BINANCE_MAX_OPEN_INTEREST_LIMIT: usize = 500;
+
+/// 基于 crypto_exc_all 的 Binance 客户端
+struct BinanceSdkClient {
+    /// crypto_exc_all SDK，只配置 Binance。
+    sdk: CryptoSdk,
+    /// 是否配置了真实 API 凭证；未配置时只能访问公共行情。
+    has_credentials: bool,
+}
+
+impl BinanceSdkClient {
+    /// 从 BINANCE_API_KEY / BINANCE_API_SECRET / BINANCE_PROXY_URL 构建
+    fn from_env() -> Result<Self> {
+        let api_key = non_empty_env("BINANCE_API_KEY");
+        let api_secret = non_empty_env("BINANCE_API_SECRET");
+        let has_credentials = api_key.is_some() && api_secret.is_some();
+        let config = SdkConfig {
+            binance: Some(BinanceExchangeConfig {
+                api_key: api_key.unwrap_or_else(|| PUBLIC_MARKET_ONLY.to_string()),
+                api_secret: api_secret.unwrap_or_else(|| PUBLIC_MARKET_ONLY.to_string()),
+                api_url: None,
+                sapi_api_url: None,
+                web_api_url: None,
+                ws_stream_url: None,
+                api_timeout_ms: None,
+                recv_window_ms: None,
+                proxy_url: non_empty_env("BINANCE_PROXY_URL"),
+            }),
+            ..SdkConfig::default()
+        };
+        let sdk = CryptoSdk::from_config(config)
+            .map_err(|error| anyhow!("创建 Binance crypto_exc_all sdk 失败: {}", error))?;
+        Ok(Self {
+            sdk,
+            has_credentials,
+        })
+    }
+
+    /// 要求配置签名密钥，账户接口在创建时即校验
+    fn require_credentials(&self) -> Result<()> {
+        if !self.has_credentials {
+            return Err(anyhow!(
+                "BINANCE_API_KEY and BINANCE_API_SECRET are required for Binance account queries"
+            ));
+        }
+        Ok(())
+    }
+
+    /// 获取单个合约 Ticker
+    async fn ticker(&self, symbol: &str) -> Result<Ticker> {
+        let instrument = instrument_from_symbol(symbol)?;
+        self.sdk
+            .market(ExchangeId::Binance)
+            .map_err(|error| anyhow!("Binance market facade unavailable: {}", error))?
+            .ticker(&instrument)
+            .await
+            .map_err(|error| anyhow!("Binance ticker request failed: {}", error))
+    }
+
+    /// 拉取 K 线并整理为 OKX 格式（新到旧）
+    async fn klines(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Value>> {
+        let mut query = CandleQuery::new(
+            instrument_from_symbol(symbol)?,
+            binance_interval_from_timeframe(timeframe),
+        );
+        if let Some(start_time) = start_time {
+            query = query.with_start_time(start_time.max(0) as u64);
+        }
+        if let Some(end_time) = end_time {
+            query = query.with_end_time(end_time.max(0) as u64);
+        }
+        if let Some(limit) = limit {
+            query = query.with_limit(limit.min(BINANCE_MAX_KLINE_LIMIT) as u32);
+        }
+        let candles = self
+            .sdk
+            .market(ExchangeId::Binance)
+            .map_err(|error| anyhow!("Binance market facade unavailable: {}", error))?
+            .candles(query)
+            .await
+            .map_err(|error| anyhow!("Binance candles request failed: {}", error))?;
+        Ok(okx_candles_from_exchange(
+            &candles,
+            Utc::now().timestamp_millis(),
+        ))
+    }
+
+    /// 获取合约账户各币种余额
+    async fn balances(&self) -> Result<Vec<Balance>> {
+        self.sdk
+            .account(ExchangeId::Binance)
+            .map_err(|error| anyhow!("Binance account facade unavailable: {}", error))?
+            .balances()
+            .await
+            .map_err(|error| anyhow!("Binance balances request failed: {}", error))
+    }
+}
+
+/// Binance 公共 fapi 客户端
+///
+/// 仅承载 crypto_exc_all 尚未提供的全市场 Ticker 与持仓量历史，均为无需签名的公共接口。
+struct BinancePublicFapi {
+    /// REST 传输层。
+    transport: Arc<dyn RestTransport>,
+}
+
+impl BinancePublicFapi {
+    /// 从 BINANCE_FAPI_BASE_URL / BINANCE_PROXY_URL 构建
+    fn from_env() -> Result<Self> {
+        let base_url = non_empty_env("BINANCE_FAPI_BASE_URL")
+            .unwrap_or_else(|| DEFAULT_BINANCE_FAPI_BASE_URL.to_string());
+        let proxy_url = non_empty_env("BINANCE_PROXY_URL");
+        Ok(Self {
+            transport: Arc::new(HttpRestTransport::new(&base_url, proxy_url.as_deref())?),
+        })
+    }
+
+    /// 发送 Binance 公共 GET 请求
+    async fn get_json(&self, path: &str, params: &[(&str, String)]) -> Result<Value> {
+        let query: Vec<(String, String)> = params
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect();
+        self.transport.get_json(path, &query, &[]).await
+    }
+}
+
+/// Binance市场数据适配器
+pub struct BinanceMarketDataAdapter {
+    /// 外部服务客户端。
+    client: BinanceSdkClient,
+    /// 全市场 Ticker 使用的公共接口客户端。
+    public: BinancePublicFapi,
+}
+
+impl BinanceMarketDataAdapter {
+    /// 构建 配置、基础设施和运行时 所需实例，并集中初始化依赖和默认状态。
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: BinanceSdkClient::from_env()?,
+            public: BinancePublicFapi::from_env()?,
+        })
+    }
+}
+
+#[async_trait]
+impl ExchangeMarketData for BinanceMarketDataAdapter {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_ticker(&self, symbol: &str) -> Result<Value> {
+        debug!("Binance: 获取Ticker - {}", symbol);
+        let ticker = self.client.ticker(symbol).await?;
+        Ok(Value::Array(vec![okx_ticker_from_exchange(&ticker)]))
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_tickers(&self, inst_type: &str) -> Result<Vec<Value>> {
+        debug!("Binance: 批量获取Ticker - {}", inst_type);
+        if !inst_type.eq_ignore_ascii_case("SWAP") {
+            return Err(anyhow!(
+                "Binance adapter only supports USDⓈ-M SWAP tickers, got {}",
+                inst_type
+            ));
+        }
+        let tickers = self.public.get_json("/fapi/v1/ticker/24hr", &[]).await?;
+        let books = self
+            .public
+            .get_json("/fapi/v1/ticker/bookTicker", &[])
+            .await?;
+        let books_by_symbol: HashMap<&str, &Value> = books
+            .as_array()
+            .map(|rows| {
+                rows.iter()
+                    .filter_map(|row| Some((row.get("symbol")?.as_str()?, row)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(tickers
+            .as_array()
+            .ok_or_else(|| anyhow!("Binance ticker response is not an array"))?
+            .iter()
+            .filter_map(|ticker| {
+                let symbol = ticker.get("symbol")?.as_str()?;
+                okx_ticker_from_binance(ticker, books_by_symbol.get(symbol).copied())
+            })
+            .collect())
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    ///
+    /// 与 OKX 语义保持一致：`start` 表示返回早于该时间的数据，`end` 表示返回晚于该时间的数据。
+    async fn fetch_candles(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        start: Option<i64>,
+        end: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Value>> {
+        debug!("Binance: 获取历史K线 - {} {}", symbol, timeframe);
+        let end_time = start.map(|after| after - 1);
+        let start_time = end.map(|before| before + 1);
+        self.client
+            .klines(symbol, timeframe, start_time, end_time, limit)
+            .await
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_latest_candles(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Value>> {
+        debug!("Binance: 获取最新K线 - {} {}", symbol, timeframe);
+        self.client
+            .klines(symbol, timeframe, None, None, limit)
+            .await
+    }
+}
+
+/// Binance账户适配器
+pub struct BinanceAccountAdapter {
+    /// 外部服务客户端。
+    client: BinanceSdkClient,
+}
+
+impl BinanceAccountAdapter {
+    /// 构建 配置、基础设施和运行时 所需实例，并集中初始化依赖和默认状态。
+    pub fn new() -> Result<Self> {
+        ensure_legacy_signed_read_only_allowed("Binance")?;
+        let client = BinanceSdkClient::from_env()?;
+        client.require_credentials()?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl ExchangeAccount for BinanceAccountAdapter {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_balance(&self, currency: Option<&str>) -> Result<Value> {
+        debug!("Binance: 获取账户余额 - {:?}", currency);
+        let balances = self.client.balances().await?;
+        Ok(okx_balance_from_exchange(&balances, currency))
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_asset_balances(&self, currencies: Option<&[String]>) -> Result<Value> {
+        debug!("Binance: 获取资产余额 - {:?}", currencies);
+        let balances = self.client.balances().await?;
+        Ok(okx_asset_balances_from_exchange(&balances, currencies))
+    }
+}
+
+/// Binance合约适配器
+pub struct BinanceContractsAdapter {
+    /// 外部服务客户端，用于拉取同周期 K 线成交额。
+    client: BinanceSdkClient,
+    /// 持仓量历史使用的公共接口客户端。
+    public: BinancePublicFapi,
+}
+
+impl BinanceContractsAdapter {
+    /// 构建 配置、基础设施和运行时 所需实例，并集中初始化依赖和默认状态。
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: BinanceSdkClient::from_env()?,
+            public: BinancePublicFapi::from_env()?,
+        })
+    }
+}
+
+#[async_trait]
+impl ExchangeContracts for BinanceContractsAdapter {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    ///
+    /// 返回 `{ts, oi, vol}`：`oi` 为持仓价值（USDT），`vol` 为同周期 K 线成交额（USDT）。
+    async fn fetch_open_interest_volume(
+        &self,
+        inst_id: Option<&str>,
+        begin: Option<i64>,
+        end: Option<i64>,
+        period: Option<&str>,
+    ) -> Result<Value> {
+        debug!("Binance: 获取持仓量数据 - {:?} {:?}", inst_id, period);
+        let base = inst_id.ok_or_else(|| anyhow!("Binance open interest requires inst_id"))?;
+        let symbol = compact_symbol_from_inst_id(&format!("{}-USDT", base));
+        let period = binance_interval_from_timeframe(period.unwrap_or("1D"));
+        let mut params = vec![
+            ("symbol", symbol.clone()),
+            ("period", period.clone()),
+            ("limit", BINANCE_MAX_OPEN_INTEREST_LIMIT.to_string()),
+        ];
+        if let Some(begin) = begin {
+            params.push(("startTime", begin.to_string()));
+        }
+        if let Some(end) = end {
+            params.push(("endTime", end.to_string()));
+        }
+        let open_interest = self
+            .public
+            .get_json("/futures/data/openInterestHist", &params)
+            .await?;
+        let volumes = self
+            .client
+            .klines(
+                &symbol,
+                &period,
+                begin,
+                end,
+                Some(BINANCE_MAX_OPEN_INTEREST_LIMIT),
+            )
+            .await?;
+        Ok(okx_open_interest_volume_from_binance(
+            &open_interest,
+            &volumes,
+        ))
+    }
+}
+
+/// Binance公共数据适配器
+pub struct BinancePublicDataAdapter;
+
+impl BinancePublicDataAdapter {
+    pub fn new() -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+#[async_trait]
+impl ExchangePublicData for BinancePublicDataAdapter {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_announcements(
+        &self,
+        _ann_type: Option<&str>,
+        _page_size: Option<&str>,
+    ) -> Result<Vec<String>> {
+        debug!("Binance: 获取公告数据");
+        // Binance Futures REST 不提供公告接口，返回空
+        Ok(vec![])
+    }
+}
+
+/// OKX 周期（`1H`、`4H`、`1D`、`1Dutc`）转为 Binance interval（`1h`、`4h`、`1d`）
+fn binance_interval_from_timeframe(timeframe: &str) -> String {
+    match timeframe.trim() {
+        "1Dutc" | "1DUTC" | "1dutc" => "1d".to_string(),
+        // 月线在两边都是大写 M，不能与分钟混淆
+        value if value.ends_with('M') => value.to_string(),
+        value => value.to_ascii_lowercase(),
+    }
+}
+
+/// 交易对（`BTC-USDT-SWAP`、`BTC-USDT` 或 `BTCUSDT`）转为 crypto_exc_all 永续合约标的
+fn instrument_from_symbol(symbol: &str) -> Result<Instrument> {
+    let inst_id = if symbol.contains('-') {
+        symbol.trim().to_string()
+    } else {
+        swap_inst_id_from_compact_symbol(symbol.trim())
+    };
+    let mut parts = inst_id.split('-').map(str::trim);
+    match (parts.next(), parts.next()) {
+        (Some(base), Some(quote)) if !base.is_empty() && !quote.is_empty() => {
+            Ok(Instrument::perp(base, quote).with_settlement(quote))
+        }
+        _ => Err(anyhow!("无法解析 Binance 交易对: {}", symbol)),
+    }
+}
+
+/// crypto_exc_all K 线转为 OKX 格式 `[ts, o, h, l, c, vol, volCcy, volCcyQuote, confirm]`（新到旧）
+fn okx_candles_from_exchange(candles: &[Candle], now_ms: i64) -> Vec<Value> {
+    let mut rows: Vec<(u64, Value)> = candles
+        .iter()
+        .filter_map(|candle| {
+            let open_time = candle.open_time?;
+            let confirmed = candle.closed.unwrap_or_else(|| {
+                candle
+                    .close_time
+                    .map(|close_time| (close_time as i64) < now_ms)
+                    .unwrap_or(true)
+            });
+            Some((
+                open_time,
+                json!([
+                    open_time.to_string(),
+                    candle.open,
+                    candle.high,
+                    candle.low,
+                    candle.close,
+                    candle.volume,
+                    candle.volume,
+                    candle.quote_volume.clone().unwrap_or_default(),
+                    if confirmed { "1" } else { "0" },
+                ]),
+            ))
+        })
+        .collect();
+    rows.sort_by_key(|(open_time, _)| std::cmp::Reverse(*open_time));
+    rows.into_iter().map(|(_, row)| row).collect()
+}
+
+/// crypto_exc_all Ticker 转为 OKX ticker 字段
+///
+/// OKX 永续的 `vol24h` 为张数、`volCcy24h` 为基础币成交量；Binance U 本位合约一张即一个基础币，
+/// 两个字段都取基础币成交量，计价币成交额不写入。
+fn okx_ticker_from_exchange(ticker: &Ticker) -> Value {
+    let field = |value: &Option<String>| value.clone().unwrap_or_default();
+    let base_volume = ticker
+        .base_volume_24h
+        .clone()
+        .or_else(|| ticker.volume_24h.clone())
+        .unwrap_or_default();
+    json!({
+        "instType": "SWAP",
+        "instId": swap_inst_id_from_compact_symbol(&ticker.exchange_symbol),
+        "last": ticker.last_price,
+        "lastSz": field(&ticker.last_size),
+        "askPx": field(&ticker.ask_price),
+        "askSz": field(&ticker.ask_size),
+        "bidPx": field(&ticker.bid_price),
+        "bidSz": field(&ticker.bid_size),
+        "open24h": field(&ticker.open_24h),
+        "high24h": field(&ticker.high_24h),
+        "low24h": field(&ticker.low_24h),
+        "volCcy24h": base_volume,
+        "vol24h": base_volume,
+        "sodUtc0": field(&ticker.sod_utc0),
+        "sodUtc8": field(&ticker.sod_utc8),
+        "ts": ticker.timestamp.map(|ts| ts.to_string()).unwrap_or_default(),
+    })
+}
+
+/// Binance 24hr ticker（可选 bookTicker）转为 OKX ticker 字段，成交量口径同 `okx_ticker_from_exchange`
+fn okx_ticker_from_binance(ticker: &Value, book: Option<&Value>) -> Option<Value> {
+    let symbol = ticker.get("symbol")?.as_str()?;
+    let book_field = |key: &str| book.and_then(|book| string_field(book, key));
+    let base_volume = string_field(ticker, "volume").unwrap_or_default();
+    Some(json!({
+        "instType": "SWAP",
+        "instId": swap_inst_id_from_compact_symbol(symbol),
+        "last": string_field(ticker, "lastPrice")?,
+        "lastSz": string_field(ticker, "lastQty").unwrap_or_default(),
+        "askPx": book_field("askPrice").unwrap_or_default(),
+        "askSz": book_field("askQty").unwrap_or_default(),
+        "bidPx": book_field("bidPrice").unwrap_or_default(),
+        "bidSz": book_field("bidQty").unwrap_or_default(),
+        "open24h": string_field(ticker, "openPrice").unwrap_or_default(),
+        "high24h": string_field(ticker, "highPrice").unwrap_or_default(),
+        "low24h": string_field(ticker, "lowPrice").unwrap_or_default(),
+        "volCcy24h": base_volume,
+        "vol24h": base_volume,
+        "sodUtc0": "",
+        "sodUtc8": "",
+        "ts": string_field(ticker, "closeTime").unwrap_or_default(),
+    }))
+}
+
+/// crypto_exc_all 余额转为 OKX 账户余额 `[{totalEq, uTime, details}]`
+///
+/// `totalEq` 为所选币种权益之和，U 本位合约账户通常只有 USDT/USDC 两类保证金。
+fn okx_balance_from_exchange(balances: &[Balance], currency: Option<&str>) -> Value {
+    let currencies = currency.map(|currency| vec![currency.to_string()]);
+    let selected: Vec<&Balance> = balances
+        .iter()
+        .filter(|balance| currency_selected(&balance.asset, currencies.as_deref()))
+        .collect();
+    let total_eq: f64 = selected
+        .iter()
+        .filter_map(|balance| balance.total.parse::<f64>().ok())
+        .sum();
+    let details: Vec<Value> = selected
+        .iter()
+        .map(|balance| {
+            json!({
+                "ccy": balance.asset,
+                "eq": balance.total,
+                "cashBal": string_field(&balance.raw, "walletBalance")
+                    .or_else(|| string_field(&balance.raw, "balance"))
+                    .unwrap_or_else(|| balance.total.clone()),
+                "availBal": balance.available,
+                "upl": string_field(&balance.raw, "unrealizedProfit")
+                    .or_else(|| string_field(&balance.raw, "crossUnPnl"))
+                    .unwrap_or_default(),
+                "uTime": string_field(&balance.raw, "updateTime").unwrap_or_default(),
+            })
+        })
+        .collect();
+    let u_time = selected
+        .iter()
+        .filter_map(|balance| {
+            string_field(&balance.raw, "updateTime")?
+                .parse::<i64>()
+                .ok()
+        })
+        .max()
+        .map(|ts| ts.to_string())
+        .unwrap_or_default();
+    json!([{
+        "totalEq": total_eq.to_string(),
+        "uTime": u_time,
+        "details": details,
+    }])
+}
+
+/// crypto_exc_all 余额转为 OKX 资金账户余额 `[{ccy, bal, availBal, frozenBal}]`
+fn okx_asset_balances_from_exchange(balances: &[Balance], currencies: Option<&[String]>) -> Value {
+    let rows: Vec<Value> = balances
+        .iter()
+        .filter(|balance| currency_selected(&balance.asset, currencies))
+        .map(|balance| {
+            let frozen = balance.frozen.clone().unwrap_or_else(|| {
+                let frozen = balance.total.parse::<f64>().unwrap_or(0.0)
+                    - balance.available.parse::<f64>().unwrap_or(0.0);
+                frozen.max(0.0).to_string()
+            });
+            json!({
+                "ccy": balance.asset,
+                "bal": balance.total,
+                "availBal": balance.available,
+                "frozenBal": frozen,
+            })
+        })
+        .collect();
+    Value::Array(rows)
+}
+
+/// 合并 Binance 持仓量历史与同周期 K 线成交额，输出 OKX 形状 `[{ts, oi, vol}]`（新到旧）
+fn okx_open_interest_volume_from_binance(open_interest: &Value, candles: &[Value]) -> Value {
+    let volume_by_ts: HashMap<String, String> = candles
+        .iter()
+        .filter_map(|candle| {
+            let values = candle.as_array()?;
+            Some((string_at(values, 0)?, string_at(values, 7)?))
+        })
+        .collect();
+    let mut rows: Vec<(i64, Value)> = open_interest
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    let ts = row.get("timestamp")?.as_i64()?;
+                    let oi = string_field(row, "sumOpenInterestValue")?;
+                    let vol = volume_by_ts
+                        .get(&ts.to_string())
+                        .cloned()
+                        .unwrap_or_default();
+                    Some((ts, json!({ "ts": ts.to_string(), "oi": oi, "vol": vol })))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    rows.sort_by_key(|(ts, _)| std::cmp::Reverse(*ts));
+    Value::Array(rows.into_iter().map(|(_, row)| row).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_mapping_follows_binance_conventions() {
+        assert_eq!(binance_interval_from_timeframe("1H"), "1h");
+        assert_eq!(binance_interval_from_timeframe("4H"), "4h");
+        assert_eq!(binance_interval_from_timeframe("1Dutc"), "1d");
+        assert_eq!(binance_interval_from_timeframe("5m"), "5m");
+        assert_eq!(binance_interval_from_timeframe("1M"), "1M");
+    }
+
+    #[test]
+    fn symbols_are_parsed_into_perpetual_instruments() {
+        let expected = Instrument::perp("BTC", "USDT").with_settlement("USDT");
+        assert_eq!(instrument_from_symbol("BTC-USDT-SWAP").unwrap(), expected);
+        assert_eq!(instrument_from_symbol("BTCUSDT").unwrap(), expected);
+        assert!(instrument_from_symbol("BTC").is_err());
+    }
+
+    #[test]
+    fn ticker_and_balances_use_okx_field_names() {
+        let ticker = Ticker {
+            exchange: ExchangeId::Binance,
+            instrument: Instrument::perp("BTC", "USDT"),
+            instrument_type: Some("swap".to_string()),
+            exchange_symbol: "BTCUSDT".to_string(),
+            last_price: "65000.1".to_string(),
+            last_size: None,
+            bid_price: Some("65000.0".to_string()),
+            bid_size: None,
+            ask_price: Some("65000.2".to_string()),
+            ask_size: None,
+            open_24h: None,
+            high_24h: None,
+            low_24h: None,
+            volume_24h: Some("10".to_string()),
+            base_volume_24h: None,
+            quote_volume_24h: Some("650001".to_string()),
+            sod_utc0: None,
+            sod_utc8: None,
+            timestamp: Some(1_700_000_000_000),
+            raw: json!({}),
+        };
+        let converted = okx_ticker_from_exchange(&ticker);
+        assert_eq!(converted["instId"], "BTC-USDT-SWAP");
+        assert_eq!(converted["last"], "65000.1");
+        assert_eq!(converted["bidPx"], "65000.0");
+        assert_eq!(converted["vol24h"], "10");
+        assert_eq!(converted["volCcy24h"], "10");
+        assert_eq!(converted["ts"], "1700000000000");
+
+        let raw = json!({
+            "symbol": "ETHUSDT",
+            "lastPrice": "3000.5",
+            "volume": "1200.5",
+            "quoteVolume": "3601500.25",
+            "closeTime": 1_700_000_000_000u64
+        });
+        let converted = okx_ticker_from_binance(&raw, None).unwrap();
+        assert_eq!(converted["instId"], "ETH-USDT-SWAP");
+        assert_eq!(converted["vol24h"], "1200.5");
+        assert_eq!(converted["volCcy24h"], "1200.5");
+
+        let balances = vec![
+            Balance {
+                exchange: ExchangeId::Binance,
+                asset: "USDT".to_string(),
+                total: "100.5".to_string(),
+                available: "80.5".to_string(),
+                frozen: None,
+                raw: json!({ "walletBalance": "98.5", "unrealizedProfit": "2", "updateTime": 7 }),
+            },
+            Balance {
+                exchange: ExchangeId::Binance,
+                asset: "BNB".to_string(),
+                total: "1".to_string(),
+                available: "1".to_string(),
+                frozen: None,
+                raw: json!({}),
+            },
+        ];
+        let usdt = vec!["usdt".to_string()];
+        assert_eq!(
+            okx_asset_balances_from_exchange(&balances, Some(&usdt)),
+            json!([{ "ccy": "USDT", "bal": "100.5", "availBal": "80.5", "frozenBal": "20" }])
+        );
+        let account = okx_balance_from_exchange(&balances, Some("USDT"));
+        assert_eq!(account[0]["totalEq"], "100.5");
+        assert_eq!(account[0]["uTime"], "7");
+        assert_eq!(
+            account[0]["details"],
+            json!([{
+                "ccy": "USDT",
+                "eq": "100.5",
+                "cashBal": "98.5",
+                "availBal": "80.5",
+                "upl": "2",
+                "uTime": "7"
+            }])
+        );
+    }
+
+    #[test]
+    fn open_interest_joins_kline_turnover_newest_first() {
+        let open_interest = json!([
+            { "timestamp": 1000, "sumOpenInterest": "1", "sumOpenInterestValue": "10" },
+            { "timestamp": 2000, "sumOpenInterest": "2", "sumOpenInterestValue": "20" }
+        ]);
+        let candles = vec![json!(["2000", "1", "1", "1", "1", "5", "5", "50", "1"])];
+        let converted = okx_open_interest_volume_from_binance(&open_interest, &candles);
+        assert_eq!(
+            converted,
+            json!([
+                { "ts": "2000", "oi": "20", "vol": "50" },
+                { "ts": "1000", "oi": "10", "vol": "" }
+            ])
+        );
+    }
+}
@@ -0,0 +1,630 @@
+//! Bitget U 本位永续适配器
+//!
+//! 基于 Bitget v2 REST API（productType=USDT-FUTURES）实现 domain 层定义的交易所接口，
+//! 返回值整理为 OKX 形状的 JSON，与 OKX/Binance 适配器保持同一解析口径。
+use super::okx_adapter::ensure_legacy_signed_read_only_allowed;
+use super::rest_support::{
+    compact_symbol_from_inst_id, currency_selected, multiply_decimal_strings, non_empty_env,
+    okx_candle_row, okx_candle_ts, paginate_candles_backwards, string_at, string_field,
+    swap_inst_id_from_compact_symbol, timeframe_duration_ms, HttpRestTransport, RestTransport,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rust_quant_domain::traits::{
+    ExchangeAccount, ExchangeContracts, ExchangeMarketData, ExchangePublicData,
+};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::sync::Arc;
+use tracing::debug;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const DEFAULT_BITGET_BASE_URL: &str = "https://api.bitget.com";
+const BITGET_PRODUCT_TYPE: &str = "USDT-FUTURES";
+const BITGET_SUCCESS_CODE: &str = "00000";
+/// Bitget 单次 K 线请求上限
+const BITGET_MAX_KLINE_LIMIT: usize = 1000;
+/// Bitget 支持的 K 线周期（不含 utc 后缀）
+const BITGET_GRANULARITIES: [&str; 13] = [
+    "1m", "3m", "5m", "15m", "30m", "1H", "4H", "6H", "12H", "1D", "3D", "1W", "1M",
+];
+
+/// Bitget 签名密钥
+struct BitgetCredentials {
+    api_key: String,
+    api_secret: String,
+    passphrase: String,
+}
+
+/// Bitget v2 REST 客户端
+struct BitgetClient {
+    /// REST 传输层。
+    transport: Arc<dyn RestTransport>,
+    /// K 线单页条数。
+    kline_page_limit: usize,
+    /// 签名密钥；公共行情接口不需要。
+    credentials: Option<BitgetCredentials>,
+}
+
+impl BitgetClient {
+    /// 从 BITGET_BASE_URL / BITGET_PROXY_URL / BITGET_API_KEY / BITGET_API_SECRET / BITGET_PASSPHRASE 构建
+    fn from_env() -> Result<Self> {
+        let base_url =
+            non_empty_env("BITGET_BASE_URL").unwrap_or_else(|| DEFAULT_BITGET_BASE_URL.to_string());
+        let proxy_url = non_empty_env("BITGET_PROXY_URL");
+        let credentials = match (
+            non_empty_env("BITGET_API_KEY"),
+            non_empty_env("BITGET_API_SECRET"),
+            non_empty_env("BITGET_PASSPHRASE"),
+        ) {
+            (Some(api_key), Some(api_secret), Some(passphrase)) => Some(BitgetCredentials {
+                api_key,
+                api_secret,
+                passphrase,
+            }),
+            _ => None,
+        };
+        Ok(Self {
+            transport: Arc::new(HttpRestTransport::new(&base_url, proxy_url.as_deref())?),
+            kline_page_limit: BITGET_MAX_KLINE_LIMIT,
+            credentials,
+        })
+    }
+
+    fn public(transport: Arc<dyn RestTransport>) -> Self {
+        Self {
+            transport,
+            kline_page_limit: BITGET_MAX_KLINE_LIMIT,
+            credentials: None,
+        }
+    }
+
+    /// 发送公共 GET 请求并取出 `data`
+    async fn public_get(&self, path: &str, params: &[(&str, String)]) -> Result<Value> {
+        let query = owned_query(params);
+        let response = self.transport.get_json(path, &query, &[]).await?;
+        bitget_data(response)
+    }
+
+    /// 发送私有 GET 请求：签名串为 `timestamp + GET + path + ?query`，Base64 编码
+    async fn signed_get(&self, path: &str, params: &[(&str, String)]) -> Result<Value> {
+        let Some(credentials) = &self.credentials else {
+            return Err(anyhow!(
+                "BITGET_API_KEY, BITGET_API_SECRET and BITGET_PASSPHRASE are required for Bitget account queries"
+            ));
+        };
+        let query = owned_query(params);
+        let query_string = query
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        let request_path = if query_string.is_empty() {
+            path.to_string()
+        } else {
+            format!("{path}?{query_string}")
+        };
+        let timestamp = Utc::now().timestamp_millis().to_string();
+        let mut mac = HmacSha256::new_from_slice(credentials.api_secret.as_bytes())
+            .map_err(|_| anyhow!("invalid Bitget API secret for HMAC"))?;
+        mac.update(format!("{timestamp}GET{request_path}").as_bytes());
+        let headers = [
+            ("ACCESS-KEY".to_string(), credentials.api_key.clone()),
+            (
+                "ACCESS-SIGN".to_string(),
+                BASE64_STANDARD.encode(mac.finalize().into_bytes()),
+            ),
+            ("ACCESS-TIMESTAMP".to_string(), timestamp),
+            (
+                "ACCESS-PASSPHRASE".to_string(),
+                credentials.passphrase.clone(),
+            ),
+            ("locale".to_string(), "en-US".to_string()),
+        ];
+        let response = self.transport.get_json(path, &query, &headers).await?;
+        bitget_data(response)
+    }
+
+    /// 拉取 K 线并整理为 OKX 格式（新到旧），`limit` 超过单页上限时自动翻页
+    async fn klines(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Value>> {
+        let symbol = compact_symbol_from_inst_id(symbol);
+        let granularity = bitget_granularity_from_timeframe(timeframe)?;
+        let duration_ms = timeframe_duration_ms(timeframe)
+            .ok_or_else(|| anyhow!("unsupported Bitget timeframe: {}", timeframe))?;
+        paginate_candles_backwards(limit, self.kline_page_limit, end_time, |end, page_limit| {
+            let mut params = vec![
+                ("symbol", symbol.clone()),
+                ("productType", BITGET_PRODUCT_TYPE.to_string()),
+                ("granularity", granularity.clone()),
+            ];
+            if let Some(start_time) = start_time {
+                params.push(("startTime", start_time.to_string()));
+            }
+            if let Some(end) = end {
+                params.push(("endTime", end.to_string()));
+            }
+            if let Some(page_limit) = page_limit {
+                params.push(("limit", page_limit.to_string()));
+            }
+            async move {
+                let data = self
+                    .public_get("/api/v2/mix/market/candles", &params)
+                    .await?;
+                Ok(okx_candles_from_bitget(
+                    &data,
+                    duration_ms,
+                    Utc::now().timestamp_millis(),
+                ))
+            }
+        })
+        .await
+    }
+}
+
+/// Bitget市场数据适配器
+pub struct BitgetMarketDataAdapter {
+    /// 外部服务客户端。
+    client: BitgetClient,
+}
+
+impl BitgetMarketDataAdapter {
+    /// 构建 配置、基础设施和运行时 所需实例，并集中初始化依赖和默认状态。
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: BitgetClient::from_env()?,
+        })
+    }
+
+    /// 使用指定传输层构建，契约测试用于回放录制响应
+    pub fn with_transport(transport: Arc<dyn RestTransport>) -> Self {
+        Self {
+            client: BitgetClient::public(transport),
+        }
+    }
+
+    /// 调整 K 线单页条数，不超过交易所上限
+    pub fn with_kline_page_limit(mut self, page_limit: usize) -> Self {
+        self.client.kline_page_limit = page_limit.clamp(1, BITGET_MAX_KLINE_LIMIT);
+        self
+    }
+}
+
+#[async_trait]
+impl ExchangeMarketData for BitgetMarketDataAdapter {
+    fn name(&self) -> &'static str {
+        "bitget"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_ticker(&self, symbol: &str) -> Result<Value> {
+        debug!("Bitget: 获取Ticker - {}", symbol);
+        let params = [
+            ("symbol", compact_symbol_from_inst_id(symbol)),
+            ("productType", BITGET_PRODUCT_TYPE.to_string()),
+        ];
+        let data = self
+            .client
+            .public_get("/api/v2/mix/market/ticker", &params)
+            .await?;
+        let tickers = okx_tickers_from_bitget(&data);
+        if tickers.is_empty() {
+            return Err(anyhow!("Bitget ticker response missing symbol: {}", symbol));
+        }
+        Ok(Value::Array(tickers))
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_tickers(&self, inst_type: &str) -> Result<Vec<Value>> {
+        debug!("Bitget: 批量获取Ticker - {}", inst_type);
+        if !inst_type.eq_ignore_ascii_case("SWAP") {
+            return Err(anyhow!(
+                "Bitget adapter only supports USDT-FUTURES SWAP tickers, got {}",
+                inst_type
+            ));
+        }
+        let params = [("productType", BITGET_PRODUCT_TYPE.to_string())];
+        let data = self
+            .client
+            .public_get("/api/v2/mix/market/tickers", &params)
+            .await?;
+        Ok(okx_tickers_from_bitget(&data))
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    ///
+    /// 与 OKX 语义保持一致：`start` 表示返回早于该时间的数据，`end` 表示返回晚于该时间的数据。
+    async fn fetch_candles(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        start: Option<i64>,
+        end: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Value>> {
+        debug!("Bitget: 获取历史K线 - {} {}", symbol, timeframe);
+        let end_time = start.map(|after| after - 1);
+        let start_time = end.map(|before| before + 1);
+        self.client
+            .klines(symbol, timeframe, start_time, end_time, limit)
+            .await
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_latest_candles(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Value>> {
+        debug!("Bitget: 获取最新K线 - {} {}", symbol, timeframe);
+        self.client
+            .klines(symbol, timeframe, None, None, limit)
+            .await
+    }
+}
+
+/// Bitget账户适配器
+pub struct BitgetAccountAdapter {
+    /// 外部服务客户端。
+    client: BitgetClient,
+}
+
+impl BitgetAccountAdapter {
+    /// 构建 配置、基础设施和运行时 所需实例，并集中初始化依赖和默认状态。
+    pub fn new() -> Result<Self> {
+        ensure_legacy_signed_read_only_allowed("Bitget")?;
+        let client = BitgetClient::from_env()?;
+        if client.credentials.is_none() {
+            return Err(anyhow!(
+                "BITGET_API_KEY, BITGET_API_SECRET and BITGET_PASSPHRASE are required for Bitget account queries"
+            ));
+        }
+        Ok(Self { client })
+    }
+
+    /// 使用指定传输层与密钥构建，契约测试用于回放录制响应
+    pub fn with_transport(
+        transport: Arc<dyn RestTransport>,
+        api_key: impl Into<String>,
+        api_secret: impl Into<String>,
+        passphrase: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: BitgetClient {
+                transport,
+                kline_page_limit: BITGET_MAX_KLINE_LIMIT,
+                credentials: Some(BitgetCredentials {
+                    api_key: api_key.into(),
+                    api_secret: api_secret.into(),
+                    passphrase: passphrase.into(),
+                }),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl ExchangeAccount for BitgetAccountAdapter {
+    fn name(&self) -> &'static str {
+        "bitget"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_balance(&self, currency: Option<&str>) -> Result<Value> {
+        debug!("Bitget: 获取账户余额 - {:?}", currency);
+        let params = [("productType", BITGET_PRODUCT_TYPE.to_string())];
+        let data = self
+            .client
+            .signed_get("/api/v2/mix/account/accounts", &params)
+            .await?;
+        Ok(okx_balance_from_bitget_accounts(&data, currency))
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_asset_balances(&self, currencies: Option<&[String]>) -> Result<Value> {
+        debug!("Bitget: 获取资产余额 - {:?}", currencies);
+        let data = self
+            .client
+            .signed_get("/api/v2/spot/account/assets", &[])
+            .await?;
+        Ok(okx_asset_balances_from_bitget(&data, currencies))
+    }
+}
+
+/// Bitget合约适配器
+pub struct BitgetContractsAdapter {
+    /// 外部服务客户端。
+    client: BitgetClient,
+}
+
+impl BitgetContractsAdapter {
+    /// 构建 配置、基础设施和运行时 所需实例，并集中初始化依赖和默认状态。
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: BitgetClient::from_env()?,
+        })
+    }
+
+    /// 使用指定传输层构建，契约测试用于回放录制响应
+    pub fn with_transport(transport: Arc<dyn RestTransport>) -> Self {
+        Self {
+            client: BitgetClient::public(transport),
+        }
+    }
+}
+
+#[async_trait]
+impl ExchangeContracts for BitgetContractsAdapter {
+    fn name(&self) -> &'static str {
+        "bitget"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    ///
+    /// Bitget 只提供当前持仓量，返回单条 `{ts, oi, vol}`：`oi` 按最新一根 K 线收盘价折算，
+    /// `vol` 为该 K 线成交额；`begin`/`end` 不生效。
+    async fn fetch_open_interest_volume(
+        &self,
+        inst_id: Option<&str>,
+        begin: Option<i64>,
+        end: Option<i64>,
+        period: Option<&str>,
+    ) -> Result<Value> {
+        debug!(
+            "Bitget: 获取持仓量数据 - {:?} {:?} (忽略区间 {:?}-{:?})",
+            inst_id, period, begin, end
+        );
+        let base = inst_id.ok_or_else(|| anyhow!("Bitget open interest requires inst_id"))?;
+        let symbol = compact_symbol_from_inst_id(&format!("{}-USDT", base));
+        let params = [
+            ("symbol", symbol.clone()),
+            ("productType", BITGET_PRODUCT_TYPE.to_string()),
+        ];
+        let open_interest = self
+            .client
+            .public_get("/api/v2/mix/market/open-interest", &params)
+            .await?;
+        let candles = self
+            .client
+            .klines(&symbol, period.unwrap_or("1D"), None, None, Some(1))
+            .await?;
+        Ok(okx_open_interest_volume_from_bitget(
+            &open_interest,
+            candles.first(),
+        ))
+    }
+}
+
+/// Bitget公共数据适配器
+pub struct BitgetPublicDataAdapter;
+
+impl BitgetPublicDataAdapter {
+    pub fn new() -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+#[async_trait]
+impl ExchangePublicData for BitgetPublicDataAdapter {
+    fn name(&self) -> &'static str {
+        "bitget"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_announcements(
+        &self,
+        _ann_type: Option<&str>,
+        _page_size: Option<&str>,
+    ) -> Result<Vec<String>> {
+        debug!("Bitget: 获取公告数据");
+        // 公告暂不接入，返回空
+        Ok(vec![])
+    }
+}
+
+fn owned_query(params: &[(&str, String)]) -> Vec<(String, String)> {
+    params
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect()
+}
+
+/// 校验 `code` 并取出 `data`
+fn bitget_data(response: Value) -> Result<Value> {
+    let code = response.get("code").and_then(Value::as_str);
+    if code != Some(BITGET_SUCCESS_CODE) {
+        return Err(anyhow!(
+            "Bitget API error {:?}: {}",
+            code,
+            response
+                .get("msg")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        ));
+    }
+    response
+        .get("data")
+        .cloned()
+        .ok_or_else(|| anyhow!("Bitget response missing data"))
+}
+
+/// OKX 周期转为 Bitget granularity：分钟小写 `m`，小时及以上大写，保留 `utc` 后缀
+fn bitget_granularity_from_timeframe(timeframe: &str) -> Result<String> {
+    let value = timeframe.trim();
+    let (body, utc) = match value
+        .strip_suffix("utc")
+        .or_else(|| value.strip_suffix("UTC"))
+    {
+        Some(body) => (body, "utc"),
+        None => (value, ""),
+    };
+    // 分钟周期保持小写 m，月线为大写 M，其余周期单位统一大写
+    let granularity = if body.ends_with('m') || body == "1M" {
+        body.to_string()
+    } else {
+        body.to_ascii_uppercase()
+    };
+    if !BITGET_GRANULARITIES.contains(&granularity.as_str()) {
+        return Err(anyhow!("unsupported Bitget timeframe: {}", timeframe));
+    }
+    Ok(format!("{granularity}{utc}"))
+}
+
+/// Bitget candles `[ts, o, h, l, c, baseVolume, quoteVolume]`（旧到新）转为 OKX K 线（新到旧）
+fn okx_candles_from_bitget(data: &Value, duration_ms: i64, now_ms: i64) -> Vec<Value> {
+    let mut candles: Vec<Value> = data
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| okx_candle_row(row.as_array()?, 5, 6, duration_ms, now_ms))
+                .collect()
+        })
+        .unwrap_or_default();
+    candles.sort_by_key(|candle| std::cmp::Reverse(okx_candle_ts(candle)));
+    candles
+}
+
+/// Bitget tickers 转为 OKX ticker 字段
+///
+/// OKX 永续的 `volCcy24h` 是基础币成交量，与 `vol24h` 一样取 `baseVolume`，不取 `quoteVolume`。
+fn okx_tickers_from_bitget(data: &Value) -> Vec<Value> {
+    let Some(rows) = data.as_array() else {
+        return Vec::new();
+    };
+    rows.iter()
+        .filter_map(|ticker| {
+            let symbol = ticker.get("symbol")?.as_str()?;
+            let field = |key: &str| string_field(ticker, key).unwrap_or_default();
+            Some(json!({
+                "instType": "SWAP",
+                "instId": swap_inst_id_from_compact_symbol(symbol),
+                "last": string_field(ticker, "lastPr")?,
+                "lastSz": "",
+                "askPx": field("askPr"),
+                "askSz": field("askSz"),
+                "bidPx": field("bidPr"),
+                "bidSz": field("bidSz"),
+                "open24h": field("open24h"),
+                "high24h": field("high24h"),
+                "low24h": field("low24h"),
+                "volCcy24h": field("baseVolume"),
+                "vol24h": field("baseVolume"),
+                "sodUtc0": field("openUtc"),
+                "sodUtc8": "",
+                "ts": field("ts"),
+            }))
+        })
+        .collect()
+}
+
+/// Bitget 合约账户转为 OKX 账户余额 `[{totalEq, details}]`，`totalEq` 取各保证金币种的 USDT 权益之和
+fn okx_balance_from_bitget_accounts(data: &Value, currency: Option<&str>) -> Value {
+    let currencies = currency.map(|currency| vec![currency.to_string()]);
+    let accounts = data.as_array().cloned().unwrap_or_default();
+    let mut total_eq = 0.0;
+    let details: Vec<Value> = accounts
+        .iter()
+        .filter_map(|account| {
+            let ccy = account.get("marginCoin")?.as_str()?;
+            if !currency_selected(ccy, currencies.as_deref()) {
+                return None;
+            }
+            total_eq += string_field(account, "usdtEquity")
+                .and_then(|value| value.parse::<f64>().ok())
+                .unwrap_or(0.0);
+            Some(json!({
+                "ccy": ccy,
+                "eq": string_field(account, "accountEquity").unwrap_or_default(),
+                "availBal": string_field(account, "available").unwrap_or_default(),
+                "frozenBal": string_field(account, "locked").unwrap_or_default(),
+                "upl": string_field(account, "unrealizedPL").unwrap_or_default(),
+            }))
+        })
+        .collect();
+    json!([{ "totalEq": total_eq.to_string(), "details": details }])
+}
+
+/// Bitget 现货资产转为 OKX 资金账户余额 `[{ccy, bal, availBal, frozenBal}]`
+fn okx_asset_balances_from_bitget(data: &Value, currencies: Option<&[String]>) -> Value {
+    let rows: Vec<Value> = data
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    let ccy = row.get("coin")?.as_str()?;
+                    if !currency_selected(ccy, currencies) {
+                        return None;
+                    }
+                    let amount = |key: &str| {
+                        string_field(row, key)
+                            .and_then(|value| value.parse::<f64>().ok())
+                            .unwrap_or(0.0)
+                    };
+                    let available = string_field(row, "available")?;
+                    let frozen = amount("frozen") + amount("locked");
+                    Some(json!({
+                        "ccy": ccy,
+                        "bal": (amount("available") + frozen).to_string(),
+                        "availBal": available,
+                        "frozenBal": frozen.to_string(),
+                    }))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Value::Array(rows)
+}
+
+/// 当前持仓量（基础币）按最新 K 线折算，输出单条 OKX 形状 `[{ts, oi, vol}]`
+fn okx_open_interest_volume_from_bitget(data: &Value, latest_candle: Option<&Value>) -> Value {
+    let size = data
+        .get("openInterestList")
+        .and_then(Value::as_array)
+        .and_then(|rows| rows.first())
+        .and_then(|row| string_field(row, "size"));
+    let candle = latest_candle.and_then(Value::as_array);
+    let (Some(size), Some(candle), Some(ts)) = (size, candle, string_field(data, "ts")) else {
+        return json!([]);
+    };
+    let (Some(close), Some(turnover)) = (string_at(candle, 4), string_at(candle, 7)) else {
+        return json!([]);
+    };
+    match multiply_decimal_strings(&size, &close) {
+        Some(oi) => json!([{ "ts": ts, "oi": oi, "vol": turnover }]),
+        None => json!([]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn granularity_mapping_follows_bitget_conventions() {
+        assert_eq!(bitget_granularity_from_timeframe("5m").unwrap(), "5m");
+        assert_eq!(bitget_granularity_from_timeframe("1h").unwrap(), "1H");
+        assert_eq!(bitget_granularity_from_timeframe("4H").unwrap(), "4H");
+        assert_eq!(bitget_granularity_from_timeframe("1Dutc").unwrap(), "1Dutc");
+        assert_eq!(bitget_granularity_from_timeframe("1M").unwrap(), "1M");
+        assert!(bitget_granularity_from_timeframe("2H").is_err());
+    }
+
+    #[test]
+    fn error_envelope_is_surfaced() {
+        let error = bitget_data(json!({ "code": "40034", "msg": "Parameter does not exist" }))
+            .expect_err("non-success code must fail");
+        assert!(error.to_string().contains("Parameter does not exist"));
+    }
+}
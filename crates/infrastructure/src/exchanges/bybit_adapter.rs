@@ -0,0 +1,664 @@
+//! Bybit U 本位永续适配器
+//!
+//! 基于 Bybit v5 REST API（category=linear）实现 domain 层定义的交易所接口，
+//! 返回值整理为 OKX 形状的 JSON，与 OKX/Binance 适配器保持同一解析口径。
+use super::okx_adapter::ensure_legacy_signed_read_only_allowed;
+use super::rest_support::{
+    compact_symbol_from_inst_id, currency_selected, multiply_decimal_strings, non_empty_env,
+    okx_candle_row, paginate_candles_backwards, string_at, string_field,
+    swap_inst_id_from_compact_symbol, timeframe_duration_ms, HttpRestTransport, RestTransport,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rust_quant_domain::traits::{
+    ExchangeAccount, ExchangeContracts, ExchangeMarketData, ExchangePublicData,
+};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const DEFAULT_BYBIT_BASE_URL: &str = "https://api.bybit.com";
+const BYBIT_CATEGORY: &str = "linear";
+const BYBIT_RECV_WINDOW: &str = "5000";
+/// Bybit 单次 K 线请求上限
+const BYBIT_MAX_KLINE_LIMIT: usize = 1000;
+/// Bybit 单次持仓量请求上限
+const BYBIT_MAX_OPEN_INTEREST_LIMIT: usize = 200;
+
+/// Bybit v5 REST 客户端
+struct BybitClient {
+    /// REST 传输层。
+    transport: Arc<dyn RestTransport>,
+    /// K 线单页条数。
+    kline_page_limit: usize,
+    /// Bybit API Key；公共行情接口不需要。
+    api_key: Option<String>,
+    /// Bybit API Secret，用于签名私有请求。
+    api_secret: Option<String>,
+}
+
+impl BybitClient {
+    /// 从 BYBIT_BASE_URL / BYBIT_PROXY_URL / BYBIT_API_KEY / BYBIT_API_SECRET 构建
+    fn from_env() -> Result<Self> {
+        let base_url =
+            non_empty_env("BYBIT_BASE_URL").unwrap_or_else(|| DEFAULT_BYBIT_BASE_URL.to_string());
+        let proxy_url = non_empty_env("BYBIT_PROXY_URL");
+        Ok(Self {
+            transport: Arc::new(HttpRestTransport::new(&base_url, proxy_url.as_deref())?),
+            kline_page_limit: BYBIT_MAX_KLINE_LIMIT,
+            api_key: non_empty_env("BYBIT_API_KEY"),
+            api_secret: non_empty_env("BYBIT_API_SECRET"),
+        })
+    }
+
+    fn public(transport: Arc<dyn RestTransport>) -> Self {
+        Self {
+            transport,
+            kline_page_limit: BYBIT_MAX_KLINE_LIMIT,
+            api_key: None,
+            api_secret: None,
+        }
+    }
+
+    /// 发送公共 GET 请求并取出 `result`
+    async fn public_get(&self, path: &str, params: &[(&str, String)]) -> Result<Value> {
+        let query = owned_query(params);
+        let response = self.transport.get_json(path, &query, &[]).await?;
+        bybit_result(response)
+    }
+
+    /// 发送私有 GET 请求：签名串为 `timestamp + apiKey + recvWindow + queryString`
+    async fn signed_get(&self, path: &str, params: &[(&str, String)]) -> Result<Value> {
+        let (Some(api_key), Some(api_secret)) = (&self.api_key, &self.api_secret) else {
+            return Err(anyhow!(
+                "BYBIT_API_KEY and BYBIT_API_SECRET are required for Bybit account queries"
+            ));
+        };
+        let query = owned_query(params);
+        let query_string = query
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        let timestamp = Utc::now().timestamp_millis().to_string();
+        let mut mac = HmacSha256::new_from_slice(api_secret.as_bytes())
+            .map_err(|_| anyhow!("invalid Bybit API secret for HMAC"))?;
+        mac.update(format!("{timestamp}{api_key}{BYBIT_RECV_WINDOW}{query_string}").as_bytes());
+        let headers = [
+            ("X-BAPI-API-KEY".to_string(), api_key.clone()),
+            ("X-BAPI-TIMESTAMP".to_string(), timestamp),
+            (
+                "X-BAPI-RECV-WINDOW".to_string(),
+                BYBIT_RECV_WINDOW.to_string(),
+            ),
+            (
+                "X-BAPI-SIGN".to_string(),
+                hex::encode(mac.finalize().into_bytes()),
+            ),
+        ];
+        let response = self.transport.get_json(path, &query, &headers).await?;
+        bybit_result(response)
+    }
+
+    /// 拉取 K 线并整理为 OKX 格式（新到旧），`limit` 超过单页上限时自动翻页
+    async fn klines(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Value>> {
+        let symbol = compact_symbol_from_inst_id(symbol);
+        let interval = bybit_interval_from_timeframe(timeframe)?;
+        let duration_ms = timeframe_duration_ms(timeframe)
+            .ok_or_else(|| anyhow!("unsupported Bybit timeframe: {}", timeframe))?;
+        paginate_candles_backwards(limit, self.kline_page_limit, end_time, |end, page_limit| {
+            let mut params = vec![
+                ("category", BYBIT_CATEGORY.to_string()),
+                ("symbol", symbol.clone()),
+                ("interval", interval.clone()),
+            ];
+            if let Some(start_time) = start_time {
+                params.push(("start", start_time.to_string()));
+            }
+            if let Some(end) = end {
+                params.push(("end", end.to_string()));
+            }
+            if let Some(page_limit) = page_limit {
+                params.push(("limit", page_limit.to_string()));
+            }
+            async move {
+                let result = self.public_get("/v5/market/kline", &params).await?;
+                Ok(okx_candles_from_bybit_kline(
+                    &result,
+                    duration_ms,
+                    Utc::now().timestamp_millis(),
+                ))
+            }
+        })
+        .await
+    }
+}
+
+/// Bybit市场数据适配器
+pub struct BybitMarketDataAdapter {
+    /// 外部服务客户端。
+    client: BybitClient,
+}
+
+impl BybitMarketDataAdapter {
+    /// 构建 配置、基础设施和运行时 所需实例，并集中初始化依赖和默认状态。
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: BybitClient::from_env()?,
+        })
+    }
+
+    /// 使用指定传输层构建，契约测试用于回放录制响应
+    pub fn with_transport(transport: Arc<dyn RestTransport>) -> Self {
+        Self {
+            client: BybitClient::public(transport),
+        }
+    }
+
+    /// 调整 K 线单页条数，不超过交易所上限
+    pub fn with_kline_page_limit(mut self, page_limit: usize) -> Self {
+        self.client.kline_page_limit = page_limit.clamp(1, BYBIT_MAX_KLINE_LIMIT);
+        self
+    }
+}
+
+#[async_trait]
+impl ExchangeMarketData for BybitMarketDataAdapter {
+    fn name(&self) -> &'static str {
+        "bybit"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_ticker(&self, symbol: &str) -> Result<Value> {
+        debug!("Bybit: 获取Ticker - {}", symbol);
+        let params = [
+            ("category", BYBIT_CATEGORY.to_string()),
+            ("symbol", compact_symbol_from_inst_id(symbol)),
+        ];
+        let result = self
+            .client
+            .public_get("/v5/market/tickers", &params)
+            .await?;
+        let tickers = okx_tickers_from_bybit(&result);
+        if tickers.is_empty() {
+            return Err(anyhow!("Bybit ticker response missing symbol: {}", symbol));
+        }
+        Ok(Value::Array(tickers))
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_tickers(&self, inst_type: &str) -> Result<Vec<Value>> {
+        debug!("Bybit: 批量获取Ticker - {}", inst_type);
+        if !inst_type.eq_ignore_ascii_case("SWAP") {
+            return Err(anyhow!(
+                "Bybit adapter only supports linear SWAP tickers, got {}",
+                inst_type
+            ));
+        }
+        let params = [("category", BYBIT_CATEGORY.to_string())];
+        let result = self
+            .client
+            .public_get("/v5/market/tickers", &params)
+            .await?;
+        Ok(okx_tickers_from_bybit(&result))
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    ///
+    /// 与 OKX 语义保持一致：`start` 表示返回早于该时间的数据，`end` 表示返回晚于该时间的数据。
+    async fn fetch_candles(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        start: Option<i64>,
+        end: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Value>> {
+        debug!("Bybit: 获取历史K线 - {} {}", symbol, timeframe);
+        let end_time = start.map(|after| after - 1);
+        let start_time = end.map(|before| before + 1);
+        self.client
+            .klines(symbol, timeframe, start_time, end_time, limit)
+            .await
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_latest_candles(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Value>> {
+        debug!("Bybit: 获取最新K线 - {} {}", symbol, timeframe);
+        self.client
+            .klines(symbol, timeframe, None, None, limit)
+            .await
+    }
+}
+
+/// Bybit账户适配器
+pub struct BybitAccountAdapter {
+    /// 外部服务客户端。
+    client: BybitClient,
+}
+
+impl BybitAccountAdapter {
+    /// 构建 配置、基础设施和运行时 所需实例，并集中初始化依赖和默认状态。
+    pub fn new() -> Result<Self> {
+        ensure_legacy_signed_read_only_allowed("Bybit")?;
+        let client = BybitClient::from_env()?;
+        if client.api_key.is_none() || client.api_secret.is_none() {
+            return Err(anyhow!(
+                "BYBIT_API_KEY and BYBIT_API_SECRET are required for Bybit account queries"
+            ));
+        }
+        Ok(Self { client })
+    }
+
+    /// 使用指定传输层与密钥构建，契约测试用于回放录制响应
+    pub fn with_transport(
+        transport: Arc<dyn RestTransport>,
+        api_key: impl Into<String>,
+        api_secret: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: BybitClient {
+                transport,
+                kline_page_limit: BYBIT_MAX_KLINE_LIMIT,
+                api_key: Some(api_key.into()),
+                api_secret: Some(api_secret.into()),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl ExchangeAccount for BybitAccountAdapter {
+    fn name(&self) -> &'static str {
+        "bybit"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_balance(&self, currency: Option<&str>) -> Result<Value> {
+        debug!("Bybit: 获取账户余额 - {:?}", currency);
+        let mut params = vec![("accountType", "UNIFIED".to_string())];
+        if let Some(currency) = currency {
+            params.push(("coin", currency.to_ascii_uppercase()));
+        }
+        let result = self
+            .client
+            .signed_get("/v5/account/wallet-balance", &params)
+            .await?;
+        Ok(okx_balance_from_bybit_wallet(&result, currency))
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_asset_balances(&self, currencies: Option<&[String]>) -> Result<Value> {
+        debug!("Bybit: 获取资产余额 - {:?}", currencies);
+        let params = [("accountType", "FUND".to_string())];
+        let result = self
+            .client
+            .signed_get("/v5/asset/transfer/query-account-coins-balance", &params)
+            .await?;
+        Ok(okx_asset_balances_from_bybit(&result, currencies))
+    }
+}
+
+/// Bybit合约适配器
+pub struct BybitContractsAdapter {
+    /// 外部服务客户端。
+    client: BybitClient,
+}
+
+impl BybitContractsAdapter {
+    /// 构建 配置、基础设施和运行时 所需实例，并集中初始化依赖和默认状态。
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: BybitClient::from_env()?,
+        })
+    }
+
+    /// 使用指定传输层构建，契约测试用于回放录制响应
+    pub fn with_transport(transport: Arc<dyn RestTransport>) -> Self {
+        Self {
+            client: BybitClient::public(transport),
+        }
+    }
+}
+
+#[async_trait]
+impl ExchangeContracts for BybitContractsAdapter {
+    fn name(&self) -> &'static str {
+        "bybit"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    ///
+    /// 返回 `{ts, oi, vol}`：`oi` 为持仓量按同周期收盘价折算的价值，`vol` 为同周期成交额。
+    async fn fetch_open_interest_volume(
+        &self,
+        inst_id: Option<&str>,
+        begin: Option<i64>,
+        end: Option<i64>,
+        period: Option<&str>,
+    ) -> Result<Value> {
+        debug!("Bybit: 获取持仓量数据 - {:?} {:?}", inst_id, period);
+        let base = inst_id.ok_or_else(|| anyhow!("Bybit open interest requires inst_id"))?;
+        let symbol = compact_symbol_from_inst_id(&format!("{}-USDT", base));
+        let period = period.unwrap_or("1D");
+        let mut params = vec![
+            ("category", BYBIT_CATEGORY.to_string()),
+            ("symbol", symbol.clone()),
+            ("intervalTime", bybit_open_interest_interval(period)?),
+            ("limit", BYBIT_MAX_OPEN_INTEREST_LIMIT.to_string()),
+        ];
+        if let Some(begin) = begin {
+            params.push(("startTime", begin.to_string()));
+        }
+        if let Some(end) = end {
+            params.push(("endTime", end.to_string()));
+        }
+        let open_interest = self
+            .client
+            .public_get("/v5/market/open-interest", &params)
+            .await?;
+        let candles = self
+            .client
+            .klines(
+                &symbol,
+                period,
+                begin,
+                end,
+                Some(BYBIT_MAX_OPEN_INTEREST_LIMIT),
+            )
+            .await?;
+        Ok(okx_open_interest_volume_from_bybit(
+            &open_interest,
+            &candles,
+        ))
+    }
+}
+
+/// Bybit公共数据适配器
+pub struct BybitPublicDataAdapter;
+
+impl BybitPublicDataAdapter {
+    pub fn new() -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+#[async_trait]
+impl ExchangePublicData for BybitPublicDataAdapter {
+    fn name(&self) -> &'static str {
+        "bybit"
+    }
+
+    /// 加载 配置、基础设施和运行时 运行所需数据，并把缺失或异常交给调用方处理。
+    async fn fetch_announcements(
+        &self,
+        _ann_type: Option<&str>,
+        _page_size: Option<&str>,
+    ) -> Result<Vec<String>> {
+        debug!("Bybit: 获取公告数据");
+        // 公告暂不接入，返回空
+        Ok(vec![])
+    }
+}
+
+fn owned_query(params: &[(&str, String)]) -> Vec<(String, String)> {
+    params
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect()
+}
+
+/// 校验 `retCode` 并取出 `result`
+fn bybit_result(response: Value) -> Result<Value> {
+    let code = response.get("retCode").and_then(Value::as_i64);
+    if code != Some(0) {
+        return Err(anyhow!(
+            "Bybit API error {:?}: {}",
+            code,
+            response
+                .get("retMsg")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        ));
+    }
+    response
+        .get("result")
+        .cloned()
+        .ok_or_else(|| anyhow!("Bybit response missing result"))
+}
+
+/// OKX 周期转为 Bybit interval：分钟数或 `D`/`W`/`M`
+fn bybit_interval_from_timeframe(timeframe: &str) -> Result<String> {
+    let value = timeframe.trim();
+    let unsupported = || anyhow!("unsupported Bybit timeframe: {}", timeframe);
+    if value.ends_with('M') {
+        return (value == "1M")
+            .then(|| "M".to_string())
+            .ok_or_else(unsupported);
+    }
+    match value.to_ascii_lowercase().as_str() {
+        "1d" | "1dutc" => Ok("D".to_string()),
+        "1w" | "1wutc" => Ok("W".to_string()),
+        _ => {
+            let minutes = timeframe_duration_ms(value).ok_or_else(unsupported)? / 60_000;
+            match minutes {
+                1 | 3 | 5 | 15 | 30 | 60 | 120 | 240 | 360 | 720 => Ok(minutes.to_string()),
+                _ => Err(unsupported()),
+            }
+        }
+    }
+}
+
+/// OKX 周期转为 Bybit 持仓量 intervalTime
+fn bybit_open_interest_interval(period: &str) -> Result<String> {
+    match period.trim().to_ascii_lowercase().as_str() {
+        "5m" => Ok("5min".to_string()),
+        "15m" => Ok("15min".to_string()),
+        "30m" => Ok("30min".to_string()),
+        "1h" => Ok("1h".to_string()),
+        "4h" => Ok("4h".to_string()),
+        "1d" | "1dutc" => Ok("1d".to_string()),
+        _ => Err(anyhow!(
+            "unsupported Bybit open interest period: {}",
+            period
+        )),
+    }
+}
+
+/// Bybit kline `result.list`（新到旧）`[start, o, h, l, c, volume, turnover]` 转为 OKX K 线
+fn okx_candles_from_bybit_kline(result: &Value, duration_ms: i64, now_ms: i64) -> Vec<Value> {
+    result
+        .get("list")
+        .and_then(Value::as_array)
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| okx_candle_row(row.as_array()?, 5, 6, duration_ms, now_ms))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Bybit tickers `result.list` 转为 OKX ticker 字段
+///
+/// 线性合约按基础币计量，`vol24h`/`volCcy24h` 都取 `volume24h`，计价币成交额 `turnover24h` 不写入。
+fn okx_tickers_from_bybit(result: &Value) -> Vec<Value> {
+    let Some(rows) = result.get("list").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    rows.iter()
+        .filter_map(|ticker| {
+            let symbol = ticker.get("symbol")?.as_str()?;
+            let field = |key: &str| string_field(ticker, key).unwrap_or_default();
+            Some(json!({
+                "instType": "SWAP",
+                "instId": swap_inst_id_from_compact_symbol(symbol),
+                "last": string_field(ticker, "lastPrice")?,
+                "lastSz": "",
+                "askPx": field("ask1Price"),
+                "askSz": field("ask1Size"),
+                "bidPx": field("bid1Price"),
+                "bidSz": field("bid1Size"),
+                "open24h": field("prevPrice24h"),
+                "high24h": field("highPrice24h"),
+                "low24h": field("lowPrice24h"),
+                "volCcy24h": field("volume24h"),
+                "vol24h": field("volume24h"),
+                "sodUtc0": "",
+                "sodUtc8": "",
+                "ts": Utc::now().timestamp_millis().to_string(),
+            }))
+        })
+        .collect()
+}
+
+/// Bybit 统一账户钱包转为 OKX 账户余额 `[{totalEq, details}]`
+fn okx_balance_from_bybit_wallet(result: &Value, currency: Option<&str>) -> Value {
+    let currencies = currency.map(|currency| vec![currency.to_string()]);
+    let accounts = result
+        .get("list")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let balances: Vec<Value> = accounts
+        .iter()
+        .map(|account| {
+            let details: Vec<Value> = account
+                .get("coin")
+                .and_then(Value::as_array)
+                .map(|coins| {
+                    coins
+                        .iter()
+                        .filter_map(|coin| {
+                            let ccy = coin.get("coin")?.as_str()?;
+                            if !currency_selected(ccy, currencies.as_deref()) {
+                                return None;
+                            }
+                            let wallet = string_field(coin, "walletBalance").unwrap_or_default();
+                            let avail = string_field(coin, "availableToWithdraw")
+                                .filter(|value| !value.is_empty())
+                                .unwrap_or_else(|| wallet.clone());
+                            Some(json!({
+                                "ccy": ccy,
+                                "eq": string_field(coin, "equity").unwrap_or_default(),
+                                "cashBal": wallet,
+                                "availBal": avail,
+                                "frozenBal": string_field(coin, "locked").unwrap_or_default(),
+                                "upl": string_field(coin, "unrealisedPnl").unwrap_or_default(),
+                            }))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            json!({
+                "totalEq": string_field(account, "totalEquity").unwrap_or_default(),
+                "details": details,
+            })
+        })
+        .collect();
+    Value::Array(balances)
+}
+
+/// Bybit 资金账户余额转为 OKX 资金账户余额 `[{ccy, bal, availBal, frozenBal}]`
+fn okx_asset_balances_from_bybit(result: &Value, currencies: Option<&[String]>) -> Value {
+    let rows: Vec<Value> = result
+        .get("balance")
+        .and_then(Value::as_array)
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    let ccy = row.get("coin")?.as_str()?;
+                    if !currency_selected(ccy, currencies) {
+                        return None;
+                    }
+                    let bal = string_field(row, "walletBalance")?;
+                    let avail_bal = string_field(row, "transferBalance").unwrap_or_default();
+                    let frozen =
+                        bal.parse::<f64>().unwrap_or(0.0) - avail_bal.parse::<f64>().unwrap_or(0.0);
+                    Some(json!({
+                        "ccy": ccy,
+                        "bal": bal,
+                        "availBal": avail_bal,
+                        "frozenBal": frozen.max(0.0).to_string(),
+                    }))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Value::Array(rows)
+}
+
+/// 合并持仓量（基础币）与同周期 K 线，输出 OKX 形状 `[{ts, oi, vol}]`（新到旧）。
+/// 找不到对应 K 线时无法折算价值，该点被丢弃。
+fn okx_open_interest_volume_from_bybit(result: &Value, candles: &[Value]) -> Value {
+    let candle_by_ts: HashMap<String, (String, String)> = candles
+        .iter()
+        .filter_map(|candle| {
+            let values = candle.as_array()?;
+            Some((
+                string_at(values, 0)?,
+                (string_at(values, 4)?, string_at(values, 7)?),
+            ))
+        })
+        .collect();
+    let mut rows: Vec<(i64, Value)> = result
+        .get("list")
+        .and_then(Value::as_array)
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    let ts = string_field(row, "timestamp")?;
+                    let (close, turnover) = candle_by_ts.get(&ts)?;
+                    let oi = multiply_decimal_strings(&string_field(row, "openInterest")?, close)?;
+                    Some((
+                        ts.parse::<i64>().ok()?,
+                        json!({ "ts": ts, "oi": oi, "vol": turnover }),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    rows.sort_by_key(|(ts, _)| std::cmp::Reverse(*ts));
+    Value::Array(rows.into_iter().map(|(_, row)| row).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_mapping_follows_bybit_conventions() {
+        assert_eq!(bybit_interval_from_timeframe("1m").unwrap(), "1");
+        assert_eq!(bybit_interval_from_timeframe("4H").unwrap(), "240");
+        assert_eq!(bybit_interval_from_timeframe("1Dutc").unwrap(), "D");
+        assert_eq!(bybit_interval_from_timeframe("1W").unwrap(), "W");
+        assert_eq!(bybit_interval_from_timeframe("1M").unwrap(), "M");
+        assert!(bybit_interval_from_timeframe("2D").is_err());
+        assert_eq!(bybit_open_interest_interval("1H").unwrap(), "1h");
+        assert!(bybit_open_interest_interval("2H").is_err());
+    }
+
+    #[test]
+    fn error_envelope_is_surfaced() {
+        let error = bybit_result(json!({ "retCode": 10001, "retMsg": "params error" }))
+            .expect_err("non-zero retCode must fail");
+        assert!(error.to_string().contains("params error"));
+    }
+}
@@ -4,7 +4,13 @@
 //!
 //! # 架构位置
 //! 放在infrastructure层，避免core → infrastructure的循环依赖
-use super::{OkxAccountAdapter, OkxContractsAdapter, OkxMarketDataAdapter, OkxPublicDataAdapter};
+use super::{
+    BinanceAccountAdapter, BinanceContractsAdapter, BinanceMarketDataAdapter,
+    BinancePublicDataAdapter, BitgetAccountAdapter, BitgetContractsAdapter,
+    BitgetMarketDataAdapter, BitgetPublicDataAdapter, BybitAccountAdapter, BybitContractsAdapter,
+    BybitMarketDataAdapter, BybitPublicDataAdapter, OkxAccountAdapter, OkxContractsAdapter,
+    OkxMarketDataAdapter, OkxPublicDataAdapter,
+};
 use anyhow::{anyhow, Result};
 use rust_quant_domain::traits::{
     ExchangeAccount, ExchangeContracts, ExchangeMarketData, ExchangePublicData,
@@ -16,15 +22,15 @@ pub struct ExchangeFactory;
 impl ExchangeFactory {
     /// 创建市场数据客户端
     /// # Arguments
-    /// * `exchange_name` - 交易所名称（"okx", "binance", "bybit"）
+    /// * `exchange_name` - 交易所名称（"okx", "binance", "bybit", "bitget"）
     /// # Returns
     /// * 实现ExchangeMarketData接口的客户端
     pub fn create_market_data(exchange_name: &str) -> Result<Box<dyn ExchangeMarketData>> {
         match exchange_name.to_lowercase().as_str() {
             "okx" => Ok(Box::new(OkxMarketDataAdapter::new()?)),
-            // 未来添加其他交易所：
-            // "binance" => Ok(Box::new(BinanceMarketDataAdapter::new()?)),
-            // "bybit" => Ok(Box::new(BybitMarketDataAdapter::new()?)),
+            "binance" => Ok(Box::new(BinanceMarketDataAdapter::new()?)),
+            "bybit" => Ok(Box::new(BybitMarketDataAdapter::new()?)),
+            "bitget" => Ok(Box::new(BitgetMarketDataAdapter::new()?)),
             _ => Err(anyhow!("不支持的交易所: {}", exchange_name)),
         }
     }
@@ -39,7 +45,9 @@ impl ExchangeFactory {
     pub fn create_account(exchange_name: &str) -> Result<Box<dyn ExchangeAccount>> {
         match exchange_name.to_lowercase().as_str() {
             "okx" => Ok(Box::new(OkxAccountAdapter::new()?)),
-            // "binance" => Ok(Box::new(BinanceAccountAdapter::new()?)),
+            "binance" => Ok(Box::new(BinanceAccountAdapter::new()?)),
+            "bybit" => Ok(Box::new(BybitAccountAdapter::new()?)),
+            "bitget" => Ok(Box::new(BitgetAccountAdapter::new()?)),
             _ => Err(anyhow!("不支持的交易所: {}", exchange_name)),
         }
     }
@@ -52,6 +60,9 @@ impl ExchangeFactory {
     pub fn create_contracts(exchange_name: &str) -> Result<Box<dyn ExchangeContracts>> {
         match exchange_name.to_lowercase().as_str() {
             "okx" => Ok(Box::new(OkxContractsAdapter::new()?)),
+            "binance" => Ok(Box::new(BinanceContractsAdapter::new()?)),
+            "bybit" => Ok(Box::new(BybitContractsAdapter::new()?)),
+            "bitget" => Ok(Box::new(BitgetContractsAdapter::new()?)),
             _ => Err(anyhow!("不支持的交易所: {}", exchange_name)),
         }
     }
@@ -64,9 +75,17 @@ impl ExchangeFactory {
     pub fn create_public_data(exchange_name: &str) -> Result<Box<dyn ExchangePublicData>> {
         match exchange_name.to_lowercase().as_str() {
             "okx" => Ok(Box::new(OkxPublicDataAdapter::new()?)),
+            "binance" => Ok(Box::new(BinancePublicDataAdapter::new()?)),
+            "bybit" => Ok(Box::new(BybitPublicDataAdapter::new()?)),
+            "bitget" => Ok(Box::new(BitgetPublicDataAdapter::new()?)),
             _ => Err(anyhow!("不支持的交易所: {}", exchange_name)),
         }
     }
+    /// 从环境变量创建公共数据客户端
+    pub fn create_default_public_data() -> Result<Box<dyn ExchangePublicData>> {
+        let exchange = std::env::var("DEFAULT_EXCHANGE").unwrap_or_else(|_| "okx".to_string());
+        Self::create_public_data(&exchange)
+    }
     /// 创建多个交易所的市场数据客户端（用于套利）
     /// # Arguments
     /// * `exchanges` - 交易所名称列表
@@ -152,6 +171,27 @@ mod tests {
         }
     }
     #[test]
+    fn test_binance_factory_creation() {
+        // Binance 公共行情不需要密钥
+        let market_data = ExchangeFactory::create_market_data("binance")
+            .expect("binance market data adapter should not require credentials");
+        assert_eq!(market_data.name(), "binance");
+        let contracts = ExchangeFactory::create_contracts("Binance")
+            .expect("binance contracts adapter should not require credentials");
+        assert_eq!(contracts.name(), "binance");
+    }
+    #[test]
+    fn test_bybit_and_bitget_factory_creation() {
+        for exchange in ["bybit", "bitget"] {
+            let market_data = ExchangeFactory::create_market_data(exchange)
+                .expect("public market data adapter should not require credentials");
+            assert_eq!(market_data.name(), exchange);
+            let contracts = ExchangeFactory::create_contracts(exchange)
+                .expect("public contracts adapter should not require credentials");
+            assert_eq!(contracts.name(), exchange);
+        }
+    }
+    #[test]
     fn test_unsupported_exchange() {
         // 测试不支持的交易所返回错误
         let result = ExchangeFactory::create_market_data("unknown");
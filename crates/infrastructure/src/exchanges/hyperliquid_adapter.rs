@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Result};
 use hyperliquid_rust_sdk::{AssetContext, BaseUrl, FundingHistoryResponse, InfoClient, Meta};
+use rust_quant_domain::entities::{OpenInterestPoint, Ticker};
 use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HyperliquidFundingHistoryPoint {
@@ -27,6 +28,35 @@ pub struct HyperliquidAssetContextSnapshot {
     /// 价格数值。
     pub mark_price: Option<f64>,
 }
+impl HyperliquidAssetContextSnapshot {
+    /// 转为标准化持仓量数据点；Hyperliquid 持仓量以币计，按标记价格折算为美元
+    pub fn to_open_interest_point(&self, timestamp: i64) -> Option<OpenInterestPoint> {
+        Some(OpenInterestPoint {
+            symbol: self.coin.clone(),
+            timestamp,
+            open_interest: self.open_interest? * self.mark_price?,
+            volume: None,
+        })
+    }
+    /// 转为标准化Ticker；Hyperliquid 资产上下文只有标记价格，盘口与 24 小时统计留空
+    pub fn to_ticker(&self, timestamp: i64) -> Option<Ticker> {
+        Some(Ticker {
+            symbol: self.coin.clone(),
+            last: self.mark_price?,
+            last_size: None,
+            bid_price: None,
+            bid_size: None,
+            ask_price: None,
+            ask_size: None,
+            open_24h: None,
+            high_24h: None,
+            low_24h: None,
+            volume_24h: None,
+            volume_ccy_24h: None,
+            timestamp,
+        })
+    }
+}
 /// Hyperliquid 公共数据适配器
 pub struct HyperliquidPublicAdapter;
 impl HyperliquidPublicAdapter {
@@ -55,6 +85,20 @@ impl HyperliquidPublicAdapter {
         let (meta, contexts) = client.meta_and_asset_contexts().await?;
         Self::from_sdk_meta_and_asset_ctxs(&meta, &contexts, coin)
     }
+    /// 获取标准化持仓量快照，时间戳取本地请求时间
+    pub async fn fetch_open_interest_point(&self, coin: &str) -> Result<OpenInterestPoint> {
+        let snapshot = self.fetch_meta_and_asset_ctxs(coin).await?;
+        snapshot
+            .to_open_interest_point(chrono::Utc::now().timestamp_millis())
+            .ok_or_else(|| anyhow!("coin {} missing open interest or mark price", coin))
+    }
+    /// 获取标准化Ticker（标记价格），时间戳取本地请求时间
+    pub async fn fetch_ticker(&self, coin: &str) -> Result<Ticker> {
+        let snapshot = self.fetch_meta_and_asset_ctxs(coin).await?;
+        snapshot
+            .to_ticker(chrono::Utc::now().timestamp_millis())
+            .ok_or_else(|| anyhow!("coin {} missing mark price", coin))
+    }
     /// 从外部输入转换为内部模型，隔离 配置、基础设施和运行时 的字段适配细节。
     pub fn from_sdk_funding_history(
         rows: Vec<FundingHistoryResponse>,
@@ -116,3 +160,37 @@ fn parse_optional_f64_string(value: Option<&str>, field: &str) -> Result<Option<
             .map_err(|e| anyhow!("failed to parse {}: {}", field, e)),
     }
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> HyperliquidAssetContextSnapshot {
+        HyperliquidAssetContextSnapshot {
+            coin: "BTC".to_string(),
+            funding: Some(0.0001),
+            open_interest: Some(1500.0),
+            premium: None,
+            oracle_price: Some(60010.0),
+            mark_price: Some(60000.0),
+        }
+    }
+
+    #[test]
+    fn asset_context_maps_to_normalized_open_interest_and_ticker() {
+        let point = snapshot()
+            .to_open_interest_point(1_717_000_000_000)
+            .unwrap();
+        assert_eq!(point.symbol, "BTC");
+        assert_eq!(point.open_interest, 90_000_000.0);
+        assert_eq!(point.volume, None);
+
+        let ticker = snapshot().to_ticker(1_717_000_000_000).unwrap();
+        assert_eq!(ticker.last, 60000.0);
+        assert_eq!(ticker.spread(), None);
+
+        let mut missing = snapshot();
+        missing.mark_price = None;
+        assert!(missing.to_open_interest_point(0).is_none());
+        assert!(missing.to_ticker(0).is_none());
+    }
+}
@@ -1,9 +1,23 @@
 //! 交易所适配器模块
 //!
 //! 实现各个交易所的adapter，统一接口
+mod binance_adapter;
+mod bitget_adapter;
+mod bybit_adapter;
 mod factory;
 mod hyperliquid_adapter;
 mod okx_adapter;
+mod rest_support;
+pub use binance_adapter::{
+    BinanceAccountAdapter, BinanceContractsAdapter, BinanceMarketDataAdapter,
+    BinancePublicDataAdapter,
+};
+pub use bitget_adapter::{
+    BitgetAccountAdapter, BitgetContractsAdapter, BitgetMarketDataAdapter, BitgetPublicDataAdapter,
+};
+pub use bybit_adapter::{
+    BybitAccountAdapter, BybitContractsAdapter, BybitMarketDataAdapter, BybitPublicDataAdapter,
+};
 pub use factory::ExchangeFactory;
 pub use hyperliquid_adapter::{
     HyperliquidAssetContextSnapshot, HyperliquidFundingHistoryPoint, HyperliquidPublicAdapter,
@@ -11,8 +25,4 @@ pub use hyperliquid_adapter::{
 pub use okx_adapter::{
     OkxAccountAdapter, OkxContractsAdapter, OkxMarketDataAdapter, OkxPublicDataAdapter,
 };
-// 未来添加其他交易所：
-// mod binance_adapter;
-// mod bybit_adapter;
-// pub use binance_adapter::*;
-// pub use bybit_adapter::*;
+pub use rest_support::{FixtureRestTransport, HttpRestTransport, RecordedRequest, RestTransport};
@@ -1,30 +1,35 @@
 //! OKX交易所适配器
 //!
 //! 实现domain层定义的交易所接口，将OKX SDK适配为统一接口
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use okx::api::account::{OkxAccount, OkxContracts};
 use okx::api::api_trait::OkxApiTrait;
 use okx::api::asset::OkxAsset;
 use okx::api::market::OkxMarket;
+use okx::dto::market_dto::{CandleOkxRespDto, TickerOkxResDto};
+use rust_quant_domain::entities::{Candle, Ticker};
+use rust_quant_domain::enums::Timeframe;
 use rust_quant_domain::traits::{
     ExchangeAccount, ExchangeContracts, ExchangeMarketData, ExchangePublicData,
 };
+use rust_quant_domain::value_objects::{Price, Volume};
 use tracing::debug;
 const LEGACY_SIGNED_READ_ONLY_CONFIRM_ENV: &str = "LEGACY_SIGNED_READ_ONLY_CONFIRM";
 const LEGACY_SIGNED_READ_ONLY_CONFIRM_TOKEN: &str =
     "I_UNDERSTAND_LEGACY_SIGNED_READ_ONLY_ACCOUNT_READS";
 /// 封装当前函数，减少配置运行时调用方重复实现相同细节。
 /// 返回 Result 以便错误透明上抛、统一降级处理，便于后续重试和观测。
-fn ensure_legacy_signed_read_only_allowed() -> Result<()> {
+pub(super) fn ensure_legacy_signed_read_only_allowed(exchange: &str) -> Result<()> {
     let confirmation = std::env::var(LEGACY_SIGNED_READ_ONLY_CONFIRM_ENV).ok();
     if confirmation.as_deref().map(str::trim) == Some(LEGACY_SIGNED_READ_ONLY_CONFIRM_TOKEN) {
         return Ok(());
     }
     Err(anyhow::anyhow!(
-        "{}={} is required before using legacy rust_quant_infrastructure OKX account adapter signed read-only queries; prefer the quant_web execution reconciliation path with exact credential_id and target task scope",
+        "{}={} is required before using legacy rust_quant_infrastructure {} account adapter signed read-only queries; prefer the quant_web execution reconciliation path with exact credential_id and target task scope",
         LEGACY_SIGNED_READ_ONLY_CONFIRM_ENV,
-        LEGACY_SIGNED_READ_ONLY_CONFIRM_TOKEN
+        LEGACY_SIGNED_READ_ONLY_CONFIRM_TOKEN,
+        exchange
     ))
 }
 /// OKX市场数据适配器
@@ -106,6 +111,121 @@ impl ExchangeMarketData for OkxMarketDataAdapter {
             .map(|c| serde_json::to_value(c).unwrap())
             .collect())
     }
+    /// 直接从 OKX DTO 映射标准化Ticker，避免 JSON 往返
+    async fn fetch_ticker_normalized(&self, symbol: &str) -> Result<Ticker> {
+        let tickers = self.client.get_ticker(symbol).await?;
+        let ticker = tickers
+            .first()
+            .ok_or_else(|| anyhow!("OKX 未返回Ticker: {}", symbol))?;
+        okx_ticker_to_domain(ticker)
+    }
+    /// 直接从 OKX DTO 映射标准化Ticker列表
+    async fn fetch_tickers_normalized(&self, inst_type: &str) -> Result<Vec<Ticker>> {
+        let tickers = self.client.get_tickers(inst_type).await?;
+        tickers.iter().map(okx_ticker_to_domain).collect()
+    }
+    /// 直接从 OKX DTO 映射标准化历史K线
+    async fn fetch_candles_normalized(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: Option<i64>,
+        end: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Candle>> {
+        let after = start.map(|s| s.to_string());
+        let before = end.map(|e| e.to_string());
+        let limit_str = limit.map(|l| l.to_string());
+        let candles = self
+            .client
+            .get_history_candles(
+                symbol,
+                timeframe.as_str(),
+                after.as_deref(),
+                before.as_deref(),
+                limit_str.as_deref(),
+            )
+            .await?;
+        candles
+            .iter()
+            .map(|candle| okx_candle_to_domain(symbol, timeframe, candle))
+            .collect()
+    }
+    /// 直接从 OKX DTO 映射标准化最新K线
+    async fn fetch_latest_candles_normalized(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        limit: Option<usize>,
+    ) -> Result<Vec<Candle>> {
+        let limit_str = limit.map(|l| l.to_string());
+        let candles = self
+            .client
+            .get_candles(symbol, timeframe.as_str(), None, None, limit_str.as_deref())
+            .await?;
+        candles
+            .iter()
+            .map(|candle| okx_candle_to_domain(symbol, timeframe, candle))
+            .collect()
+    }
+}
+/// OKX Ticker DTO 转为领域 Ticker；空字符串字段视为缺失
+fn okx_ticker_to_domain(ticker: &TickerOkxResDto) -> Result<Ticker> {
+    let optional = |raw: &str| raw.trim().parse::<f64>().ok();
+    Ok(Ticker {
+        symbol: ticker.inst_id.clone(),
+        last: ticker
+            .last
+            .parse::<f64>()
+            .map_err(|e| anyhow!("解析 OKX last 失败: {} ({})", ticker.last, e))?,
+        last_size: optional(&ticker.last_sz),
+        bid_price: optional(&ticker.bid_px),
+        bid_size: optional(&ticker.bid_sz),
+        ask_price: optional(&ticker.ask_px),
+        ask_size: optional(&ticker.ask_sz),
+        open_24h: optional(&ticker.open24h),
+        high_24h: optional(&ticker.high24h),
+        low_24h: optional(&ticker.low24h),
+        volume_24h: optional(&ticker.vol24h),
+        volume_ccy_24h: optional(&ticker.vol_ccy24h),
+        timestamp: ticker
+            .ts
+            .parse::<i64>()
+            .map_err(|e| anyhow!("解析 OKX ts 失败: {} ({})", ticker.ts, e))?,
+    })
+}
+/// OKX K线 DTO 转为领域 Candle；成交量取 `volCcy`，与K线仓储口径一致
+fn okx_candle_to_domain(
+    symbol: &str,
+    timeframe: Timeframe,
+    candle: &CandleOkxRespDto,
+) -> Result<Candle> {
+    let number = |raw: &str, field: &str| -> Result<f64> {
+        raw.parse::<f64>()
+            .map_err(|e| anyhow!("解析 OKX K线 {} 失败: {} ({})", field, raw, e))
+    };
+    let price = |raw: &str, field: &str| -> Result<Price> {
+        Price::new(number(raw, field)?).map_err(|e| anyhow!("OKX K线 {} 无效: {}", field, e))
+    };
+    let timestamp = candle
+        .ts
+        .parse::<i64>()
+        .map_err(|e| anyhow!("解析 OKX K线 ts 失败: {} ({})", candle.ts, e))?;
+    let mut domain = Candle::new(
+        symbol.to_string(),
+        timeframe,
+        timestamp,
+        price(&candle.o, "open")?,
+        price(&candle.h, "high")?,
+        price(&candle.l, "low")?,
+        price(&candle.c, "close")?,
+        Volume::new(number(&candle.vol_ccy, "volCcy")?)
+            .map_err(|e| anyhow!("OKX K线成交量无效: {}", e))?,
+    );
+    if candle.confirm == "1" {
+        domain.confirm();
+    }
+    Ok(domain)
 }
 /// OKX账户适配器
 pub struct OkxAccountAdapter {
@@ -117,7 +237,7 @@ pub struct OkxAccountAdapter {
 impl OkxAccountAdapter {
     /// 构建 配置、基础设施和运行时 所需实例，并集中初始化依赖和默认状态。
     pub fn new() -> Result<Self> {
-        ensure_legacy_signed_read_only_allowed()?;
+        ensure_legacy_signed_read_only_allowed("OKX")?;
         Ok(Self {
             account_client: OkxAccount::from_env()?,
             asset_client: OkxAsset::from_env()?,
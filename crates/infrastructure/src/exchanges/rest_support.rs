@@ -0,0 +1,389 @@
+//! REST 交易所适配器公共组件
+//!
+//! 提供可替换的 HTTP 传输层与 JSON 字段工具。线上使用 [`HttpRestTransport`]，
+//! 契约测试使用 [`FixtureRestTransport`] 回放录制好的响应，无需访问交易所。
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 常见计价币种，用于把 `BTCUSDT` 还原为 `BTC-USDT-SWAP`
+const QUOTE_ASSETS: [&str; 4] = ["USDT", "USDC", "FDUSD", "BUSD"];
+
+/// 一次已发出的 GET 请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    /// 请求路径（不含域名）
+    pub path: String,
+    /// 查询参数，保持发送顺序
+    pub query: Vec<(String, String)>,
+}
+
+impl RecordedRequest {
+    /// 查询参数取值
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// REST 传输层
+#[async_trait]
+pub trait RestTransport: Send + Sync {
+    /// 发送 GET 请求并解析 JSON 响应
+    async fn get_json(
+        &self,
+        path: &str,
+        query: &[(String, String)],
+        headers: &[(String, String)],
+    ) -> Result<Value>;
+}
+
+/// 基于 reqwest 的传输层
+pub struct HttpRestTransport {
+    /// 复用连接池的 HTTP 客户端。
+    client: reqwest::Client,
+    /// 交易所 API 基础地址。
+    base_url: String,
+}
+
+impl HttpRestTransport {
+    /// 构建 HTTP 传输层，代理地址为空时直连
+    pub fn new(base_url: &str, proxy_url: Option<&str>) -> Result<Self> {
+        let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(15));
+        if let Some(proxy_url) = proxy_url {
+            builder = builder.proxy(reqwest::Proxy::all(proxy_url)?);
+        }
+        Ok(Self {
+            client: builder.build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl RestTransport for HttpRestTransport {
+    async fn get_json(
+        &self,
+        path: &str,
+        query: &[(String, String)],
+        headers: &[(String, String)],
+    ) -> Result<Value> {
+        let mut request = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .query(query);
+        for (name, value) in headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        Ok(request
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Value>()
+            .await?)
+    }
+}
+
+/// 回放录制响应的传输层，用于离线契约测试
+///
+/// 同一路径可登记多份响应，按登记顺序依次返回，便于验证分页。
+#[derive(Default)]
+pub struct FixtureRestTransport {
+    /// 按路径排队的响应。
+    fixtures: Mutex<HashMap<String, VecDeque<Value>>>,
+    /// 已发出的请求。
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl FixtureRestTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 为路径登记一份录制响应
+    pub fn with_fixture(self, path: &str, body: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(body)
+            .map_err(|error| anyhow!("invalid fixture for {}: {}", path, error))?;
+        self.fixtures
+            .lock()
+            .map_err(|_| anyhow!("fixture lock poisoned"))?
+            .entry(path.to_string())
+            .or_default()
+            .push_back(value);
+        Ok(self)
+    }
+
+    /// 已发出的请求，按发送顺序排列
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests
+            .lock()
+            .map(|requests| requests.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl RestTransport for FixtureRestTransport {
+    async fn get_json(
+        &self,
+        path: &str,
+        query: &[(String, String)],
+        _headers: &[(String, String)],
+    ) -> Result<Value> {
+        self.requests
+            .lock()
+            .map_err(|_| anyhow!("fixture lock poisoned"))?
+            .push(RecordedRequest {
+                path: path.to_string(),
+                query: query.to_vec(),
+            });
+        self.fixtures
+            .lock()
+            .map_err(|_| anyhow!("fixture lock poisoned"))?
+            .get_mut(path)
+            .and_then(VecDeque::pop_front)
+            .ok_or_else(|| anyhow!("no recorded fixture left for {}", path))
+    }
+}
+
+/// 读取非空环境变量
+pub(super) fn non_empty_env(key: &str) -> Option<String> {
+    std::env::var(key)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// `BTC-USDT-SWAP` / `BTC-USDT` / `btcusdt` 转为交易所合约代码 `BTCUSDT`
+pub(super) fn compact_symbol_from_inst_id(inst_id: &str) -> String {
+    let parts: Vec<&str> = inst_id
+        .split('-')
+        .map(str::trim)
+        .filter(|part| !part.is_empty() && !part.eq_ignore_ascii_case("SWAP"))
+        .collect();
+    if parts.len() >= 2 {
+        return format!("{}{}", parts[0], parts[1]).to_ascii_uppercase();
+    }
+    inst_id
+        .chars()
+        .filter(|ch| ch.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+/// 合约代码 `BTCUSDT` 还原为 `BTC-USDT-SWAP`；无法识别计价币时原样返回
+pub(super) fn swap_inst_id_from_compact_symbol(symbol: &str) -> String {
+    QUOTE_ASSETS
+        .iter()
+        .find_map(|quote| {
+            let base = symbol.strip_suffix(quote)?;
+            (!base.is_empty()).then(|| format!("{}-{}-SWAP", base, quote))
+        })
+        .unwrap_or_else(|| symbol.to_string())
+}
+
+/// 取字符串或数字字段，统一为字符串
+pub(super) fn string_field(value: &Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// 按索引取字符串或数字，统一为字符串
+pub(super) fn string_at(values: &[Value], index: usize) -> Option<String> {
+    match values.get(index)? {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// 币种过滤：未指定时保留全部
+pub(super) fn currency_selected(asset: &str, currencies: Option<&[String]>) -> bool {
+    let Some(currencies) = currencies else {
+        return true;
+    };
+    currencies
+        .iter()
+        .any(|currency| currency.eq_ignore_ascii_case(asset))
+}
+
+/// 数值字符串相乘，任一侧无法解析时返回 None
+pub(super) fn multiply_decimal_strings(left: &str, right: &str) -> Option<String> {
+    let product = left.parse::<f64>().ok()? * right.parse::<f64>().ok()?;
+    product.is_finite().then(|| product.to_string())
+}
+
+/// OKX 周期（`1m`、`4H`、`1D`、`1Dutc`、`1W`）对应的毫秒数；月线按 30 天计
+pub(super) fn timeframe_duration_ms(timeframe: &str) -> Option<i64> {
+    let value = timeframe.trim();
+    let value = value
+        .strip_suffix("utc")
+        .or_else(|| value.strip_suffix("UTC"))
+        .unwrap_or(value);
+    let unit = value.chars().last()?;
+    let count = value[..value.len() - unit.len_utf8()].parse::<i64>().ok()?;
+    let unit_ms = match unit {
+        'm' => 60_000,
+        'H' | 'h' => 60 * 60_000,
+        'D' | 'd' => 24 * 60 * 60_000,
+        'W' | 'w' => 7 * 24 * 60 * 60_000,
+        'M' => 30 * 24 * 60 * 60_000,
+        _ => return None,
+    };
+    (count > 0).then_some(count * unit_ms)
+}
+
+/// 组装 OKX 格式 K 线 `[ts, o, h, l, c, vol, volCcy, volCcyQuote, confirm]`
+///
+/// `vol` 与 `volCcy` 均为基础币成交量，`volCcyQuote` 为计价币成交额；
+/// 收盘时间不晚于 `now_ms` 时视为已确认。
+pub(super) fn okx_candle_row(
+    values: &[Value],
+    volume_index: usize,
+    turnover_index: usize,
+    duration_ms: i64,
+    now_ms: i64,
+) -> Option<Value> {
+    let ts = string_at(values, 0)?;
+    let confirm = if ts.parse::<i64>().ok()? + duration_ms <= now_ms {
+        "1"
+    } else {
+        "0"
+    };
+    let volume = string_at(values, volume_index)?;
+    Some(json!([
+        ts,
+        string_at(values, 1)?,
+        string_at(values, 2)?,
+        string_at(values, 3)?,
+        string_at(values, 4)?,
+        volume.clone(),
+        volume,
+        string_at(values, turnover_index)?,
+        confirm,
+    ]))
+}
+
+/// OKX 格式 K 线的时间戳
+pub(super) fn okx_candle_ts(candle: &Value) -> Option<i64> {
+    candle.get(0)?.as_str()?.parse::<i64>().ok()
+}
+
+/// 按 OKX 语义向更早方向翻页拉取 K 线，结果新到旧排列。
+///
+/// `fetch_page(end_inclusive, page_limit)` 返回不晚于 `end_inclusive` 的最新一页（新到旧）；
+/// 未指定 `limit` 时只拉取一页，由交易所决定默认条数。
+pub(super) async fn paginate_candles_backwards<F, Fut>(
+    limit: Option<usize>,
+    page_max: usize,
+    end_inclusive: Option<i64>,
+    mut fetch_page: F,
+) -> Result<Vec<Value>>
+where
+    F: FnMut(Option<i64>, Option<usize>) -> Fut,
+    Fut: Future<Output = Result<Vec<Value>>>,
+{
+    let Some(limit) = limit else {
+        return fetch_page(end_inclusive, None).await;
+    };
+    let mut candles: Vec<Value> = Vec::with_capacity(limit);
+    let mut cursor = end_inclusive;
+    while candles.len() < limit {
+        let page_limit = (limit - candles.len()).min(page_max.max(1));
+        let page = fetch_page(cursor, Some(page_limit)).await?;
+        let page_len = page.len();
+        let oldest_ts = page.last().and_then(okx_candle_ts);
+        // 交易所可能把游标那根 K 线重复返回，按时间戳去重
+        let newest_kept = candles.last().and_then(okx_candle_ts);
+        candles.extend(
+            page.into_iter()
+                .filter(|candle| newest_kept.is_none() || okx_candle_ts(candle) < newest_kept),
+        );
+        let Some(oldest_ts) = oldest_ts else {
+            break;
+        };
+        if page_len < page_limit {
+            break;
+        }
+        cursor = Some(oldest_ts - 1);
+    }
+    candles.truncate(limit);
+    Ok(candles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbol_mapping_round_trips_swap_inst_ids() {
+        assert_eq!(compact_symbol_from_inst_id("BTC-USDT-SWAP"), "BTCUSDT");
+        assert_eq!(compact_symbol_from_inst_id("eth-usdt"), "ETHUSDT");
+        assert_eq!(compact_symbol_from_inst_id("solusdt"), "SOLUSDT");
+        assert_eq!(swap_inst_id_from_compact_symbol("BTCUSDT"), "BTC-USDT-SWAP");
+        assert_eq!(swap_inst_id_from_compact_symbol("ETHUSDC"), "ETH-USDC-SWAP");
+        assert_eq!(swap_inst_id_from_compact_symbol("USDT"), "USDT");
+    }
+
+    #[test]
+    fn timeframe_duration_covers_okx_periods() {
+        assert_eq!(timeframe_duration_ms("5m"), Some(5 * 60_000));
+        assert_eq!(timeframe_duration_ms("4H"), Some(4 * 60 * 60_000));
+        assert_eq!(timeframe_duration_ms("1Dutc"), Some(24 * 60 * 60_000));
+        assert_eq!(timeframe_duration_ms("1W"), Some(7 * 24 * 60 * 60_000));
+        assert_eq!(timeframe_duration_ms("x"), None);
+    }
+
+    #[tokio::test]
+    async fn pagination_walks_backwards_until_limit() {
+        let candle = |ts: i64| json!([ts.to_string(), "1", "1", "1", "1", "1", "1", "1", "1"]);
+        let cursors = Mutex::new(Vec::new());
+        let candles = paginate_candles_backwards(Some(5), 2, Some(100), |end, page_limit| {
+            cursors.lock().unwrap().push((end, page_limit));
+            let end = end.unwrap();
+            let page: Vec<Value> = (0..page_limit.unwrap() as i64)
+                .map(|offset| candle(end - offset * 10))
+                .collect();
+            async move { Ok(page) }
+        })
+        .await
+        .unwrap();
+
+        let ts: Vec<i64> = candles.iter().filter_map(okx_candle_ts).collect();
+        assert_eq!(ts, vec![100, 90, 89, 79, 78]);
+        assert_eq!(
+            *cursors.lock().unwrap(),
+            vec![
+                (Some(100), Some(2)),
+                (Some(89), Some(2)),
+                (Some(78), Some(1))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fixture_transport_replays_responses_in_order() {
+        let transport = FixtureRestTransport::new()
+            .with_fixture("/page", r#"{"page": 1}"#)
+            .and_then(|transport| transport.with_fixture("/page", r#"{"page": 2}"#))
+            .expect("valid fixtures");
+        let query = vec![("cursor".to_string(), "1".to_string())];
+
+        let first = transport.get_json("/page", &query, &[]).await.unwrap();
+        let second = transport.get_json("/page", &[], &[]).await.unwrap();
+        assert_eq!(first["page"], 1);
+        assert_eq!(second["page"], 2);
+        assert!(transport.get_json("/page", &[], &[]).await.is_err());
+        assert_eq!(transport.requests()[0].param("cursor"), Some("1"));
+        assert_eq!(transport.requests().len(), 3);
+    }
+}
@@ -36,9 +36,10 @@ pub mod repositories;
 pub use exchanges::*;
 pub use external_data::*;
 pub use repositories::{
-    PostgresCandleRepository, PostgresExchangeSymbolRepository, PostgresStrategyConfigRepository,
-    SignalLogEntity, SignalLogRepository, SqlxBacktestRepository, SqlxCandleRepository,
-    SqlxStrategyConfigRepository, StrategyConfigEntity, StrategyConfigEntityModel,
+    FileCandleRepository, PostgresCandleRepository, PostgresExchangeSymbolRepository,
+    PostgresStrategyConfigRepository, SignalLogEntity, SignalLogRepository, SqlxBacktestRepository,
+    SqlxCandleRepository, SqlxStrategyConfigRepository, StrategyConfigEntity,
+    StrategyConfigEntityModel,
 };
 // 导出通用缓存接口（泛型，不依赖业务类型）
 pub use cache::{CacheProvider, InMemoryCache, RedisCache, TwoLevelCache};
@@ -68,8 +68,9 @@ impl BacktestLogRepository for SqlxBacktestRepository {
                 ten_bar_after_win_rate,
                 kline_start_time,
                 kline_end_time,
-                kline_nums
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+                kline_nums,
+                total_funding_fee
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
             RETURNING id
             "#,
         )
@@ -91,6 +92,7 @@ impl BacktestLogRepository for SqlxBacktestRepository {
         .bind(log.kline_start_time)
         .bind(log.kline_end_time)
         .bind(log.kline_nums)
+        .bind(log.total_funding_fee)
         .fetch_one(self.pool())
         .await?;
         Ok(inserted_id)
@@ -205,8 +207,18 @@ impl BacktestLogRepository for SqlxBacktestRepository {
                 annual_return = $2,
                 total_return = $3,
                 max_drawdown = $4,
-                volatility = $5
-            WHERE id = $6
+                volatility = $5,
+                sortino_ratio = $6,
+                calmar_ratio = $7,
+                profit_factor = $8,
+                expectancy_r = $9,
+                average_hold_hours = $10,
+                exposure = $11,
+                longest_drawdown_days = $12,
+                max_win_streak = $13,
+                max_loss_streak = $14,
+                tail_ratio = $15
+            WHERE id = $16
             "#,
         )
         .bind(metrics.sharpe_ratio)
@@ -214,6 +226,16 @@ impl BacktestLogRepository for SqlxBacktestRepository {
         .bind(metrics.total_return)
         .bind(metrics.max_drawdown)
         .bind(metrics.volatility)
+        .bind(metrics.sortino_ratio)
+        .bind(metrics.calmar_ratio)
+        .bind(metrics.profit_factor)
+        .bind(metrics.expectancy_r)
+        .bind(metrics.average_hold_hours)
+        .bind(metrics.exposure)
+        .bind(metrics.longest_drawdown_days)
+        .bind(metrics.max_win_streak)
+        .bind(metrics.max_loss_streak)
+        .bind(metrics.tail_ratio)
         .bind(backtest_id)
         .execute(self.pool())
         .await?;
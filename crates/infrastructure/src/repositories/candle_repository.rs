@@ -1,4 +1,5 @@
 //! K线数据访问层实现
+use super::file_candle_repository::CandleColumns;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use chrono::NaiveDateTime;
@@ -6,6 +7,7 @@ use rust_quant_domain::traits::CandleRepository;
 use rust_quant_domain::{Candle, Price, Timeframe, Volume};
 use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
 use std::collections::HashSet;
+use std::ops::Range;
 use tokio::sync::Mutex;
 use tracing::{debug, error};
 
@@ -31,8 +33,34 @@ fn build_candle_upsert_query(
             .push_bind(candle.volume.value().to_string())
             .push_bind(if candle.confirmed { "1" } else { "0" });
     });
-    query_builder.push(
-        " ON CONFLICT (ts) DO UPDATE SET
+    query_builder.push(CANDLE_UPSERT_CONFLICT_SQL);
+    query_builder
+}
+/// 列式数据版本的批量 UPSERT，`vol` 与 `vol_ccy` 分别写入。
+fn build_candle_columns_upsert_query(
+    table_name: &str,
+    columns: &CandleColumns,
+    rows: Range<usize>,
+) -> QueryBuilder<'static, Postgres> {
+    let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(format!(
+        "INSERT INTO {} AS current_candle (ts, o, h, l, c, vol, vol_ccy, confirm) ",
+        table_name
+    ));
+    query_builder.push_values(rows, |mut row, index| {
+        row.push_bind(columns.ts[index])
+            .push_bind(columns.open[index].to_string())
+            .push_bind(columns.high[index].to_string())
+            .push_bind(columns.low[index].to_string())
+            .push_bind(columns.close[index].to_string())
+            .push_bind(columns.volume[index].to_string())
+            .push_bind(columns.volume_ccy[index].to_string())
+            .push_bind(if columns.confirmed[index] { "1" } else { "0" });
+    });
+    query_builder.push(CANDLE_UPSERT_CONFLICT_SQL);
+    query_builder
+}
+/// 冲突时只更新内容变化的行，避免无意义 WAL。
+const CANDLE_UPSERT_CONFLICT_SQL: &str = " ON CONFLICT (ts) DO UPDATE SET
             o = EXCLUDED.o,
             h = EXCLUDED.h,
             l = EXCLUDED.l,
@@ -46,10 +74,7 @@ fn build_candle_upsert_query(
                  current_candle.confirm)
                 IS DISTINCT FROM
                 (EXCLUDED.o, EXCLUDED.h, EXCLUDED.l, EXCLUDED.c,
-                 EXCLUDED.vol, EXCLUDED.vol_ccy, EXCLUDED.confirm)",
-    );
-    query_builder
-}
+                 EXCLUDED.vol, EXCLUDED.vol_ccy, EXCLUDED.confirm)";
 /// K线数据库实体
 #[derive(Debug, Clone, FromRow)]
 #[allow(dead_code)]
@@ -81,6 +106,24 @@ struct CandlesEntity {
     pub updated_at: Option<NaiveDateTime>,
 }
 impl CandlesEntity {
+    /// 解析 o/h/l/c/vol/vol_ccy 六列原始字符串。
+    fn to_column_values(&self) -> Result<[f64; 6]> {
+        let mut values = [0.0; 6];
+        for (slot, (name, raw)) in values.iter_mut().zip([
+            ("o", &self.o),
+            ("h", &self.h),
+            ("l", &self.l),
+            ("c", &self.c),
+            ("vol", &self.vol),
+            ("vol_ccy", &self.vol_ccy),
+        ]) {
+            *slot = raw
+                .trim()
+                .parse::<f64>()
+                .map_err(|e| anyhow!("解析 K线字段 {} 失败 ts={}: {}", name, self.ts, e))?;
+        }
+        Ok(values)
+    }
     /// 转换为领域实体
     /// 封装当前函数，减少行情数据调用方重复实现相同细节。
     /// 返回 Result 以便错误透明上抛、统一降级处理，便于后续重试和观测。
@@ -227,7 +270,7 @@ impl PostgresCandleRepository {
         Ok(())
     }
     /// 加载 行情与市场数据 运行所需数据，并把缺失或异常交给调用方处理。
-    fn get_table_name(symbol: &str, timeframe: Timeframe) -> String {
+    pub(crate) fn get_table_name(symbol: &str, timeframe: Timeframe) -> String {
         format!(
             "{}_candles_{}",
             symbol.to_ascii_lowercase(),
@@ -246,6 +289,75 @@ impl PostgresCandleRepository {
             Err(anyhow!("非法 K线分表名: {}", table_name))
         }
     }
+    /// 按 `ts` 升序分页读取原始列数据，供导出到列式 K线文件。
+    ///
+    /// 以 `after_ts` 作为游标（不含），`end_time` 为闭区间上界；保留 `vol` 与 `vol_ccy`
+    /// 原值，不经过领域模型，避免导出时把两列成交量合并。
+    pub async fn fetch_candle_columns(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        after_ts: Option<i64>,
+        end_time: Option<i64>,
+        page_size: usize,
+    ) -> Result<CandleColumns> {
+        self.ensure_table(symbol, timeframe).await?;
+        let table_name = Self::quoted_table_name(symbol, timeframe)?;
+        let query = format!(
+            "SELECT id, ts, o, h, l, c, vol, vol_ccy, confirm, created_at, updated_at
+             FROM {}
+             WHERE ts > $1 AND ts <= $2
+             ORDER BY ts ASC
+             LIMIT $3",
+            table_name
+        );
+        let entities = sqlx::query_as::<_, CandlesEntity>(&query)
+            .bind(after_ts.unwrap_or(i64::MIN))
+            .bind(end_time.unwrap_or(i64::MAX))
+            .bind(page_size as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("分页读取 Postgres K线数据失败: {}", e);
+                anyhow!("分页读取 Postgres K线数据失败: {}", e)
+            })?;
+        let mut columns = CandleColumns::with_capacity(entities.len());
+        for entity in entities {
+            columns.push(entity.ts, entity.to_column_values()?, entity.confirm == "1")?;
+        }
+        Ok(columns)
+    }
+    /// 把列式 K线数据批量 UPSERT 回分表，返回实际变更行数。
+    pub async fn save_candle_columns(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        columns: &CandleColumns,
+    ) -> Result<usize> {
+        if columns.is_empty() {
+            return Ok(0);
+        }
+        self.ensure_table(symbol, timeframe).await?;
+        let table_name = Self::quoted_table_name(symbol, timeframe)?;
+        let mut saved_count = 0;
+        let mut start = 0;
+        while start < columns.len() {
+            let end = (start + CANDLE_UPSERT_BATCH_SIZE).min(columns.len());
+            let mut query_builder =
+                build_candle_columns_upsert_query(&table_name, columns, start..end);
+            let result = query_builder
+                .build()
+                .execute(&self.pool)
+                .await
+                .map_err(|e| {
+                    error!("导入 Postgres K线数据失败: {}", e);
+                    anyhow!("导入 Postgres K线数据失败: {}", e)
+                })?;
+            saved_count += result.rows_affected() as usize;
+            start = end;
+        }
+        Ok(saved_count)
+    }
 }
 #[async_trait]
 impl CandleRepository for PostgresCandleRepository {
@@ -0,0 +1,660 @@
+//! 文件 K线仓储实现
+//!
+//! 研究回测和 CI 不一定能连上 quant_core Postgres，这里把每个 `{inst_id}_candles_{period}`
+//! 分表落成一个列式二进制文件（`.rqc`），整文件一次顺序读取后按列解码，
+//! 避免逐行 SQL 和字符串价格解析，百万级 1m K 线也能秒级载入。
+//!
+//! 文件布局（全部小端序）：
+//! - `RQCANDLE` 魔数 + `u16` 版本号
+//! - `u16` 长度前缀的 symbol、timeframe 字符串
+//! - `u64` 行数 `n`
+//! - 列数据：`ts: i64 × n`，`o/h/l/c/vol/vol_ccy: f64 × n`，`confirm: u8 × n`
+//!
+//! 行按 `ts` 升序且唯一，查询时直接二分定位时间范围。
+use super::candle_repository::PostgresCandleRepository;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use rust_quant_domain::traits::CandleRepository;
+use rust_quant_domain::{Candle, Price, Timeframe, Volume};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tokio::sync::Mutex;
+use tracing::{debug, error};
+
+/// 列式 K线文件扩展名。
+pub const CANDLE_FILE_EXTENSION: &str = "rqc";
+const CANDLE_FILE_MAGIC: &[u8; 8] = b"RQCANDLE";
+const CANDLE_FILE_VERSION: u16 = 1;
+/// 每行字节数：ts 与六个价格/成交量列各 8 字节，confirm 1 字节。
+const CANDLE_FILE_ROW_BYTES: usize = 8 * 7 + 1;
+/// 与 Postgres 仓储一致，未指定 limit 时最多返回 1000 根。
+const DEFAULT_FIND_LIMIT: usize = 1000;
+
+/// 按列存放的一段 K线序列，`ts` 升序且唯一。
+///
+/// 同时保留 `vol` 与 `vol_ccy` 两列，导入回 Postgres 时不丢失分表原有口径。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CandleColumns {
+    pub ts: Vec<i64>,
+    pub open: Vec<f64>,
+    pub high: Vec<f64>,
+    pub low: Vec<f64>,
+    pub close: Vec<f64>,
+    pub volume: Vec<f64>,
+    pub volume_ccy: Vec<f64>,
+    pub confirmed: Vec<bool>,
+}
+impl CandleColumns {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ts: Vec::with_capacity(capacity),
+            open: Vec::with_capacity(capacity),
+            high: Vec::with_capacity(capacity),
+            low: Vec::with_capacity(capacity),
+            close: Vec::with_capacity(capacity),
+            volume: Vec::with_capacity(capacity),
+            volume_ccy: Vec::with_capacity(capacity),
+            confirmed: Vec::with_capacity(capacity),
+        }
+    }
+    /// 由领域 K线构建列数据；领域模型只有一个成交量字段，`vol` 与 `vol_ccy` 同值写入，
+    /// 与 `PostgresCandleRepository::save_candles` 的写入口径一致。
+    pub fn from_candles(candles: &[Candle]) -> Self {
+        let mut sorted: Vec<&Candle> = candles.iter().collect();
+        sorted.sort_by_key(|candle| candle.timestamp);
+        let mut columns = Self::with_capacity(sorted.len());
+        for candle in sorted {
+            let volume = candle.volume.value();
+            columns.upsert_last(
+                candle.timestamp,
+                [
+                    candle.open.value(),
+                    candle.high.value(),
+                    candle.low.value(),
+                    candle.close.value(),
+                    volume,
+                    volume,
+                ],
+                candle.confirmed,
+            );
+        }
+        columns
+    }
+    pub fn len(&self) -> usize {
+        self.ts.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.ts.is_empty()
+    }
+    /// 追加一行；调用方需保证 `ts` 不小于最后一行，重复 `ts` 以新值覆盖。
+    pub fn push(&mut self, ts: i64, ohlcv: [f64; 6], confirmed: bool) -> Result<()> {
+        if let Some(last) = self.ts.last() {
+            if ts < *last {
+                return Err(anyhow!("K线列数据必须按 ts 升序追加: {} < {}", ts, last));
+            }
+        }
+        self.upsert_last(ts, ohlcv, confirmed);
+        Ok(())
+    }
+    fn upsert_last(&mut self, ts: i64, ohlcv: [f64; 6], confirmed: bool) {
+        if self.ts.last() == Some(&ts) {
+            let index = self.ts.len() - 1;
+            self.open[index] = ohlcv[0];
+            self.high[index] = ohlcv[1];
+            self.low[index] = ohlcv[2];
+            self.close[index] = ohlcv[3];
+            self.volume[index] = ohlcv[4];
+            self.volume_ccy[index] = ohlcv[5];
+            self.confirmed[index] = confirmed;
+            return;
+        }
+        self.ts.push(ts);
+        self.open.push(ohlcv[0]);
+        self.high.push(ohlcv[1]);
+        self.low.push(ohlcv[2]);
+        self.close.push(ohlcv[3]);
+        self.volume.push(ohlcv[4]);
+        self.volume_ccy.push(ohlcv[5]);
+        self.confirmed.push(confirmed);
+    }
+    fn row(&self, index: usize) -> (i64, [f64; 6], bool) {
+        (
+            self.ts[index],
+            [
+                self.open[index],
+                self.high[index],
+                self.low[index],
+                self.close[index],
+                self.volume[index],
+                self.volume_ccy[index],
+            ],
+            self.confirmed[index],
+        )
+    }
+    /// 追加一段 `ts` 更大的列数据，用于分页读取后拼接。
+    pub fn append(&mut self, other: &CandleColumns) -> Result<()> {
+        for index in 0..other.len() {
+            let (ts, ohlcv, confirmed) = other.row(index);
+            self.push(ts, ohlcv, confirmed)?;
+        }
+        Ok(())
+    }
+    /// 复制指定行区间。
+    pub fn slice(&self, rows: Range<usize>) -> CandleColumns {
+        CandleColumns {
+            ts: self.ts[rows.clone()].to_vec(),
+            open: self.open[rows.clone()].to_vec(),
+            high: self.high[rows.clone()].to_vec(),
+            low: self.low[rows.clone()].to_vec(),
+            close: self.close[rows.clone()].to_vec(),
+            volume: self.volume[rows.clone()].to_vec(),
+            volume_ccy: self.volume_ccy[rows.clone()].to_vec(),
+            confirmed: self.confirmed[rows].to_vec(),
+        }
+    }
+    /// 返回 `start_time..=end_time` 对应的行下标区间。
+    pub fn range_indices(&self, start_time: i64, end_time: i64) -> Range<usize> {
+        let start = self.ts.partition_point(|ts| *ts < start_time);
+        let end = self.ts.partition_point(|ts| *ts <= end_time).max(start);
+        start..end
+    }
+    /// 合并另一段列数据，相同 `ts` 以 `incoming` 为准；返回新增或内容变化的行数。
+    pub fn merge(&self, incoming: &CandleColumns) -> (CandleColumns, usize) {
+        let mut merged = Self::with_capacity(self.len() + incoming.len());
+        let mut changed = 0usize;
+        let (mut left, mut right) = (0usize, 0usize);
+        while left < self.len() || right < incoming.len() {
+            let take_left = right >= incoming.len()
+                || (left < self.len() && self.ts[left] < incoming.ts[right]);
+            if take_left {
+                let (ts, ohlcv, confirmed) = self.row(left);
+                merged.upsert_last(ts, ohlcv, confirmed);
+                left += 1;
+                continue;
+            }
+            let (ts, ohlcv, confirmed) = incoming.row(right);
+            if left < self.len() && self.ts[left] == ts {
+                if self.row(left) != (ts, ohlcv, confirmed) {
+                    changed += 1;
+                }
+                left += 1;
+            } else {
+                changed += 1;
+            }
+            merged.upsert_last(ts, ohlcv, confirmed);
+            right += 1;
+        }
+        (merged, changed)
+    }
+    /// 把单行转换为领域实体；价格非法时返回错误，由调用方决定跳过还是中断。
+    pub fn to_candle(&self, index: usize, symbol: &str, timeframe: Timeframe) -> Result<Candle> {
+        let price = |value: f64, name: &str| {
+            Price::new(value)
+                .map_err(|e| anyhow!("解析{}失败 ts={}: {:?}", name, self.ts[index], e))
+        };
+        let volume = Volume::new(self.volume_ccy[index])
+            .map_err(|e| anyhow!("解析成交量失败 ts={}: {:?}", self.ts[index], e))?;
+        let mut candle = Candle::new(
+            symbol.to_string(),
+            timeframe,
+            self.ts[index],
+            price(self.open[index], "开盘价")?,
+            price(self.high[index], "最高价")?,
+            price(self.low[index], "最低价")?,
+            price(self.close[index], "收盘价")?,
+            volume,
+        );
+        if self.confirmed[index] {
+            candle.confirm();
+        }
+        Ok(candle)
+    }
+}
+/// 列式 K线文件头中记录的序列标识。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandleSeriesKey {
+    pub symbol: String,
+    pub timeframe: Timeframe,
+}
+/// 编码为 `.rqc` 文件内容。
+pub fn encode_candle_file(symbol: &str, timeframe: Timeframe, columns: &CandleColumns) -> Vec<u8> {
+    let timeframe = timeframe.as_str();
+    let mut bytes = Vec::with_capacity(
+        CANDLE_FILE_MAGIC.len()
+            + 2
+            + 2
+            + symbol.len()
+            + 2
+            + timeframe.len()
+            + 8
+            + columns.len() * CANDLE_FILE_ROW_BYTES,
+    );
+    bytes.extend_from_slice(CANDLE_FILE_MAGIC);
+    bytes.extend_from_slice(&CANDLE_FILE_VERSION.to_le_bytes());
+    for text in [symbol, timeframe] {
+        bytes.extend_from_slice(&(text.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(text.as_bytes());
+    }
+    bytes.extend_from_slice(&(columns.len() as u64).to_le_bytes());
+    for ts in &columns.ts {
+        bytes.extend_from_slice(&ts.to_le_bytes());
+    }
+    for column in [
+        &columns.open,
+        &columns.high,
+        &columns.low,
+        &columns.close,
+        &columns.volume,
+        &columns.volume_ccy,
+    ] {
+        for value in column {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    bytes.extend(
+        columns
+            .confirmed
+            .iter()
+            .map(|confirmed| u8::from(*confirmed)),
+    );
+    bytes
+}
+/// 解码 `.rqc` 文件内容，校验魔数、版本、长度和 `ts` 单调性。
+pub fn decode_candle_file(bytes: &[u8]) -> Result<(CandleSeriesKey, CandleColumns)> {
+    let mut reader = ByteReader { bytes, offset: 0 };
+    if reader.take(CANDLE_FILE_MAGIC.len())? != CANDLE_FILE_MAGIC {
+        return Err(anyhow!("不是列式 K线文件: 魔数不匹配"));
+    }
+    let version = u16::from_le_bytes(reader.array()?);
+    if version != CANDLE_FILE_VERSION {
+        return Err(anyhow!("不支持的列式 K线文件版本: {}", version));
+    }
+    let symbol = reader.string()?;
+    let timeframe = Timeframe::from_str(&reader.string()?)
+        .map_err(|error| anyhow!("列式 K线文件周期无效: {}", error))?;
+    let rows = usize::try_from(u64::from_le_bytes(reader.array()?))
+        .map_err(|_| anyhow!("列式 K线文件行数溢出"))?;
+    let expected = rows
+        .checked_mul(CANDLE_FILE_ROW_BYTES)
+        .ok_or_else(|| anyhow!("列式 K线文件行数溢出"))?;
+    if reader.remaining() != expected {
+        return Err(anyhow!(
+            "列式 K线文件长度不匹配: rows={} 期望 {} 字节, 实际 {} 字节",
+            rows,
+            expected,
+            reader.remaining()
+        ));
+    }
+    let ts: Vec<i64> = reader
+        .take(rows * 8)?
+        .chunks_exact(8)
+        .map(|chunk| i64::from_le_bytes(chunk.try_into().expect("chunk size is 8")))
+        .collect();
+    if ts.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(anyhow!("列式 K线文件 ts 未严格升序"));
+    }
+    let mut f64_column = || -> Result<Vec<f64>> {
+        Ok(reader
+            .take(rows * 8)?
+            .chunks_exact(8)
+            .map(|chunk| f64::from_le_bytes(chunk.try_into().expect("chunk size is 8")))
+            .collect())
+    };
+    let open = f64_column()?;
+    let high = f64_column()?;
+    let low = f64_column()?;
+    let close = f64_column()?;
+    let volume = f64_column()?;
+    let volume_ccy = f64_column()?;
+    let confirmed = reader.take(rows)?.iter().map(|value| *value == 1).collect();
+    Ok((
+        CandleSeriesKey { symbol, timeframe },
+        CandleColumns {
+            ts,
+            open,
+            high,
+            low,
+            close,
+            volume,
+            volume_ccy,
+            confirmed,
+        },
+    ))
+}
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+impl<'a> ByteReader<'a> {
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.remaining() < len {
+            return Err(anyhow!("列式 K线文件被截断"));
+        }
+        let slice = &self.bytes[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(slice)
+    }
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("slice length matches"))
+    }
+    fn string(&mut self) -> Result<String> {
+        let len = u16::from_le_bytes(self.array()?) as usize;
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|error| anyhow!("列式 K线文件头不是合法 UTF-8: {}", error))
+    }
+}
+/// 基于本地目录的 K线仓储实现。
+///
+/// 目录下每个 `{inst_id}_candles_{period}.rqc` 对应一张 Postgres K 线分表，
+/// 文件名沿用 `PostgresCandleRepository` 的分表命名规则，便于两边互相导入导出。
+pub struct FileCandleRepository {
+    /// 数据目录。
+    root: PathBuf,
+    /// 串行化写入，避免同一进程并发合并覆盖彼此的结果。
+    write_lock: Mutex<()>,
+}
+impl FileCandleRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+    /// 返回序列对应的文件路径；分表名校验同时防止路径穿越。
+    pub fn file_path(&self, symbol: &str, timeframe: Timeframe) -> Result<PathBuf> {
+        PostgresCandleRepository::quoted_table_name(symbol, timeframe)?;
+        Ok(self.root.join(format!(
+            "{}.{}",
+            PostgresCandleRepository::get_table_name(symbol, timeframe),
+            CANDLE_FILE_EXTENSION
+        )))
+    }
+    /// 读取整条序列；文件不存在时返回 `None`。
+    pub async fn read_columns(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+    ) -> Result<Option<CandleColumns>> {
+        let path = self.file_path(symbol, timeframe)?;
+        let loaded = tokio::task::spawn_blocking(move || read_candle_file(&path))
+            .await
+            .map_err(|e| anyhow!("读取列式 K线文件任务失败: {}", e))??;
+        Ok(loaded.map(|(_, columns)| columns))
+    }
+    /// 合并写入一段列数据，返回新增或内容变化的行数。
+    ///
+    /// 先写临时文件再 rename，中途失败不会留下半个文件。
+    pub async fn write_columns(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        columns: CandleColumns,
+    ) -> Result<usize> {
+        if columns.is_empty() {
+            return Ok(0);
+        }
+        let path = self.file_path(symbol, timeframe)?;
+        let symbol = symbol.to_string();
+        let _guard = self.write_lock.lock().await;
+        tokio::task::spawn_blocking(move || -> Result<usize> {
+            let (merged, changed) = match read_candle_file(&path)? {
+                Some((_, existing)) => existing.merge(&columns),
+                None => CandleColumns::default().merge(&columns),
+            };
+            if changed == 0 {
+                return Ok(0);
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("创建 K线数据目录失败: {}", parent.display()))?;
+            }
+            let tmp_path = path.with_extension(format!("{}.tmp", CANDLE_FILE_EXTENSION));
+            std::fs::write(&tmp_path, encode_candle_file(&symbol, timeframe, &merged))
+                .with_context(|| format!("写入列式 K线文件失败: {}", tmp_path.display()))?;
+            std::fs::rename(&tmp_path, &path)
+                .with_context(|| format!("替换列式 K线文件失败: {}", path.display()))?;
+            Ok(changed)
+        })
+        .await
+        .map_err(|e| anyhow!("写入列式 K线文件任务失败: {}", e))?
+    }
+    /// 列出目录中的全部序列，按 symbol、周期排序。
+    pub async fn list_series(&self) -> Result<Vec<CandleSeriesKey>> {
+        let root = self.root.clone();
+        tokio::task::spawn_blocking(move || -> Result<Vec<CandleSeriesKey>> {
+            let entries = match std::fs::read_dir(&root) {
+                Ok(entries) => entries,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+                Err(error) => {
+                    return Err(anyhow!(
+                        "读取 K线数据目录失败 {}: {}",
+                        root.display(),
+                        error
+                    ))
+                }
+            };
+            let mut series = Vec::new();
+            for entry in entries {
+                let path = entry?.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some(CANDLE_FILE_EXTENSION) {
+                    continue;
+                }
+                if let Some((key, _)) = read_candle_file(&path)? {
+                    series.push(key);
+                }
+            }
+            series.sort_by(|a, b| {
+                (a.symbol.as_str(), a.timeframe.to_minutes())
+                    .cmp(&(b.symbol.as_str(), b.timeframe.to_minutes()))
+            });
+            Ok(series)
+        })
+        .await
+        .map_err(|e| anyhow!("扫描 K线数据目录任务失败: {}", e))?
+    }
+}
+/// 读取并解码单个文件；文件不存在时返回 `None`。
+fn read_candle_file(path: &Path) -> Result<Option<(CandleSeriesKey, CandleColumns)>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(anyhow!(
+                "读取列式 K线文件失败 {}: {}",
+                path.display(),
+                error
+            ))
+        }
+    };
+    decode_candle_file(&bytes)
+        .map(Some)
+        .with_context(|| format!("解析列式 K线文件失败: {}", path.display()))
+}
+#[async_trait]
+impl CandleRepository for FileCandleRepository {
+    /// 二分定位时间范围后按升序返回，非法行记录日志并跳过，与 Postgres 仓储一致。
+    async fn find_candles(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start_time: i64,
+        end_time: i64,
+        limit: Option<usize>,
+    ) -> Result<Vec<Candle>> {
+        let Some(columns) = self.read_columns(symbol, timeframe).await? else {
+            return Ok(vec![]);
+        };
+        let limit = limit.unwrap_or(DEFAULT_FIND_LIMIT);
+        let candles = columns
+            .range_indices(start_time, end_time)
+            .take(limit)
+            .filter_map(|index| match columns.to_candle(index, symbol, timeframe) {
+                Ok(candle) => Some(candle),
+                Err(e) => {
+                    error!("转换文件 K线失败: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Ok(candles)
+    }
+    async fn get_latest_candle(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+    ) -> Result<Option<Candle>> {
+        let Some(columns) = self.read_columns(symbol, timeframe).await? else {
+            return Ok(None);
+        };
+        match columns.len().checked_sub(1) {
+            Some(index) => Ok(Some(columns.to_candle(index, symbol, timeframe)?)),
+            None => Ok(None),
+        }
+    }
+    /// 按 symbol/周期分组合并写入，返回新增或内容变化的行数。
+    async fn save_candles(&self, candles: Vec<Candle>) -> Result<usize> {
+        let mut groups: HashMap<(String, Timeframe), Vec<Candle>> = HashMap::new();
+        for candle in candles {
+            groups
+                .entry((candle.symbol.clone(), candle.timeframe))
+                .or_default()
+                .push(candle);
+        }
+        let mut saved_count = 0;
+        for ((symbol, timeframe), group) in groups {
+            saved_count += self
+                .write_columns(&symbol, timeframe, CandleColumns::from_candles(&group))
+                .await?;
+        }
+        debug!("批量保存文件 K线数据，实际变更行数: {}", saved_count);
+        Ok(saved_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(timestamp: i64, close: f64) -> Candle {
+        let mut candle = Candle::new(
+            "BTC-USDT-SWAP".to_string(),
+            Timeframe::H1,
+            timestamp,
+            Price::new(100.0).unwrap(),
+            Price::new(close.max(100.0) + 1.0).unwrap(),
+            Price::new(close.min(100.0) - 1.0).unwrap(),
+            Price::new(close).unwrap(),
+            Volume::new(42.0).unwrap(),
+        );
+        candle.confirm();
+        candle
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rust_quant_file_candles_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn candle_file_round_trips_and_rejects_corruption() {
+        let mut columns = CandleColumns::default();
+        columns
+            .push(1_000, [1.0, 2.0, 0.5, 1.5, 10.0, 15.0], true)
+            .unwrap();
+        columns
+            .push(2_000, [1.5, 2.5, 1.0, 2.0, 11.0, 22.0], false)
+            .unwrap();
+        assert!(columns.push(1_500, [1.0; 6], true).is_err());
+
+        let bytes = encode_candle_file("BTC-USDT-SWAP", Timeframe::H4, &columns);
+        let (key, decoded) = decode_candle_file(&bytes).unwrap();
+        assert_eq!(key.symbol, "BTC-USDT-SWAP");
+        assert_eq!(key.timeframe, Timeframe::H4);
+        assert_eq!(decoded, columns);
+
+        assert!(decode_candle_file(&bytes[..bytes.len() - 1]).is_err());
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(decode_candle_file(&bad_magic).is_err());
+    }
+
+    #[test]
+    fn merge_overrides_same_ts_and_counts_changes() {
+        let existing = CandleColumns::from_candles(&[candle(1, 100.0), candle(2, 101.0)]);
+        let incoming =
+            CandleColumns::from_candles(&[candle(3, 103.0), candle(2, 102.0), candle(1, 100.0)]);
+        let (merged, changed) = existing.merge(&incoming);
+
+        assert_eq!(merged.ts, vec![1, 2, 3]);
+        assert_eq!(merged.close, vec![100.0, 102.0, 103.0]);
+        assert_eq!(changed, 2);
+        assert_eq!(merged.range_indices(2, 10), 1..3);
+        assert_eq!(merged.range_indices(4, 10), 3..3);
+    }
+
+    #[tokio::test]
+    async fn file_repository_implements_candle_repository() {
+        let dir = temp_dir("repository");
+        let repo = FileCandleRepository::new(&dir);
+        let candles: Vec<Candle> = (0..5)
+            .map(|i| candle(i * 3_600_000, 100.0 + i as f64))
+            .collect();
+
+        assert_eq!(repo.save_candles(candles.clone()).await.unwrap(), 5);
+        assert_eq!(repo.save_candles(candles).await.unwrap(), 0);
+        assert!(dir.join("btc-usdt-swap_candles_1h.rqc").exists());
+
+        let found = repo
+            .find_candles(
+                "BTC-USDT-SWAP",
+                Timeframe::H1,
+                3_600_000,
+                10_800_000,
+                Some(2),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            found.iter().map(|c| c.timestamp).collect::<Vec<_>>(),
+            vec![3_600_000, 7_200_000]
+        );
+        assert!(found.iter().all(|c| c.confirmed));
+
+        let latest = repo
+            .get_latest_candle("BTC-USDT-SWAP", Timeframe::H1)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.timestamp, 4 * 3_600_000);
+        assert_eq!(latest.close.value(), 104.0);
+        assert!(repo
+            .get_latest_candle("ETH-USDT-SWAP", Timeframe::H1)
+            .await
+            .unwrap()
+            .is_none());
+
+        let series = repo.list_series().await.unwrap();
+        assert_eq!(
+            series,
+            vec![CandleSeriesKey {
+                symbol: "BTC-USDT-SWAP".to_string(),
+                timeframe: Timeframe::H1,
+            }]
+        );
+        assert!(repo.file_path("../etc", Timeframe::H1).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -8,6 +8,7 @@ pub mod economic_event_repository;
 pub mod exchange_api_config_repository;
 pub mod exchange_symbol_repository;
 pub mod external_market_snapshot_repository;
+pub mod file_candle_repository;
 pub mod fund_monitoring_repository;
 pub mod funding_rate_repository;
 pub mod position_repository;
@@ -29,6 +30,7 @@ pub use exchange_symbol_repository::PostgresExchangeSymbolRepository;
 pub use external_market_snapshot_repository::{
     ShardedExternalMarketSnapshotRepository, SqlxExternalMarketSnapshotRepository,
 };
+pub use file_candle_repository::{CandleColumns, CandleSeriesKey, FileCandleRepository};
 pub use funding_rate_repository::SqlxFundingRateRepository;
 pub use signal_log_repository::{SignalLogEntity, SignalLogRepository};
 pub use strategy_config_postgres_repository::PostgresStrategyConfigRepository;
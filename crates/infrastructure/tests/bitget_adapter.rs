@@ -0,0 +1,139 @@
+//! Bitget 适配器契约测试：回放 tests/fixtures/bitget 下录制的 v2 响应，离线验证字段映射与分页。
+use rust_quant_domain::traits::{ExchangeAccount, ExchangeContracts, ExchangeMarketData};
+use rust_quant_infrastructure::exchanges::{
+    BitgetAccountAdapter, BitgetContractsAdapter, BitgetMarketDataAdapter, FixtureRestTransport,
+};
+use std::sync::Arc;
+const TICKERS: &str = include_str!("fixtures/bitget/tickers_usdt_futures.json");
+const CANDLES_PAGE1: &str = include_str!("fixtures/bitget/candles_1h_page1.json");
+const CANDLES_PAGE2: &str = include_str!("fixtures/bitget/candles_1h_page2.json");
+const CANDLES_LATEST_1D: &str = include_str!("fixtures/bitget/candles_1d_latest.json");
+const CANDLES_ERROR: &str = include_str!("fixtures/bitget/candles_error.json");
+const MIX_ACCOUNTS: &str = include_str!("fixtures/bitget/mix_accounts.json");
+const SPOT_ASSETS: &str = include_str!("fixtures/bitget/spot_assets.json");
+const OPEN_INTEREST: &str = include_str!("fixtures/bitget/open_interest.json");
+fn transport(fixtures: &[(&str, &str)]) -> Arc<FixtureRestTransport> {
+    let transport = fixtures
+        .iter()
+        .try_fold(FixtureRestTransport::new(), |transport, (path, body)| {
+            transport.with_fixture(path, body)
+        })
+        .expect("fixtures should be valid json");
+    Arc::new(transport)
+}
+#[tokio::test]
+async fn tickers_are_mapped_to_okx_ticker_fields() {
+    let transport = transport(&[("/api/v2/mix/market/tickers", TICKERS)]);
+    let adapter = BitgetMarketDataAdapter::with_transport(transport.clone());
+    let tickers = adapter.fetch_tickers("SWAP").await.expect("tickers");
+    assert_eq!(tickers.len(), 2);
+    let btc = &tickers[0];
+    assert_eq!(btc["instId"], "BTC-USDT-SWAP");
+    assert_eq!(btc["last"], "67318.5");
+    assert_eq!(btc["askPx"], "67318.6");
+    assert_eq!(btc["bidSz"], "3.2150");
+    assert_eq!(btc["open24h"], "66211.0");
+    assert_eq!(btc["sodUtc0"], "66980.2");
+    assert_eq!(btc["vol24h"], "70211.3321");
+    assert_eq!(btc["volCcy24h"], "70211.3321");
+    assert_eq!(btc["ts"], "1718160000110");
+    assert_eq!(
+        transport.requests()[0].param("productType"),
+        Some("USDT-FUTURES")
+    );
+}
+#[tokio::test]
+async fn candles_paginate_backwards_and_return_newest_first() {
+    let transport = transport(&[
+        ("/api/v2/mix/market/candles", CANDLES_PAGE1),
+        ("/api/v2/mix/market/candles", CANDLES_PAGE2),
+    ]);
+    let adapter =
+        BitgetMarketDataAdapter::with_transport(transport.clone()).with_kline_page_limit(2);
+    let candles = adapter
+        .fetch_candles(
+            "BTC-USDT-SWAP",
+            "1h",
+            Some(1_718_157_600_000),
+            None,
+            Some(3),
+        )
+        .await
+        .expect("candles");
+    let ts: Vec<&str> = candles
+        .iter()
+        .map(|candle| candle[0].as_str().unwrap())
+        .collect();
+    assert_eq!(ts, vec!["1718154000000", "1718150400000", "1718146800000"]);
+    assert_eq!(candles[0][4], "67318.5");
+    assert_eq!(candles[0][7], "101002331.1100");
+    assert_eq!(candles[0][8], "1");
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].param("granularity"), Some("1H"));
+    assert_eq!(requests[0].param("endTime"), Some("1718157599999"));
+    assert_eq!(requests[0].param("limit"), Some("2"));
+    assert_eq!(requests[1].param("endTime"), Some("1718150399999"));
+    assert_eq!(requests[1].param("limit"), Some("1"));
+}
+#[tokio::test]
+async fn api_errors_are_surfaced() {
+    let transport = transport(&[("/api/v2/mix/market/candles", CANDLES_ERROR)]);
+    let adapter = BitgetMarketDataAdapter::with_transport(transport);
+    let error = adapter
+        .fetch_latest_candles("BTC-USDT-SWAP", "1H", Some(10))
+        .await
+        .expect_err("non-success code must fail");
+    assert!(error.to_string().contains("granularity does not exist"));
+}
+#[tokio::test]
+async fn balances_are_mapped_to_okx_account_and_asset_shapes() {
+    let transport = transport(&[
+        ("/api/v2/mix/account/accounts", MIX_ACCOUNTS),
+        ("/api/v2/spot/account/assets", SPOT_ASSETS),
+    ]);
+    let adapter =
+        BitgetAccountAdapter::with_transport(transport.clone(), "key", "secret", "passphrase");
+    let balance = adapter.fetch_balance(None).await.expect("balance");
+    let total_eq: f64 = balance[0]["totalEq"].as_str().unwrap().parse().unwrap();
+    assert!((total_eq - (10012.8834 + 199.98)).abs() < 1e-6);
+    let details = balance[0]["details"].as_array().unwrap();
+    assert_eq!(details.len(), 2);
+    assert_eq!(details[0]["ccy"], "USDT");
+    assert_eq!(details[0]["availBal"], "9980.1234");
+    assert_eq!(details[0]["frozenBal"], "25.5");
+    assert_eq!(details[0]["upl"], "7.26");
+    let usdt = vec!["USDT".to_string()];
+    let assets = adapter
+        .fetch_asset_balances(Some(&usdt))
+        .await
+        .expect("asset balances");
+    assert_eq!(
+        assets,
+        serde_json::json!([
+            { "ccy": "USDT", "bal": "830", "availBal": "812.5", "frozenBal": "17.5" }
+        ])
+    );
+}
+#[tokio::test]
+async fn open_interest_uses_current_snapshot_and_latest_kline() {
+    let transport = transport(&[
+        ("/api/v2/mix/market/open-interest", OPEN_INTEREST),
+        ("/api/v2/mix/market/candles", CANDLES_LATEST_1D),
+    ]);
+    let adapter = BitgetContractsAdapter::with_transport(transport.clone());
+    let rows = adapter
+        .fetch_open_interest_volume(Some("BTC"), None, None, Some("1D"))
+        .await
+        .expect("open interest");
+    let rows = rows.as_array().unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["ts"], "1718160000700");
+    assert_eq!(rows[0]["vol"], "2031226671.3310");
+    let oi: f64 = rows[0]["oi"].as_str().unwrap().parse().unwrap();
+    assert!((oi - 41233.1022 * 67318.5).abs() < 1e-3);
+    let requests = transport.requests();
+    assert_eq!(requests[0].param("symbol"), Some("BTCUSDT"));
+    assert_eq!(requests[1].param("granularity"), Some("1D"));
+    assert_eq!(requests[1].param("limit"), Some("1"));
+}
@@ -0,0 +1,196 @@
+//! Bybit 适配器契约测试：回放 tests/fixtures/bybit 下录制的 v5 响应，离线验证字段映射与分页。
+use rust_quant_domain::enums::Timeframe;
+use rust_quant_domain::traits::{ExchangeAccount, ExchangeContracts, ExchangeMarketData};
+use rust_quant_infrastructure::exchanges::{
+    BybitAccountAdapter, BybitContractsAdapter, BybitMarketDataAdapter, FixtureRestTransport,
+};
+use std::sync::Arc;
+const TICKERS: &str = include_str!("fixtures/bybit/tickers_linear.json");
+const KLINE_PAGE1: &str = include_str!("fixtures/bybit/kline_1h_page1.json");
+const KLINE_PAGE2: &str = include_str!("fixtures/bybit/kline_1h_page2.json");
+const KLINE_ERROR: &str = include_str!("fixtures/bybit/kline_error.json");
+const WALLET_BALANCE: &str = include_str!("fixtures/bybit/wallet_balance_unified.json");
+const FUND_BALANCE: &str = include_str!("fixtures/bybit/fund_coins_balance.json");
+const OPEN_INTEREST: &str = include_str!("fixtures/bybit/open_interest_1h.json");
+fn transport(fixtures: &[(&str, &str)]) -> Arc<FixtureRestTransport> {
+    let transport = fixtures
+        .iter()
+        .try_fold(FixtureRestTransport::new(), |transport, (path, body)| {
+            transport.with_fixture(path, body)
+        })
+        .expect("fixtures should be valid json");
+    Arc::new(transport)
+}
+#[tokio::test]
+async fn tickers_are_mapped_to_okx_ticker_fields() {
+    let transport = transport(&[("/v5/market/tickers", TICKERS)]);
+    let adapter = BybitMarketDataAdapter::with_transport(transport.clone());
+    let tickers = adapter.fetch_tickers("SWAP").await.expect("tickers");
+    assert_eq!(tickers.len(), 2);
+    let btc = &tickers[0];
+    assert_eq!(btc["instType"], "SWAP");
+    assert_eq!(btc["instId"], "BTC-USDT-SWAP");
+    assert_eq!(btc["last"], "67321.40");
+    assert_eq!(btc["askPx"], "67321.40");
+    assert_eq!(btc["bidPx"], "67321.30");
+    assert_eq!(btc["open24h"], "66210.00");
+    assert_eq!(btc["volCcy24h"], "96820.7710");
+    assert_eq!(btc["vol24h"], "96820.7710");
+    assert_eq!(transport.requests()[0].param("category"), Some("linear"));
+    assert!(adapter.fetch_tickers("SPOT").await.is_err());
+}
+#[tokio::test]
+async fn candles_paginate_backwards_with_okx_cursor_semantics() {
+    let transport = transport(&[
+        ("/v5/market/kline", KLINE_PAGE1),
+        ("/v5/market/kline", KLINE_PAGE2),
+    ]);
+    let adapter =
+        BybitMarketDataAdapter::with_transport(transport.clone()).with_kline_page_limit(2);
+    let candles = adapter
+        .fetch_candles(
+            "BTC-USDT-SWAP",
+            "1H",
+            Some(1_718_157_600_000),
+            None,
+            Some(3),
+        )
+        .await
+        .expect("candles");
+    let ts: Vec<&str> = candles
+        .iter()
+        .map(|candle| candle[0].as_str().unwrap())
+        .collect();
+    assert_eq!(ts, vec!["1718154000000", "1718150400000", "1718146800000"]);
+    assert_eq!(
+        candles[0],
+        serde_json::json!([
+            "1718154000000",
+            "67250.1",
+            "67400.0",
+            "67190.0",
+            "67321.4",
+            "1523.112",
+            "1523.112",
+            "102501223.51",
+            "1"
+        ])
+    );
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].param("symbol"), Some("BTCUSDT"));
+    assert_eq!(requests[0].param("interval"), Some("60"));
+    assert_eq!(requests[0].param("end"), Some("1718157599999"));
+    assert_eq!(requests[0].param("limit"), Some("2"));
+    assert_eq!(requests[1].param("end"), Some("1718150399999"));
+    assert_eq!(requests[1].param("limit"), Some("1"));
+}
+#[tokio::test]
+async fn api_errors_are_surfaced() {
+    let transport = transport(&[("/v5/market/kline", KLINE_ERROR)]);
+    let adapter = BybitMarketDataAdapter::with_transport(transport);
+    let error = adapter
+        .fetch_latest_candles("BTC-USDT-SWAP", "1H", Some(10))
+        .await
+        .expect_err("retCode != 0 must fail");
+    assert!(error.to_string().contains("Invalid period!"));
+}
+#[tokio::test]
+async fn balances_are_mapped_to_okx_account_and_asset_shapes() {
+    let transport = transport(&[
+        ("/v5/account/wallet-balance", WALLET_BALANCE),
+        (
+            "/v5/asset/transfer/query-account-coins-balance",
+            FUND_BALANCE,
+        ),
+    ]);
+    let adapter = BybitAccountAdapter::with_transport(transport.clone(), "key", "secret");
+    let balance = adapter.fetch_balance(Some("USDT")).await.expect("balance");
+    assert_eq!(balance[0]["totalEq"], "10250.4312");
+    let details = balance[0]["details"].as_array().unwrap();
+    assert_eq!(details.len(), 1);
+    assert_eq!(details[0]["ccy"], "USDT");
+    assert_eq!(details[0]["eq"], "10012.4312");
+    assert_eq!(details[0]["availBal"], "9810.5500");
+    assert_eq!(details[0]["upl"], "12.4312");
+    let usdt = vec!["usdt".to_string()];
+    let assets = adapter
+        .fetch_asset_balances(Some(&usdt))
+        .await
+        .expect("asset balances");
+    assert_eq!(
+        assets,
+        serde_json::json!([
+            { "ccy": "USDT", "bal": "1520.25", "availBal": "1500.25", "frozenBal": "20" }
+        ])
+    );
+    let requests = transport.requests();
+    assert_eq!(requests[0].param("accountType"), Some("UNIFIED"));
+    assert_eq!(requests[0].param("coin"), Some("USDT"));
+    assert_eq!(requests[1].param("accountType"), Some("FUND"));
+}
+#[tokio::test]
+async fn open_interest_is_valued_with_matching_kline_close() {
+    let transport = transport(&[
+        ("/v5/market/open-interest", OPEN_INTEREST),
+        ("/v5/market/kline", KLINE_PAGE1),
+    ]);
+    let adapter = BybitContractsAdapter::with_transport(transport.clone());
+    let rows = adapter
+        .fetch_open_interest_volume(Some("BTC"), None, None, Some("1H"))
+        .await
+        .expect("open interest");
+    let rows = rows.as_array().unwrap();
+    // 第三个点没有对应 K 线，无法折算价值而被丢弃
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["ts"], "1718154000000");
+    assert_eq!(rows[0]["vol"], "102501223.51");
+    let oi: f64 = rows[0]["oi"].as_str().unwrap().parse().unwrap();
+    assert!((oi - 53120.214 * 67321.4).abs() < 1e-3);
+    assert_eq!(transport.requests()[0].param("intervalTime"), Some("1h"));
+}
+#[tokio::test]
+async fn normalized_methods_map_into_domain_types() {
+    let transport = transport(&[
+        ("/v5/market/tickers", TICKERS),
+        ("/v5/market/kline", KLINE_PAGE1),
+        ("/v5/account/wallet-balance", WALLET_BALANCE),
+        ("/v5/market/open-interest", OPEN_INTEREST),
+        ("/v5/market/kline", KLINE_PAGE1),
+    ]);
+    let market = BybitMarketDataAdapter::with_transport(transport.clone());
+    let tickers = market
+        .fetch_tickers_normalized("SWAP")
+        .await
+        .expect("tickers");
+    assert_eq!(tickers[0].symbol, "BTC-USDT-SWAP");
+    assert_eq!(tickers[0].last, 67321.4);
+    assert!((tickers[0].spread().unwrap() - 0.1).abs() < 1e-6);
+    let candles = market
+        .fetch_latest_candles_normalized("BTC-USDT-SWAP", Timeframe::H1, Some(2))
+        .await
+        .expect("candles");
+    assert_eq!(candles[0].timeframe, Timeframe::H1);
+    assert_eq!(candles[0].timestamp, 1_718_154_000_000);
+    assert_eq!(candles[0].close.value(), 67321.4);
+    assert_eq!(candles[0].volume.value(), 1523.112);
+    assert!(candles[0].confirmed);
+    assert_eq!(transport.requests()[1].param("interval"), Some("60"));
+
+    let account = BybitAccountAdapter::with_transport(transport.clone(), "key", "secret");
+    let snapshot = account
+        .fetch_balance_snapshot(Some("USDT"))
+        .await
+        .expect("balance snapshot");
+    assert_eq!(snapshot.total_equity, 10250.4312);
+    assert_eq!(snapshot.currency("USDT").unwrap().available, 9810.55);
+
+    let contracts = BybitContractsAdapter::with_transport(transport);
+    let points = contracts
+        .fetch_open_interest_points(Some("BTC"), None, None, Some("1H"))
+        .await
+        .expect("open interest points");
+    assert_eq!(points.len(), 2);
+    assert_eq!(points[0].symbol, "BTC");
+    assert_eq!(points[0].volume, Some(102501223.51));
+}
@@ -0,0 +1,179 @@
+use rust_quant_common::CandleItem;
+
+/// 可按周期桶合并的已确认 K 线。
+///
+/// 实盘聚合器与回测高周期上下文共用同一套分桶与收盘判定，保证两边产出的高周期 K 线一致。
+pub trait BucketCandle: Clone {
+    /// K 线开盘时间，Unix 毫秒时间戳。
+    fn open_time_ms(&self) -> i64;
+    /// 改写开盘时间；桶内首根 K 线以桶起点作为高周期 K 线开盘时间。
+    fn set_open_time_ms(&mut self, open_time_ms: i64);
+    /// 合并桶内下一根 K 线的高低收与成交量。
+    fn merge(&mut self, next: &Self);
+}
+
+/// 基础周期到目标周期的分桶规格。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CandleBucketSpec {
+    /// 输入 K 线周期，单位为毫秒。
+    pub base_ms: i64,
+    /// 目标高周期，单位为毫秒。
+    pub target_ms: i64,
+}
+
+impl CandleBucketSpec {
+    /// 创建分桶规格；目标周期必须是基础周期的整数倍且严格更长。
+    pub fn new(base_ms: i64, target_ms: i64) -> Option<Self> {
+        (base_ms > 0 && target_ms > base_ms && target_ms % base_ms == 0)
+            .then_some(Self { base_ms, target_ms })
+    }
+
+    /// 完整目标周期必须包含的连续基础 K 线数量。
+    pub const fn expected_count(self) -> usize {
+        (self.target_ms / self.base_ms) as usize
+    }
+}
+
+/// 尚未收满的高周期 K 线；只允许连续的基础周期输入推进。
+#[derive(Debug, Clone)]
+pub struct PartialCandleBucket<C> {
+    /// 高周期桶起点，Unix 毫秒时间戳。
+    bucket_start_ms: i64,
+    /// 最近纳入的基础 K 线开盘时间，Unix 毫秒时间戳。
+    last_open_time_ms: i64,
+    /// 当前桶已纳入的连续基础 K 线数量。
+    count: usize,
+    /// 当前桶的 OHLCV 聚合值。
+    candle: C,
+}
+
+impl<C: BucketCandle> PartialCandleBucket<C> {
+    /// 从桶内第一根基础 K 线初始化高周期聚合状态。
+    pub fn new(spec: CandleBucketSpec, candle: &C) -> Self {
+        let bucket_start_ms = align_bucket(candle.open_time_ms(), spec.target_ms);
+        let last_open_time_ms = candle.open_time_ms();
+        let mut candle = candle.clone();
+        candle.set_open_time_ms(bucket_start_ms);
+        Self {
+            bucket_start_ms,
+            last_open_time_ms,
+            count: 1,
+            candle,
+        }
+    }
+
+    /// 合并下一根基础 K 线，仅在桶内样本完整且抵达预期末根时返回收盘 K 线。
+    pub fn push(&mut self, spec: CandleBucketSpec, next: &C) -> Option<C> {
+        let bucket_start_ms = align_bucket(next.open_time_ms(), spec.target_ms);
+        if bucket_start_ms != self.bucket_start_ms {
+            *self = Self::new(spec, next);
+            return None;
+        }
+
+        self.candle.merge(next);
+        self.last_open_time_ms = next.open_time_ms();
+        self.count += 1;
+
+        let expected_last = self
+            .bucket_start_ms
+            .saturating_add(spec.target_ms - spec.base_ms);
+        (self.count == spec.expected_count() && self.last_open_time_ms == expected_last)
+            .then(|| self.candle.clone())
+    }
+}
+
+/// 推进指定高周期桶，并仅在该桶完整收盘时产出 K 线。
+pub fn update_candle_bucket<C: BucketCandle>(
+    partial: &mut Option<PartialCandleBucket<C>>,
+    spec: CandleBucketSpec,
+    candle: &C,
+) -> Option<C> {
+    match partial {
+        Some(partial) => partial.push(spec, candle),
+        None => {
+            *partial = Some(PartialCandleBucket::new(spec, candle));
+            None
+        }
+    }
+}
+
+/// 将开盘时间对齐到对应高周期的 UTC 桶起点。
+pub fn align_bucket(open_time_ms: i64, timeframe_ms: i64) -> i64 {
+    open_time_ms - open_time_ms.rem_euclid(timeframe_ms)
+}
+
+impl BucketCandle for CandleItem {
+    fn open_time_ms(&self) -> i64 {
+        self.ts
+    }
+
+    fn set_open_time_ms(&mut self, open_time_ms: i64) {
+        self.ts = open_time_ms;
+    }
+
+    fn merge(&mut self, next: &Self) {
+        self.h = self.h.max(next.h);
+        self.l = self.l.min(next.l);
+        self.c = next.c;
+        self.v += next.v;
+        self.confirm = next.confirm;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIFTEEN_MINUTES_MS: i64 = 15 * 60_000;
+
+    fn candle(index: i64, close: f64) -> CandleItem {
+        CandleItem {
+            o: close - 1.0,
+            h: close + 1.0,
+            l: close - 2.0,
+            c: close,
+            v: 10.0,
+            ts: index * FIFTEEN_MINUTES_MS,
+            confirm: 1,
+        }
+    }
+
+    #[test]
+    fn closes_hour_bucket_on_fourth_contiguous_quarter() {
+        let spec = CandleBucketSpec::new(FIFTEEN_MINUTES_MS, 4 * FIFTEEN_MINUTES_MS).unwrap();
+        let mut partial = None;
+        let closed: Vec<_> = (0..8)
+            .filter_map(|index| {
+                update_candle_bucket(&mut partial, spec, &candle(index, 100.0 + index as f64))
+            })
+            .collect();
+
+        assert_eq!(closed.len(), 2);
+        assert_eq!(closed[0].ts, 0);
+        assert_eq!(closed[0].o, 99.0);
+        assert_eq!(closed[0].c, 103.0);
+        assert_eq!(closed[0].h, 104.0);
+        assert_eq!(closed[0].l, 98.0);
+        assert_eq!(closed[0].v, 40.0);
+        assert_eq!(closed[1].ts, 4 * FIFTEEN_MINUTES_MS);
+    }
+
+    #[test]
+    fn gap_inside_bucket_never_emits_partial_candle() {
+        let spec = CandleBucketSpec::new(FIFTEEN_MINUTES_MS, 4 * FIFTEEN_MINUTES_MS).unwrap();
+        let mut partial = None;
+        let closed: Vec<_> = [0, 1, 3, 4, 5, 6, 7]
+            .into_iter()
+            .filter_map(|index| update_candle_bucket(&mut partial, spec, &candle(index, 100.0)))
+            .collect();
+
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].ts, 4 * FIFTEEN_MINUTES_MS);
+    }
+
+    #[test]
+    fn rejects_target_that_is_not_a_longer_multiple() {
+        assert!(CandleBucketSpec::new(FIFTEEN_MINUTES_MS, FIFTEEN_MINUTES_MS).is_none());
+        assert!(CandleBucketSpec::new(FIFTEEN_MINUTES_MS, 20 * 60_000).is_none());
+    }
+}
@@ -1,3 +1,6 @@
+use super::candle_bucket::{
+    update_candle_bucket, BucketCandle, CandleBucketSpec, PartialCandleBucket,
+};
 use anyhow::{bail, Context, Result};
 use okx::dto::market_dto::CandleOkxRespDto;
 use rust_decimal::Decimal;
@@ -41,9 +44,12 @@ impl AggregatedTimeframe {
         }
     }
 
-    /// 返回完整周期必须包含的连续 1m K 线数量。
-    const fn expected_one_minute_count(self) -> usize {
-        (self.duration_ms() / ONE_MINUTE_MS) as usize
+    /// 返回由连续 1m K 线聚合到该周期的分桶规格。
+    const fn bucket_spec(self) -> CandleBucketSpec {
+        CandleBucketSpec {
+            base_ms: ONE_MINUTE_MS,
+            target_ms: self.duration_ms(),
+        }
     }
 }
 
@@ -121,6 +127,25 @@ impl ConfirmedCandle {
     }
 }
 
+impl BucketCandle for ConfirmedCandle {
+    fn open_time_ms(&self) -> i64 {
+        self.open_time_ms
+    }
+
+    fn set_open_time_ms(&mut self, open_time_ms: i64) {
+        self.open_time_ms = open_time_ms;
+    }
+
+    fn merge(&mut self, next: &Self) {
+        self.high = self.high.max(next.high);
+        self.low = self.low.min(next.low);
+        self.close = next.close;
+        self.volume_contracts += next.volume_contracts;
+        self.volume_base += next.volume_base;
+        self.volume_quote += next.volume_quote;
+    }
+}
+
 /// 一根已确认 K 线及其周期。
 #[derive(Debug, Clone, PartialEq)]
 pub struct TimeframedCandle {
@@ -210,64 +235,6 @@ impl RollingVolumeWindow {
     }
 }
 
-/// 尚未收满的高周期 K 线；只允许连续 1m 输入推进。
-#[derive(Debug, Clone)]
-struct PartialCandle {
-    /// 高周期桶起点，Unix 毫秒时间戳。
-    bucket_start_ms: i64,
-    /// 最近纳入的 1m 开盘时间，Unix 毫秒时间戳。
-    last_open_time_ms: i64,
-    /// 当前桶已纳入的连续 1m K 线数量。
-    one_minute_count: usize,
-    /// 当前桶的 OHLCV 聚合值。
-    candle: ConfirmedCandle,
-}
-
-impl PartialCandle {
-    /// 从桶内第一根 1m K 线初始化高周期聚合状态。
-    fn new(timeframe: AggregatedTimeframe, candle: &ConfirmedCandle) -> Self {
-        let bucket_start_ms = align_bucket(candle.open_time_ms, timeframe.duration_ms());
-        let last_open_time_ms = candle.open_time_ms;
-        let mut candle = candle.clone();
-        candle.open_time_ms = bucket_start_ms;
-        Self {
-            bucket_start_ms,
-            last_open_time_ms,
-            one_minute_count: 1,
-            candle,
-        }
-    }
-
-    /// 合并下一根 1m K 线，仅在桶内样本完整且抵达预期末分钟时返回收盘 K 线。
-    fn push(
-        &mut self,
-        timeframe: AggregatedTimeframe,
-        one_minute: &ConfirmedCandle,
-    ) -> Option<ConfirmedCandle> {
-        let bucket_start_ms = align_bucket(one_minute.open_time_ms, timeframe.duration_ms());
-        if bucket_start_ms != self.bucket_start_ms {
-            *self = Self::new(timeframe, one_minute);
-            return None;
-        }
-
-        self.candle.high = self.candle.high.max(one_minute.high);
-        self.candle.low = self.candle.low.min(one_minute.low);
-        self.candle.close = one_minute.close;
-        self.candle.volume_contracts += one_minute.volume_contracts;
-        self.candle.volume_base += one_minute.volume_base;
-        self.candle.volume_quote += one_minute.volume_quote;
-        self.last_open_time_ms = one_minute.open_time_ms;
-        self.one_minute_count += 1;
-
-        let expected_last = self
-            .bucket_start_ms
-            .saturating_add(timeframe.duration_ms() - ONE_MINUTE_MS);
-        (self.one_minute_count == timeframe.expected_one_minute_count()
-            && self.last_open_time_ms == expected_last)
-            .then(|| self.candle.clone())
-    }
-}
-
 /// 单个交易对的连续性、成交量窗口与高周期部分桶状态。
 #[derive(Debug, Default)]
 struct SymbolAggregationState {
@@ -282,11 +249,11 @@ struct SymbolAggregationState {
     /// 4H 最近 20 根成交量窗口。
     volume_4h: RollingVolumeWindow,
     /// 当前未收盘的 5m 桶。
-    partial_5m: Option<PartialCandle>,
+    partial_5m: Option<PartialCandleBucket<ConfirmedCandle>>,
     /// 当前未收盘的 15m 桶。
-    partial_15m: Option<PartialCandle>,
+    partial_15m: Option<PartialCandleBucket<ConfirmedCandle>>,
     /// 当前未收盘的 4H 桶。
-    partial_4h: Option<PartialCandle>,
+    partial_4h: Option<PartialCandleBucket<ConfirmedCandle>>,
 }
 
 impl SymbolAggregationState {
@@ -301,7 +268,10 @@ impl SymbolAggregationState {
     }
 
     /// 返回高周期部分桶；1m 不经过桶聚合，因此调用 1m 属于编程错误。
-    fn partial_mut(&mut self, timeframe: AggregatedTimeframe) -> &mut Option<PartialCandle> {
+    fn partial_mut(
+        &mut self,
+        timeframe: AggregatedTimeframe,
+    ) -> &mut Option<PartialCandleBucket<ConfirmedCandle>> {
         match timeframe {
             AggregatedTimeframe::M5 => &mut self.partial_5m,
             AggregatedTimeframe::M15 => &mut self.partial_15m,
@@ -412,22 +382,16 @@ impl ConfirmedCandleAggregator {
 
 /// 推进指定高周期桶，并仅在该桶完整收盘时产出 K 线。
 fn update_partial(
-    partial: &mut Option<PartialCandle>,
+    partial: &mut Option<PartialCandleBucket<ConfirmedCandle>>,
     timeframe: AggregatedTimeframe,
     candle: &ConfirmedCandle,
 ) -> Option<ConfirmedCandle> {
-    match partial {
-        Some(partial) => partial.push(timeframe, candle),
-        None => {
-            *partial = Some(PartialCandle::new(timeframe, candle));
-            None
-        }
-    }
+    update_candle_bucket(partial, timeframe.bucket_spec(), candle)
 }
 
 /// 预热部分桶但不发出历史观测，避免启动时重放旧放量事件。
 fn update_partial_without_emitting(
-    partial: &mut Option<PartialCandle>,
+    partial: &mut Option<PartialCandleBucket<ConfirmedCandle>>,
     timeframe: AggregatedTimeframe,
     candle: &ConfirmedCandle,
 ) {
@@ -459,11 +423,6 @@ fn record_closed_candle(
         .push(TimeframedCandle { timeframe, candle });
 }
 
-/// 将 1m 开盘时间对齐到对应高周期的 UTC 桶起点。
-fn align_bucket(open_time_ms: i64, timeframe_ms: i64) -> i64 {
-    open_time_ms - open_time_ms.rem_euclid(timeframe_ms)
-}
-
 /// 解析交易所必填十进制字段，并保留字段名帮助定位坏数据。
 fn parse_decimal_field(value: &str, field: &str) -> Result<Decimal> {
     value
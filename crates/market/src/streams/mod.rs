@@ -1,10 +1,12 @@
 //! WebSocket 数据流
+pub mod candle_bucket;
 pub mod confirmed_candle_aggregator;
 pub mod confirmed_candle_stream;
 pub mod deep_stream_manager;
 pub mod websocket_runtime;
 pub mod websocket_service;
 // 重新导出
+pub use candle_bucket::*;
 pub use confirmed_candle_aggregator::*;
 pub use confirmed_candle_stream::*;
 pub use websocket_runtime::*;
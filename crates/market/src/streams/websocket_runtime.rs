@@ -1,3 +1,4 @@
+use rust_quant_core::metrics;
 use rust_quant_domain::Timeframe;
 use serde::Serialize;
 use std::collections::HashMap;
@@ -262,11 +263,34 @@ impl CandleRuntimeRegistry {
             .cloned()
             .collect()
     }
+
+    /// 把各订阅目标的消息延迟和漏触发次数写入进程级指标；尚未收到消息的目标不输出延迟。
+    pub fn export_metrics(&self, now_ms: i64) {
+        let registry = metrics::global();
+        registry.clear(metrics::WEBSOCKET_MESSAGE_LAG_SECONDS);
+        registry.clear(metrics::WEBSOCKET_MISSED_TRIGGERS);
+        for snapshot in self.snapshots() {
+            if let Some(last_message_at_ms) = snapshot.last_message_at_ms {
+                let lag_ms = now_ms.saturating_sub(last_message_at_ms).max(0);
+                metrics::set_websocket_message_lag(
+                    &snapshot.symbol,
+                    &snapshot.timeframe,
+                    lag_ms as f64 / 1000.0,
+                );
+            }
+            metrics::set_websocket_missed_triggers(
+                &snapshot.symbol,
+                &snapshot.timeframe,
+                snapshot.missed_trigger_count,
+            );
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{CandleRuntimeRegistry, WatchdogDecision, WATCHDOG_TRIGGER_WINDOW_MS};
+    use rust_quant_core::metrics;
 
     #[test]
     fn watchdog_allows_trigger_at_exact_ten_second_boundary() {
@@ -327,4 +351,31 @@ mod tests {
         assert_eq!(snapshot.last_triggered_candle_ts, Some(1_000));
         assert_eq!(snapshot.last_triggered_at_ms, Some(10_200));
     }
+
+    #[test]
+    fn exported_metrics_report_message_lag_and_missed_triggers() {
+        let registry = CandleRuntimeRegistry::default();
+        registry.record_message("METRICS-USDT-SWAP", "1H", 10_000);
+        registry.record_expired("METRICS-USDT-SWAP", "1H", 1_000);
+        registry.register_target("METRICS-USDT-SWAP", "4H");
+
+        registry.export_metrics(12_500);
+
+        let labels = [("symbol", "METRICS-USDT-SWAP"), ("timeframe", "1H")];
+        assert_eq!(
+            metrics::global().scalar_value(metrics::WEBSOCKET_MESSAGE_LAG_SECONDS, &labels),
+            Some(2.5)
+        );
+        assert_eq!(
+            metrics::global().scalar_value(metrics::WEBSOCKET_MISSED_TRIGGERS, &labels),
+            Some(1.0)
+        );
+        assert_eq!(
+            metrics::global().scalar_value(
+                metrics::WEBSOCKET_MESSAGE_LAG_SECONDS,
+                &[("symbol", "METRICS-USDT-SWAP"), ("timeframe", "4H")],
+            ),
+            None
+        );
+    }
 }
@@ -75,6 +75,13 @@ pub async fn run_socket_with_strategy_trigger(
     for target in &targets {
         runtime_registry.register_target(&target.symbol, &target.timeframe);
     }
+    // 指标在抓取时按当前时钟计算延迟；只持有弱引用，服务退出后不会延长运行态生命周期。
+    let metrics_runtime = Arc::downgrade(&runtime_registry);
+    rust_quant_core::metrics::global().register_collector("candle_runtime", move |_| {
+        if let Some(runtime) = metrics_runtime.upgrade() {
+            runtime.export_metrics(Utc::now().timestamp_millis());
+        }
+    });
 
     info!("初始化 K 线批处理 Worker");
     let (persist_tx, persist_rx) = mpsc::unbounded_channel::<PersistTask>();
@@ -2,23 +2,53 @@
 //!
 //! 负责回测策略的执行，协调 BacktestService 和 CandleService
 use crate::infra::data_validator;
+use crate::infra::param_optimizer::BacktestObjectiveMetrics;
 use crate::workflow::job_param_generator::ParamMergeBuilder;
 use anyhow::{anyhow, Result};
 use futures::future::join_all;
 use rust_quant_common::CandleItem;
-use rust_quant_domain::StrategyType;
+use rust_quant_core::{config::env_is_true, database::get_db_pool};
+use rust_quant_domain::entities::FundingRate;
+use rust_quant_domain::traits::funding_rate_repository::FundingRateRepository;
+use rust_quant_domain::{StrategyType, Timeframe};
 use rust_quant_indicators::trend::vegas::VegasStrategy;
+use rust_quant_infrastructure::repositories::economic_event_repository::SqlxEconomicEventRepository;
+use rust_quant_infrastructure::repositories::funding_rate_repository::SqlxFundingRateRepository;
 use rust_quant_market::models::SelectTime;
-use rust_quant_services::market::CandleService;
+use rust_quant_services::market::{
+    ensure_no_unresolved_candle_findings, CandleQualityStore, CandleService,
+};
 use rust_quant_services::strategy::BacktestService;
-use rust_quant_strategies::framework::backtest::BackTestAbleStrategyTrait;
+use rust_quant_strategies::framework::backtest::{
+    BackTestAbleStrategyTrait, EconomicBlackout, EconomicBlackoutConfig, FundingSeries,
+    IntrabarCandles, DEFAULT_FUNDING_INTERVAL_MS,
+};
 use rust_quant_strategies::implementations::nwe_strategy::{NweStrategy, NweStrategyConfig};
 use rust_quant_strategies::implementations::vegas_backtest::VegasBacktestAdapter;
 use rust_quant_strategies::strategy_common::BasicRiskStrategyConfig;
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
 use tokio::time::Instant;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+/// 盘中成交顺序解析使用的低周期（如 1m / 5m），未设置时沿用止损优先口径
+const INTRABAR_PERIOD_ENV: &str = "BACKTEST_INTRABAR_PERIOD";
+/// 经济事件 fixture 文件（`EconomicEvent` JSON 数组），设置后优先于数据库
+const ECON_EVENT_FIXTURE_ENV: &str = "BACKTEST_ECON_EVENT_FIXTURE";
+/// 为 true 时从经济日历表加载事件，窗口内拦截开仓
+const ECON_EVENT_BLACKOUT_ENV: &str = "BACKTEST_ECON_EVENT_BLACKOUT";
+/// 为 true 时从资金费率表加载回测区间内的历史并计提资金费；默认关闭，保持既有回测口径
+const FUNDING_FEE_ENV: &str = "BACKTEST_FUNDING_FEE";
+/// 同一 inst_id/period 批次内所有参数组合共享的回测上下文，由调用方按批次加载一次后随任务下发
+#[derive(Debug, Clone, Default)]
+pub struct BacktestRunContext {
+    /// 资金费率序列；未开启、无记录或查询失败时为空
+    pub funding: Option<Arc<FundingSeries>>,
+    /// 盘中解析使用的低周期K线；各组合共享同一份，未开启时为空
+    pub intrabar: Option<Arc<IntrabarCandles>>,
+    /// 经济事件停牌日历；未开启时为空
+    pub economic_blackout: Option<Arc<EconomicBlackout>>,
+}
 /// 回测执行器
 ///
 /// 职责：
@@ -54,12 +84,21 @@ impl BacktestExecutor {
         strategy: VegasStrategy,
         risk_strategy_config: BasicRiskStrategyConfig,
         source_candles: Arc<Vec<CandleItem>>,
+        context: BacktestRunContext,
     ) -> Result<i64> {
         let adapter = VegasBacktestAdapter::new(strategy);
-        self.run_strategy_backtest(inst_id, time, adapter, risk_strategy_config, source_candles)
-            .await
+        self.run_strategy_backtest(
+            inst_id,
+            time,
+            adapter,
+            risk_strategy_config,
+            source_candles,
+            context,
+        )
+        .await
     }
     /// 使用独立策略身份运行共享 Vegas 引擎。
+    #[allow(clippy::too_many_arguments)]
     pub async fn run_vegas_test_as(
         &self,
         inst_id: &str,
@@ -68,10 +107,18 @@ impl BacktestExecutor {
         strategy_type: StrategyType,
         risk_strategy_config: BasicRiskStrategyConfig,
         source_candles: Arc<Vec<CandleItem>>,
+        context: BacktestRunContext,
     ) -> Result<i64> {
         let adapter = VegasBacktestAdapter::with_strategy_type(strategy, strategy_type);
-        self.run_strategy_backtest(inst_id, time, adapter, risk_strategy_config, source_candles)
-            .await
+        self.run_strategy_backtest(
+            inst_id,
+            time,
+            adapter,
+            risk_strategy_config,
+            source_candles,
+            context,
+        )
+        .await
     }
     /// 运行 NWE 策略测试
     pub async fn run_nwe_test(
@@ -81,6 +128,7 @@ impl BacktestExecutor {
         strategy: NweStrategy,
         risk_strategy_config: BasicRiskStrategyConfig,
         source_candles: Arc<Vec<CandleItem>>,
+        context: BacktestRunContext,
     ) -> Result<i64> {
         self.run_strategy_backtest(
             inst_id,
@@ -88,6 +136,7 @@ impl BacktestExecutor {
             strategy,
             risk_strategy_config,
             source_candles,
+            context,
         )
         .await
     }
@@ -108,6 +157,16 @@ impl BacktestExecutor {
         let db_query_duration = db_query_start.elapsed();
         let validation_start = Instant::now();
         data_validator::valid_candles_continuity(&source_candles, period)?;
+        // 设置 BACKTEST_REQUIRE_CLEAN_CANDLES=1 时，回测区间内有未解决的K线质量问题直接拒绝运行
+        if env_is_true("BACKTEST_REQUIRE_CLEAN_CANDLES", false) {
+            if let (Some(first), Some(last)) = (source_candles.first(), source_candles.last()) {
+                let timeframe =
+                    Timeframe::from_str(period).map_err(|e| anyhow!("无效的K线周期: {}", e))?;
+                let store = CandleQualityStore::from_env()?;
+                ensure_no_unresolved_candle_findings(&store, inst_id, timeframe, first.ts, last.ts)
+                    .await?;
+            }
+        }
         let validation_duration = validation_start.elapsed();
         let total_duration = start_time.elapsed();
         info!(
@@ -157,6 +216,7 @@ impl BacktestExecutor {
         Ok(Arc::new(candle_item_vec))
     }
     /// 运行回测策略
+    #[allow(clippy::too_many_arguments)]
     pub async fn run_back_test_strategy(
         &self,
         params_batch: Vec<ParamMergeBuilder>,
@@ -164,6 +224,7 @@ impl BacktestExecutor {
         time: &str,
         strategy_type: StrategyType,
         arc_candle_item_clone: Arc<Vec<CandleItem>>,
+        context: BacktestRunContext,
         semaphore: Arc<Semaphore>,
     ) {
         let mut batch_tasks = Vec::with_capacity(params_batch.len());
@@ -174,6 +235,7 @@ impl BacktestExecutor {
             let time = time.to_string();
             let strategy_type = strategy_type;
             let source_candles = Arc::clone(&arc_candle_item_clone);
+            let context = context.clone();
             let permit = Arc::clone(&semaphore);
             // 创建任务
             let executor = self.clone_for_spawn();
@@ -187,6 +249,7 @@ impl BacktestExecutor {
                         strategy_type,
                         risk_strategy_config,
                         source_candles,
+                        context,
                     )
                     .await
                 {
@@ -201,6 +264,102 @@ impl BacktestExecutor {
         // 等待当前批次完成
         join_all(batch_tasks).await;
     }
+    /// 运行一批 Vegas 参数并按输入顺序返回目标指标，供参数优化器评分；失败的组合返回 None。
+    #[allow(clippy::too_many_arguments)]
+    pub async fn evaluate_back_test_strategy(
+        &self,
+        params_batch: Vec<ParamMergeBuilder>,
+        inst_id: &str,
+        time: &str,
+        strategy_type: StrategyType,
+        arc_candle_item_clone: Arc<Vec<CandleItem>>,
+        context: BacktestRunContext,
+        semaphore: Arc<Semaphore>,
+    ) -> Vec<Option<BacktestObjectiveMetrics>> {
+        let mut batch_tasks = Vec::with_capacity(params_batch.len());
+        for param in params_batch {
+            let risk_strategy_config = param.to_risk_config();
+            let strategy = param.to_vegas_strategy(time.to_string());
+            let inst_id = inst_id.to_string();
+            let time = time.to_string();
+            let source_candles = Arc::clone(&arc_candle_item_clone);
+            let context = context.clone();
+            let permit = Arc::clone(&semaphore);
+            let executor = self.clone_for_spawn();
+            batch_tasks.push(tokio::spawn(async move {
+                let _permit: tokio::sync::SemaphorePermit<'_> = permit.acquire().await.unwrap();
+                let adapter = VegasBacktestAdapter::with_strategy_type(strategy, strategy_type);
+                match executor
+                    .run_strategy_backtest_with_metrics(
+                        &inst_id,
+                        &time,
+                        adapter,
+                        risk_strategy_config,
+                        source_candles,
+                        context,
+                    )
+                    .await
+                {
+                    Ok((_, metrics)) => Some(metrics),
+                    Err(e) => {
+                        error!("Vegas optimizer trial failed: {:?}", e);
+                        None
+                    }
+                }
+            }));
+        }
+        join_all(batch_tasks)
+            .await
+            .into_iter()
+            .map(|result| result.ok().flatten())
+            .collect()
+    }
+    /// 运行一批 NWE 参数并按输入顺序返回目标指标；失败的组合返回 None。
+    pub async fn evaluate_nwe_strategy(
+        &self,
+        params_batch: Vec<(NweStrategyConfig, BasicRiskStrategyConfig)>,
+        inst_id: &str,
+        time: &str,
+        arc_candle_item_clone: Arc<Vec<CandleItem>>,
+        context: BacktestRunContext,
+        semaphore: Arc<Semaphore>,
+    ) -> Vec<Option<BacktestObjectiveMetrics>> {
+        let mut batch_tasks = Vec::with_capacity(params_batch.len());
+        for (cfg, risk_cfg) in params_batch {
+            let strategy = NweStrategy::new(cfg);
+            let inst_id = inst_id.to_string();
+            let time = time.to_string();
+            let source_candles = Arc::clone(&arc_candle_item_clone);
+            let context = context.clone();
+            let permit = Arc::clone(&semaphore);
+            let executor = self.clone_for_spawn();
+            batch_tasks.push(tokio::spawn(async move {
+                let _permit: tokio::sync::SemaphorePermit<'_> = permit.acquire().await.unwrap();
+                match executor
+                    .run_strategy_backtest_with_metrics(
+                        &inst_id,
+                        &time,
+                        strategy,
+                        risk_cfg,
+                        source_candles,
+                        context,
+                    )
+                    .await
+                {
+                    Ok((_, metrics)) => Some(metrics),
+                    Err(e) => {
+                        error!("NWE evaluation failed: {:?}", e);
+                        None
+                    }
+                }
+            }));
+        }
+        join_all(batch_tasks)
+            .await
+            .into_iter()
+            .map(|result| result.ok().flatten())
+            .collect()
+    }
     /// 运行一组 NWE 策略（随机/网格参数）回测，复用与 Vegas 相同的并发调度思路
     pub async fn run_nwe_random_batch(
         &self,
@@ -211,6 +370,7 @@ impl BacktestExecutor {
         inst_id: &str,
         time: &str,
         arc_candle_item_clone: Arc<Vec<CandleItem>>,
+        context: BacktestRunContext,
         semaphore: Arc<Semaphore>,
     ) {
         let mut batch_tasks = Vec::with_capacity(params_batch.len());
@@ -219,12 +379,13 @@ impl BacktestExecutor {
             let inst_id = inst_id.to_string();
             let time = time.to_string();
             let source_candles = Arc::clone(&arc_candle_item_clone);
+            let context = context.clone();
             let permit = Arc::clone(&semaphore);
             let executor = self.clone_for_spawn();
             batch_tasks.push(tokio::spawn(async move {
                 let _permit: tokio::sync::SemaphorePermit<'_> = permit.acquire().await.unwrap();
                 match executor
-                    .run_nwe_test(&inst_id, &time, strategy, risk_cfg, source_candles)
+                    .run_nwe_test(&inst_id, &time, strategy, risk_cfg, source_candles, context)
                     .await
                 {
                     Ok(back_test_id) => Some(back_test_id),
@@ -238,6 +399,138 @@ impl BacktestExecutor {
         // 等待当前批次完成
         join_all(batch_tasks).await;
     }
+    /// 按 `BACKTEST_INTRABAR_PERIOD` 加载盘中解析所需的低周期K线；未开启时返回 None。
+    async fn load_intrabar_candles(
+        &self,
+        inst_id: &str,
+        period: &str,
+        source_candles: &[CandleItem],
+    ) -> Result<Option<IntrabarCandles>> {
+        let Ok(lower_period) = std::env::var(INTRABAR_PERIOD_ENV) else {
+            return Ok(None);
+        };
+        let lower_period = lower_period.trim();
+        if lower_period.is_empty() {
+            return Ok(None);
+        }
+        let intrabar = self
+            .candle_service
+            .load_intrabar_candles(inst_id, lower_period, period, source_candles)
+            .await
+            .map_err(|e| anyhow!("加载盘中解析K线失败: {}", e))?;
+        if intrabar.is_empty() {
+            warn!(
+                "盘中解析K线为空，回退止损优先口径: inst_id={}, period={}, intrabar_period={}",
+                inst_id, period, lower_period
+            );
+        }
+        Ok(Some(intrabar))
+    }
+    /// 按 `BACKTEST_ECON_EVENT_FIXTURE` / `BACKTEST_ECON_EVENT_BLACKOUT` 加载经济事件停牌日历；未开启时返回 None。
+    async fn load_economic_blackout(
+        &self,
+        period: &str,
+        source_candles: &[CandleItem],
+    ) -> Result<Option<EconomicBlackout>> {
+        let fixture = std::env::var(ECON_EVENT_FIXTURE_ENV)
+            .ok()
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty());
+        if fixture.is_none() && !env_is_true(ECON_EVENT_BLACKOUT_ENV, false) {
+            return Ok(None);
+        }
+        let (Some(first), Some(last)) = (source_candles.first(), source_candles.last()) else {
+            return Ok(None);
+        };
+        let bar_duration_ms = Timeframe::from_str(period)
+            .map_err(|error| anyhow!("无效的K线周期: {}", error))?
+            .to_minutes()
+            * 60
+            * 1000;
+        let config = EconomicBlackoutConfig::from_env();
+        let blackout = match fixture {
+            Some(path) => EconomicBlackout::from_fixture_file(&path, &config, bar_duration_ms)?,
+            None => {
+                let repository = SqlxEconomicEventRepository::new(get_db_pool().clone());
+                EconomicBlackout::load(&repository, first.ts, last.ts, &config, bar_duration_ms)
+                    .await
+                    .map_err(|e| anyhow!("加载经济事件失败: {}", e))?
+            }
+        };
+        if blackout.is_empty() {
+            warn!("经济事件停牌已开启但回测区间内无事件: period={}", period);
+        }
+        Ok(Some(blackout))
+    }
+    /// 按批次加载回测上下文；同一 inst_id/period 的参数组合共享结果，避免逐组合重复查询。
+    pub async fn load_backtest_context(
+        &self,
+        inst_id: &str,
+        period: &str,
+        source_candles: &[CandleItem],
+    ) -> Result<BacktestRunContext> {
+        let funding = self.load_funding_series(inst_id, source_candles).await;
+        let intrabar = self
+            .load_intrabar_candles(inst_id, period, source_candles)
+            .await?;
+        let economic_blackout = self.load_economic_blackout(period, source_candles).await?;
+        info!(
+            "回测上下文加载完成: inst_id={}, period={}, funding={}, intrabar_candles={}, economic_blackout={}",
+            inst_id,
+            period,
+            funding.is_some(),
+            intrabar.as_ref().map_or(0, IntrabarCandles::len),
+            economic_blackout.is_some()
+        );
+        Ok(BacktestRunContext {
+            funding: funding.map(Arc::new),
+            intrabar: intrabar.map(Arc::new),
+            economic_blackout: economic_blackout.map(Arc::new),
+        })
+    }
+    /// 按 `BACKTEST_FUNDING_FEE` 从资金费率表加载回测区间内的资金费序列；
+    /// 未开启、无记录或查询失败时返回 None，回测按不计提资金费继续。
+    async fn load_funding_series(
+        &self,
+        inst_id: &str,
+        source_candles: &[CandleItem],
+    ) -> Option<FundingSeries> {
+        if !env_is_true(FUNDING_FEE_ENV, false) {
+            return None;
+        }
+        let (Some(first), Some(last)) = (source_candles.first(), source_candles.last()) else {
+            return None;
+        };
+        // 取区间起点之前一个周期的记录，保证第一次结算时刻也能取到生效费率。
+        let start_time = first.ts - DEFAULT_FUNDING_INTERVAL_MS;
+        // 按最短 1 小时结算周期估算条数上限，避免仓储默认的 100 条截断长区间。
+        let limit = (last.ts - start_time) / (60 * 60 * 1000) + 2;
+        let repository = SqlxFundingRateRepository::new(get_db_pool().clone());
+        let rates = match repository
+            .find_history(inst_id, start_time, last.ts, Some(limit))
+            .await
+        {
+            Ok(rates) => rates,
+            Err(e) => {
+                warn!(
+                    "加载资金费率历史失败，跳过资金费计提: inst_id={}, err={}",
+                    inst_id, e
+                );
+                return None;
+            }
+        };
+        if rates.is_empty() {
+            warn!(
+                "资金费率表在回测区间内无记录，跳过资金费计提: inst_id={}",
+                inst_id
+            );
+            return None;
+        }
+        Some(FundingSeries::from_funding_rates(
+            &rates,
+            funding_interval_ms(&rates),
+        ))
+    }
     /// 克隆执行器用于异步任务（内部方法）
     fn clone_for_spawn(&self) -> Arc<Self> {
         Arc::new(BacktestExecutor {
@@ -253,7 +546,34 @@ impl BacktestExecutor {
         strategy: S,
         risk_strategy_config: BasicRiskStrategyConfig,
         source_candles: Arc<Vec<CandleItem>>,
+        context: BacktestRunContext,
     ) -> Result<i64>
+    where
+        S: BackTestAbleStrategyTrait + Send + 'static,
+        S::IndicatorValues: Send + Sync,
+        S::IndicatorCombine: Send + Sync,
+    {
+        self.run_strategy_backtest_with_metrics(
+            inst_id,
+            period,
+            strategy,
+            risk_strategy_config,
+            source_candles,
+            context,
+        )
+        .await
+        .map(|(back_test_id, _)| back_test_id)
+    }
+    /// 与 `run_strategy_backtest` 相同，额外返回落库前从回测结果计算的目标指标。
+    async fn run_strategy_backtest_with_metrics<S>(
+        &self,
+        inst_id: &str,
+        period: &str,
+        strategy: S,
+        risk_strategy_config: BasicRiskStrategyConfig,
+        source_candles: Arc<Vec<CandleItem>>,
+        context: BacktestRunContext,
+    ) -> Result<(i64, BacktestObjectiveMetrics)>
     where
         S: BackTestAbleStrategyTrait + Send + 'static,
         S::IndicatorValues: Send + Sync,
@@ -261,6 +581,9 @@ impl BacktestExecutor {
     {
         let start_time = Instant::now();
         let strategy_type = strategy.strategy_type();
+        let intrabar = context.intrabar;
+        let economic_blackout = context.economic_blackout.as_deref().cloned();
+        let funding = context.funding.as_deref().cloned();
         let compute_start = Instant::now();
         let compute_inst_id = inst_id.to_string();
         let compute_candles = Arc::clone(&source_candles);
@@ -268,13 +591,36 @@ impl BacktestExecutor {
         // 的数据库保存、Redis 进度和停止检查。Semaphore 仍负责限制同时在跑的组合数。
         let (config_desc, res) = tokio::task::spawn_blocking(move || {
             let config_desc = strategy.config_json();
-            let result =
-                strategy.run_test(&compute_inst_id, &compute_candles, risk_strategy_config);
+            let result = if funding.is_none() && intrabar.is_none() && economic_blackout.is_none() {
+                strategy.run_test(&compute_inst_id, &compute_candles, risk_strategy_config)
+            } else {
+                strategy.run_test_with_context(
+                    &compute_inst_id,
+                    &compute_candles,
+                    risk_strategy_config,
+                    funding,
+                    intrabar,
+                    economic_blackout,
+                )
+            };
             (config_desc, result)
         })
         .await
         .map_err(|error| anyhow!("回测计算任务异常退出: {}", error))?;
         let compute_duration = compute_start.elapsed();
+        if let Some(report) = &res.intrabar_report {
+            info!(
+                "[{} 回测] 盘中解析 inst_id={}, period={}, ambiguous_bars={}, resolved_bars={}, missing_data_bars={}, changed_outcomes={}",
+                strategy_type.as_str(),
+                inst_id,
+                period,
+                report.ambiguous_bars,
+                report.resolved_bars,
+                report.missing_data_bars,
+                report.changed_outcomes,
+            );
+        }
+        let metrics = BacktestObjectiveMetrics::from_result(&res);
         let persist_start = Instant::now();
         let back_test_id = self
             .backtest_service
@@ -300,6 +646,43 @@ impl BacktestExecutor {
             compute_duration.as_millis(),
             persist_duration.as_millis(),
         );
-        Ok(back_test_id)
+        Ok((back_test_id, metrics))
+    }
+}
+/// 按相邻两条资金费记录的最小间隔推断结算周期，兼容 8 小时与 1 小时结算的交易所。
+fn funding_interval_ms(rates: &[FundingRate]) -> i64 {
+    let mut times: Vec<i64> = rates.iter().map(|rate| rate.funding_time).collect();
+    times.sort_unstable();
+    times
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .filter(|gap| *gap > 0)
+        .min()
+        .unwrap_or(DEFAULT_FUNDING_INTERVAL_MS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(funding_time: i64) -> FundingRate {
+        FundingRate::new(
+            "BTC-USDT-SWAP".to_string(),
+            0.0001,
+            funding_time,
+            "current_period".to_string(),
+            funding_time,
+        )
+    }
+
+    #[test]
+    fn funding_interval_follows_recorded_settlement_gap() {
+        let hour = 60 * 60 * 1000;
+        let hourly = [rate(3 * hour), rate(hour), rate(2 * hour)];
+        assert_eq!(funding_interval_ms(&hourly), hour);
+        assert_eq!(
+            funding_interval_ms(&[rate(hour)]),
+            DEFAULT_FUNDING_INTERVAL_MS
+        );
     }
 }
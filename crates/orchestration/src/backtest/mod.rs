@@ -1,4 +1,6 @@
 pub mod executor;
 pub mod runner;
+pub mod sensitivity;
+pub mod walk_forward;
 pub use executor::BacktestExecutor;
 pub use runner::*;
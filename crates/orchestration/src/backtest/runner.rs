@@ -1,7 +1,12 @@
 use crate::backtest::executor::BacktestExecutor;
+use crate::backtest::sensitivity::{run_sensitivity_analysis, SensitivityConfig};
+use crate::backtest::walk_forward::{
+    is_vegas_family, run_walk_forward, WalkForwardConfig, WalkForwardSearchSpace,
+};
 use crate::workflow::job_param_generator::{NweParamGenerator, ParamGenerator, ParamMergeBuilder};
+use crate::workflow::param_optimizer::{OptimizerConfig, ParamOptimizer, SearchSpace};
 use crate::workflow::progress_manager::{
-    NweRandomStrategyConfig, RandomStrategyConfig, StrategyProgressManager,
+    NweRandomStrategyConfig, OptimizerProgress, RandomStrategyConfig, StrategyProgressManager,
 };
 use crate::workflow::strategy_config::{
     get_nwe_strategy_config_from_db_with_selector,
@@ -9,12 +14,13 @@ use crate::workflow::strategy_config::{
 };
 use anyhow::{anyhow, Result};
 use rust_quant_core::{config::env_is_true, database::get_db_pool};
+use rust_quant_domain::StrategyType;
 use rust_quant_infrastructure::repositories::{
-    PostgresStrategyConfigRepository, SqlxAuditRepository, SqlxBacktestRepository,
-    SqlxCandleRepository,
+    FileCandleRepository, PostgresStrategyConfigRepository, SqlxAuditRepository,
+    SqlxBacktestRepository, SqlxCandleRepository,
 };
 use rust_quant_market::models::{SelectTime, TimeDirect};
-use rust_quant_services::market::CandleService;
+use rust_quant_services::market::{candle_source_from_env, CandleService, CandleSource};
 use rust_quant_services::strategy::{BacktestService, StrategyConfigService};
 use rust_quant_strategies::implementations::nwe_strategy::NweStrategy;
 use std::sync::Arc;
@@ -50,8 +56,16 @@ impl BacktestRunner {
             Box::new(backtest_repo),
             audit_repository,
         ));
-        let candle_repo = SqlxCandleRepository::new(pool.clone());
-        let candle_service = Arc::new(CandleService::new(Box::new(candle_repo)));
+        // CANDLE_SOURCE=file 时主周期与盘中低周期 K 线都从本地列式文件读取。
+        let candle_repo: Box<dyn rust_quant_domain::traits::CandleRepository> =
+            match candle_source_from_env()? {
+                CandleSource::QuantCore => Box::new(SqlxCandleRepository::new(pool.clone())),
+                CandleSource::File(dir) => {
+                    info!("📁 回测K线数据源: 本地列式文件 {}", dir.display());
+                    Box::new(FileCandleRepository::new(dir))
+                }
+            };
+        let candle_service = Arc::new(CandleService::new(candle_repo));
         let config_service = create_strategy_config_service(pool)?;
         let executor = Arc::new(BacktestExecutor::new(backtest_service, candle_service));
         Ok(Self {
@@ -168,11 +182,44 @@ impl BacktestRunner {
             self.run_vegas_random_backtest(inst_id, period, semaphore.clone(), config)
                 .await?;
         }
+        if let Some(optimizer_config) = &config.vegas_optimizer {
+            executed = true;
+            self.run_vegas_optimizer_backtest(
+                inst_id,
+                period,
+                semaphore.clone(),
+                config,
+                optimizer_config,
+            )
+            .await?;
+        }
         if config.enable_specified_test_vegas {
             executed = true;
             self.run_vegas_specified_backtest(inst_id, period, semaphore.clone(), config)
                 .await?;
         }
+        if let Some(sensitivity_config) = &config.sensitivity {
+            executed = true;
+            self.run_sensitivity_backtest(
+                inst_id,
+                period,
+                semaphore.clone(),
+                config,
+                sensitivity_config,
+            )
+            .await?;
+        }
+        if let Some(walk_forward_config) = &config.walk_forward {
+            executed = true;
+            self.run_walk_forward_backtest(
+                inst_id,
+                period,
+                semaphore.clone(),
+                config,
+                walk_forward_config,
+            )
+            .await?;
+        }
         if !executed {
             warn!("未启用任何回测模式，inst_id={} period={}", inst_id, period);
         }
@@ -202,6 +249,10 @@ impl BacktestRunner {
             .executor
             .load_and_convert_candle_data(inst_id, period, config.candle_limit, None)
             .await?;
+        let context = self
+            .executor
+            .load_backtest_context(inst_id, period, &arc_candle_data)
+            .await?;
         let random_config = build_default_nwe_random_config(config.max_concurrent);
         let progress = match StrategyProgressManager::load_progress(inst_id, period).await? {
             Some(saved) => {
@@ -230,25 +281,7 @@ impl BacktestRunner {
             }
         };
         StrategyProgressManager::save_progress(&progress).await?;
-        let mut generator = NweParamGenerator::new(
-            random_config.stc_fast_length.clone(),
-            random_config.stc_slow_length.clone(),
-            random_config.stc_cycle_length.clone(),
-            random_config.stc_d1_length.clone(),
-            random_config.stc_d2_length.clone(),
-            random_config.rsi_periods.clone(),
-            random_config.rsi_over_buy_sell.clone(),
-            random_config.atr_periods.clone(),
-            random_config.atr_multipliers.clone(),
-            random_config.volume_bar_num.clone(),
-            random_config.volume_ratios.clone(),
-            random_config.nwe_periods.clone(),
-            random_config.nwe_multi.clone(),
-            random_config.max_loss_percent.clone(),
-            random_config.take_profit_ratios.clone(),
-            random_config.is_used_signal_k_line_stop_loss.clone(),
-            random_config.k_line_hammer_shadow_ratios.clone(),
-        );
+        let mut generator = build_nwe_param_generator(&random_config);
         generator.set_current_index(progress.current_index);
         loop {
             let batch = generator.get_next_batch(random_config.batch_size);
@@ -261,6 +294,7 @@ impl BacktestRunner {
                     inst_id,
                     period,
                     arc_candle_data.clone(),
+                    context.clone(),
                     semaphore.clone(),
                 )
                 .await;
@@ -307,6 +341,10 @@ impl BacktestRunner {
             .executor
             .load_and_convert_candle_data(inst_id, period, config.candle_limit, None)
             .await?;
+        let context = self
+            .executor
+            .load_backtest_context(inst_id, period, &arc_candle_data)
+            .await?;
         let pairs = get_nwe_strategy_config_from_db_with_selector(
             &self.config_service,
             inst_id,
@@ -327,7 +365,14 @@ impl BacktestRunner {
             let strategy = NweStrategy::new(cfg);
             match self
                 .executor
-                .run_nwe_test(inst_id, period, strategy, risk_cfg, arc_candle_data.clone())
+                .run_nwe_test(
+                    inst_id,
+                    period,
+                    strategy,
+                    risk_cfg,
+                    arc_candle_data.clone(),
+                    context.clone(),
+                )
                 .await
             {
                 Ok(_) => {
@@ -382,6 +427,10 @@ impl BacktestRunner {
             .executor
             .load_and_convert_candle_data(inst_id, period, config.candle_limit, None)
             .await?;
+        let context = self
+            .executor
+            .load_backtest_context(inst_id, period, &arc_candle_data)
+            .await?;
         let random_config = RandomStrategyConfig::for_target(inst_id, period).with_sampling(
             config.random_sample_size,
             config.random_sample_seed,
@@ -447,6 +496,7 @@ impl BacktestRunner {
                     period,
                     rust_quant_domain::StrategyType::Vegas,
                     arc_candle_data.clone(),
+                    context.clone(),
                     semaphore.clone(),
                 )
                 .await;
@@ -491,6 +541,331 @@ impl BacktestRunner {
         );
         Ok(())
     }
+    /// 执行 Vegas 参数优化搜索
+    /// 与随机回测使用同一参数空间，由优化器按已评估的试验逐批提出候选；
+    /// 每批结束后把试验写入 Redis，配置不变时重跑会从已评估的试验继续。
+    async fn run_vegas_optimizer_backtest(
+        &self,
+        inst_id: &str,
+        period: &str,
+        semaphore: Arc<Semaphore>,
+        config: &BackTestConfig,
+        optimizer_config: &OptimizerConfig,
+    ) -> Result<()> {
+        let start = Instant::now();
+        let arc_candle_data = self
+            .executor
+            .load_and_convert_candle_data(inst_id, period, config.candle_limit, None)
+            .await?;
+        let context = self
+            .executor
+            .load_backtest_context(inst_id, period, &arc_candle_data)
+            .await?;
+        let space_config = RandomStrategyConfig::for_target(inst_id, period);
+        let generator = build_vegas_param_generator(&space_config);
+        let space = SearchSpace::new(generator.dimension_sizes())?;
+        let config_hash =
+            StrategyProgressManager::optimizer_config_hash(&space_config, optimizer_config);
+        let saved_trials =
+            match StrategyProgressManager::load_optimizer_progress(inst_id, period).await? {
+                Some(saved) if saved.config_hash == config_hash => {
+                    if saved.status == "completed" {
+                        info!(
+                            "[Vegas 优化] 已找到完成进度，跳过执行: inst_id={}, period={}",
+                            inst_id, period
+                        );
+                        return Ok(());
+                    }
+                    saved.trials
+                }
+                Some(_) => {
+                    warn!(
+                        "[Vegas 优化] 配置变更，重置进度: inst_id={}, period={}",
+                        inst_id, period
+                    );
+                    vec![]
+                }
+                None => vec![],
+            };
+        let mut optimizer =
+            ParamOptimizer::new(space, optimizer_config.clone()).with_trials(saved_trials);
+        info!(
+            "[Vegas 优化] 策略={}, 参数空间={}, 最大试验={}, 已完成={}, batch={}, seed={}",
+            optimizer.strategy_name(),
+            optimizer.space().size(),
+            optimizer_config.max_trials,
+            optimizer.trials().len(),
+            optimizer_config.batch_size,
+            optimizer_config.seed,
+        );
+        let mut progress = OptimizerProgress {
+            inst_id: inst_id.to_string(),
+            time: period.to_string(),
+            config_hash,
+            strategy: optimizer.strategy_name().to_string(),
+            trials: optimizer.trials().to_vec(),
+            last_update_time: chrono::Utc::now().timestamp_millis(),
+            status: "running".to_string(),
+        };
+        loop {
+            let candidates = optimizer.next_batch();
+            if candidates.is_empty() {
+                break;
+            }
+            let params_batch = candidates
+                .iter()
+                .map(|indices| generator.param_at_indices(indices))
+                .collect();
+            let results = self
+                .executor
+                .evaluate_back_test_strategy(
+                    params_batch,
+                    inst_id,
+                    period,
+                    rust_quant_domain::StrategyType::Vegas,
+                    arc_candle_data.clone(),
+                    context.clone(),
+                    semaphore.clone(),
+                )
+                .await;
+            for (indices, metrics) in candidates.into_iter().zip(results) {
+                optimizer.record(indices, metrics);
+            }
+            progress.trials = optimizer.trials().to_vec();
+            progress.last_update_time = chrono::Utc::now().timestamp_millis();
+            StrategyProgressManager::save_optimizer_progress(&progress).await?;
+            if let Some(best) = optimizer.best() {
+                info!(
+                    "[Vegas 优化] 进度 {}/{}, 最优 score={:.3}, indices={:?}, metrics={:?}",
+                    optimizer.trials().len(),
+                    optimizer_config.max_trials,
+                    best.score,
+                    best.indices,
+                    best.metrics,
+                );
+            }
+        }
+        let stop_reason = optimizer
+            .stop_reason()
+            .map(|reason| reason.as_str())
+            .unwrap_or("no_new_candidates");
+        progress.status = "completed".to_string();
+        progress.last_update_time = chrono::Utc::now().timestamp_millis();
+        StrategyProgressManager::save_optimizer_progress(&progress).await?;
+        for trial in optimizer.pareto_front().into_iter().take(10) {
+            let param = generator.param_at_indices(&trial.indices);
+            info!(
+                "[Vegas 优化] 帕累托前沿 score={:.3}, metrics={:?}, bb={}/{}, volume={}/{}, rsi={}/{}/{}, hammer={}, max_loss={}, take_profit={}",
+                trial.score,
+                trial.metrics,
+                param.bb_period,
+                param.bb_multiplier,
+                param.volume_bar_num,
+                param.volume_increase_ratio,
+                param.rsi_period,
+                param.rsi_overbought,
+                param.rsi_oversold,
+                param.hammer_shadow_ratio,
+                param.max_loss_percent,
+                param.take_profit_ratio,
+            );
+        }
+        info!(
+            "[Vegas 优化] 完成 inst_id={}, period={}, 试验={}, 停止原因={}, 耗时={}ms",
+            inst_id,
+            period,
+            optimizer.trials().len(),
+            stop_reason,
+            start.elapsed().as_millis()
+        );
+        Ok(())
+    }
+    /// 执行指定配置的参数敏感性分析
+    /// 与指定配置回测读取同一组数据库配置，逐个配置扰动参数并输出敏感性曲线、热力图与刀锋参数。
+    async fn run_sensitivity_backtest(
+        &self,
+        inst_id: &str,
+        period: &str,
+        semaphore: Arc<Semaphore>,
+        config: &BackTestConfig,
+        sensitivity_config: &SensitivityConfig,
+    ) -> Result<()> {
+        let start = Instant::now();
+        let strategy_key = config.strategy_key.as_deref().unwrap_or("vegas");
+        let strategy_type: StrategyType = strategy_key
+            .parse()
+            .map_err(|_| anyhow!("无效的策略 Key: {}", strategy_key))?;
+        if strategy_type == StrategyType::Nwe {
+            warn!(
+                "[敏感性] NWE 参数不在 Vegas 参数结构内，跳过 inst_id={}, period={}",
+                inst_id, period
+            );
+            return Ok(());
+        }
+        if !is_vegas_family(strategy_type) {
+            return Err(anyhow!(
+                "敏感性分析暂不支持策略类型 {}：仅支持 Vegas 系列",
+                strategy_key
+            ));
+        }
+        let params_batch = get_strategy_config_from_db_with_strategy_selector(
+            &self.config_service,
+            inst_id,
+            period,
+            strategy_key,
+            config.strategy_config_id.as_deref(),
+        )
+        .await?;
+        if params_batch.is_empty() {
+            warn!(
+                "[敏感性] 未找到策略配置，跳过 inst_id={}, period={}",
+                inst_id, period
+            );
+            return Ok(());
+        }
+        let select_time = derive_select_time(&params_batch);
+        let arc_candle_data = self
+            .executor
+            .load_and_convert_candle_data(inst_id, period, config.candle_limit, select_time)
+            .await?;
+        let context = self
+            .executor
+            .load_backtest_context(inst_id, period, &arc_candle_data)
+            .await?;
+        for (index, param) in params_batch.iter().enumerate() {
+            let report = run_sensitivity_analysis(
+                &self.executor,
+                inst_id,
+                period,
+                strategy_type,
+                param,
+                arc_candle_data.clone(),
+                context.clone(),
+                sensitivity_config,
+                semaphore.clone(),
+            )
+            .await?;
+            for curve in &report.curves {
+                info!(
+                    "[敏感性] {} base={}, points={:?}",
+                    curve.param.as_str(),
+                    curve.base_value,
+                    curve
+                        .points
+                        .iter()
+                        .map(|point| (
+                            point.offset,
+                            point.metrics.map(|metrics| metrics.total_return_pct),
+                            point.metrics.map(|metrics| metrics.max_drawdown_pct),
+                        ))
+                        .collect::<Vec<_>>(),
+                );
+            }
+            if let Some(report_dir) = &sensitivity_config.report_dir {
+                let suffix = match config.strategy_config_id.as_deref() {
+                    Some(config_id) => format!("{}_{}", config_id, index),
+                    None => index.to_string(),
+                };
+                let path = report.write_json(report_dir, &suffix)?;
+                info!("[敏感性] 报告已写入 {}", path.display());
+            }
+            info!(
+                "[敏感性] 配置 {} 完成 inst_id={}, period={}, base={:?}, 热力图={}, 刀锋参数={:?}, 刀锋组合={:?}",
+                index,
+                inst_id,
+                period,
+                report.base,
+                report.heatmaps.len(),
+                report
+                    .knife_edge_params
+                    .iter()
+                    .map(|param| param.as_str())
+                    .collect::<Vec<_>>(),
+                report.knife_edge_pairs,
+            );
+        }
+        info!(
+            "[敏感性] 完成 inst_id={}, period={}, 配置数={}, 耗时={}ms",
+            inst_id,
+            period,
+            params_batch.len(),
+            start.elapsed().as_millis()
+        );
+        Ok(())
+    }
+    /// 执行 walk-forward 回测
+    /// 策略类型取自 `strategy_key`（缺省 vegas）：NWE 搜索 NWE 网格，Vegas 系列在 Vegas 网格上搜索并以该策略身份回测，其余类型报错。
+    async fn run_walk_forward_backtest(
+        &self,
+        inst_id: &str,
+        period: &str,
+        semaphore: Arc<Semaphore>,
+        config: &BackTestConfig,
+        walk_forward_config: &WalkForwardConfig,
+    ) -> Result<()> {
+        let start = Instant::now();
+        let strategy_key = config.strategy_key.as_deref().unwrap_or("vegas");
+        let strategy_type: StrategyType = strategy_key
+            .parse()
+            .map_err(|_| anyhow!("无效的策略 Key: {}", strategy_key))?;
+        let space = match strategy_type {
+            StrategyType::Nwe => WalkForwardSearchSpace::nwe(build_nwe_param_generator(
+                &build_default_nwe_random_config(config.max_concurrent),
+            )),
+            _ => WalkForwardSearchSpace::vegas(
+                build_vegas_param_generator(&RandomStrategyConfig::for_target(inst_id, period)),
+                strategy_type,
+            )?,
+        };
+        let arc_candle_data = self
+            .executor
+            .load_and_convert_candle_data(inst_id, period, config.candle_limit, None)
+            .await?;
+        let context = self
+            .executor
+            .load_backtest_context(inst_id, period, &arc_candle_data)
+            .await?;
+        let report = run_walk_forward(
+            &self.executor,
+            inst_id,
+            period,
+            &space,
+            arc_candle_data,
+            context,
+            walk_forward_config,
+            semaphore,
+        )
+        .await?;
+        for item in &report.parameter_stability {
+            info!(
+                "[Walk-forward] 参数稳定性 {}: stability={:.3}, mode_share={:.2}, distinct={}/{}, selected={:?}",
+                item.name,
+                item.stability,
+                item.mode_share,
+                item.distinct,
+                item.choices,
+                item.selected,
+            );
+        }
+        if let Some(report_dir) = &walk_forward_config.report_dir {
+            let path = report.write_json(report_dir)?;
+            info!("[Walk-forward] 报告已写入 {}", path.display());
+        }
+        info!(
+            "[Walk-forward] 完成 inst_id={}, period={}, strategy={}, windows={}, oos_return={:.2}%, oos_max_drawdown={:.2}%, oos_trades={}, efficiency={:?}, stability={:?}, 耗时={}ms",
+            inst_id,
+            period,
+            report.strategy_type,
+            report.windows.len(),
+            report.oos_total_return_pct,
+            report.oos_max_drawdown_pct,
+            report.oos_trade_count,
+            report.walk_forward_efficiency,
+            report.overall_stability,
+            start.elapsed().as_millis()
+        );
+        Ok(())
+    }
     /// 执行 Vegas 指定配置回测
     async fn run_vegas_specified_backtest(
         &self,
@@ -528,6 +903,10 @@ impl BacktestRunner {
             .executor
             .load_and_convert_candle_data(inst_id, period, config.candle_limit, select_time)
             .await?;
+        let context = self
+            .executor
+            .load_backtest_context(inst_id, period, &arc_candle_data)
+            .await?;
         info!(
             "[Vegas 指定] 找到 {} 个策略配置，开始执行回测",
             params_batch.len()
@@ -539,6 +918,7 @@ impl BacktestRunner {
                 period,
                 strategy_type,
                 arc_candle_data,
+                context,
                 semaphore,
             )
             .await;
@@ -583,6 +963,45 @@ fn derive_select_time(params: &[ParamMergeBuilder]) -> Option<SelectTime> {
         (None, None) => None,
     }
 }
+/// 用 Vegas 随机配置的取值列表构造完整网格，供参数优化器与 walk-forward 共用。
+fn build_vegas_param_generator(space_config: &RandomStrategyConfig) -> ParamGenerator {
+    ParamGenerator::new(
+        space_config.bb_periods.clone(),
+        space_config.k_line_hammer_shadow_ratios.clone(),
+        space_config.bb_multipliers.clone(),
+        space_config.volume_bar_nums.clone(),
+        space_config.volume_ratios.clone(),
+        space_config.breakthrough_thresholds.clone(),
+        space_config.rsi_periods.clone(),
+        space_config.rsi_over_buy_sell.clone(),
+        space_config.max_loss_percent.clone(),
+        space_config.take_profit_ratios.clone(),
+        space_config.is_used_signal_k_line_stop_loss.clone(),
+        space_config.fix_signal_kline_take_profit_ratios.clone(),
+    )
+}
+/// 用 NWE 随机配置的取值列表构造网格。
+fn build_nwe_param_generator(random_config: &NweRandomStrategyConfig) -> NweParamGenerator {
+    NweParamGenerator::new(
+        random_config.stc_fast_length.clone(),
+        random_config.stc_slow_length.clone(),
+        random_config.stc_cycle_length.clone(),
+        random_config.stc_d1_length.clone(),
+        random_config.stc_d2_length.clone(),
+        random_config.rsi_periods.clone(),
+        random_config.rsi_over_buy_sell.clone(),
+        random_config.atr_periods.clone(),
+        random_config.atr_multipliers.clone(),
+        random_config.volume_bar_num.clone(),
+        random_config.volume_ratios.clone(),
+        random_config.nwe_periods.clone(),
+        random_config.nwe_multi.clone(),
+        random_config.max_loss_percent.clone(),
+        random_config.take_profit_ratios.clone(),
+        random_config.is_used_signal_k_line_stop_loss.clone(),
+        random_config.k_line_hammer_shadow_ratios.clone(),
+    )
+}
 /// 构建默认的 NWE 随机配置
 fn build_default_nwe_random_config(batch_size: usize) -> NweRandomStrategyConfig {
     NweRandomStrategyConfig {
@@ -610,7 +1029,10 @@ fn build_default_nwe_random_config(batch_size: usize) -> NweRandomStrategyConfig
 mod tests {
     use super::derive_select_time;
     use super::validate_strategy_config_source;
+    use super::{build_vegas_param_generator, WalkForwardSearchSpace};
     use crate::workflow::job_param_generator::ParamMergeBuilder;
+    use crate::workflow::progress_manager::RandomStrategyConfig;
+    use rust_quant_domain::StrategyType;
     use rust_quant_market::models::TimeDirect;
     use std::sync::Mutex;
     static ENV_LOCK: Mutex<()> = Mutex::new(());
@@ -635,6 +1057,25 @@ mod tests {
         assert!(derive_select_time(&params).is_none());
     }
     #[test]
+    fn walk_forward_vegas_space_rejects_non_vegas_strategies() {
+        let generator = || {
+            build_vegas_param_generator(&RandomStrategyConfig::for_target("BTC-USDT-SWAP", "4H"))
+        };
+        let space = WalkForwardSearchSpace::vegas(generator(), StrategyType::VegasUniversal4h)
+            .expect("vegas family uses the vegas grid");
+        assert_eq!(space.strategy_type(), StrategyType::VegasUniversal4h);
+        for strategy_type in [
+            StrategyType::MacdKdj,
+            StrategyType::BscEventArb,
+            StrategyType::Nwe,
+        ] {
+            let err = WalkForwardSearchSpace::vegas(generator(), strategy_type)
+                .err()
+                .expect("non-vegas strategy must be rejected");
+            assert!(err.to_string().contains(strategy_type.as_str()));
+        }
+    }
+    #[test]
     fn quant_core_strategy_config_source_rejects_legacy_env() {
         let _guard = ENV_LOCK.lock().expect("env lock");
         let original_source = std::env::var("STRATEGY_CONFIG_SOURCE").ok();
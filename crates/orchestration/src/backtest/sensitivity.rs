@@ -0,0 +1,842 @@
+//! 参数敏感性分析
+//!
+//! 对一组选定的 Vegas 参数，逐个按比例扰动 `ParamMergeBuilder` 的数值字段（默认 ±10%/±20%）并重跑回测，
+//! 得到单参数敏感性曲线；再对单参数影响最大的几个字段两两组合扰动，得到收益/回撤热力图。
+//! 邻域（默认 ±10%）内收益大幅缩水或回撤显著放大的参数标记为“刀锋”参数，提示该最优点可能过拟合。
+use crate::backtest::executor::{BacktestExecutor, BacktestRunContext};
+use crate::infra::job_param_generator::ParamMergeBuilder;
+use crate::infra::param_optimizer::BacktestObjectiveMetrics;
+use anyhow::{anyhow, Result};
+use rust_quant_common::CandleItem;
+use rust_quant_domain::StrategyType;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
+/// 判断回撤放大倍数时基准回撤的下限（%），避免基准几乎无回撤时倍数失真。
+const MIN_DRAWDOWN_BASELINE_PCT: f64 = 1.0;
+/// 浮点偏移比较容差。
+const OFFSET_EPSILON: f64 = 1e-9;
+/// 可扰动的 `ParamMergeBuilder` 数值字段。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitivityParam {
+    BbPeriod,
+    BbMultiplier,
+    HammerShadowRatio,
+    VolumeBarNum,
+    VolumeIncreaseRatio,
+    VolumeDecreaseRatio,
+    BreakthroughThreshold,
+    RsiPeriod,
+    RsiOverbought,
+    RsiOversold,
+    MaxLossPercent,
+    TakeProfitRatio,
+    FixSignalKlineTakeProfitRatio,
+}
+impl SensitivityParam {
+    pub const ALL: [Self; 13] = [
+        Self::BbPeriod,
+        Self::BbMultiplier,
+        Self::HammerShadowRatio,
+        Self::VolumeBarNum,
+        Self::VolumeIncreaseRatio,
+        Self::VolumeDecreaseRatio,
+        Self::BreakthroughThreshold,
+        Self::RsiPeriod,
+        Self::RsiOverbought,
+        Self::RsiOversold,
+        Self::MaxLossPercent,
+        Self::TakeProfitRatio,
+        Self::FixSignalKlineTakeProfitRatio,
+    ];
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BbPeriod => "bb_period",
+            Self::BbMultiplier => "bb_multiplier",
+            Self::HammerShadowRatio => "hammer_shadow_ratio",
+            Self::VolumeBarNum => "volume_bar_num",
+            Self::VolumeIncreaseRatio => "volume_increase_ratio",
+            Self::VolumeDecreaseRatio => "volume_decrease_ratio",
+            Self::BreakthroughThreshold => "breakthrough_threshold",
+            Self::RsiPeriod => "rsi_period",
+            Self::RsiOverbought => "rsi_overbought",
+            Self::RsiOversold => "rsi_oversold",
+            Self::MaxLossPercent => "max_loss_percent",
+            Self::TakeProfitRatio => "take_profit_ratio",
+            Self::FixSignalKlineTakeProfitRatio => "fix_signal_kline_take_profit_ratio",
+        }
+    }
+    /// 周期/根数类字段扰动后四舍五入为整数，且不小于 1。
+    fn is_integer(&self) -> bool {
+        matches!(self, Self::BbPeriod | Self::VolumeBarNum | Self::RsiPeriod)
+    }
+    /// 当前取值；未配置（None）时返回 None，不参与扰动。
+    pub fn value(&self, param: &ParamMergeBuilder) -> Option<f64> {
+        Some(match self {
+            Self::BbPeriod => param.bb_period as f64,
+            Self::BbMultiplier => param.bb_multiplier,
+            Self::HammerShadowRatio => param.hammer_shadow_ratio,
+            Self::VolumeBarNum => param.volume_bar_num as f64,
+            Self::VolumeIncreaseRatio => param.volume_increase_ratio,
+            Self::VolumeDecreaseRatio => param.volume_decrease_ratio,
+            Self::BreakthroughThreshold => param.breakthrough_threshold,
+            Self::RsiPeriod => param.rsi_period as f64,
+            Self::RsiOverbought => param.rsi_overbought,
+            Self::RsiOversold => param.rsi_oversold,
+            Self::MaxLossPercent => param.max_loss_percent,
+            Self::TakeProfitRatio => param.take_profit_ratio,
+            Self::FixSignalKlineTakeProfitRatio => param.fix_signal_kline_take_profit_ratio?,
+        })
+    }
+    fn apply(&self, param: &mut ParamMergeBuilder, value: f64) {
+        match self {
+            Self::BbPeriod => param.bb_period = value as i32,
+            Self::BbMultiplier => param.bb_multiplier = value,
+            Self::HammerShadowRatio => param.hammer_shadow_ratio = value,
+            Self::VolumeBarNum => param.volume_bar_num = value as usize,
+            Self::VolumeIncreaseRatio => param.volume_increase_ratio = value,
+            Self::VolumeDecreaseRatio => param.volume_decrease_ratio = value,
+            Self::BreakthroughThreshold => param.breakthrough_threshold = value,
+            Self::RsiPeriod => param.rsi_period = value as usize,
+            Self::RsiOverbought => param.rsi_overbought = value,
+            Self::RsiOversold => param.rsi_oversold = value,
+            Self::MaxLossPercent => param.max_loss_percent = value,
+            Self::TakeProfitRatio => param.take_profit_ratio = value,
+            Self::FixSignalKlineTakeProfitRatio => {
+                param.fix_signal_kline_take_profit_ratio = Some(value)
+            }
+        }
+    }
+}
+impl FromStr for SensitivityParam {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value.trim();
+        Self::ALL
+            .into_iter()
+            .find(|param| param.as_str().eq_ignore_ascii_case(value))
+            .ok_or_else(|| anyhow!("不支持的敏感性分析参数: {}", value))
+    }
+}
+/// 敏感性分析配置。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensitivityConfig {
+    /// 相对基准值的扰动比例，如 -0.2 表示 -20%。
+    #[serde(default = "default_offsets")]
+    pub offsets: Vec<f64>,
+    /// 参与扰动的字段；为空时扰动全部已配置且非零的字段。
+    #[serde(default)]
+    pub params: Vec<SensitivityParam>,
+    /// 按单参数收益波动挑选前 N 个字段两两组合生成热力图；0 表示不生成。
+    #[serde(default = "default_pair_top_k")]
+    pub pair_top_k: usize,
+    /// 邻域半径，绝对值不超过该比例的扰动视为基准点的邻居。
+    #[serde(default = "default_neighbourhood")]
+    pub neighbourhood: f64,
+    /// 邻居收益低于基准收益的该比例时视为收益塌陷。
+    #[serde(default = "default_min_return_retention")]
+    pub min_return_retention: f64,
+    /// 邻居回撤超过基准回撤的该倍数时视为回撤失控。
+    #[serde(default = "default_max_drawdown_ratio")]
+    pub max_drawdown_ratio: f64,
+    /// 报告 JSON 输出目录；为空时只写日志。
+    #[serde(default)]
+    pub report_dir: Option<String>,
+}
+fn default_offsets() -> Vec<f64> {
+    vec![-0.2, -0.1, 0.1, 0.2]
+}
+fn default_pair_top_k() -> usize {
+    4
+}
+fn default_neighbourhood() -> f64 {
+    0.1
+}
+fn default_min_return_retention() -> f64 {
+    0.5
+}
+fn default_max_drawdown_ratio() -> f64 {
+    2.0
+}
+impl Default for SensitivityConfig {
+    fn default() -> Self {
+        Self {
+            offsets: default_offsets(),
+            params: vec![],
+            pair_top_k: default_pair_top_k(),
+            neighbourhood: default_neighbourhood(),
+            min_return_retention: default_min_return_retention(),
+            max_drawdown_ratio: default_max_drawdown_ratio(),
+            report_dir: None,
+        }
+    }
+}
+impl SensitivityConfig {
+    pub fn with_offsets(mut self, offsets: Vec<f64>) -> Self {
+        self.offsets = offsets;
+        self
+    }
+    pub fn with_params(mut self, params: Vec<SensitivityParam>) -> Self {
+        self.params = params;
+        self
+    }
+    pub fn with_pair_top_k(mut self, pair_top_k: usize) -> Self {
+        self.pair_top_k = pair_top_k;
+        self
+    }
+    pub fn with_knife_edge_thresholds(
+        mut self,
+        neighbourhood: f64,
+        min_return_retention: f64,
+        max_drawdown_ratio: f64,
+    ) -> Self {
+        self.neighbourhood = neighbourhood;
+        self.min_return_retention = min_return_retention;
+        self.max_drawdown_ratio = max_drawdown_ratio;
+        self
+    }
+    pub fn with_report_dir(mut self, report_dir: impl Into<String>) -> Self {
+        self.report_dir = Some(report_dir.into());
+        self
+    }
+    /// 去掉 0、非有限值和重复项后按升序排列的扰动比例。
+    fn normalized_offsets(&self) -> Vec<f64> {
+        let mut offsets: Vec<f64> = self
+            .offsets
+            .iter()
+            .copied()
+            .filter(|offset| offset.is_finite() && offset.abs() > OFFSET_EPSILON && *offset > -1.0)
+            .collect();
+        offsets.sort_by(|a, b| a.total_cmp(b));
+        offsets.dedup_by(|a, b| (*a - *b).abs() <= OFFSET_EPSILON);
+        offsets
+    }
+    fn is_neighbour(&self, offset: f64) -> bool {
+        offset.abs() <= self.neighbourhood + OFFSET_EPSILON
+    }
+}
+/// 按比例扰动基准值，返回 `(扰动比例, 扰动后取值)`；整数字段取整后与基准或前一个点重合的扰动被丢弃。
+pub fn perturbed_values(
+    param: SensitivityParam,
+    base_value: f64,
+    offsets: &[f64],
+) -> Vec<(f64, f64)> {
+    let mut values: Vec<(f64, f64)> = Vec::with_capacity(offsets.len());
+    for offset in offsets {
+        let mut value = base_value * (1.0 + offset);
+        if param.is_integer() {
+            value = value.round().max(1.0);
+        }
+        let duplicate = (value - base_value).abs() <= OFFSET_EPSILON
+            || values
+                .iter()
+                .any(|(_, existing)| (existing - value).abs() <= OFFSET_EPSILON);
+        if !duplicate {
+            values.push((*offset, value));
+        }
+    }
+    values
+}
+/// 一组字段取值的扰动；与基准相同的字段不记录，保证同一组实际参数只回测一次。
+type Perturbation = Vec<(SensitivityParam, f64)>;
+fn perturbation_key(perturbation: &[(SensitivityParam, f64)]) -> Vec<(SensitivityParam, u64)> {
+    let mut key: Vec<(SensitivityParam, u64)> = perturbation
+        .iter()
+        .map(|(param, value)| (*param, value.to_bits()))
+        .collect();
+    key.sort();
+    key
+}
+/// 基准点邻域的评估结果。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NeighbourhoodAssessment {
+    /// 邻居收益 / 基准收益的最小值；基准不盈利或没有成功的邻居时为空。
+    pub worst_return_retention: Option<f64>,
+    /// 邻居回撤 / 基准回撤的最大值，基准回撤低于 1% 时按 1% 计。
+    pub max_drawdown_ratio: Option<f64>,
+    /// 邻域内收益塌陷或回撤失控。
+    pub knife_edge: bool,
+}
+/// 回测失败的邻居不参与判断；基准本身不盈利时不存在“塌陷”，只看回撤是否失控。
+pub fn assess_neighbourhood(
+    base: &BacktestObjectiveMetrics,
+    neighbours: &[Option<BacktestObjectiveMetrics>],
+    config: &SensitivityConfig,
+) -> NeighbourhoodAssessment {
+    let evaluated: Vec<&BacktestObjectiveMetrics> = neighbours.iter().flatten().collect();
+    let worst_return_retention = (base.total_return_pct > 0.0)
+        .then(|| {
+            evaluated
+                .iter()
+                .map(|metrics| metrics.total_return_pct / base.total_return_pct)
+                .min_by(|a, b| a.total_cmp(b))
+        })
+        .flatten();
+    let drawdown_baseline = base.max_drawdown_pct.max(MIN_DRAWDOWN_BASELINE_PCT);
+    let max_drawdown_ratio = evaluated
+        .iter()
+        .map(|metrics| metrics.max_drawdown_pct / drawdown_baseline)
+        .max_by(|a, b| a.total_cmp(b));
+    let knife_edge = worst_return_retention
+        .map(|retention| retention < config.min_return_retention)
+        .unwrap_or(false)
+        || max_drawdown_ratio
+            .map(|ratio| ratio > config.max_drawdown_ratio)
+            .unwrap_or(false);
+    NeighbourhoodAssessment {
+        worst_return_retention,
+        max_drawdown_ratio,
+        knife_edge,
+    }
+}
+/// 敏感性曲线上的一个点；扰动比例为 0 的点即基准。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SensitivityPoint {
+    pub offset: f64,
+    pub value: f64,
+    /// 回测失败时为空。
+    pub metrics: Option<BacktestObjectiveMetrics>,
+}
+/// 单参数敏感性曲线。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensitivityCurve {
+    pub param: SensitivityParam,
+    pub base_value: f64,
+    /// 按扰动比例升序，包含基准点。
+    pub points: Vec<SensitivityPoint>,
+    /// 曲线上最高与最低收益之差（百分点），用于挑选热力图字段。
+    pub return_range_pct: f64,
+    pub neighbourhood: NeighbourhoodAssessment,
+}
+impl SensitivityCurve {
+    fn build(
+        param: SensitivityParam,
+        base_value: f64,
+        base: &BacktestObjectiveMetrics,
+        perturbed: Vec<SensitivityPoint>,
+        config: &SensitivityConfig,
+    ) -> Self {
+        let neighbours: Vec<Option<BacktestObjectiveMetrics>> = perturbed
+            .iter()
+            .filter(|point| config.is_neighbour(point.offset))
+            .map(|point| point.metrics)
+            .collect();
+        let mut points = perturbed;
+        points.push(SensitivityPoint {
+            offset: 0.0,
+            value: base_value,
+            metrics: Some(*base),
+        });
+        points.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        let returns = points
+            .iter()
+            .filter_map(|point| point.metrics.map(|metrics| metrics.total_return_pct));
+        let (min_return, max_return) = returns.fold((f64::MAX, f64::MIN), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+        Self {
+            param,
+            base_value,
+            points,
+            return_range_pct: max_return - min_return,
+            neighbourhood: assess_neighbourhood(base, &neighbours, config),
+        }
+    }
+}
+/// 两个参数组合扰动的热力图；矩阵行对应 `y`，列对应 `x`，中心格为基准。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensitivityHeatmap {
+    pub x: SensitivityParam,
+    pub y: SensitivityParam,
+    pub x_offsets: Vec<f64>,
+    pub y_offsets: Vec<f64>,
+    pub x_values: Vec<f64>,
+    pub y_values: Vec<f64>,
+    pub return_pct: Vec<Vec<Option<f64>>>,
+    pub max_drawdown_pct: Vec<Vec<Option<f64>>>,
+    /// 基准盈利时，非基准格中收益保持在阈值以上的比例。
+    pub robust_share: Option<f64>,
+    /// 两个字段都落在邻域内的格子是否出现收益塌陷或回撤失控。
+    pub neighbourhood: NeighbourhoodAssessment,
+}
+/// 一个轴上的取值：`(扰动比例, 取值)`，包含比例为 0 的基准。
+type HeatmapAxis = Vec<(f64, f64)>;
+fn heatmap_axis(param: SensitivityParam, base_value: f64, offsets: &[f64]) -> HeatmapAxis {
+    let mut axis = perturbed_values(param, base_value, offsets);
+    axis.push((0.0, base_value));
+    axis.sort_by(|a, b| a.0.total_cmp(&b.0));
+    axis
+}
+impl SensitivityHeatmap {
+    fn build(
+        x: (SensitivityParam, HeatmapAxis),
+        y: (SensitivityParam, HeatmapAxis),
+        base: &BacktestObjectiveMetrics,
+        cell: impl Fn(usize, usize) -> Option<BacktestObjectiveMetrics>,
+        config: &SensitivityConfig,
+    ) -> Self {
+        let (x_param, x_axis) = x;
+        let (y_param, y_axis) = y;
+        let mut return_pct = Vec::with_capacity(y_axis.len());
+        let mut max_drawdown_pct = Vec::with_capacity(y_axis.len());
+        let mut neighbours = Vec::new();
+        let mut others = Vec::new();
+        for (row, (y_offset, _)) in y_axis.iter().enumerate() {
+            let mut return_row = Vec::with_capacity(x_axis.len());
+            let mut drawdown_row = Vec::with_capacity(x_axis.len());
+            for (col, (x_offset, _)) in x_axis.iter().enumerate() {
+                let metrics = cell(row, col);
+                return_row.push(metrics.map(|metrics| metrics.total_return_pct));
+                drawdown_row.push(metrics.map(|metrics| metrics.max_drawdown_pct));
+                if x_offset.abs() <= OFFSET_EPSILON && y_offset.abs() <= OFFSET_EPSILON {
+                    continue;
+                }
+                others.push(metrics);
+                if config.is_neighbour(*x_offset) && config.is_neighbour(*y_offset) {
+                    neighbours.push(metrics);
+                }
+            }
+            return_pct.push(return_row);
+            max_drawdown_pct.push(drawdown_row);
+        }
+        let evaluated: Vec<&BacktestObjectiveMetrics> = others.iter().flatten().collect();
+        let robust_share = (base.total_return_pct > 0.0 && !evaluated.is_empty()).then(|| {
+            let robust = evaluated
+                .iter()
+                .filter(|metrics| {
+                    metrics.total_return_pct / base.total_return_pct >= config.min_return_retention
+                })
+                .count();
+            robust as f64 / evaluated.len() as f64
+        });
+        Self {
+            x: x_param,
+            y: y_param,
+            x_offsets: x_axis.iter().map(|(offset, _)| *offset).collect(),
+            y_offsets: y_axis.iter().map(|(offset, _)| *offset).collect(),
+            x_values: x_axis.iter().map(|(_, value)| *value).collect(),
+            y_values: y_axis.iter().map(|(_, value)| *value).collect(),
+            return_pct,
+            max_drawdown_pct,
+            robust_share,
+            neighbourhood: assess_neighbourhood(base, &neighbours, config),
+        }
+    }
+}
+/// 按单参数收益波动从大到小挑选前 `top_k` 个字段，返回两两组合。
+pub fn heatmap_pairs(
+    curves: &[SensitivityCurve],
+    top_k: usize,
+) -> Vec<(SensitivityParam, SensitivityParam)> {
+    let mut ranked: Vec<&SensitivityCurve> = curves
+        .iter()
+        .filter(|curve| curve.return_range_pct.is_finite())
+        .collect();
+    ranked.sort_by(|a, b| b.return_range_pct.total_cmp(&a.return_range_pct));
+    let selected: Vec<SensitivityParam> = ranked
+        .into_iter()
+        .take(top_k)
+        .map(|curve| curve.param)
+        .collect();
+    let mut pairs = Vec::new();
+    for (i, x) in selected.iter().enumerate() {
+        for y in &selected[i + 1..] {
+            pairs.push((*x, *y));
+        }
+    }
+    pairs
+}
+/// 参数敏感性报告。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensitivityReport {
+    pub inst_id: String,
+    pub period: String,
+    pub strategy_type: String,
+    pub config: SensitivityConfig,
+    /// 基准参数的策略/风控配置。
+    pub base_params: serde_json::Value,
+    pub base: BacktestObjectiveMetrics,
+    pub curves: Vec<SensitivityCurve>,
+    pub heatmaps: Vec<SensitivityHeatmap>,
+    /// 单参数邻域塌陷的字段。
+    pub knife_edge_params: Vec<SensitivityParam>,
+    /// 组合邻域塌陷的字段对。
+    pub knife_edge_pairs: Vec<(SensitivityParam, SensitivityParam)>,
+}
+impl SensitivityReport {
+    /// 是否存在刀锋参数或刀锋组合。
+    pub fn is_knife_edge(&self) -> bool {
+        !self.knife_edge_params.is_empty() || !self.knife_edge_pairs.is_empty()
+    }
+    /// 写入 `{dir}/sensitivity_{inst_id}_{period}_{strategy}_{suffix}.json`，返回文件路径。
+    pub fn write_json(&self, dir: impl AsRef<Path>, suffix: &str) -> Result<PathBuf> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "sensitivity_{}_{}_{}_{}.json",
+            self.inst_id, self.period, self.strategy_type, suffix
+        ));
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+/// 按扰动缓存回测结果，同一组实际参数只回测一次。
+struct SensitivityEvaluator<'a> {
+    executor: &'a BacktestExecutor,
+    inst_id: &'a str,
+    period: &'a str,
+    strategy_type: StrategyType,
+    base: &'a ParamMergeBuilder,
+    candles: Arc<Vec<CandleItem>>,
+    context: BacktestRunContext,
+    semaphore: Arc<Semaphore>,
+    batch_size: usize,
+    results: HashMap<Vec<(SensitivityParam, u64)>, Option<BacktestObjectiveMetrics>>,
+}
+impl SensitivityEvaluator<'_> {
+    fn get(&self, perturbation: &[(SensitivityParam, f64)]) -> Option<BacktestObjectiveMetrics> {
+        self.results
+            .get(&perturbation_key(perturbation))
+            .copied()
+            .flatten()
+    }
+    async fn evaluate(&mut self, perturbations: Vec<Perturbation>) {
+        let mut pending: Vec<Perturbation> = Vec::new();
+        for perturbation in perturbations {
+            let key = perturbation_key(&perturbation);
+            if self.results.contains_key(&key)
+                || pending
+                    .iter()
+                    .any(|existing| perturbation_key(existing) == key)
+            {
+                continue;
+            }
+            pending.push(perturbation);
+        }
+        for chunk in pending.chunks(self.batch_size.max(1)) {
+            let params_batch = chunk
+                .iter()
+                .map(|perturbation| {
+                    let mut param = self.base.clone();
+                    for (field, value) in perturbation {
+                        field.apply(&mut param, *value);
+                    }
+                    param
+                })
+                .collect();
+            let metrics = self
+                .executor
+                .evaluate_back_test_strategy(
+                    params_batch,
+                    self.inst_id,
+                    self.period,
+                    self.strategy_type,
+                    Arc::clone(&self.candles),
+                    self.context.clone(),
+                    Arc::clone(&self.semaphore),
+                )
+                .await;
+            for (perturbation, metrics) in chunk.iter().zip(metrics) {
+                self.results.insert(perturbation_key(perturbation), metrics);
+            }
+        }
+    }
+}
+/// 对一组参数执行敏感性分析：先跑基准与单参数扰动，再对影响最大的字段两两组合扰动。
+#[allow(clippy::too_many_arguments)]
+pub async fn run_sensitivity_analysis(
+    executor: &BacktestExecutor,
+    inst_id: &str,
+    period: &str,
+    strategy_type: StrategyType,
+    base: &ParamMergeBuilder,
+    candles: Arc<Vec<CandleItem>>,
+    context: BacktestRunContext,
+    config: &SensitivityConfig,
+    semaphore: Arc<Semaphore>,
+) -> Result<SensitivityReport> {
+    let offsets = config.normalized_offsets();
+    if offsets.is_empty() {
+        return Err(anyhow!("敏感性分析至少需要一个非零扰动比例"));
+    }
+    let fields: Vec<(SensitivityParam, f64)> = if config.params.is_empty() {
+        SensitivityParam::ALL.to_vec()
+    } else {
+        config.params.clone()
+    }
+    .into_iter()
+    .filter_map(|param| param.value(base).map(|value| (param, value)))
+    .filter(|(_, value)| value.is_finite() && value.abs() > OFFSET_EPSILON)
+    .collect();
+    let mut evaluator = SensitivityEvaluator {
+        executor,
+        inst_id,
+        period,
+        strategy_type,
+        base,
+        candles,
+        context,
+        batch_size: semaphore.available_permits().max(1) * 2,
+        semaphore,
+        results: HashMap::new(),
+    };
+    evaluator.evaluate(vec![vec![]]).await;
+    let base_metrics = evaluator.get(&[]).ok_or_else(|| {
+        anyhow!(
+            "敏感性分析基准回测失败: inst_id={}, period={}",
+            inst_id,
+            period
+        )
+    })?;
+    info!(
+        "[敏感性] 开始 inst_id={}, period={}, strategy={}, 字段={}, 扰动={:?}, 基准={:?}",
+        inst_id,
+        period,
+        strategy_type.as_str(),
+        fields.len(),
+        offsets,
+        base_metrics,
+    );
+    let single: Vec<Perturbation> = fields
+        .iter()
+        .flat_map(|(param, base_value)| {
+            perturbed_values(*param, *base_value, &offsets)
+                .into_iter()
+                .map(|(_, value)| vec![(*param, value)])
+        })
+        .collect();
+    evaluator.evaluate(single).await;
+    let curves: Vec<SensitivityCurve> = fields
+        .iter()
+        .map(|(param, base_value)| {
+            let points = perturbed_values(*param, *base_value, &offsets)
+                .into_iter()
+                .map(|(offset, value)| SensitivityPoint {
+                    offset,
+                    value,
+                    metrics: evaluator.get(&[(*param, value)]),
+                })
+                .collect();
+            SensitivityCurve::build(*param, *base_value, &base_metrics, points, config)
+        })
+        .collect();
+    let base_values: HashMap<SensitivityParam, f64> = fields.iter().copied().collect();
+    let mut heatmaps = Vec::new();
+    for (x, y) in heatmap_pairs(&curves, config.pair_top_k) {
+        let x_axis = heatmap_axis(x, base_values[&x], &offsets);
+        let y_axis = heatmap_axis(y, base_values[&y], &offsets);
+        let cell_perturbation = |row: usize, col: usize| -> Perturbation {
+            let mut perturbation = Vec::with_capacity(2);
+            if x_axis[col].0.abs() > OFFSET_EPSILON {
+                perturbation.push((x, x_axis[col].1));
+            }
+            if y_axis[row].0.abs() > OFFSET_EPSILON {
+                perturbation.push((y, y_axis[row].1));
+            }
+            perturbation
+        };
+        let cells: Vec<Perturbation> = (0..y_axis.len())
+            .flat_map(|row| (0..x_axis.len()).map(move |col| (row, col)))
+            .map(|(row, col)| cell_perturbation(row, col))
+            .collect();
+        evaluator.evaluate(cells).await;
+        let heatmap = SensitivityHeatmap::build(
+            (x, x_axis.clone()),
+            (y, y_axis.clone()),
+            &base_metrics,
+            |row, col| evaluator.get(&cell_perturbation(row, col)),
+            config,
+        );
+        info!(
+            "[敏感性] 热力图 {} × {}: robust_share={:?}, neighbourhood={:?}",
+            x.as_str(),
+            y.as_str(),
+            heatmap.robust_share,
+            heatmap.neighbourhood,
+        );
+        heatmaps.push(heatmap);
+    }
+    let knife_edge_params: Vec<SensitivityParam> = curves
+        .iter()
+        .filter(|curve| curve.neighbourhood.knife_edge)
+        .map(|curve| curve.param)
+        .collect();
+    let knife_edge_pairs: Vec<(SensitivityParam, SensitivityParam)> = heatmaps
+        .iter()
+        .filter(|heatmap| heatmap.neighbourhood.knife_edge)
+        .map(|heatmap| (heatmap.x, heatmap.y))
+        .collect();
+    for curve in curves.iter().filter(|curve| curve.neighbourhood.knife_edge) {
+        warn!(
+            "[敏感性] 刀锋参数 {}={}: worst_return_retention={:?}, max_drawdown_ratio={:?}, inst_id={}, period={}",
+            curve.param.as_str(),
+            curve.base_value,
+            curve.neighbourhood.worst_return_retention,
+            curve.neighbourhood.max_drawdown_ratio,
+            inst_id,
+            period,
+        );
+    }
+    Ok(SensitivityReport {
+        inst_id: inst_id.to_string(),
+        period: period.to_string(),
+        strategy_type: strategy_type.as_str().to_string(),
+        config: config.clone(),
+        base_params: json!({
+            "strategy": base.to_vegas_strategy(period.to_string()),
+            "risk": base.to_risk_config(),
+        }),
+        base: base_metrics,
+        curves,
+        heatmaps,
+        knife_edge_params,
+        knife_edge_pairs,
+    })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(total_return_pct: f64, max_drawdown_pct: f64) -> BacktestObjectiveMetrics {
+        BacktestObjectiveMetrics {
+            total_return_pct,
+            max_drawdown_pct,
+            trade_count: 20,
+        }
+    }
+
+    #[test]
+    fn perturbed_values_round_integer_fields_and_drop_duplicates() {
+        let offsets = SensitivityConfig::default().normalized_offsets();
+        let values = perturbed_values(SensitivityParam::BbPeriod, 4.0, &offsets);
+        // 4 × 0.9 与 4 × 1.1 取整后回到 4，被丢弃。
+        assert_eq!(values, vec![(-0.2, 3.0), (0.2, 5.0)]);
+        let values = perturbed_values(SensitivityParam::MaxLossPercent, 0.02, &offsets);
+        assert_eq!(values.len(), 4);
+        assert!((values[0].1 - 0.016).abs() < 1e-12);
+        assert!((values[3].1 - 0.024).abs() < 1e-12);
+        let config = SensitivityConfig::default().with_offsets(vec![0.1, 0.0, -0.1, 0.1, -1.5]);
+        assert_eq!(config.normalized_offsets(), vec![-0.1, 0.1]);
+        assert_eq!(
+            "RSI_PERIOD".parse::<SensitivityParam>().unwrap(),
+            SensitivityParam::RsiPeriod
+        );
+        assert!("ema_signal".parse::<SensitivityParam>().is_err());
+    }
+
+    #[test]
+    fn flags_knife_edge_when_neighbourhood_collapses() {
+        let config = SensitivityConfig::default();
+        let base = metrics(40.0, 10.0);
+        let robust = assess_neighbourhood(&base, &[Some(metrics(30.0, 12.0)), None], &config);
+        assert_eq!(robust.worst_return_retention, Some(0.75));
+        assert!(!robust.knife_edge);
+        let collapsed = assess_neighbourhood(
+            &base,
+            &[Some(metrics(36.0, 11.0)), Some(metrics(-4.0, 15.0))],
+            &config,
+        );
+        assert_eq!(collapsed.worst_return_retention, Some(-0.1));
+        assert!(collapsed.knife_edge);
+        let blown_drawdown = assess_neighbourhood(&base, &[Some(metrics(38.0, 25.0))], &config);
+        assert_eq!(blown_drawdown.max_drawdown_ratio, Some(2.5));
+        assert!(blown_drawdown.knife_edge);
+        let losing_base =
+            assess_neighbourhood(&metrics(-5.0, 0.5), &[Some(metrics(-9.0, 1.5))], &config);
+        assert_eq!(losing_base.worst_return_retention, None);
+        assert!(!losing_base.knife_edge);
+    }
+
+    #[test]
+    fn curve_only_uses_neighbours_and_heatmap_pairs_follow_return_range() {
+        let config = SensitivityConfig::default();
+        let base = metrics(40.0, 10.0);
+        let point = |offset: f64, total_return_pct: f64| SensitivityPoint {
+            offset,
+            value: 1.0 + offset,
+            metrics: Some(metrics(total_return_pct, 10.0)),
+        };
+        // ±20% 塌陷但 ±10% 稳定：不算刀锋。
+        let wide = SensitivityCurve::build(
+            SensitivityParam::TakeProfitRatio,
+            1.0,
+            &base,
+            vec![
+                point(-0.2, -10.0),
+                point(-0.1, 35.0),
+                point(0.1, 38.0),
+                point(0.2, 0.0),
+            ],
+            &config,
+        );
+        assert_eq!(wide.points.len(), 5);
+        assert_eq!(wide.points[2].offset, 0.0);
+        assert!((wide.return_range_pct - 50.0).abs() < 1e-9);
+        assert!(!wide.neighbourhood.knife_edge);
+        let narrow = SensitivityCurve::build(
+            SensitivityParam::BbMultiplier,
+            1.0,
+            &base,
+            vec![point(-0.1, 5.0), point(0.1, 41.0)],
+            &config,
+        );
+        assert!(narrow.neighbourhood.knife_edge);
+        let flat = SensitivityCurve::build(
+            SensitivityParam::RsiOverbought,
+            1.0,
+            &base,
+            vec![point(-0.1, 39.0), point(0.1, 40.0)],
+            &config,
+        );
+        let pairs = heatmap_pairs(&[flat, narrow, wide], 2);
+        assert_eq!(
+            pairs,
+            vec![(
+                SensitivityParam::TakeProfitRatio,
+                SensitivityParam::BbMultiplier
+            )]
+        );
+    }
+
+    #[test]
+    fn heatmap_places_base_at_center_and_scores_neighbours() {
+        let config = SensitivityConfig::default().with_offsets(vec![-0.1, 0.1]);
+        let x_axis = heatmap_axis(
+            SensitivityParam::BbMultiplier,
+            2.0,
+            &config.normalized_offsets(),
+        );
+        let y_axis = heatmap_axis(
+            SensitivityParam::MaxLossPercent,
+            0.02,
+            &config.normalized_offsets(),
+        );
+        let base = metrics(40.0, 10.0);
+        let heatmap = SensitivityHeatmap::build(
+            (SensitivityParam::BbMultiplier, x_axis),
+            (SensitivityParam::MaxLossPercent, y_axis),
+            &base,
+            |row, col| match (row, col) {
+                (1, 1) => Some(base),
+                (0, 0) => Some(metrics(-20.0, 30.0)),
+                (2, 2) => None,
+                _ => Some(metrics(30.0, 11.0)),
+            },
+            &config,
+        );
+        assert_eq!(heatmap.x_offsets, vec![-0.1, 0.0, 0.1]);
+        assert!((heatmap.x_values[2] - 2.2).abs() < 1e-12);
+        assert_eq!(heatmap.return_pct[1][1], Some(40.0));
+        assert_eq!(heatmap.return_pct[2][2], None);
+        assert_eq!(heatmap.max_drawdown_pct[0][0], Some(30.0));
+        // 非基准且回测成功的 7 格中 6 格收益保留率 ≥ 50%。
+        assert!((heatmap.robust_share.unwrap() - 6.0 / 7.0).abs() < 1e-9);
+        assert!(heatmap.neighbourhood.knife_edge);
+    }
+}
@@ -0,0 +1,810 @@
+//! Walk-forward 滚动回测
+//!
+//! 把单个交易对的历史K线切成滚动的样本内（IS）/样本外（OOS）窗口：每个窗口在样本内用参数优化器搜索，
+//! 把最优参数放到紧随其后的样本外窗口上评估，再把各窗口的样本外收益按时间复利拼接成资金曲线，
+//! 汇总 walk-forward 效率与跨窗口参数稳定性。样本外窗口首尾相接，滚动步长固定等于 `test_bars`。
+use crate::backtest::executor::{BacktestExecutor, BacktestRunContext};
+use crate::infra::job_param_generator::{NweParamGenerator, ParamGenerator};
+use crate::infra::param_optimizer::{
+    BacktestObjectiveMetrics, OptimizerConfig, OptimizerStrategyKind, ParamOptimizer, SearchSpace,
+};
+use anyhow::{anyhow, Result};
+use rust_quant_common::CandleItem;
+use rust_quant_domain::StrategyType;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
+/// 拼接资金曲线的起始权益，与回测初始资金口径一致。
+const INITIAL_EQUITY: f64 = 100.0;
+/// Vegas 引擎默认预热K线数，与 `ParamMergeBuilder::min_k_line_num` 缺省值一致。
+const VEGAS_DEFAULT_WARMUP_BARS: usize = 3600;
+/// NWE 参数生成器写入的 `min_k_line_num`。
+const NWE_DEFAULT_WARMUP_BARS: usize = 500;
+/// Walk-forward 配置。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardConfig {
+    /// 样本内窗口K线数。
+    #[serde(default = "default_train_bars")]
+    pub train_bars: usize,
+    /// 样本外窗口K线数，同时也是窗口滚动步长。
+    #[serde(default = "default_test_bars")]
+    pub test_bars: usize,
+    /// 每段回测前附带的预热K线数，同时写入策略的最小数据长度，预热段内不产生信号；为空时取策略默认值。
+    #[serde(default)]
+    pub warmup_bars: Option<usize>,
+    /// 为 true 时样本内起点固定（扩张窗口），否则样本内长度固定、随样本外窗口滚动。
+    #[serde(default)]
+    pub anchored: bool,
+    /// 最多保留的窗口数，超出时只保留最近的窗口；0 表示不限制。
+    #[serde(default)]
+    pub max_windows: usize,
+    /// 每个样本内窗口的参数搜索配置。
+    #[serde(default = "default_walk_forward_optimizer")]
+    pub optimizer: OptimizerConfig,
+    /// 报告 JSON 输出目录；为空时只写日志。
+    #[serde(default)]
+    pub report_dir: Option<String>,
+}
+fn default_train_bars() -> usize {
+    4000
+}
+fn default_test_bars() -> usize {
+    1000
+}
+fn default_walk_forward_optimizer() -> OptimizerConfig {
+    OptimizerConfig::new(OptimizerStrategyKind::Tpe)
+        .with_max_trials(64)
+        .with_initial_random_trials(16)
+}
+impl Default for WalkForwardConfig {
+    fn default() -> Self {
+        Self {
+            train_bars: default_train_bars(),
+            test_bars: default_test_bars(),
+            warmup_bars: None,
+            anchored: false,
+            max_windows: 0,
+            optimizer: default_walk_forward_optimizer(),
+            report_dir: None,
+        }
+    }
+}
+impl WalkForwardConfig {
+    pub fn with_windows(mut self, train_bars: usize, test_bars: usize) -> Self {
+        self.train_bars = train_bars;
+        self.test_bars = test_bars;
+        self
+    }
+    pub fn with_warmup_bars(mut self, warmup_bars: usize) -> Self {
+        self.warmup_bars = Some(warmup_bars);
+        self
+    }
+    pub fn with_anchored(mut self, anchored: bool) -> Self {
+        self.anchored = anchored;
+        self
+    }
+    pub fn with_max_windows(mut self, max_windows: usize) -> Self {
+        self.max_windows = max_windows;
+        self
+    }
+    pub fn with_optimizer(mut self, optimizer: OptimizerConfig) -> Self {
+        self.optimizer = optimizer;
+        self
+    }
+    pub fn with_report_dir(mut self, report_dir: impl Into<String>) -> Self {
+        self.report_dir = Some(report_dir.into());
+        self
+    }
+    /// 实际使用的预热K线数：未显式配置时沿用策略自身的最小数据长度，保证与普通回测口径一致。
+    pub fn warmup_bars_for(&self, strategy_type: StrategyType) -> usize {
+        self.warmup_bars
+            .unwrap_or(match strategy_type {
+                StrategyType::Nwe => NWE_DEFAULT_WARMUP_BARS,
+                _ => VEGAS_DEFAULT_WARMUP_BARS,
+            })
+            .max(1)
+    }
+}
+/// 一个样本内/样本外窗口，区间均为K线下标（左闭右开）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalkForwardWindow {
+    pub index: usize,
+    pub train: Range<usize>,
+    /// 紧接样本内区间的样本外区间。
+    pub test: Range<usize>,
+}
+impl WalkForwardWindow {
+    /// 样本内回测实际传入的K线区间，向前补足预热K线。
+    pub fn train_with_warmup(&self, warmup_bars: usize) -> Range<usize> {
+        with_warmup(&self.train, warmup_bars)
+    }
+    /// 样本外回测实际传入的K线区间，预热段取自样本内末尾，只用于指标计算。
+    pub fn test_with_warmup(&self, warmup_bars: usize) -> Range<usize> {
+        with_warmup(&self.test, warmup_bars)
+    }
+}
+/// 信号阶段在缓冲区达到最小数据长度时才开始出信号，即第 `warmup_bars - 1` 根（从 0 计）；
+/// 向前补 `warmup_bars - 1` 根后，第一根可交易K线恰好是区间起点。
+fn with_warmup(range: &Range<usize>, warmup_bars: usize) -> Range<usize> {
+    range.start.saturating_sub(warmup_bars.saturating_sub(1))..range.end
+}
+/// 按配置切分窗口；第一个样本内区间从预热段之后开始，K线不足一个完整窗口时返回错误。
+pub fn build_walk_forward_windows(
+    total_bars: usize,
+    config: &WalkForwardConfig,
+    warmup_bars: usize,
+) -> Result<Vec<WalkForwardWindow>> {
+    if config.train_bars == 0 || config.test_bars == 0 {
+        return Err(anyhow!("walk-forward 样本内/样本外K线数必须大于 0"));
+    }
+    let first_start = warmup_bars.saturating_sub(1);
+    let mut windows = Vec::new();
+    loop {
+        let offset = windows.len() * config.test_bars;
+        let train_end = first_start + config.train_bars + offset;
+        let test_end = train_end + config.test_bars;
+        if test_end > total_bars {
+            break;
+        }
+        let train_start = if config.anchored {
+            first_start
+        } else {
+            first_start + offset
+        };
+        windows.push(WalkForwardWindow {
+            index: windows.len(),
+            train: train_start..train_end,
+            test: train_end..test_end,
+        });
+    }
+    if windows.is_empty() {
+        return Err(anyhow!(
+            "K线数量不足以构建 walk-forward 窗口: total={}, warmup={}, train={}, test={}",
+            total_bars,
+            warmup_bars,
+            config.train_bars,
+            config.test_bars
+        ));
+    }
+    if config.max_windows > 0 && windows.len() > config.max_windows {
+        windows.drain(..windows.len() - config.max_windows);
+        for (index, window) in windows.iter_mut().enumerate() {
+            window.index = index;
+        }
+    }
+    Ok(windows)
+}
+/// Vegas 系列策略（`vegas` 前缀）共用 Vegas 引擎与参数网格，可在 Vegas 网格上搜索。
+pub fn is_vegas_family(strategy_type: StrategyType) -> bool {
+    strategy_type.as_str().starts_with("vegas")
+}
+/// Walk-forward 的参数空间与回测引擎：NWE 使用 NWE 网格，Vegas 系列共用 Vegas 引擎与参数网格。
+pub enum WalkForwardSearchSpace {
+    Vegas {
+        generator: ParamGenerator,
+        strategy_type: StrategyType,
+    },
+    Nwe {
+        generator: NweParamGenerator,
+    },
+}
+impl WalkForwardSearchSpace {
+    /// 非 Vegas 系列策略的参数不在 Vegas 网格内，直接报错而不是以错误的参数回测。
+    pub fn vegas(generator: ParamGenerator, strategy_type: StrategyType) -> Result<Self> {
+        if !is_vegas_family(strategy_type) {
+            return Err(anyhow!(
+                "walk-forward 暂不支持策略类型 {}：仅支持 Vegas 系列与 NWE",
+                strategy_type.as_str()
+            ));
+        }
+        Ok(Self::Vegas {
+            generator,
+            strategy_type,
+        })
+    }
+    pub fn nwe(generator: NweParamGenerator) -> Self {
+        Self::Nwe { generator }
+    }
+    pub fn strategy_type(&self) -> StrategyType {
+        match self {
+            Self::Vegas { strategy_type, .. } => *strategy_type,
+            Self::Nwe { .. } => StrategyType::Nwe,
+        }
+    }
+    pub fn dimension_names(&self) -> &'static [&'static str] {
+        match self {
+            Self::Vegas { .. } => &ParamGenerator::DIMENSION_NAMES,
+            Self::Nwe { .. } => &NweParamGenerator::DIMENSION_NAMES,
+        }
+    }
+    pub fn dimension_sizes(&self) -> Vec<usize> {
+        match self {
+            Self::Vegas { generator, .. } => generator.dimension_sizes(),
+            Self::Nwe { generator } => generator.dimension_sizes(),
+        }
+    }
+    /// 还原参数并输出策略/风控配置 JSON，供报告记录每个窗口选中的参数。
+    pub fn describe(
+        &self,
+        indices: &[usize],
+        period: &str,
+        warmup_bars: usize,
+    ) -> serde_json::Value {
+        match self {
+            Self::Vegas { generator, .. } => {
+                let mut param = generator.param_at_indices(indices);
+                param.min_k_line_num = Some(warmup_bars);
+                json!({
+                    "strategy": param.to_vegas_strategy(period.to_string()),
+                    "risk": param.to_risk_config(),
+                })
+            }
+            Self::Nwe { generator } => {
+                let (mut cfg, risk) = generator.param_at_indices(indices);
+                cfg.min_k_line_num = warmup_bars;
+                json!({ "strategy": cfg, "risk": risk })
+            }
+        }
+    }
+    /// 在给定K线上评估一批候选，结果与候选顺序一致；预热长度写入策略的最小数据长度。
+    #[allow(clippy::too_many_arguments)]
+    async fn evaluate(
+        &self,
+        executor: &BacktestExecutor,
+        candidates: &[Vec<usize>],
+        inst_id: &str,
+        period: &str,
+        warmup_bars: usize,
+        candles: Arc<Vec<CandleItem>>,
+        context: BacktestRunContext,
+        semaphore: Arc<Semaphore>,
+    ) -> Vec<Option<BacktestObjectiveMetrics>> {
+        match self {
+            Self::Vegas {
+                generator,
+                strategy_type,
+            } => {
+                let params_batch = candidates
+                    .iter()
+                    .map(|indices| {
+                        let mut param = generator.param_at_indices(indices);
+                        param.min_k_line_num = Some(warmup_bars);
+                        param
+                    })
+                    .collect();
+                executor
+                    .evaluate_back_test_strategy(
+                        params_batch,
+                        inst_id,
+                        period,
+                        *strategy_type,
+                        candles,
+                        context,
+                        semaphore,
+                    )
+                    .await
+            }
+            Self::Nwe { generator } => {
+                let params_batch = candidates
+                    .iter()
+                    .map(|indices| {
+                        let (mut cfg, risk) = generator.param_at_indices(indices);
+                        cfg.min_k_line_num = warmup_bars;
+                        (cfg, risk)
+                    })
+                    .collect();
+                executor
+                    .evaluate_nwe_strategy(
+                        params_batch,
+                        inst_id,
+                        period,
+                        candles,
+                        context,
+                        semaphore,
+                    )
+                    .await
+            }
+        }
+    }
+}
+/// 单个窗口的搜索与验证结果。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardWindowResult {
+    pub window: WalkForwardWindow,
+    /// 样本外首根K线时间戳（毫秒）。
+    pub test_start_ts: i64,
+    /// 样本外末根K线时间戳（毫秒）。
+    pub test_end_ts: i64,
+    /// 样本内最优参数的各维度取值下标。
+    pub best_indices: Vec<usize>,
+    /// 样本内最优参数的策略/风控配置。
+    pub best_params: serde_json::Value,
+    pub in_sample: BacktestObjectiveMetrics,
+    /// 样本外回测失败时为空，拼接资金曲线时按持平处理。
+    pub out_of_sample: Option<BacktestObjectiveMetrics>,
+    /// 样本内评估的试验数。
+    pub trials: usize,
+}
+impl WalkForwardWindowResult {
+    /// 单窗口 walk-forward 效率：样本外每根K线收益 / 样本内每根K线收益。
+    pub fn efficiency(&self) -> Option<f64> {
+        let out_of_sample = self.out_of_sample?;
+        per_bar_return_ratio(
+            out_of_sample.total_return_pct,
+            self.window.test.len(),
+            self.in_sample.total_return_pct,
+            self.window.train.len(),
+        )
+    }
+}
+/// 样本内每根K线收益非正时效率没有意义，返回 None。
+fn per_bar_return_ratio(
+    out_of_sample_return_pct: f64,
+    out_of_sample_bars: usize,
+    in_sample_return_pct: f64,
+    in_sample_bars: usize,
+) -> Option<f64> {
+    if out_of_sample_bars == 0 || in_sample_bars == 0 {
+        return None;
+    }
+    let in_sample_rate = in_sample_return_pct / in_sample_bars as f64;
+    if !in_sample_rate.is_finite() || in_sample_rate <= 0.0 {
+        return None;
+    }
+    let ratio = (out_of_sample_return_pct / out_of_sample_bars as f64) / in_sample_rate;
+    ratio.is_finite().then_some(ratio)
+}
+/// 拼接后的样本外资金曲线点，位于每个样本外窗口的起止时刻。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardEquityPoint {
+    pub window_index: usize,
+    pub ts: i64,
+    /// 以 100 为起点的权益。
+    pub equity: f64,
+}
+/// 拼接后的样本外表现。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StitchedEquity {
+    pub points: Vec<WalkForwardEquityPoint>,
+    pub total_return_pct: f64,
+    /// 窗口内只有汇总指标，跨窗口回撤按窗口边界权益计算，再与各窗口内最大回撤取大者。
+    pub max_drawdown_pct: f64,
+    pub trade_count: usize,
+}
+/// 把各窗口的样本外收益按时间顺序复利拼接。
+pub fn stitch_out_of_sample_equity(results: &[WalkForwardWindowResult]) -> StitchedEquity {
+    let mut equity = INITIAL_EQUITY;
+    let mut peak = equity;
+    let mut max_drawdown_pct: f64 = 0.0;
+    let mut trade_count = 0;
+    let mut points = Vec::with_capacity(results.len() + 1);
+    if let Some(first) = results.first() {
+        points.push(WalkForwardEquityPoint {
+            window_index: first.window.index,
+            ts: first.test_start_ts,
+            equity,
+        });
+    }
+    for result in results {
+        if let Some(metrics) = result.out_of_sample {
+            equity *= 1.0 + metrics.total_return_pct / 100.0;
+            peak = peak.max(equity);
+            if peak > 0.0 {
+                max_drawdown_pct = max_drawdown_pct.max((peak - equity) / peak * 100.0);
+            }
+            max_drawdown_pct = max_drawdown_pct.max(metrics.max_drawdown_pct);
+            trade_count += metrics.trade_count;
+        }
+        points.push(WalkForwardEquityPoint {
+            window_index: result.window.index,
+            ts: result.test_end_ts,
+            equity,
+        });
+    }
+    StitchedEquity {
+        points,
+        total_return_pct: (equity / INITIAL_EQUITY - 1.0) * 100.0,
+        max_drawdown_pct,
+        trade_count,
+    }
+}
+/// 整体 walk-forward 效率：全部样本外窗口的每根K线收益 / 对应样本内窗口的每根K线收益。
+pub fn walk_forward_efficiency(results: &[WalkForwardWindowResult]) -> Option<f64> {
+    let mut out_of_sample_return = 0.0;
+    let mut out_of_sample_bars = 0;
+    let mut in_sample_return = 0.0;
+    let mut in_sample_bars = 0;
+    for result in results {
+        let Some(out_of_sample) = result.out_of_sample else {
+            continue;
+        };
+        out_of_sample_return += out_of_sample.total_return_pct;
+        out_of_sample_bars += result.window.test.len();
+        in_sample_return += result.in_sample.total_return_pct;
+        in_sample_bars += result.window.train.len();
+    }
+    per_bar_return_ratio(
+        out_of_sample_return,
+        out_of_sample_bars,
+        in_sample_return,
+        in_sample_bars,
+    )
+}
+/// 单个参数维度在各窗口间的稳定性。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterStability {
+    pub name: String,
+    /// 该维度可选取值个数。
+    pub choices: usize,
+    /// 各窗口选中的取值下标。
+    pub selected: Vec<usize>,
+    /// 选中的不同取值个数。
+    pub distinct: usize,
+    /// 众数取值所占窗口比例。
+    pub mode_share: f64,
+    /// 1 - 2 × 归一化下标的标准差，范围 [0, 1]；1 表示所有窗口选同一个值。
+    pub stability: f64,
+}
+/// 计算每个可变维度（取值数大于 1）的跨窗口稳定性；下标按网格顺序归一化，相邻取值视为接近。
+pub fn parameter_stability(
+    names: &[&str],
+    sizes: &[usize],
+    winners: &[Vec<usize>],
+) -> Vec<ParameterStability> {
+    if winners.is_empty() {
+        return vec![];
+    }
+    sizes
+        .iter()
+        .enumerate()
+        .filter(|(_, size)| **size > 1)
+        .map(|(dim, size)| {
+            let selected: Vec<usize> = winners.iter().map(|indices| indices[dim]).collect();
+            let mut counts: HashMap<usize, usize> = HashMap::new();
+            for value in &selected {
+                *counts.entry(*value).or_default() += 1;
+            }
+            let mode_count = counts.values().copied().max().unwrap_or(0);
+            let scale = (*size - 1) as f64;
+            let normalized: Vec<f64> = selected.iter().map(|value| *value as f64 / scale).collect();
+            let mean = normalized.iter().sum::<f64>() / normalized.len() as f64;
+            let variance = normalized
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / normalized.len() as f64;
+            ParameterStability {
+                name: names
+                    .get(dim)
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| format!("dim_{}", dim)),
+                choices: *size,
+                distinct: counts.len(),
+                mode_share: mode_count as f64 / selected.len() as f64,
+                stability: (1.0 - 2.0 * variance.sqrt()).clamp(0.0, 1.0),
+                selected,
+            }
+        })
+        .collect()
+}
+/// Walk-forward 汇总报告。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardReport {
+    pub inst_id: String,
+    pub period: String,
+    pub strategy_type: String,
+    pub config: WalkForwardConfig,
+    pub warmup_bars: usize,
+    pub windows: Vec<WalkForwardWindowResult>,
+    pub oos_equity: Vec<WalkForwardEquityPoint>,
+    pub oos_total_return_pct: f64,
+    pub oos_max_drawdown_pct: f64,
+    pub oos_trade_count: usize,
+    /// 样本外窗口回测失败的个数。
+    pub failed_oos_windows: usize,
+    pub walk_forward_efficiency: Option<f64>,
+    pub parameter_stability: Vec<ParameterStability>,
+    /// 各可变维度稳定性的均值；少于两个窗口时为空。
+    pub overall_stability: Option<f64>,
+}
+impl WalkForwardReport {
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        inst_id: &str,
+        period: &str,
+        strategy_type: StrategyType,
+        config: &WalkForwardConfig,
+        warmup_bars: usize,
+        dimension_names: &[&str],
+        dimension_sizes: &[usize],
+        windows: Vec<WalkForwardWindowResult>,
+    ) -> Self {
+        let stitched = stitch_out_of_sample_equity(&windows);
+        let winners: Vec<Vec<usize>> = windows
+            .iter()
+            .map(|result| result.best_indices.clone())
+            .collect();
+        let parameter_stability = parameter_stability(dimension_names, dimension_sizes, &winners);
+        let overall_stability =
+            (windows.len() >= 2 && !parameter_stability.is_empty()).then(|| {
+                parameter_stability
+                    .iter()
+                    .map(|item| item.stability)
+                    .sum::<f64>()
+                    / parameter_stability.len() as f64
+            });
+        Self {
+            inst_id: inst_id.to_string(),
+            period: period.to_string(),
+            strategy_type: strategy_type.as_str().to_string(),
+            config: config.clone(),
+            warmup_bars,
+            walk_forward_efficiency: walk_forward_efficiency(&windows),
+            failed_oos_windows: windows
+                .iter()
+                .filter(|result| result.out_of_sample.is_none())
+                .count(),
+            oos_equity: stitched.points,
+            oos_total_return_pct: stitched.total_return_pct,
+            oos_max_drawdown_pct: stitched.max_drawdown_pct,
+            oos_trade_count: stitched.trade_count,
+            parameter_stability,
+            overall_stability,
+            windows,
+        }
+    }
+    /// 写入 `{dir}/walk_forward_{inst_id}_{period}_{strategy}.json`，返回文件路径。
+    pub fn write_json(&self, dir: impl AsRef<Path>) -> Result<PathBuf> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "walk_forward_{}_{}_{}.json",
+            self.inst_id, self.period, self.strategy_type
+        ));
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+/// 在已加载的K线上执行 walk-forward：逐窗口样本内搜索、样本外验证，返回汇总报告。
+///
+/// 每个窗口使用独立的优化器且种子相同，窗口之间只共享参数空间；样本内无有效试验的窗口会被跳过。
+/// `context` 按全量K线区间加载，各窗口按时间戳取用其中的子区间。
+#[allow(clippy::too_many_arguments)]
+pub async fn run_walk_forward(
+    executor: &BacktestExecutor,
+    inst_id: &str,
+    period: &str,
+    space: &WalkForwardSearchSpace,
+    candles: Arc<Vec<CandleItem>>,
+    context: BacktestRunContext,
+    config: &WalkForwardConfig,
+    semaphore: Arc<Semaphore>,
+) -> Result<WalkForwardReport> {
+    let strategy_type = space.strategy_type();
+    let warmup_bars = config.warmup_bars_for(strategy_type);
+    let windows = build_walk_forward_windows(candles.len(), config, warmup_bars)?;
+    let dimension_sizes = space.dimension_sizes();
+    info!(
+        "[Walk-forward] 开始 inst_id={}, period={}, strategy={}, windows={}, train={}, test={}, warmup={}, anchored={}",
+        inst_id,
+        period,
+        strategy_type.as_str(),
+        windows.len(),
+        config.train_bars,
+        config.test_bars,
+        warmup_bars,
+        config.anchored,
+    );
+    let mut results = Vec::with_capacity(windows.len());
+    for window in windows {
+        let train_candles = Arc::new(candles[window.train_with_warmup(warmup_bars)].to_vec());
+        let mut optimizer = ParamOptimizer::new(
+            SearchSpace::new(dimension_sizes.clone())?,
+            config.optimizer.clone(),
+        );
+        loop {
+            let candidates = optimizer.next_batch();
+            if candidates.is_empty() {
+                break;
+            }
+            let metrics = space
+                .evaluate(
+                    executor,
+                    &candidates,
+                    inst_id,
+                    period,
+                    warmup_bars,
+                    train_candles.clone(),
+                    context.clone(),
+                    semaphore.clone(),
+                )
+                .await;
+            for (indices, metrics) in candidates.into_iter().zip(metrics) {
+                optimizer.record(indices, metrics);
+            }
+        }
+        let Some((best_indices, in_sample)) = optimizer.best().and_then(|trial| {
+            trial
+                .metrics
+                .map(|metrics| (trial.indices.clone(), metrics))
+        }) else {
+            warn!(
+                "[Walk-forward] 样本内无有效试验，跳过窗口 {}: inst_id={}, period={}",
+                window.index, inst_id, period
+            );
+            continue;
+        };
+        let test_candles = Arc::new(candles[window.test_with_warmup(warmup_bars)].to_vec());
+        let out_of_sample = space
+            .evaluate(
+                executor,
+                std::slice::from_ref(&best_indices),
+                inst_id,
+                period,
+                warmup_bars,
+                test_candles,
+                context.clone(),
+                semaphore.clone(),
+            )
+            .await
+            .into_iter()
+            .next()
+            .flatten();
+        if out_of_sample.is_none() {
+            warn!(
+                "[Walk-forward] 样本外回测失败，窗口 {} 按持平计入: inst_id={}, period={}",
+                window.index, inst_id, period
+            );
+        }
+        let result = WalkForwardWindowResult {
+            test_start_ts: candles[window.test.start].ts,
+            test_end_ts: candles[window.test.end - 1].ts,
+            best_params: space.describe(&best_indices, period, warmup_bars),
+            best_indices,
+            in_sample,
+            out_of_sample,
+            trials: optimizer.trials().len(),
+            window,
+        };
+        info!(
+            "[Walk-forward] 窗口 {} 完成 inst_id={}, period={}, trials={}, indices={:?}, is={:?}, oos={:?}, efficiency={:?}",
+            result.window.index,
+            inst_id,
+            period,
+            result.trials,
+            result.best_indices,
+            result.in_sample,
+            result.out_of_sample,
+            result.efficiency(),
+        );
+        results.push(result);
+    }
+    if results.is_empty() {
+        return Err(anyhow!(
+            "walk-forward 所有窗口的样本内搜索均无有效结果: inst_id={}, period={}",
+            inst_id,
+            period
+        ));
+    }
+    Ok(WalkForwardReport::build(
+        inst_id,
+        period,
+        strategy_type,
+        config,
+        warmup_bars,
+        space.dimension_names(),
+        &dimension_sizes,
+        results,
+    ))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window_result(
+        index: usize,
+        train: Range<usize>,
+        test: Range<usize>,
+        in_sample_return_pct: f64,
+        out_of_sample: Option<(f64, f64)>,
+    ) -> WalkForwardWindowResult {
+        let metrics = |total_return_pct, max_drawdown_pct| BacktestObjectiveMetrics {
+            total_return_pct,
+            max_drawdown_pct,
+            trade_count: 5,
+        };
+        WalkForwardWindowResult {
+            test_start_ts: test.start as i64,
+            test_end_ts: test.end as i64 - 1,
+            window: WalkForwardWindow { index, train, test },
+            best_indices: vec![index],
+            best_params: serde_json::Value::Null,
+            in_sample: metrics(in_sample_return_pct, 5.0),
+            out_of_sample: out_of_sample
+                .map(|(return_pct, drawdown_pct)| metrics(return_pct, drawdown_pct)),
+            trials: 10,
+        }
+    }
+
+    #[test]
+    fn rolling_windows_start_after_warmup_and_step_by_test_bars() {
+        let config = WalkForwardConfig::default().with_windows(100, 50);
+        let windows = build_walk_forward_windows(359, &config, 11).unwrap();
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[0].train, 10..110);
+        assert_eq!(windows[0].test, 110..160);
+        assert_eq!(windows[3].train, 160..260);
+        assert_eq!(windows[3].test, 260..310);
+        for pair in windows.windows(2) {
+            assert_eq!(pair[0].test.end, pair[1].test.start);
+        }
+        // 预热后第一根可交易K线恰好是区间起点。
+        assert_eq!(windows[0].train_with_warmup(11), 0..110);
+        assert_eq!(windows[1].test_with_warmup(11), 150..210);
+    }
+
+    #[test]
+    fn anchored_windows_expand_and_max_windows_keeps_latest() {
+        let config = WalkForwardConfig::default()
+            .with_windows(100, 50)
+            .with_anchored(true)
+            .with_max_windows(2);
+        let windows = build_walk_forward_windows(300, &config, 1).unwrap();
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].index, 0);
+        assert_eq!(windows[0].train, 0..200);
+        assert_eq!(windows[1].train, 0..250);
+        assert_eq!(windows[1].test, 250..300);
+        assert!(build_walk_forward_windows(149, &config, 1).is_err());
+        assert!(build_walk_forward_windows(
+            300,
+            &WalkForwardConfig::default().with_windows(0, 50),
+            1
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn stitches_out_of_sample_returns_and_computes_efficiency() {
+        let results = vec![
+            window_result(0, 0..100, 100..150, 20.0, Some((10.0, 3.0))),
+            window_result(1, 50..150, 150..200, 20.0, Some((-20.0, 25.0))),
+            window_result(2, 100..200, 200..250, 10.0, None),
+        ];
+        let stitched = stitch_out_of_sample_equity(&results);
+        assert_eq!(stitched.points.len(), 4);
+        assert!((stitched.points[1].equity - 110.0).abs() < 1e-9);
+        assert!((stitched.points[2].equity - 88.0).abs() < 1e-9);
+        assert!((stitched.points[3].equity - 88.0).abs() < 1e-9);
+        assert!((stitched.total_return_pct + 12.0).abs() < 1e-9);
+        assert!((stitched.max_drawdown_pct - 25.0).abs() < 1e-9);
+        assert_eq!(stitched.trade_count, 10);
+        // OOS (10 - 20) / 100 bars vs IS (20 + 20) / 200 bars。
+        let efficiency = walk_forward_efficiency(&results).unwrap();
+        assert!((efficiency + 0.5).abs() < 1e-9);
+        assert!((results[0].efficiency().unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(results[2].efficiency(), None);
+        let losing = vec![window_result(0, 0..100, 100..150, -5.0, Some((1.0, 0.0)))];
+        assert_eq!(walk_forward_efficiency(&losing), None);
+    }
+
+    #[test]
+    fn parameter_stability_skips_fixed_dimensions() {
+        let winners = vec![vec![0, 2, 1], vec![0, 2, 3], vec![0, 2, 1], vec![0, 2, 3]];
+        let stability = parameter_stability(&["fixed", "steady", "jumpy"], &[1, 5, 4], &winners);
+        assert_eq!(stability.len(), 2);
+        assert_eq!(stability[0].name, "steady");
+        assert_eq!(stability[0].distinct, 1);
+        assert!((stability[0].stability - 1.0).abs() < 1e-9);
+        assert_eq!(stability[1].name, "jumpy");
+        assert_eq!(stability[1].selected, vec![1, 3, 1, 3]);
+        assert!((stability[1].mode_share - 0.5).abs() < 1e-9);
+        // 归一化下标 1/3 与 1，标准差 1/3。
+        assert!((stability[1].stability - 1.0 / 3.0).abs() < 1e-9);
+    }
+}
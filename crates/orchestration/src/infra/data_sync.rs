@@ -4,7 +4,7 @@
 //! 重构为符合架构规范：orchestration层只做编排，调用jobs层
 use crate::jobs::data::candles_job::CandlesJob;
 use anyhow::Result;
-use tracing::info;
+use tracing::{info, warn};
 /// 同步所有数据任务的统一入口
 /// # Migration Notes
 /// - ✅ 从 src/trading/task/data_sync.rs 迁移
@@ -45,6 +45,15 @@ pub async fn sync_market_data(inst_ids: &[String], periods: &[String]) -> Result
         // 调用candles_job完成完整的数据同步（建表、补历史、补增量）
         job.sync_all_data(inst_ids, periods).await?;
     }
+    // 设置 SYNC_CANDLE_QUALITY_REPAIR=1 时，同步后巡检K线质量并定向补拉缺口。
+    if rust_quant_core::config::env_is_true("SYNC_CANDLE_QUALITY_REPAIR", false) {
+        let remaining = job
+            .scan_and_repair_candle_quality(inst_ids, periods)
+            .await?;
+        if !remaining.is_empty() {
+            warn!("⚠️ K线质量巡检后仍有 {} 个未解决问题", remaining.len());
+        }
+    }
     Ok(())
 }
 /// 同步账户数据
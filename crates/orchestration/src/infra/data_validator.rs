@@ -14,15 +14,27 @@ use tracing::debug;
 /// - `true` - 数据有效
 /// - `false` - 数据过期或无效
 pub fn valid_newest_candle_data(candle: &CandlesEntity, period: &str) -> bool {
-    let ts = candle.ts;
-    // ⏳ P1: 时间转换函数待实现或从common导入
-    // let datetime = time::mill_time_to_local_datetime(ts);
-    // let data_period = time::format_to_period(period, Some(datetime));
-    // let current_period = time::format_to_period(period, None);
-    // 当前简化实现
-    debug!("验证K线数据: ts={}, period={}", ts, period);
-    // 当前返回true，实际验证逻辑待完善
-    true
+    valid_newest_candle_data_at(candle, period, chrono::Utc::now().timestamp_millis())
+}
+/// 以 `now_ms` 为当前时间验证最新K线：只接受当前周期或刚收盘的上一周期。
+/// 不按自然周期对齐，OKX 日线按 UTC+8 开盘，对齐判断会误伤。
+fn valid_newest_candle_data_at(candle: &CandlesEntity, period: &str, now_ms: i64) -> bool {
+    let period_ms = match time::parse_period_to_mill(period) {
+        Ok(period_ms) => period_ms,
+        Err(e) => {
+            debug!("无法验证K线数据: period={}, error={}", period, e);
+            return false;
+        }
+    };
+    let age = now_ms - candle.ts;
+    let valid = (0..2 * period_ms).contains(&age);
+    if !valid {
+        debug!(
+            "最新K线不在当前周期: ts={}, period={}, now={}",
+            candle.ts, period, now_ms
+        );
+    }
+    valid
 }
 /// 验证K线数据序列的连续性
 /// # Arguments
@@ -95,4 +107,27 @@ mod tests {
         let result = valid_candles_continuity(&candles, "1m");
         assert!(result.is_err());
     }
+    #[test]
+    fn test_valid_newest_candle_data_accepts_current_or_previous_period() {
+        let hour = 3_600_000;
+        let now = 10 * hour + 1_000;
+        let candle = |ts| CandlesEntity {
+            id: None,
+            ts,
+            o: "100".to_string(),
+            h: "110".to_string(),
+            l: "90".to_string(),
+            c: "105".to_string(),
+            vol: "1000".to_string(),
+            vol_ccy: "1000".to_string(),
+            confirm: "0".to_string(),
+            created_at: None,
+            updated_at: None,
+        };
+        assert!(valid_newest_candle_data_at(&candle(10 * hour), "1H", now));
+        assert!(valid_newest_candle_data_at(&candle(9 * hour), "1H", now));
+        assert!(!valid_newest_candle_data_at(&candle(8 * hour), "1H", now));
+        assert!(!valid_newest_candle_data_at(&candle(11 * hour), "1H", now));
+        assert!(!valid_newest_candle_data_at(&candle(10 * hour), "7X", now));
+    }
 }
@@ -231,6 +231,8 @@ impl ParamMergeBuilder {
             position_leverage: self.position_leverage,
             tiered_take_profit_level_1_close_ratio: None,
             tiered_take_profit_level_2_close_ratio: None,
+            pyramiding: None,
+            cost_model: None,
         }
     }
     /// 转换为 Vegas 策略配置
@@ -355,6 +357,21 @@ pub struct ParamGenerator {
     fix_signal_kline_take_profit_ratios: Vec<f64>,
 }
 impl ParamGenerator {
+    /// 各维度名称，与 `dimension_sizes` 顺序一致。
+    pub const DIMENSION_NAMES: [&'static str; 12] = [
+        "bb_period",
+        "hammer_shadow_ratio",
+        "bb_multiplier",
+        "volume_bar_num",
+        "volume_ratio",
+        "breakthrough_threshold",
+        "rsi_period",
+        "rsi_over_buy_sell",
+        "max_loss_percent",
+        "take_profit_ratio",
+        "is_used_signal_k_line_stop_loss",
+        "fix_signal_kline_take_profit_ratio",
+    ];
     #[allow(clippy::too_many_arguments)]
     /// 封装当前函数，减少配置运行时调用方重复实现相同细节。
     /// 当前函数完成参数检查、流程切分与结果封装，确保上层可安全复用。
@@ -448,106 +465,107 @@ impl ParamGenerator {
         let mut batch = Vec::with_capacity(batch_size);
         while batch.len() < batch_size && self.current_index < self.total_count {
             // 仿射排列只保存起点和步长，既能固定种子重放，又避免构造完整索引数组。
-            let mut index = sampled_grid_index(
+            let index = sampled_grid_index(
                 self.current_index,
                 self.grid_count,
                 self.sample_start,
                 self.sample_stride,
             );
-            let bb_p_size = self.bb_periods.len();
-            let sr_size = self.hammer_shadow_ratios.len();
-            let bm_size = self.bb_multipliers.len();
-            let vbn_size = self.volume_bar_nums.len();
-            let vir_size = self.volume_ratios.len();
-            let bt_size = self.breakthrough_thresholds.len();
-            let rp_size = self.rsi_periods.len();
-            let rob_size = self.rsi_over_buy_sell.len();
-            let i_bb_p = index % bb_p_size;
-            index /= bb_p_size;
-            let i_sr = index % sr_size;
-            index /= sr_size;
-            let i_bm = index % bm_size;
-            index /= bm_size;
-            let i_vbn = index % vbn_size;
-            index /= vbn_size;
-            let i_vir = index % vir_size;
-            index /= vir_size;
-            let i_bt = index % bt_size;
-            index /= bt_size;
-            let i_rp = index % rp_size;
-            index /= rp_size;
-            let i_rob = index % rob_size;
-            index /= rob_size;
-            let i_mlp = index % self.max_loss_percent.len();
-            index /= self.max_loss_percent.len();
-            let i_pt = index % self.take_profit_ratios.len();
-            index /= self.take_profit_ratios.len();
-            let i_usklsl = index % self.is_used_signal_k_line_stop_loss.len();
-            index /= self.is_used_signal_k_line_stop_loss.len();
-            let i_fsktpr = index % self.fix_signal_kline_take_profit_ratios.len();
-            let param = ParamMergeBuilder {
-                bb_period: self.bb_periods[i_bb_p],
-                hammer_shadow_ratio: self.hammer_shadow_ratios[i_sr],
-                bb_multiplier: self.bb_multipliers[i_bm],
-                volume_bar_num: self.volume_bar_nums[i_vbn],
-                volume_increase_ratio: self.volume_ratios[i_vir],
-                volume_decrease_ratio: self.volume_ratios[i_vir],
-                breakthrough_threshold: self.breakthrough_thresholds[i_bt],
-                ema_signal: None,
-                rsi_period: self.rsi_periods[i_rp],
-                rsi_overbought: self.rsi_over_buy_sell[i_rob].0,
-                rsi_oversold: self.rsi_over_buy_sell[i_rob].1,
-                kline_start_time: None,
-                kline_end_time: None,
-                min_k_line_num: None,
-                max_loss_percent: self.max_loss_percent[i_mlp],
-                take_profit_ratio: self.take_profit_ratios[i_pt],
-                is_used_signal_k_line_stop_loss: self.is_used_signal_k_line_stop_loss[i_usklsl],
-                fix_signal_kline_take_profit_ratio: Some(
-                    self.fix_signal_kline_take_profit_ratios[i_fsktpr],
-                ),
-                dynamic_max_loss: None,
-                dynamic_entry_amp_threshold: None,
-                dynamic_entry_loss_percent: None,
-                dynamic_entry_require_direction_mismatch: None,
-                dynamic_range_threshold: None,
-                dynamic_range_loss_percent: None,
-                position_leverage: None,
-                signal_weights: None,
-                engulfing_signal: None,
-                ema_touch_trend_signal: None,
-                leg_detection_signal: None,
-                market_structure_signal: None,
-                range_filter_signal: None,
-                chase_confirm_config: None,
-                extreme_k_filter_signal: None,
-                ema_distance_config: None,
-                atr_stop_loss_multiplier: None,
-                emit_debug: None,
-                macd_signal: None,
-                fib_retracement_signal: None,
-                entry_block_config: None,
-                candle_momentum_activation: None,
-                cross_asset_adaptive_threshold: None,
-                liquidity_sweep_reversal: None,
-                compressed_range_breakout: None,
-                ema_tunnel_retest_confirmation: None,
-                volume_profile_value_area_retest: None,
-                volume_profile_value_area_breakout: None,
-                volume_profile_failed_auction: None,
-                donchian_volume_breakout: None,
-                donchian_breakout_acceptance: None,
-                bos_fvg_retest: None,
-                fvg_reclaim: None,
-                macd_divergence_reversal: None,
-                macd_trend_reset_bos: None,
-                short_profit_protection: None,
-            };
+            let param = self.param_at_indices(&self.grid_indices(index));
             batch.push(param);
             self.current_index += 1;
         }
         batch
     }
+    /// 各维度取值个数，顺序与网格下标展开顺序一致，供参数优化器构造搜索空间。
+    pub fn dimension_sizes(&self) -> Vec<usize> {
+        vec![
+            self.bb_periods.len(),
+            self.hammer_shadow_ratios.len(),
+            self.bb_multipliers.len(),
+            self.volume_bar_nums.len(),
+            self.volume_ratios.len(),
+            self.breakthrough_thresholds.len(),
+            self.rsi_periods.len(),
+            self.rsi_over_buy_sell.len(),
+            self.max_loss_percent.len(),
+            self.take_profit_ratios.len(),
+            self.is_used_signal_k_line_stop_loss.len(),
+            self.fix_signal_kline_take_profit_ratios.len(),
+        ]
+    }
+    /// 把网格下标按维度展开为各维度的取值下标，第一个维度变化最快。
+    fn grid_indices(&self, mut index: usize) -> Vec<usize> {
+        self.dimension_sizes()
+            .into_iter()
+            .map(|size| {
+                let value = index % size;
+                index /= size;
+                value
+            })
+            .collect()
+    }
+    /// 按各维度取值下标构造参数组合；下标越界会 panic，调用方应以 `dimension_sizes` 为界。
+    pub fn param_at_indices(&self, indices: &[usize]) -> ParamMergeBuilder {
+        ParamMergeBuilder {
+            bb_period: self.bb_periods[indices[0]],
+            hammer_shadow_ratio: self.hammer_shadow_ratios[indices[1]],
+            bb_multiplier: self.bb_multipliers[indices[2]],
+            volume_bar_num: self.volume_bar_nums[indices[3]],
+            volume_increase_ratio: self.volume_ratios[indices[4]],
+            volume_decrease_ratio: self.volume_ratios[indices[4]],
+            breakthrough_threshold: self.breakthrough_thresholds[indices[5]],
+            ema_signal: None,
+            rsi_period: self.rsi_periods[indices[6]],
+            rsi_overbought: self.rsi_over_buy_sell[indices[7]].0,
+            rsi_oversold: self.rsi_over_buy_sell[indices[7]].1,
+            kline_start_time: None,
+            kline_end_time: None,
+            min_k_line_num: None,
+            max_loss_percent: self.max_loss_percent[indices[8]],
+            take_profit_ratio: self.take_profit_ratios[indices[9]],
+            is_used_signal_k_line_stop_loss: self.is_used_signal_k_line_stop_loss[indices[10]],
+            fix_signal_kline_take_profit_ratio: Some(
+                self.fix_signal_kline_take_profit_ratios[indices[11]],
+            ),
+            dynamic_max_loss: None,
+            dynamic_entry_amp_threshold: None,
+            dynamic_entry_loss_percent: None,
+            dynamic_entry_require_direction_mismatch: None,
+            dynamic_range_threshold: None,
+            dynamic_range_loss_percent: None,
+            position_leverage: None,
+            signal_weights: None,
+            engulfing_signal: None,
+            ema_touch_trend_signal: None,
+            leg_detection_signal: None,
+            market_structure_signal: None,
+            range_filter_signal: None,
+            chase_confirm_config: None,
+            extreme_k_filter_signal: None,
+            ema_distance_config: None,
+            atr_stop_loss_multiplier: None,
+            emit_debug: None,
+            macd_signal: None,
+            fib_retracement_signal: None,
+            entry_block_config: None,
+            candle_momentum_activation: None,
+            cross_asset_adaptive_threshold: None,
+            liquidity_sweep_reversal: None,
+            compressed_range_breakout: None,
+            ema_tunnel_retest_confirmation: None,
+            volume_profile_value_area_retest: None,
+            volume_profile_value_area_breakout: None,
+            volume_profile_failed_auction: None,
+            donchian_volume_breakout: None,
+            donchian_breakout_acceptance: None,
+            bos_fvg_retest: None,
+            fvg_reclaim: None,
+            macd_divergence_reversal: None,
+            macd_trend_reset_bos: None,
+            short_profit_protection: None,
+        }
+    }
     pub fn progress(&self) -> (usize, usize) {
         (self.current_index, self.total_count)
     }
@@ -609,7 +627,7 @@ fn greatest_common_divisor(mut left: usize, mut right: usize) -> usize {
     left
 }
 
-fn splitmix64(mut value: u64) -> u64 {
+pub(crate) fn splitmix64(mut value: u64) -> u64 {
     value = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
     value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
     value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
@@ -758,6 +776,26 @@ pub struct NweParamGenerator {
     total_count: usize,
 }
 impl NweParamGenerator {
+    /// 各维度名称，与 `dimension_sizes` 顺序一致。
+    pub const DIMENSION_NAMES: [&'static str; 17] = [
+        "stc_fast_length",
+        "stc_slow_length",
+        "stc_cycle_length",
+        "stc_d1_length",
+        "stc_d2_length",
+        "rsi_period",
+        "rsi_over_buy_sell",
+        "atr_period",
+        "atr_multiplier",
+        "volume_bar_num",
+        "volume_ratio",
+        "nwe_period",
+        "nwe_multi",
+        "max_loss_percent",
+        "take_profit_ratio",
+        "is_used_signal_k_line_stop_loss",
+        "k_line_hammer_shadow_ratio",
+    ];
     #[allow(clippy::too_many_arguments)]
     /// 封装当前函数，减少配置运行时调用方重复实现相同细节。
     /// 当前函数完成参数检查、流程切分与结果封装，确保上层可安全复用。
@@ -831,91 +869,83 @@ impl NweParamGenerator {
     )> {
         let mut batch = Vec::with_capacity(batch_size);
         while batch.len() < batch_size && self.current_index < self.total_count {
-            let mut idx = self.current_index;
-            let stc_fast_length_len = self.stc_fast_length.len();
-            let stc_slow_length_len = self.stc_slow_length.len();
-            let stc_cycle_length_len = self.stc_cycle_length.len();
-            let stc_d1_length_len = self.stc_d1_length.len();
-            let stc_d2_length_len = self.stc_d2_length.len();
-            let rp_len = self.rsi_periods.len();
-            let rob_len = self.rsi_over_buy_sell.len();
-            let ap_len = self.atr_periods.len();
-            let am_len = self.atr_multipliers.len();
-            let vbn_len = self.volume_bar_num.len();
-            let vr_len = self.volume_ratios.len();
-            let nwe_p_len = self.nwe_periods.len();
-            let nwe_m_len = self.nwe_multi.len();
-            let mlp_len = self.max_loss_percent.len();
-            let tpr_len = self.take_profit_ratios.len();
-            let usklsl_len = self.is_used_signal_k_line_stop_loss.len();
-            let kh_sr_len = self.k_line_hammer_shadow_ratios.len();
-            // 按维度展开索引（顺序需与 total_count 维度相同）
-            let i_stc_fast_length = idx % stc_fast_length_len;
-            idx /= stc_fast_length_len;
-            let i_stc_slow_length = idx % stc_slow_length_len;
-            idx /= stc_slow_length_len;
-            let i_stc_cycle_length = idx % stc_cycle_length_len;
-            idx /= stc_cycle_length_len;
-            let i_stc_d1_length = idx % stc_d1_length_len;
-            idx /= stc_d1_length_len;
-            let i_stc_d2_length = idx % stc_d2_length_len;
-            idx /= stc_d2_length_len;
-            let _i_rp = idx % rp_len;
-            idx /= rp_len;
-            let _i_rob = idx % rob_len;
-            idx /= rob_len;
-            let i_ap = idx % ap_len;
-            idx /= ap_len;
-            let i_am = idx % am_len;
-            idx /= am_len;
-            let i_vbn = idx % vbn_len;
-            idx /= vbn_len;
-            let i_vr = idx % vr_len;
-            idx /= vr_len;
-            let i_nwe_p = idx % nwe_p_len;
-            idx /= nwe_p_len;
-            let i_nwe_m = idx % nwe_m_len;
-            idx /= nwe_m_len;
-            let i_mlp = idx % mlp_len;
-            idx /= mlp_len;
-            let i_tpr = idx % tpr_len;
-            idx /= tpr_len;
-            let i_usklsl = idx % usklsl_len;
-            idx /= usklsl_len;
-            let i_kh_sr = idx % kh_sr_len;
-            let mut cfg = rust_quant_strategies::implementations::nwe_strategy::NweStrategyConfig {
-                period: "5m".to_string(),
-                ..Default::default()
-            };
-            cfg.stc_fast_length = self.stc_fast_length[i_stc_fast_length];
-            cfg.stc_slow_length = self.stc_slow_length[i_stc_slow_length];
-            cfg.stc_cycle_length = self.stc_cycle_length[i_stc_cycle_length];
-            cfg.stc_d1_length = self.stc_d1_length[i_stc_d1_length];
-            cfg.stc_d2_length = self.stc_d2_length[i_stc_d2_length];
-            cfg.atr_period = self.atr_periods[i_ap];
-            cfg.atr_multiplier = self.atr_multipliers[i_am];
-            cfg.nwe_period = self.nwe_periods[i_nwe_p];
-            cfg.nwe_multi = self.nwe_multi[i_nwe_m];
-            cfg.volume_bar_num = self.volume_bar_num[i_vbn];
-            cfg.volume_ratio = self.volume_ratios[i_vr];
-            // 使用 NWE 周期作为最小数据长度的基线，确保指标有足够数据
-            cfg.min_k_line_num = 500;
-            cfg.k_line_hammer_shadow_ratio = self.k_line_hammer_shadow_ratios[i_kh_sr];
-            let risk = rust_quant_strategies::strategy_common::BasicRiskStrategyConfig {
-                is_used_signal_k_line_stop_loss: Some(
-                    self.is_used_signal_k_line_stop_loss[i_usklsl],
-                ),
-                max_loss_percent: self.max_loss_percent[i_mlp],
-                atr_take_profit_ratio: Some(self.take_profit_ratios[i_tpr]),
-                fixed_signal_kline_take_profit_ratio: None,
-                dynamic_max_loss: Some(true),
-                ..Default::default()
-            };
-            batch.push((cfg, risk));
+            let param = self.param_at_indices(&self.grid_indices(self.current_index));
+            batch.push(param);
             self.current_index += 1;
         }
         batch
     }
+    /// 各维度取值个数，顺序与网格下标展开顺序一致；RSI 两个维度仅占位，不影响生成的配置。
+    pub fn dimension_sizes(&self) -> Vec<usize> {
+        vec![
+            self.stc_fast_length.len(),
+            self.stc_slow_length.len(),
+            self.stc_cycle_length.len(),
+            self.stc_d1_length.len(),
+            self.stc_d2_length.len(),
+            self.rsi_periods.len(),
+            self.rsi_over_buy_sell.len(),
+            self.atr_periods.len(),
+            self.atr_multipliers.len(),
+            self.volume_bar_num.len(),
+            self.volume_ratios.len(),
+            self.nwe_periods.len(),
+            self.nwe_multi.len(),
+            self.max_loss_percent.len(),
+            self.take_profit_ratios.len(),
+            self.is_used_signal_k_line_stop_loss.len(),
+            self.k_line_hammer_shadow_ratios.len(),
+        ]
+    }
+    /// 把网格下标按维度展开为各维度的取值下标，第一个维度变化最快。
+    fn grid_indices(&self, mut index: usize) -> Vec<usize> {
+        self.dimension_sizes()
+            .into_iter()
+            .map(|size| {
+                let value = index % size;
+                index /= size;
+                value
+            })
+            .collect()
+    }
+    /// 按各维度取值下标构造 NWE 配置与风控参数；下标越界会 panic，调用方应以 `dimension_sizes` 为界。
+    pub fn param_at_indices(
+        &self,
+        indices: &[usize],
+    ) -> (
+        rust_quant_strategies::implementations::nwe_strategy::NweStrategyConfig,
+        rust_quant_strategies::strategy_common::BasicRiskStrategyConfig,
+    ) {
+        let mut cfg = rust_quant_strategies::implementations::nwe_strategy::NweStrategyConfig {
+            period: "5m".to_string(),
+            ..Default::default()
+        };
+        cfg.stc_fast_length = self.stc_fast_length[indices[0]];
+        cfg.stc_slow_length = self.stc_slow_length[indices[1]];
+        cfg.stc_cycle_length = self.stc_cycle_length[indices[2]];
+        cfg.stc_d1_length = self.stc_d1_length[indices[3]];
+        cfg.stc_d2_length = self.stc_d2_length[indices[4]];
+        cfg.atr_period = self.atr_periods[indices[7]];
+        cfg.atr_multiplier = self.atr_multipliers[indices[8]];
+        cfg.nwe_period = self.nwe_periods[indices[11]];
+        cfg.nwe_multi = self.nwe_multi[indices[12]];
+        cfg.volume_bar_num = self.volume_bar_num[indices[9]];
+        cfg.volume_ratio = self.volume_ratios[indices[10]];
+        // 使用 NWE 周期作为最小数据长度的基线，确保指标有足够数据
+        cfg.min_k_line_num = 500;
+        cfg.k_line_hammer_shadow_ratio = self.k_line_hammer_shadow_ratios[indices[16]];
+        let risk = rust_quant_strategies::strategy_common::BasicRiskStrategyConfig {
+            is_used_signal_k_line_stop_loss: Some(
+                self.is_used_signal_k_line_stop_loss[indices[15]],
+            ),
+            max_loss_percent: self.max_loss_percent[indices[13]],
+            atr_take_profit_ratio: Some(self.take_profit_ratios[indices[14]]),
+            fixed_signal_kline_take_profit_ratio: None,
+            dynamic_max_loss: Some(true),
+            ..Default::default()
+        };
+        (cfg, risk)
+    }
     pub fn progress(&self) -> (usize, usize) {
         (self.current_index, self.total_count)
     }
@@ -1,6 +1,7 @@
 pub mod data_sync;
 pub mod data_validator;
 pub mod job_param_generator;
+pub mod param_optimizer;
 pub mod progress_manager;
 pub mod signal_logger;
 pub mod strategy_config;
@@ -9,6 +10,7 @@ pub mod time_checker;
 pub use data_sync::*;
 pub use data_validator::*;
 pub use job_param_generator::*;
+pub use param_optimizer::*;
 pub use progress_manager::*;
 pub use signal_logger::*;
 pub use strategy_config::*;
@@ -0,0 +1,1018 @@
+//! 参数优化器
+//!
+//! 在 `ParamGenerator` 的离散参数空间上做序贯搜索：每轮根据已评估的试验提出下一批候选，
+//! 用几百次回测替代数万次网格/随机采样。候选以每个维度的取值下标表示，
+//! 由 `ParamGenerator::param_at_indices` 还原为 `ParamMergeBuilder`。
+use crate::infra::job_param_generator::splitmix64;
+use anyhow::{anyhow, Result};
+use rust_quant_strategies::framework::backtest::BackTestResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::str::FromStr;
+/// 回测失败的试验得分；使用有限值而不是负无穷，保证进度 JSON 可以序列化。
+pub const FAILED_TRIAL_SCORE: f64 = -1.0e9;
+/// 回测初始资金，与 `BacktestService::save_backtest_log` 的口径一致。
+const INITIAL_FUNDS: f64 = 100.0;
+/// 每个待选位置生成的候选数，候选越多采集函数越准，但 GP 预测成本线性增加。
+const CANDIDATES_PER_SUGGESTION: usize = 32;
+/// GP 最多使用的历史试验数，避免 O(n^3) 的 Cholesky 分解在长搜索中失控。
+const MAX_GP_OBSERVATIONS: usize = 256;
+/// 搜索策略。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptimizerStrategyKind {
+    /// Tree-structured Parzen Estimator：好/坏两组试验分别建模，取密度比最大的候选。
+    Tpe,
+    /// 高斯过程代理模型 + 期望提升（EI）采集函数。
+    GaussianProcess,
+    /// 遗传算法：锦标赛选择、均匀交叉与邻域变异。
+    Genetic,
+}
+impl OptimizerStrategyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tpe => "tpe",
+            Self::GaussianProcess => "gaussian_process",
+            Self::Genetic => "genetic",
+        }
+    }
+}
+impl FromStr for OptimizerStrategyKind {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "tpe" => Ok(Self::Tpe),
+            "gp" | "gaussian_process" | "bayes" | "bayesian" => Ok(Self::GaussianProcess),
+            "ga" | "genetic" => Ok(Self::Genetic),
+            other => Err(anyhow!(
+                "不支持的参数优化策略: {}，可选 tpe / gp / ga",
+                other
+            )),
+        }
+    }
+}
+/// 单次回测的目标指标。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BacktestObjectiveMetrics {
+    /// 总收益率（%），以初始资金 100 计。
+    pub total_return_pct: f64,
+    /// 按平仓顺序累计权益的最大回撤（%）。
+    pub max_drawdown_pct: f64,
+    /// 已平仓交易数，同一开仓时间的分批平仓计为一笔。
+    pub trade_count: usize,
+}
+impl BacktestObjectiveMetrics {
+    pub fn from_result(result: &BackTestResult) -> Self {
+        Self::from_closed_trades(
+            result.funds,
+            result
+                .trade_records
+                .iter()
+                .filter(|record| record.option_type.ends_with("close"))
+                .map(|record| (record.open_position_time.as_str(), record.profit_loss)),
+        )
+    }
+    fn from_closed_trades<'a>(funds: f64, closed: impl Iterator<Item = (&'a str, f64)>) -> Self {
+        let mut equity = INITIAL_FUNDS;
+        let mut peak = equity;
+        let mut max_drawdown_pct: f64 = 0.0;
+        let mut opened = HashSet::new();
+        for (open_time, profit_loss) in closed {
+            opened.insert(open_time);
+            equity += profit_loss;
+            peak = peak.max(equity);
+            if peak > 0.0 {
+                max_drawdown_pct = max_drawdown_pct.max((peak - equity) / peak * 100.0);
+            }
+        }
+        Self {
+            total_return_pct: (funds / INITIAL_FUNDS - 1.0) * 100.0,
+            max_drawdown_pct,
+            trade_count: opened.len(),
+        }
+    }
+    /// 三个目标上是否帕累托支配 `other`：收益不低、回撤不高、交易数不少，且至少一项更优。
+    pub fn dominates(&self, other: &Self) -> bool {
+        let no_worse = self.total_return_pct >= other.total_return_pct
+            && self.max_drawdown_pct <= other.max_drawdown_pct
+            && self.trade_count >= other.trade_count;
+        let better = self.total_return_pct > other.total_return_pct
+            || self.max_drawdown_pct < other.max_drawdown_pct
+            || self.trade_count > other.trade_count;
+        no_worse && better
+    }
+}
+/// 多目标加权评分：收益为正向，回撤为负向，交易数取对数避免高频组合独占排名。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptimizerObjective {
+    #[serde(default = "default_return_weight")]
+    pub return_weight: f64,
+    #[serde(default = "default_drawdown_weight")]
+    pub drawdown_weight: f64,
+    #[serde(default = "default_trade_count_weight")]
+    pub trade_count_weight: f64,
+    /// 交易数低于该值时按缺口比例扣分，防止只开一两单的偶然结果排到最前。
+    #[serde(default = "default_min_trades")]
+    pub min_trades: usize,
+    /// 交易数为 0 时的满额扣分。
+    #[serde(default = "default_insufficient_trade_penalty")]
+    pub insufficient_trade_penalty: f64,
+}
+fn default_return_weight() -> f64 {
+    1.0
+}
+fn default_drawdown_weight() -> f64 {
+    0.5
+}
+fn default_trade_count_weight() -> f64 {
+    1.0
+}
+fn default_min_trades() -> usize {
+    10
+}
+fn default_insufficient_trade_penalty() -> f64 {
+    100.0
+}
+impl Default for OptimizerObjective {
+    fn default() -> Self {
+        Self {
+            return_weight: default_return_weight(),
+            drawdown_weight: default_drawdown_weight(),
+            trade_count_weight: default_trade_count_weight(),
+            min_trades: default_min_trades(),
+            insufficient_trade_penalty: default_insufficient_trade_penalty(),
+        }
+    }
+}
+impl OptimizerObjective {
+    pub fn with_weights(
+        mut self,
+        return_weight: f64,
+        drawdown_weight: f64,
+        trade_count_weight: f64,
+    ) -> Self {
+        self.return_weight = return_weight;
+        self.drawdown_weight = drawdown_weight;
+        self.trade_count_weight = trade_count_weight;
+        self
+    }
+    pub fn with_min_trades(mut self, min_trades: usize) -> Self {
+        self.min_trades = min_trades;
+        self
+    }
+    pub fn score(&self, metrics: &BacktestObjectiveMetrics) -> f64 {
+        let mut score = self.return_weight * metrics.total_return_pct
+            - self.drawdown_weight * metrics.max_drawdown_pct
+            + self.trade_count_weight * (metrics.trade_count as f64).ln_1p();
+        if metrics.trade_count < self.min_trades {
+            let shortfall = 1.0 - metrics.trade_count as f64 / self.min_trades as f64;
+            score -= self.insufficient_trade_penalty * shortfall;
+        }
+        if score.is_finite() {
+            score
+        } else {
+            FAILED_TRIAL_SCORE
+        }
+    }
+}
+/// 优化器配置。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptimizerConfig {
+    pub strategy: OptimizerStrategyKind,
+    /// 最多评估的参数组合数。
+    #[serde(default = "default_max_trials")]
+    pub max_trials: usize,
+    /// 每批并发评估的组合数。
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    /// 冷启动阶段的随机试验数，代理模型需要足够样本才有意义。
+    #[serde(default = "default_initial_random_trials")]
+    pub initial_random_trials: usize,
+    /// 连续多少次试验没有超过最优得分 `min_improvement` 就提前停止；0 表示不启用。
+    #[serde(default = "default_patience")]
+    pub patience: usize,
+    #[serde(default = "default_min_improvement")]
+    pub min_improvement: f64,
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub objective: OptimizerObjective,
+    /// TPE 中划为“好”组的试验比例。
+    #[serde(default = "default_tpe_gamma")]
+    pub tpe_gamma: f64,
+    /// 遗传算法的精英种群大小。
+    #[serde(default = "default_ga_population")]
+    pub ga_population: usize,
+    /// 遗传算法每个基因的变异概率。
+    #[serde(default = "default_ga_mutation_rate")]
+    pub ga_mutation_rate: f64,
+}
+fn default_max_trials() -> usize {
+    300
+}
+fn default_batch_size() -> usize {
+    8
+}
+fn default_initial_random_trials() -> usize {
+    24
+}
+fn default_patience() -> usize {
+    80
+}
+fn default_min_improvement() -> f64 {
+    0.01
+}
+fn default_tpe_gamma() -> f64 {
+    0.25
+}
+fn default_ga_population() -> usize {
+    24
+}
+fn default_ga_mutation_rate() -> f64 {
+    0.15
+}
+impl OptimizerConfig {
+    pub fn new(strategy: OptimizerStrategyKind) -> Self {
+        Self {
+            strategy,
+            max_trials: default_max_trials(),
+            batch_size: default_batch_size(),
+            initial_random_trials: default_initial_random_trials(),
+            patience: default_patience(),
+            min_improvement: default_min_improvement(),
+            seed: 0,
+            objective: OptimizerObjective::default(),
+            tpe_gamma: default_tpe_gamma(),
+            ga_population: default_ga_population(),
+            ga_mutation_rate: default_ga_mutation_rate(),
+        }
+    }
+    pub fn with_max_trials(mut self, max_trials: usize) -> Self {
+        self.max_trials = max_trials.max(1);
+        self
+    }
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+    pub fn with_initial_random_trials(mut self, trials: usize) -> Self {
+        self.initial_random_trials = trials;
+        self
+    }
+    pub fn with_early_stopping(mut self, patience: usize, min_improvement: f64) -> Self {
+        self.patience = patience;
+        self.min_improvement = min_improvement;
+        self
+    }
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    pub fn with_objective(mut self, objective: OptimizerObjective) -> Self {
+        self.objective = objective;
+        self
+    }
+}
+/// 已评估的一组参数。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptimizerTrial {
+    /// 每个维度的取值下标。
+    pub indices: Vec<usize>,
+    /// 回测失败时为空。
+    pub metrics: Option<BacktestObjectiveMetrics>,
+    pub score: f64,
+}
+/// 停止原因。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptimizerStopReason {
+    MaxTrials,
+    SearchSpaceExhausted,
+    EarlyStopped,
+}
+impl OptimizerStopReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MaxTrials => "max_trials",
+            Self::SearchSpaceExhausted => "search_space_exhausted",
+            Self::EarlyStopped => "early_stopped",
+        }
+    }
+}
+/// 离散参数空间，维度顺序与 `ParamGenerator` 的网格下标展开顺序一致。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchSpace {
+    dims: Vec<usize>,
+}
+impl SearchSpace {
+    pub fn new(dims: Vec<usize>) -> Result<Self> {
+        if dims.is_empty() || dims.contains(&0) {
+            return Err(anyhow!("参数空间存在空维度: {:?}", dims));
+        }
+        Ok(Self { dims })
+    }
+    pub fn dims(&self) -> &[usize] {
+        &self.dims
+    }
+    /// 组合总数，溢出时饱和。
+    pub fn size(&self) -> usize {
+        self.dims
+            .iter()
+            .fold(1usize, |size, dim| size.saturating_mul(*dim))
+    }
+    fn random_point(&self, rng: &mut OptimizerRng) -> Vec<usize> {
+        self.dims.iter().map(|dim| rng.below(*dim)).collect()
+    }
+    /// 把下标映射到 `[0, 1]`，单值维度固定为 0。
+    fn normalize(&self, indices: &[usize]) -> Vec<f64> {
+        indices
+            .iter()
+            .zip(&self.dims)
+            .map(|(index, dim)| {
+                if *dim <= 1 {
+                    0.0
+                } else {
+                    *index as f64 / (*dim - 1) as f64
+                }
+            })
+            .collect()
+    }
+    /// 邻域变异：多数情况下移动一格，保留有序参数的局部性；少数情况下随机跳转。
+    fn mutate_gene(&self, dim_index: usize, value: usize, rng: &mut OptimizerRng) -> usize {
+        let dim = self.dims[dim_index];
+        if dim <= 1 {
+            return 0;
+        }
+        if rng.next_f64() < 0.7 {
+            if value == 0 {
+                1
+            } else if value + 1 >= dim || rng.next_f64() < 0.5 {
+                value - 1
+            } else {
+                value + 1
+            }
+        } else {
+            rng.below(dim)
+        }
+    }
+}
+/// 可重放的伪随机数，基于 `splitmix64`，不引入额外依赖。
+#[derive(Debug, Clone)]
+pub struct OptimizerRng {
+    state: u64,
+}
+impl OptimizerRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        splitmix64(self.state)
+    }
+    /// `[0, 1)` 均匀分布。
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    pub fn below(&mut self, upper: usize) -> usize {
+        if upper <= 1 {
+            return 0;
+        }
+        (self.next_u64() % upper as u64) as usize
+    }
+}
+/// 可插拔的搜索策略：根据历史试验提出候选，去重和冷启动由 `ParamOptimizer` 负责。
+pub trait SearchStrategy: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn suggest(
+        &self,
+        space: &SearchSpace,
+        trials: &[OptimizerTrial],
+        count: usize,
+        rng: &mut OptimizerRng,
+    ) -> Vec<Vec<usize>>;
+}
+/// 按配置创建搜索策略。
+pub fn search_strategy(config: &OptimizerConfig) -> Box<dyn SearchStrategy> {
+    match config.strategy {
+        OptimizerStrategyKind::Tpe => Box::new(TpeSearch {
+            gamma: config.tpe_gamma,
+        }),
+        OptimizerStrategyKind::GaussianProcess => Box::new(GaussianProcessSearch::default()),
+        OptimizerStrategyKind::Genetic => Box::new(GeneticSearch {
+            population: config.ga_population,
+            mutation_rate: config.ga_mutation_rate,
+        }),
+    }
+}
+fn trials_by_score_desc(trials: &[OptimizerTrial]) -> Vec<&OptimizerTrial> {
+    let mut sorted: Vec<&OptimizerTrial> = trials.iter().collect();
+    sorted.sort_by(|a, b| b.score.total_cmp(&a.score));
+    sorted
+}
+/// TPE：每个维度独立估计好/坏两组的离散 Parzen 密度，相邻取值共享一半权重。
+pub struct TpeSearch {
+    pub gamma: f64,
+}
+impl TpeSearch {
+    fn densities(space: &SearchSpace, group: &[&OptimizerTrial]) -> Vec<Vec<f64>> {
+        space
+            .dims()
+            .iter()
+            .enumerate()
+            .map(|(dim_index, dim)| {
+                // 均匀先验保证没出现过的取值也有被采样的机会。
+                let mut weights = vec![1.0 / *dim as f64; *dim];
+                for trial in group {
+                    let value = trial.indices[dim_index];
+                    weights[value] += 1.0;
+                    if value > 0 {
+                        weights[value - 1] += 0.5;
+                    }
+                    if value + 1 < *dim {
+                        weights[value + 1] += 0.5;
+                    }
+                }
+                let total: f64 = weights.iter().sum();
+                weights.iter().map(|weight| weight / total).collect()
+            })
+            .collect()
+    }
+    fn sample(density: &[f64], rng: &mut OptimizerRng) -> usize {
+        let mut target = rng.next_f64();
+        for (index, probability) in density.iter().enumerate() {
+            if target < *probability {
+                return index;
+            }
+            target -= probability;
+        }
+        density.len() - 1
+    }
+}
+impl SearchStrategy for TpeSearch {
+    fn name(&self) -> &'static str {
+        "tpe"
+    }
+    fn suggest(
+        &self,
+        space: &SearchSpace,
+        trials: &[OptimizerTrial],
+        count: usize,
+        rng: &mut OptimizerRng,
+    ) -> Vec<Vec<usize>> {
+        let sorted = trials_by_score_desc(trials);
+        let good_count =
+            ((sorted.len() as f64 * self.gamma).ceil() as usize).clamp(1, sorted.len().max(1));
+        let (good, bad) = sorted.split_at(good_count.min(sorted.len()));
+        let good_density = Self::densities(space, good);
+        let bad_density = Self::densities(space, bad);
+        let mut candidates: Vec<(f64, Vec<usize>)> = (0..count * CANDIDATES_PER_SUGGESTION)
+            .map(|_| {
+                let point: Vec<usize> = good_density
+                    .iter()
+                    .map(|density| Self::sample(density, rng))
+                    .collect();
+                let log_ratio = point
+                    .iter()
+                    .enumerate()
+                    .map(|(dim_index, value)| {
+                        good_density[dim_index][*value].ln() - bad_density[dim_index][*value].ln()
+                    })
+                    .sum::<f64>();
+                (log_ratio, point)
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.into_iter().map(|(_, point)| point).collect()
+    }
+}
+/// 高斯过程代理：RBF 核 + 期望提升，候选来自随机点和当前最优试验的邻域。
+pub struct GaussianProcessSearch {
+    /// 归一化坐标下的核长度尺度。
+    pub length_scale: f64,
+    /// 观测噪声方差（标准化后的得分尺度）。
+    pub noise: f64,
+    /// EI 的探索偏置。
+    pub exploration: f64,
+}
+impl Default for GaussianProcessSearch {
+    fn default() -> Self {
+        Self {
+            length_scale: 0.2,
+            noise: 1.0e-2,
+            exploration: 0.01,
+        }
+    }
+}
+impl GaussianProcessSearch {
+    fn kernel(&self, left: &[f64], right: &[f64]) -> f64 {
+        let mean_sq = left
+            .iter()
+            .zip(right)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>()
+            / left.len().max(1) as f64;
+        (-mean_sq / (2.0 * self.length_scale.powi(2))).exp()
+    }
+}
+impl SearchStrategy for GaussianProcessSearch {
+    fn name(&self) -> &'static str {
+        "gaussian_process"
+    }
+    fn suggest(
+        &self,
+        space: &SearchSpace,
+        trials: &[OptimizerTrial],
+        count: usize,
+        rng: &mut OptimizerRng,
+    ) -> Vec<Vec<usize>> {
+        let observed: Vec<&OptimizerTrial> = trials_by_score_desc(trials)
+            .into_iter()
+            .take(MAX_GP_OBSERVATIONS)
+            .collect();
+        if observed.is_empty() {
+            return vec![];
+        }
+        let xs: Vec<Vec<f64>> = observed
+            .iter()
+            .map(|trial| space.normalize(&trial.indices))
+            .collect();
+        let raw: Vec<f64> = observed.iter().map(|trial| trial.score).collect();
+        let mean = raw.iter().sum::<f64>() / raw.len() as f64;
+        let std = (raw.iter().map(|y| (y - mean).powi(2)).sum::<f64>() / raw.len() as f64)
+            .sqrt()
+            .max(1.0e-9);
+        let ys: Vec<f64> = raw.iter().map(|y| (y - mean) / std).collect();
+        let n = xs.len();
+        let mut gram = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            for j in 0..=i {
+                let value = self.kernel(&xs[i], &xs[j]) + if i == j { self.noise } else { 0.0 };
+                gram[i][j] = value;
+                gram[j][i] = value;
+            }
+        }
+        let Some(chol) = cholesky(&gram) else {
+            return vec![];
+        };
+        let alpha = cholesky_solve(&chol, &ys);
+        let best = ys.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let elites = &observed[..observed.len().min(5)];
+        let mut candidates: Vec<(f64, Vec<usize>)> = (0..count * CANDIDATES_PER_SUGGESTION)
+            .map(|candidate_index| {
+                // 一半候选在精英附近做局部搜索，一半全局随机，兼顾开发与探索。
+                let point = if candidate_index % 2 == 0 {
+                    let elite = &elites[rng.below(elites.len())].indices;
+                    elite
+                        .iter()
+                        .enumerate()
+                        .map(|(dim_index, value)| {
+                            if rng.next_f64() < 0.3 {
+                                space.mutate_gene(dim_index, *value, rng)
+                            } else {
+                                *value
+                            }
+                        })
+                        .collect()
+                } else {
+                    space.random_point(rng)
+                };
+                let x = space.normalize(&point);
+                let k_star: Vec<f64> = xs.iter().map(|xi| self.kernel(xi, &x)).collect();
+                let mu: f64 = k_star.iter().zip(&alpha).map(|(k, a)| k * a).sum();
+                let v = forward_substitute(&chol, &k_star);
+                let variance = (1.0 + self.noise - v.iter().map(|x| x * x).sum::<f64>()).max(0.0);
+                let ei = expected_improvement(mu, variance.sqrt(), best + self.exploration);
+                (ei, point)
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.into_iter().map(|(_, point)| point).collect()
+    }
+}
+fn cholesky(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let mut lower = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+            if i == j {
+                let diagonal = matrix[i][i] - sum;
+                if diagonal <= 0.0 {
+                    return None;
+                }
+                lower[i][j] = diagonal.sqrt();
+            } else {
+                lower[i][j] = (matrix[i][j] - sum) / lower[j][j];
+            }
+        }
+    }
+    Some(lower)
+}
+fn forward_substitute(lower: &[Vec<f64>], rhs: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0; rhs.len()];
+    for i in 0..rhs.len() {
+        let sum: f64 = (0..i).map(|k| lower[i][k] * out[k]).sum();
+        out[i] = (rhs[i] - sum) / lower[i][i];
+    }
+    out
+}
+fn cholesky_solve(lower: &[Vec<f64>], rhs: &[f64]) -> Vec<f64> {
+    let forward = forward_substitute(lower, rhs);
+    let n = forward.len();
+    let mut out = vec![0.0; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|k| lower[k][i] * out[k]).sum();
+        out[i] = (forward[i] - sum) / lower[i][i];
+    }
+    out
+}
+fn expected_improvement(mu: f64, sigma: f64, target: f64) -> f64 {
+    if sigma <= 1.0e-12 {
+        return (mu - target).max(0.0);
+    }
+    let z = (mu - target) / sigma;
+    let pdf = (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt();
+    let cdf = 0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2));
+    (mu - target) * cdf + sigma * pdf
+}
+/// Abramowitz & Stegun 7.1.26，误差小于 1.5e-7，对采集函数排序足够。
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    sign * (1.0 - poly * (-x * x).exp())
+}
+/// 遗传算法：以得分最高的试验为种群，锦标赛选择父代后均匀交叉并逐基因变异。
+pub struct GeneticSearch {
+    pub population: usize,
+    pub mutation_rate: f64,
+}
+impl GeneticSearch {
+    fn tournament<'a>(
+        population: &[&'a OptimizerTrial],
+        rng: &mut OptimizerRng,
+    ) -> &'a OptimizerTrial {
+        (0..3)
+            .map(|_| population[rng.below(population.len())])
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+            .expect("锦标赛至少抽取一个个体")
+    }
+}
+impl SearchStrategy for GeneticSearch {
+    fn name(&self) -> &'static str {
+        "genetic"
+    }
+    fn suggest(
+        &self,
+        space: &SearchSpace,
+        trials: &[OptimizerTrial],
+        count: usize,
+        rng: &mut OptimizerRng,
+    ) -> Vec<Vec<usize>> {
+        let population: Vec<&OptimizerTrial> = trials_by_score_desc(trials)
+            .into_iter()
+            .take(self.population.max(2))
+            .collect();
+        if population.is_empty() {
+            return vec![];
+        }
+        (0..count * 4)
+            .map(|_| {
+                let left = Self::tournament(&population, rng);
+                let right = Self::tournament(&population, rng);
+                left.indices
+                    .iter()
+                    .zip(&right.indices)
+                    .enumerate()
+                    .map(|(dim_index, (a, b))| {
+                        let gene = if rng.next_f64() < 0.5 { *a } else { *b };
+                        if rng.next_f64() < self.mutation_rate {
+                            space.mutate_gene(dim_index, gene, rng)
+                        } else {
+                            gene
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+/// 参数优化器：维护试验历史，负责冷启动、去重、提前停止和帕累托前沿。
+///
+/// 候选只由配置、种子和已评估的试验决定，断点恢复时把保存的试验放回即可继续同一条搜索路径。
+pub struct ParamOptimizer {
+    space: SearchSpace,
+    config: OptimizerConfig,
+    strategy: Box<dyn SearchStrategy>,
+    trials: Vec<OptimizerTrial>,
+}
+impl ParamOptimizer {
+    pub fn new(space: SearchSpace, config: OptimizerConfig) -> Self {
+        let strategy = search_strategy(&config);
+        Self::with_strategy(space, config, strategy)
+    }
+    /// 使用自定义搜索策略。
+    pub fn with_strategy(
+        space: SearchSpace,
+        config: OptimizerConfig,
+        strategy: Box<dyn SearchStrategy>,
+    ) -> Self {
+        Self {
+            space,
+            config,
+            strategy,
+            trials: Vec::new(),
+        }
+    }
+    /// 恢复已保存的试验，维度不匹配的记录直接丢弃。
+    pub fn with_trials(mut self, trials: Vec<OptimizerTrial>) -> Self {
+        let dims = self.space.dims().to_vec();
+        self.trials = trials
+            .into_iter()
+            .filter(|trial| {
+                trial.indices.len() == dims.len()
+                    && trial.indices.iter().zip(&dims).all(|(i, d)| i < d)
+            })
+            .collect();
+        self
+    }
+    pub fn space(&self) -> &SearchSpace {
+        &self.space
+    }
+    pub fn strategy_name(&self) -> &'static str {
+        self.strategy.name()
+    }
+    pub fn trials(&self) -> &[OptimizerTrial] {
+        &self.trials
+    }
+    /// 记录一次评估结果，返回得分。
+    pub fn record(
+        &mut self,
+        indices: Vec<usize>,
+        metrics: Option<BacktestObjectiveMetrics>,
+    ) -> f64 {
+        let score = metrics
+            .as_ref()
+            .map(|metrics| self.config.objective.score(metrics))
+            .unwrap_or(FAILED_TRIAL_SCORE);
+        self.trials.push(OptimizerTrial {
+            indices,
+            metrics,
+            score,
+        });
+        score
+    }
+    pub fn best(&self) -> Option<&OptimizerTrial> {
+        self.trials
+            .iter()
+            .filter(|trial| trial.metrics.is_some())
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+    }
+    /// 收益、回撤、交易数三个目标上的非支配试验，按得分降序。
+    pub fn pareto_front(&self) -> Vec<&OptimizerTrial> {
+        let evaluated: Vec<(&OptimizerTrial, &BacktestObjectiveMetrics)> = self
+            .trials
+            .iter()
+            .filter_map(|trial| trial.metrics.as_ref().map(|metrics| (trial, metrics)))
+            .collect();
+        let mut front: Vec<&OptimizerTrial> = evaluated
+            .iter()
+            .filter(|(_, metrics)| !evaluated.iter().any(|(_, other)| other.dominates(metrics)))
+            .map(|(trial, _)| *trial)
+            .collect();
+        front.sort_by(|a, b| b.score.total_cmp(&a.score));
+        front
+    }
+    /// 自上次有效提升以来的试验数；冷启动阶段不计入。
+    fn trials_since_improvement(&self) -> usize {
+        let mut best = f64::NEG_INFINITY;
+        let mut last_improvement = 0;
+        for (index, trial) in self.trials.iter().enumerate() {
+            if trial.score > best + self.config.min_improvement {
+                best = trial.score;
+                last_improvement = index;
+            }
+        }
+        let counted_from =
+            last_improvement.max(self.config.initial_random_trials.saturating_sub(1));
+        self.trials.len().saturating_sub(counted_from + 1)
+    }
+    pub fn stop_reason(&self) -> Option<OptimizerStopReason> {
+        if self.trials.len() >= self.config.max_trials {
+            return Some(OptimizerStopReason::MaxTrials);
+        }
+        if self.trials.len() >= self.space.size() {
+            return Some(OptimizerStopReason::SearchSpaceExhausted);
+        }
+        if self.config.patience > 0 && self.trials_since_improvement() >= self.config.patience {
+            return Some(OptimizerStopReason::EarlyStopped);
+        }
+        None
+    }
+    /// 提出下一批未评估过的候选；已满足停止条件时返回空。
+    pub fn next_batch(&self) -> Vec<Vec<usize>> {
+        if self.stop_reason().is_some() {
+            return vec![];
+        }
+        let count = self
+            .config
+            .batch_size
+            .max(1)
+            .min(self.config.max_trials - self.trials.len())
+            .min(self.space.size() - self.trials.len());
+        let mut rng = OptimizerRng::new(self.config.seed ^ splitmix64(self.trials.len() as u64));
+        let mut seen: HashSet<Vec<usize>> = self
+            .trials
+            .iter()
+            .map(|trial| trial.indices.clone())
+            .collect();
+        let mut batch = Vec::with_capacity(count);
+        if self.trials.len() >= self.config.initial_random_trials {
+            let evaluated: Vec<OptimizerTrial> = self
+                .trials
+                .iter()
+                .filter(|trial| trial.metrics.is_some())
+                .cloned()
+                .collect();
+            for point in self
+                .strategy
+                .suggest(&self.space, &evaluated, count, &mut rng)
+            {
+                if batch.len() >= count {
+                    break;
+                }
+                if seen.insert(point.clone()) {
+                    batch.push(point);
+                }
+            }
+        }
+        // 冷启动或代理模型给不出足够新候选时用随机点补齐；尝试次数有限，避免近乎穷尽的空间里死循环。
+        let mut attempts = 0;
+        while batch.len() < count && attempts < count * 256 {
+            attempts += 1;
+            let point = self.space.random_point(&mut rng);
+            if seen.insert(point.clone()) {
+                batch.push(point);
+            }
+        }
+        batch
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    /// 单峰目标：峰值在每个维度的中点，得分随距离线性下降。
+    fn peak_metrics(space: &SearchSpace, indices: &[usize]) -> BacktestObjectiveMetrics {
+        let distance: f64 = indices
+            .iter()
+            .zip(space.dims())
+            .map(|(index, dim)| (*index as f64 - (*dim / 2) as f64).abs())
+            .sum();
+        BacktestObjectiveMetrics {
+            total_return_pct: 50.0 - distance * 5.0,
+            max_drawdown_pct: 10.0,
+            trade_count: 30,
+        }
+    }
+    fn run(strategy: OptimizerStrategyKind) -> ParamOptimizer {
+        let space = SearchSpace::new(vec![9, 9, 9, 9]).unwrap();
+        let config = OptimizerConfig::new(strategy)
+            .with_max_trials(120)
+            .with_batch_size(6)
+            .with_initial_random_trials(18)
+            .with_early_stopping(0, 0.0)
+            .with_seed(7);
+        let mut optimizer = ParamOptimizer::new(space.clone(), config);
+        loop {
+            let batch = optimizer.next_batch();
+            if batch.is_empty() {
+                break;
+            }
+            for indices in batch {
+                let metrics = peak_metrics(&space, &indices);
+                optimizer.record(indices, Some(metrics));
+            }
+        }
+        optimizer
+    }
+    #[test]
+    fn every_strategy_beats_random_search_on_unimodal_objective() {
+        let space = SearchSpace::new(vec![9, 9, 9, 9]).unwrap();
+        let mut rng = OptimizerRng::new(7);
+        let random_best = (0..120)
+            .map(|_| peak_metrics(&space, &space.random_point(&mut rng)).total_return_pct)
+            .fold(f64::NEG_INFINITY, f64::max);
+        for strategy in [
+            OptimizerStrategyKind::Tpe,
+            OptimizerStrategyKind::GaussianProcess,
+            OptimizerStrategyKind::Genetic,
+        ] {
+            let optimizer = run(strategy);
+            assert_eq!(optimizer.trials().len(), 120, "{:?}", strategy);
+            assert_eq!(
+                optimizer.stop_reason(),
+                Some(OptimizerStopReason::MaxTrials)
+            );
+            let unique: HashSet<_> = optimizer.trials().iter().map(|t| &t.indices).collect();
+            assert_eq!(unique.len(), 120, "{:?} 产生了重复候选", strategy);
+            let best = optimizer.best().unwrap().metrics.unwrap().total_return_pct;
+            assert!(
+                best >= random_best,
+                "{:?} best={} random_best={}",
+                strategy,
+                best,
+                random_best
+            );
+        }
+    }
+    #[test]
+    fn resumed_optimizer_replays_the_same_candidates() {
+        let space = SearchSpace::new(vec![5, 4, 6]).unwrap();
+        let config = OptimizerConfig::new(OptimizerStrategyKind::Tpe)
+            .with_initial_random_trials(4)
+            .with_batch_size(3)
+            .with_seed(11);
+        let mut original = ParamOptimizer::new(space.clone(), config.clone());
+        for _ in 0..3 {
+            for indices in original.next_batch() {
+                let metrics = peak_metrics(&space, &indices);
+                original.record(indices, Some(metrics));
+            }
+        }
+        let resumed = ParamOptimizer::new(space, config).with_trials(original.trials().to_vec());
+        assert_eq!(resumed.next_batch(), original.next_batch());
+    }
+    #[test]
+    fn early_stopping_and_exhaustion() {
+        let space = SearchSpace::new(vec![2, 3]).unwrap();
+        let config = OptimizerConfig::new(OptimizerStrategyKind::Genetic)
+            .with_initial_random_trials(2)
+            .with_early_stopping(3, 0.5);
+        let flat = BacktestObjectiveMetrics {
+            total_return_pct: 1.0,
+            max_drawdown_pct: 1.0,
+            trade_count: 20,
+        };
+        let mut optimizer = ParamOptimizer::new(space.clone(), config.clone());
+        for indices in [vec![0, 0], vec![1, 0], vec![0, 1], vec![1, 1], vec![0, 2]] {
+            assert_eq!(optimizer.stop_reason(), None);
+            optimizer.record(indices, Some(flat));
+        }
+        assert_eq!(
+            optimizer.stop_reason(),
+            Some(OptimizerStopReason::EarlyStopped)
+        );
+        assert!(optimizer.next_batch().is_empty());
+        let mut exhaustive = ParamOptimizer::new(
+            space,
+            config.with_early_stopping(0, 0.0).with_batch_size(10),
+        );
+        let batch = exhaustive.next_batch();
+        assert_eq!(batch.len(), 6);
+        for indices in batch {
+            exhaustive.record(indices, None);
+        }
+        assert_eq!(
+            exhaustive.stop_reason(),
+            Some(OptimizerStopReason::SearchSpaceExhausted)
+        );
+        assert!(exhaustive.best().is_none());
+    }
+    #[test]
+    fn objective_penalizes_drawdown_and_thin_samples() {
+        let objective = OptimizerObjective::default();
+        let metrics = BacktestObjectiveMetrics::from_closed_trades(
+            112.0,
+            [("t1", 10.0), ("t2", -20.0), ("t2", 5.0), ("t3", 17.0)].into_iter(),
+        );
+        assert!((metrics.total_return_pct - 12.0).abs() < 1e-9);
+        assert!((metrics.max_drawdown_pct - 20.0 / 110.0 * 100.0).abs() < 1e-9);
+        assert_eq!(metrics.trade_count, 3);
+        let thin = objective.score(&metrics);
+        let thick = objective.score(&BacktestObjectiveMetrics {
+            trade_count: 10,
+            ..metrics
+        });
+        assert!(thick > thin + 60.0);
+        let deep = objective.score(&BacktestObjectiveMetrics {
+            max_drawdown_pct: 40.0,
+            trade_count: 10,
+            ..metrics
+        });
+        assert!(deep < thick);
+        let front_winner = BacktestObjectiveMetrics {
+            max_drawdown_pct: 10.0,
+            ..metrics
+        };
+        assert!(front_winner.dominates(&metrics));
+        assert!(!metrics.dominates(&front_winner));
+        assert_eq!(
+            "GP".parse::<OptimizerStrategyKind>().unwrap(),
+            OptimizerStrategyKind::GaussianProcess
+        );
+        assert!("grid".parse::<OptimizerStrategyKind>().is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::infra::param_optimizer::{OptimizerConfig, OptimizerTrial};
 use anyhow::Result;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
@@ -208,6 +209,24 @@ impl RandomStrategyConfig {
         self.sample_size.min(self.calculate_search_space_size())
     }
 }
+/// 参数优化器断点：保存全部已评估试验，恢复时放回优化器即可沿同一条搜索路径继续。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizerProgress {
+    /// 交易所合约或现货交易对标识。
+    pub inst_id: String,
+    /// 时间字段。
+    pub time: String,
+    /// 参数空间与优化器配置的哈希值，任一变化都重新开始搜索。
+    pub config_hash: String,
+    /// 搜索策略名称。
+    pub strategy: String,
+    /// 已评估的试验。
+    pub trials: Vec<OptimizerTrial>,
+    /// 最后更新时间。
+    pub last_update_time: i64,
+    /// 状态：running, completed。
+    pub status: String,
+}
 /// 进度管理器
 pub struct StrategyProgressManager;
 impl StrategyProgressManager {
@@ -329,6 +348,48 @@ impl StrategyProgressManager {
         info!("[断点续传] 进度已清除: {}", key);
         Ok(())
     }
+    fn get_optimizer_progress_key(inst_id: &str, time: &str) -> String {
+        format!("strategy_optimizer:{}:{}", inst_id, time)
+    }
+    /// 参数空间与优化器配置的联合哈希。
+    pub fn optimizer_config_hash(
+        space_config: &RandomStrategyConfig,
+        optimizer_config: &OptimizerConfig,
+    ) -> String {
+        let config_json =
+            serde_json::to_string(&(space_config, optimizer_config)).unwrap_or_default();
+        let mut hasher = DefaultHasher::new();
+        config_json.hash(&mut hasher);
+        format!("{:x}", hasher.finish())
+    }
+    /// 保存参数优化器进度到 Redis
+    pub async fn save_optimizer_progress(progress: &OptimizerProgress) -> Result<()> {
+        let mut redis_conn = rust_quant_core::cache::get_redis_connection().await?;
+        let key = Self::get_optimizer_progress_key(&progress.inst_id, &progress.time);
+        let progress_json = serde_json::to_string(progress)?;
+        redis_conn
+            .set_ex::<_, _, ()>(&key, progress_json, 86400 * 7)
+            .await?; // 保存7天
+        info!(
+            "优化器进度已保存: {} - {} 次试验",
+            key,
+            progress.trials.len()
+        );
+        Ok(())
+    }
+    /// 从 Redis 加载参数优化器进度
+    pub async fn load_optimizer_progress(
+        inst_id: &str,
+        time: &str,
+    ) -> Result<Option<OptimizerProgress>> {
+        let mut redis_conn = rust_quant_core::cache::get_redis_connection().await?;
+        let key = Self::get_optimizer_progress_key(inst_id, time);
+        let progress_json: Option<String> = redis_conn.get(&key).await?;
+        match progress_json {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
     /// 获取进度百分比
     pub fn get_progress_percentage(progress: &StrategyTestProgress) -> f64 {
         if progress.total_combinations == 0 {
@@ -1,4 +1,9 @@
+use crate::backtest::sensitivity::{SensitivityConfig, SensitivityParam};
+use crate::backtest::walk_forward::WalkForwardConfig;
 use crate::workflow::job_param_generator::ParamMergeBuilder;
+use crate::workflow::param_optimizer::{
+    OptimizerConfig, OptimizerObjective, OptimizerStrategyKind,
+};
 use anyhow::{anyhow, Result};
 use rust_quant_domain::StrategyConfig;
 use rust_quant_indicators::trend::vegas::VegasStrategy;
@@ -6,6 +11,7 @@ use rust_quant_services::strategy::StrategyConfigService;
 use rust_quant_strategies::implementations::nwe_strategy::NweStrategyConfig;
 use rust_quant_strategies::strategy_common::BasicRiskStrategyConfig;
 use std::env;
+use std::str::FromStr;
 use tracing::warn;
 /// Vegas 策略回测配置
 #[derive(Debug, Clone)]
@@ -36,6 +42,12 @@ pub struct BackTestConfig {
     pub enable_random_test_nwe: bool,
     /// 是否启用NWE指定配置回测
     pub enable_specified_test_nwe: bool,
+    /// Vegas 参数优化器（`VEGAS_OPTIMIZER=tpe|gp|ga`）；为空时不启用。
+    pub vegas_optimizer: Option<OptimizerConfig>,
+    /// Walk-forward 滚动样本内/样本外回测（`BACKTEST_WALK_FORWARD=true`）；为空时不启用。
+    pub walk_forward: Option<WalkForwardConfig>,
+    /// 指定配置的参数敏感性分析（`BACKTEST_SENSITIVITY=true`）；为空时不启用。
+    pub sensitivity: Option<SensitivityConfig>,
 }
 impl Default for BackTestConfig {
     /// 提供默认参数，保证 回测与策略研究 在未显式配置时仍有稳定初始值。
@@ -63,10 +75,183 @@ impl Default for BackTestConfig {
                 == "true",
             enable_specified_test_nwe: env::var("ENABLE_SPECIFIED_TEST_NWE").unwrap_or_default()
                 == "true",
+            vegas_optimizer: vegas_optimizer_config_from_env(),
+            walk_forward: walk_forward_config_from_env(),
+            sensitivity: sensitivity_config_from_env(),
         }
     }
 }
 
+/// 读取 `VEGAS_OPTIMIZER*` 环境变量；策略名无效时告警并关闭优化器，避免误跑成全量网格。
+fn vegas_optimizer_config_from_env() -> Option<OptimizerConfig> {
+    let raw = env::var("VEGAS_OPTIMIZER").ok()?;
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let strategy = match OptimizerStrategyKind::from_str(raw) {
+        Ok(strategy) => strategy,
+        Err(error) => {
+            warn!("{}", error);
+            return None;
+        }
+    };
+    let defaults = OptimizerConfig::new(strategy);
+    let base = OptimizerObjective::default();
+    let objective = OptimizerObjective::default()
+        .with_weights(
+            positive_f64_env("VEGAS_OPTIMIZER_RETURN_WEIGHT", base.return_weight),
+            non_negative_f64_env("VEGAS_OPTIMIZER_DRAWDOWN_WEIGHT", base.drawdown_weight),
+            non_negative_f64_env(
+                "VEGAS_OPTIMIZER_TRADE_COUNT_WEIGHT",
+                base.trade_count_weight,
+            ),
+        )
+        .with_min_trades(usize_env("VEGAS_OPTIMIZER_MIN_TRADES", base.min_trades));
+    Some(
+        defaults
+            .clone()
+            .with_max_trials(positive_usize_env(
+                "VEGAS_OPTIMIZER_MAX_TRIALS",
+                defaults.max_trials,
+            ))
+            .with_batch_size(positive_usize_env(
+                "VEGAS_OPTIMIZER_BATCH_SIZE",
+                defaults.batch_size,
+            ))
+            .with_initial_random_trials(usize_env(
+                "VEGAS_OPTIMIZER_INITIAL_RANDOM_TRIALS",
+                defaults.initial_random_trials,
+            ))
+            .with_early_stopping(
+                usize_env("VEGAS_OPTIMIZER_PATIENCE", defaults.patience),
+                non_negative_f64_env("VEGAS_OPTIMIZER_MIN_IMPROVEMENT", defaults.min_improvement),
+            )
+            .with_seed(u64_env("VEGAS_OPTIMIZER_SEED", 20_260_721))
+            .with_objective(objective),
+    )
+}
+
+/// 读取 `BACKTEST_WALK_FORWARD*` 环境变量；未开启时返回 None。
+fn walk_forward_config_from_env() -> Option<WalkForwardConfig> {
+    if env::var("BACKTEST_WALK_FORWARD").unwrap_or_default() != "true" {
+        return None;
+    }
+    let defaults = WalkForwardConfig::default();
+    let strategy = match env::var("BACKTEST_WALK_FORWARD_OPTIMIZER") {
+        Ok(raw) if !raw.trim().is_empty() => match OptimizerStrategyKind::from_str(&raw) {
+            Ok(strategy) => strategy,
+            Err(error) => {
+                warn!(
+                    "{}，walk-forward 沿用 {}",
+                    error,
+                    defaults.optimizer.strategy.as_str()
+                );
+                defaults.optimizer.strategy
+            }
+        },
+        _ => defaults.optimizer.strategy,
+    };
+    let optimizer = OptimizerConfig {
+        strategy,
+        ..defaults.optimizer.clone()
+    }
+    .with_max_trials(positive_usize_env(
+        "BACKTEST_WALK_FORWARD_MAX_TRIALS",
+        defaults.optimizer.max_trials,
+    ))
+    .with_seed(u64_env("BACKTEST_WALK_FORWARD_SEED", 20_260_721));
+    let mut config = defaults
+        .clone()
+        .with_windows(
+            positive_usize_env("BACKTEST_WALK_FORWARD_TRAIN_BARS", defaults.train_bars),
+            positive_usize_env("BACKTEST_WALK_FORWARD_TEST_BARS", defaults.test_bars),
+        )
+        .with_anchored(env::var("BACKTEST_WALK_FORWARD_ANCHORED").unwrap_or_default() == "true")
+        .with_max_windows(usize_env("BACKTEST_WALK_FORWARD_MAX_WINDOWS", 0))
+        .with_optimizer(optimizer);
+    if let Some(warmup_bars) = env::var("BACKTEST_WALK_FORWARD_WARMUP_BARS")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|value| *value > 0)
+    {
+        config = config.with_warmup_bars(warmup_bars);
+    }
+    if let Some(report_dir) = env::var("BACKTEST_WALK_FORWARD_REPORT_DIR")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        config = config.with_report_dir(report_dir);
+    }
+    Some(config)
+}
+
+/// 读取 `BACKTEST_SENSITIVITY*` 环境变量；未开启时返回 None，无法解析的扰动比例或字段名告警后忽略。
+fn sensitivity_config_from_env() -> Option<SensitivityConfig> {
+    if env::var("BACKTEST_SENSITIVITY").unwrap_or_default() != "true" {
+        return None;
+    }
+    let defaults = SensitivityConfig::default();
+    let mut config = defaults
+        .clone()
+        .with_pair_top_k(usize_env(
+            "BACKTEST_SENSITIVITY_PAIR_TOP_K",
+            defaults.pair_top_k,
+        ))
+        .with_knife_edge_thresholds(
+            positive_f64_env("BACKTEST_SENSITIVITY_NEIGHBOURHOOD", defaults.neighbourhood),
+            non_negative_f64_env(
+                "BACKTEST_SENSITIVITY_MIN_RETURN_RETENTION",
+                defaults.min_return_retention,
+            ),
+            positive_f64_env(
+                "BACKTEST_SENSITIVITY_MAX_DRAWDOWN_RATIO",
+                defaults.max_drawdown_ratio,
+            ),
+        );
+    if let Ok(raw) = env::var("BACKTEST_SENSITIVITY_OFFSETS") {
+        let offsets: Vec<f64> = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .filter_map(|value| match value.parse::<f64>() {
+                Ok(offset) => Some(offset),
+                Err(_) => {
+                    warn!("忽略无效的敏感性扰动比例: {}", value);
+                    None
+                }
+            })
+            .collect();
+        if !offsets.is_empty() {
+            config = config.with_offsets(offsets);
+        }
+    }
+    if let Ok(raw) = env::var("BACKTEST_SENSITIVITY_PARAMS") {
+        let params: Vec<SensitivityParam> = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .filter_map(|value| match SensitivityParam::from_str(value) {
+                Ok(param) => Some(param),
+                Err(error) => {
+                    warn!("{}", error);
+                    None
+                }
+            })
+            .collect();
+        config = config.with_params(params);
+    }
+    if let Some(report_dir) = env::var("BACKTEST_SENSITIVITY_REPORT_DIR")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        config = config.with_report_dir(report_dir);
+    }
+    Some(config)
+}
+
 fn default_backtest_concurrency() -> usize {
     std::thread::available_parallelism()
         .map(|parallelism| (parallelism.get() / 2).clamp(1, 6))
@@ -81,6 +266,13 @@ fn positive_usize_env(key: &str, default: usize) -> usize {
         .unwrap_or(default)
 }
 
+fn usize_env(key: &str, default: usize) -> usize {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(default)
+}
+
 fn u64_env(key: &str, default: u64) -> u64 {
     env::var(key)
         .ok()
@@ -95,6 +287,14 @@ fn positive_f64_env(key: &str, default: f64) -> f64 {
         .filter(|value| value.is_finite() && *value > 0.0)
         .unwrap_or(default)
 }
+
+fn non_negative_f64_env(key: &str, default: f64) -> f64 {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|value| value.is_finite() && *value >= 0.0)
+        .unwrap_or(default)
+}
 pub async fn get_strate_config(
     config_service: &StrategyConfigService,
     inst_id: &str,
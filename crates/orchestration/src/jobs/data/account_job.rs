@@ -24,7 +24,7 @@ use tracing::info;
 pub async fn get_account_balance() -> Result<()> {
     info!("🏦 开始获取账户余额...");
     let service = AccountService::new();
-    let balances = service.fetch_all_balances().await?;
+    let balances = service.fetch_balance_snapshot_from_exchange(None).await?;
     info!("✅ 账户余额: {:#?}", balances);
     // ⏳ P1: 集成AccountRepository持久化
     // 集成示例：
@@ -39,7 +39,9 @@ pub async fn get_account_balance() -> Result<()> {
 pub async fn get_account_balance_by_currency(currency: Option<&str>) -> Result<()> {
     info!("🏦 获取指定币种余额: {:?}", currency);
     let service = AccountService::new();
-    let balances = service.fetch_balance_from_exchange(currency).await?;
+    let balances = service
+        .fetch_balance_snapshot_from_exchange(currency)
+        .await?;
     info!("✅ 余额查询完成: {:#?}", balances);
     Ok(())
 }
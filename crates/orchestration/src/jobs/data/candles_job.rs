@@ -5,15 +5,20 @@
 //! - services层：封装业务逻辑和外部API调用
 //! - 通过service层访问所有业务功能
 use anyhow::Result;
-use rust_quant_domain::{Candle, Price, Timeframe, Volume};
+use rust_quant_domain::{Candle, Timeframe};
 use rust_quant_infrastructure::repositories::PostgresCandleRepository;
-use rust_quant_market::models::CandlesEntity;
 use rust_quant_services::market::{
-    should_use_quant_core_candle_source, CandleService as CandleMarketService, DataSyncService,
+    scan_candle_quality, should_use_quant_core_candle_source, CandleQualityConfig,
+    CandleQualityFinding, CandleQualityIssue, CandleQualityStore,
+    CandleService as CandleMarketService, DataSyncService,
 };
 use sqlx::postgres::PgPoolOptions;
 use std::str::FromStr;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+/// 未设置 `CANDLE_QUALITY_SCAN_BARS` 时每个序列巡检的最近K线根数。
+const DEFAULT_CANDLE_QUALITY_SCAN_BARS: usize = 5000;
+/// 定向补拉时单次请求的K线根数，兼顾 OKX 与 Binance 的单页上限。
+const CANDLE_REPAIR_PAGE_BARS: i64 = 300;
 /// K线数据同步任务
 ///
 /// # Architecture
@@ -93,11 +98,6 @@ impl CandlesJob {
         let latest_candle = service.get_latest_candle(inst_id, timeframe).await?;
         let after_ts = latest_candle.map(|c| c.timestamp).unwrap_or(0);
         // 3. 通过service层获取增量K线
-        let after_str = if after_ts > 0 {
-            Some(after_ts.to_string())
-        } else {
-            None
-        };
         let domain_candles = if market_data_exchange() == "binance" {
             let after =
                 after_ts
@@ -107,22 +107,17 @@ impl CandlesJob {
                 .fetch_candles_from_crypto_exc_all("binance", inst_id, period, after, None, 100)
                 .await?
         } else {
-            let exchange_candles = service
-                .fetch_candles_from_exchange(
+            let after = if after_ts > 0 { Some(after_ts) } else { None };
+            service
+                .fetch_domain_candles_from_exchange(
                     inst_id,
                     period,
-                    after_str.as_deref(),
+                    timeframe,
+                    after,
                     None,
-                    Some("100"),
+                    Some(100),
                 )
-                .await?;
-            if exchange_candles.is_empty() {
-                return Ok(0);
-            }
-            exchange_candles
-                .iter()
-                .map(|entity| Self::convert_entity_to_domain(entity, inst_id, timeframe))
-                .collect::<Result<Vec<Candle>>>()?
+                .await?
         };
         if domain_candles.is_empty() {
             return Ok(0);
@@ -131,61 +126,6 @@ impl CandlesJob {
         let saved_count = service.save_candles(domain_candles).await?;
         Ok(saved_count)
     }
-    /// 转换K线实体到Domain Candle
-    /// # Architecture
-    /// 数据转换逻辑，将持久化实体转换为领域实体
-    fn convert_entity_to_domain(
-        entity: &CandlesEntity,
-        symbol: &str,
-        timeframe: Timeframe,
-    ) -> Result<Candle> {
-        let timestamp = entity.ts;
-        let open = entity
-            .o
-            .parse::<f64>()
-            .map_err(|e| anyhow::anyhow!("解析开盘价失败: o={}, err={}", entity.o, e))?;
-        let open = Price::new(open)
-            .map_err(|e| anyhow::anyhow!("创建Price失败: value={}, err={:?}", entity.o, e))?;
-        let high = entity
-            .h
-            .parse::<f64>()
-            .map_err(|e| anyhow::anyhow!("解析最高价失败: h={}, err={}", entity.h, e))?;
-        let high = Price::new(high)
-            .map_err(|e| anyhow::anyhow!("创建Price失败: value={}, err={:?}", entity.h, e))?;
-        let low = entity
-            .l
-            .parse::<f64>()
-            .map_err(|e| anyhow::anyhow!("解析最低价失败: l={}, err={}", entity.l, e))?;
-        let low = Price::new(low)
-            .map_err(|e| anyhow::anyhow!("创建Price失败: value={}, err={:?}", entity.l, e))?;
-        let close = entity
-            .c
-            .parse::<f64>()
-            .map_err(|e| anyhow::anyhow!("解析收盘价失败: c={}, err={}", entity.c, e))?;
-        let close = Price::new(close)
-            .map_err(|e| anyhow::anyhow!("创建Price失败: value={}, err={:?}", entity.c, e))?;
-        let volume = entity.vol_ccy.parse::<f64>().map_err(|e| {
-            anyhow::anyhow!("解析成交量失败: vol_ccy={}, err={}", entity.vol_ccy, e)
-        })?;
-        let volume = Volume::new(volume).map_err(|e| {
-            anyhow::anyhow!("创建Volume失败: value={}, err={:?}", entity.vol_ccy, e)
-        })?;
-        let mut candle = Candle::new(
-            symbol.to_string(),
-            timeframe,
-            timestamp,
-            open,
-            high,
-            low,
-            close,
-            volume,
-        );
-        // 设置确认状态
-        if entity.confirm == "1" {
-            candle.confirm();
-        }
-        Ok(candle)
-    }
     /// 全量执行数据同步（三步：建表、补历史、补增量）
     /// # Architecture
     /// orchestration层：只做编排，委托给DataSyncService完成业务逻辑
@@ -203,6 +143,185 @@ impl CandlesJob {
         info!("✅ 完整数据同步完成");
         Ok(())
     }
+    /// 巡检K线数据质量并修复缺口
+    /// # Architecture
+    /// orchestration层：扫描交给service层的 `scan_candle_quality`，可修复的问题先按区间定向补拉；
+    /// 补拉后仍存在缺口或遗留未确认K线的序列，再交给 `sync_all_data` 全量同步兜底。
+    /// # Arguments
+    /// * `inst_ids` - 交易对列表
+    /// * `periods` - 时间周期列表
+    /// # Returns
+    /// 修复后仍未解决的问题
+    pub async fn scan_and_repair_candle_quality(
+        &self,
+        inst_ids: &[String],
+        periods: &[String],
+    ) -> Result<Vec<CandleQualityFinding>> {
+        info!(
+            "🩺 开始K线质量巡检: {} 个交易对, {} 个周期",
+            inst_ids.len(),
+            periods.len()
+        );
+        let service = Self::create_candle_service()?;
+        let store = CandleQualityStore::from_env()?;
+        let config = CandleQualityConfig::default();
+        let scan_bars = candle_quality_scan_bars();
+        let mut remaining = Vec::new();
+        let mut fallback = Vec::new();
+        for inst_id in inst_ids {
+            for period in periods {
+                let timeframe = Timeframe::from_str(period)
+                    .map_err(|_| anyhow::anyhow!("无效的时间周期: {}", period))?;
+                let findings = self
+                    .scan_candle_quality_once(
+                        &service, &store, inst_id, timeframe, scan_bars, &config,
+                    )
+                    .await?;
+                let repairable: Vec<&CandleQualityFinding> = findings
+                    .iter()
+                    .filter(|finding| finding.issue.is_repairable())
+                    .collect();
+                if repairable.is_empty() {
+                    remaining.extend(findings);
+                    continue;
+                }
+                for finding in repairable {
+                    store.mark_repair_attempted(finding).await?;
+                    match self
+                        .refetch_candle_range(
+                            &service,
+                            inst_id,
+                            timeframe,
+                            finding.start_ts,
+                            finding.end_ts,
+                        )
+                        .await
+                    {
+                        Ok(count) => info!(
+                            "🔧 K线区间补拉完成: {} {} {} [{}, {}] - {} 条",
+                            inst_id,
+                            period,
+                            finding.issue.as_str(),
+                            finding.start_ts,
+                            finding.end_ts,
+                            count
+                        ),
+                        Err(e) => warn!(
+                            "⚠️ K线区间补拉失败: {} {} {} [{}, {}] - {}",
+                            inst_id,
+                            period,
+                            finding.issue.as_str(),
+                            finding.start_ts,
+                            finding.end_ts,
+                            e
+                        ),
+                    }
+                }
+                let findings = self
+                    .scan_candle_quality_once(
+                        &service, &store, inst_id, timeframe, scan_bars, &config,
+                    )
+                    .await?;
+                let needs_full_sync = findings.iter().any(|finding| {
+                    matches!(
+                        finding.issue,
+                        CandleQualityIssue::Gap | CandleQualityIssue::StaleUnconfirmed
+                    )
+                });
+                if needs_full_sync {
+                    fallback.push((inst_id.clone(), timeframe));
+                } else {
+                    remaining.extend(findings);
+                }
+            }
+        }
+        for (inst_id, timeframe) in fallback {
+            let period = timeframe.as_str().to_string();
+            if let Err(e) = self
+                .sync_all_data(
+                    std::slice::from_ref(&inst_id),
+                    std::slice::from_ref(&period),
+                )
+                .await
+            {
+                error!("❌ K线缺口全量同步失败: {} {} - {}", inst_id, period, e);
+            }
+            let findings = self
+                .scan_candle_quality_once(&service, &store, &inst_id, timeframe, scan_bars, &config)
+                .await?;
+            remaining.extend(findings);
+        }
+        info!("✅ K线质量巡检完成: 未解决问题 {} 个", remaining.len());
+        Ok(remaining)
+    }
+    /// 扫描单个序列最近 `scan_bars` 根K线并写入问题表
+    async fn scan_candle_quality_once(
+        &self,
+        service: &CandleMarketService,
+        store: &CandleQualityStore,
+        inst_id: &str,
+        timeframe: Timeframe,
+        scan_bars: usize,
+        config: &CandleQualityConfig,
+    ) -> Result<Vec<CandleQualityFinding>> {
+        let period_ms = timeframe.to_minutes() * 60_000;
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let scan_start = now_ms - period_ms * scan_bars as i64;
+        let candles = service
+            .get_candles(inst_id, timeframe, scan_start, now_ms, Some(scan_bars))
+            .await?;
+        let findings = scan_candle_quality(inst_id, timeframe, &candles, config, now_ms);
+        let summary = store
+            .record_scan(inst_id, timeframe.as_str(), scan_start, now_ms, &findings)
+            .await?;
+        info!(
+            "🩺 K线质量扫描: {} {} - {} 根, 问题 {} 个, 已解决 {} 个",
+            inst_id,
+            timeframe.as_str(),
+            candles.len(),
+            summary.open,
+            summary.resolved
+        );
+        Ok(findings)
+    }
+    /// 从交易所重新拉取 `[start_ts, end_ts]`（前后各扩一根）的已确认K线并覆盖写入
+    async fn refetch_candle_range(
+        &self,
+        service: &CandleMarketService,
+        inst_id: &str,
+        timeframe: Timeframe,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<usize> {
+        let exchange = market_data_exchange();
+        let period_ms = timeframe.to_minutes() * 60_000;
+        let end_ts = end_ts + period_ms;
+        let mut page_start = (start_ts - period_ms).max(0);
+        let mut saved = 0;
+        while page_start <= end_ts {
+            let page_end = (page_start + period_ms * (CANDLE_REPAIR_PAGE_BARS - 1)).min(end_ts);
+            let candles: Vec<Candle> = service
+                .fetch_candles_from_crypto_exc_all(
+                    &exchange,
+                    inst_id,
+                    timeframe.as_str(),
+                    Some(page_start as u64),
+                    Some(page_end as u64),
+                    CANDLE_REPAIR_PAGE_BARS as u32,
+                )
+                .await?
+                .into_iter()
+                .filter(|candle| {
+                    candle.confirmed && (page_start..=page_end).contains(&candle.timestamp)
+                })
+                .collect();
+            if !candles.is_empty() {
+                saved += service.save_candles(candles).await?;
+            }
+            page_start = page_end + period_ms;
+        }
+        Ok(saved)
+    }
 }
 impl Default for CandlesJob {
     fn default() -> Self {
@@ -219,6 +338,14 @@ fn market_data_exchange() -> String {
         .trim()
         .to_ascii_lowercase()
 }
+/// 读取 `CANDLE_QUALITY_SCAN_BARS`，非法或未设置时使用默认值。
+fn candle_quality_scan_bars() -> usize {
+    std::env::var("CANDLE_QUALITY_SCAN_BARS")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|bars| *bars > 0)
+        .unwrap_or(DEFAULT_CANDLE_QUALITY_SCAN_BARS)
+}
 /// 并发同步多个交易对的K线
 /// # Arguments
 /// * `inst_ids` - 交易对列表
@@ -49,7 +49,7 @@ impl MaintenanceScheduler {
         }
     }
 
-    async fn run_once(&mut self, now: DateTime<Utc>) {
+    pub(crate) async fn run_once(&mut self, now: DateTime<Utc>) {
         for job in &mut self.jobs {
             let job_name = job.name();
             if let Err(err) = job.run_tick(now).await {
@@ -0,0 +1,474 @@
+//! 权益回撤熔断任务
+//!
+//! 把账户权益（`rust_quant_risk::account`）和策略实盘权益喂入 `DrawdownCircuitBreaker`，
+//! 策略权益按交易所持仓与平仓历史计算；
+//! 熔断时暂停 `StrategyManager` 中受影响的策略、撤销本地任务队列中的待执行任务并发送通知。
+//! 熔断状态持久化在 Redis，恢复只能通过控制面显式调用 `resume`。
+use crate::jobs::maintenance::scheduler::MaintenanceJob;
+use crate::strategy_runner::StrategyManager;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use once_cell::sync::OnceCell;
+use rust_quant_risk::account::{
+    get_account_total_equity, DrawdownBreach, DrawdownCircuitBreaker, DrawdownScope,
+};
+use rust_quant_services::notification::TelegramNotifier;
+use rust_quant_services::risk::ExchangeStrategyEquityProvider;
+use rust_quant_services::rust_quan_web::{
+    ExecutionTaskCancelPendingRequest, ExecutionTaskSourceKind, PostgresExecutionTaskSource,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{error, info, warn};
+const DEFAULT_DRAWDOWN_ACCOUNT_ID: &str = "okx";
+/// 账户总权益来源
+#[async_trait]
+pub trait AccountEquitySource: Send + Sync {
+    /// 读取账户总权益（USD 计价）
+    async fn total_equity(&self) -> Result<f64>;
+}
+/// 读取 OKX 交易账户总权益，需要签名只读确认
+pub struct OkxAccountEquitySource;
+#[async_trait]
+impl AccountEquitySource for OkxAccountEquitySource {
+    async fn total_equity(&self) -> Result<f64> {
+        get_account_total_equity().await
+    }
+}
+/// 策略实盘权益来源
+#[async_trait]
+pub trait StrategyEquitySource: Send + Sync {
+    /// 读取策略在交易品种上的实盘权益（USD 计价）
+    async fn strategy_equity(&self, inst_id: &str) -> Result<f64>;
+}
+/// 按交易所持仓与平仓历史计算策略权益，需要签名只读确认
+#[async_trait]
+impl StrategyEquitySource for ExchangeStrategyEquityProvider {
+    async fn strategy_equity(&self, inst_id: &str) -> Result<f64> {
+        ExchangeStrategyEquityProvider::strategy_equity(self, inst_id).await
+    }
+}
+/// 权益回撤熔断任务
+pub struct DrawdownBreakerJob {
+    /// 熔断器（状态持久化）
+    breaker: Arc<DrawdownCircuitBreaker>,
+    /// 账户级范围 ID
+    account_id: String,
+    /// 账户总权益来源
+    equity_source: Arc<dyn AccountEquitySource>,
+    /// 策略实盘权益来源；未配置时只做账户级检查
+    strategy_equity_source: Option<Arc<dyn StrategyEquitySource>>,
+    /// 本进程因熔断暂停的策略 key（按范围分组），控制面恢复后据此恢复运行
+    paused_by_scope: Mutex<HashMap<DrawdownScope, Vec<String>>>,
+}
+impl DrawdownBreakerJob {
+    pub fn new(breaker: Arc<DrawdownCircuitBreaker>, account_id: impl Into<String>) -> Self {
+        Self {
+            breaker,
+            account_id: account_id.into(),
+            equity_source: Arc::new(OkxAccountEquitySource),
+            strategy_equity_source: None,
+            paused_by_scope: Mutex::new(HashMap::new()),
+        }
+    }
+    /// 从环境变量构建
+    ///
+    /// - `DRAWDOWN_BREAKER_ACCOUNT_ID`: 账户级范围 ID，默认 `okx`
+    /// - `DRAWDOWN_BREAKER_STRATEGY_CAPITAL_USDT`: 单个策略的分配资金，配置后启用策略级检查
+    /// - `DRAWDOWN_BREAKER_STRATEGY_SINCE_MS`: 策略已实现盈亏的统计起点，默认 0
+    /// - 阈值见 `DrawdownCircuitBreaker::from_env`
+    pub fn from_env() -> Result<Self> {
+        let account_id = std::env::var("DRAWDOWN_BREAKER_ACCOUNT_ID")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| DEFAULT_DRAWDOWN_ACCOUNT_ID.to_string());
+        let job = Self::new(Arc::new(DrawdownCircuitBreaker::from_env()?), account_id);
+        let Some(capital) = env_parse::<f64>("DRAWDOWN_BREAKER_STRATEGY_CAPITAL_USDT")? else {
+            info!("未配置 DRAWDOWN_BREAKER_STRATEGY_CAPITAL_USDT，策略级回撤检查关闭");
+            return Ok(job);
+        };
+        if !capital.is_finite() || capital <= 0.0 {
+            return Err(anyhow!(
+                "DRAWDOWN_BREAKER_STRATEGY_CAPITAL_USDT 必须为正数: {}",
+                capital
+            ));
+        }
+        let since_ms = env_parse::<u64>("DRAWDOWN_BREAKER_STRATEGY_SINCE_MS")?.unwrap_or(0);
+        Ok(
+            job.with_strategy_equity_source(Arc::new(ExchangeStrategyEquityProvider::from_env(
+                capital, since_ms,
+            )?)),
+        )
+    }
+    pub fn with_equity_source(mut self, equity_source: Arc<dyn AccountEquitySource>) -> Self {
+        self.equity_source = equity_source;
+        self
+    }
+    pub fn with_strategy_equity_source(
+        mut self,
+        strategy_equity_source: Arc<dyn StrategyEquitySource>,
+    ) -> Self {
+        self.strategy_equity_source = Some(strategy_equity_source);
+        self
+    }
+    /// 获取全局实例；`DRAWDOWN_BREAKER_ENABLED` 未开启或配置无效时返回 None
+    pub fn global() -> Option<&'static DrawdownBreakerJob> {
+        static INSTANCE: OnceCell<Option<DrawdownBreakerJob>> = OnceCell::new();
+        INSTANCE
+            .get_or_init(|| {
+                if !env_enabled("DRAWDOWN_BREAKER_ENABLED") {
+                    return None;
+                }
+                match Self::from_env() {
+                    Ok(job) => Some(job),
+                    Err(e) => {
+                        error!("❌ 权益回撤熔断配置无效，熔断未启用: {}", e);
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+    pub fn breaker(&self) -> &DrawdownCircuitBreaker {
+        &self.breaker
+    }
+    pub fn account_scope(&self) -> DrawdownScope {
+        DrawdownScope::Account(self.account_id.clone())
+    }
+    /// 读取账户总权益并检查回撤
+    pub async fn run_account_check(&self) -> Result<Option<DrawdownBreach>> {
+        let equity = self.equity_source.total_equity().await?;
+        let breach = self
+            .breaker
+            .observe(self.account_scope(), equity, Utc::now().timestamp_millis())
+            .await?;
+        if let Some(breach) = &breach {
+            self.handle_breach(breach, None).await;
+        }
+        self.sync_resumed_scopes().await?;
+        Ok(breach)
+    }
+    /// 从策略权益来源读取该策略交易品种的实盘权益并检查回撤；未配置来源时跳过
+    pub async fn run_strategy_check(
+        &self,
+        config_id: i64,
+        strategy_key: &str,
+        inst_id: &str,
+    ) -> Result<Option<DrawdownBreach>> {
+        let Some(source) = &self.strategy_equity_source else {
+            return Ok(None);
+        };
+        let equity = source.strategy_equity(inst_id).await?;
+        self.record_strategy_equity(config_id, strategy_key, equity)
+            .await
+    }
+    /// 记录策略实盘权益并检查回撤
+    ///
+    /// `strategy_key` 与 Web 侧策略信号的 `strategy_key` 一致，用于撤销该策略的待执行任务。
+    pub async fn record_strategy_equity(
+        &self,
+        config_id: i64,
+        strategy_key: &str,
+        equity: f64,
+    ) -> Result<Option<DrawdownBreach>> {
+        let breach = self
+            .breaker
+            .observe(
+                DrawdownScope::Strategy(config_id.to_string()),
+                equity,
+                Utc::now().timestamp_millis(),
+            )
+            .await?;
+        if let Some(breach) = &breach {
+            self.handle_breach(breach, Some(strategy_key)).await;
+        }
+        Ok(breach)
+    }
+    /// 策略是否因回撤熔断被禁止执行（账户级或策略级）
+    ///
+    /// 熔断状态读取失败时按本进程暂停状态判断，不因 Redis 抖动放开已暂停的策略。
+    pub async fn is_strategy_blocked(&self, config_id: i64) -> bool {
+        let manager = StrategyManager::global();
+        let scopes = [
+            self.account_scope(),
+            DrawdownScope::Strategy(config_id.to_string()),
+        ];
+        for scope in &scopes {
+            match self.breaker.is_halted(scope).await {
+                Ok(true) => return true,
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("⚠️ 读取回撤熔断状态失败: scope={}, err={}", scope.key(), e);
+                    return manager.is_config_paused(config_id);
+                }
+            }
+        }
+        if manager.is_config_paused(config_id) {
+            if let Err(e) = self.sync_resumed_scopes().await {
+                warn!("⚠️ 同步回撤熔断恢复状态失败: {}", e);
+            }
+            return manager.is_config_paused(config_id);
+        }
+        false
+    }
+    /// 处理回撤升级：警告只通知；熔断时暂停策略、撤销待执行任务并通知
+    pub async fn handle_breach(&self, breach: &DrawdownBreach, strategy_key: Option<&str>) {
+        if breach.is_halt() {
+            let manager = StrategyManager::global();
+            let paused = match &breach.scope {
+                DrawdownScope::Account(_) => manager.pause_all_strategies(),
+                DrawdownScope::Strategy(id) => match id.parse::<i64>() {
+                    Ok(config_id) => manager.pause_strategies_by_config_id(config_id),
+                    Err(_) => Vec::new(),
+                },
+            };
+            warn!(
+                "🛑 权益回撤熔断: scope={}, drawdown={:.2}%, paused={:?}",
+                breach.scope.key(),
+                breach.drawdown_percent,
+                paused
+            );
+            if let Ok(mut guard) = self.paused_by_scope.lock() {
+                guard
+                    .entry(breach.scope.clone())
+                    .or_default()
+                    .extend(paused);
+            }
+            if let Err(e) = self.cancel_pending_tasks(breach, strategy_key).await {
+                error!(
+                    "❌ 撤销待执行任务失败: scope={}, err={}",
+                    breach.scope.key(),
+                    e
+                );
+            }
+        }
+        match TelegramNotifier::from_env() {
+            Ok(notifier) => {
+                if let Err(e) = notifier.notify_drawdown_breach(breach).await {
+                    warn!("⚠️ 发送回撤熔断通知失败: {}", e);
+                }
+            }
+            Err(e) => warn!("⚠️ 未配置回撤熔断通知: {}", e),
+        }
+    }
+    /// 控制面显式恢复后（跨进程），恢复本进程因熔断暂停的策略
+    pub async fn sync_resumed_scopes(&self) -> Result<Vec<String>> {
+        let scopes: Vec<DrawdownScope> = match self.paused_by_scope.lock() {
+            Ok(guard) => guard.keys().cloned().collect(),
+            Err(_) => return Err(anyhow!("回撤熔断暂停记录锁异常")),
+        };
+        let mut resumed = Vec::new();
+        for scope in scopes {
+            if self.breaker.is_halted(&scope).await? {
+                continue;
+            }
+            let keys = match self.paused_by_scope.lock() {
+                Ok(mut guard) => guard.remove(&scope).unwrap_or_default(),
+                Err(_) => return Err(anyhow!("回撤熔断暂停记录锁异常")),
+            };
+            resumed.extend(StrategyManager::global().resume_strategies(&keys));
+        }
+        if !resumed.is_empty() {
+            info!("✅ 回撤熔断已恢复，策略恢复运行: {:?}", resumed);
+        }
+        Ok(resumed)
+    }
+    /// 撤销本地任务队列中尚未被租约的任务
+    ///
+    /// 只有 `EXECUTION_TASK_SOURCE=postgres` 时任务在本进程可见；Web 任务源没有撤销接口，
+    /// 此时只依赖策略暂停阻止新信号。
+    async fn cancel_pending_tasks(
+        &self,
+        breach: &DrawdownBreach,
+        strategy_key: Option<&str>,
+    ) -> Result<()> {
+        if ExecutionTaskSourceKind::from_env()? != ExecutionTaskSourceKind::Postgres {
+            info!(
+                "执行任务来源不是本地队列，跳过撤销待执行任务: scope={}",
+                breach.scope.key()
+            );
+            return Ok(());
+        }
+        let request = build_cancel_pending_request(breach, strategy_key);
+        let response = PostgresExecutionTaskSource::from_env()?
+            .cancel_pending_tasks(request)
+            .await?;
+        info!(
+            "✅ 已撤销待执行任务: scope={}, task_ids={:?}",
+            breach.scope.key(),
+            response.cancelled_task_ids
+        );
+        Ok(())
+    }
+}
+/// 账户级回撤检查的定时任务，由风控任务调度器按固定间隔驱动
+pub struct DrawdownAccountCheckJob {
+    guard: &'static DrawdownBreakerJob,
+}
+impl DrawdownAccountCheckJob {
+    pub fn new(guard: &'static DrawdownBreakerJob) -> Self {
+        Self { guard }
+    }
+}
+#[async_trait]
+impl MaintenanceJob for DrawdownAccountCheckJob {
+    fn name(&self) -> &'static str {
+        "drawdown_account_check"
+    }
+    async fn run_tick(&mut self, _now: DateTime<Utc>) -> Result<()> {
+        self.guard.run_account_check().await.map(|_| ())
+    }
+}
+/// 构建撤销请求：账户级撤销全部，策略级按 strategy_key 过滤
+fn build_cancel_pending_request(
+    breach: &DrawdownBreach,
+    strategy_key: Option<&str>,
+) -> ExecutionTaskCancelPendingRequest {
+    let strategy_key = match &breach.scope {
+        DrawdownScope::Account(_) => None,
+        DrawdownScope::Strategy(_) => strategy_key.map(str::to_string),
+    };
+    ExecutionTaskCancelPendingRequest {
+        source: "rust_quant".to_string(),
+        reason: format!(
+            "drawdown circuit breaker: scope={}, drawdown={:.2}%",
+            breach.scope.key(),
+            breach.drawdown_percent
+        ),
+        strategy_key,
+        symbol: None,
+    }
+}
+/// 判断环境变量开关是否开启
+fn env_enabled(key: &str) -> bool {
+    std::env::var(key)
+        .map(|value| {
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        })
+        .unwrap_or(false)
+}
+/// 解析可选的数值环境变量，未设置或为空时返回 None
+fn env_parse<T>(key: &str) -> Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match std::env::var(key) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse::<T>()
+            .map(Some)
+            .map_err(|e| anyhow!("{} 配置无效: {}", key, e)),
+        _ => Ok(None),
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_quant_risk::account::InMemoryDrawdownStateStore;
+    use rust_quant_risk::policies::{DrawdownAction, DrawdownPolicy};
+    fn breach(scope: DrawdownScope) -> DrawdownBreach {
+        DrawdownBreach {
+            scope,
+            previous_action: DrawdownAction::ReducePositions,
+            action: DrawdownAction::StopAllTrading,
+            drawdown_percent: 21.5,
+            peak_equity: 1000.0,
+            equity: 785.0,
+            ts: 1,
+        }
+    }
+    #[test]
+    fn cancel_request_filters_by_strategy_key_only_for_strategy_scope() {
+        let account = build_cancel_pending_request(
+            &breach(DrawdownScope::Account("okx".into())),
+            Some("vegas:BTC-USDT-SWAP:1H:7"),
+        );
+        assert_eq!(account.strategy_key, None);
+        assert!(account.reason.contains("account:okx"));
+        let strategy = build_cancel_pending_request(
+            &breach(DrawdownScope::Strategy("7".into())),
+            Some("vegas:BTC-USDT-SWAP:1H:7"),
+        );
+        assert_eq!(
+            strategy.strategy_key.as_deref(),
+            Some("vegas:BTC-USDT-SWAP:1H:7")
+        );
+    }
+    #[tokio::test]
+    async fn strategy_equity_breach_blocks_until_explicit_resume() {
+        let job = DrawdownBreakerJob::new(
+            Arc::new(DrawdownCircuitBreaker::new(
+                DrawdownPolicy::default(),
+                Arc::new(InMemoryDrawdownStateStore::default()),
+            )),
+            "okx",
+        );
+        assert!(job
+            .record_strategy_equity(9001, "vegas:BTC-USDT-SWAP:1H:9001", 100.0)
+            .await
+            .unwrap()
+            .is_none());
+        let breach = job
+            .record_strategy_equity(9001, "vegas:BTC-USDT-SWAP:1H:9001", 70.0)
+            .await
+            .unwrap()
+            .expect("30% drawdown must trip the breaker");
+        assert!(breach.is_halt());
+        assert!(job.is_strategy_blocked(9001).await);
+        assert!(!job.is_strategy_blocked(9002).await);
+        // 权益回升不会自动恢复
+        job.record_strategy_equity(9001, "vegas:BTC-USDT-SWAP:1H:9001", 100.0)
+            .await
+            .unwrap();
+        assert!(job.is_strategy_blocked(9001).await);
+        job.breaker()
+            .resume(&DrawdownScope::Strategy("9001".into()), 2)
+            .await
+            .unwrap();
+        assert!(!job.is_strategy_blocked(9001).await);
+    }
+    struct ScriptedStrategyEquity(Mutex<Vec<f64>>);
+    #[async_trait]
+    impl StrategyEquitySource for ScriptedStrategyEquity {
+        async fn strategy_equity(&self, inst_id: &str) -> Result<f64> {
+            assert_eq!(inst_id, "ETH-USDT-SWAP");
+            let mut values = self.0.lock().unwrap();
+            Ok(values.remove(0))
+        }
+    }
+    #[tokio::test]
+    async fn strategy_check_reads_equity_source_and_skips_without_one() {
+        let breaker = Arc::new(DrawdownCircuitBreaker::new(
+            DrawdownPolicy::default(),
+            Arc::new(InMemoryDrawdownStateStore::default()),
+        ));
+        let unconfigured = DrawdownBreakerJob::new(breaker.clone(), "okx");
+        assert!(unconfigured
+            .run_strategy_check(9101, "vegas:ETH-USDT-SWAP:1H:9101", "ETH-USDT-SWAP")
+            .await
+            .unwrap()
+            .is_none());
+        let job = DrawdownBreakerJob::new(breaker, "okx").with_strategy_equity_source(Arc::new(
+            ScriptedStrategyEquity(Mutex::new(vec![1_000.0, 700.0])),
+        ));
+        assert!(job
+            .run_strategy_check(9101, "vegas:ETH-USDT-SWAP:1H:9101", "ETH-USDT-SWAP")
+            .await
+            .unwrap()
+            .is_none());
+        let breach = job
+            .run_strategy_check(9101, "vegas:ETH-USDT-SWAP:1H:9101", "ETH-USDT-SWAP")
+            .await
+            .unwrap()
+            .expect("30% drawdown on exchange equity must trip the breaker");
+        assert!(breach.is_halt());
+        assert_eq!(breach.equity, 700.0);
+        assert!(job.is_strategy_blocked(9101).await);
+    }
+}
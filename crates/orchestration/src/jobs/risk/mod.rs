@@ -1,2 +1,4 @@
+pub mod drawdown_breaker_job;
 pub mod risk_balance_job;
 pub mod risk_position_job;
+pub mod scheduler;
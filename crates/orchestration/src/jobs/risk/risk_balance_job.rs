@@ -1,4 +1,5 @@
 // 风险监控任务
+use super::drawdown_breaker_job::DrawdownBreakerJob;
 use anyhow::{anyhow, Context, Result};
 use okx::api::api_trait::OkxApiTrait;
 use okx::dto::account_dto::SetLeverageRequest;
@@ -8,6 +9,7 @@ use okx::dto::EnumToStrTrait;
 use okx::dto::PositionSide;
 use okx::enums::account_enums::AccountType;
 use okx::{OkxAccount, OkxAsset};
+use rust_quant_risk::account::DrawdownBreach;
 use std::str::FromStr;
 use tracing::{error, info, span, Level};
 // 常量定义
@@ -55,6 +57,12 @@ impl RiskBalanceWithLevelJob {
         //2. 控制合约杠杆
         self.run_set_leverage(inst_ids).await?;
         info!("设置最大杠杆完成!");
+        //3. 权益回撤熔断
+        if let Some(guard) = DrawdownBreakerJob::global() {
+            if let Err(e) = self.run_drawdown_guard(guard).await {
+                error!("账户权益回撤检查失败: {:?}", e);
+            }
+        }
         Ok(())
     }
     /// 使用自定义参数创建风险管理任务实例
@@ -93,6 +101,16 @@ impl RiskBalanceWithLevelJob {
         }
         Ok(())
     }
+    /// risk 3 权益回撤熔断：读取账户总权益喂入熔断器，越线时暂停策略并撤销待执行任务
+    ///
+    /// 只读取账户余额，需要签名只读确认，不需要资金划转/杠杆写操作确认。
+    pub async fn run_drawdown_guard(
+        &self,
+        guard: &DrawdownBreakerJob,
+    ) -> Result<Option<DrawdownBreach>> {
+        Self::ensure_signed_read_only_allowed()?;
+        guard.run_account_check().await
+    }
     /// risk 1 执行风险管理任务，保持交易账户资金为资金账户的一半
     pub async fn control_trade_amount(&self) -> Result<()> {
         // 获取资金账户和交易账户的余额
@@ -385,6 +403,30 @@ mod tests {
             "unexpected error: {error:#}"
         );
     }
+    #[tokio::test]
+    async fn drawdown_guard_requires_signed_read_confirmation_before_account_read() {
+        use rust_quant_risk::account::{DrawdownCircuitBreaker, InMemoryDrawdownStateStore};
+        use rust_quant_risk::policies::DrawdownPolicy;
+        let _guard = lock_env();
+        let _snapshot = EnvSnapshot::capture();
+        std::env::remove_var(TEST_CONFIRM_ENV);
+        std::env::remove_var(TEST_SIGNED_READ_CONFIRM_ENV);
+        let guard = DrawdownBreakerJob::new(
+            std::sync::Arc::new(DrawdownCircuitBreaker::new(
+                DrawdownPolicy::default(),
+                std::sync::Arc::new(InMemoryDrawdownStateStore::default()),
+            )),
+            "okx",
+        );
+        let error = RiskBalanceWithLevelJob::new()
+            .run_drawdown_guard(&guard)
+            .await
+            .expect_err("drawdown guard account reads must require explicit signed-read scope");
+        assert!(
+            error.to_string().contains(TEST_SIGNED_READ_CONFIRM_ENV),
+            "unexpected error: {error:#}"
+        );
+    }
     #[test]
     fn signed_read_confirmation_accepts_exact_token_or_live_mutation_token() {
         let _guard = lock_env();
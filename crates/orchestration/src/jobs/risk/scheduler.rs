@@ -0,0 +1,107 @@
+//! 风控任务调度器
+//!
+//! 复用 `MaintenanceScheduler` 的逻辑任务契约，在实盘进程内按固定间隔运行风控检查。
+use super::drawdown_breaker_job::{DrawdownAccountCheckJob, DrawdownBreakerJob};
+use crate::jobs::maintenance::MaintenanceScheduler;
+use tokio::time::Duration;
+use tracing::info;
+const DEFAULT_DRAWDOWN_CHECK_INTERVAL_SECS: u64 = 60;
+/// 构建风控任务调度器；没有任何启用的风控任务时返回 None
+///
+/// - 账户级权益回撤检查：`DRAWDOWN_BREAKER_ENABLED` 开启时注册，
+///   间隔由 `DRAWDOWN_BREAKER_CHECK_INTERVAL_SECS` 配置（默认 60 秒）
+pub fn build_risk_job_scheduler() -> Option<MaintenanceScheduler> {
+    let interval_secs = std::env::var("DRAWDOWN_BREAKER_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_DRAWDOWN_CHECK_INTERVAL_SECS);
+    risk_job_scheduler_with(
+        DrawdownBreakerJob::global(),
+        Duration::from_secs(interval_secs),
+    )
+}
+/// 按给定的回撤熔断实例构建调度器
+fn risk_job_scheduler_with(
+    drawdown_guard: Option<&'static DrawdownBreakerJob>,
+    tick_interval: Duration,
+) -> Option<MaintenanceScheduler> {
+    let mut scheduler = MaintenanceScheduler::new(tick_interval);
+    if let Some(guard) = drawdown_guard {
+        scheduler.register_job(DrawdownAccountCheckJob::new(guard));
+        info!(
+            "已注册账户权益回撤检查: interval_secs={}",
+            tick_interval.as_secs()
+        );
+    }
+    (scheduler.job_count() > 0).then_some(scheduler)
+}
+/// 启动风控任务调度器；没有启用的风控任务时不启动
+pub fn start_risk_job_scheduler() {
+    let Some(scheduler) = build_risk_job_scheduler() else {
+        info!("未启用风控定时任务，跳过风控任务调度器");
+        return;
+    };
+    tokio::spawn(async move {
+        scheduler.run_forever().await;
+    });
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::risk::drawdown_breaker_job::AccountEquitySource;
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use rust_quant_risk::account::{
+        DrawdownCircuitBreaker, DrawdownScope, InMemoryDrawdownStateStore,
+    };
+    use rust_quant_risk::policies::DrawdownPolicy;
+    use std::sync::{Arc, Mutex};
+    /// 按脚本依次返回权益
+    struct ScriptedEquity(Mutex<Vec<f64>>);
+    #[async_trait]
+    impl AccountEquitySource for ScriptedEquity {
+        async fn total_equity(&self) -> Result<f64> {
+            let mut script = self.0.lock().unwrap();
+            Ok(if script.len() > 1 {
+                script.remove(0)
+            } else {
+                script[0]
+            })
+        }
+    }
+    #[test]
+    fn scheduler_is_skipped_without_enabled_risk_jobs() {
+        assert!(risk_job_scheduler_with(None, Duration::from_secs(60)).is_none());
+    }
+    #[tokio::test]
+    async fn scheduled_account_check_trips_breaker_until_explicit_resume() {
+        let guard: &'static DrawdownBreakerJob = Box::leak(Box::new(
+            DrawdownBreakerJob::new(
+                Arc::new(DrawdownCircuitBreaker::new(
+                    DrawdownPolicy::default(),
+                    Arc::new(InMemoryDrawdownStateStore::default()),
+                )),
+                "scheduled-drawdown-test",
+            )
+            .with_equity_source(Arc::new(ScriptedEquity(Mutex::new(vec![
+                1_000.0, 700.0, 1_000.0,
+            ])))),
+        ));
+        let mut scheduler = risk_job_scheduler_with(Some(guard), Duration::from_secs(60))
+            .expect("drawdown check must be registered");
+        assert_eq!(scheduler.job_count(), 1);
+        let scope = DrawdownScope::Account("scheduled-drawdown-test".into());
+        scheduler.run_once(Utc::now()).await;
+        assert!(!guard.breaker().is_halted(&scope).await.unwrap());
+        scheduler.run_once(Utc::now()).await;
+        assert!(guard.breaker().is_halted(&scope).await.unwrap());
+        // 权益回升不会自动恢复，控制面恢复后下一次检查同步恢复状态
+        scheduler.run_once(Utc::now()).await;
+        assert!(guard.breaker().is_halted(&scope).await.unwrap());
+        guard.breaker().resume(&scope, 2).await.unwrap();
+        scheduler.run_once(Utc::now()).await;
+        assert!(!guard.breaker().is_halted(&scope).await.unwrap());
+    }
+}
@@ -13,6 +13,7 @@ use std::time::{Duration, SystemTime};
 use tracing::{debug, error, info, warn};
 
 use super::bear_short_live_snapshot::enrich_bear_short_live_snapshot;
+use crate::jobs::risk::drawdown_breaker_job::DrawdownBreakerJob;
 /// 策略执行状态跟踪 - 用于时间戳去重
 #[derive(Debug, Clone)]
 struct StrategyExecutionState {
@@ -244,6 +245,18 @@ pub async fn execute_strategy(
     } else {
         info!("✅ 策略配置验证成功: key={}, config_id={}", key, config.id);
     }
+    // 2.1 权益回撤熔断：账户或策略熔断后暂停执行，直到控制面显式恢复
+    let drawdown_guard = DrawdownBreakerJob::global();
+    if let Some(guard) = drawdown_guard {
+        if guard.is_strategy_blocked(config.id).await {
+            warn!(
+                "🛑 权益回撤熔断中，跳过策略执行: key={}, config_id={}",
+                key, config.id
+            );
+            StrategyExecutionStateManager::mark_completed(&key, timestamp);
+            return Ok(());
+        }
+    }
     // 3. 执行策略
     let execution_config =
         match enrich_bear_short_live_snapshot(&config, inst_id, timeframe_str, snap.as_ref()).await
@@ -269,6 +282,21 @@ pub async fn execute_strategy(
                 "✅ 策略执行成功: {} - buy={}, sell={}",
                 key, signal_result.should_buy, signal_result.should_sell
             );
+            if let Some(guard) = drawdown_guard {
+                let strategy_key = format!(
+                    "{}:{}:{}:{}",
+                    config.strategy_type.as_str(),
+                    inst_id,
+                    timeframe_str,
+                    config.id
+                );
+                if let Err(e) = guard
+                    .run_strategy_check(config.id, &strategy_key, inst_id)
+                    .await
+                {
+                    warn!("⚠️ 记录策略权益回撤失败: key={}, error={}", key, e);
+                }
+            }
             Ok(())
         }
         Err(e) => {
@@ -146,10 +146,88 @@ impl StrategyManager {
             .map(|entry| entry.key().clone())
             .collect()
     }
-    /// 检查策略是否运行中
+    /// 检查策略是否运行中（暂停的策略不算运行中）
     pub fn is_running(&self, inst_id: &str, period: &str, strategy_type: &str) -> bool {
         let strategy_key = Self::build_strategy_key(inst_id, period, strategy_type);
-        self.running_strategies.contains_key(&strategy_key)
+        self.running_strategies
+            .get(&strategy_key)
+            .map(|entry| matches!(entry.status, StrategyRunStatus::Running))
+            .unwrap_or(false)
+    }
+    /// 检查策略配置是否被暂停
+    pub fn is_config_paused(&self, config_id: i64) -> bool {
+        self.running_strategies.iter().any(|entry| {
+            entry.config_id == config_id && matches!(entry.status, StrategyRunStatus::Paused)
+        })
+    }
+    /// 暂停指定策略配置，返回被暂停的策略 key
+    ///
+    /// 运行时信息保留，恢复前不再执行。
+    pub fn pause_strategies_by_config_id(&self, config_id: i64) -> Vec<String> {
+        self.transition_status(
+            |info| info.config_id == config_id && matches!(info.status, StrategyRunStatus::Running),
+            StrategyRunStatus::Paused,
+        )
+    }
+    /// 暂停全部运行中的策略，返回被暂停的策略 key
+    pub fn pause_all_strategies(&self) -> Vec<String> {
+        self.transition_status(
+            |info| matches!(info.status, StrategyRunStatus::Running),
+            StrategyRunStatus::Paused,
+        )
+    }
+    /// 恢复指定策略配置，返回被恢复的策略 key
+    pub fn resume_strategies_by_config_id(&self, config_id: i64) -> Vec<String> {
+        self.transition_status(
+            |info| info.config_id == config_id && matches!(info.status, StrategyRunStatus::Paused),
+            StrategyRunStatus::Running,
+        )
+    }
+    /// 恢复全部暂停的策略，返回被恢复的策略 key
+    pub fn resume_all_paused_strategies(&self) -> Vec<String> {
+        self.transition_status(
+            |info| matches!(info.status, StrategyRunStatus::Paused),
+            StrategyRunStatus::Running,
+        )
+    }
+    /// 恢复指定 key 中仍处于暂停的策略，返回被恢复的策略 key
+    pub fn resume_strategies(&self, keys: &[String]) -> Vec<String> {
+        let mut resumed = Vec::new();
+        for key in keys {
+            if let Some(mut entry) = self.running_strategies.get_mut(key) {
+                if matches!(entry.status, StrategyRunStatus::Paused) {
+                    entry.status = StrategyRunStatus::Running;
+                    resumed.push(key.clone());
+                }
+            }
+        }
+        resumed.sort();
+        if !resumed.is_empty() {
+            info!(
+                "策略状态切换为 {:?}: {:?}",
+                StrategyRunStatus::Running,
+                resumed
+            );
+        }
+        resumed
+    }
+    fn transition_status(
+        &self,
+        matches: impl Fn(&StrategyRuntimeInfo) -> bool,
+        status: StrategyRunStatus,
+    ) -> Vec<String> {
+        let mut keys = Vec::new();
+        for mut entry in self.running_strategies.iter_mut() {
+            if matches(entry.value()) {
+                entry.status = status.clone();
+                keys.push(entry.key().clone());
+            }
+        }
+        keys.sort();
+        if !keys.is_empty() {
+            info!("策略状态切换为 {:?}: {:?}", status, keys);
+        }
+        keys
     }
 }
 impl Default for StrategyManager {
@@ -173,4 +251,48 @@ mod tests {
         let manager = StrategyManager::new();
         assert_eq!(manager.get_running_strategies().len(), 0);
     }
+    fn insert_running(manager: &StrategyManager, config_id: i64, inst_id: &str) {
+        let config = StrategyConfig::new(
+            config_id,
+            StrategyType::Vegas,
+            inst_id.to_string(),
+            rust_quant_domain::Timeframe::H1,
+            serde_json::json!({}),
+            serde_json::json!({}),
+        );
+        manager.running_strategies.insert(
+            StrategyManager::build_strategy_key(inst_id, "1H", "vegas"),
+            StrategyRuntimeInfo {
+                config_id,
+                inst_id: inst_id.to_string(),
+                period: "1H".to_string(),
+                strategy_type: "vegas".to_string(),
+                status: StrategyRunStatus::Running,
+                current_config: Arc::new(RwLock::new(config)),
+            },
+        );
+    }
+    #[test]
+    fn test_pause_and_resume_strategies() {
+        let manager = StrategyManager::new();
+        insert_running(&manager, 1, "BTC-USDT-SWAP");
+        insert_running(&manager, 2, "ETH-USDT-SWAP");
+        assert_eq!(
+            manager.pause_strategies_by_config_id(1),
+            vec!["BTC-USDT-SWAP:1H:vegas".to_string()]
+        );
+        assert!(manager.is_config_paused(1));
+        assert!(!manager.is_running("BTC-USDT-SWAP", "1H", "vegas"));
+        assert!(manager.is_running("ETH-USDT-SWAP", "1H", "vegas"));
+        assert_eq!(manager.pause_all_strategies().len(), 1);
+        assert_eq!(manager.resume_strategies_by_config_id(2).len(), 1);
+        assert_eq!(
+            manager.resume_strategies(&["BTC-USDT-SWAP:1H:vegas".to_string()]),
+            vec!["BTC-USDT-SWAP:1H:vegas".to_string()]
+        );
+        assert!(!manager.is_config_paused(1));
+        assert_eq!(manager.pause_all_strategies().len(), 2);
+        assert_eq!(manager.resume_all_paused_strategies().len(), 2);
+        assert_eq!(manager.get_running_strategies().len(), 2);
+    }
 }
@@ -1,6 +1,8 @@
+use super::{DrawdownBreach, DrawdownCircuitBreaker, DrawdownScope};
 use crate::legacy_signed_read_only::ensure_legacy_signed_read_only_allowed;
 use okx::api::account::OkxAccount;
 use okx::api::api_trait::OkxApiTrait;
+use rust_quant_domain::entities::BalanceSnapshot;
 /// 封装当前函数，减少风控调用方重复实现相同细节。
 /// 采用 async 以便与数据库/网络 I/O 协调，减少阻塞并提升并发吞吐。
 pub async fn get_account_balance() -> anyhow::Result<()> {
@@ -15,6 +17,30 @@ pub async fn get_account_balance() -> anyhow::Result<()> {
     println!("账户余额:{:#?}", balances);
     Ok(())
 }
+/// 读取交易账户总权益（USD 计价），供账户级回撤熔断使用。
+pub async fn get_account_total_equity() -> anyhow::Result<f64> {
+    ensure_legacy_signed_read_only_allowed()?;
+    let balances = OkxAccount::from_env()?
+        .get_balance(None)
+        .await
+        .map_err(|e| anyhow::anyhow!("OKX错误: {:?}", e))?;
+    let snapshot = BalanceSnapshot::from_exchange_json(&serde_json::to_value(&balances)?)?;
+    Ok(snapshot.total_equity)
+}
+/// 读取账户总权益并喂入回撤熔断器；回撤级别升级时返回熔断事件。
+pub async fn observe_account_drawdown(
+    breaker: &DrawdownCircuitBreaker,
+    account_id: &str,
+) -> anyhow::Result<Option<DrawdownBreach>> {
+    let equity = get_account_total_equity().await?;
+    breaker
+        .observe(
+            DrawdownScope::Account(account_id.to_string()),
+            equity,
+            chrono::Utc::now().timestamp_millis(),
+        )
+        .await
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,4 +89,24 @@ mod tests {
             "unexpected error: {message}"
         );
     }
+    #[tokio::test]
+    async fn account_drawdown_feed_requires_signed_read_only_confirmation_before_okx_client() {
+        let _guard = env_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _snapshot = EnvSnapshot::capture();
+        std::env::remove_var(TEST_CONFIRM_ENV);
+        let breaker = DrawdownCircuitBreaker::new(
+            crate::policies::DrawdownPolicy::default(),
+            std::sync::Arc::new(super::super::InMemoryDrawdownStateStore::default()),
+        );
+        let error = observe_account_drawdown(&breaker, "okx").await.expect_err(
+            "account drawdown feed must require explicit signed read-only confirmation",
+        );
+        assert!(
+            error.to_string().contains(TEST_CONFIRM_ENV),
+            "unexpected error: {error}"
+        );
+        assert!(breaker.states().await.unwrap().is_empty());
+    }
 }
@@ -0,0 +1,479 @@
+//! 账户 / 策略级权益回撤熔断
+//!
+//! 按权益曲线跟踪峰值到谷底的回撤，越过 `DrawdownPolicy` 阈值时产生熔断事件。
+//! 熔断状态会持久化，一旦进入 `StopAllTrading` 即锁定，只有显式调用 `resume` 才能恢复。
+use crate::policies::{DrawdownAction, DrawdownPolicy};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use redis::AsyncCommands;
+use rust_quant_core::cache::get_redis_connection;
+use rust_quant_domain::value_objects::Percentage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+/// 熔断状态在 Redis 中的 hash key
+const DRAWDOWN_BREAKER_REDIS_KEY: &str = "risk:drawdown_breaker:states";
+/// 回撤跟踪范围
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "scope", content = "id", rename_all = "snake_case")]
+pub enum DrawdownScope {
+    /// 交易账户（如 `okx`、某个 API 配置）
+    Account(String),
+    /// 单个策略配置（strategy_config_id）
+    Strategy(String),
+}
+impl DrawdownScope {
+    /// 持久化使用的唯一键
+    pub fn key(&self) -> String {
+        match self {
+            DrawdownScope::Account(id) => format!("account:{}", id),
+            DrawdownScope::Strategy(id) => format!("strategy:{}", id),
+        }
+    }
+    /// 从 `scope` + `id` 解析（控制面入参）
+    pub fn parse(scope: &str, id: &str) -> Result<Self> {
+        let id = id.trim();
+        if id.is_empty() {
+            return Err(anyhow!("回撤熔断范围 id 不能为空"));
+        }
+        match scope.trim().to_ascii_lowercase().as_str() {
+            "account" => Ok(DrawdownScope::Account(id.to_string())),
+            "strategy" => Ok(DrawdownScope::Strategy(id.to_string())),
+            other => Err(anyhow!("未知的回撤熔断范围: {}", other)),
+        }
+    }
+}
+/// 单个范围的回撤跟踪状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityDrawdownState {
+    /// 跟踪范围
+    pub scope: DrawdownScope,
+    /// 权益峰值
+    pub peak_equity: f64,
+    /// 峰值之后的最低权益
+    pub trough_equity: f64,
+    /// 最新权益
+    pub last_equity: f64,
+    /// 当前回撤（百分比，0-100）
+    pub drawdown_percent: f64,
+    /// 峰值以来的最大回撤（百分比，0-100）
+    pub max_drawdown_percent: f64,
+    /// 当前熔断动作
+    pub action: DrawdownAction,
+    /// 是否已熔断（停止交易，需显式恢复）
+    pub halted: bool,
+    /// 熔断时间戳（毫秒）
+    pub halted_at: Option<i64>,
+    /// 最近更新时间戳（毫秒）
+    pub updated_at: i64,
+}
+/// 回撤升级事件：动作级别提高时产生
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrawdownBreach {
+    /// 跟踪范围
+    pub scope: DrawdownScope,
+    /// 之前的动作
+    pub previous_action: DrawdownAction,
+    /// 新的动作
+    pub action: DrawdownAction,
+    /// 触发时回撤（百分比）
+    pub drawdown_percent: f64,
+    /// 权益峰值
+    pub peak_equity: f64,
+    /// 触发时权益
+    pub equity: f64,
+    /// 触发时间戳（毫秒）
+    pub ts: i64,
+}
+impl DrawdownBreach {
+    /// 是否要求停止交易
+    pub fn is_halt(&self) -> bool {
+        self.action == DrawdownAction::StopAllTrading
+    }
+}
+/// 动作严重程度，用于判断是否升级
+fn action_level(action: DrawdownAction) -> u8 {
+    match action {
+        DrawdownAction::Continue => 0,
+        DrawdownAction::ReducePositions => 1,
+        DrawdownAction::StopAllTrading => 2,
+    }
+}
+impl EquityDrawdownState {
+    /// 以首个权益观测值初始化
+    pub fn new(scope: DrawdownScope, equity: f64, ts: i64) -> Self {
+        Self {
+            scope,
+            peak_equity: equity,
+            trough_equity: equity,
+            last_equity: equity,
+            drawdown_percent: 0.0,
+            max_drawdown_percent: 0.0,
+            action: DrawdownAction::Continue,
+            halted: false,
+            halted_at: None,
+            updated_at: ts,
+        }
+    }
+    /// 喂入新的权益观测值；动作级别提高时返回熔断事件
+    ///
+    /// 已熔断的状态只更新权益和回撤，不会自动解除。
+    pub fn observe(
+        &mut self,
+        equity: f64,
+        ts: i64,
+        policy: &DrawdownPolicy,
+    ) -> Option<DrawdownBreach> {
+        if !equity.is_finite() {
+            return None;
+        }
+        self.last_equity = equity;
+        self.updated_at = ts;
+        if equity > self.peak_equity {
+            self.peak_equity = equity;
+            self.trough_equity = equity;
+        } else if equity < self.trough_equity {
+            self.trough_equity = equity;
+        }
+        self.drawdown_percent = if self.peak_equity > 0.0 {
+            ((self.peak_equity - equity) / self.peak_equity * 100.0).max(0.0)
+        } else {
+            0.0
+        };
+        self.max_drawdown_percent = self.max_drawdown_percent.max(self.drawdown_percent);
+        if self.halted {
+            return None;
+        }
+        let previous_action = self.action;
+        let action = policy.get_action(self.drawdown_percent);
+        self.action = action;
+        if action == DrawdownAction::StopAllTrading {
+            self.halted = true;
+            self.halted_at = Some(ts);
+        }
+        (action_level(action) > action_level(previous_action)).then(|| DrawdownBreach {
+            scope: self.scope.clone(),
+            previous_action,
+            action,
+            drawdown_percent: self.drawdown_percent,
+            peak_equity: self.peak_equity,
+            equity,
+            ts,
+        })
+    }
+    /// 显式恢复：以最新权益作为新的峰值重新计算回撤
+    pub fn resume(&mut self, ts: i64) {
+        self.peak_equity = self.last_equity;
+        self.trough_equity = self.last_equity;
+        self.drawdown_percent = 0.0;
+        self.max_drawdown_percent = 0.0;
+        self.action = DrawdownAction::Continue;
+        self.halted = false;
+        self.halted_at = None;
+        self.updated_at = ts;
+    }
+}
+/// 熔断状态存储
+#[async_trait]
+pub trait DrawdownStateStore: Send + Sync {
+    async fn load(&self, scope: &DrawdownScope) -> Result<Option<EquityDrawdownState>>;
+    async fn load_all(&self) -> Result<Vec<EquityDrawdownState>>;
+    async fn save(&self, state: &EquityDrawdownState) -> Result<()>;
+}
+/// Redis 存储：所有范围保存在同一个 hash 中，跨进程共享（控制面恢复 / 策略进程读取）
+#[derive(Debug, Default)]
+pub struct RedisDrawdownStateStore;
+#[async_trait]
+impl DrawdownStateStore for RedisDrawdownStateStore {
+    async fn load(&self, scope: &DrawdownScope) -> Result<Option<EquityDrawdownState>> {
+        let mut conn = get_redis_connection().await?;
+        let raw: Option<String> = conn.hget(DRAWDOWN_BREAKER_REDIS_KEY, scope.key()).await?;
+        raw.map(|raw| {
+            serde_json::from_str(&raw).map_err(|e| anyhow!("解析回撤熔断状态失败: {}", e))
+        })
+        .transpose()
+    }
+    async fn load_all(&self) -> Result<Vec<EquityDrawdownState>> {
+        let mut conn = get_redis_connection().await?;
+        let raw: HashMap<String, String> = conn.hgetall(DRAWDOWN_BREAKER_REDIS_KEY).await?;
+        let mut states = raw
+            .into_values()
+            .map(|raw| {
+                serde_json::from_str(&raw).map_err(|e| anyhow!("解析回撤熔断状态失败: {}", e))
+            })
+            .collect::<Result<Vec<EquityDrawdownState>>>()?;
+        states.sort_by_key(|state| state.scope.key());
+        Ok(states)
+    }
+    async fn save(&self, state: &EquityDrawdownState) -> Result<()> {
+        let mut conn = get_redis_connection().await?;
+        conn.hset::<_, _, _, ()>(
+            DRAWDOWN_BREAKER_REDIS_KEY,
+            state.scope.key(),
+            serde_json::to_string(state)?,
+        )
+        .await?;
+        Ok(())
+    }
+}
+/// 内存存储（单进程 / 测试）
+#[derive(Debug, Default)]
+pub struct InMemoryDrawdownStateStore {
+    states: Mutex<HashMap<String, EquityDrawdownState>>,
+}
+#[async_trait]
+impl DrawdownStateStore for InMemoryDrawdownStateStore {
+    async fn load(&self, scope: &DrawdownScope) -> Result<Option<EquityDrawdownState>> {
+        let states = self
+            .states
+            .lock()
+            .map_err(|_| anyhow!("回撤熔断状态锁异常"))?;
+        Ok(states.get(&scope.key()).cloned())
+    }
+    async fn load_all(&self) -> Result<Vec<EquityDrawdownState>> {
+        let states = self
+            .states
+            .lock()
+            .map_err(|_| anyhow!("回撤熔断状态锁异常"))?;
+        let mut states: Vec<_> = states.values().cloned().collect();
+        states.sort_by_key(|state| state.scope.key());
+        Ok(states)
+    }
+    async fn save(&self, state: &EquityDrawdownState) -> Result<()> {
+        let mut states = self
+            .states
+            .lock()
+            .map_err(|_| anyhow!("回撤熔断状态锁异常"))?;
+        states.insert(state.scope.key(), state.clone());
+        Ok(())
+    }
+}
+/// 权益回撤熔断器
+///
+/// 只负责状态推进与持久化；暂停策略、撤销待执行任务、发送通知由上层根据返回的事件处理。
+pub struct DrawdownCircuitBreaker {
+    /// 回撤阈值
+    policy: DrawdownPolicy,
+    /// 状态存储
+    store: Arc<dyn DrawdownStateStore>,
+}
+impl DrawdownCircuitBreaker {
+    pub fn new(policy: DrawdownPolicy, store: Arc<dyn DrawdownStateStore>) -> Self {
+        Self { policy, store }
+    }
+    /// 从环境变量构建，状态持久化到 Redis
+    ///
+    /// - `DRAWDOWN_BREAKER_MAX_PERCENT`: 熔断回撤百分比，默认 20
+    /// - `DRAWDOWN_BREAKER_WARNING_PERCENT`: 警告回撤百分比，默认 15
+    pub fn from_env() -> Result<Self> {
+        let default_policy = DrawdownPolicy::default();
+        let max_drawdown =
+            env_percentage("DRAWDOWN_BREAKER_MAX_PERCENT", default_policy.max_drawdown)?;
+        let warning_drawdown = env_percentage(
+            "DRAWDOWN_BREAKER_WARNING_PERCENT",
+            default_policy.warning_drawdown,
+        )?;
+        if warning_drawdown.value() > max_drawdown.value() {
+            return Err(anyhow!(
+                "DRAWDOWN_BREAKER_WARNING_PERCENT({}) 不能大于 DRAWDOWN_BREAKER_MAX_PERCENT({})",
+                warning_drawdown,
+                max_drawdown
+            ));
+        }
+        Ok(Self::new(
+            DrawdownPolicy {
+                max_drawdown,
+                warning_drawdown,
+            },
+            Arc::new(RedisDrawdownStateStore),
+        ))
+    }
+    pub fn policy(&self) -> &DrawdownPolicy {
+        &self.policy
+    }
+    /// 喂入权益观测值并持久化；动作升级时返回熔断事件
+    pub async fn observe(
+        &self,
+        scope: DrawdownScope,
+        equity: f64,
+        ts: i64,
+    ) -> Result<Option<DrawdownBreach>> {
+        let breach = match self.store.load(&scope).await? {
+            Some(mut state) => {
+                let breach = state.observe(equity, ts, &self.policy);
+                self.store.save(&state).await?;
+                breach
+            }
+            None => {
+                let mut state = EquityDrawdownState::new(scope, equity, ts);
+                let breach = state.observe(equity, ts, &self.policy);
+                self.store.save(&state).await?;
+                breach
+            }
+        };
+        if let Some(breach) = &breach {
+            warn!(
+                "⚠️ 权益回撤升级: scope={}, action={:?}, drawdown={:.2}%, peak={:.4}, equity={:.4}",
+                breach.scope.key(),
+                breach.action,
+                breach.drawdown_percent,
+                breach.peak_equity,
+                breach.equity
+            );
+        }
+        Ok(breach)
+    }
+    /// 指定范围是否处于熔断状态
+    pub async fn is_halted(&self, scope: &DrawdownScope) -> Result<bool> {
+        Ok(self
+            .store
+            .load(scope)
+            .await?
+            .map(|state| state.halted)
+            .unwrap_or(false))
+    }
+    /// 查询单个范围状态
+    pub async fn state(&self, scope: &DrawdownScope) -> Result<Option<EquityDrawdownState>> {
+        self.store.load(scope).await
+    }
+    /// 查询所有范围状态
+    pub async fn states(&self) -> Result<Vec<EquityDrawdownState>> {
+        self.store.load_all().await
+    }
+    /// 显式恢复交易；未熔断的范围返回错误，避免误操作重置峰值
+    pub async fn resume(&self, scope: &DrawdownScope, ts: i64) -> Result<EquityDrawdownState> {
+        let mut state = self
+            .store
+            .load(scope)
+            .await?
+            .ok_or_else(|| anyhow!("回撤熔断状态不存在: {}", scope.key()))?;
+        if !state.halted {
+            return Err(anyhow!("回撤熔断未触发，无需恢复: {}", scope.key()));
+        }
+        state.resume(ts);
+        self.store.save(&state).await?;
+        info!(
+            "✅ 回撤熔断已恢复: scope={}, new_peak={:.4}",
+            scope.key(),
+            state.peak_equity
+        );
+        Ok(state)
+    }
+}
+/// 解析百分比环境变量，缺省时使用默认值
+fn env_percentage(key: &str, default: Percentage) -> Result<Percentage> {
+    match std::env::var(key) {
+        Ok(raw) if !raw.trim().is_empty() => {
+            let value = raw
+                .trim()
+                .parse::<f64>()
+                .map_err(|e| anyhow!("解析 {} 失败: {}", key, e))?;
+            Percentage::new(value).map_err(|e| anyhow!("{} 无效: {}", key, e))
+        }
+        _ => Ok(default),
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn breaker() -> DrawdownCircuitBreaker {
+        DrawdownCircuitBreaker::new(
+            DrawdownPolicy {
+                max_drawdown: Percentage::new(20.0).unwrap(),
+                warning_drawdown: Percentage::new(10.0).unwrap(),
+            },
+            Arc::new(InMemoryDrawdownStateStore::default()),
+        )
+    }
+    #[test]
+    fn state_tracks_peak_to_trough_drawdown() {
+        let policy = DrawdownPolicy::default();
+        let mut state = EquityDrawdownState::new(DrawdownScope::Account("okx".into()), 1000.0, 1);
+        assert!(state.observe(1200.0, 2, &policy).is_none());
+        assert!(state.observe(1100.0, 3, &policy).is_none());
+        assert!(state.observe(1150.0, 4, &policy).is_none());
+        assert_eq!(state.peak_equity, 1200.0);
+        assert_eq!(state.trough_equity, 1100.0);
+        assert!((state.drawdown_percent - 50.0 / 1200.0 * 100.0).abs() < 1e-9);
+        assert!((state.max_drawdown_percent - 100.0 / 1200.0 * 100.0).abs() < 1e-9);
+    }
+    #[tokio::test]
+    async fn breaker_escalates_once_and_latches_until_resume() {
+        let breaker = breaker();
+        let scope = DrawdownScope::Strategy("42".into());
+        assert!(breaker
+            .observe(scope.clone(), 1000.0, 1)
+            .await
+            .unwrap()
+            .is_none());
+        let warning = breaker
+            .observe(scope.clone(), 880.0, 2)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(warning.action, DrawdownAction::ReducePositions);
+        assert!(!warning.is_halt());
+        // 同级别不重复产生事件
+        assert!(breaker
+            .observe(scope.clone(), 870.0, 3)
+            .await
+            .unwrap()
+            .is_none());
+        let halt = breaker
+            .observe(scope.clone(), 790.0, 4)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(halt.is_halt());
+        assert_eq!(halt.previous_action, DrawdownAction::ReducePositions);
+        assert!(breaker.is_halted(&scope).await.unwrap());
+        // 权益回升也不会自动解除
+        assert!(breaker
+            .observe(scope.clone(), 1000.0, 5)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(breaker.is_halted(&scope).await.unwrap());
+        let resumed = breaker.resume(&scope, 6).await.unwrap();
+        assert!(!resumed.halted);
+        assert_eq!(resumed.peak_equity, 1000.0);
+        assert_eq!(resumed.action, DrawdownAction::Continue);
+        assert!(!breaker.is_halted(&scope).await.unwrap());
+    }
+    #[tokio::test]
+    async fn resume_requires_halted_state() {
+        let breaker = breaker();
+        let scope = DrawdownScope::Account("okx".into());
+        assert!(breaker.resume(&scope, 1).await.is_err());
+        breaker.observe(scope.clone(), 1000.0, 1).await.unwrap();
+        assert!(breaker.resume(&scope, 2).await.is_err());
+    }
+    #[tokio::test]
+    async fn scopes_are_tracked_independently() {
+        let breaker = breaker();
+        let account = DrawdownScope::Account("okx".into());
+        let strategy = DrawdownScope::Strategy("7".into());
+        breaker.observe(account.clone(), 1000.0, 1).await.unwrap();
+        breaker.observe(strategy.clone(), 100.0, 1).await.unwrap();
+        let breach = breaker
+            .observe(strategy.clone(), 50.0, 2)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(breach.scope, strategy);
+        assert!(breaker.is_halted(&strategy).await.unwrap());
+        assert!(!breaker.is_halted(&account).await.unwrap());
+        assert_eq!(breaker.states().await.unwrap().len(), 2);
+    }
+    #[test]
+    fn scope_parse_and_serde_round_trip() {
+        let scope = DrawdownScope::parse("Strategy", " 12 ").unwrap();
+        assert_eq!(scope, DrawdownScope::Strategy("12".into()));
+        assert_eq!(scope.key(), "strategy:12");
+        let json = serde_json::to_value(&scope).unwrap();
+        assert_eq!(json, serde_json::json!({"scope": "strategy", "id": "12"}));
+        assert!(DrawdownScope::parse("portfolio", "1").is_err());
+        assert!(DrawdownScope::parse("account", " ").is_err());
+    }
+}
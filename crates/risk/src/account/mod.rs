@@ -1,4 +1,6 @@
 // 账户管理模块
 pub mod account_job;
+pub mod drawdown_breaker;
 // 重新导出
 pub use account_job::*;
+pub use drawdown_breaker::*;
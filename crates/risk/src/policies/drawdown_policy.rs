@@ -1,6 +1,8 @@
 //! 回撤控制策略
 use rust_quant_domain::value_objects::Percentage;
+use serde::{Deserialize, Serialize};
 /// 回撤控制策略
+#[derive(Debug, Clone)]
 pub struct DrawdownPolicy {
     /// 最大回撤限制
     pub max_drawdown: Percentage,
@@ -26,6 +28,8 @@ impl DrawdownPolicy {
     }
 }
 /// 回撤控制动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DrawdownAction {
     /// 继续交易
     Continue,
@@ -1,5 +1,9 @@
 //! 风控策略模块
 pub mod drawdown_policy;
 pub mod position_limit_policy;
+pub mod pre_trade_policy;
 pub use drawdown_policy::{DrawdownAction, DrawdownPolicy};
 pub use position_limit_policy::PositionLimitPolicy;
+pub use pre_trade_policy::{
+    AccountRiskSnapshot, PreTradeRiskLimits, PreTradeRiskPolicy, PreTradeRiskRequest, RiskDecision,
+};
@@ -2,6 +2,7 @@
 use rust_quant_domain::entities::Position;
 use rust_quant_domain::value_objects::Percentage;
 /// 持仓限额策略
+#[derive(Debug, Clone)]
 pub struct PositionLimitPolicy {
     /// 单个持仓最大占比 (占总资金)
     pub max_single_position_percent: Percentage,
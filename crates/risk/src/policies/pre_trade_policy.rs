@@ -0,0 +1,619 @@
+//! 下单前风控策略
+//!
+//! 组合持仓限额、回撤、交易频率、当日亏损、相关敞口和杠杆上限，
+//! 输出“放行 / 缩仓 / 拒绝”的结构化决策，由执行层在创建下单任务前统一遵守。
+use super::{DrawdownAction, DrawdownPolicy, PositionLimitPolicy};
+use rust_quant_domain::entities::{Position, PositionStatus};
+use rust_quant_domain::enums::PositionSide;
+use rust_quant_domain::value_objects::Percentage;
+use serde::{Deserialize, Serialize};
+/// 下单前风控限额
+///
+/// 所有限额默认关闭（0 表示不限制），需通过配置显式开启。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreTradeRiskLimits {
+    /// 统计窗口内单品种最多开仓次数；0 表示不限制
+    pub max_trades_per_symbol: usize,
+    /// 交易频率统计窗口（毫秒）
+    pub trade_frequency_window_ms: i64,
+    /// 当日最大亏损（占当日初始权益的百分比）；0 表示不限制
+    pub max_daily_loss_percent: f64,
+    /// 同向相关敞口上限（占权益的百分比）；0 表示不限制
+    pub max_correlated_exposure_percent: f64,
+    /// 相关性分组（基础币种，如 `["BTC", "ETH"]`）；为空时所有同向持仓视为相关
+    pub correlation_groups: Vec<Vec<String>>,
+    /// 杠杆上限；0 表示不限制
+    pub max_leverage: f64,
+    /// 单个持仓价值上限（占权益的百分比）；0 表示不限制
+    pub max_single_position_percent: f64,
+    /// 总持仓价值上限（占权益的百分比）；0 表示不限制
+    pub max_total_position_percent: f64,
+    /// 单个交易对最大持仓数量；0 表示不限制
+    pub max_positions_per_symbol: usize,
+    /// 最大回撤（百分比），超过后停止开仓；0 表示不限制
+    pub max_drawdown_percent: f64,
+    /// 回撤警告线（百分比），超过后缩仓；0 表示与最大回撤相同
+    pub warning_drawdown_percent: f64,
+    /// 回撤进入警告区时的缩仓比例
+    pub drawdown_warning_size_ratio: f64,
+    /// 缩仓后的最小保留比例，低于该比例直接拒绝
+    pub min_size_ratio: f64,
+}
+impl Default for PreTradeRiskLimits {
+    fn default() -> Self {
+        Self {
+            max_trades_per_symbol: 0,
+            trade_frequency_window_ms: 6 * 60 * 60 * 1000,
+            max_daily_loss_percent: 0.0,
+            max_correlated_exposure_percent: 0.0,
+            correlation_groups: Vec::new(),
+            max_leverage: 0.0,
+            max_single_position_percent: 0.0,
+            max_total_position_percent: 0.0,
+            max_positions_per_symbol: 0,
+            max_drawdown_percent: 0.0,
+            warning_drawdown_percent: 0.0,
+            drawdown_warning_size_ratio: 0.5,
+            min_size_ratio: 0.25,
+        }
+    }
+}
+impl PreTradeRiskLimits {
+    /// 持仓限额策略；三项均未配置时返回 None
+    fn position_limit_policy(&self) -> Option<PositionLimitPolicy> {
+        if self.max_single_position_percent <= 0.0
+            && self.max_total_position_percent <= 0.0
+            && self.max_positions_per_symbol == 0
+        {
+            return None;
+        }
+        Some(PositionLimitPolicy {
+            max_single_position_percent: percentage_or_unlimited(self.max_single_position_percent),
+            max_total_position_percent: percentage_or_unlimited(self.max_total_position_percent),
+            max_positions_per_symbol: match self.max_positions_per_symbol {
+                0 => usize::MAX,
+                limit => limit,
+            },
+        })
+    }
+    /// 回撤控制策略；未配置最大回撤时返回 None
+    fn drawdown_policy(&self) -> Option<DrawdownPolicy> {
+        if self.max_drawdown_percent <= 0.0 {
+            return None;
+        }
+        let warning = if self.warning_drawdown_percent > 0.0 {
+            self.warning_drawdown_percent.min(self.max_drawdown_percent)
+        } else {
+            self.max_drawdown_percent
+        };
+        Some(DrawdownPolicy {
+            max_drawdown: percentage_or_unlimited(self.max_drawdown_percent),
+            warning_drawdown: percentage_or_unlimited(warning),
+        })
+    }
+}
+/// 百分比限额转为 `Percentage`；未配置或越界时视为 100%
+fn percentage_or_unlimited(value: f64) -> Percentage {
+    Percentage::new(value)
+        .ok()
+        .filter(|_| value > 0.0)
+        .unwrap_or_else(|| Percentage::new(100.0).expect("100% is a valid percentage"))
+}
+/// 下单前风控请求
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreTradeRiskRequest {
+    /// 交易对
+    pub inst_id: String,
+    /// 开仓方向
+    pub side: PositionSide,
+    /// 拟开仓名义价值；为空时表示由下游执行层定量，只校验现有敞口
+    pub proposed_notional: Option<f64>,
+    /// 拟使用杠杆；为空时不做杠杆校验
+    pub leverage: Option<f64>,
+    /// 信号时间戳（毫秒），交易频率窗口以此为终点
+    pub signal_ts: i64,
+}
+/// 账户风险快照
+#[derive(Debug, Clone)]
+pub struct AccountRiskSnapshot {
+    /// 当前权益
+    pub equity: f64,
+    /// 历史峰值权益
+    pub peak_equity: f64,
+    /// 当日初始权益
+    pub day_start_equity: f64,
+    /// 当前持仓
+    pub positions: Vec<Position>,
+}
+/// 风控决策
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RiskDecision {
+    /// 放行
+    Allow,
+    /// 按比例缩减下单规模后放行
+    Shrink {
+        size_ratio: f64,
+        reasons: Vec<String>,
+    },
+    /// 拒绝
+    Reject { reasons: Vec<String> },
+}
+impl RiskDecision {
+    pub fn is_rejected(&self) -> bool {
+        matches!(self, RiskDecision::Reject { .. })
+    }
+    /// 下单规模系数：放行为 1，拒绝为 0
+    pub fn size_ratio(&self) -> f64 {
+        match self {
+            RiskDecision::Allow => 1.0,
+            RiskDecision::Shrink { size_ratio, .. } => *size_ratio,
+            RiskDecision::Reject { .. } => 0.0,
+        }
+    }
+    pub fn reasons(&self) -> &[String] {
+        match self {
+            RiskDecision::Allow => &[],
+            RiskDecision::Shrink { reasons, .. } | RiskDecision::Reject { reasons } => reasons,
+        }
+    }
+}
+/// 下单前风控策略
+///
+/// 默认不启用任何限额，所有请求放行。
+#[derive(Debug, Clone, Default)]
+pub struct PreTradeRiskPolicy {
+    /// 限额配置
+    pub limits: PreTradeRiskLimits,
+    /// 持仓限额策略；为空时不校验持仓数量与价值
+    pub position_limit: Option<PositionLimitPolicy>,
+    /// 回撤控制策略；为空时不校验回撤
+    pub drawdown: Option<DrawdownPolicy>,
+}
+/// 评估过程中累积的拒绝与缩仓原因
+#[derive(Default)]
+struct Verdicts {
+    rejects: Vec<String>,
+    shrinks: Vec<(f64, String)>,
+}
+impl Verdicts {
+    /// 允许规模不足最小保留比例时转为拒绝
+    fn shrink_or_reject(&mut self, ratio: f64, min_ratio: f64, reason: String) {
+        if ratio >= min_ratio {
+            self.shrinks.push((ratio, reason));
+        } else {
+            self.rejects.push(reason);
+        }
+    }
+    fn into_decision(self) -> RiskDecision {
+        if !self.rejects.is_empty() {
+            return RiskDecision::Reject {
+                reasons: self.rejects,
+            };
+        }
+        if self.shrinks.is_empty() {
+            return RiskDecision::Allow;
+        }
+        let size_ratio = self
+            .shrinks
+            .iter()
+            .map(|(ratio, _)| *ratio)
+            .fold(1.0_f64, f64::min);
+        RiskDecision::Shrink {
+            size_ratio,
+            reasons: self.shrinks.into_iter().map(|(_, reason)| reason).collect(),
+        }
+    }
+}
+impl PreTradeRiskPolicy {
+    pub fn new(limits: PreTradeRiskLimits) -> Self {
+        Self {
+            position_limit: limits.position_limit_policy(),
+            drawdown: limits.drawdown_policy(),
+            limits,
+        }
+    }
+    /// 评估一次开仓请求
+    ///
+    /// `recent_trade_ts` 为该品种已放行开仓的信号时间戳；账户快照缺失时只执行杠杆和频率校验。
+    pub fn evaluate(
+        &self,
+        request: &PreTradeRiskRequest,
+        snapshot: Option<&AccountRiskSnapshot>,
+        recent_trade_ts: &[i64],
+    ) -> RiskDecision {
+        let mut verdicts = Verdicts::default();
+        self.check_leverage(request, &mut verdicts);
+        self.check_trade_frequency(request, recent_trade_ts, &mut verdicts);
+        if let Some(snapshot) = snapshot {
+            if snapshot.equity <= 0.0 {
+                verdicts
+                    .rejects
+                    .push(format!("账户权益无效: {:.2}", snapshot.equity));
+                return verdicts.into_decision();
+            }
+            self.check_drawdown(snapshot, &mut verdicts);
+            self.check_daily_loss(snapshot, &mut verdicts);
+            self.check_position_limit(request, snapshot, &mut verdicts);
+            self.check_correlated_exposure(request, snapshot, &mut verdicts);
+        }
+        verdicts.into_decision()
+    }
+    fn check_leverage(&self, request: &PreTradeRiskRequest, verdicts: &mut Verdicts) {
+        let Some(leverage) = request.leverage else {
+            return;
+        };
+        if self.limits.max_leverage > 0.0 && leverage > self.limits.max_leverage {
+            verdicts.rejects.push(format!(
+                "杠杆 {:.2}x 超过上限 {:.2}x",
+                leverage, self.limits.max_leverage
+            ));
+        }
+    }
+    fn check_trade_frequency(
+        &self,
+        request: &PreTradeRiskRequest,
+        recent_trade_ts: &[i64],
+        verdicts: &mut Verdicts,
+    ) {
+        let max_trades = self.limits.max_trades_per_symbol;
+        if max_trades == 0 {
+            return;
+        }
+        let window_start = request.signal_ts - self.limits.trade_frequency_window_ms;
+        let count = recent_trade_ts
+            .iter()
+            .filter(|ts| **ts > window_start && **ts <= request.signal_ts)
+            .count();
+        if count >= max_trades {
+            verdicts.rejects.push(format!(
+                "{} 在 {} 分钟内已开仓 {} 次，达到上限 {}",
+                request.inst_id,
+                self.limits.trade_frequency_window_ms / 60_000,
+                count,
+                max_trades
+            ));
+        }
+    }
+    fn check_drawdown(&self, snapshot: &AccountRiskSnapshot, verdicts: &mut Verdicts) {
+        let Some(policy) = &self.drawdown else {
+            return;
+        };
+        if snapshot.peak_equity <= 0.0 {
+            return;
+        }
+        let drawdown =
+            ((snapshot.peak_equity - snapshot.equity) / snapshot.peak_equity * 100.0).max(0.0);
+        match policy.get_action(drawdown) {
+            DrawdownAction::Continue => {}
+            DrawdownAction::ReducePositions => verdicts.shrink_or_reject(
+                self.limits.drawdown_warning_size_ratio,
+                self.limits.min_size_ratio,
+                format!(
+                    "回撤 {:.2}% 超过警告线 {}%，缩减开仓规模",
+                    drawdown, policy.warning_drawdown
+                ),
+            ),
+            DrawdownAction::StopAllTrading => verdicts.rejects.push(format!(
+                "回撤 {:.2}% 超过上限 {}%，停止开仓",
+                drawdown, policy.max_drawdown
+            )),
+        }
+    }
+    fn check_daily_loss(&self, snapshot: &AccountRiskSnapshot, verdicts: &mut Verdicts) {
+        let max_loss = self.limits.max_daily_loss_percent;
+        if max_loss <= 0.0 || snapshot.day_start_equity <= 0.0 {
+            return;
+        }
+        let loss =
+            (snapshot.day_start_equity - snapshot.equity) / snapshot.day_start_equity * 100.0;
+        if loss >= max_loss {
+            verdicts
+                .rejects
+                .push(format!("当日亏损 {:.2}% 达到上限 {:.2}%", loss, max_loss));
+        }
+    }
+    fn check_position_limit(
+        &self,
+        request: &PreTradeRiskRequest,
+        snapshot: &AccountRiskSnapshot,
+        verdicts: &mut Verdicts,
+    ) {
+        let Some(policy) = &self.position_limit else {
+            return;
+        };
+        let open_positions: Vec<Position> = snapshot
+            .positions
+            .iter()
+            .filter(|position| position.status != PositionStatus::Closed)
+            .cloned()
+            .collect();
+        let symbol_positions = open_positions
+            .iter()
+            .filter(|position| position.symbol == request.inst_id)
+            .count();
+        if symbol_positions >= policy.max_positions_per_symbol {
+            verdicts.rejects.push(format!(
+                "{} 已有 {} 个持仓，达到上限 {}",
+                request.inst_id, symbol_positions, policy.max_positions_per_symbol
+            ));
+            return;
+        }
+        let total_value: f64 = open_positions.iter().map(Position::position_value).sum();
+        let total_cap = snapshot.equity * policy.max_total_position_percent.value() / 100.0;
+        let Some(notional) = request.proposed_notional.filter(|value| *value > 0.0) else {
+            if total_value >= total_cap {
+                verdicts.rejects.push(format!(
+                    "总持仓价值 {:.2} 已达到上限 {:.2}",
+                    total_value, total_cap
+                ));
+            }
+            return;
+        };
+        if let Err(reason) = policy.can_open_position(notional, snapshot.equity, &open_positions) {
+            let single_cap = snapshot.equity * policy.max_single_position_percent.value() / 100.0;
+            let allowed = single_cap.min(total_cap - total_value).max(0.0);
+            verdicts.shrink_or_reject(allowed / notional, self.limits.min_size_ratio, reason);
+        }
+    }
+    fn check_correlated_exposure(
+        &self,
+        request: &PreTradeRiskRequest,
+        snapshot: &AccountRiskSnapshot,
+        verdicts: &mut Verdicts,
+    ) {
+        let max_percent = self.limits.max_correlated_exposure_percent;
+        if max_percent <= 0.0 {
+            return;
+        }
+        let exposure: f64 = snapshot
+            .positions
+            .iter()
+            .filter(|position| {
+                position.status != PositionStatus::Closed
+                    && position.side == request.side
+                    && self.is_correlated(&request.inst_id, &position.symbol)
+            })
+            .map(Position::position_value)
+            .sum();
+        let cap = snapshot.equity * max_percent / 100.0;
+        let proposed = request.proposed_notional.unwrap_or(0.0).max(0.0);
+        if exposure + proposed <= cap && exposure < cap {
+            return;
+        }
+        let reason = format!(
+            "同向相关敞口 {:.2} + {:.2} 超过上限 {:.2} ({}%)",
+            exposure, proposed, cap, max_percent
+        );
+        if proposed > 0.0 {
+            verdicts.shrink_or_reject(
+                (cap - exposure).max(0.0) / proposed,
+                self.limits.min_size_ratio,
+                reason,
+            );
+        } else {
+            verdicts.rejects.push(reason);
+        }
+    }
+    /// 两个交易对是否属于同一相关性分组
+    fn is_correlated(&self, inst_id: &str, other: &str) -> bool {
+        let base = base_asset(inst_id);
+        let other_base = base_asset(other);
+        if base == other_base || self.limits.correlation_groups.is_empty() {
+            return true;
+        }
+        self.limits.correlation_groups.iter().any(|group| {
+            let contains = |asset: &str| group.iter().any(|item| item.eq_ignore_ascii_case(asset));
+            contains(&base) && contains(&other_base)
+        })
+    }
+}
+/// 交易对的基础币种（`BTC-USDT-SWAP` → `BTC`）
+fn base_asset(inst_id: &str) -> String {
+    inst_id
+        .split('-')
+        .next()
+        .unwrap_or(inst_id)
+        .to_ascii_uppercase()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_quant_domain::entities::MarginMode;
+    use rust_quant_domain::value_objects::{Price, Volume};
+
+    fn position(symbol: &str, side: PositionSide, value: f64) -> Position {
+        Position::new(
+            format!("{symbol}-{value}"),
+            symbol.to_string(),
+            side,
+            Volume::new(1.0).unwrap(),
+            Price::new(value).unwrap(),
+            3.0,
+            MarginMode::Cross,
+        )
+        .unwrap()
+    }
+
+    fn snapshot(equity: f64, positions: Vec<Position>) -> AccountRiskSnapshot {
+        AccountRiskSnapshot {
+            equity,
+            peak_equity: equity,
+            day_start_equity: equity,
+            positions,
+        }
+    }
+
+    fn enforced_limits() -> PreTradeRiskLimits {
+        PreTradeRiskLimits {
+            max_trades_per_symbol: 3,
+            max_daily_loss_percent: 5.0,
+            max_correlated_exposure_percent: 60.0,
+            max_leverage: 10.0,
+            max_single_position_percent: 20.0,
+            max_total_position_percent: 80.0,
+            max_positions_per_symbol: 3,
+            max_drawdown_percent: 20.0,
+            warning_drawdown_percent: 15.0,
+            ..PreTradeRiskLimits::default()
+        }
+    }
+
+    fn enforced_policy() -> PreTradeRiskPolicy {
+        PreTradeRiskPolicy::new(enforced_limits())
+    }
+
+    fn request(notional: Option<f64>) -> PreTradeRiskRequest {
+        PreTradeRiskRequest {
+            inst_id: "BTC-USDT-SWAP".to_string(),
+            side: PositionSide::Long,
+            proposed_notional: notional,
+            leverage: Some(5.0),
+            signal_ts: 10 * 60 * 60 * 1000,
+        }
+    }
+
+    #[test]
+    fn allows_trade_within_all_limits() {
+        let policy = enforced_policy();
+        let decision = policy.evaluate(
+            &request(Some(1_000.0)),
+            Some(&snapshot(10_000.0, vec![])),
+            &[],
+        );
+        assert_eq!(decision, RiskDecision::Allow);
+        assert_eq!(decision.size_ratio(), 1.0);
+    }
+
+    #[test]
+    fn default_policy_enforces_no_limits() {
+        let policy = PreTradeRiskPolicy::default();
+        assert!(policy.position_limit.is_none());
+        assert!(policy.drawdown.is_none());
+        let mut req = request(Some(50_000.0));
+        req.leverage = Some(100.0);
+        let mut snap = snapshot(5_000.0, vec![]);
+        snap.peak_equity = 10_000.0;
+        snap.day_start_equity = 10_000.0;
+        let recent = [req.signal_ts - 1_000; 10];
+        assert_eq!(
+            policy.evaluate(&req, Some(&snap), &recent),
+            RiskDecision::Allow
+        );
+    }
+
+    #[test]
+    fn rejects_leverage_and_trade_frequency_without_snapshot() {
+        let policy = enforced_policy();
+        let mut req = request(None);
+        req.leverage = Some(20.0);
+        let ts = req.signal_ts;
+        let recent = [ts - 1_000, ts - 2_000, ts - 3_000];
+        let decision = policy.evaluate(&req, None, &recent);
+        assert!(decision.is_rejected());
+        assert_eq!(decision.reasons().len(), 2);
+
+        // 窗口之外的历史开仓不计入频率
+        req.leverage = Some(2.0);
+        let stale = [ts - 7 * 60 * 60 * 1000; 3];
+        assert_eq!(policy.evaluate(&req, None, &stale), RiskDecision::Allow);
+    }
+
+    #[test]
+    fn drawdown_warning_shrinks_and_breach_rejects() {
+        let policy = enforced_policy();
+        let mut snap = snapshot(8_300.0, vec![]);
+        snap.peak_equity = 10_000.0;
+        snap.day_start_equity = 8_300.0;
+        let decision = policy.evaluate(&request(None), Some(&snap), &[]);
+        assert_eq!(decision.size_ratio(), 0.5);
+
+        snap.equity = 7_500.0;
+        snap.day_start_equity = 7_500.0;
+        assert!(policy
+            .evaluate(&request(None), Some(&snap), &[])
+            .is_rejected());
+    }
+
+    #[test]
+    fn daily_loss_limit_rejects() {
+        let policy = enforced_policy();
+        let mut snap = snapshot(9_400.0, vec![]);
+        snap.peak_equity = 9_400.0;
+        snap.day_start_equity = 10_000.0;
+        let decision = policy.evaluate(&request(Some(100.0)), Some(&snap), &[]);
+        assert!(decision.is_rejected());
+        assert!(decision.reasons()[0].contains("当日亏损"));
+    }
+
+    #[test]
+    fn oversized_position_is_shrunk_to_single_position_cap() {
+        let policy = enforced_policy();
+        // 单仓上限 20% = 2000，请求 3000 → 缩到 2/3
+        let decision = policy.evaluate(
+            &request(Some(3_000.0)),
+            Some(&snapshot(10_000.0, vec![])),
+            &[],
+        );
+        match decision {
+            RiskDecision::Shrink { size_ratio, .. } => {
+                assert!((size_ratio - 2.0 / 3.0).abs() < 1e-9)
+            }
+            other => panic!("unexpected decision: {other:?}"),
+        }
+        // 请求远超上限，缩仓后不足最小保留比例 → 拒绝
+        assert!(policy
+            .evaluate(
+                &request(Some(20_000.0)),
+                Some(&snapshot(10_000.0, vec![])),
+                &[]
+            )
+            .is_rejected());
+    }
+
+    #[test]
+    fn correlated_exposure_respects_groups() {
+        let mut limits = PreTradeRiskLimits {
+            max_correlated_exposure_percent: 30.0,
+            ..enforced_limits()
+        };
+        let positions = vec![
+            position("ETH-USDT-SWAP", PositionSide::Long, 1_500.0),
+            position("DOGE-USDT-SWAP", PositionSide::Long, 1_500.0),
+            position("SOL-USDT-SWAP", PositionSide::Short, 1_500.0),
+        ];
+        // 无分组：所有同向持仓视为相关，3000 已达到 30% 上限
+        let policy = PreTradeRiskPolicy::new(limits.clone());
+        let decision = policy.evaluate(
+            &request(Some(500.0)),
+            Some(&snapshot(10_000.0, positions.clone())),
+            &[],
+        );
+        assert!(decision.is_rejected());
+
+        // BTC 只与 ETH 相关：1500 + 1000 ≤ 3000
+        limits.correlation_groups = vec![vec!["BTC".to_string(), "ETH".to_string()]];
+        let policy = PreTradeRiskPolicy::new(limits);
+        let decision = policy.evaluate(
+            &request(Some(1_000.0)),
+            Some(&snapshot(10_000.0, positions)),
+            &[],
+        );
+        assert_eq!(decision, RiskDecision::Allow);
+    }
+
+    #[test]
+    fn per_symbol_position_count_rejects() {
+        let policy = enforced_policy();
+        let positions = (0..3)
+            .map(|i| position("BTC-USDT-SWAP", PositionSide::Long, 100.0 + i as f64))
+            .collect();
+        let decision = policy.evaluate(
+            &request(Some(100.0)),
+            Some(&snapshot(100_000.0, positions)),
+            &[],
+        );
+        assert!(decision.is_rejected());
+    }
+}
@@ -1,4 +1,6 @@
-use super::{MarketCandle, PositionSnapshot, StopLossAmender, StrategyRiskConfigSnapshot};
+use super::{
+    MarketCandle, PositionSnapshot, StopLossAmender, StopLossMove, StrategyRiskConfigSnapshot,
+};
 use rust_quant_common::CandleItem;
 use rust_quant_domain::enums::PositionSide;
 use rust_quant_domain::BasicRiskConfig;
@@ -115,8 +117,25 @@ impl<A: StopLossAmender> BreakevenStopLossService<A> {
             },
         );
     }
-    /// 处理 K线更新（可在 confirm=1 时调用）
-    pub async fn on_candle(&self, market_candle: MarketCandle) {
+    /// 同步其他规则已提交的止损：止损已到达或越过开仓价时视为已保本，避免回退止损
+    pub async fn record_stop_moves(&self, moves: &[StopLossMove]) {
+        let mut guard = self.inner.write().await;
+        for mv in moves {
+            let key = StrategyKey::new(mv.strategy_config_id, mv.inst_id.clone());
+            if let Some(st) = guard.positions.get_mut(&key) {
+                let at_or_beyond_entry = match st.snapshot.pos_side {
+                    PositionSide::Long => mv.price >= st.snapshot.entry_price,
+                    PositionSide::Short => mv.price <= st.snapshot.entry_price,
+                    PositionSide::Both => false,
+                };
+                if at_or_beyond_entry {
+                    st.moved_to_breakeven = true;
+                }
+            }
+        }
+    }
+    /// 处理 K线更新（可在 confirm=1 时调用），返回本次成功提交的止损移动
+    pub async fn on_candle(&self, market_candle: MarketCandle) -> Vec<StopLossMove> {
         // 只在确认K线时触发，可以减少噪声（上层若传入未确认K线，这里不强制拦截）
         let inst_id = market_candle.inst_id.clone();
         let candle: CandleItem = market_candle.candle;
@@ -131,8 +150,9 @@ impl<A: StopLossAmender> BreakevenStopLossService<A> {
                 .map(|(k, st)| (k.clone(), st.clone()))
                 .collect::<Vec<_>>()
         };
+        let mut moves = Vec::new();
         if candidates.is_empty() {
-            return;
+            return moves;
         }
         for (key, st) in candidates {
             if !Self::is_enabled(&st.risk) {
@@ -189,6 +209,11 @@ impl<A: StopLossAmender> BreakevenStopLossService<A> {
                     if let Some(s) = guard.positions.get_mut(&key) {
                         s.moved_to_breakeven = true;
                     }
+                    moves.push(StopLossMove {
+                        strategy_config_id: key.strategy_config_id,
+                        inst_id: key.inst_id.clone(),
+                        price: breakeven_price,
+                    });
                 }
                 Err(e) => {
                     warn!(
@@ -198,6 +223,7 @@ impl<A: StopLossAmender> BreakevenStopLossService<A> {
                 }
             }
         }
+        moves
     }
     fn is_enabled(risk: &BasicRiskConfig) -> bool {
         risk.atr_take_profit_ratio.unwrap_or(0.0) > 0.0
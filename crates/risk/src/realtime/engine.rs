@@ -1,6 +1,6 @@
 use super::{
-    BreakevenStopLossService, MarketCandle, PositionSnapshot, RealtimeRiskEvent, StopLossAmender,
-    StrategyRiskConfigSnapshot,
+    BreakevenStopLossService, ExitRuleService, MarketCandle, PositionSnapshot, RealtimeRiskEvent,
+    StopLossAmender, StrategyRiskConfigSnapshot,
 };
 use rust_quant_domain::BasicRiskConfig;
 use std::collections::HashMap;
@@ -11,15 +11,18 @@ use tracing::debug;
 ///
 /// 当前内置：
 /// - 1.5R 触发后移动止损到开仓价（保本）
+/// - 按 `BasicRiskConfig.realtime_rules` 启用的 ATR 跟踪止损、吊灯止损、时间退出、分批止盈
 pub struct RealtimeRiskEngine<A: StopLossAmender> {
     breakeven: BreakevenStopLossService<A>,
+    exit_rules: ExitRuleService<A>,
     risk_cache: Arc<RwLock<HashMap<(i64, String), BasicRiskConfig>>>,
 }
 impl<A: StopLossAmender> RealtimeRiskEngine<A> {
     /// 构建 交易执行与风控 所需实例，并集中初始化依赖和默认状态。
     pub fn new(amender: Arc<A>) -> Self {
         Self {
-            breakeven: BreakevenStopLossService::new(amender),
+            breakeven: BreakevenStopLossService::new(amender.clone()),
+            exit_rules: ExitRuleService::new(amender),
             risk_cache: Arc::new(RwLock::new(HashMap::new())),
         }
     }
@@ -48,6 +51,7 @@ impl<A: StopLossAmender> RealtimeRiskEngine<A> {
                 cfg.risk.clone(),
             );
         }
+        self.exit_rules.upsert_risk_config(cfg.clone()).await;
         self.breakeven.upsert_risk_config(cfg).await;
     }
     /// 封装处理position，减少风控调用方重复实现相同细节。
@@ -63,9 +67,16 @@ impl<A: StopLossAmender> RealtimeRiskEngine<A> {
             "收到持仓更新: strategy_config_id={}, inst_id={}, open={}, side={:?}",
             pos.strategy_config_id, pos.inst_id, pos.is_open, pos.pos_side
         );
+        self.exit_rules
+            .upsert_position(pos.clone(), risk.clone())
+            .await;
         self.breakeven.upsert_position(pos, risk).await;
     }
+    /// 先执行保本规则，再执行退出规则；两者互相同步已提交的止损，避免止损回退。
     async fn on_candle(&self, candle: MarketCandle) {
-        self.breakeven.on_candle(candle).await;
+        let breakeven_moves = self.breakeven.on_candle(candle.clone()).await;
+        self.exit_rules.record_stop_moves(&breakeven_moves).await;
+        let exit_moves = self.exit_rules.on_candle(candle).await;
+        self.breakeven.record_stop_moves(&exit_moves).await;
     }
 }
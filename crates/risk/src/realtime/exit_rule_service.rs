@@ -0,0 +1,600 @@
+use super::{
+    build_exit_rules, initial_risk_per_unit, resolve_exit_actions, ExitRuleAction, ExitRuleContext,
+    MarketCandle, OrderSizeFilter, PositionSnapshot, StopLossAmender, StopLossMove,
+    StrategyRiskConfigSnapshot,
+};
+use rust_quant_common::CandleItem;
+use rust_quant_domain::enums::PositionSide;
+use rust_quant_domain::BasicRiskConfig;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+/// 每个交易对缓存的确认K线上限（覆盖 ATR/吊灯回看窗口）
+const MAX_CANDLE_HISTORY: usize = 256;
+/// 按策略风险配置执行实时退出规则（ATR 跟踪止损 / 吊灯止损 / 时间退出 / 分批止盈）
+///
+/// 注意：
+/// - 仅处理确认K线（confirm=1），持仓K线数按确认K线计数。
+/// - 规则由 `BasicRiskConfig.realtime_rules` 逐策略选择，未配置时不做任何动作。
+/// - 止损只朝有利方向移动；改单/平仓失败时保留状态，在后续K线重试。
+pub struct ExitRuleService<A: StopLossAmender> {
+    amender: Arc<A>,
+    inner: Arc<RwLock<InnerState>>,
+}
+#[derive(Debug, Clone)]
+struct PositionRuleState {
+    /// 快照。
+    snapshot: PositionSnapshot,
+    /// 风险。
+    risk: BasicRiskConfig,
+    /// 开仓后已处理的确认K线数。
+    bars_held: usize,
+    /// 最近处理的确认K线时间戳。
+    last_candle_ts: Option<i64>,
+    /// 持仓期最有利价格。
+    favorable_extreme: f64,
+    /// 当前止损触发价。
+    current_stop: Option<f64>,
+    /// 已完成的分批止盈档数。
+    ladder_filled: usize,
+    /// 已提交全部平仓，等待持仓关闭事件。
+    closing: bool,
+}
+#[derive(Default)]
+struct InnerState {
+    /// 确认K线历史（按交易对）。
+    candles: HashMap<String, VecDeque<CandleItem>>,
+    /// 持仓规则状态（按策略配置 + 交易对）。
+    positions: HashMap<(i64, String), PositionRuleState>,
+}
+impl<A: StopLossAmender> ExitRuleService<A> {
+    /// 构建 交易执行与风控 所需实例，并集中初始化依赖和默认状态。
+    pub fn new(amender: Arc<A>) -> Self {
+        Self {
+            amender,
+            inner: Arc::new(RwLock::new(InnerState::default())),
+        }
+    }
+    /// 更新策略风险配置（热更新）
+    pub async fn upsert_risk_config(&self, cfg: StrategyRiskConfigSnapshot) {
+        let mut guard = self.inner.write().await;
+        if let Some(st) = guard
+            .positions
+            .get_mut(&(cfg.strategy_config_id, cfg.inst_id.clone()))
+        {
+            st.risk = cfg.risk;
+        }
+    }
+    /// 更新持仓快照；同一持仓的加减仓不会重置规则进度
+    pub async fn upsert_position(&self, snapshot: PositionSnapshot, risk: BasicRiskConfig) {
+        let mut guard = self.inner.write().await;
+        let key = (snapshot.strategy_config_id, snapshot.inst_id.clone());
+        if !snapshot.is_open {
+            guard.positions.remove(&key);
+            return;
+        }
+        match guard.positions.get_mut(&key) {
+            Some(st) if st.snapshot.pos_side == snapshot.pos_side => {
+                st.snapshot = snapshot;
+                st.risk = risk;
+            }
+            _ => {
+                let favorable_extreme = snapshot.entry_price;
+                let current_stop = snapshot.initial_stop_loss;
+                guard.positions.insert(
+                    key,
+                    PositionRuleState {
+                        snapshot,
+                        risk,
+                        bars_held: 0,
+                        last_candle_ts: None,
+                        favorable_extreme,
+                        current_stop,
+                        ladder_filled: 0,
+                        closing: false,
+                    },
+                );
+            }
+        }
+    }
+    /// 同步其他规则（如保本止损）已提交的止损，保证后续只朝有利方向移动
+    pub async fn record_stop_moves(&self, moves: &[StopLossMove]) {
+        let mut guard = self.inner.write().await;
+        for mv in moves {
+            if let Some(st) = guard
+                .positions
+                .get_mut(&(mv.strategy_config_id, mv.inst_id.clone()))
+            {
+                let improves = match (st.snapshot.pos_side, st.current_stop) {
+                    (_, None) => true,
+                    (PositionSide::Long, Some(stop)) => mv.price > stop,
+                    (PositionSide::Short, Some(stop)) => mv.price < stop,
+                    (PositionSide::Both, Some(_)) => false,
+                };
+                if improves {
+                    st.current_stop = Some(mv.price);
+                }
+            }
+        }
+    }
+    /// 处理确认K线，返回本次成功提交的止损移动
+    pub async fn on_candle(&self, market_candle: MarketCandle) -> Vec<StopLossMove> {
+        let mut moves = Vec::new();
+        if market_candle.candle.confirm != 1 {
+            return moves;
+        }
+        let inst_id = market_candle.inst_id;
+        let candle = market_candle.candle;
+        let plans = {
+            let mut guard = self.inner.write().await;
+            let history = guard.candles.entry(inst_id.clone()).or_default();
+            if history.back().is_some_and(|last| last.ts == candle.ts) {
+                history.pop_back();
+            }
+            history.push_back(candle.clone());
+            while history.len() > MAX_CANDLE_HISTORY {
+                history.pop_front();
+            }
+            let candles: Vec<CandleItem> = history.iter().cloned().collect();
+            let mut plans = Vec::new();
+            for (key, st) in guard.positions.iter_mut() {
+                if key.1 != inst_id || st.closing {
+                    continue;
+                }
+                let Some(rules_cfg) = st.risk.realtime_rules.as_ref().filter(|c| !c.is_empty())
+                else {
+                    continue;
+                };
+                if st.last_candle_ts.is_some_and(|ts| ts >= candle.ts) {
+                    continue;
+                }
+                st.last_candle_ts = Some(candle.ts);
+                st.bars_held += 1;
+                st.favorable_extreme = match st.snapshot.pos_side {
+                    PositionSide::Long => st.favorable_extreme.max(candle.h),
+                    PositionSide::Short => st.favorable_extreme.min(candle.l),
+                    PositionSide::Both => continue,
+                };
+                let ctx = ExitRuleContext {
+                    side: st.snapshot.pos_side,
+                    entry_price: st.snapshot.entry_price,
+                    risk_per_unit: initial_risk_per_unit(
+                        st.snapshot.pos_side,
+                        st.snapshot.entry_price,
+                        st.snapshot.initial_stop_loss,
+                        st.risk.max_loss_percent,
+                    ),
+                    candles: &candles,
+                    bars_held: st.bars_held,
+                    favorable_extreme: st.favorable_extreme,
+                    current_stop: st.current_stop,
+                    ladder_filled: st.ladder_filled,
+                };
+                let actions = build_exit_rules(rules_cfg)
+                    .iter()
+                    .filter_map(|rule| {
+                        let action = rule.evaluate(&ctx);
+                        if let Some(action) = &action {
+                            debug!(
+                                "实时退出规则触发: rule={}, strategy_config_id={}, inst_id={}, action={:?}",
+                                rule.name(),
+                                key.0,
+                                key.1,
+                                action
+                            );
+                        }
+                        action
+                    })
+                    .collect::<Vec<_>>();
+                let actions = resolve_exit_actions(st.snapshot.pos_side, actions);
+                if !actions.is_empty() {
+                    plans.push((key.clone(), st.snapshot.clone(), actions));
+                }
+            }
+            plans
+        };
+        for (key, snapshot, actions) in plans {
+            for action in actions {
+                if let Some(mv) = self.execute(&key, &snapshot, action).await {
+                    moves.push(mv);
+                }
+            }
+        }
+        moves
+    }
+    /// 执行单个动作，成功后回写规则状态
+    async fn execute(
+        &self,
+        key: &(i64, String),
+        snapshot: &PositionSnapshot,
+        action: ExitRuleAction,
+    ) -> Option<StopLossMove> {
+        match action {
+            ExitRuleAction::MoveStopLoss { price, reason } => {
+                let ord_id = match snapshot.ord_id.as_deref() {
+                    Some(v) if !v.is_empty() => v,
+                    _ => {
+                        warn!(
+                            "缺少 ord_id，无法移动止损: strategy_config_id={}, inst_id={}",
+                            key.0, key.1
+                        );
+                        return None;
+                    }
+                };
+                info!(
+                    "{}: strategy_config_id={}, inst_id={}, new_sl={}",
+                    reason, key.0, key.1, price
+                );
+                match self
+                    .amender
+                    .move_stop_loss_to_price(&key.1, ord_id, price)
+                    .await
+                {
+                    Ok(_) => {
+                        let mut guard = self.inner.write().await;
+                        if let Some(st) = guard.positions.get_mut(key) {
+                            st.current_stop = Some(price);
+                        }
+                        Some(StopLossMove {
+                            strategy_config_id: key.0,
+                            inst_id: key.1.clone(),
+                            price,
+                        })
+                    }
+                    Err(e) => {
+                        warn!(
+                            "移动止损失败(稍后会在后续K线重试): strategy_config_id={}, inst_id={}, err={}",
+                            key.0, key.1, e
+                        );
+                        None
+                    }
+                }
+            }
+            ExitRuleAction::TakeProfit {
+                step,
+                close_ratio,
+                reason,
+            } => {
+                let filter = self.order_size_filter(key).await?;
+                let size = match Self::ladder_close_size(snapshot.size, close_ratio, filter) {
+                    Some(size) => size,
+                    None => {
+                        info!(
+                            "{}(第{}档)平仓数量低于最小下单数量，跳过该档: strategy_config_id={}, inst_id={}, size={}",
+                            reason,
+                            step + 1,
+                            key.0,
+                            key.1,
+                            snapshot.size * close_ratio
+                        );
+                        let mut guard = self.inner.write().await;
+                        if let Some(st) = guard.positions.get_mut(key) {
+                            st.ladder_filled = step + 1;
+                        }
+                        return None;
+                    }
+                };
+                let closes_all = match filter {
+                    Some(filter) => filter.floor(snapshot.size - size).is_none(),
+                    None => size >= snapshot.size,
+                };
+                info!(
+                    "{}(第{}档): strategy_config_id={}, inst_id={}, size={}",
+                    reason,
+                    step + 1,
+                    key.0,
+                    key.1,
+                    size
+                );
+                match self
+                    .amender
+                    .close_position(&key.1, snapshot.pos_side, size)
+                    .await
+                {
+                    Ok(_) => {
+                        let mut guard = self.inner.write().await;
+                        if let Some(st) = guard.positions.get_mut(key) {
+                            st.ladder_filled = step + 1;
+                            st.snapshot.size = (st.snapshot.size - size).max(0.0);
+                            st.closing = closes_all;
+                        }
+                    }
+                    Err(e) => warn!(
+                        "分批止盈失败(稍后会在后续K线重试): strategy_config_id={}, inst_id={}, err={}",
+                        key.0, key.1, e
+                    ),
+                }
+                None
+            }
+            ExitRuleAction::ClosePosition { reason } => {
+                let filter = self.order_size_filter(key).await?;
+                let size = match filter {
+                    Some(filter) => match filter.floor(snapshot.size) {
+                        Some(size) => size,
+                        None => {
+                            warn!(
+                                "{}: 剩余持仓低于最小下单数量，无法平仓: strategy_config_id={}, inst_id={}, size={}, min_sz={}",
+                                reason, key.0, key.1, snapshot.size, filter.min_size
+                            );
+                            return None;
+                        }
+                    },
+                    None => snapshot.size,
+                };
+                info!(
+                    "{}: strategy_config_id={}, inst_id={}, size={}",
+                    reason, key.0, key.1, size
+                );
+                match self
+                    .amender
+                    .close_position(&key.1, snapshot.pos_side, size)
+                    .await
+                {
+                    Ok(_) => {
+                        let mut guard = self.inner.write().await;
+                        if let Some(st) = guard.positions.get_mut(key) {
+                            st.closing = true;
+                        }
+                    }
+                    Err(e) => warn!(
+                        "实时风控平仓失败(稍后会在后续K线重试): strategy_config_id={}, inst_id={}, err={}",
+                        key.0, key.1, e
+                    ),
+                }
+                None
+            }
+        }
+    }
+    /// 查询下单数量约束；失败时保留状态，在后续K线重试
+    async fn order_size_filter(&self, key: &(i64, String)) -> Option<Option<OrderSizeFilter>> {
+        match self.amender.order_size_filter(&key.1).await {
+            Ok(filter) => Some(filter),
+            Err(e) => {
+                warn!(
+                    "查询下单数量约束失败(稍后会在后续K线重试): strategy_config_id={}, inst_id={}, err={}",
+                    key.0, key.1, e
+                );
+                None
+            }
+        }
+    }
+    /// 计算分批止盈平仓数量：按 lotSz 向下取整，低于 minSz 时返回 None（跳过该档）；
+    /// 平仓后剩余数量不足 minSz 时一并平掉，避免留下无法平仓的尾仓。
+    fn ladder_close_size(
+        position_size: f64,
+        close_ratio: f64,
+        filter: Option<OrderSizeFilter>,
+    ) -> Option<f64> {
+        let raw = position_size * close_ratio;
+        let Some(filter) = filter else {
+            return Some(raw);
+        };
+        let size = filter.floor(raw)?;
+        let rest = position_size - size;
+        if rest > 0.0 && filter.floor(rest).is_none() {
+            return Some(filter.floor(position_size).unwrap_or(size));
+        }
+        Some(size)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_quant_domain::entities::{
+        AtrTrailingStopRuleConfig, RealtimeRiskRulesConfig, TakeProfitLadderStep,
+        TimeExitRuleConfig,
+    };
+    use std::sync::Mutex;
+    #[derive(Default)]
+    struct RecordingAmender {
+        calls: Mutex<Vec<String>>,
+        size_filter: Option<OrderSizeFilter>,
+    }
+    #[async_trait::async_trait]
+    impl StopLossAmender for RecordingAmender {
+        async fn move_stop_loss_to_price(
+            &self,
+            inst_id: &str,
+            ord_id: &str,
+            new_sl_trigger_px: f64,
+        ) -> anyhow::Result<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("move:{inst_id}:{ord_id}:{new_sl_trigger_px}"));
+            Ok(())
+        }
+        async fn close_position(
+            &self,
+            inst_id: &str,
+            pos_side: PositionSide,
+            size: f64,
+        ) -> anyhow::Result<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("close:{inst_id}:{pos_side:?}:{size}"));
+            Ok(())
+        }
+        async fn order_size_filter(
+            &self,
+            _inst_id: &str,
+        ) -> anyhow::Result<Option<OrderSizeFilter>> {
+            Ok(self.size_filter)
+        }
+    }
+    fn market_candle(ts: i64, h: f64, l: f64, c: f64) -> MarketCandle {
+        MarketCandle {
+            inst_id: "BTC-USDT-SWAP".to_string(),
+            candle: CandleItem {
+                o: c,
+                h,
+                l,
+                c,
+                v: 1.0,
+                ts,
+                confirm: 1,
+            },
+        }
+    }
+    fn long_position() -> PositionSnapshot {
+        PositionSnapshot {
+            strategy_config_id: 7,
+            inst_id: "BTC-USDT-SWAP".to_string(),
+            pos_side: PositionSide::Long,
+            entry_price: 100.0,
+            size: 10.0,
+            initial_stop_loss: Some(95.0),
+            ord_id: Some("ord-1".to_string()),
+            is_open: true,
+        }
+    }
+    fn risk_with(rules: RealtimeRiskRulesConfig) -> BasicRiskConfig {
+        BasicRiskConfig {
+            realtime_rules: Some(rules),
+            ..BasicRiskConfig::default()
+        }
+    }
+    #[tokio::test]
+    async fn ladder_and_trailing_stop_amend_through_amender() {
+        let amender = Arc::new(RecordingAmender::default());
+        let service = ExitRuleService::new(amender.clone());
+        let risk = risk_with(RealtimeRiskRulesConfig {
+            atr_trailing_stop: Some(AtrTrailingStopRuleConfig {
+                atr_period: 1,
+                multiplier: 1.0,
+                activation_r: 1.0,
+            }),
+            take_profit_ladder: vec![TakeProfitLadderStep {
+                r_multiple: 1.0,
+                close_ratio: 0.5,
+            }],
+            ..RealtimeRiskRulesConfig::default()
+        });
+        service.upsert_position(long_position(), risk).await;
+        let moves = service
+            .on_candle(market_candle(1, 107.0, 104.0, 106.0))
+            .await;
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].price, 104.0);
+        assert_eq!(
+            amender.calls.lock().unwrap().clone(),
+            vec![
+                "close:BTC-USDT-SWAP:Long:5".to_string(),
+                "move:BTC-USDT-SWAP:ord-1:104".to_string(),
+            ]
+        );
+        // 重复推送同一根确认K线不会重复执行
+        assert!(service
+            .on_candle(market_candle(1, 107.0, 104.0, 106.0))
+            .await
+            .is_empty());
+        assert_eq!(amender.calls.lock().unwrap().len(), 2);
+    }
+    #[tokio::test]
+    async fn time_exit_closes_once_and_ignores_unconfirmed_candles() {
+        let amender = Arc::new(RecordingAmender::default());
+        let service = ExitRuleService::new(amender.clone());
+        let risk = risk_with(RealtimeRiskRulesConfig {
+            time_exit: Some(TimeExitRuleConfig {
+                max_bars: 2,
+                min_profit_r: None,
+            }),
+            ..RealtimeRiskRulesConfig::default()
+        });
+        service.upsert_position(long_position(), risk).await;
+        let mut unconfirmed = market_candle(1, 101.0, 99.0, 100.0);
+        unconfirmed.candle.confirm = 0;
+        service.on_candle(unconfirmed).await;
+        service
+            .on_candle(market_candle(1, 101.0, 99.0, 100.0))
+            .await;
+        assert!(amender.calls.lock().unwrap().is_empty());
+        service
+            .on_candle(market_candle(2, 101.0, 99.0, 100.0))
+            .await;
+        service
+            .on_candle(market_candle(3, 101.0, 99.0, 100.0))
+            .await;
+        assert_eq!(
+            amender.calls.lock().unwrap().clone(),
+            vec!["close:BTC-USDT-SWAP:Long:10".to_string()]
+        );
+    }
+    #[tokio::test]
+    async fn positions_without_realtime_rules_are_untouched() {
+        let amender = Arc::new(RecordingAmender::default());
+        let service = ExitRuleService::new(amender.clone());
+        service
+            .upsert_position(long_position(), BasicRiskConfig::default())
+            .await;
+        service
+            .on_candle(market_candle(1, 130.0, 120.0, 125.0))
+            .await;
+        assert!(amender.calls.lock().unwrap().is_empty());
+    }
+    #[tokio::test]
+    async fn ladder_rounds_down_to_lot_size_and_skips_steps_below_min_size() {
+        let amender = Arc::new(RecordingAmender {
+            size_filter: Some(OrderSizeFilter {
+                lot_size: 1.0,
+                min_size: 1.0,
+            }),
+            ..RecordingAmender::default()
+        });
+        let service = ExitRuleService::new(amender.clone());
+        let risk = risk_with(RealtimeRiskRulesConfig {
+            take_profit_ladder: vec![
+                TakeProfitLadderStep {
+                    r_multiple: 1.0,
+                    close_ratio: 0.25,
+                },
+                TakeProfitLadderStep {
+                    r_multiple: 2.0,
+                    close_ratio: 0.5,
+                },
+            ],
+            ..RealtimeRiskRulesConfig::default()
+        });
+        let mut position = long_position();
+        position.size = 3.0;
+        service.upsert_position(position, risk).await;
+        // 3 * 0.25 = 0.75 < minSz，跳过第一档
+        service
+            .on_candle(market_candle(1, 106.0, 101.0, 105.0))
+            .await;
+        assert!(amender.calls.lock().unwrap().is_empty());
+        // 3 * 0.5 = 1.5，按 lotSz 向下取整为 1
+        service
+            .on_candle(market_candle(2, 111.0, 106.0, 110.0))
+            .await;
+        assert_eq!(
+            amender.calls.lock().unwrap().clone(),
+            vec!["close:BTC-USDT-SWAP:Long:1".to_string()]
+        );
+    }
+    #[test]
+    fn ladder_close_size_closes_remainder_below_min_size() {
+        let filter = Some(OrderSizeFilter {
+            lot_size: 0.1,
+            min_size: 1.0,
+        });
+        assert_eq!(
+            ExitRuleService::<RecordingAmender>::ladder_close_size(1.5, 0.5, filter),
+            None
+        );
+        assert_eq!(
+            ExitRuleService::<RecordingAmender>::ladder_close_size(2.5, 0.5, filter),
+            Some(1.2)
+        );
+        assert_eq!(
+            ExitRuleService::<RecordingAmender>::ladder_close_size(1.8, 0.6, filter),
+            Some(1.8)
+        );
+        assert_eq!(
+            ExitRuleService::<RecordingAmender>::ladder_close_size(3.0, 0.5, None),
+            Some(1.5)
+        );
+    }
+}
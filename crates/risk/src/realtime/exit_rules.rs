@@ -0,0 +1,466 @@
+//! 实时退出规则：ATR 跟踪止损、吊灯止损、时间退出、分批止盈阶梯
+//!
+//! 规则只根据确认K线与持仓状态给出动作，改单/平仓由 `ExitRuleService` 通过 `StopLossAmender` 执行。
+//! 规则口径与回测 `risk.rs` 对齐：R = |开仓价 - 初始止损|，止损只朝有利方向移动，分批止盈按剩余仓位比例平仓。
+use rust_quant_common::CandleItem;
+use rust_quant_domain::entities::{
+    AtrTrailingStopRuleConfig, ChandelierExitRuleConfig, RealtimeRiskRulesConfig,
+    TakeProfitLadderStep, TimeExitRuleConfig,
+};
+use rust_quant_domain::enums::PositionSide;
+/// 规则给出的风控动作
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExitRuleAction {
+    /// 移动止损到指定触发价
+    MoveStopLoss { price: f64, reason: &'static str },
+    /// 分批止盈：按当前剩余仓位比例平仓，`step` 为阶梯序号
+    TakeProfit {
+        step: usize,
+        close_ratio: f64,
+        reason: &'static str,
+    },
+    /// 全部平仓
+    ClosePosition { reason: &'static str },
+}
+/// 规则评估上下文（单个持仓、单根确认K线）
+#[derive(Debug, Clone)]
+pub struct ExitRuleContext<'a> {
+    /// 持仓方向（仅 Long/Short）
+    pub side: PositionSide,
+    /// 开仓均价
+    pub entry_price: f64,
+    /// 1R 对应的价格距离
+    pub risk_per_unit: f64,
+    /// 确认K线历史（时间升序，最后一根为当前K线）
+    pub candles: &'a [CandleItem],
+    /// 开仓后已经过的确认K线数
+    pub bars_held: usize,
+    /// 持仓期最有利价格（多头最高价 / 空头最低价）
+    pub favorable_extreme: f64,
+    /// 当前止损触发价
+    pub current_stop: Option<f64>,
+    /// 已完成的分批止盈档数
+    pub ladder_filled: usize,
+}
+impl ExitRuleContext<'_> {
+    /// 当前K线
+    pub fn current(&self) -> Option<&CandleItem> {
+        self.candles.last()
+    }
+    /// 指定价格对应的浮盈 R 倍数
+    pub fn profit_r(&self, price: f64) -> f64 {
+        if self.risk_per_unit <= 0.0 {
+            return 0.0;
+        }
+        match self.side {
+            PositionSide::Long => (price - self.entry_price) / self.risk_per_unit,
+            PositionSide::Short => (self.entry_price - price) / self.risk_per_unit,
+            PositionSide::Both => 0.0,
+        }
+    }
+    /// 候选止损是否比当前止损更有利，且仍在当前收盘价的保护侧（否则交易所会拒绝或立即触发）
+    pub fn is_valid_stop_improvement(&self, candidate: f64) -> bool {
+        let Some(current) = self.current() else {
+            return false;
+        };
+        if !candidate.is_finite() || candidate <= 0.0 {
+            return false;
+        }
+        match self.side {
+            PositionSide::Long => {
+                candidate < current.c && self.current_stop.map_or(true, |stop| candidate > stop)
+            }
+            PositionSide::Short => {
+                candidate > current.c && self.current_stop.map_or(true, |stop| candidate < stop)
+            }
+            PositionSide::Both => false,
+        }
+    }
+}
+/// 可插拔的实时退出规则
+pub trait RealtimeExitRule: Send + Sync {
+    /// 规则名称（日志使用）
+    fn name(&self) -> &'static str;
+    /// 评估当前K线，返回需要执行的动作
+    fn evaluate(&self, ctx: &ExitRuleContext<'_>) -> Option<ExitRuleAction>;
+}
+/// 按 `BasicRiskConfig.realtime_rules` 构建启用的规则
+pub fn build_exit_rules(config: &RealtimeRiskRulesConfig) -> Vec<Box<dyn RealtimeExitRule>> {
+    let mut rules: Vec<Box<dyn RealtimeExitRule>> = Vec::new();
+    if let Some(cfg) = &config.time_exit {
+        rules.push(Box::new(TimeExitRule::new(cfg.clone())));
+    }
+    if !config.take_profit_ladder.is_empty() {
+        rules.push(Box::new(TakeProfitLadderRule::new(
+            config.take_profit_ladder.clone(),
+        )));
+    }
+    if let Some(cfg) = &config.atr_trailing_stop {
+        rules.push(Box::new(AtrTrailingStopRule::new(cfg.clone())));
+    }
+    if let Some(cfg) = &config.chandelier_exit {
+        rules.push(Box::new(ChandelierExitRule::new(cfg.clone())));
+    }
+    rules
+}
+/// 合并多条规则的动作：全部平仓优先；否则最多一档分批止盈 + 最有利的一次止损移动
+pub fn resolve_exit_actions(
+    side: PositionSide,
+    actions: Vec<ExitRuleAction>,
+) -> Vec<ExitRuleAction> {
+    if let Some(close) = actions
+        .iter()
+        .find(|action| matches!(action, ExitRuleAction::ClosePosition { .. }))
+    {
+        return vec![close.clone()];
+    }
+    let mut resolved = Vec::new();
+    if let Some(take_profit) = actions
+        .iter()
+        .find(|action| matches!(action, ExitRuleAction::TakeProfit { .. }))
+    {
+        resolved.push(take_profit.clone());
+    }
+    let best_stop = actions
+        .into_iter()
+        .filter_map(|action| match action {
+            ExitRuleAction::MoveStopLoss { price, reason } => Some((price, reason)),
+            _ => None,
+        })
+        .reduce(|best, candidate| {
+            let better = match side {
+                PositionSide::Short => candidate.0 < best.0,
+                _ => candidate.0 > best.0,
+            };
+            if better {
+                candidate
+            } else {
+                best
+            }
+        });
+    if let Some((price, reason)) = best_stop {
+        resolved.push(ExitRuleAction::MoveStopLoss { price, reason });
+    }
+    resolved
+}
+/// 计算 1R 价格距离；缺少初始止损时退化用 `max_loss_percent` 推算（与保本规则一致）
+pub fn initial_risk_per_unit(
+    side: PositionSide,
+    entry_price: f64,
+    initial_stop_loss: Option<f64>,
+    max_loss_percent: f64,
+) -> f64 {
+    let fallback_sl = match side {
+        PositionSide::Long => entry_price * (1.0 - max_loss_percent),
+        PositionSide::Short => entry_price * (1.0 + max_loss_percent),
+        PositionSide::Both => entry_price,
+    };
+    (entry_price - initial_stop_loss.unwrap_or(fallback_sl)).abs()
+}
+/// 平均真实波幅（最近 `period` 根K线真实波幅的简单平均）
+pub fn average_true_range(candles: &[CandleItem], period: usize) -> Option<f64> {
+    if period == 0 || candles.len() < period {
+        return None;
+    }
+    let start = candles.len() - period;
+    let sum: f64 = (start..candles.len())
+        .map(|i| {
+            let candle = &candles[i];
+            let range = candle.h - candle.l;
+            match i.checked_sub(1).map(|prev| candles[prev].c) {
+                Some(prev_close) => range
+                    .max((candle.h - prev_close).abs())
+                    .max((candle.l - prev_close).abs()),
+                None => range,
+            }
+        })
+        .sum();
+    Some(sum / period as f64)
+}
+/// ATR 跟踪止损
+pub struct AtrTrailingStopRule {
+    config: AtrTrailingStopRuleConfig,
+}
+impl AtrTrailingStopRule {
+    pub fn new(config: AtrTrailingStopRuleConfig) -> Self {
+        Self { config }
+    }
+}
+impl RealtimeExitRule for AtrTrailingStopRule {
+    fn name(&self) -> &'static str {
+        "atr_trailing_stop"
+    }
+    fn evaluate(&self, ctx: &ExitRuleContext<'_>) -> Option<ExitRuleAction> {
+        if ctx.profit_r(ctx.favorable_extreme) < self.config.activation_r {
+            return None;
+        }
+        let atr = average_true_range(ctx.candles, self.config.atr_period)?;
+        let offset = atr * self.config.multiplier;
+        let price = match ctx.side {
+            PositionSide::Long => ctx.favorable_extreme - offset,
+            PositionSide::Short => ctx.favorable_extreme + offset,
+            PositionSide::Both => return None,
+        };
+        ctx.is_valid_stop_improvement(price)
+            .then_some(ExitRuleAction::MoveStopLoss {
+                price,
+                reason: "ATR跟踪止损",
+            })
+    }
+}
+/// 吊灯止损
+pub struct ChandelierExitRule {
+    config: ChandelierExitRuleConfig,
+}
+impl ChandelierExitRule {
+    pub fn new(config: ChandelierExitRuleConfig) -> Self {
+        Self { config }
+    }
+}
+impl RealtimeExitRule for ChandelierExitRule {
+    fn name(&self) -> &'static str {
+        "chandelier_exit"
+    }
+    fn evaluate(&self, ctx: &ExitRuleContext<'_>) -> Option<ExitRuleAction> {
+        if self.config.lookback == 0 || ctx.candles.len() < self.config.lookback {
+            return None;
+        }
+        let atr = average_true_range(ctx.candles, self.config.atr_period)?;
+        let window = &ctx.candles[ctx.candles.len() - self.config.lookback..];
+        let offset = atr * self.config.multiplier;
+        let price = match ctx.side {
+            PositionSide::Long => window.iter().map(|c| c.h).fold(f64::MIN, f64::max) - offset,
+            PositionSide::Short => window.iter().map(|c| c.l).fold(f64::MAX, f64::min) + offset,
+            PositionSide::Both => return None,
+        };
+        ctx.is_valid_stop_improvement(price)
+            .then_some(ExitRuleAction::MoveStopLoss {
+                price,
+                reason: "吊灯止损",
+            })
+    }
+}
+/// 时间退出
+pub struct TimeExitRule {
+    config: TimeExitRuleConfig,
+}
+impl TimeExitRule {
+    pub fn new(config: TimeExitRuleConfig) -> Self {
+        Self { config }
+    }
+}
+impl RealtimeExitRule for TimeExitRule {
+    fn name(&self) -> &'static str {
+        "time_exit"
+    }
+    fn evaluate(&self, ctx: &ExitRuleContext<'_>) -> Option<ExitRuleAction> {
+        if self.config.max_bars == 0 || ctx.bars_held < self.config.max_bars {
+            return None;
+        }
+        let close = ctx.current()?.c;
+        let exempt = self
+            .config
+            .min_profit_r
+            .is_some_and(|min_profit_r| ctx.profit_r(close) >= min_profit_r);
+        (!exempt).then_some(ExitRuleAction::ClosePosition {
+            reason: "持仓超时退出",
+        })
+    }
+}
+/// 分批止盈阶梯
+pub struct TakeProfitLadderRule {
+    steps: Vec<TakeProfitLadderStep>,
+}
+impl TakeProfitLadderRule {
+    /// 阶梯按 `r_multiple` 升序排列，过滤无效档位
+    pub fn new(mut steps: Vec<TakeProfitLadderStep>) -> Self {
+        steps.retain(|step| {
+            step.r_multiple.is_finite()
+                && step.r_multiple > 0.0
+                && step.close_ratio.is_finite()
+                && step.close_ratio > 0.0
+        });
+        steps.sort_by(|a, b| a.r_multiple.total_cmp(&b.r_multiple));
+        Self { steps }
+    }
+}
+impl RealtimeExitRule for TakeProfitLadderRule {
+    fn name(&self) -> &'static str {
+        "take_profit_ladder"
+    }
+    fn evaluate(&self, ctx: &ExitRuleContext<'_>) -> Option<ExitRuleAction> {
+        let step = self.steps.get(ctx.ladder_filled)?;
+        let candle = ctx.current()?;
+        let favorable = match ctx.side {
+            PositionSide::Long => candle.h,
+            PositionSide::Short => candle.l,
+            PositionSide::Both => return None,
+        };
+        (ctx.profit_r(favorable) >= step.r_multiple).then_some(ExitRuleAction::TakeProfit {
+            step: ctx.ladder_filled,
+            close_ratio: step.close_ratio.min(1.0),
+            reason: "分批止盈",
+        })
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn candle(ts: i64, h: f64, l: f64, c: f64) -> CandleItem {
+        CandleItem {
+            o: c,
+            h,
+            l,
+            c,
+            v: 1.0,
+            ts,
+            confirm: 1,
+        }
+    }
+    fn long_ctx(candles: &[CandleItem], bars_held: usize) -> ExitRuleContext<'_> {
+        ExitRuleContext {
+            side: PositionSide::Long,
+            entry_price: 100.0,
+            risk_per_unit: 5.0,
+            candles,
+            bars_held,
+            favorable_extreme: candles.iter().map(|c| c.h).fold(f64::MIN, f64::max),
+            current_stop: Some(95.0),
+            ladder_filled: 0,
+        }
+    }
+    #[test]
+    fn atr_uses_previous_close_for_true_range() {
+        let candles = vec![
+            candle(1, 101.0, 99.0, 100.0),
+            candle(2, 106.0, 104.0, 105.0),
+        ];
+        // 第二根真实波幅 = max(2, |106-100|, |104-100|) = 6
+        assert_eq!(average_true_range(&candles, 2), Some(4.0));
+        assert_eq!(average_true_range(&candles, 3), None);
+    }
+    #[test]
+    fn atr_trailing_waits_for_activation_and_only_ratchets() {
+        let rule = AtrTrailingStopRule::new(AtrTrailingStopRuleConfig {
+            atr_period: 2,
+            multiplier: 1.0,
+            activation_r: 1.0,
+        });
+        let flat = vec![
+            candle(1, 102.0, 100.0, 101.0),
+            candle(2, 103.0, 101.0, 102.0),
+        ];
+        assert!(rule.evaluate(&long_ctx(&flat, 2)).is_none());
+        let rally = vec![
+            candle(1, 104.0, 102.0, 103.0),
+            candle(2, 112.0, 108.0, 111.0),
+        ];
+        match rule.evaluate(&long_ctx(&rally, 2)) {
+            Some(ExitRuleAction::MoveStopLoss { price, .. }) => {
+                assert!((price - (112.0 - 5.5)).abs() < 1e-9)
+            }
+            other => panic!("unexpected action: {other:?}"),
+        }
+        let mut ctx = long_ctx(&rally, 2);
+        ctx.current_stop = Some(108.0);
+        assert!(rule.evaluate(&ctx).is_none());
+    }
+    #[test]
+    fn chandelier_uses_lookback_extreme() {
+        let rule = ChandelierExitRule::new(ChandelierExitRuleConfig {
+            lookback: 3,
+            atr_period: 2,
+            multiplier: 2.0,
+        });
+        let candles = vec![
+            candle(1, 120.0, 100.0, 110.0),
+            candle(2, 118.0, 116.0, 117.0),
+            candle(3, 119.0, 117.0, 118.0),
+        ];
+        match rule.evaluate(&long_ctx(&candles, 3)) {
+            Some(ExitRuleAction::MoveStopLoss { price, .. }) => {
+                // ATR(2) = (8 + 2) / 2 = 5
+                assert!((price - (120.0 - 2.0 * 5.0)).abs() < 1e-9)
+            }
+            other => panic!("unexpected action: {other:?}"),
+        }
+    }
+    #[test]
+    fn time_exit_respects_min_profit_exemption() {
+        let candles = vec![candle(1, 104.0, 102.0, 103.0)];
+        let rule = TimeExitRule::new(TimeExitRuleConfig {
+            max_bars: 3,
+            min_profit_r: Some(1.0),
+        });
+        assert!(rule.evaluate(&long_ctx(&candles, 2)).is_none());
+        assert!(matches!(
+            rule.evaluate(&long_ctx(&candles, 3)),
+            Some(ExitRuleAction::ClosePosition { .. })
+        ));
+        let winning = vec![candle(1, 107.0, 105.0, 106.0)];
+        assert!(rule.evaluate(&long_ctx(&winning, 3)).is_none());
+    }
+    #[test]
+    fn take_profit_ladder_fires_next_unfilled_step() {
+        let rule = TakeProfitLadderRule::new(vec![
+            TakeProfitLadderStep {
+                r_multiple: 2.0,
+                close_ratio: 0.5,
+            },
+            TakeProfitLadderStep {
+                r_multiple: 1.0,
+                close_ratio: 0.3,
+            },
+        ]);
+        let candles = vec![candle(1, 111.0, 104.0, 110.0)];
+        let mut ctx = long_ctx(&candles, 1);
+        assert_eq!(
+            rule.evaluate(&ctx),
+            Some(ExitRuleAction::TakeProfit {
+                step: 0,
+                close_ratio: 0.3,
+                reason: "分批止盈",
+            })
+        );
+        ctx.ladder_filled = 1;
+        assert!(matches!(
+            rule.evaluate(&ctx),
+            Some(ExitRuleAction::TakeProfit { step: 1, .. })
+        ));
+        ctx.ladder_filled = 2;
+        assert!(rule.evaluate(&ctx).is_none());
+    }
+    #[test]
+    fn resolve_prefers_full_close_then_best_stop() {
+        let actions = vec![
+            ExitRuleAction::MoveStopLoss {
+                price: 101.0,
+                reason: "a",
+            },
+            ExitRuleAction::MoveStopLoss {
+                price: 103.0,
+                reason: "b",
+            },
+        ];
+        assert_eq!(
+            resolve_exit_actions(PositionSide::Long, actions.clone()),
+            vec![ExitRuleAction::MoveStopLoss {
+                price: 103.0,
+                reason: "b",
+            }]
+        );
+        assert_eq!(
+            resolve_exit_actions(PositionSide::Short, actions.clone())[0],
+            ExitRuleAction::MoveStopLoss {
+                price: 101.0,
+                reason: "a",
+            }
+        );
+        let mut with_close = actions;
+        with_close.push(ExitRuleAction::ClosePosition { reason: "t" });
+        assert_eq!(
+            resolve_exit_actions(PositionSide::Long, with_close),
+            vec![ExitRuleAction::ClosePosition { reason: "t" }]
+        );
+    }
+}
@@ -3,16 +3,20 @@
 //! 实盘实时风控模块：
 //! - 监听K线/价格事件
 //! - 监听策略运行中的持仓事件
-//! - 触发风控动作（如：到达 1.5R 后移动止损到开仓价，保本）
+//! - 触发风控动作（如：到达 1.5R 后移动止损到开仓价，保本；ATR/吊灯跟踪止损、时间退出、分批止盈）
 //!
 //! 说明：
 //! - 本模块不直接依赖 services/execution 的具体实现，采用“事件输入 + 执行器注入”的方式。
 //! - 真实接入时，上层（runner / service / job）负责把K线与持仓变更事件推送进来。
 pub mod breakeven_stop_loss;
 pub mod engine;
+pub mod exit_rule_service;
+pub mod exit_rules;
 pub mod okx_stop_loss_amender;
 pub mod types;
 pub use breakeven_stop_loss::*;
 pub use engine::*;
+pub use exit_rule_service::*;
+pub use exit_rules::*;
 pub use okx_stop_loss_amender::*;
 pub use types::*;
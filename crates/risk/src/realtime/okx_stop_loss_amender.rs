@@ -1,3 +1,4 @@
+use super::OrderSizeFilter;
 use anyhow::anyhow;
 use okx::api::api_trait::OkxApiTrait;
 use okx::config::Credentials;
@@ -5,6 +6,7 @@ use okx::OkxClient;
 use okx::OkxTrade;
 use reqwest::Method;
 use rust_quant_domain::entities::ExchangeApiConfig;
+use rust_quant_domain::enums::PositionSide;
 use serde_json::json;
 use tracing::{info, warn};
 #[async_trait::async_trait]
@@ -15,6 +17,28 @@ pub trait StopLossAmender: Send + Sync {
         ord_id: &str,
         new_sl_trigger_px: f64,
     ) -> anyhow::Result<()>;
+    /// 减仓/平仓（分批止盈、时间退出），`size` 为平仓数量
+    ///
+    /// 默认不支持，只做止损改单的执行器无需实现。
+    async fn close_position(
+        &self,
+        inst_id: &str,
+        pos_side: PositionSide,
+        size: f64,
+    ) -> anyhow::Result<()> {
+        Err(anyhow!(
+            "当前执行器不支持平仓: inst_id={}, pos_side={:?}, size={}",
+            inst_id,
+            pos_side,
+            size
+        ))
+    }
+    /// 查询交易对的下单数量约束，平仓数量需按其向下取整
+    ///
+    /// 默认返回 None，表示不做取整。
+    async fn order_size_filter(&self, _inst_id: &str) -> anyhow::Result<Option<OrderSizeFilter>> {
+        Ok(None)
+    }
 }
 /// 使用 okx crate 的 `OkxClient::send_request` 直接调用 OKX `/api/v5/trade/amend-order`
 ///
@@ -208,6 +232,121 @@ impl StopLossAmender for OkxStopLossAmender {
         info!("OKX改单请求已提交: inst_id={}, ord_id={}", inst_id, ord_id);
         Ok(())
     }
+    /// 以只减仓市价单平仓；保证金模式沿用风控任务设置杠杆时的逐仓模式
+    async fn close_position(
+        &self,
+        inst_id: &str,
+        pos_side: PositionSide,
+        size: f64,
+    ) -> anyhow::Result<()> {
+        Self::ensure_legacy_direct_live_exchange_order_allowed()?;
+        let filter = self.order_size_filter(inst_id).await?;
+        let body = Self::reduce_only_close_body(inst_id, pos_side, size, filter.as_ref())?;
+        let body_str =
+            serde_json::to_string(&body).map_err(|e| anyhow!("序列化请求失败: {}", e))?;
+        info!(
+            "触发实时风控平仓: inst_id={}, pos_side={:?}, size={}",
+            inst_id, pos_side, size
+        );
+        let resp: serde_json::Value = self
+            .trade
+            .client()
+            .send_request(Method::POST, "/api/v5/trade/order", &body_str)
+            .await
+            .map_err(|e| anyhow!("OKX平仓失败: inst_id={}, err={}", inst_id, e))?;
+        if let Some(code) = resp
+            .get(0)
+            .and_then(|v| v.get("sCode"))
+            .and_then(|v| v.as_str())
+        {
+            if code != "0" {
+                return Err(anyhow!(
+                    "OKX平仓返回非0: inst_id={}, resp={}",
+                    inst_id,
+                    resp
+                ));
+            }
+        }
+        Ok(())
+    }
+    /// 查询 OKX 永续合约的 lotSz / minSz
+    async fn order_size_filter(&self, inst_id: &str) -> anyhow::Result<Option<OrderSizeFilter>> {
+        let path = format!(
+            "/api/v5/public/instruments?instType=SWAP&instId={}",
+            inst_id
+        );
+        let resp: serde_json::Value = self
+            .trade
+            .client()
+            .send_request(Method::GET, &path, "")
+            .await
+            .map_err(|e| anyhow!("OKX查询合约信息失败: inst_id={}, err={}", inst_id, e))?;
+        Self::parse_order_size_filter(inst_id, &resp).map(Some)
+    }
+}
+impl OkxStopLossAmender {
+    /// 从 instruments 返回中解析下单数量约束
+    fn parse_order_size_filter(
+        inst_id: &str,
+        resp: &serde_json::Value,
+    ) -> anyhow::Result<OrderSizeFilter> {
+        let instrument = resp
+            .get(0)
+            .ok_or_else(|| anyhow!("OKX合约信息为空: inst_id={}", inst_id))?;
+        let field = |name: &str| -> anyhow::Result<f64> {
+            instrument
+                .get(name)
+                .and_then(|v| v.as_str())
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| v.is_finite() && *v > 0.0)
+                .ok_or_else(|| anyhow!("OKX合约信息缺少{}: inst_id={}", name, inst_id))
+        };
+        Ok(OrderSizeFilter {
+            lot_size: field("lotSz")?,
+            min_size: field("minSz")?,
+        })
+    }
+    /// 构建只减仓市价平仓请求体，有数量约束时按 lotSz 向下取整
+    fn reduce_only_close_body(
+        inst_id: &str,
+        pos_side: PositionSide,
+        size: f64,
+        filter: Option<&OrderSizeFilter>,
+    ) -> anyhow::Result<serde_json::Value> {
+        if !size.is_finite() || size <= 0.0 {
+            return Err(anyhow!("平仓数量无效: inst_id={}, size={}", inst_id, size));
+        }
+        let sz = match filter {
+            Some(filter) => {
+                let floored = filter.floor(size).ok_or_else(|| {
+                    anyhow!(
+                        "平仓数量低于最小下单数量: inst_id={}, size={}, min_sz={}",
+                        inst_id,
+                        size,
+                        filter.min_size
+                    )
+                })?;
+                filter.format(floored)
+            }
+            None => format!("{}", size),
+        };
+        let (side, pos_side) = match pos_side {
+            PositionSide::Long => ("sell", "long"),
+            PositionSide::Short => ("buy", "short"),
+            PositionSide::Both => {
+                return Err(anyhow!("不支持双向持仓平仓: inst_id={}", inst_id));
+            }
+        };
+        Ok(json!({
+            "instId": inst_id,
+            "tdMode": "isolated",
+            "side": side,
+            "posSide": pos_side,
+            "ordType": "market",
+            "sz": sz,
+            "reduceOnly": true
+        }))
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -231,4 +370,46 @@ mod tests {
         ))
         .expect("exact legacy confirmation token should allow direct OKX stop-loss amend");
     }
+    #[test]
+    fn reduce_only_close_body_maps_position_side() {
+        let body = OkxStopLossAmender::reduce_only_close_body(
+            "BTC-USDT-SWAP",
+            PositionSide::Long,
+            1.5,
+            None,
+        )
+        .unwrap();
+        assert_eq!(body["side"], "sell");
+        assert_eq!(body["posSide"], "long");
+        assert_eq!(body["sz"], "1.5");
+        assert_eq!(body["reduceOnly"], true);
+        assert!(OkxStopLossAmender::reduce_only_close_body(
+            "BTC-USDT-SWAP",
+            PositionSide::Short,
+            0.0,
+            None
+        )
+        .is_err());
+    }
+    #[test]
+    fn reduce_only_close_body_rounds_size_down_to_lot_size() {
+        let resp = json!([{ "instId": "BTC-USDT-SWAP", "lotSz": "0.01", "minSz": "0.01" }]);
+        let filter = OkxStopLossAmender::parse_order_size_filter("BTC-USDT-SWAP", &resp).unwrap();
+        let body = OkxStopLossAmender::reduce_only_close_body(
+            "BTC-USDT-SWAP",
+            PositionSide::Short,
+            0.3333333,
+            Some(&filter),
+        )
+        .unwrap();
+        assert_eq!(body["side"], "buy");
+        assert_eq!(body["sz"], "0.33");
+        assert!(OkxStopLossAmender::reduce_only_close_body(
+            "BTC-USDT-SWAP",
+            PositionSide::Short,
+            0.005,
+            Some(&filter)
+        )
+        .is_err());
+    }
 }
@@ -81,3 +81,75 @@ pub struct PositionSnapshot {
     /// 是否处于打开状态。
     pub is_open: bool,
 }
+/// 已成功提交的止损移动（多条实时规则之间同步当前止损）
+#[derive(Debug, Clone, PartialEq)]
+pub struct StopLossMove {
+    /// 策略config ID。
+    pub strategy_config_id: i64,
+    /// 交易所合约或现货交易对标识。
+    pub inst_id: String,
+    /// 新止损触发价。
+    pub price: f64,
+}
+/// 交易所下单数量约束（OKX `lotSz` / `minSz`）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderSizeFilter {
+    /// 下单数量步长。
+    pub lot_size: f64,
+    /// 最小下单数量。
+    pub min_size: f64,
+}
+impl OrderSizeFilter {
+    /// 浮点误差容忍度，避免 0.3 / 0.1 之类的结果被向下多舍一档。
+    const EPSILON: f64 = 1e-9;
+    /// 按步长向下取整；结果低于最小下单数量时返回 None。
+    pub fn floor(&self, size: f64) -> Option<f64> {
+        if !size.is_finite() || size <= 0.0 {
+            return None;
+        }
+        let floored = if self.lot_size > 0.0 {
+            let steps = (size / self.lot_size + Self::EPSILON).floor();
+            let scale = 10f64.powi(self.lot_decimals() as i32);
+            (steps * self.lot_size * scale).round() / scale
+        } else {
+            size
+        };
+        if floored <= 0.0 || floored + Self::EPSILON < self.min_size {
+            return None;
+        }
+        Some(floored)
+    }
+    /// 按步长精度格式化下单数量。
+    pub fn format(&self, size: f64) -> String {
+        format!("{:.*}", self.lot_decimals(), size)
+    }
+    /// 步长的小数位数。
+    fn lot_decimals(&self) -> usize {
+        let text = self.lot_size.to_string();
+        text.split_once('.')
+            .map(|(_, frac)| frac.len())
+            .unwrap_or(0)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn order_size_filter_floors_to_lot_size() {
+        let filter = OrderSizeFilter {
+            lot_size: 0.1,
+            min_size: 0.2,
+        };
+        assert_eq!(filter.floor(3.33), Some(3.3));
+        assert_eq!(filter.floor(0.3), Some(0.3));
+        assert_eq!(filter.floor(0.19), None);
+        assert_eq!(filter.format(3.3), "3.3");
+        let contracts = OrderSizeFilter {
+            lot_size: 1.0,
+            min_size: 1.0,
+        };
+        assert_eq!(contracts.floor(2.5), Some(2.0));
+        assert_eq!(contracts.floor(0.5), None);
+        assert_eq!(contracts.format(2.0), "2");
+    }
+}
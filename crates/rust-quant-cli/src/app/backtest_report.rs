@@ -0,0 +1,353 @@
+//! 回测报告导出
+//!
+//! 从 quant_core 的 `back_test_log`、`back_test_detail` 和 `filtered_signal_log` 还原回测记录，
+//! 交给 `analytics::reporting` 生成 JSON/HTML 报告。CLI `backtest_report` 和 internal server
+//! `/api/internal/backtests/details?report=json|html` 共用这里的加载逻辑。
+use crate::app::env_parse::{first_non_empty_env, parse_u64_env};
+use anyhow::{anyhow, Context, Result};
+use rust_quant_analytics::reporting::{
+    build_backtest_report, render_backtest_report_html, BacktestReport, BacktestReportConfig,
+};
+use rust_quant_strategies::framework::backtest::types::FilteredSignal;
+use rust_quant_strategies::strategy_common::TradeRecord;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::path::{Path, PathBuf};
+
+/// 与 `BacktestService::save_backtest_log` 一致的期初资金。
+const BACKTEST_INITIAL_FUND: f64 = 100.0;
+pub const DEFAULT_BACKTEST_REPORT_MONTE_CARLO_ITERATIONS: usize = 1000;
+const DEFAULT_BACKTEST_REPORT_DIR: &str = "reports/backtests";
+
+#[derive(Debug, sqlx::FromRow)]
+struct BacktestLogRow {
+    strategy_type: String,
+    inst_type: String,
+    time: String,
+    final_fund: f64,
+    kline_start_time: i64,
+    kline_end_time: i64,
+}
+
+/// `back_test_detail` 中价格、数量和盈亏按历史口径存为字符串，读取后再解析。
+#[derive(Debug, sqlx::FromRow)]
+struct BacktestDetailRow {
+    option_type: String,
+    signal_open_position_time: Option<String>,
+    open_position_time: String,
+    close_position_time: String,
+    open_price: String,
+    close_price: Option<String>,
+    profit_loss: String,
+    quantity: String,
+    full_close: String,
+    close_type: String,
+    signal_status: i32,
+    win_nums: i32,
+    loss_nums: Option<i32>,
+    stop_loss_source: Option<String>,
+    initial_stop_price: Option<f64>,
+    initial_risk_amount: Option<f64>,
+    net_profit_r: Option<f64>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct FilteredSignalRow {
+    ts: i64,
+    inst_id: String,
+    direction: String,
+    signal_price: f64,
+    filter_reasons: Value,
+    indicator_snapshot: Option<String>,
+    theoretical_profit: f64,
+    theoretical_loss: f64,
+    final_pnl: f64,
+    trade_result: String,
+    signal_value: Option<String>,
+}
+
+/// CLI 导出结果。
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReportFiles {
+    pub back_test_id: i64,
+    pub html_path: PathBuf,
+    pub json_path: PathBuf,
+}
+
+/// 读取指定回测并生成报告；`back_test_log` 中不存在该 ID 时返回 `None`。
+pub async fn load_backtest_report(
+    pool: &PgPool,
+    back_test_id: i64,
+    monte_carlo_iterations: usize,
+) -> Result<Option<BacktestReport>> {
+    let Some(log) = sqlx::query_as::<_, BacktestLogRow>(
+        r#"
+        SELECT strategy_type, inst_type, time, final_fund, kline_start_time, kline_end_time
+        FROM back_test_log
+        WHERE id = $1
+        "#,
+    )
+    .bind(back_test_id)
+    .fetch_optional(pool)
+    .await
+    .context("load backtest log for report")?
+    else {
+        return Ok(None);
+    };
+    let details = sqlx::query_as::<_, BacktestDetailRow>(
+        r#"
+        SELECT
+            option_type,
+            to_char(signal_open_position_time, 'YYYY-MM-DD HH24:MI:SS') AS signal_open_position_time,
+            to_char(open_position_time, 'YYYY-MM-DD HH24:MI:SS') AS open_position_time,
+            to_char(close_position_time, 'YYYY-MM-DD HH24:MI:SS') AS close_position_time,
+            open_price,
+            close_price,
+            profit_loss,
+            quantity,
+            full_close,
+            close_type,
+            signal_status,
+            win_nums,
+            loss_nums,
+            stop_loss_source,
+            initial_stop_price,
+            initial_risk_amount,
+            net_profit_r
+        FROM back_test_detail
+        WHERE back_test_id = $1
+        ORDER BY id ASC
+        "#,
+    )
+    .bind(back_test_id)
+    .fetch_all(pool)
+    .await
+    .context("load backtest details for report")?;
+    let filtered_signals = sqlx::query_as::<_, FilteredSignalRow>(
+        r#"
+        SELECT
+            (EXTRACT(EPOCH FROM signal_time) * 1000)::BIGINT AS ts,
+            inst_id,
+            direction,
+            signal_price::DOUBLE PRECISION AS signal_price,
+            filter_reasons,
+            indicator_snapshot::TEXT AS indicator_snapshot,
+            COALESCE(theoretical_profit, 0)::DOUBLE PRECISION AS theoretical_profit,
+            COALESCE(theoretical_loss, 0)::DOUBLE PRECISION AS theoretical_loss,
+            COALESCE(final_pnl, 0)::DOUBLE PRECISION AS final_pnl,
+            COALESCE(trade_result, '') AS trade_result,
+            signal_value::TEXT AS signal_value
+        FROM filtered_signal_log
+        WHERE backtest_id = $1
+        ORDER BY signal_time ASC, id ASC
+        "#,
+    )
+    .bind(back_test_id)
+    .fetch_all(pool)
+    .await
+    .context("load filtered signals for report")?;
+    let trade_records = details
+        .into_iter()
+        .map(trade_record_from_row)
+        .collect::<Result<Vec<_>>>()?;
+    let filtered_signals: Vec<FilteredSignal> = filtered_signals
+        .into_iter()
+        .map(filtered_signal_from_row)
+        .collect();
+    let config = BacktestReportConfig::default()
+        .with_title(format!(
+            "#{back_test_id} {} {} {}",
+            log.strategy_type, log.inst_type, log.time
+        ))
+        .with_initial_fund(BACKTEST_INITIAL_FUND)
+        .with_final_fund(log.final_fund)
+        .with_time_range(log.kline_start_time, log.kline_end_time)
+        .with_monte_carlo_iterations(monte_carlo_iterations);
+    Ok(Some(build_backtest_report(
+        config,
+        &trade_records,
+        &filtered_signals,
+    )))
+}
+
+/// 按环境变量导出报告：`BACKTEST_REPORT_ID` 必填，`BACKTEST_REPORT_DIR` 为输出目录，
+/// `BACKTEST_REPORT_MC_ITERATIONS` 为蒙特卡洛次数。
+pub async fn run_backtest_report_from_env() -> Result<BacktestReportFiles> {
+    let back_test_id = first_non_empty_env(&["BACKTEST_REPORT_ID"])
+        .context("BACKTEST_REPORT_ID is required")?
+        .parse::<i64>()
+        .context("BACKTEST_REPORT_ID must be i64")?;
+    let output_dir = first_non_empty_env(&["BACKTEST_REPORT_DIR"])
+        .unwrap_or_else(|| DEFAULT_BACKTEST_REPORT_DIR.to_string());
+    let iterations = parse_u64_env(
+        "BACKTEST_REPORT_MC_ITERATIONS",
+        DEFAULT_BACKTEST_REPORT_MONTE_CARLO_ITERATIONS as u64,
+    )? as usize;
+    let database_url = first_non_empty_env(&[
+        "QUANT_CORE_DATABASE_URL",
+        "POSTGRES_QUANT_CORE_DATABASE_URL",
+    ])
+    .context("backtest_report requires QUANT_CORE_DATABASE_URL")?;
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&database_url)
+        .await
+        .context("connect quant_core for backtest report")?;
+    let report = load_backtest_report(&pool, back_test_id, iterations)
+        .await?
+        .ok_or_else(|| anyhow!("back_test_log not found: {back_test_id}"))?;
+    write_backtest_report_files(&report, back_test_id, Path::new(&output_dir))
+}
+
+/// 写出 `backtest_<id>.html` 与 `backtest_<id>.json`。
+pub fn write_backtest_report_files(
+    report: &BacktestReport,
+    back_test_id: i64,
+    output_dir: &Path,
+) -> Result<BacktestReportFiles> {
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("create report dir {}", output_dir.display()))?;
+    let html_path = output_dir.join(format!("backtest_{back_test_id}.html"));
+    let json_path = output_dir.join(format!("backtest_{back_test_id}.json"));
+    std::fs::write(&html_path, render_backtest_report_html(report))
+        .with_context(|| format!("write {}", html_path.display()))?;
+    std::fs::write(&json_path, serde_json::to_vec_pretty(report)?)
+        .with_context(|| format!("write {}", json_path.display()))?;
+    Ok(BacktestReportFiles {
+        back_test_id,
+        html_path,
+        json_path,
+    })
+}
+
+fn trade_record_from_row(row: BacktestDetailRow) -> Result<TradeRecord> {
+    Ok(TradeRecord {
+        option_type: row.option_type,
+        open_position_time: row.open_position_time,
+        signal_open_position_time: row.signal_open_position_time,
+        close_position_time: Some(row.close_position_time),
+        open_price: parse_decimal_text("open_price", &row.open_price)?,
+        signal_status: row.signal_status,
+        close_price: row
+            .close_price
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| parse_decimal_text("close_price", value))
+            .transpose()?,
+        profit_loss: parse_decimal_text("profit_loss", &row.profit_loss)?,
+        quantity: parse_decimal_text("quantity", &row.quantity)?,
+        full_close: row.full_close.trim().eq_ignore_ascii_case("true"),
+        close_type: row.close_type,
+        win_num: i64::from(row.win_nums),
+        loss_num: i64::from(row.loss_nums.unwrap_or_default()),
+        signal_value: None,
+        signal_result: None,
+        stop_loss_source: row.stop_loss_source,
+        stop_loss_update_history: None,
+        initial_stop_price: row.initial_stop_price,
+        initial_risk_amount: row.initial_risk_amount,
+        net_profit_r: row.net_profit_r,
+        // back_test_detail 未持久化资金费。
+        funding_fee: None,
+    })
+}
+
+fn filtered_signal_from_row(row: FilteredSignalRow) -> FilteredSignal {
+    let filter_reasons = match row.filter_reasons {
+        Value::Array(items) => items
+            .into_iter()
+            .map(|item| match item {
+                Value::String(reason) => reason,
+                other => other.to_string(),
+            })
+            .collect(),
+        Value::String(reason) => vec![reason],
+        _ => Vec::new(),
+    };
+    FilteredSignal {
+        ts: row.ts,
+        inst_id: row.inst_id,
+        direction: row.direction,
+        signal_price: row.signal_price,
+        filter_reasons,
+        indicator_snapshot: row.indicator_snapshot.unwrap_or_default(),
+        theoretical_profit: row.theoretical_profit,
+        theoretical_loss: row.theoretical_loss,
+        final_pnl: row.final_pnl,
+        trade_result: row.trade_result,
+        signal_value: row.signal_value,
+    }
+}
+
+fn parse_decimal_text(field: &str, value: &str) -> Result<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .with_context(|| format!("back_test_detail.{field} is not a number: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detail_row(close_type: &str, profit_loss: &str) -> BacktestDetailRow {
+        BacktestDetailRow {
+            option_type: "close".to_string(),
+            signal_open_position_time: None,
+            open_position_time: "2024-01-01 08:00:00".to_string(),
+            close_position_time: "2024-01-02 08:00:00".to_string(),
+            open_price: "100.5".to_string(),
+            close_price: Some(" ".to_string()),
+            profit_loss: profit_loss.to_string(),
+            quantity: "2".to_string(),
+            full_close: "true".to_string(),
+            close_type: close_type.to_string(),
+            signal_status: 1,
+            win_nums: 3,
+            loss_nums: None,
+            stop_loss_source: None,
+            initial_stop_price: Some(95.0),
+            initial_risk_amount: Some(11.0),
+            net_profit_r: Some(1.2),
+        }
+    }
+
+    #[test]
+    fn detail_rows_parse_text_columns_into_trade_records() {
+        let record = trade_record_from_row(detail_row("止盈", "-3.25")).unwrap();
+        assert_eq!(record.open_price, 100.5);
+        assert_eq!(record.close_price, None);
+        assert_eq!(record.profit_loss, -3.25);
+        assert!(record.full_close);
+        assert_eq!((record.win_num, record.loss_num), (3, 0));
+        assert_eq!(
+            record.close_position_time.as_deref(),
+            Some("2024-01-02 08:00:00")
+        );
+        assert!(trade_record_from_row(detail_row("止盈", "n/a")).is_err());
+    }
+
+    #[test]
+    fn filtered_signal_reasons_accept_json_array_or_string() {
+        let row = |filter_reasons| FilteredSignalRow {
+            ts: 1,
+            inst_id: "BTC-USDT-SWAP".to_string(),
+            direction: "long".to_string(),
+            signal_price: 100.0,
+            filter_reasons,
+            indicator_snapshot: None,
+            theoretical_profit: 0.0,
+            theoretical_loss: 0.0,
+            final_pnl: 1.0,
+            trade_result: "WIN".to_string(),
+            signal_value: None,
+        };
+        let signal = filtered_signal_from_row(row(serde_json::json!(["rsi", 3])));
+        assert_eq!(signal.filter_reasons, vec!["rsi", "3"]);
+        let signal = filtered_signal_from_row(row(serde_json::json!("volume")));
+        assert_eq!(signal.filter_reasons, vec!["volume"]);
+    }
+}
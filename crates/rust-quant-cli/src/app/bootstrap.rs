@@ -23,6 +23,7 @@ use rust_quant_orchestration::jobs::data::fund_monitor_job::FundMonitorJob;
 use rust_quant_orchestration::jobs::maintenance::{
     MaintenanceScheduler, MarketRankSnapshotPruneJob,
 };
+use rust_quant_orchestration::jobs::risk::scheduler::start_risk_job_scheduler;
 use rust_quant_orchestration::workflow::{
     backtest_runner, data_sync, external_market_sync_job::ExternalMarketSyncJob, funding_rate_job,
     tickets_job,
@@ -244,6 +245,7 @@ pub async fn run_modes() -> Result<()> {
             Ok(started_configs) if !started_configs.is_empty() => {
                 live_runtime_configs = started_configs;
                 live_runtime_services = Some((config_service, execution_service));
+                start_risk_job_scheduler();
             }
             Ok(_) => {
                 return Err(anyhow!(
@@ -0,0 +1,174 @@
+//! 列式 K线文件导入导出
+//!
+//! `export` 把 quant_core 的 `{inst_id}_candles_{period}` 分表导出为 `.rqc` 文件，
+//! 供 `CANDLE_SOURCE=file` 的回测在笔记本和 CI 上离线读取；`import` 把文件写回分表。
+use crate::app::env_parse::first_non_empty_env;
+use anyhow::{anyhow, Context, Result};
+use rust_quant_domain::Timeframe;
+use rust_quant_infrastructure::repositories::{FileCandleRepository, PostgresCandleRepository};
+use rust_quant_services::market::{
+    export_candles_to_file, import_candles_from_file, CandleTransferSummary,
+    DEFAULT_CANDLE_FILE_DIR,
+};
+use sqlx::postgres::PgPoolOptions;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// 导入导出方向。
+pub enum CandleStoreAction {
+    /// Postgres 分表 -> 列式文件。
+    Export,
+    /// 列式文件 -> Postgres 分表。
+    Import,
+}
+
+fn parse_candle_store_action(raw: Option<&str>) -> Result<CandleStoreAction> {
+    match raw
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("export") => Ok(CandleStoreAction::Export),
+        Some("import") => Ok(CandleStoreAction::Import),
+        Some(other) => Err(anyhow!(
+            "CANDLE_STORE_ACTION={} 无效，必须是 export 或 import",
+            other
+        )),
+        None => Err(anyhow!("必须设置 CANDLE_STORE_ACTION=export|import")),
+    }
+}
+
+/// 解析 `SYMBOL@TIMEFRAME` 逗号列表，与 `BACKTEST_ONLY_TARGETS` 格式一致。
+fn parse_candle_store_targets(raw: Option<&str>) -> Result<Vec<(String, Timeframe)>> {
+    let Some(raw) = raw.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(vec![]);
+    };
+    let mut targets = Vec::new();
+    for item in raw
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+    {
+        let Some((symbol, timeframe)) = item.split_once('@') else {
+            return Err(anyhow!(
+                "CANDLE_STORE_TARGETS={} 格式无效，必须使用 SYMBOL@TIMEFRAME",
+                item
+            ));
+        };
+        let symbol = symbol.trim();
+        if symbol.is_empty() {
+            return Err(anyhow!("CANDLE_STORE_TARGETS={} 缺少交易对", item));
+        }
+        let timeframe = Timeframe::from_str(timeframe.trim())
+            .map_err(|error| anyhow!("CANDLE_STORE_TARGETS={} 周期无效: {}", item, error))?;
+        if !targets.contains(&(symbol.to_string(), timeframe)) {
+            targets.push((symbol.to_string(), timeframe));
+        }
+    }
+    Ok(targets)
+}
+
+fn parse_optional_ms_env(key: &str) -> Result<Option<i64>> {
+    first_non_empty_env(&[key])
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .with_context(|| format!("{key} 必须是毫秒时间戳"))
+        })
+        .transpose()
+}
+
+/// 按环境变量执行一次导入或导出，返回每个序列的读写行数。
+///
+/// - `CANDLE_STORE_ACTION`: `export` 或 `import`
+/// - `CANDLE_STORE_TARGETS`: `SYMBOL@TIMEFRAME` 列表；`import` 留空时导入目录内全部文件
+/// - `CANDLE_STORE_DIR`: 文件目录，默认取 `CANDLE_FILE_DIR`，再默认 `data/candles`
+/// - `CANDLE_STORE_START_MS` / `CANDLE_STORE_END_MS`: 可选的闭区间时间范围
+pub async fn run_candle_store_from_env() -> Result<Vec<CandleTransferSummary>> {
+    let action =
+        parse_candle_store_action(first_non_empty_env(&["CANDLE_STORE_ACTION"]).as_deref())?;
+    let mut targets =
+        parse_candle_store_targets(first_non_empty_env(&["CANDLE_STORE_TARGETS"]).as_deref())?;
+    let dir = first_non_empty_env(&["CANDLE_STORE_DIR", "CANDLE_FILE_DIR"])
+        .unwrap_or_else(|| DEFAULT_CANDLE_FILE_DIR.to_string());
+    let start_time = parse_optional_ms_env("CANDLE_STORE_START_MS")?;
+    let end_time = parse_optional_ms_env("CANDLE_STORE_END_MS")?;
+    let files = FileCandleRepository::new(dir);
+    if targets.is_empty() {
+        if action == CandleStoreAction::Export {
+            return Err(anyhow!("导出时必须设置 CANDLE_STORE_TARGETS"));
+        }
+        targets = files
+            .list_series()
+            .await?
+            .into_iter()
+            .map(|series| (series.symbol, series.timeframe))
+            .collect();
+        if targets.is_empty() {
+            return Err(anyhow!(
+                "目录中没有列式 K线文件: {}",
+                files.root().display()
+            ));
+        }
+    }
+    let database_url = std::env::var("QUANT_CORE_DATABASE_URL")
+        .context("导入导出 K 线文件时必须设置 QUANT_CORE_DATABASE_URL")?;
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&database_url)
+        .await
+        .context("连接 quant_core Postgres 失败")?;
+    let postgres = PostgresCandleRepository::new(pool);
+    let mut summaries = Vec::with_capacity(targets.len());
+    for (inst_id, timeframe) in targets {
+        let summary = match action {
+            CandleStoreAction::Export => {
+                export_candles_to_file(&postgres, &files, &inst_id, timeframe, start_time, end_time)
+                    .await
+            }
+            CandleStoreAction::Import => {
+                import_candles_from_file(
+                    &files, &postgres, &inst_id, timeframe, start_time, end_time,
+                )
+                .await
+            }
+        }
+        .with_context(|| format!("{:?} {}@{} 失败", action, inst_id, timeframe.as_str()))?;
+        summaries.push(summary);
+    }
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_candle_store_action() {
+        assert_eq!(
+            parse_candle_store_action(Some(" Export ")).unwrap(),
+            CandleStoreAction::Export
+        );
+        assert_eq!(
+            parse_candle_store_action(Some("import")).unwrap(),
+            CandleStoreAction::Import
+        );
+        assert!(parse_candle_store_action(Some("sync")).is_err());
+        assert!(parse_candle_store_action(None).is_err());
+    }
+
+    #[test]
+    fn parses_candle_store_targets() {
+        assert!(parse_candle_store_targets(None).unwrap().is_empty());
+        assert_eq!(
+            parse_candle_store_targets(Some("BTC-USDT-SWAP@1H, ETH-USDT-SWAP@1m,BTC-USDT-SWAP@1h"))
+                .unwrap(),
+            vec![
+                ("BTC-USDT-SWAP".to_string(), Timeframe::H1),
+                ("ETH-USDT-SWAP".to_string(), Timeframe::M1),
+            ]
+        );
+        assert!(parse_candle_store_targets(Some("BTC-USDT-SWAP")).is_err());
+        assert!(parse_candle_store_targets(Some("BTC-USDT-SWAP@7m")).is_err());
+        assert!(parse_candle_store_targets(Some("@1H")).is_err());
+    }
+}
@@ -15,6 +15,7 @@ use tracing::{error, info};
 mod auth;
 mod backtest_details;
 mod backtest_logs;
+mod drawdown_breaker;
 mod http;
 mod json_helpers;
 mod market_rank_technical_context;
@@ -25,13 +26,18 @@ use crate::app::exchange_symbol_sync::{
 };
 use crate::app::market_velocity_event_backtest::market_velocity_paper_strategy_preset_manifest;
 use auth::authorize_internal_request;
-pub use backtest_details::{backtest_detail_list_query_from_path, BacktestDetailListQuery};
+pub use backtest_details::{
+    backtest_detail_list_query_from_path, backtest_report_format_from_path,
+    BacktestDetailListQuery, BacktestReportFormat,
+};
 pub use backtest_logs::{
     backtest_log_list_query_from_path, core_backtest_run_list_query_from_path, BacktestLogListQuery,
 };
+pub use drawdown_breaker::drawdown_breaker_resume_scope_from_body;
 pub use http::InternalHttpJsonResponse;
 use http::{
     json_response, query_param, read_request, required_query_param, route_path, write_response,
+    write_text_response,
 };
 use json_helpers::parse_json_value_or_string;
 use market_rank_technical_context::{
@@ -1060,6 +1066,32 @@ async fn handle_connection(mut stream: TcpStream) -> Result<()> {
     {
         return write_response(&mut stream, response).await;
     }
+    if request.method == "GET" && is_metrics_route(route) {
+        return write_text_response(
+            &mut stream,
+            200,
+            rust_quant_core::metrics::PROMETHEUS_TEXT_CONTENT_TYPE,
+            &rust_quant_core::metrics::render(),
+        )
+        .await;
+    }
+    if request.method == "GET" && route == "/api/internal/backtests/details" {
+        match backtest_report_format_from_path(&request.path) {
+            Ok(Some(format)) => {
+                return backtest_details::write_backtest_report_response(
+                    &mut stream,
+                    &request.path,
+                    format,
+                )
+                .await;
+            }
+            Ok(None) => {}
+            Err(error) => {
+                return write_response(&mut stream, json_response(400, json!({ "error": error })))
+                    .await;
+            }
+        }
+    }
     let response = match (request.method.as_str(), route) {
         ("POST", "/internal/backtests/run") | ("POST", "/api/internal/backtests/run") => {
             handle_backtest_run_body(&request.body).await
@@ -1103,6 +1135,12 @@ async fn handle_connection(mut stream: TcpStream) -> Result<()> {
         | ("POST", "/api/internal/exchange-symbols/sync") => {
             handle_exchange_symbol_sync_body(&request.body).await
         }
+        ("GET", "/api/internal/risk/drawdown-breaker") => {
+            drawdown_breaker::handle_drawdown_breaker_list_path().await
+        }
+        ("POST", "/api/internal/risk/drawdown-breaker/resume") => {
+            drawdown_breaker::handle_drawdown_breaker_resume_body(&request.body).await
+        }
         ("GET", "/internal/health") | ("GET", "/api/internal/health") => {
             json_response(200, json!({ "status": "ok" }))
         }
@@ -1111,6 +1149,13 @@ async fn handle_connection(mut stream: TcpStream) -> Result<()> {
     };
     write_response(&mut stream, response).await
 }
+/// `/metrics` 保留 Prometheus 约定路径，同时提供与其他内部接口一致的前缀别名。
+pub fn is_metrics_route(route: &str) -> bool {
+    matches!(
+        route,
+        "/metrics" | "/internal/metrics" | "/api/internal/metrics"
+    )
+}
 async fn handle_market_velocity_paper_strategy_preset_manifest_path(
     path: &str,
 ) -> InternalHttpJsonResponse {
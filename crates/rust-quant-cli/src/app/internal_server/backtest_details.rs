@@ -1,9 +1,23 @@
+use super::http::{write_response, write_text_response};
 use super::{json_response, query_param, strategy_configs, InternalHttpJsonResponse};
+use crate::app::backtest_report::{
+    load_backtest_report, DEFAULT_BACKTEST_REPORT_MONTE_CARLO_ITERATIONS,
+};
 use anyhow::{Context, Result};
+use rust_quant_analytics::reporting::{render_backtest_report_html, BacktestReport};
 use serde_json::{json, Value};
 use sqlx::PgPool;
+use tokio::net::TcpStream;
 const DEFAULT_PAGE_SIZE: usize = 20;
 const MAX_PAGE_SIZE: usize = 200;
+/// 接口内同步执行蒙特卡洛，限制次数避免单个请求占满 CPU。
+const MAX_REPORT_MONTE_CARLO_ITERATIONS: usize = 10_000;
+/// `report` 查询参数指定的回测报告格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BacktestReportFormat {
+    Json,
+    Html,
+}
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BacktestDetailListQuery {
     /// 页码。
@@ -52,6 +66,80 @@ pub fn backtest_detail_list_query_from_path(path: &str) -> Result<BacktestDetail
         end_time: optional_text(query, &["endTime", "end_time"]),
     })
 }
+/// 解析 `report=json|html`；未携带时返回 `None`，继续走明细分页查询。
+pub fn backtest_report_format_from_path(
+    path: &str,
+) -> Result<Option<BacktestReportFormat>, String> {
+    let query = path
+        .split_once('?')
+        .map(|(_, query)| query)
+        .unwrap_or_default();
+    match optional_text(query, &["report"])
+        .map(|value| value.to_ascii_lowercase())
+        .as_deref()
+    {
+        None => Ok(None),
+        Some("json") => Ok(Some(BacktestReportFormat::Json)),
+        Some("html") => Ok(Some(BacktestReportFormat::Html)),
+        Some(other) => Err(format!("unsupported report format: {other}")),
+    }
+}
+/// 输出单次回测报告；HTML 直接返回文档，错误仍按 JSON 返回。
+pub(super) async fn write_backtest_report_response(
+    stream: &mut TcpStream,
+    path: &str,
+    format: BacktestReportFormat,
+) -> Result<()> {
+    let report = match backtest_report_response(path).await {
+        Ok(report) => report,
+        Err(response) => return write_response(stream, response).await,
+    };
+    match format {
+        BacktestReportFormat::Json => match serde_json::to_value(&report) {
+            Ok(body) => write_response(stream, json_response(200, body)).await,
+            Err(error) => {
+                write_response(
+                    stream,
+                    json_response(500, json!({ "error": error.to_string() })),
+                )
+                .await
+            }
+        },
+        BacktestReportFormat::Html => {
+            write_text_response(
+                stream,
+                200,
+                "text/html; charset=utf-8",
+                &render_backtest_report_html(&report),
+            )
+            .await
+        }
+    }
+}
+/// 读取报告所需的回测数据；`back_test_id` 必填，`mcIterations` 控制蒙特卡洛次数。
+async fn backtest_report_response(path: &str) -> Result<BacktestReport, InternalHttpJsonResponse> {
+    let query = path
+        .split_once('?')
+        .map(|(_, query)| query)
+        .unwrap_or_default();
+    let back_test_id = optional_text(query, &["backTestId", "back_test_id"])
+        .and_then(|value| value.parse::<i64>().ok())
+        .ok_or_else(|| json_response(400, json!({ "error": "back_test_id is required" })))?;
+    let iterations = query_param(query, &["mcIterations", "mc_iterations"])
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_BACKTEST_REPORT_MONTE_CARLO_ITERATIONS)
+        .min(MAX_REPORT_MONTE_CARLO_ITERATIONS);
+    let pool = strategy_configs::create_quant_core_internal_pool()
+        .map_err(|error| json_response(503, json!({ "error": error.to_string() })))?;
+    match load_backtest_report(&pool, back_test_id, iterations).await {
+        Ok(Some(report)) => Ok(report),
+        Ok(None) => Err(json_response(
+            404,
+            json!({ "error": format!("backtest not found: {back_test_id}") }),
+        )),
+        Err(error) => Err(json_response(500, json!({ "error": error.to_string() }))),
+    }
+}
 /// 执行 回测与策略研究 主流程，并把外部依赖调用、状态推进和错误返回串起来。
 pub(super) async fn handle_backtest_detail_list_path(path: &str) -> InternalHttpJsonResponse {
     let query = match backtest_detail_list_query_from_path(path) {
@@ -212,6 +212,17 @@ async fn legacy_backtest_logs_response(
                 total_return,
                 max_drawdown,
                 volatility,
+                sortino_ratio,
+                calmar_ratio,
+                profit_factor,
+                expectancy_r,
+                average_hold_hours,
+                exposure,
+                longest_drawdown_days,
+                max_win_streak,
+                max_loss_streak,
+                tail_ratio,
+                total_funding_fee,
                 'back_test_log'::text AS source_table
             FROM back_test_log
             WHERE ($1::TEXT IS NULL OR strategy_type ILIKE '%' || $1 || '%' OR inst_type ILIKE '%' || $1 || '%' OR time ILIKE '%' || $1 || '%')
@@ -0,0 +1,46 @@
+use super::{json_response, InternalHttpJsonResponse};
+use rust_quant_risk::account::{DrawdownCircuitBreaker, DrawdownScope};
+use serde::Deserialize;
+use serde_json::json;
+#[derive(Debug, Deserialize)]
+struct DrawdownBreakerResumeBody {
+    /// 熔断范围：account / strategy。
+    scope: String,
+    /// 范围 ID：账户 ID 或策略配置 ID。
+    id: String,
+}
+/// 解析回撤熔断恢复请求。
+pub fn drawdown_breaker_resume_scope_from_body(body: &[u8]) -> Result<DrawdownScope, String> {
+    let body: DrawdownBreakerResumeBody =
+        serde_json::from_slice(body).map_err(|e| format!("invalid request body: {e}"))?;
+    DrawdownScope::parse(&body.scope, &body.id).map_err(|e| e.to_string())
+}
+/// 查询全部回撤熔断状态。
+pub(super) async fn handle_drawdown_breaker_list_path() -> InternalHttpJsonResponse {
+    let breaker = match DrawdownCircuitBreaker::from_env() {
+        Ok(breaker) => breaker,
+        Err(error) => return json_response(503, json!({ "error": error.to_string() })),
+    };
+    match breaker.states().await {
+        Ok(items) => json_response(200, json!({ "total": items.len(), "items": items })),
+        Err(error) => json_response(500, json!({ "error": error.to_string() })),
+    }
+}
+/// 显式恢复已熔断的范围；这是熔断后恢复交易的唯一入口。
+pub(super) async fn handle_drawdown_breaker_resume_body(body: &[u8]) -> InternalHttpJsonResponse {
+    let scope = match drawdown_breaker_resume_scope_from_body(body) {
+        Ok(scope) => scope,
+        Err(message) => return json_response(400, json!({ "error": message })),
+    };
+    let breaker = match DrawdownCircuitBreaker::from_env() {
+        Ok(breaker) => breaker,
+        Err(error) => return json_response(503, json!({ "error": error.to_string() })),
+    };
+    match breaker
+        .resume(&scope, chrono::Utc::now().timestamp_millis())
+        .await
+    {
+        Ok(state) => json_response(200, json!({ "resumed": true, "state": state })),
+        Err(error) => json_response(409, json!({ "error": error.to_string() })),
+    }
+}
@@ -123,15 +123,39 @@ pub(super) async fn write_response(
     response: InternalHttpJsonResponse,
 ) -> Result<()> {
     let body = serde_json::to_vec(&response.body)?;
-    let reason = reason_phrase(response.status_code);
-    let header = format!(
-        "HTTP/1.1 {} {}\r\ncontent-type: application/json; charset=utf-8\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+    write_raw_response(
+        stream,
         response.status_code,
+        "application/json; charset=utf-8",
+        &body,
+    )
+    .await
+}
+/// 输出非 JSON 响应体，例如 Prometheus 文本格式的 `/metrics`。
+pub(super) async fn write_text_response(
+    stream: &mut TcpStream,
+    status_code: u16,
+    content_type: &str,
+    body: &str,
+) -> Result<()> {
+    write_raw_response(stream, status_code, content_type, body.as_bytes()).await
+}
+async fn write_raw_response(
+    stream: &mut TcpStream,
+    status_code: u16,
+    content_type: &str,
+    body: &[u8],
+) -> Result<()> {
+    let reason = reason_phrase(status_code);
+    let header = format!(
+        "HTTP/1.1 {} {}\r\ncontent-type: {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+        status_code,
         reason,
+        content_type,
         body.len()
     );
     stream.write_all(header.as_bytes()).await?;
-    stream.write_all(&body).await?;
+    stream.write_all(body).await?;
     stream.shutdown().await?;
     Ok(())
 }
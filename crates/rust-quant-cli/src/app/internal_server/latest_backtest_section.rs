@@ -212,6 +212,9 @@ fn backtest_config_from_request(request: &BacktestRunRequest) -> BackTestConfig
     config.enable_specified_test_vegas = false;
     config.enable_random_test_nwe = false;
     config.enable_specified_test_nwe = false;
+    config.vegas_optimizer = None;
+    config.walk_forward = None;
+    config.sensitivity = None;
     if request.strategy_key.trim().eq_ignore_ascii_case("nwe") {
         config.enable_specified_test_nwe = true;
     } else {
@@ -1,15 +1,18 @@
 use super::{
     account_snapshot_sync_credential_ref, backtest_detail_list_query_from_path,
-    backtest_log_list_query_from_path, compute_rank_change_pct,
-    core_backtest_run_list_query_from_path, exchange_account_snapshot_sync_request_from_body,
-    finalize_market_rank_rows, handle_exchange_account_snapshot_sync_body,
-    handle_market_velocity_paper_strategy_preset_manifest_path, kline_sync_request_from_body,
-    market_rank_events_query_from_path, market_rank_sort_can_use_recent_query,
-    market_rank_sort_requires_legacy_volume_before_limit, recent_market_rank_events_sql,
-    strategy_config_list_query_from_path, strategy_config_risk_config_update_value,
-    strategy_config_upsert_request_from_body, BacktestLogListQuery, MarketRankEventItem,
+    backtest_log_list_query_from_path, backtest_report_format_from_path, compute_rank_change_pct,
+    core_backtest_run_list_query_from_path, drawdown_breaker_resume_scope_from_body,
+    exchange_account_snapshot_sync_request_from_body, finalize_market_rank_rows,
+    handle_exchange_account_snapshot_sync_body,
+    handle_market_velocity_paper_strategy_preset_manifest_path, is_metrics_route,
+    kline_sync_request_from_body, market_rank_events_query_from_path,
+    market_rank_sort_can_use_recent_query, market_rank_sort_requires_legacy_volume_before_limit,
+    recent_market_rank_events_sql, strategy_config_list_query_from_path,
+    strategy_config_risk_config_update_value, strategy_config_upsert_request_from_body,
+    BacktestLogListQuery, BacktestReportFormat, MarketRankEventItem,
 };
 use chrono::{TimeZone, Utc};
+use rust_quant_risk::account::DrawdownScope;
 use serde_json::json;
 
 #[test]
@@ -439,6 +442,28 @@ fn backtest_detail_list_query_accepts_api_internal_prefix_and_filters() {
     assert_eq!(query.side.as_deref(), Some("long"));
 }
 #[test]
+fn backtest_detail_report_param_selects_report_format() {
+    assert_eq!(
+        backtest_report_format_from_path("/api/internal/backtests/details?backTestId=42"),
+        Ok(None)
+    );
+    assert_eq!(
+        backtest_report_format_from_path(
+            "/api/internal/backtests/details?backTestId=42&report=HTML"
+        ),
+        Ok(Some(BacktestReportFormat::Html))
+    );
+    assert_eq!(
+        backtest_report_format_from_path(
+            "/api/internal/backtests/details?report=json&back_test_id=42"
+        ),
+        Ok(Some(BacktestReportFormat::Json))
+    );
+    assert!(
+        backtest_report_format_from_path("/api/internal/backtests/details?report=pdf").is_err()
+    );
+}
+#[test]
 fn core_backtest_run_list_query_accepts_api_internal_prefix_and_filters() {
     let query = core_backtest_run_list_query_from_path(
         "/api/internal/core/backtest-runs?page=2&pageSize=999&keyword=vegas&status=success&exchange=okx&symbol=eth-usdt-swap",
@@ -734,3 +759,27 @@ fn market_rank_rows_sort_volume_by_absolute_change_rate() {
     let rows = finalize_market_rank_rows(rows, Some("volume_24h"), 10);
     assert_eq!(rows[0].symbol, "LARGE-DOWN-USDT-SWAP");
 }
+#[test]
+fn drawdown_breaker_resume_body_requires_known_scope_and_id() {
+    let scope = drawdown_breaker_resume_scope_from_body(br#"{"scope":"strategy","id":" 42 "}"#)
+        .expect("strategy scope should parse");
+    assert_eq!(scope, DrawdownScope::Strategy("42".to_string()));
+    let scope = drawdown_breaker_resume_scope_from_body(br#"{"scope":"ACCOUNT","id":"okx"}"#)
+        .expect("account scope should parse");
+    assert_eq!(scope, DrawdownScope::Account("okx".to_string()));
+    assert!(drawdown_breaker_resume_scope_from_body(br#"{"scope":"symbol","id":"BTC"}"#).is_err());
+    assert!(drawdown_breaker_resume_scope_from_body(br#"{"scope":"account","id":""}"#).is_err());
+    assert!(drawdown_breaker_resume_scope_from_body(b"not json").is_err());
+}
+
+#[test]
+fn metrics_route_accepts_prometheus_path_and_internal_aliases() {
+    for route in ["/metrics", "/internal/metrics", "/api/internal/metrics"] {
+        assert!(is_metrics_route(route), "{route}");
+    }
+    assert!(!is_metrics_route("/api/internal/metrics/extra"));
+    rust_quant_core::metrics::record_strategy_signal("internal_server_metrics_test", "buy");
+    assert!(rust_quant_core::metrics::render().contains(
+        "quant_core_strategy_signals_total{side=\"buy\",strategy=\"internal_server_metrics_test\"} 1"
+    ));
+}
@@ -1,6 +1,8 @@
 pub mod all_market_candle_volume_monitor;
+pub mod backtest_report;
 pub mod binance_eth_micro_live_validation;
 pub mod bootstrap;
+pub mod candle_store;
 pub mod control_api;
 pub(crate) mod env_parse;
 pub mod exchange_symbol_sync;
@@ -0,0 +1,11 @@
+use anyhow::Result;
+use rust_quant_cli::app::backtest_report::run_backtest_report_from_env;
+
+/// 按 `BACKTEST_REPORT_ID` 导出单次回测的 HTML/JSON 报告。
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    let files = run_backtest_report_from_env().await?;
+    println!("{}", serde_json::to_string_pretty(&files)?);
+    Ok(())
+}
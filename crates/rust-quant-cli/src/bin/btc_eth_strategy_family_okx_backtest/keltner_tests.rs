@@ -438,6 +438,7 @@ fn trade_record(
         initial_stop_price: None,
         initial_risk_amount: None,
         net_profit_r: None,
+        funding_fee: None,
     }
 }
 
@@ -1920,5 +1920,6 @@ fn trade_record(
         initial_stop_price: None,
         initial_risk_amount: None,
         net_profit_r: None,
+        funding_fee: None,
     }
 }
@@ -0,0 +1,11 @@
+use anyhow::Result;
+use rust_quant_cli::app::candle_store::run_candle_store_from_env;
+
+/// 按 `CANDLE_STORE_ACTION` 在 quant_core K 线分表与本地列式文件之间导入导出。
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    let summaries = run_candle_store_from_env().await?;
+    println!("{}", serde_json::to_string_pretty(&summaries)?);
+    Ok(())
+}
@@ -269,6 +269,8 @@ fn risk_config() -> BasicRiskStrategyConfig {
         position_leverage: None,
         tiered_take_profit_level_1_close_ratio: None,
         tiered_take_profit_level_2_close_ratio: None,
+        pyramiding: None,
+        cost_model: None,
     }
 }
 
@@ -394,6 +396,7 @@ mod tests {
             initial_stop_price: None,
             initial_risk_amount: None,
             net_profit_r: None,
+            funding_fee: None,
         }
     }
 
@@ -33,6 +33,8 @@ pub async fn app_init() -> Result<()> {
     rust_quant_core::database::init_db_pool().await?;
     // 初始化 Redis 连接池
     rust_quant_core::cache::init_redis_pool().await?;
+    // worker 进程没有内部 HTTP 路由；配置 QUANT_METRICS_ADDR 时单独暴露 /metrics 供 Prometheus 抓取。
+    rust_quant_core::metrics::spawn_metrics_server_from_env();
     info!("应用初始化完成");
     Ok(())
 }
@@ -101,6 +101,9 @@ fn backtest_config_overrides_bound_manual_vegas_runs() {
     assert!(!config.enable_random_test_vegas);
     assert!(!config.enable_specified_test_nwe);
     assert!(!config.enable_random_test_nwe);
+    assert!(config.vegas_optimizer.is_none());
+    assert!(config.walk_forward.is_none());
+    assert!(config.sensitivity.is_none());
 }
 
 #[test]
@@ -0,0 +1,189 @@
+use super::crypto_exc_all_gateway::{CryptoExcAllGateway, OrderPlacementRequest};
+use async_trait::async_trait;
+use crypto_exc_all::{
+    AccountBill, AccountBillQuery, Balance, CancelOrderRequest, ExchangeId, Fill, FillListQuery,
+    Instrument, MaxOrderSize, MaxOrderSizeRequest, Order, OrderAck, OrderBook, OrderBookQuery,
+    OrderListQuery, OrderQuery, Position, PositionHistory, PositionHistoryQuery,
+    PrepareOrderSettingsRequest, PrepareOrderSettingsResult, ProtectiveOrderQuery,
+    ProtectiveOrderRequest, Result, Ticker,
+};
+/// 执行链路依赖的交易所交易与查询能力。
+///
+/// 实盘由 `CryptoExcAllGateway` 转发到各交易所 SDK；离线演练与测试由 `SimulatedExchange`
+/// 按脚本价格路径撮合，二者返回相同的 SDK 数据结构与错误类型。
+#[async_trait]
+pub trait ExchangeGateway: Send + Sync {
+    /// 已配置可用的交易所。
+    fn configured_exchanges(&self) -> Vec<ExchangeId>;
+    /// 查询最新行情。
+    async fn ticker(&self, exchange: ExchangeId, instrument: &Instrument) -> Result<Ticker>;
+    /// 查询盘口深度。
+    async fn orderbook(&self, exchange: ExchangeId, query: OrderBookQuery) -> Result<OrderBook>;
+    /// 下单前同步杠杆、保证金与持仓模式。
+    async fn prepare_order_settings(
+        &self,
+        exchange: ExchangeId,
+        request: PrepareOrderSettingsRequest,
+    ) -> Result<PrepareOrderSettingsResult>;
+    /// 查询当前最大可下单数量。
+    async fn max_order_size(
+        &self,
+        exchange: ExchangeId,
+        request: MaxOrderSizeRequest,
+    ) -> Result<MaxOrderSize>;
+    /// 下单，可附带止损。
+    async fn place_order(&self, request: OrderPlacementRequest) -> Result<OrderAck>;
+    /// 挂出保护性条件单。
+    async fn place_protective_order(
+        &self,
+        exchange: ExchangeId,
+        request: ProtectiveOrderRequest,
+    ) -> Result<OrderAck>;
+    /// 查询普通委托。
+    async fn order(&self, exchange: ExchangeId, query: OrderQuery) -> Result<Order>;
+    /// 查询保护性条件单。
+    async fn protective_order(
+        &self,
+        exchange: ExchangeId,
+        query: ProtectiveOrderQuery,
+    ) -> Result<Order>;
+    /// 查询挂单中的委托。
+    async fn open_orders(&self, exchange: ExchangeId, query: OrderListQuery) -> Result<Vec<Order>>;
+    /// 查询历史委托。
+    async fn order_history(
+        &self,
+        exchange: ExchangeId,
+        query: OrderListQuery,
+    ) -> Result<Vec<Order>>;
+    /// 查询历史持仓。
+    async fn position_history(
+        &self,
+        exchange: ExchangeId,
+        query: PositionHistoryQuery,
+    ) -> Result<Vec<PositionHistory>>;
+    /// 查询成交明细。
+    async fn fills(&self, exchange: ExchangeId, query: FillListQuery) -> Result<Vec<Fill>>;
+    /// 查询账户余额。
+    async fn balances(&self, exchange: ExchangeId) -> Result<Vec<Balance>>;
+    /// 查询账户流水。
+    async fn account_bills(
+        &self,
+        exchange: ExchangeId,
+        query: AccountBillQuery,
+    ) -> Result<Vec<AccountBill>>;
+    /// 查询持仓；未指定标的时返回全部持仓。
+    async fn positions(
+        &self,
+        exchange: ExchangeId,
+        instrument: Option<&Instrument>,
+    ) -> Result<Vec<Position>>;
+    /// 撤销普通委托。
+    async fn cancel_order(
+        &self,
+        exchange: ExchangeId,
+        request: CancelOrderRequest,
+    ) -> Result<OrderAck>;
+    /// 撤销保护性条件单。
+    async fn cancel_protective_order(
+        &self,
+        exchange: ExchangeId,
+        request: CancelOrderRequest,
+    ) -> Result<OrderAck>;
+}
+#[async_trait]
+impl ExchangeGateway for CryptoExcAllGateway {
+    fn configured_exchanges(&self) -> Vec<ExchangeId> {
+        CryptoExcAllGateway::configured_exchanges(self)
+    }
+    async fn ticker(&self, exchange: ExchangeId, instrument: &Instrument) -> Result<Ticker> {
+        CryptoExcAllGateway::ticker(self, exchange, instrument).await
+    }
+    async fn orderbook(&self, exchange: ExchangeId, query: OrderBookQuery) -> Result<OrderBook> {
+        CryptoExcAllGateway::orderbook(self, exchange, query).await
+    }
+    async fn prepare_order_settings(
+        &self,
+        exchange: ExchangeId,
+        request: PrepareOrderSettingsRequest,
+    ) -> Result<PrepareOrderSettingsResult> {
+        CryptoExcAllGateway::prepare_order_settings(self, exchange, request).await
+    }
+    async fn max_order_size(
+        &self,
+        exchange: ExchangeId,
+        request: MaxOrderSizeRequest,
+    ) -> Result<MaxOrderSize> {
+        CryptoExcAllGateway::max_order_size(self, exchange, request).await
+    }
+    async fn place_order(&self, request: OrderPlacementRequest) -> Result<OrderAck> {
+        CryptoExcAllGateway::place_order(self, request).await
+    }
+    async fn place_protective_order(
+        &self,
+        exchange: ExchangeId,
+        request: ProtectiveOrderRequest,
+    ) -> Result<OrderAck> {
+        CryptoExcAllGateway::place_protective_order(self, exchange, request).await
+    }
+    async fn order(&self, exchange: ExchangeId, query: OrderQuery) -> Result<Order> {
+        CryptoExcAllGateway::order(self, exchange, query).await
+    }
+    async fn protective_order(
+        &self,
+        exchange: ExchangeId,
+        query: ProtectiveOrderQuery,
+    ) -> Result<Order> {
+        CryptoExcAllGateway::protective_order(self, exchange, query).await
+    }
+    async fn open_orders(&self, exchange: ExchangeId, query: OrderListQuery) -> Result<Vec<Order>> {
+        CryptoExcAllGateway::open_orders(self, exchange, query).await
+    }
+    async fn order_history(
+        &self,
+        exchange: ExchangeId,
+        query: OrderListQuery,
+    ) -> Result<Vec<Order>> {
+        CryptoExcAllGateway::order_history(self, exchange, query).await
+    }
+    async fn position_history(
+        &self,
+        exchange: ExchangeId,
+        query: PositionHistoryQuery,
+    ) -> Result<Vec<PositionHistory>> {
+        CryptoExcAllGateway::position_history(self, exchange, query).await
+    }
+    async fn fills(&self, exchange: ExchangeId, query: FillListQuery) -> Result<Vec<Fill>> {
+        CryptoExcAllGateway::fills(self, exchange, query).await
+    }
+    async fn balances(&self, exchange: ExchangeId) -> Result<Vec<Balance>> {
+        CryptoExcAllGateway::balances(self, exchange).await
+    }
+    async fn account_bills(
+        &self,
+        exchange: ExchangeId,
+        query: AccountBillQuery,
+    ) -> Result<Vec<AccountBill>> {
+        CryptoExcAllGateway::account_bills(self, exchange, query).await
+    }
+    async fn positions(
+        &self,
+        exchange: ExchangeId,
+        instrument: Option<&Instrument>,
+    ) -> Result<Vec<Position>> {
+        CryptoExcAllGateway::positions(self, exchange, instrument).await
+    }
+    async fn cancel_order(
+        &self,
+        exchange: ExchangeId,
+        request: CancelOrderRequest,
+    ) -> Result<OrderAck> {
+        CryptoExcAllGateway::cancel_order(self, exchange, request).await
+    }
+    async fn cancel_protective_order(
+        &self,
+        exchange: ExchangeId,
+        request: CancelOrderRequest,
+    ) -> Result<OrderAck> {
+        CryptoExcAllGateway::cancel_protective_order(self, exchange, request).await
+    }
+}
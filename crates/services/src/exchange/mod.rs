@@ -1,6 +1,12 @@
 pub mod crypto_exc_all_gateway;
 pub mod exchange_api_service;
+pub mod gateway;
 pub mod okx_order_service;
+pub mod simulated_exchange;
+pub mod simulated_matching;
 pub use crypto_exc_all_gateway::{CryptoExcAllGateway, OrderPlacementRequest};
 pub use exchange_api_service::{create_exchange_api_service, ExchangeApiService};
+pub use gateway::ExchangeGateway;
 pub use okx_order_service::OkxOrderService;
+pub use simulated_exchange::SimulatedExchange;
+pub use simulated_matching::MatchingEngine;
@@ -0,0 +1,797 @@
+//! 内存模拟交易所。
+//!
+//! 在 `MatchingEngine` 之上实现 `ExchangeGateway`，按脚本价格路径撮合委托，
+//! 使下单 → 挂保护 → 止盈 → 对账的完整链路可以离线运行。响应字段沿用 Binance USDⓈ-M
+//! 的状态与错误码口径；账户为单向持仓、单一结算币种，不模拟保证金占用与强平。
+use super::crypto_exc_all_gateway::OrderPlacementRequest;
+use super::gateway::ExchangeGateway;
+use super::simulated_matching::{
+    MatchingEngine, SimFill, SimOrder, SimOrderKind, SimOrderRequest, SimOrderStatus, SimReject,
+    SimSide, SimTimeInForce,
+};
+use async_trait::async_trait;
+use crypto_exc_all::{
+    AccountBill, AccountBillQuery, Balance, CancelOrderRequest, Error, ExchangeId, Fill,
+    FillListQuery, Instrument, MaxOrderSize, MaxOrderSizeRequest, Order, OrderAck, OrderBook,
+    OrderBookLevel, OrderBookQuery, OrderListQuery, OrderQuery, OrderSide, OrderType, Position,
+    PositionHistory, PositionHistoryQuery, PrepareOrderSettingsRequest, PrepareOrderSettingsResult,
+    ProtectiveOrderQuery, ProtectiveOrderRequest, Result, Ticker, TimeInForce,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+/// 合成盘口的单边半价差比例，买一/卖一分列最新价两侧。
+const SIM_BOOK_HALF_SPREAD_RATIO: f64 = 0.0001;
+/// 合成盘口每档挂单数量，足够覆盖演练规模的下单深度校验。
+const SIM_BOOK_LEVEL_SIZE: f64 = 1_000_000.0;
+/// 按脚本价格撮合的内存交易所。
+pub struct SimulatedExchange {
+    /// 模拟的交易所，决定标的代码格式；其他交易所的请求直接拒绝。
+    exchange: ExchangeId,
+    /// 结算币种。
+    settlement_asset: String,
+    /// 撮合引擎与已出现过的标的。
+    state: Mutex<SimulatedExchangeState>,
+}
+struct SimulatedExchangeState {
+    engine: MatchingEngine,
+    /// 交易所标的代码到标的的映射，用于在无标的的查询结果中回填标的。
+    instruments: HashMap<String, Instrument>,
+}
+impl SimulatedExchange {
+    /// 以初始结算币余额创建模拟交易所，默认结算币种为 USDT、不收手续费。
+    pub fn new(exchange: ExchangeId, initial_balance: f64) -> Self {
+        Self {
+            exchange,
+            settlement_asset: "USDT".to_string(),
+            state: Mutex::new(SimulatedExchangeState {
+                engine: MatchingEngine::new(initial_balance),
+                instruments: HashMap::new(),
+            }),
+        }
+    }
+    /// 设置 maker/taker 手续费率。
+    pub fn with_fee_rates(mut self, maker_fee_rate: f64, taker_fee_rate: f64) -> Self {
+        let state = self
+            .state
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.engine = state
+            .engine
+            .clone()
+            .with_fee_rates(maker_fee_rate, taker_fee_rate);
+        self
+    }
+    /// 设置结算币种。
+    pub fn with_settlement_asset(mut self, asset: impl Into<String>) -> Self {
+        self.settlement_asset = asset.into();
+        self
+    }
+    /// 模拟的交易所。
+    pub fn exchange(&self) -> ExchangeId {
+        self.exchange
+    }
+    /// 拨动撮合时钟，毫秒；时钟只允许前进。
+    pub fn set_clock_ms(&self, clock_ms: u64) {
+        self.state().engine.set_clock_ms(clock_ms);
+    }
+    /// 推送标的最新价并撮合挂单，返回本次产生的成交。
+    pub fn set_price(&self, instrument: &Instrument, price: f64) -> Vec<Fill> {
+        let mut state = self.state();
+        let symbol = self.register(&mut state, instrument);
+        let fills = state.engine.update_price(&symbol, price);
+        fills
+            .iter()
+            .map(|fill| self.fill_to_sdk(instrument, fill))
+            .collect()
+    }
+    /// 依次推送价格路径上的每个价格，返回整条路径产生的成交。
+    pub fn replay_prices(
+        &self,
+        instrument: &Instrument,
+        prices: impl IntoIterator<Item = f64>,
+    ) -> Vec<Fill> {
+        prices
+            .into_iter()
+            .flat_map(|price| self.set_price(instrument, price))
+            .collect()
+    }
+    fn state(&self) -> MutexGuard<'_, SimulatedExchangeState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    fn register(&self, state: &mut SimulatedExchangeState, instrument: &Instrument) -> String {
+        let symbol = instrument.symbol_for(self.exchange);
+        state
+            .instruments
+            .entry(symbol.clone())
+            .or_insert_with(|| instrument.clone());
+        symbol
+    }
+    fn ensure_exchange(&self, exchange: ExchangeId) -> Result<()> {
+        if exchange == self.exchange {
+            Ok(())
+        } else {
+            Err(Error::Unsupported {
+                exchange,
+                capability: "simulated exchange routing",
+            })
+        }
+    }
+    fn reject(&self, reject: SimReject) -> Error {
+        Error::Api {
+            exchange: self.exchange,
+            status: None,
+            code: reject.code().to_string(),
+            message: reject.to_string(),
+        }
+    }
+    fn instrument_for(&self, state: &SimulatedExchangeState, symbol: &str) -> Option<Instrument> {
+        state.instruments.get(symbol).cloned()
+    }
+    /// 读取标的最新价与撮合时钟；尚未推送过价格时按无行情拒绝。
+    fn quote(&self, instrument: &Instrument) -> Result<(String, f64, u64)> {
+        let state = self.state();
+        let symbol = instrument.symbol_for(self.exchange);
+        let price = state
+            .engine
+            .mark_price(&symbol)
+            .ok_or_else(|| self.reject(SimReject::NoMarketPrice))?;
+        Ok((symbol, price, state.engine.now_ms()))
+    }
+    fn order_to_sdk(&self, instrument: &Instrument, order: &SimOrder) -> Order {
+        Order {
+            exchange: self.exchange,
+            instrument: instrument.clone(),
+            exchange_symbol: order.symbol.clone(),
+            order_id: Some(order.order_id.clone()),
+            client_order_id: order.client_order_id.clone(),
+            side: Some(side_text(order.side).to_string()),
+            order_type: Some(order_kind_text(order.kind).to_string()),
+            price: order.price.map(|price| price.to_string()),
+            size: Some(order.quantity.to_string()),
+            filled_size: Some(order.filled_quantity.to_string()),
+            average_price: order.average_price.map(|price| price.to_string()),
+            status: Some(order_status_text(order.status).to_string()),
+            created_at: Some(order.created_at),
+            updated_at: Some(order.updated_at),
+            raw: order_raw(order),
+        }
+    }
+    fn ack_from_order(&self, instrument: &Instrument, order: &SimOrder) -> OrderAck {
+        OrderAck {
+            exchange: self.exchange,
+            instrument: instrument.clone(),
+            exchange_symbol: order.symbol.clone(),
+            order_id: Some(order.order_id.clone()),
+            client_order_id: order.client_order_id.clone(),
+            status: Some(order_status_text(order.status).to_string()),
+            raw: order_raw(order),
+        }
+    }
+    fn fill_to_sdk(&self, instrument: &Instrument, fill: &SimFill) -> Fill {
+        Fill {
+            exchange: self.exchange,
+            instrument: instrument.clone(),
+            exchange_symbol: fill.symbol.clone(),
+            trade_id: Some(fill.trade_id.clone()),
+            order_id: Some(fill.order_id.clone()),
+            side: Some(side_text(fill.side).to_string()),
+            price: Some(fill.price.to_string()),
+            size: Some(fill.quantity.to_string()),
+            fee: Some(fill.fee.to_string()),
+            fee_asset: Some(self.settlement_asset.clone()),
+            role: Some(if fill.maker { "maker" } else { "taker" }.to_string()),
+            timestamp: Some(fill.timestamp),
+            raw: json!({
+                "simulated": true,
+                "id": fill.trade_id,
+                "orderId": fill.order_id,
+                "realizedPnl": fill.realized_pnl,
+            }),
+        }
+    }
+    fn submit(&self, instrument: &Instrument, mut request: SimOrderRequest) -> Result<OrderAck> {
+        let mut state = self.state();
+        request.symbol = self.register(&mut state, instrument);
+        let order = state
+            .engine
+            .submit(request)
+            .map_err(|reject| self.reject(reject))?;
+        Ok(self.ack_from_order(instrument, &order))
+    }
+    fn find_order(
+        &self,
+        instrument: &Instrument,
+        order_id: Option<&str>,
+        client_order_id: Option<&str>,
+    ) -> Result<Order> {
+        let state = self.state();
+        let symbol = instrument.symbol_for(self.exchange);
+        state
+            .engine
+            .order(&symbol, order_id, client_order_id)
+            .map(|order| self.order_to_sdk(instrument, order))
+            .ok_or_else(|| self.reject(SimReject::OrderNotFound))
+    }
+    fn cancel(
+        &self,
+        instrument: &Instrument,
+        order_id: Option<&str>,
+        client_order_id: Option<&str>,
+    ) -> Result<OrderAck> {
+        let mut state = self.state();
+        let symbol = instrument.symbol_for(self.exchange);
+        let order = state
+            .engine
+            .cancel(&symbol, order_id, client_order_id)
+            .map_err(|reject| self.reject(reject))?;
+        Ok(self.ack_from_order(instrument, &order))
+    }
+    /// 按时间倒序列出委托；模拟账户数据量小，不分页。
+    fn list_orders(&self, instrument: Option<&Instrument>, open: bool) -> Vec<Order> {
+        let state = self.state();
+        let symbol = instrument.map(|instrument| instrument.symbol_for(self.exchange));
+        let orders = if open {
+            state.engine.open_orders(symbol.as_deref())
+        } else {
+            state.engine.order_history(symbol.as_deref())
+        };
+        orders
+            .into_iter()
+            .rev()
+            .filter_map(|order| {
+                let instrument = self.instrument_for(&state, &order.symbol)?;
+                Some(self.order_to_sdk(&instrument, order))
+            })
+            .collect()
+    }
+}
+#[async_trait]
+impl ExchangeGateway for SimulatedExchange {
+    fn configured_exchanges(&self) -> Vec<ExchangeId> {
+        vec![self.exchange]
+    }
+    async fn ticker(&self, exchange: ExchangeId, instrument: &Instrument) -> Result<Ticker> {
+        self.ensure_exchange(exchange)?;
+        let (symbol, price, now_ms) = self.quote(instrument)?;
+        let half_spread = price * SIM_BOOK_HALF_SPREAD_RATIO;
+        Ok(Ticker {
+            exchange: self.exchange,
+            instrument: instrument.clone(),
+            instrument_type: None,
+            exchange_symbol: symbol,
+            last_price: price.to_string(),
+            last_size: None,
+            bid_price: Some((price - half_spread).to_string()),
+            bid_size: Some(SIM_BOOK_LEVEL_SIZE.to_string()),
+            ask_price: Some((price + half_spread).to_string()),
+            ask_size: Some(SIM_BOOK_LEVEL_SIZE.to_string()),
+            open_24h: None,
+            high_24h: None,
+            low_24h: None,
+            volume_24h: None,
+            base_volume_24h: None,
+            quote_volume_24h: None,
+            sod_utc0: None,
+            sod_utc8: None,
+            timestamp: Some(now_ms),
+            raw: json!({"simulated": true, "lastPrice": price}),
+        })
+    }
+    async fn orderbook(&self, exchange: ExchangeId, query: OrderBookQuery) -> Result<OrderBook> {
+        self.ensure_exchange(exchange)?;
+        let (symbol, price, now_ms) = self.quote(&query.instrument)?;
+        let half_spread = price * SIM_BOOK_HALF_SPREAD_RATIO;
+        let level = |price: f64| OrderBookLevel {
+            price: price.to_string(),
+            size: SIM_BOOK_LEVEL_SIZE.to_string(),
+            raw: json!({"simulated": true}),
+        };
+        Ok(OrderBook {
+            exchange: self.exchange,
+            instrument: query.instrument,
+            exchange_symbol: symbol,
+            bids: vec![level(price - half_spread)],
+            asks: vec![level(price + half_spread)],
+            timestamp: Some(now_ms),
+            raw: json!({"simulated": true}),
+        })
+    }
+    async fn prepare_order_settings(
+        &self,
+        exchange: ExchangeId,
+        request: PrepareOrderSettingsRequest,
+    ) -> Result<PrepareOrderSettingsResult> {
+        self.ensure_exchange(exchange)?;
+        // 模拟账户固定为单向持仓且不计保证金，设置请求只做回显
+        Ok(PrepareOrderSettingsResult {
+            exchange: self.exchange,
+            exchange_symbol: request.instrument.symbol_for(self.exchange),
+            instrument: request.instrument,
+            raw: json!({
+                "simulated": true,
+                "leverage": request.leverage,
+                "marginMode": request.margin_mode,
+                "positionMode": request.position_mode,
+            }),
+        })
+    }
+    async fn max_order_size(
+        &self,
+        exchange: ExchangeId,
+        request: MaxOrderSizeRequest,
+    ) -> Result<MaxOrderSize> {
+        self.ensure_exchange(exchange)?;
+        let (symbol, mark_price, _) = self.quote(&request.instrument)?;
+        let price = request
+            .price
+            .as_deref()
+            .and_then(parse_decimal)
+            .unwrap_or(mark_price);
+        let leverage = request
+            .leverage
+            .as_deref()
+            .and_then(parse_decimal)
+            .unwrap_or(1.0);
+        let available = {
+            let state = self.state();
+            (state.engine.cash() + state.engine.unrealized_pnl()).max(0.0)
+        };
+        let max_size = (available * leverage / price).to_string();
+        Ok(MaxOrderSize {
+            exchange: self.exchange,
+            instrument: request.instrument,
+            exchange_symbol: symbol,
+            max_buy: max_size.clone(),
+            max_sell: max_size,
+            raw: json!({"simulated": true, "availableBalance": available}),
+        })
+    }
+    async fn place_order(&self, request: OrderPlacementRequest) -> Result<OrderAck> {
+        self.ensure_exchange(request.exchange)?;
+        let kind = if request.order_type == OrderType::Limit {
+            SimOrderKind::Limit
+        } else {
+            SimOrderKind::Market
+        };
+        let mut order = SimOrderRequest::new(String::new(), sim_side(request.side), kind);
+        order.quantity = Some(
+            parse_decimal(&request.size).ok_or_else(|| self.reject(SimReject::InvalidQuantity))?,
+        );
+        if kind == SimOrderKind::Limit {
+            order.price = Some(
+                request
+                    .price
+                    .as_deref()
+                    .and_then(parse_decimal)
+                    .ok_or_else(|| self.reject(SimReject::InvalidPrice))?,
+            );
+        }
+        order.time_in_force = request
+            .time_in_force
+            .map(sim_time_in_force)
+            .unwrap_or_default();
+        // 双向持仓的平仓腿按只减仓处理，模拟账户统一记为单向净持仓
+        order.reduce_only = request.reduce_only.unwrap_or(false)
+            || request
+                .trade_side
+                .as_deref()
+                .is_some_and(|trade_side| trade_side.eq_ignore_ascii_case("close"));
+        order.client_order_id = request.client_order_id;
+        order.attached_stop_loss = match request.attached_stop_loss_price.as_deref() {
+            Some(price) => {
+                Some(parse_decimal(price).ok_or_else(|| self.reject(SimReject::InvalidPrice))?)
+            }
+            None => None,
+        };
+        self.submit(&request.instrument, order)
+    }
+    async fn place_protective_order(
+        &self,
+        exchange: ExchangeId,
+        request: ProtectiveOrderRequest,
+    ) -> Result<OrderAck> {
+        self.ensure_exchange(exchange)?;
+        let mut order = SimOrderRequest::new(
+            String::new(),
+            sim_side(request.side),
+            SimOrderKind::StopMarket,
+        );
+        order.price = Some(
+            parse_decimal(&request.stop_price)
+                .ok_or_else(|| self.reject(SimReject::InvalidPrice))?,
+        );
+        order.quantity = match request.quantity.as_deref() {
+            Some(quantity) => Some(
+                parse_decimal(quantity).ok_or_else(|| self.reject(SimReject::InvalidQuantity))?,
+            ),
+            None => None,
+        };
+        order.close_position = request.close_position.unwrap_or(false) || order.quantity.is_none();
+        order.reduce_only = true;
+        order.client_order_id = request.client_order_id;
+        self.submit(&request.instrument, order)
+    }
+    async fn order(&self, exchange: ExchangeId, query: OrderQuery) -> Result<Order> {
+        self.ensure_exchange(exchange)?;
+        self.find_order(
+            &query.instrument,
+            query.order_id.as_deref(),
+            query.client_order_id.as_deref(),
+        )
+    }
+    async fn protective_order(
+        &self,
+        exchange: ExchangeId,
+        query: ProtectiveOrderQuery,
+    ) -> Result<Order> {
+        self.ensure_exchange(exchange)?;
+        self.find_order(
+            &query.instrument,
+            query.order_id.as_deref(),
+            query.client_order_id.as_deref(),
+        )
+    }
+    async fn open_orders(&self, exchange: ExchangeId, query: OrderListQuery) -> Result<Vec<Order>> {
+        self.ensure_exchange(exchange)?;
+        Ok(self.list_orders(query.instrument.as_ref(), true))
+    }
+    async fn order_history(
+        &self,
+        exchange: ExchangeId,
+        query: OrderListQuery,
+    ) -> Result<Vec<Order>> {
+        self.ensure_exchange(exchange)?;
+        Ok(self.list_orders(query.instrument.as_ref(), false))
+    }
+    /// 模拟账户不保留已平仓位记录，历史持仓恒为空。
+    async fn position_history(
+        &self,
+        exchange: ExchangeId,
+        _query: PositionHistoryQuery,
+    ) -> Result<Vec<PositionHistory>> {
+        self.ensure_exchange(exchange)?;
+        Ok(Vec::new())
+    }
+    async fn fills(&self, exchange: ExchangeId, query: FillListQuery) -> Result<Vec<Fill>> {
+        self.ensure_exchange(exchange)?;
+        let state = self.state();
+        let symbol = query
+            .instrument
+            .as_ref()
+            .map(|instrument| instrument.symbol_for(self.exchange));
+        let fills = state
+            .engine
+            .fills(symbol.as_deref(), query.order_id.as_deref())
+            .into_iter()
+            .rev()
+            .filter_map(|fill| {
+                let instrument = self.instrument_for(&state, &fill.symbol)?;
+                Some(self.fill_to_sdk(&instrument, fill))
+            })
+            .collect();
+        Ok(fills)
+    }
+    async fn balances(&self, exchange: ExchangeId) -> Result<Vec<Balance>> {
+        self.ensure_exchange(exchange)?;
+        let state = self.state();
+        let wallet = state.engine.cash();
+        let unrealized_pnl = state.engine.unrealized_pnl();
+        Ok(vec![Balance {
+            exchange: self.exchange,
+            asset: self.settlement_asset.clone(),
+            total: wallet.to_string(),
+            available: (wallet + unrealized_pnl).to_string(),
+            frozen: None,
+            raw: json!({
+                "simulated": true,
+                "walletBalance": wallet,
+                "unrealizedProfit": unrealized_pnl,
+            }),
+        }])
+    }
+    /// 资金变动只体现在余额上，模拟账户不生成账单流水。
+    async fn account_bills(
+        &self,
+        exchange: ExchangeId,
+        _query: AccountBillQuery,
+    ) -> Result<Vec<AccountBill>> {
+        self.ensure_exchange(exchange)?;
+        Ok(Vec::new())
+    }
+    async fn positions(
+        &self,
+        exchange: ExchangeId,
+        instrument: Option<&Instrument>,
+    ) -> Result<Vec<Position>> {
+        self.ensure_exchange(exchange)?;
+        let state = self.state();
+        let symbol = instrument.map(|instrument| instrument.symbol_for(self.exchange));
+        Ok(state
+            .engine
+            .positions(symbol.as_deref())
+            .into_iter()
+            .filter_map(|position| {
+                let instrument = self.instrument_for(&state, &position.symbol)?;
+                let mark_price = state.engine.mark_price(&position.symbol);
+                Some(Position {
+                    exchange: self.exchange,
+                    instrument,
+                    exchange_symbol: position.symbol.clone(),
+                    side: Some(
+                        if position.quantity > 0.0 {
+                            "LONG"
+                        } else {
+                            "SHORT"
+                        }
+                        .to_string(),
+                    ),
+                    size: position.quantity.abs().to_string(),
+                    entry_price: Some(position.entry_price.to_string()),
+                    mark_price: mark_price.map(|price| price.to_string()),
+                    unrealized_pnl: mark_price
+                        .map(|price| position.unrealized_pnl(price).to_string()),
+                    leverage: None,
+                    margin_mode: None,
+                    liquidation_price: None,
+                    raw: json!({
+                        "simulated": true,
+                        "positionAmt": position.quantity,
+                    }),
+                })
+            })
+            .collect())
+    }
+    async fn cancel_order(
+        &self,
+        exchange: ExchangeId,
+        request: CancelOrderRequest,
+    ) -> Result<OrderAck> {
+        self.ensure_exchange(exchange)?;
+        self.cancel(
+            &request.instrument,
+            request.order_id.as_deref(),
+            request.client_order_id.as_deref(),
+        )
+    }
+    async fn cancel_protective_order(
+        &self,
+        exchange: ExchangeId,
+        request: CancelOrderRequest,
+    ) -> Result<OrderAck> {
+        self.ensure_exchange(exchange)?;
+        self.cancel(
+            &request.instrument,
+            request.order_id.as_deref(),
+            request.client_order_id.as_deref(),
+        )
+    }
+}
+fn sim_side(side: OrderSide) -> SimSide {
+    match side {
+        OrderSide::Buy => SimSide::Buy,
+        OrderSide::Sell => SimSide::Sell,
+    }
+}
+fn sim_time_in_force(time_in_force: TimeInForce) -> SimTimeInForce {
+    if time_in_force == TimeInForce::PostOnly {
+        SimTimeInForce::PostOnly
+    } else if time_in_force == TimeInForce::Ioc {
+        SimTimeInForce::Ioc
+    } else if time_in_force == TimeInForce::Fok {
+        SimTimeInForce::Fok
+    } else {
+        SimTimeInForce::Gtc
+    }
+}
+fn parse_decimal(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|parsed| parsed.is_finite() && *parsed > 0.0)
+}
+fn side_text(side: SimSide) -> &'static str {
+    match side {
+        SimSide::Buy => "BUY",
+        SimSide::Sell => "SELL",
+    }
+}
+fn order_kind_text(kind: SimOrderKind) -> &'static str {
+    match kind {
+        SimOrderKind::Market => "MARKET",
+        SimOrderKind::Limit => "LIMIT",
+        SimOrderKind::StopMarket => "STOP_MARKET",
+    }
+}
+fn order_status_text(status: SimOrderStatus) -> &'static str {
+    match status {
+        SimOrderStatus::Open => "NEW",
+        SimOrderStatus::Filled => "FILLED",
+        SimOrderStatus::Cancelled => "CANCELED",
+    }
+}
+fn order_raw(order: &SimOrder) -> serde_json::Value {
+    json!({
+        "simulated": true,
+        "orderId": order.order_id,
+        "clientOrderId": order.client_order_id,
+        "status": order_status_text(order.status),
+        "reduceOnly": order.reduce_only,
+        "closePosition": order.close_position,
+        "stopPrice": if order.kind == SimOrderKind::StopMarket { order.price } else { None },
+        "executedQty": order.filled_quantity,
+        "avgPrice": order.average_price,
+    })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn instrument() -> Instrument {
+        Instrument::perp("ETH", "USDT").with_settlement("USDT")
+    }
+    fn market_entry(side: OrderSide, size: &str, stop_loss: Option<&str>) -> OrderPlacementRequest {
+        OrderPlacementRequest {
+            exchange: ExchangeId::Binance,
+            instrument: instrument(),
+            side,
+            order_type: OrderType::Market,
+            size: size.to_string(),
+            price: None,
+            margin_mode: None,
+            margin_coin: None,
+            position_side: None,
+            trade_side: None,
+            client_order_id: Some("rqentry1".to_string()),
+            reduce_only: None,
+            time_in_force: None,
+            attached_stop_loss_price: stop_loss.map(str::to_string),
+        }
+    }
+    fn reduce_only_limit(side: OrderSide, size: &str, price: &str) -> OrderPlacementRequest {
+        OrderPlacementRequest {
+            order_type: OrderType::Limit,
+            price: Some(price.to_string()),
+            client_order_id: Some("rqtp1".to_string()),
+            reduce_only: Some(true),
+            attached_stop_loss_price: None,
+            ..market_entry(side, size, None)
+        }
+    }
+    fn api_code(error: Error) -> String {
+        match error {
+            Error::Api { code, .. } => code,
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+    #[tokio::test]
+    async fn entry_protect_take_profit_and_reconcile_offline() {
+        let exchange =
+            SimulatedExchange::new(ExchangeId::Binance, 1_000.0).with_fee_rates(0.0002, 0.0005);
+        let gateway: &dyn ExchangeGateway = &exchange;
+        exchange.set_price(&instrument(), 2_000.0);
+        let ack = gateway
+            .place_order(market_entry(OrderSide::Buy, "0.5", None))
+            .await
+            .unwrap();
+        assert_eq!(ack.status.as_deref(), Some("FILLED"));
+        assert_eq!(ack.exchange_symbol, "ETHUSDT");
+        let stop = ProtectiveOrderRequest::stop_market(instrument(), OrderSide::Sell, "1900")
+            .with_close_position(true)
+            .with_client_order_id("rqsl1".to_string());
+        gateway
+            .place_protective_order(ExchangeId::Binance, stop)
+            .await
+            .unwrap();
+        gateway
+            .place_order(reduce_only_limit(OrderSide::Sell, "0.5", "2100"))
+            .await
+            .unwrap();
+        let open = gateway
+            .open_orders(
+                ExchangeId::Binance,
+                OrderListQuery::for_instrument(instrument()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(open.len(), 2);
+        let fills = exchange.replay_prices(&instrument(), [2_050.0, 1_950.0, 2_120.0]);
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].price.as_deref(), Some("2100"));
+        assert_eq!(fills[0].role.as_deref(), Some("maker"));
+        let positions = gateway.positions(ExchangeId::Binance, None).await.unwrap();
+        assert!(positions.is_empty());
+        let open = gateway
+            .open_orders(ExchangeId::Binance, OrderListQuery::new())
+            .await
+            .unwrap();
+        assert!(open.is_empty(), "止盈成交后止损应随仓位归零撤销");
+        let stop = gateway
+            .protective_order(
+                ExchangeId::Binance,
+                ProtectiveOrderQuery::by_client_order_id(instrument(), "rqsl1".to_string()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stop.status.as_deref(), Some("CANCELED"));
+        let entry_fills = gateway
+            .fills(
+                ExchangeId::Binance,
+                FillListQuery::for_instrument(instrument())
+                    .with_order_id(ack.order_id.as_deref().unwrap()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(entry_fills.len(), 1);
+        assert_eq!(entry_fills[0].role.as_deref(), Some("taker"));
+        let balances = gateway.balances(ExchangeId::Binance).await.unwrap();
+        let wallet: f64 = balances[0].total.parse().unwrap();
+        let expected = 1_000.0 + 50.0 - 2_000.0 * 0.5 * 0.0005 - 2_100.0 * 0.5 * 0.0002;
+        assert!((wallet - expected).abs() < 1e-9);
+    }
+    #[tokio::test]
+    async fn attached_stop_loss_triggers_on_price_path() {
+        let exchange = SimulatedExchange::new(ExchangeId::Binance, 1_000.0);
+        let gateway: &dyn ExchangeGateway = &exchange;
+        exchange.set_price(&instrument(), 2_000.0);
+        gateway
+            .place_order(market_entry(OrderSide::Sell, "1", Some("2050")))
+            .await
+            .unwrap();
+        let positions = gateway
+            .positions(ExchangeId::Binance, Some(&instrument()))
+            .await
+            .unwrap();
+        assert_eq!(positions[0].side.as_deref(), Some("SHORT"));
+        assert_eq!(positions[0].size, "1");
+        let fills = exchange.replay_prices(&instrument(), [2_020.0, 2_060.0]);
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].side.as_deref(), Some("BUY"));
+        assert_eq!(fills[0].price.as_deref(), Some("2060"));
+        assert!(gateway
+            .positions(ExchangeId::Binance, None)
+            .await
+            .unwrap()
+            .is_empty());
+        let history = gateway
+            .order_history(ExchangeId::Binance, OrderListQuery::new())
+            .await
+            .unwrap();
+        assert_eq!(history[0].order_type.as_deref(), Some("STOP_MARKET"));
+        assert_eq!(history[0].client_order_id.as_deref(), Some("rqentry1-sl"));
+    }
+    #[tokio::test]
+    async fn rejections_use_exchange_error_codes() {
+        let exchange = SimulatedExchange::new(ExchangeId::Binance, 1_000.0);
+        let gateway: &dyn ExchangeGateway = &exchange;
+        exchange.set_price(&instrument(), 2_000.0);
+        let error = gateway
+            .place_order(reduce_only_limit(OrderSide::Sell, "1", "2100"))
+            .await
+            .unwrap_err();
+        assert_eq!(api_code(error), "-2022");
+        gateway
+            .place_order(market_entry(OrderSide::Buy, "1", None))
+            .await
+            .unwrap();
+        let stop = ProtectiveOrderRequest::stop_market(instrument(), OrderSide::Sell, "2010")
+            .with_close_position(true);
+        let error = gateway
+            .place_protective_order(ExchangeId::Binance, stop)
+            .await
+            .unwrap_err();
+        assert_eq!(api_code(error), "-2021");
+        let error = gateway
+            .cancel_protective_order(
+                ExchangeId::Binance,
+                CancelOrderRequest::by_client_order_id(instrument(), "missing".to_string()),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(api_code(error), "-2011");
+        let error = gateway.balances(ExchangeId::Okx).await.unwrap_err();
+        assert!(matches!(error, Error::Unsupported { .. }));
+    }
+}
@@ -0,0 +1,774 @@
+//! 模拟交易所的内存撮合引擎。
+//!
+//! 价格由调用方按脚本逐个推送，引擎在每个价格点撮合挂单：限价单触价按委托价以 maker 成交，
+//! 条件市价单触发后按当前价以 taker 成交，市价单按最新价立即成交。账户按单向净持仓记账，
+//! 仓位归零时自动撤销该标的剩余的只减仓挂单（附带止损、止盈等）。
+//!
+//! 引擎不依赖交易所 SDK 类型，`SimulatedExchange` 负责与网关接口之间的字段映射。
+use std::collections::HashMap;
+use std::fmt;
+/// 数量低于该值视为零，吸收浮点误差。
+const QUANTITY_EPSILON: f64 = 1e-12;
+/// 买卖方向。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimSide {
+    Buy,
+    Sell,
+}
+impl SimSide {
+    /// 买入为正、卖出为负的持仓变化方向。
+    fn sign(self) -> f64 {
+        match self {
+            SimSide::Buy => 1.0,
+            SimSide::Sell => -1.0,
+        }
+    }
+}
+/// 委托类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimOrderKind {
+    /// 按最新价立即成交。
+    Market,
+    /// 按委托价或更优价格成交。
+    Limit,
+    /// 价格触及触发价后按当前价成交。
+    StopMarket,
+}
+/// 委托有效期。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SimTimeInForce {
+    #[default]
+    Gtc,
+    Ioc,
+    Fok,
+    PostOnly,
+}
+/// 委托状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimOrderStatus {
+    /// 挂单中。
+    Open,
+    /// 全部成交。
+    Filled,
+    /// 已撤销，含 IOC/FOK 未成交与仓位归零后撤销的只减仓单。
+    Cancelled,
+}
+/// 下单请求。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimOrderRequest {
+    /// 交易所标的代码。
+    pub symbol: String,
+    /// 买卖方向。
+    pub side: SimSide,
+    /// 委托类型。
+    pub kind: SimOrderKind,
+    /// 委托数量；`close_position` 为 true 时可为空，成交时按当时的持仓数量平仓。
+    pub quantity: Option<f64>,
+    /// 限价单委托价或条件单触发价。
+    pub price: Option<f64>,
+    /// 委托有效期。
+    pub time_in_force: SimTimeInForce,
+    /// 只减仓。
+    pub reduce_only: bool,
+    /// 全部平仓。
+    pub close_position: bool,
+    /// 客户端委托 ID，同一引擎内不可重复。
+    pub client_order_id: Option<String>,
+    /// 开仓成交后附带挂出的止损触发价。
+    pub attached_stop_loss: Option<f64>,
+}
+impl SimOrderRequest {
+    /// 创建 GTC 委托，其余选项按默认关闭。
+    pub fn new(symbol: impl Into<String>, side: SimSide, kind: SimOrderKind) -> Self {
+        Self {
+            symbol: symbol.into(),
+            side,
+            kind,
+            quantity: None,
+            price: None,
+            time_in_force: SimTimeInForce::Gtc,
+            reduce_only: false,
+            close_position: false,
+            client_order_id: None,
+            attached_stop_loss: None,
+        }
+    }
+}
+/// 引擎内的委托记录。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimOrder {
+    /// 交易所委托 ID。
+    pub order_id: String,
+    /// 客户端委托 ID。
+    pub client_order_id: Option<String>,
+    /// 交易所标的代码。
+    pub symbol: String,
+    /// 买卖方向。
+    pub side: SimSide,
+    /// 委托类型。
+    pub kind: SimOrderKind,
+    /// 限价单委托价或条件单触发价。
+    pub price: Option<f64>,
+    /// 委托数量；全部平仓的条件单在成交前为 0。
+    pub quantity: f64,
+    /// 已成交数量。
+    pub filled_quantity: f64,
+    /// 成交均价。
+    pub average_price: Option<f64>,
+    /// 委托状态。
+    pub status: SimOrderStatus,
+    /// 只减仓。
+    pub reduce_only: bool,
+    /// 全部平仓。
+    pub close_position: bool,
+    /// 开仓成交后附带挂出的止损触发价。
+    pub attached_stop_loss: Option<f64>,
+    /// 创建时间，引擎时钟毫秒。
+    pub created_at: u64,
+    /// 最近更新时间，引擎时钟毫秒。
+    pub updated_at: u64,
+}
+/// 单笔成交。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimFill {
+    /// 成交 ID。
+    pub trade_id: String,
+    /// 所属委托 ID。
+    pub order_id: String,
+    /// 交易所标的代码。
+    pub symbol: String,
+    /// 买卖方向。
+    pub side: SimSide,
+    /// 成交价。
+    pub price: f64,
+    /// 成交数量。
+    pub quantity: f64,
+    /// 手续费，计价币种。
+    pub fee: f64,
+    /// 是否为 maker 成交。
+    pub maker: bool,
+    /// 本笔成交实现的盈亏，不含手续费。
+    pub realized_pnl: f64,
+    /// 成交时间，引擎时钟毫秒。
+    pub timestamp: u64,
+}
+/// 单向净持仓。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimPosition {
+    /// 交易所标的代码。
+    pub symbol: String,
+    /// 持仓数量，多头为正、空头为负。
+    pub quantity: f64,
+    /// 持仓均价。
+    pub entry_price: f64,
+}
+impl SimPosition {
+    /// 按标记价计算的未实现盈亏。
+    pub fn unrealized_pnl(&self, mark_price: f64) -> f64 {
+        (mark_price - self.entry_price) * self.quantity
+    }
+}
+/// 拒单原因。
+///
+/// 错误码沿用 Binance USDⓈ-M 口径，使执行链路现有的错误分类可直接复用。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimReject {
+    /// 标的尚无价格，无法撮合市价单。
+    NoMarketPrice,
+    /// 数量缺失或非正数。
+    InvalidQuantity,
+    /// 限价单或条件单缺少有效价格。
+    InvalidPrice,
+    /// 只减仓委托没有可减少的持仓。
+    ReduceOnlyRejected,
+    /// Post-only 委托会立即吃单。
+    PostOnlyWouldTake,
+    /// 条件单下单时已满足触发条件。
+    WouldImmediatelyTrigger,
+    /// 客户端委托 ID 重复。
+    DuplicateClientOrderId,
+    /// 撤单时委托不存在或已不在挂单中。
+    UnknownOrder,
+    /// 查询的委托不存在。
+    OrderNotFound,
+}
+impl SimReject {
+    /// 交易所错误码。
+    pub fn code(self) -> &'static str {
+        match self {
+            SimReject::NoMarketPrice => "-1003",
+            SimReject::InvalidQuantity => "-4003",
+            SimReject::InvalidPrice => "-4014",
+            SimReject::ReduceOnlyRejected => "-2022",
+            SimReject::PostOnlyWouldTake => "-5022",
+            SimReject::WouldImmediatelyTrigger => "-2021",
+            SimReject::DuplicateClientOrderId => "-4116",
+            SimReject::UnknownOrder => "-2011",
+            SimReject::OrderNotFound => "-2013",
+        }
+    }
+}
+impl fmt::Display for SimReject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            SimReject::NoMarketPrice => "no market price for symbol",
+            SimReject::InvalidQuantity => "quantity must be positive",
+            SimReject::InvalidPrice => "price must be positive",
+            SimReject::ReduceOnlyRejected => "ReduceOnly Order is rejected.",
+            SimReject::PostOnlyWouldTake => "Post Only order will be rejected.",
+            SimReject::WouldImmediatelyTrigger => "Order would immediately trigger.",
+            SimReject::DuplicateClientOrderId => "ClientOrderId is duplicated.",
+            SimReject::UnknownOrder => "Unknown order sent.",
+            SimReject::OrderNotFound => "Order does not exist.",
+        };
+        f.write_str(message)
+    }
+}
+/// 内存撮合引擎。
+#[derive(Debug, Clone)]
+pub struct MatchingEngine {
+    clock_ms: u64,
+    next_order_id: u64,
+    next_trade_id: u64,
+    maker_fee_rate: f64,
+    taker_fee_rate: f64,
+    cash: f64,
+    prices: HashMap<String, f64>,
+    orders: Vec<SimOrder>,
+    fills: Vec<SimFill>,
+    positions: HashMap<String, SimPosition>,
+}
+impl MatchingEngine {
+    /// 以初始现金余额创建引擎，默认不收手续费。
+    pub fn new(initial_cash: f64) -> Self {
+        Self {
+            clock_ms: 0,
+            next_order_id: 1,
+            next_trade_id: 1,
+            maker_fee_rate: 0.0,
+            taker_fee_rate: 0.0,
+            cash: initial_cash,
+            prices: HashMap::new(),
+            orders: Vec::new(),
+            fills: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+    /// 设置 maker/taker 手续费率。
+    pub fn with_fee_rates(mut self, maker_fee_rate: f64, taker_fee_rate: f64) -> Self {
+        self.maker_fee_rate = maker_fee_rate;
+        self.taker_fee_rate = taker_fee_rate;
+        self
+    }
+    /// 引擎时钟，毫秒。
+    pub fn now_ms(&self) -> u64 {
+        self.clock_ms
+    }
+    /// 拨动引擎时钟；时钟只允许前进。
+    pub fn set_clock_ms(&mut self, clock_ms: u64) {
+        self.clock_ms = self.clock_ms.max(clock_ms);
+    }
+    /// 标的最新价。
+    pub fn mark_price(&self, symbol: &str) -> Option<f64> {
+        self.prices.get(symbol).copied()
+    }
+    /// 现金余额：初始资金加已实现盈亏减手续费。
+    pub fn cash(&self) -> f64 {
+        self.cash
+    }
+    /// 按最新价计算的全部持仓未实现盈亏。
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.positions
+            .values()
+            .filter_map(|position| {
+                self.mark_price(&position.symbol)
+                    .map(|mark| position.unrealized_pnl(mark))
+            })
+            .sum()
+    }
+    /// 推送标的最新价并撮合该标的挂单，返回本次产生的成交。
+    ///
+    /// 时钟前进 1 毫秒，保证同一价格点上的事件时间有序。
+    pub fn update_price(&mut self, symbol: &str, price: f64) -> Vec<SimFill> {
+        self.clock_ms += 1;
+        self.prices.insert(symbol.to_string(), price);
+        let first_fill = self.fills.len();
+        for index in 0..self.orders.len() {
+            let order = &self.orders[index];
+            if order.status != SimOrderStatus::Open || order.symbol != symbol {
+                continue;
+            }
+            let Some(order_price) = order.price else {
+                continue;
+            };
+            let fill = match (order.kind, order.side) {
+                (SimOrderKind::Limit, SimSide::Buy) => {
+                    (price <= order_price).then_some((order_price, true))
+                }
+                (SimOrderKind::Limit, SimSide::Sell) => {
+                    (price >= order_price).then_some((order_price, true))
+                }
+                (SimOrderKind::StopMarket, SimSide::Buy) => {
+                    (price >= order_price).then_some((price, false))
+                }
+                (SimOrderKind::StopMarket, SimSide::Sell) => {
+                    (price <= order_price).then_some((price, false))
+                }
+                (SimOrderKind::Market, _) => Some((price, false)),
+            };
+            if let Some((fill_price, maker)) = fill {
+                self.execute(index, fill_price, maker);
+            }
+        }
+        self.fills[first_fill..].to_vec()
+    }
+    /// 提交委托；市价单与穿价限价单立即成交，其余委托挂单等待价格推送。
+    pub fn submit(&mut self, request: SimOrderRequest) -> Result<SimOrder, SimReject> {
+        if let Some(client_order_id) = request.client_order_id.as_deref() {
+            if self
+                .orders
+                .iter()
+                .any(|order| order.client_order_id.as_deref() == Some(client_order_id))
+            {
+                return Err(SimReject::DuplicateClientOrderId);
+            }
+        }
+        let quantity = match request.quantity {
+            Some(quantity) if quantity.is_finite() && quantity > 0.0 => quantity,
+            None if request.close_position => 0.0,
+            _ => return Err(SimReject::InvalidQuantity),
+        };
+        // 条件单与 Binance 一致在触发时才校验只减仓，允许开仓前预挂保护止损
+        if (request.reduce_only || request.close_position)
+            && request.kind != SimOrderKind::StopMarket
+            && self.reducible_quantity(&request.symbol, request.side) <= QUANTITY_EPSILON
+        {
+            return Err(SimReject::ReduceOnlyRejected);
+        }
+        let mark = self.mark_price(&request.symbol);
+        let price = request
+            .price
+            .filter(|price| price.is_finite() && *price > 0.0);
+        let immediate = match request.kind {
+            SimOrderKind::Market => Some(mark.ok_or(SimReject::NoMarketPrice)?),
+            SimOrderKind::Limit => {
+                let limit = price.ok_or(SimReject::InvalidPrice)?;
+                let marketable = mark.filter(|mark| match request.side {
+                    SimSide::Buy => limit >= *mark,
+                    SimSide::Sell => limit <= *mark,
+                });
+                if marketable.is_some() && request.time_in_force == SimTimeInForce::PostOnly {
+                    return Err(SimReject::PostOnlyWouldTake);
+                }
+                marketable
+            }
+            SimOrderKind::StopMarket => {
+                let trigger = price.ok_or(SimReject::InvalidPrice)?;
+                let triggered = mark.is_some_and(|mark| match request.side {
+                    SimSide::Buy => mark >= trigger,
+                    SimSide::Sell => mark <= trigger,
+                });
+                if triggered {
+                    return Err(SimReject::WouldImmediatelyTrigger);
+                }
+                None
+            }
+        };
+        let order_id = self.next_order_id.to_string();
+        self.next_order_id += 1;
+        self.orders.push(SimOrder {
+            order_id,
+            client_order_id: request.client_order_id,
+            symbol: request.symbol,
+            side: request.side,
+            kind: request.kind,
+            price: if request.kind == SimOrderKind::Market {
+                None
+            } else {
+                price
+            },
+            quantity,
+            filled_quantity: 0.0,
+            average_price: None,
+            status: SimOrderStatus::Open,
+            reduce_only: request.reduce_only,
+            close_position: request.close_position,
+            attached_stop_loss: request.attached_stop_loss,
+            created_at: self.clock_ms,
+            updated_at: self.clock_ms,
+        });
+        let index = self.orders.len() - 1;
+        match immediate {
+            // 穿价限价单与市价单都按最新价吃单成交
+            Some(fill_price) => self.execute(index, fill_price, false),
+            None if matches!(
+                request.time_in_force,
+                SimTimeInForce::Ioc | SimTimeInForce::Fok
+            ) =>
+            {
+                self.close_order(index, SimOrderStatus::Cancelled)
+            }
+            None => {}
+        }
+        Ok(self.orders[index].clone())
+    }
+    /// 撤销挂单；按委托 ID 优先匹配，其次按客户端委托 ID。
+    pub fn cancel(
+        &mut self,
+        symbol: &str,
+        order_id: Option<&str>,
+        client_order_id: Option<&str>,
+    ) -> Result<SimOrder, SimReject> {
+        let index = self
+            .order_index(symbol, order_id, client_order_id)
+            .filter(|index| self.orders[*index].status == SimOrderStatus::Open)
+            .ok_or(SimReject::UnknownOrder)?;
+        self.close_order(index, SimOrderStatus::Cancelled);
+        Ok(self.orders[index].clone())
+    }
+    /// 查询委托；按委托 ID 优先匹配，其次按客户端委托 ID。
+    pub fn order(
+        &self,
+        symbol: &str,
+        order_id: Option<&str>,
+        client_order_id: Option<&str>,
+    ) -> Option<&SimOrder> {
+        self.order_index(symbol, order_id, client_order_id)
+            .map(|index| &self.orders[index])
+    }
+    /// 挂单中的委托，可按标的过滤。
+    pub fn open_orders(&self, symbol: Option<&str>) -> Vec<&SimOrder> {
+        self.orders
+            .iter()
+            .filter(|order| order.status == SimOrderStatus::Open)
+            .filter(|order| matches_filter(symbol, &order.symbol))
+            .collect()
+    }
+    /// 已成交或已撤销的委托，可按标的过滤。
+    pub fn order_history(&self, symbol: Option<&str>) -> Vec<&SimOrder> {
+        self.orders
+            .iter()
+            .filter(|order| order.status != SimOrderStatus::Open)
+            .filter(|order| matches_filter(symbol, &order.symbol))
+            .collect()
+    }
+    /// 成交记录，可按标的与委托 ID 过滤。
+    pub fn fills(&self, symbol: Option<&str>, order_id: Option<&str>) -> Vec<&SimFill> {
+        self.fills
+            .iter()
+            .filter(|fill| matches_filter(symbol, &fill.symbol))
+            .filter(|fill| matches_filter(order_id, &fill.order_id))
+            .collect()
+    }
+    /// 非零持仓，可按标的过滤。
+    pub fn positions(&self, symbol: Option<&str>) -> Vec<&SimPosition> {
+        let mut positions: Vec<_> = self
+            .positions
+            .values()
+            .filter(|position| position.quantity.abs() > QUANTITY_EPSILON)
+            .filter(|position| matches_filter(symbol, &position.symbol))
+            .collect();
+        positions.sort_by(|left, right| left.symbol.cmp(&right.symbol));
+        positions
+    }
+    fn order_index(
+        &self,
+        symbol: &str,
+        order_id: Option<&str>,
+        client_order_id: Option<&str>,
+    ) -> Option<usize> {
+        self.orders.iter().position(|order| {
+            order.symbol == symbol
+                && match (order_id, client_order_id) {
+                    (Some(order_id), _) => order.order_id == order_id,
+                    (None, Some(client_order_id)) => {
+                        order.client_order_id.as_deref() == Some(client_order_id)
+                    }
+                    (None, None) => false,
+                }
+        })
+    }
+    /// 当前持仓中可被该方向委托减少的数量。
+    fn reducible_quantity(&self, symbol: &str, side: SimSide) -> f64 {
+        self.positions
+            .get(symbol)
+            .map(|position| position.quantity * side.sign())
+            .filter(|signed| *signed < 0.0)
+            .map_or(0.0, f64::abs)
+    }
+    fn close_order(&mut self, index: usize, status: SimOrderStatus) {
+        let clock_ms = self.clock_ms;
+        let order = &mut self.orders[index];
+        order.status = status;
+        order.updated_at = clock_ms;
+    }
+    /// 按成交价成交委托剩余数量；只减仓委托按当时持仓截断，无仓可减时撤单。
+    fn execute(&mut self, index: usize, price: f64, maker: bool) {
+        let order = self.orders[index].clone();
+        let mut quantity = order.quantity - order.filled_quantity;
+        if order.reduce_only || order.close_position {
+            let reducible = self.reducible_quantity(&order.symbol, order.side);
+            quantity = if order.close_position {
+                reducible
+            } else {
+                quantity.min(reducible)
+            };
+        }
+        if quantity <= QUANTITY_EPSILON {
+            self.close_order(index, SimOrderStatus::Cancelled);
+            return;
+        }
+        let realized_pnl = self.apply_position(&order.symbol, order.side, quantity, price);
+        let fee_rate = if maker {
+            self.maker_fee_rate
+        } else {
+            self.taker_fee_rate
+        };
+        let fee = price * quantity * fee_rate;
+        self.cash += realized_pnl - fee;
+        let trade_id = self.next_trade_id.to_string();
+        self.next_trade_id += 1;
+        self.fills.push(SimFill {
+            trade_id,
+            order_id: order.order_id.clone(),
+            symbol: order.symbol.clone(),
+            side: order.side,
+            price,
+            quantity,
+            fee,
+            maker,
+            realized_pnl,
+            timestamp: self.clock_ms,
+        });
+        let clock_ms = self.clock_ms;
+        let stored = &mut self.orders[index];
+        if stored.close_position {
+            stored.quantity = quantity;
+        }
+        stored.filled_quantity += quantity;
+        stored.average_price = Some(price);
+        stored.status = SimOrderStatus::Filled;
+        stored.updated_at = clock_ms;
+        if let Some(stop_price) = order
+            .attached_stop_loss
+            .filter(|_| !order.reduce_only && !order.close_position)
+        {
+            self.attach_stop_loss(&order, stop_price);
+        }
+        if self.reducible_quantity(&order.symbol, SimSide::Buy) <= QUANTITY_EPSILON
+            && self.reducible_quantity(&order.symbol, SimSide::Sell) <= QUANTITY_EPSILON
+        {
+            self.cancel_reduce_only_orders(&order.symbol);
+        }
+    }
+    /// 开仓成交后挂出全部平仓的止损条件单；触发价已失效时不挂单。
+    fn attach_stop_loss(&mut self, parent: &SimOrder, stop_price: f64) {
+        let side = match parent.side {
+            SimSide::Buy => SimSide::Sell,
+            SimSide::Sell => SimSide::Buy,
+        };
+        let mut request = SimOrderRequest::new(&parent.symbol, side, SimOrderKind::StopMarket);
+        request.price = Some(stop_price);
+        request.close_position = true;
+        request.client_order_id = parent
+            .client_order_id
+            .as_ref()
+            .map(|client_order_id| format!("{client_order_id}-sl"));
+        let _ = self.submit(request);
+    }
+    /// 仓位归零后撤销该标的剩余的只减仓挂单。
+    fn cancel_reduce_only_orders(&mut self, symbol: &str) {
+        for index in 0..self.orders.len() {
+            let order = &self.orders[index];
+            if order.status == SimOrderStatus::Open
+                && order.symbol == symbol
+                && (order.reduce_only || order.close_position)
+            {
+                self.close_order(index, SimOrderStatus::Cancelled);
+            }
+        }
+    }
+    /// 更新净持仓并返回本次实现的盈亏。
+    fn apply_position(&mut self, symbol: &str, side: SimSide, quantity: f64, price: f64) -> f64 {
+        let position = self
+            .positions
+            .entry(symbol.to_string())
+            .or_insert_with(|| SimPosition {
+                symbol: symbol.to_string(),
+                quantity: 0.0,
+                entry_price: 0.0,
+            });
+        let signed = side.sign() * quantity;
+        if position.quantity.abs() <= QUANTITY_EPSILON || position.quantity * signed > 0.0 {
+            let total = position.quantity.abs() + quantity;
+            position.entry_price =
+                (position.entry_price * position.quantity.abs() + price * quantity) / total;
+            position.quantity += signed;
+            return 0.0;
+        }
+        let closing = quantity.min(position.quantity.abs());
+        let realized = closing * (price - position.entry_price) * position.quantity.signum();
+        let previous_sign = position.quantity.signum();
+        position.quantity += signed;
+        if position.quantity.abs() <= QUANTITY_EPSILON {
+            position.quantity = 0.0;
+            position.entry_price = 0.0;
+        } else if position.quantity.signum() != previous_sign {
+            position.entry_price = price;
+        }
+        realized
+    }
+}
+/// 未指定过滤条件时视为匹配。
+fn matches_filter(filter: Option<&str>, value: &str) -> bool {
+    filter.is_none() || filter == Some(value)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const SYMBOL: &str = "BTCUSDT";
+    fn market(side: SimSide, quantity: f64) -> SimOrderRequest {
+        let mut request = SimOrderRequest::new(SYMBOL, side, SimOrderKind::Market);
+        request.quantity = Some(quantity);
+        request
+    }
+    fn limit(side: SimSide, quantity: f64, price: f64) -> SimOrderRequest {
+        let mut request = SimOrderRequest::new(SYMBOL, side, SimOrderKind::Limit);
+        request.quantity = Some(quantity);
+        request.price = Some(price);
+        request
+    }
+    fn engine_at(price: f64) -> MatchingEngine {
+        let mut engine = MatchingEngine::new(1_000.0).with_fee_rates(0.0002, 0.0005);
+        engine.update_price(SYMBOL, price);
+        engine
+    }
+    #[test]
+    fn market_entry_attaches_stop_loss_that_closes_on_trigger() {
+        let mut engine = engine_at(100.0);
+        let mut request = market(SimSide::Buy, 2.0);
+        request.client_order_id = Some("entry".to_string());
+        request.attached_stop_loss = Some(95.0);
+        let entry = engine.submit(request).unwrap();
+        assert_eq!(entry.status, SimOrderStatus::Filled);
+        assert_eq!(entry.average_price, Some(100.0));
+        let stop = engine.order(SYMBOL, None, Some("entry-sl")).unwrap();
+        assert_eq!(stop.kind, SimOrderKind::StopMarket);
+        assert_eq!(stop.side, SimSide::Sell);
+        assert!(stop.close_position);
+        assert_eq!(engine.positions(None)[0].quantity, 2.0);
+        assert!(engine.update_price(SYMBOL, 96.0).is_empty());
+        let fills = engine.update_price(SYMBOL, 94.0);
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].price, 94.0, "止损按触发后的当前价成交");
+        assert!(!fills[0].maker);
+        assert_eq!(fills[0].realized_pnl, -12.0);
+        assert!(engine.positions(None).is_empty());
+        let fees = 100.0 * 2.0 * 0.0005 + 94.0 * 2.0 * 0.0005;
+        assert!((engine.cash() - (1_000.0 - 12.0 - fees)).abs() < 1e-9);
+    }
+    #[test]
+    fn take_profit_fill_cancels_remaining_protective_orders() {
+        let mut engine = engine_at(100.0);
+        let mut request = market(SimSide::Buy, 1.0);
+        request.attached_stop_loss = Some(90.0);
+        engine.submit(request).unwrap();
+        let mut take_profit = limit(SimSide::Sell, 1.0, 110.0);
+        take_profit.reduce_only = true;
+        let take_profit = engine.submit(take_profit).unwrap();
+        assert_eq!(take_profit.status, SimOrderStatus::Open);
+        assert_eq!(engine.open_orders(Some(SYMBOL)).len(), 2);
+        let fills = engine.update_price(SYMBOL, 112.0);
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].price, 110.0, "限价单按委托价成交");
+        assert!(fills[0].maker);
+        assert!(engine.positions(None).is_empty());
+        assert!(
+            engine.open_orders(None).is_empty(),
+            "仓位归零后止损应被撤销"
+        );
+        let history = engine.order_history(Some(SYMBOL));
+        assert!(history
+            .iter()
+            .any(|order| order.kind == SimOrderKind::StopMarket
+                && order.status == SimOrderStatus::Cancelled));
+    }
+    #[test]
+    fn limit_orders_honour_time_in_force() {
+        let mut engine = engine_at(100.0);
+        let mut post_only = limit(SimSide::Buy, 1.0, 101.0);
+        post_only.time_in_force = SimTimeInForce::PostOnly;
+        let rejected = engine.submit(post_only).unwrap_err();
+        assert_eq!(rejected, SimReject::PostOnlyWouldTake);
+        assert_eq!(rejected.code(), "-5022");
+        let marketable = engine.submit(limit(SimSide::Buy, 1.0, 101.0)).unwrap();
+        assert_eq!(marketable.status, SimOrderStatus::Filled);
+        assert_eq!(
+            marketable.average_price,
+            Some(100.0),
+            "穿价限价单按最新价吃单"
+        );
+        let mut ioc = limit(SimSide::Buy, 1.0, 95.0);
+        ioc.time_in_force = SimTimeInForce::Ioc;
+        assert_eq!(
+            engine.submit(ioc).unwrap().status,
+            SimOrderStatus::Cancelled
+        );
+        let resting = engine.submit(limit(SimSide::Buy, 1.0, 95.0)).unwrap();
+        assert_eq!(resting.status, SimOrderStatus::Open);
+        let cancelled = engine
+            .cancel(SYMBOL, Some(&resting.order_id), None)
+            .unwrap();
+        assert_eq!(cancelled.status, SimOrderStatus::Cancelled);
+        assert_eq!(
+            engine.cancel(SYMBOL, Some(&resting.order_id), None),
+            Err(SimReject::UnknownOrder)
+        );
+    }
+    #[test]
+    fn stop_orders_and_reduce_only_are_validated() {
+        let mut engine = engine_at(100.0);
+        let mut reduce_only = market(SimSide::Sell, 1.0);
+        reduce_only.reduce_only = true;
+        assert_eq!(
+            engine.submit(reduce_only.clone()),
+            Err(SimReject::ReduceOnlyRejected)
+        );
+        engine.submit(market(SimSide::Buy, 1.0)).unwrap();
+        let mut stop = SimOrderRequest::new(SYMBOL, SimSide::Sell, SimOrderKind::StopMarket);
+        stop.price = Some(101.0);
+        stop.close_position = true;
+        let rejected = engine.submit(stop).unwrap_err();
+        assert_eq!(rejected.code(), "-2021");
+        // 只减仓数量超过持仓时按持仓截断，不会反手
+        reduce_only.quantity = Some(3.0);
+        let closed = engine.submit(reduce_only).unwrap();
+        assert_eq!(closed.filled_quantity, 1.0);
+        assert!(engine.positions(None).is_empty());
+        let mut duplicate = market(SimSide::Buy, 1.0);
+        duplicate.client_order_id = Some("dup".to_string());
+        engine.submit(duplicate.clone()).unwrap();
+        assert_eq!(
+            engine.submit(duplicate),
+            Err(SimReject::DuplicateClientOrderId)
+        );
+    }
+    #[test]
+    fn net_position_tracks_average_entry_and_flips() {
+        let mut engine = engine_at(100.0);
+        engine.submit(market(SimSide::Buy, 1.0)).unwrap();
+        engine.update_price(SYMBOL, 110.0);
+        engine.submit(market(SimSide::Buy, 1.0)).unwrap();
+        let position = engine.positions(Some(SYMBOL))[0].clone();
+        assert_eq!(position.quantity, 2.0);
+        assert_eq!(position.entry_price, 105.0);
+        assert_eq!(engine.unrealized_pnl(), 10.0);
+        engine.update_price(SYMBOL, 120.0);
+        let flip = engine.submit(market(SimSide::Sell, 3.0)).unwrap();
+        let fill = &engine.fills(None, Some(&flip.order_id))[0];
+        assert_eq!(fill.realized_pnl, 30.0);
+        let position = engine.positions(None)[0].clone();
+        assert_eq!(position.quantity, -1.0);
+        assert_eq!(position.entry_price, 120.0);
+    }
+}
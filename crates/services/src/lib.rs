@@ -50,7 +50,10 @@ pub mod rust_quan_web;
 pub mod strategy;
 pub mod trading;
 // 重新导出常用服务
-pub use exchange::{CryptoExcAllGateway, ExchangeApiService, OrderPlacementRequest};
+pub use exchange::{
+    CryptoExcAllGateway, ExchangeApiService, ExchangeGateway, OrderPlacementRequest,
+    SimulatedExchange,
+};
 pub use risk::RiskManagementService;
 pub use rust_quan_web::{
     ExecutionOrderTask, ExecutionTask, ExecutionTaskClient, ExecutionTaskConfig,
@@ -6,6 +6,7 @@
 //! - 依赖domain::traits::ExchangeAccount接口
 //! - 支持多交易所扩展
 use anyhow::Result;
+use rust_quant_domain::entities::BalanceSnapshot;
 use rust_quant_infrastructure::ExchangeFactory;
 use tracing::info;
 /// 账户服务
@@ -32,6 +33,22 @@ impl AccountService {
         info!("✅ 从交易所 {} 获取了账户余额", exchange.name());
         Ok(balances)
     }
+    /// 从交易所获取标准化账户余额快照
+    /// # Note
+    /// 各交易所余额已在适配器层统一解析，调用方无需再处理交易所 JSON
+    pub async fn fetch_balance_snapshot_from_exchange(
+        &self,
+        currency: Option<&str>,
+    ) -> Result<BalanceSnapshot> {
+        let exchange = ExchangeFactory::create_default_account()?;
+        let snapshot = exchange.fetch_balance_snapshot(currency).await?;
+        info!(
+            "✅ 从交易所 {} 获取了账户余额快照: {} 个币种",
+            exchange.name(),
+            snapshot.currencies.len()
+        );
+        Ok(snapshot)
+    }
     pub async fn fetch_all_balances(&self) -> Result<serde_json::Value> {
         self.fetch_balance_from_exchange(None).await
     }
@@ -0,0 +1,253 @@
+//! 文件 K线数据源
+//!
+//! `CANDLE_SOURCE=file` 时回测从 `CANDLE_FILE_DIR` 下的列式 K线文件读取，
+//! 笔记本和 CI 不需要连接 quant_core Postgres；同时提供分表与文件之间的导入导出。
+use anyhow::{anyhow, Result};
+use rust_quant_domain::Timeframe;
+use rust_quant_infrastructure::repositories::{
+    CandleColumns, FileCandleRepository, PostgresCandleRepository,
+};
+use rust_quant_market::models::{CandlesEntity, SelectTime, TimeDirect};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+/// 未设置 `CANDLE_FILE_DIR` 时的默认数据目录。
+pub const DEFAULT_CANDLE_FILE_DIR: &str = "data/candles";
+/// 导出时每页读取的行数，百万级 1m K 线约二十页。
+const CANDLE_EXPORT_PAGE_SIZE: usize = 50_000;
+/// K线数据源。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CandleSource {
+    /// quant_core Postgres 分表。
+    QuantCore,
+    /// 本地列式 K线文件目录，仅支持回测读取。
+    File(PathBuf),
+}
+/// 从 `CANDLE_SOURCE` / `CANDLE_FILE_DIR` 解析 K线数据源。
+pub fn candle_source_from_env() -> Result<CandleSource> {
+    parse_candle_source(
+        &std::env::var("CANDLE_SOURCE").unwrap_or_default(),
+        std::env::var("CANDLE_FILE_DIR").ok().as_deref(),
+    )
+}
+fn parse_candle_source(source: &str, file_dir: Option<&str>) -> Result<CandleSource> {
+    let source = source.trim().to_ascii_lowercase();
+    if source.is_empty() || matches!(source.as_str(), "quant_core" | "postgres" | "pg") {
+        return Ok(CandleSource::QuantCore);
+    }
+    if matches!(source.as_str(), "file" | "files") {
+        let dir = file_dir
+            .map(str::trim)
+            .filter(|dir| !dir.is_empty())
+            .unwrap_or(DEFAULT_CANDLE_FILE_DIR);
+        return Ok(CandleSource::File(PathBuf::from(dir)));
+    }
+    Err(anyhow!("不支持的 CANDLE_SOURCE: {}", source))
+}
+/// 从列式 K线文件读取回测数据，过滤口径与 quant_core 分表查询一致：
+/// 只取已确认 K 线，按 `select_time` 截取范围后保留最近 `limit` 根并按 `ts` 升序返回。
+pub(super) async fn get_file_candles_for_backtest(
+    dir: &Path,
+    inst_id: &str,
+    period: &str,
+    limit: usize,
+    select_time: Option<SelectTime>,
+) -> Result<Vec<CandlesEntity>> {
+    let timeframe =
+        Timeframe::from_str(period).map_err(|error| anyhow!("无效的K线周期: {}", error))?;
+    let repository = FileCandleRepository::new(dir);
+    let columns = repository
+        .read_columns(inst_id, timeframe)
+        .await?
+        .unwrap_or_default();
+    let candles = select_backtest_candles(&columns, limit, select_time.as_ref());
+    if candles.is_empty() {
+        return Err(anyhow!(
+            "K线数据为空: source=file dir={} inst_id={} period={}",
+            dir.display(),
+            inst_id,
+            period
+        ));
+    }
+    Ok(candles)
+}
+fn select_backtest_candles(
+    columns: &CandleColumns,
+    limit: usize,
+    select_time: Option<&SelectTime>,
+) -> Vec<CandlesEntity> {
+    let rows = match select_time {
+        None => 0..columns.len(),
+        Some(select_time) => match select_time.direct {
+            TimeDirect::BEFORE => columns.range_indices(
+                select_time.end_time.unwrap_or(i64::MIN),
+                select_time.start_time,
+            ),
+            TimeDirect::AFTER => columns.range_indices(
+                select_time.start_time,
+                select_time.end_time.unwrap_or(i64::MAX),
+            ),
+        },
+    };
+    let confirmed: Vec<usize> = rows.filter(|index| columns.confirmed[*index]).collect();
+    let skip = confirmed.len().saturating_sub(limit);
+    confirmed[skip..]
+        .iter()
+        .map(|index| column_row_to_entity(columns, *index))
+        .collect()
+}
+fn column_row_to_entity(columns: &CandleColumns, index: usize) -> CandlesEntity {
+    CandlesEntity {
+        id: None,
+        ts: columns.ts[index],
+        o: columns.open[index].to_string(),
+        h: columns.high[index].to_string(),
+        l: columns.low[index].to_string(),
+        c: columns.close[index].to_string(),
+        vol: columns.volume[index].to_string(),
+        vol_ccy: columns.volume_ccy[index].to_string(),
+        confirm: if columns.confirmed[index] { "1" } else { "0" }.to_string(),
+        created_at: None,
+        updated_at: None,
+    }
+}
+/// 单个序列的导入导出结果。
+#[derive(Debug, Clone, Serialize)]
+pub struct CandleTransferSummary {
+    pub inst_id: String,
+    pub period: String,
+    /// 从源端读取的行数。
+    pub rows_read: usize,
+    /// 目标端新增或内容变化的行数。
+    pub rows_written: usize,
+    /// 对应的列式 K线文件。
+    pub path: String,
+}
+/// 把 quant_core 分表的 `[start_time, end_time]` 区间按 `ts` 分页导出到列式文件，已有文件按 `ts` 合并。
+pub async fn export_candles_to_file(
+    postgres: &PostgresCandleRepository,
+    files: &FileCandleRepository,
+    inst_id: &str,
+    timeframe: Timeframe,
+    start_time: Option<i64>,
+    end_time: Option<i64>,
+) -> Result<CandleTransferSummary> {
+    let mut columns = CandleColumns::default();
+    let mut after_ts = start_time.map(|ts| ts.saturating_sub(1));
+    loop {
+        let page = postgres
+            .fetch_candle_columns(
+                inst_id,
+                timeframe,
+                after_ts,
+                end_time,
+                CANDLE_EXPORT_PAGE_SIZE,
+            )
+            .await?;
+        let page_len = page.len();
+        after_ts = page.ts.last().copied().or(after_ts);
+        columns.append(&page)?;
+        if page_len < CANDLE_EXPORT_PAGE_SIZE {
+            break;
+        }
+    }
+    let rows_read = columns.len();
+    let rows_written = files.write_columns(inst_id, timeframe, columns).await?;
+    Ok(CandleTransferSummary {
+        inst_id: inst_id.to_string(),
+        period: timeframe.as_str().to_string(),
+        rows_read,
+        rows_written,
+        path: files.file_path(inst_id, timeframe)?.display().to_string(),
+    })
+}
+/// 把列式文件中 `[start_time, end_time]` 区间导入 quant_core 分表，冲突行按内容 UPSERT。
+pub async fn import_candles_from_file(
+    files: &FileCandleRepository,
+    postgres: &PostgresCandleRepository,
+    inst_id: &str,
+    timeframe: Timeframe,
+    start_time: Option<i64>,
+    end_time: Option<i64>,
+) -> Result<CandleTransferSummary> {
+    let path = files.file_path(inst_id, timeframe)?;
+    let columns = files
+        .read_columns(inst_id, timeframe)
+        .await?
+        .ok_or_else(|| anyhow!("列式 K线文件不存在: {}", path.display()))?;
+    let rows = columns.range_indices(start_time.unwrap_or(i64::MIN), end_time.unwrap_or(i64::MAX));
+    let selected = columns.slice(rows);
+    let rows_written = postgres
+        .save_candle_columns(inst_id, timeframe, &selected)
+        .await?;
+    Ok(CandleTransferSummary {
+        inst_id: inst_id.to_string(),
+        period: timeframe.as_str().to_string(),
+        rows_read: selected.len(),
+        rows_written,
+        path: path.display().to_string(),
+    })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn columns() -> CandleColumns {
+        let mut columns = CandleColumns::default();
+        for (ts, confirmed) in [(1, true), (2, true), (3, false), (4, true), (5, true)] {
+            columns
+                .push(ts, [10.0, 11.0, 9.5, 10.5, 3.0, 30.0], confirmed)
+                .unwrap();
+        }
+        columns
+    }
+    #[test]
+    fn parse_candle_source_supports_file_dir() {
+        assert_eq!(
+            parse_candle_source("", None).unwrap(),
+            CandleSource::QuantCore
+        );
+        assert_eq!(
+            parse_candle_source(" PG ", Some("ignored")).unwrap(),
+            CandleSource::QuantCore
+        );
+        assert_eq!(
+            parse_candle_source("file", None).unwrap(),
+            CandleSource::File(PathBuf::from(DEFAULT_CANDLE_FILE_DIR))
+        );
+        assert_eq!(
+            parse_candle_source("files", Some("/data/ci")).unwrap(),
+            CandleSource::File(PathBuf::from("/data/ci"))
+        );
+        assert!(parse_candle_source("mysql", None).is_err());
+    }
+    #[test]
+    fn backtest_selection_matches_quant_core_query() {
+        let columns = columns();
+        let all = select_backtest_candles(&columns, 3, None);
+        assert_eq!(all.iter().map(|c| c.ts).collect::<Vec<_>>(), vec![2, 4, 5]);
+        assert!(all.iter().all(|c| c.confirm == "1"));
+        assert_eq!(all[0].vol, "3");
+        assert_eq!(all[0].vol_ccy, "30");
+        assert_eq!(all[0].o, "10");
+        let before = SelectTime {
+            start_time: 4,
+            end_time: Some(2),
+            direct: TimeDirect::BEFORE,
+        };
+        let selected = select_backtest_candles(&columns, 10, Some(&before));
+        assert_eq!(
+            selected.iter().map(|c| c.ts).collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        let after = SelectTime {
+            start_time: 2,
+            end_time: None,
+            direct: TimeDirect::AFTER,
+        };
+        let selected = select_backtest_candles(&columns, 2, Some(&after));
+        assert_eq!(
+            selected.iter().map(|c| c.ts).collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+    }
+}
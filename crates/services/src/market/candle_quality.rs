@@ -0,0 +1,576 @@
+//! K线数据质量巡检
+//!
+//! 扫描 quant_core K线分表中的缺口、重复、连续零成交量、OHLC 不一致、遗留未确认 K 线和
+//! 异常影线，结果写入 `candle_quality_findings`。修复由 `CandlesJob` 编排重新拉取；
+//! 回测可以据此拒绝在存在未解决问题的区间上运行。
+use anyhow::{anyhow, Context, Result};
+use rust_quant_domain::{Candle, Timeframe};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{FromRow, PgPool};
+use std::str::FromStr;
+/// 异常影线判定至少需要的历史 K 线数量，样本太少时中位数不稳定。
+const MIN_OUTLIER_WICK_SAMPLES: usize = 10;
+/// 回测拒绝信息中最多列出的问题数。
+const MAX_BLOCKING_FINDINGS_IN_ERROR: usize = 5;
+/// K线数据质量问题类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandleQualityIssue {
+    /// 相邻 K 线之间缺少一根或多根。
+    Gap,
+    /// 同一 `ts` 出现多次。
+    Duplicate,
+    /// 连续多根成交量为 0。
+    ZeroVolumeRun,
+    /// 最高/最低价与开收盘价矛盾，如 `h < max(o, c)`。
+    OhlcInconsistent,
+    /// 已经收盘却仍未确认的 K 线。
+    StaleUnconfirmed,
+    /// 影线长度远超近期振幅中位数。
+    OutlierWick,
+}
+impl CandleQualityIssue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gap => "gap",
+            Self::Duplicate => "duplicate",
+            Self::ZeroVolumeRun => "zero_volume_run",
+            Self::OhlcInconsistent => "ohlc_inconsistent",
+            Self::StaleUnconfirmed => "stale_unconfirmed",
+            Self::OutlierWick => "outlier_wick",
+        }
+    }
+    /// 重复行由分表 `UNIQUE (ts)` 兜底，重新拉取无法修复；其他问题都可以尝试用交易所数据覆盖。
+    pub fn is_repairable(&self) -> bool {
+        !matches!(self, Self::Duplicate)
+    }
+}
+impl FromStr for CandleQualityIssue {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "gap" => Ok(Self::Gap),
+            "duplicate" => Ok(Self::Duplicate),
+            "zero_volume_run" => Ok(Self::ZeroVolumeRun),
+            "ohlc_inconsistent" => Ok(Self::OhlcInconsistent),
+            "stale_unconfirmed" => Ok(Self::StaleUnconfirmed),
+            "outlier_wick" => Ok(Self::OutlierWick),
+            other => Err(anyhow!("未知的K线质量问题类型: {}", other)),
+        }
+    }
+}
+/// 单条K线数据质量问题，`[start_ts, end_ts]` 为受影响 K 线的开盘时间闭区间。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandleQualityFinding {
+    pub inst_id: String,
+    pub period: String,
+    pub issue: CandleQualityIssue,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    /// 受影响的 K 线根数；缺口为缺失根数。
+    pub bar_count: i64,
+    pub detail: String,
+}
+/// 巡检阈值。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandleQualityConfig {
+    /// 连续零成交量达到该根数才记为问题，单根零成交量在冷门合约上很常见。
+    #[serde(default = "default_min_zero_volume_run")]
+    pub min_zero_volume_run: usize,
+    /// 计算振幅中位数的回看根数。
+    #[serde(default = "default_outlier_wick_window")]
+    pub outlier_wick_window: usize,
+    /// 影线超过振幅中位数的倍数即记为异常。
+    #[serde(default = "default_outlier_wick_multiple")]
+    pub outlier_wick_multiple: f64,
+}
+fn default_min_zero_volume_run() -> usize {
+    3
+}
+fn default_outlier_wick_window() -> usize {
+    50
+}
+fn default_outlier_wick_multiple() -> f64 {
+    8.0
+}
+impl Default for CandleQualityConfig {
+    fn default() -> Self {
+        Self {
+            min_zero_volume_run: default_min_zero_volume_run(),
+            outlier_wick_window: default_outlier_wick_window(),
+            outlier_wick_multiple: default_outlier_wick_multiple(),
+        }
+    }
+}
+impl CandleQualityConfig {
+    pub fn with_min_zero_volume_run(mut self, bars: usize) -> Self {
+        self.min_zero_volume_run = bars.max(1);
+        self
+    }
+    pub fn with_outlier_wick_window(mut self, bars: usize) -> Self {
+        self.outlier_wick_window = bars;
+        self
+    }
+    pub fn with_outlier_wick_multiple(mut self, multiple: f64) -> Self {
+        self.outlier_wick_multiple = multiple;
+        self
+    }
+}
+/// K 线周期对应的毫秒数；自然月长度不固定，返回 `None` 时跳过缺口与过期判断。
+fn timeframe_millis(timeframe: Timeframe) -> Option<i64> {
+    match timeframe {
+        Timeframe::MN1 => None,
+        other => Some(other.to_minutes() * 60_000),
+    }
+}
+/// 按相邻元素关系切分连续片段（等价于 `slice::chunk_by`，兼容 MSRV 1.75）。
+fn runs_by<T>(items: &[T], same: impl Fn(&T, &T) -> bool) -> Vec<&[T]> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for index in 1..=items.len() {
+        if index == items.len() || !same(&items[index - 1], &items[index]) {
+            if index > start {
+                runs.push(&items[start..index]);
+            }
+            start = index;
+        }
+    }
+    runs
+}
+/// 扫描一段 K 线序列；输入无需预先排序，`now_ms` 用于判断最后一根未确认 K 线是否已经过期。
+pub fn scan_candle_quality(
+    inst_id: &str,
+    timeframe: Timeframe,
+    candles: &[Candle],
+    config: &CandleQualityConfig,
+    now_ms: i64,
+) -> Vec<CandleQualityFinding> {
+    let period = timeframe.as_str();
+    let finding = |issue, start_ts, end_ts, bar_count, detail: String| CandleQualityFinding {
+        inst_id: inst_id.to_string(),
+        period: period.to_string(),
+        issue,
+        start_ts,
+        end_ts,
+        bar_count,
+        detail,
+    };
+    let mut sorted: Vec<&Candle> = candles.iter().collect();
+    sorted.sort_by_key(|candle| candle.timestamp);
+    let mut findings = Vec::new();
+    let mut unique: Vec<&Candle> = Vec::with_capacity(sorted.len());
+    for group in runs_by(&sorted, |a, b| a.timestamp == b.timestamp) {
+        if group.len() > 1 {
+            findings.push(finding(
+                CandleQualityIssue::Duplicate,
+                group[0].timestamp,
+                group[0].timestamp,
+                group.len() as i64,
+                format!("同一 ts 出现 {} 次", group.len()),
+            ));
+        }
+        unique.push(group[group.len() - 1]);
+    }
+    let period_ms = timeframe_millis(timeframe);
+    if let Some(period_ms) = period_ms {
+        for pair in unique.windows(2) {
+            let diff = pair[1].timestamp - pair[0].timestamp;
+            if diff <= period_ms {
+                continue;
+            }
+            let missing = diff / period_ms - 1;
+            let detail = if diff % period_ms == 0 {
+                format!("缺少 {} 根K线", missing)
+            } else {
+                format!("相邻K线间隔 {}ms 不是周期 {}ms 的整数倍", diff, period_ms)
+            };
+            findings.push(finding(
+                CandleQualityIssue::Gap,
+                pair[0].timestamp + period_ms,
+                (pair[1].timestamp - period_ms).max(pair[0].timestamp + period_ms),
+                missing.max(1),
+                detail,
+            ));
+        }
+    }
+    for run in runs_by(&unique, |a, b| {
+        (a.volume.value() == 0.0) == (b.volume.value() == 0.0)
+    }) {
+        if run[0].volume.value() == 0.0 && run.len() >= config.min_zero_volume_run {
+            findings.push(finding(
+                CandleQualityIssue::ZeroVolumeRun,
+                run[0].timestamp,
+                run[run.len() - 1].timestamp,
+                run.len() as i64,
+                format!("连续 {} 根K线成交量为 0", run.len()),
+            ));
+        }
+    }
+    let last_index = unique.len().saturating_sub(1);
+    for (index, candle) in unique.iter().enumerate() {
+        let (open, high, low, close) = (
+            candle.open.value(),
+            candle.high.value(),
+            candle.low.value(),
+            candle.close.value(),
+        );
+        if high < open.max(close) || low > open.min(close) || high < low {
+            findings.push(finding(
+                CandleQualityIssue::OhlcInconsistent,
+                candle.timestamp,
+                candle.timestamp,
+                1,
+                format!("o={} h={} l={} c={}", open, high, low, close),
+            ));
+        }
+        // 最新一根允许处于进行中；只有已经完整走过一个周期仍未确认才算遗留。
+        let stale = if index < last_index {
+            true
+        } else {
+            period_ms
+                .map(|period_ms| candle.timestamp + 2 * period_ms <= now_ms)
+                .unwrap_or(false)
+        };
+        if !candle.confirmed && stale {
+            findings.push(finding(
+                CandleQualityIssue::StaleUnconfirmed,
+                candle.timestamp,
+                candle.timestamp,
+                1,
+                "已收盘K线仍为未确认状态".to_string(),
+            ));
+        }
+        let window_start = index.saturating_sub(config.outlier_wick_window);
+        if config.outlier_wick_window == 0 || index - window_start < MIN_OUTLIER_WICK_SAMPLES {
+            continue;
+        }
+        let mut ranges: Vec<f64> = unique[window_start..index]
+            .iter()
+            .map(|candle| candle.high.value() - candle.low.value())
+            .collect();
+        ranges.sort_by(|a, b| a.total_cmp(b));
+        let median = ranges[ranges.len() / 2];
+        let wick = (high - open.max(close)).max(open.min(close) - low);
+        if median > 0.0 && wick > median * config.outlier_wick_multiple {
+            findings.push(finding(
+                CandleQualityIssue::OutlierWick,
+                candle.timestamp,
+                candle.timestamp,
+                1,
+                format!(
+                    "影线 {:.8} 超过近 {} 根振幅中位数 {:.8} 的 {} 倍",
+                    wick,
+                    index - window_start,
+                    median,
+                    config.outlier_wick_multiple
+                ),
+            ));
+        }
+    }
+    findings.sort_by(|a, b| (a.start_ts, a.issue.as_str()).cmp(&(b.start_ts, b.issue.as_str())));
+    findings
+}
+/// 一次巡检写库后的计数。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CandleQualityScanSummary {
+    /// 本次扫描仍存在的问题数。
+    pub open: usize,
+    /// 本次扫描中已消失、被标记为 resolved 的问题数。
+    pub resolved: usize,
+}
+#[derive(Debug, FromRow)]
+struct CandleQualityFindingRow {
+    inst_id: String,
+    period: String,
+    issue: String,
+    start_ts: i64,
+    end_ts: i64,
+    bar_count: i64,
+    detail: String,
+}
+impl CandleQualityFindingRow {
+    fn into_finding(self) -> Result<CandleQualityFinding> {
+        Ok(CandleQualityFinding {
+            inst_id: self.inst_id,
+            period: self.period,
+            issue: CandleQualityIssue::from_str(&self.issue)?,
+            start_ts: self.start_ts,
+            end_ts: self.end_ts,
+            bar_count: self.bar_count,
+            detail: self.detail,
+        })
+    }
+}
+/// `candle_quality_findings` 表读写。
+///
+/// 状态流转：`open` -> `resolved`（重新扫描不再出现）；人工确认的真实行情（如停牌期间零成交）
+/// 可手动改为 `ignored`，之后扫描不会重新打开，也不会阻塞回测。
+pub struct CandleQualityStore {
+    pool: PgPool,
+}
+impl CandleQualityStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+    /// 使用 `QUANT_CORE_DATABASE_URL` 创建懒连接。
+    pub fn from_env() -> Result<Self> {
+        let database_url = std::env::var("QUANT_CORE_DATABASE_URL")
+            .context("K线质量巡检需要设置 QUANT_CORE_DATABASE_URL")?;
+        let pool = PgPoolOptions::new()
+            .max_connections(2)
+            .connect_lazy(&database_url)
+            .context("创建 quant_core K线质量巡检连接池失败")?;
+        Ok(Self::new(pool))
+    }
+    /// 写入一次扫描结果：出现的问题 upsert 为 `open`，扫描区间内之前 `open` 但这次未出现的标记为 `resolved`。
+    pub async fn record_scan(
+        &self,
+        inst_id: &str,
+        period: &str,
+        scan_start_ts: i64,
+        scan_end_ts: i64,
+        findings: &[CandleQualityFinding],
+    ) -> Result<CandleQualityScanSummary> {
+        let mut tx = self.pool.begin().await?;
+        for finding in findings {
+            sqlx::query(
+                "INSERT INTO candle_quality_findings
+                    (inst_id, period, issue, start_ts, end_ts, bar_count, detail)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (inst_id, period, issue, start_ts) DO UPDATE SET
+                    end_ts = EXCLUDED.end_ts,
+                    bar_count = EXCLUDED.bar_count,
+                    detail = EXCLUDED.detail,
+                    status = CASE
+                        WHEN candle_quality_findings.status = 'ignored' THEN 'ignored'
+                        ELSE 'open'
+                    END,
+                    resolved_at = CASE
+                        WHEN candle_quality_findings.status = 'ignored'
+                            THEN candle_quality_findings.resolved_at
+                        ELSE NULL
+                    END,
+                    last_seen_at = NOW()",
+            )
+            .bind(&finding.inst_id)
+            .bind(&finding.period)
+            .bind(finding.issue.as_str())
+            .bind(finding.start_ts)
+            .bind(finding.end_ts)
+            .bind(finding.bar_count)
+            .bind(&finding.detail)
+            .execute(&mut *tx)
+            .await
+            .context("写入K线质量问题失败")?;
+        }
+        let keys: Vec<String> = findings
+            .iter()
+            .map(|finding| format!("{}:{}", finding.issue.as_str(), finding.start_ts))
+            .collect();
+        let resolved = sqlx::query(
+            "UPDATE candle_quality_findings
+             SET status = 'resolved', resolved_at = NOW()
+             WHERE inst_id = $1
+               AND period = $2
+               AND status = 'open'
+               AND start_ts >= $3
+               AND end_ts <= $4
+               AND NOT ((issue || ':' || start_ts::text) = ANY($5))",
+        )
+        .bind(inst_id)
+        .bind(period)
+        .bind(scan_start_ts)
+        .bind(scan_end_ts)
+        .bind(&keys)
+        .execute(&mut *tx)
+        .await
+        .context("关闭已修复的K线质量问题失败")?
+        .rows_affected() as usize;
+        tx.commit().await?;
+        Ok(CandleQualityScanSummary {
+            open: findings.len(),
+            resolved,
+        })
+    }
+    /// 查询与 `[start_ts, end_ts]` 重叠的未解决问题。
+    pub async fn unresolved_findings(
+        &self,
+        inst_id: &str,
+        period: &str,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<Vec<CandleQualityFinding>> {
+        let rows = sqlx::query_as::<_, CandleQualityFindingRow>(
+            "SELECT inst_id, period, issue, start_ts, end_ts, bar_count, detail
+             FROM candle_quality_findings
+             WHERE inst_id = $1
+               AND period = $2
+               AND status = 'open'
+               AND start_ts <= $4
+               AND end_ts >= $3
+             ORDER BY start_ts, issue",
+        )
+        .bind(inst_id)
+        .bind(period)
+        .bind(start_ts)
+        .bind(end_ts)
+        .fetch_all(&self.pool)
+        .await
+        .context("查询未解决的K线质量问题失败")?;
+        rows.into_iter()
+            .map(CandleQualityFindingRow::into_finding)
+            .collect()
+    }
+    /// 记录一次修复尝试，便于排查反复修不好的区间。
+    pub async fn mark_repair_attempted(&self, finding: &CandleQualityFinding) -> Result<()> {
+        sqlx::query(
+            "UPDATE candle_quality_findings
+             SET repair_attempts = repair_attempts + 1, last_repair_at = NOW()
+             WHERE inst_id = $1 AND period = $2 AND issue = $3 AND start_ts = $4",
+        )
+        .bind(&finding.inst_id)
+        .bind(&finding.period)
+        .bind(finding.issue.as_str())
+        .bind(finding.start_ts)
+        .execute(&self.pool)
+        .await
+        .context("记录K线质量修复尝试失败")?;
+        Ok(())
+    }
+}
+/// 回测前检查：区间内存在未解决的K线质量问题时返回错误。
+pub async fn ensure_no_unresolved_candle_findings(
+    store: &CandleQualityStore,
+    inst_id: &str,
+    timeframe: Timeframe,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<()> {
+    let period = timeframe.as_str();
+    let findings = store
+        .unresolved_findings(inst_id, period, start_ts, end_ts)
+        .await?;
+    if findings.is_empty() {
+        return Ok(());
+    }
+    let samples: Vec<String> = findings
+        .iter()
+        .take(MAX_BLOCKING_FINDINGS_IN_ERROR)
+        .map(|finding| {
+            format!(
+                "{}[{}..{}] {}",
+                finding.issue.as_str(),
+                finding.start_ts,
+                finding.end_ts,
+                finding.detail
+            )
+        })
+        .collect();
+    Err(anyhow!(
+        "K线数据存在 {} 个未解决的质量问题，拒绝回测: inst_id={} period={} range=[{}, {}] {}",
+        findings.len(),
+        inst_id,
+        period,
+        start_ts,
+        end_ts,
+        samples.join("; ")
+    ))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_quant_domain::{Price, Volume};
+    const HOUR: i64 = 3_600_000;
+    fn candle(index: i64, ohlc: [f64; 4], volume: f64, confirmed: bool) -> Candle {
+        let mut candle = Candle::new(
+            "BTC-USDT-SWAP".to_string(),
+            Timeframe::H1,
+            index * HOUR,
+            Price::new(ohlc[0]).unwrap(),
+            Price::new(ohlc[1]).unwrap(),
+            Price::new(ohlc[2]).unwrap(),
+            Price::new(ohlc[3]).unwrap(),
+            Volume::new(volume).unwrap(),
+        );
+        if confirmed {
+            candle.confirm();
+        }
+        candle
+    }
+    fn normal(index: i64) -> Candle {
+        candle(index, [100.0, 101.0, 99.0, 100.5], 10.0, true)
+    }
+    fn issues(findings: &[CandleQualityFinding]) -> Vec<(CandleQualityIssue, i64, i64)> {
+        findings
+            .iter()
+            .map(|finding| (finding.issue, finding.start_ts / HOUR, finding.bar_count))
+            .collect()
+    }
+    #[test]
+    fn clean_series_has_no_findings() {
+        let candles: Vec<Candle> = (0..30).map(normal).collect();
+        let findings = scan_candle_quality(
+            "BTC-USDT-SWAP",
+            Timeframe::H1,
+            &candles,
+            &CandleQualityConfig::default(),
+            30 * HOUR,
+        );
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+    #[test]
+    fn detects_gaps_duplicates_zero_volume_and_ohlc_issues() {
+        let mut candles: Vec<Candle> = (0..5).map(normal).collect();
+        candles.push(normal(2));
+        candles.extend((8..11).map(|i| candle(i, [100.0, 101.0, 99.0, 100.5], 0.0, true)));
+        candles.push(candle(11, [100.0, 100.2, 99.0, 100.5], 10.0, true));
+        let findings = scan_candle_quality(
+            "BTC-USDT-SWAP",
+            Timeframe::H1,
+            &candles,
+            &CandleQualityConfig::default(),
+            12 * HOUR,
+        );
+        assert_eq!(
+            issues(&findings),
+            vec![
+                (CandleQualityIssue::Duplicate, 2, 2),
+                (CandleQualityIssue::Gap, 5, 3),
+                (CandleQualityIssue::ZeroVolumeRun, 8, 3),
+                (CandleQualityIssue::OhlcInconsistent, 11, 1),
+            ]
+        );
+        assert_eq!(findings[1].end_ts, 7 * HOUR);
+        assert!(findings.iter().all(|f| f.period == "1H"));
+    }
+    #[test]
+    fn detects_stale_unconfirmed_and_outlier_wicks() {
+        let mut candles: Vec<Candle> = (0..20).map(normal).collect();
+        candles[5] = candle(5, [100.0, 101.0, 99.0, 100.5], 10.0, false);
+        candles.push(candle(20, [100.0, 130.0, 99.0, 100.5], 10.0, true));
+        candles.push(candle(21, [100.0, 101.0, 99.0, 100.5], 10.0, false));
+        let config = CandleQualityConfig::default().with_outlier_wick_window(20);
+        let in_progress =
+            scan_candle_quality("BTC-USDT-SWAP", Timeframe::H1, &candles, &config, 22 * HOUR);
+        assert_eq!(
+            issues(&in_progress),
+            vec![
+                (CandleQualityIssue::StaleUnconfirmed, 5, 1),
+                (CandleQualityIssue::OutlierWick, 20, 1),
+            ]
+        );
+        let later =
+            scan_candle_quality("BTC-USDT-SWAP", Timeframe::H1, &candles, &config, 23 * HOUR);
+        assert_eq!(
+            later.last().map(|f| (f.issue, f.start_ts / HOUR)),
+            Some((CandleQualityIssue::StaleUnconfirmed, 21))
+        );
+        assert!(CandleQualityIssue::Gap.is_repairable());
+        assert!(!CandleQualityIssue::Duplicate.is_repairable());
+        assert_eq!(
+            CandleQualityIssue::from_str("outlier_wick").unwrap(),
+            CandleQualityIssue::OutlierWick
+        );
+    }
+}
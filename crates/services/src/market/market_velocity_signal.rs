@@ -1454,6 +1454,7 @@ fn market_velocity_signal_result(
             .to_string(),
         ),
         direction: config.trade_direction.signal_direction(),
+        entry_order: None,
     }
 }
 
@@ -1477,6 +1478,7 @@ fn market_velocity_risk_config(
         is_used_signal_k_line_stop_loss: Some(true),
         max_hold_time: Some(i64::from(config.max_holding_hours) * 60 * 60),
         max_leverage: None,
+        realtime_rules: None,
     }
 }
 
@@ -4,6 +4,8 @@
 mod account_service;
 mod asset_service;
 pub mod binance_websocket;
+mod candle_file_store;
+mod candle_quality;
 mod contracts_service;
 mod data_sync_service;
 pub mod dune_market_sync_service;
@@ -15,6 +17,14 @@ mod public_data_service;
 pub use account_service::AccountService;
 use anyhow::{anyhow, Context, Result};
 pub use asset_service::AssetService;
+pub use candle_file_store::{
+    candle_source_from_env, export_candles_to_file, import_candles_from_file, CandleSource,
+    CandleTransferSummary, DEFAULT_CANDLE_FILE_DIR,
+};
+pub use candle_quality::{
+    ensure_no_unresolved_candle_findings, scan_candle_quality, CandleQualityConfig,
+    CandleQualityFinding, CandleQualityIssue, CandleQualityScanSummary, CandleQualityStore,
+};
 use chrono::Utc;
 pub use contracts_service::ContractsService;
 use crypto_exc_all::{Candle as ExchangeCandle, CandleQuery, ExchangeId, Instrument};
@@ -140,6 +150,41 @@ impl CandleService {
             .collect();
         Ok(candles)
     }
+    /// 从交易所获取标准化K线（领域 Candle）
+    ///
+    /// 参数语义与 `fetch_candles_from_exchange` 一致；新代码优先使用本接口，
+    /// 不再经由 `CandlesEntity` 字符串字段中转。`bar` 按配置原样请求交易所
+    /// （如 OKX 的 `1D` 与 `1Dutc` 对应不同K线），`timeframe` 只用于标记领域 Candle；
+    /// 格式异常的行告警后跳过，不影响同批其余K线。
+    pub async fn fetch_domain_candles_from_exchange(
+        &self,
+        inst_id: &str,
+        bar: &str,
+        timeframe: Timeframe,
+        after: Option<i64>,
+        before: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Candle>> {
+        use rust_quant_infrastructure::ExchangeFactory;
+        let exchange = ExchangeFactory::create_default_market_data()?;
+        let rows = exchange
+            .fetch_candles(inst_id, bar, after, before, limit)
+            .await?;
+        Ok(exchange_candle_rows_to_domain(inst_id, bar, timeframe, &rows))
+    }
+    /// 从交易所获取标准化最新K线（领域 Candle）
+    pub async fn fetch_latest_domain_candles_from_exchange(
+        &self,
+        inst_id: &str,
+        timeframe: Timeframe,
+        limit: Option<usize>,
+    ) -> Result<Vec<Candle>> {
+        use rust_quant_infrastructure::ExchangeFactory;
+        let exchange = ExchangeFactory::create_default_market_data()?;
+        exchange
+            .fetch_latest_candles_normalized(inst_id, timeframe, limit)
+            .await
+    }
     /// 从交易所获取最新K线数据
     /// # Note
     /// 使用默认交易所（从环境变量 DEFAULT_EXCHANGE），支持多交易所扩展
@@ -232,6 +277,61 @@ impl CandleService {
             })
             .collect()
     }
+    /// 加载盘中成交顺序解析使用的低周期K线（用于回测）
+    /// # 参数
+    /// * `inst_id` - 交易对
+    /// * `lower_period` - 低周期，如 1m / 5m
+    /// * `bar_period` - 回测主周期，如 4H
+    /// * `bar_candles` - 主周期K线，用于确定加载范围
+    pub async fn load_intrabar_candles(
+        &self,
+        inst_id: &str,
+        lower_period: &str,
+        bar_period: &str,
+        bar_candles: &[rust_quant_common::CandleItem],
+    ) -> Result<rust_quant_strategies::framework::backtest::IntrabarCandles> {
+        use rust_quant_common::CandleItem;
+        use rust_quant_strategies::framework::backtest::IntrabarCandles;
+        let lower = Timeframe::from_str(lower_period)
+            .map_err(|error| anyhow!("无效的K线周期: {}", error))?;
+        let bar =
+            Timeframe::from_str(bar_period).map_err(|error| anyhow!("无效的K线周期: {}", error))?;
+        if lower.to_minutes() >= bar.to_minutes() {
+            return Err(anyhow!(
+                "盘中解析周期 {} 必须小于回测周期 {}",
+                lower_period,
+                bar_period
+            ));
+        }
+        let bar_duration_ms = bar.to_minutes() * 60 * 1000;
+        let (Some(first), Some(last)) = (bar_candles.first(), bar_candles.last()) else {
+            return Ok(IntrabarCandles::new(Vec::new(), bar_duration_ms));
+        };
+        let candles = self
+            .repository
+            .find_candles(
+                inst_id,
+                lower,
+                first.ts,
+                last.ts + bar_duration_ms - 1,
+                None,
+            )
+            .await?;
+        let items = candles
+            .iter()
+            .filter(|candle| candle.confirmed)
+            .map(|candle| CandleItem {
+                o: candle.open.value(),
+                h: candle.high.value(),
+                l: candle.low.value(),
+                c: candle.close.value(),
+                v: candle.volume.value(),
+                ts: candle.timestamp,
+                confirm: 1,
+            })
+            .collect();
+        Ok(IntrabarCandles::new(items, bar_duration_ms))
+    }
 }
 /// 封装当前函数，减少行情数据调用方重复实现相同细节。
 /// 采用 async 以便与数据库/网络 I/O 协调，减少阻塞并提升并发吞吐。
@@ -243,8 +343,21 @@ pub async fn get_confirmed_candles_for_backtest(
     limit: usize,
     select_time: Option<rust_quant_market::models::SelectTime>,
 ) -> Result<Vec<rust_quant_market::models::CandlesEntity>> {
-    should_use_quant_core_candle_source()?;
-    get_quant_core_sharded_candles_for_backtest(inst_id, period, limit, select_time).await
+    match candle_source_from_env()? {
+        CandleSource::QuantCore => {
+            get_quant_core_sharded_candles_for_backtest(inst_id, period, limit, select_time).await
+        }
+        CandleSource::File(dir) => {
+            candle_file_store::get_file_candles_for_backtest(
+                &dir,
+                inst_id,
+                period,
+                limit,
+                select_time,
+            )
+            .await
+        }
+    }
 }
 /// 从指定交易所读取最新 K 线，供实盘预热修复数据库尾部缺口。
 ///
@@ -267,15 +380,15 @@ pub(crate) async fn fetch_latest_candles_for_live_warmup(
         .collect())
 }
 /// 判断 行情与市场数据 条件是否满足，给上层流程提供布尔决策。
+///
+/// `CANDLE_SOURCE=file` 只服务回测读取，实时行情和同步链路遇到时直接报错。
 pub fn should_use_quant_core_candle_source() -> Result<bool> {
-    let source = std::env::var("CANDLE_SOURCE")
-        .unwrap_or_default()
-        .trim()
-        .to_ascii_lowercase();
-    if source.is_empty() || matches!(source.as_str(), "quant_core" | "postgres" | "pg") {
-        return Ok(true);
-    }
-    Err(anyhow!("不支持的 CANDLE_SOURCE: {}", source))
+    match candle_source_from_env()? {
+        CandleSource::QuantCore => Ok(true),
+        CandleSource::File(_) => Err(anyhow!(
+            "CANDLE_SOURCE=file 仅支持回测读取 K 线，实时行情与同步链路必须使用 quant_core"
+        )),
+    }
 }
 /// 提供cryptoexcallgatewayfrom环境变量的集中实现，避免行情数据调用方重复处理相同细节。
 fn crypto_exc_all_gateway_from_env(exchange: ExchangeId) -> Result<crate::CryptoExcAllGateway> {
@@ -335,6 +448,30 @@ fn default_exchange_id() -> Result<ExchangeId> {
         .unwrap_or_else(|_| "okx".to_string());
     ExchangeId::from_str(&exchange).map_err(|error| anyhow!(error))
 }
+/// 逐行把交易所K线转换为领域 Candle；格式异常的行告警后跳过，不拖垮整批同步。
+fn exchange_candle_rows_to_domain(
+    inst_id: &str,
+    bar: &str,
+    timeframe: Timeframe,
+    rows: &[serde_json::Value],
+) -> Vec<Candle> {
+    rows.iter()
+        .filter_map(
+            |row| match Candle::from_exchange_json(inst_id, timeframe, row) {
+                Ok(candle) => Some(candle),
+                Err(e) => {
+                    tracing::warn!(
+                        "解析K线失败，跳过该条记录: inst_id={}, bar={}, err={}",
+                        inst_id,
+                        bar,
+                        e
+                    );
+                    None
+                }
+            },
+        )
+        .collect()
+}
 /// 提供交易所K 线值toentity的集中实现，避免行情数据调用方重复处理相同细节。
 fn exchange_candle_value_to_entity(value: serde_json::Value) -> Option<CandlesEntity> {
     if let Some(values) = value.as_array() {
@@ -612,7 +749,18 @@ impl TickerService {
     ) -> Result<Vec<TickersDataEntity>> {
         let exchange_id = default_exchange_id()?;
         if exchange_id != ExchangeId::Okx {
-            return Err(anyhow!("批量Ticker同步当前仅支持 OKX: {:?}", exchange_id));
+            // 其他交易所经 ExchangeFactory 适配器获取，返回值已整理为 OKX ticker 字段
+            use okx::dto::market_dto::TickerOkxResDto;
+            use rust_quant_infrastructure::ExchangeFactory;
+            let exchange = ExchangeFactory::create_default_market_data()?;
+            let tickers = exchange.fetch_tickers(inst_type).await?;
+            return tickers
+                .into_iter()
+                .map(|value| {
+                    let ticker: TickerOkxResDto = serde_json::from_value(value)?;
+                    Ok(TickersDataEntity::from_okx_ticker(&ticker))
+                })
+                .collect();
         }
         use okx::api::api_trait::OkxApiTrait;
         use okx::api::market::OkxMarket;
@@ -668,12 +816,11 @@ impl TickerService {
         top_n: usize,
     ) -> Result<Vec<TickersDataEntity>> {
         let mut tickers = self.fetch_tickers_from_exchange(inst_type).await?;
-        // 按vol24h排序
+        // 按24h成交额排序：永续 volCcy24h 为基础币成交量，乘最新价得到计价币成交额，
+        // 不同交易所、不同面值的合约可直接比较
         tickers.sort_by(|a, b| {
-            let vol_a: f64 = a.vol24h.parse().unwrap_or(0.0);
-            let vol_b: f64 = b.vol24h.parse().unwrap_or(0.0);
-            vol_b
-                .partial_cmp(&vol_a)
+            quote_turnover_24h(b)
+                .partial_cmp(&quote_turnover_24h(a))
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
         // 取前N个
@@ -681,6 +828,23 @@ impl TickerService {
         Ok(tickers)
     }
 }
+/// ticker 的24h计价币成交额
+///
+/// 现货 `volCcy24h` 即计价币成交额；永续/交割为基础币成交量，乘 `last` 换算，
+/// 缺少 `volCcy24h` 时退回 `vol24h`。
+fn quote_turnover_24h(ticker: &TickersDataEntity) -> f64 {
+    let vol_ccy: f64 = ticker.vol_ccy24h.parse().unwrap_or(0.0);
+    if ticker.inst_type.eq_ignore_ascii_case("SPOT") {
+        return vol_ccy;
+    }
+    let last: f64 = ticker.last.parse().unwrap_or(0.0);
+    let base_volume = if vol_ccy > 0.0 {
+        vol_ccy
+    } else {
+        ticker.vol24h.parse().unwrap_or(0.0)
+    };
+    base_volume * last
+}
 /// 市场深度服务
 pub struct MarketDepthService {
     // TODO: 添加市场深度数据访问
@@ -734,6 +898,38 @@ mod tests {
         }
     }
     #[test]
+    fn swap_turnover_uses_base_volume_times_last_price() {
+        let ticker =
+            |inst_type: &str, last: &str, vol_ccy24h: &str, vol24h: &str| TickersDataEntity {
+                id: None,
+                inst_type: inst_type.to_string(),
+                inst_id: String::new(),
+                last: last.to_string(),
+                last_sz: String::new(),
+                ask_px: String::new(),
+                ask_sz: String::new(),
+                bid_px: String::new(),
+                bid_sz: String::new(),
+                open24h: String::new(),
+                high24h: String::new(),
+                low24h: String::new(),
+                vol_ccy24h: vol_ccy24h.to_string(),
+                vol24h: vol24h.to_string(),
+                sod_utc0: String::new(),
+                sod_utc8: String::new(),
+                ts: 0,
+            };
+        // BTC 张数少但成交额远大于 DOGE
+        let btc = ticker("SWAP", "65000", "1200", "120000");
+        let doge = ticker("SWAP", "0.1", "900000", "900");
+        assert!(quote_turnover_24h(&btc) > quote_turnover_24h(&doge));
+        assert_eq!(quote_turnover_24h(&ticker("SWAP", "10", "", "5")), 50.0);
+        assert_eq!(
+            quote_turnover_24h(&ticker("SPOT", "10", "300", "30")),
+            300.0
+        );
+    }
+    #[test]
     fn okx_market_gateway_is_available_without_private_credentials() {
         let _guard = env_lock();
         let previous = clear_okx_env();
@@ -744,6 +940,30 @@ mod tests {
         assert_eq!(configured_exchanges, vec![ExchangeId::Okx]);
     }
     #[test]
+    fn malformed_exchange_candle_rows_are_skipped() {
+        let rows = vec![
+            serde_json::json!(["1700000000000", "100", "110", "90", "105", "3", "300", "", "1"]),
+            // 开盘价缺失的坏行只跳过自身
+            serde_json::json!(["1700086400000", "", "110", "90", "105", "3", "300", "", "1"]),
+            serde_json::json!({
+                "ts": "1700172800000",
+                "o": "105",
+                "h": "120",
+                "l": "100",
+                "c": "118",
+                "vol": "4",
+                "volCcy": "450",
+                "confirm": "0"
+            }),
+        ];
+        let candles =
+            exchange_candle_rows_to_domain("BTC-USDT-SWAP", "1Dutc", Timeframe::D1, &rows);
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].timestamp, 1_700_000_000_000);
+        assert_eq!(candles[1].timestamp, 1_700_172_800_000);
+        assert!(!candles[1].confirmed);
+    }
+    #[test]
     fn okx_candle_period_uses_okx_bar_case() {
         assert_eq!(crypto_exc_all_candle_period(ExchangeId::Okx, "4h"), "4H");
         assert_eq!(crypto_exc_all_candle_period(ExchangeId::Okx, "1h"), "1H");
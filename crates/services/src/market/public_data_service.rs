@@ -29,7 +29,7 @@ impl PublicDataService {
         ann_type: Option<&str>,
         page_size: Option<&str>,
     ) -> Result<Vec<String>> {
-        let exchange = ExchangeFactory::create_public_data("okx")?;
+        let exchange = ExchangeFactory::create_default_public_data()?;
         let announcements = exchange.fetch_announcements(ann_type, page_size).await?;
         info!("✅ 从交易所 {} 获取了公告数据", exchange.name());
         Ok(announcements)
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use reqwest::Client;
+use rust_quant_risk::account::{DrawdownBreach, DrawdownScope};
 use serde::Serialize;
 use tracing::{error, info};
 /// Telegram Bot 通知服务
@@ -91,4 +92,26 @@ impl TelegramNotifier {
         );
         self.send_message(&message).await
     }
+    /// 发送权益回撤熔断通知
+    pub async fn notify_drawdown_breach(&self, breach: &DrawdownBreach) -> Result<()> {
+        let (emoji, action) = if breach.is_halt() {
+            ("🛑", "已熔断，暂停交易（需手动恢复）")
+        } else {
+            ("⚠️", "触发回撤警告")
+        };
+        let scope = match &breach.scope {
+            DrawdownScope::Account(id) => format!("账户 `{}`", id),
+            DrawdownScope::Strategy(id) => format!("策略配置 `{}`", id),
+        };
+        let message = format!(
+            "{} *权益回撤*\n\n\
+             *范围*: {}\n\
+             *动作*: {}\n\
+             *回撤*: {:.2}%\n\
+             *峰值权益*: {:.2}\n\
+             *当前权益*: {:.2}\n",
+            emoji, scope, action, breach.drawdown_percent, breach.peak_equity, breach.equity
+        );
+        self.send_message(&message).await
+    }
 }
@@ -1,10 +1,285 @@
 //! 风险管理服务
 //!
 //! 负责策略执行前后的风控检查
-use anyhow::Result;
-use rust_quant_domain::SignalResult;
+use crate::exchange::{CryptoExcAllGateway, ExchangeGateway};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use crypto_exc_all::{ExchangeId, Instrument, Position as ExchangePosition, PositionHistoryQuery};
+use dashmap::DashMap;
+use rust_quant_domain::value_objects::{Price, Volume};
+use rust_quant_domain::{BasicRiskConfig, MarginMode, Position, PositionSide, SignalResult};
+use rust_quant_risk::policies::{
+    AccountRiskSnapshot, PreTradeRiskLimits, PreTradeRiskPolicy, PreTradeRiskRequest, RiskDecision,
+};
 use rust_quant_strategies::framework::config::StrategyConfig;
-use tracing::info;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+/// 账户权益的默认结算币种
+const DEFAULT_SETTLEMENT_ASSET: &str = "USDT";
+/// 账户风险快照来源
+///
+/// 由调用方注入，便于实盘读取交易所、测试使用固定快照
+#[async_trait]
+pub trait AccountRiskSnapshotProvider: Send + Sync {
+    /// 加载当前账户风险快照
+    async fn load_snapshot(&self) -> Result<AccountRiskSnapshot>;
+}
+/// 权益峰值与当日初始权益
+struct EquityMarks {
+    /// 历史峰值权益
+    peak: f64,
+    /// 当日初始权益对应的 UTC 日期
+    day: NaiveDate,
+    /// 当日初始权益
+    day_start: f64,
+}
+/// 基于交易所账户余额与持仓的风险快照
+///
+/// 权益取结算币种余额，持仓取交易所当前未平仓位；峰值权益与当日初始权益在进程内跟踪，
+/// 重启后从首次读取的权益重新计量。
+pub struct ExchangeAccountRiskSnapshotProvider {
+    /// 交易所网关
+    gateway: Arc<dyn ExchangeGateway>,
+    /// 读取账户的交易所
+    exchange: ExchangeId,
+    /// 结算币种
+    settlement_asset: String,
+    /// 权益标记
+    marks: Mutex<Option<EquityMarks>>,
+}
+impl ExchangeAccountRiskSnapshotProvider {
+    pub fn new(gateway: Arc<dyn ExchangeGateway>, exchange: ExchangeId) -> Self {
+        Self {
+            gateway,
+            exchange,
+            settlement_asset: DEFAULT_SETTLEMENT_ASSET.to_string(),
+            marks: Mutex::new(None),
+        }
+    }
+    /// 从环境变量构建：交易所取 DEFAULT_EXCHANGE / EXCHANGE_NAME（默认 okx），凭证由 SDK 读取
+    pub fn from_env() -> Result<Self> {
+        let exchange = std::env::var("DEFAULT_EXCHANGE")
+            .or_else(|_| std::env::var("EXCHANGE_NAME"))
+            .unwrap_or_else(|_| "okx".to_string());
+        let exchange = ExchangeId::from_str(&exchange).map_err(|e| anyhow!(e))?;
+        let gateway = CryptoExcAllGateway::from_env()
+            .map_err(|e| anyhow!("创建 crypto_exc_all gateway 失败: {}", e))?;
+        Ok(Self::new(Arc::new(gateway), exchange))
+    }
+    pub fn with_settlement_asset(mut self, asset: impl Into<String>) -> Self {
+        self.settlement_asset = asset.into();
+        self
+    }
+    /// 用最新权益更新峰值与当日初始权益，返回快照
+    fn snapshot_from_equity(
+        &self,
+        equity: f64,
+        positions: Vec<Position>,
+        today: NaiveDate,
+    ) -> AccountRiskSnapshot {
+        let mut guard = self
+            .marks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let marks = guard.get_or_insert(EquityMarks {
+            peak: equity,
+            day: today,
+            day_start: equity,
+        });
+        if marks.day != today {
+            marks.day = today;
+            marks.day_start = equity;
+        }
+        marks.peak = marks.peak.max(equity);
+        AccountRiskSnapshot {
+            equity,
+            peak_equity: marks.peak,
+            day_start_equity: marks.day_start,
+            positions,
+        }
+    }
+}
+#[async_trait]
+impl AccountRiskSnapshotProvider for ExchangeAccountRiskSnapshotProvider {
+    async fn load_snapshot(&self) -> Result<AccountRiskSnapshot> {
+        let (balances, positions) = CryptoExcAllGateway::with_signed_read_only_scope(async {
+            tokio::try_join!(
+                self.gateway.balances(self.exchange),
+                self.gateway.positions(self.exchange, None)
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("读取账户余额与持仓失败: {}", e))?;
+        let equity = balances
+            .iter()
+            .filter(|balance| balance.asset.eq_ignore_ascii_case(&self.settlement_asset))
+            .filter_map(|balance| balance.total.parse::<f64>().ok())
+            .sum();
+        let positions = positions
+            .iter()
+            .filter_map(risk_position_from_exchange)
+            .collect();
+        Ok(self.snapshot_from_equity(equity, positions, Utc::now().date_naive()))
+    }
+}
+/// 平仓历史单次读取条数上限
+const STRATEGY_POSITION_HISTORY_LIMIT: u32 = 100;
+/// 基于交易所持仓与平仓历史的策略实盘权益
+///
+/// 权益 = 策略分配资金 + 起点之后该品种平仓记录的已实现盈亏 + 该品种当前持仓的未实现盈亏。
+/// 分配资金与统计起点由配置固定，进程重启后口径不变；同一品种只应由一个实盘策略交易。
+pub struct ExchangeStrategyEquityProvider {
+    /// 交易所网关
+    gateway: Arc<dyn ExchangeGateway>,
+    /// 读取账户的交易所
+    exchange: ExchangeId,
+    /// 策略分配资金（结算币种计价）
+    capital: f64,
+    /// 已实现盈亏的统计起点（毫秒时间戳）
+    since_ms: u64,
+}
+impl ExchangeStrategyEquityProvider {
+    pub fn new(
+        gateway: Arc<dyn ExchangeGateway>,
+        exchange: ExchangeId,
+        capital: f64,
+        since_ms: u64,
+    ) -> Self {
+        Self {
+            gateway,
+            exchange,
+            capital,
+            since_ms,
+        }
+    }
+    /// 从环境变量构建：交易所取 DEFAULT_EXCHANGE / EXCHANGE_NAME（默认 okx），凭证由 SDK 读取
+    pub fn from_env(capital: f64, since_ms: u64) -> Result<Self> {
+        let exchange = std::env::var("DEFAULT_EXCHANGE")
+            .or_else(|_| std::env::var("EXCHANGE_NAME"))
+            .unwrap_or_else(|_| "okx".to_string());
+        let exchange = ExchangeId::from_str(&exchange).map_err(|e| anyhow!(e))?;
+        let gateway = CryptoExcAllGateway::from_env()
+            .map_err(|e| anyhow!("创建 crypto_exc_all gateway 失败: {}", e))?;
+        Ok(Self::new(Arc::new(gateway), exchange, capital, since_ms))
+    }
+    /// 读取策略交易品种的持仓与平仓历史，计算策略实盘权益
+    pub async fn strategy_equity(&self, inst_id: &str) -> Result<f64> {
+        let instrument = swap_instrument_from_inst_id(inst_id)?;
+        let query = PositionHistoryQuery::for_instrument(instrument.clone())
+            .with_limit(STRATEGY_POSITION_HISTORY_LIMIT);
+        let (positions, history) = CryptoExcAllGateway::with_signed_read_only_scope(async {
+            tokio::try_join!(
+                self.gateway.positions(self.exchange, Some(&instrument)),
+                self.gateway.position_history(self.exchange, query)
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("读取策略持仓与平仓历史失败: inst_id={}, err={}", inst_id, e))?;
+        let realized: f64 = history
+            .iter()
+            .filter(|record| record.close_time.unwrap_or(0) >= self.since_ms)
+            .filter_map(|record| record.realized_pnl.as_deref().or(record.pnl.as_deref()))
+            .filter_map(|value| value.parse::<f64>().ok())
+            .sum();
+        let unrealized: f64 = positions
+            .iter()
+            .filter(|position| {
+                swap_inst_id_from_exchange_symbol(&position.exchange_symbol)
+                    .eq_ignore_ascii_case(inst_id.trim())
+            })
+            .filter_map(|position| position.unrealized_pnl.as_deref())
+            .filter_map(|value| value.parse::<f64>().ok())
+            .sum();
+        Ok(self.capital + realized + unrealized)
+    }
+}
+/// 交易所持仓转为风控持仓，持仓价值取交易所给出的名义价值，缺失时按数量 × 标记价估算
+fn risk_position_from_exchange(position: &ExchangePosition) -> Option<Position> {
+    let signed_size = position.size.parse::<f64>().ok()?;
+    let price = position
+        .mark_price
+        .as_deref()
+        .or(position.entry_price.as_deref())?
+        .parse::<f64>()
+        .ok()
+        .filter(|price| price.is_finite() && *price > 0.0)?;
+    let entry_price = position
+        .entry_price
+        .as_deref()
+        .and_then(|value| value.parse::<f64>().ok())
+        .filter(|price| price.is_finite() && *price > 0.0)
+        .unwrap_or(price);
+    let notional = ["notionalUsd", "notional"]
+        .iter()
+        .find_map(|key| match position.raw.get(*key)? {
+            serde_json::Value::String(text) => text.parse::<f64>().ok(),
+            value => value.as_f64(),
+        })
+        .map(f64::abs)
+        .filter(|value| value.is_finite() && *value > 0.0)
+        .unwrap_or(signed_size.abs() * price);
+    if notional <= 0.0 {
+        return None;
+    }
+    let side = match position
+        .side
+        .as_deref()
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("long" | "buy") => PositionSide::Long,
+        Some("short" | "sell") => PositionSide::Short,
+        _ if signed_size < 0.0 => PositionSide::Short,
+        _ => PositionSide::Long,
+    };
+    let leverage = position
+        .leverage
+        .as_deref()
+        .and_then(|value| value.parse::<f64>().ok())
+        .filter(|value| *value > 0.0 && *value <= 125.0)
+        .unwrap_or(1.0);
+    let margin_mode = match position.margin_mode.as_deref() {
+        Some(mode) if mode.eq_ignore_ascii_case("isolated") => MarginMode::Isolated,
+        _ => MarginMode::Cross,
+    };
+    let inst_id = swap_inst_id_from_exchange_symbol(&position.exchange_symbol);
+    let mut risk_position = Position::new(
+        format!("{}:{}", inst_id, side.as_str()),
+        inst_id,
+        side,
+        Volume::new(notional / price).ok()?,
+        Price::new(entry_price).ok()?,
+        leverage,
+        margin_mode,
+    )
+    .ok()?;
+    risk_position.update_price(Price::new(price).ok()?);
+    Some(risk_position)
+}
+/// 交易所合约代码统一为 OKX 风格（`BTCUSDT` → `BTC-USDT-SWAP`），与策略下单的 inst_id 对齐
+fn swap_inst_id_from_exchange_symbol(symbol: &str) -> String {
+    let symbol = symbol.trim().to_ascii_uppercase();
+    if symbol.contains('-') {
+        return symbol;
+    }
+    ["USDT", "USDC", "USD"]
+        .iter()
+        .find_map(|quote| {
+            let base = symbol.strip_suffix(quote)?;
+            (!base.is_empty()).then(|| format!("{base}-{quote}-SWAP"))
+        })
+        .unwrap_or(symbol)
+}
+/// 策略 inst_id（`BTC-USDT-SWAP`）转为 SDK 永续合约品种
+fn swap_instrument_from_inst_id(inst_id: &str) -> Result<Instrument> {
+    let inst_id = inst_id.trim().to_ascii_uppercase();
+    match inst_id.split('-').collect::<Vec<_>>().as_slice() {
+        [base, quote, "SWAP"] => Ok(Instrument::perp(*base, *quote).with_settlement(*quote)),
+        _ => Err(anyhow!("不支持的永续合约 inst_id: {}", inst_id)),
+    }
+}
 /// 风险管理服务
 ///
 /// # Responsibilities
@@ -12,30 +287,150 @@ use tracing::info;
 /// - 持仓风险检查
 /// - 账户风险检查
 /// - 交易限制检查
-pub struct RiskManagementService;
+pub struct RiskManagementService {
+    /// 下单前风控策略
+    policy: PreTradeRiskPolicy,
+    /// 账户风险快照来源；为空时只执行杠杆和交易频率校验
+    snapshot_provider: Option<Arc<dyn AccountRiskSnapshotProvider>>,
+    /// 快照读取失败时是否拒绝开仓
+    fail_closed: bool,
+    /// 单笔开仓名义价值（USDT）；为空时由下游定量，仓位限额只校验已有持仓
+    order_notional_usdt: Option<f64>,
+    /// 各交易对已放行开仓的信号时间戳
+    trade_ledger: DashMap<String, Vec<i64>>,
+}
 impl RiskManagementService {
     pub fn new() -> Self {
-        Self
+        Self {
+            policy: PreTradeRiskPolicy::default(),
+            snapshot_provider: None,
+            fail_closed: false,
+            order_notional_usdt: None,
+            trade_ledger: DashMap::new(),
+        }
+    }
+    /// 从环境变量构建
+    ///
+    /// 下单前风控默认关闭，只有显式配置限额后才生效：
+    /// - `PRE_TRADE_RISK_LIMITS_JSON`: `PreTradeRiskLimits` 的 JSON，缺省字段为不限制
+    /// - `PRE_TRADE_RISK_ACCOUNT_SNAPSHOT`: 为 1/true 时读取默认交易所账户权益与持仓
+    /// - `PRE_TRADE_RISK_FAIL_CLOSED`: 为 1/true 时快照读取失败即拒绝开仓
+    /// - `STRATEGY_ORDER_NOTIONAL_USDT`: 单笔开仓名义价值，作为风控评估规模并随信号下发
+    pub fn from_env() -> Result<Self> {
+        let limits = match std::env::var("PRE_TRADE_RISK_LIMITS_JSON") {
+            Ok(raw) if !raw.trim().is_empty() => {
+                serde_json::from_str::<PreTradeRiskLimits>(&raw)
+                    .map_err(|e| anyhow!("解析 PRE_TRADE_RISK_LIMITS_JSON 失败: {}", e))?
+            }
+            _ => {
+                info!("未配置 PRE_TRADE_RISK_LIMITS_JSON，下单前风控限额保持关闭");
+                PreTradeRiskLimits::default()
+            }
+        };
+        let mut service = Self::new()
+            .with_policy(PreTradeRiskPolicy::new(limits))
+            .with_fail_closed(env_flag("PRE_TRADE_RISK_FAIL_CLOSED"));
+        if let Ok(raw) = std::env::var("STRATEGY_ORDER_NOTIONAL_USDT") {
+            let notional = raw
+                .trim()
+                .parse::<f64>()
+                .map_err(|e| anyhow!("解析 STRATEGY_ORDER_NOTIONAL_USDT 失败: {}", e))?;
+            service = service.with_order_notional(notional);
+        }
+        if env_flag("PRE_TRADE_RISK_ACCOUNT_SNAPSHOT") {
+            service = service
+                .with_snapshot_provider(Arc::new(ExchangeAccountRiskSnapshotProvider::from_env()?));
+        }
+        Ok(service)
+    }
+    pub fn with_policy(mut self, policy: PreTradeRiskPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+    pub fn with_snapshot_provider(
+        mut self,
+        provider: Arc<dyn AccountRiskSnapshotProvider>,
+    ) -> Self {
+        self.snapshot_provider = Some(provider);
+        self
+    }
+    pub fn with_fail_closed(mut self, fail_closed: bool) -> Self {
+        self.fail_closed = fail_closed;
+        self
+    }
+    /// 设置单笔开仓名义价值；非正数视为未配置
+    pub fn with_order_notional(mut self, notional_usdt: f64) -> Self {
+        self.order_notional_usdt =
+            Some(notional_usdt).filter(|value| value.is_finite() && *value > 0.0);
+        self
+    }
+    /// 单笔开仓名义价值（缩仓前）
+    pub fn order_notional_usdt(&self) -> Option<f64> {
+        self.order_notional_usdt
+    }
+    /// 下单前风控评估
+    ///
+    /// 返回放行 / 缩仓 / 拒绝决策；调用方须在创建下单任务前遵守该决策。
+    pub async fn evaluate_pre_trade(&self, request: &PreTradeRiskRequest) -> RiskDecision {
+        let snapshot = match &self.snapshot_provider {
+            Some(provider) => match provider.load_snapshot().await {
+                Ok(snapshot) => Some(snapshot),
+                Err(e) if self.fail_closed => {
+                    return RiskDecision::Reject {
+                        reasons: vec![format!("账户风险快照获取失败: {}", e)],
+                    };
+                }
+                Err(e) => {
+                    warn!(
+                        "⚠️ 账户风险快照获取失败，仅执行杠杆与频率校验: inst_id={}, err={}",
+                        request.inst_id, e
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+        let recent_trades = self
+            .trade_ledger
+            .get(&request.inst_id)
+            .map(|entry| entry.value().clone())
+            .unwrap_or_default();
+        let decision = self
+            .policy
+            .evaluate(request, snapshot.as_ref(), &recent_trades);
+        match &decision {
+            RiskDecision::Allow => {
+                info!("✅ 下单前风控放行: inst_id={}", request.inst_id)
+            }
+            RiskDecision::Shrink {
+                size_ratio,
+                reasons,
+            } => warn!(
+                "⚠️ 下单前风控缩仓: inst_id={}, size_ratio={:.4}, reasons={:?}",
+                request.inst_id, size_ratio, reasons
+            ),
+            RiskDecision::Reject { reasons } => warn!(
+                "🚫 下单前风控拒绝: inst_id={}, reasons={:?}",
+                request.inst_id, reasons
+            ),
+        }
+        decision
+    }
+    /// 记录一次已放行的开仓，供交易频率校验使用
+    pub fn record_trade(&self, inst_id: &str, signal_ts: i64) {
+        let window_start = signal_ts - self.policy.limits.trade_frequency_window_ms;
+        let mut entry = self.trade_ledger.entry(inst_id.to_string()).or_default();
+        entry.retain(|ts| *ts > window_start);
+        entry.push(signal_ts);
     }
     /// 检查策略信号是否通过风控
-    /// # Current Implementation
-    /// ⏳ 基础框架，详细规则待实现
-    /// # Full Implementation (P1)
-    /// ```rust,ignore
-    /// // 1. 检查持仓限制
-    /// let position_risk = check_position_limit(inst_id, signal).await?;
-    /// // 2. 检查账户风险
-    /// let account_risk = check_account_risk(signal).await?;
-    /// // 3. 检查交易频率
-    /// let frequency_risk = check_trading_frequency(inst_id).await?;
-    /// // 4. 综合判断
-    /// Ok(position_risk && account_risk && frequency_risk)
-    /// ```
+    ///
+    /// 兼容旧接口：杠杆取自策略风控配置 `max_leverage`，规模取配置的单笔名义价值；缩仓视为通过。
     pub async fn check_signal_risk(
         &self,
         inst_id: &str,
         signal: &SignalResult,
-        _config: &StrategyConfig,
+        config: &StrategyConfig,
     ) -> Result<bool> {
         // 基础检查：信号有效性
         let has_buy_signal = signal.should_buy.unwrap_or(false);
@@ -44,37 +439,21 @@ impl RiskManagementService {
             info!("无交易信号，跳过风控检查: inst_id={}", inst_id);
             return Ok(true);
         }
-        // ⏳ P1: 详细风控规则待实现
-        // 当前返回true，允许所有交易
-        info!("✅ 风控检查通过 (基础实现): inst_id={}", inst_id);
-        Ok(true)
-    }
-    /// 检查持仓限制
-    /// ⏳ P1: 待实现
-    async fn check_position_limit(&self, inst_id: &str, _signal: &SignalResult) -> Result<bool> {
-        // TODO: 查询当前持仓
-        // TODO: 检查最大持仓限制
-        // TODO: 检查单品种持仓比例
-        info!("持仓限制检查（占位实现）: inst_id={}", inst_id);
-        Ok(true)
-    }
-    /// 检查账户风险
-    /// ⏳ P1: 待实现
-    async fn check_account_risk(&self, _signal: &SignalResult) -> Result<bool> {
-        // TODO: 查询账户余额
-        // TODO: 检查保证金充足性
-        // TODO: 检查风险度
-        info!("账户风险检查（占位实现）");
-        Ok(true)
-    }
-    /// 检查交易频率
-    /// ⏳ P1: 待实现
-    async fn check_trading_frequency(&self, inst_id: &str) -> Result<bool> {
-        // TODO: 查询最近交易记录
-        // TODO: 检查交易频率限制
-        // TODO: 检查冷却期
-        info!("交易频率检查（占位实现）: inst_id={}", inst_id);
-        Ok(true)
+        let leverage = serde_json::from_value::<BasicRiskConfig>(config.risk_config.clone())
+            .ok()
+            .and_then(|risk| risk.max_leverage);
+        let request = PreTradeRiskRequest {
+            inst_id: inst_id.to_string(),
+            side: if has_buy_signal {
+                PositionSide::Long
+            } else {
+                PositionSide::Short
+            },
+            proposed_notional: self.order_notional_usdt,
+            leverage,
+            signal_ts: signal.ts.unwrap_or_else(|| Utc::now().timestamp_millis()),
+        };
+        Ok(!self.evaluate_pre_trade(&request).await.is_rejected())
     }
 }
 impl Default for RiskManagementService {
@@ -82,9 +461,47 @@ impl Default for RiskManagementService {
         Self::new()
     }
 }
+/// 读取布尔开关环境变量
+fn env_flag(key: &str) -> bool {
+    std::env::var(key)
+        .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true"))
+        .unwrap_or(false)
+}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::exchange::SimulatedExchange;
+    struct FixedSnapshot(f64);
+    #[async_trait]
+    impl AccountRiskSnapshotProvider for FixedSnapshot {
+        async fn load_snapshot(&self) -> Result<AccountRiskSnapshot> {
+            Ok(AccountRiskSnapshot {
+                equity: self.0,
+                peak_equity: 10_000.0,
+                day_start_equity: self.0,
+                positions: Vec::new(),
+            })
+        }
+    }
+    struct FailingSnapshot;
+    #[async_trait]
+    impl AccountRiskSnapshotProvider for FailingSnapshot {
+        async fn load_snapshot(&self) -> Result<AccountRiskSnapshot> {
+            Err(anyhow!("exchange unavailable"))
+        }
+    }
+    fn service_with(limits: PreTradeRiskLimits) -> RiskManagementService {
+        RiskManagementService::new().with_policy(PreTradeRiskPolicy::new(limits))
+    }
+    fn request(signal_ts: i64) -> PreTradeRiskRequest {
+        PreTradeRiskRequest {
+            inst_id: "BTC-USDT-SWAP".to_string(),
+            side: PositionSide::Long,
+            proposed_notional: None,
+            leverage: None,
+            signal_ts,
+        }
+    }
     #[tokio::test]
     /// 封装当前函数，减少风控调用方重复实现相同细节。
     /// 采用 async 以便与数据库/网络 I/O 协调，减少阻塞并提升并发吞吐。
@@ -107,4 +524,200 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap());
     }
+    #[tokio::test]
+    async fn test_check_signal_risk_rejects_leverage_above_cap() {
+        use rust_quant_domain::enums::{StrategyType, Timeframe};
+        use serde_json::json;
+        let service = service_with(PreTradeRiskLimits {
+            max_leverage: 10.0,
+            ..PreTradeRiskLimits::default()
+        });
+        let mut signal = SignalResult::empty();
+        signal.should_buy = Some(true);
+        signal.ts = Some(1_000);
+        let config = StrategyConfig::new(
+            1,
+            StrategyType::Vegas,
+            "BTC-USDT".to_string(),
+            Timeframe::H1,
+            json!({}),
+            json!({ "max_loss_percent": 0.02, "max_leverage": 50.0 }),
+        );
+        assert!(!service
+            .check_signal_risk("BTC-USDT", &signal, &config)
+            .await
+            .unwrap());
+    }
+    #[tokio::test]
+    async fn recorded_trades_trigger_frequency_limit() {
+        let service = service_with(PreTradeRiskLimits {
+            max_trades_per_symbol: 3,
+            ..PreTradeRiskLimits::default()
+        });
+        for ts in [1_000, 2_000, 3_000] {
+            assert!(!service.evaluate_pre_trade(&request(ts)).await.is_rejected());
+            service.record_trade("BTC-USDT-SWAP", ts);
+        }
+        assert!(service
+            .evaluate_pre_trade(&request(4_000))
+            .await
+            .is_rejected());
+        // 其他交易对不受影响
+        let mut other = request(4_000);
+        other.inst_id = "ETH-USDT-SWAP".to_string();
+        assert_eq!(
+            service.evaluate_pre_trade(&other).await,
+            RiskDecision::Allow
+        );
+    }
+    #[tokio::test]
+    async fn snapshot_drawdown_shrinks_and_failures_follow_fail_mode() {
+        let drawdown_limits = PreTradeRiskLimits {
+            max_drawdown_percent: 20.0,
+            warning_drawdown_percent: 15.0,
+            ..PreTradeRiskLimits::default()
+        };
+        let service = service_with(drawdown_limits.clone())
+            .with_snapshot_provider(Arc::new(FixedSnapshot(8_400.0)));
+        assert_eq!(
+            RiskManagementService::new()
+                .with_snapshot_provider(Arc::new(FixedSnapshot(8_400.0)))
+                .evaluate_pre_trade(&request(1))
+                .await,
+            RiskDecision::Allow
+        );
+        assert_eq!(
+            service.evaluate_pre_trade(&request(1)).await.size_ratio(),
+            0.5
+        );
+        let fail_open =
+            RiskManagementService::new().with_snapshot_provider(Arc::new(FailingSnapshot));
+        assert_eq!(
+            fail_open.evaluate_pre_trade(&request(1)).await,
+            RiskDecision::Allow
+        );
+        let fail_closed = service_with(drawdown_limits)
+            .with_snapshot_provider(Arc::new(FailingSnapshot))
+            .with_fail_closed(true);
+        assert!(fail_closed
+            .evaluate_pre_trade(&request(1))
+            .await
+            .is_rejected());
+    }
+    fn simulated_provider() -> (Arc<SimulatedExchange>, ExchangeAccountRiskSnapshotProvider) {
+        let exchange = Arc::new(SimulatedExchange::new(ExchangeId::Binance, 10_000.0));
+        let provider =
+            ExchangeAccountRiskSnapshotProvider::new(exchange.clone(), ExchangeId::Binance);
+        (exchange, provider)
+    }
+    #[test]
+    fn exchange_provider_tracks_peak_and_day_start() {
+        let (_, provider) = simulated_provider();
+        let day1 = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2024, 6, 2).unwrap();
+        provider.snapshot_from_equity(10_000.0, Vec::new(), day1);
+        let snapshot = provider.snapshot_from_equity(9_000.0, Vec::new(), day1);
+        assert_eq!(snapshot.peak_equity, 10_000.0);
+        assert_eq!(snapshot.day_start_equity, 10_000.0);
+        let snapshot = provider.snapshot_from_equity(9_500.0, Vec::new(), day2);
+        assert_eq!(snapshot.peak_equity, 10_000.0);
+        assert_eq!(snapshot.day_start_equity, 9_500.0);
+    }
+    #[tokio::test]
+    async fn exchange_provider_loads_open_positions() {
+        use crate::exchange::OrderPlacementRequest;
+        use crypto_exc_all::{Instrument, OrderSide, OrderType};
+        let (exchange, provider) = simulated_provider();
+        let instrument = Instrument::perp("ETH", "USDT").with_settlement("USDT");
+        exchange.set_price(&instrument, 2_000.0);
+        exchange
+            .place_order(OrderPlacementRequest {
+                exchange: ExchangeId::Binance,
+                instrument: instrument.clone(),
+                side: OrderSide::Sell,
+                order_type: OrderType::Market,
+                size: "1.5".to_string(),
+                price: None,
+                margin_mode: None,
+                margin_coin: None,
+                position_side: None,
+                trade_side: None,
+                client_order_id: Some("rqentry1".to_string()),
+                reduce_only: None,
+                time_in_force: None,
+                attached_stop_loss_price: None,
+            })
+            .await
+            .unwrap();
+        exchange.set_price(&instrument, 2_100.0);
+        let snapshot = provider.load_snapshot().await.unwrap();
+        assert_eq!(snapshot.positions.len(), 1);
+        let position = &snapshot.positions[0];
+        assert_eq!(position.symbol, "ETH-USDT-SWAP");
+        assert_eq!(position.side, PositionSide::Short);
+        assert!((position.position_value() - 3_150.0).abs() < 1e-6);
+        assert!(snapshot.equity > 0.0);
+        // 持仓计入总仓位限额
+        let service = service_with(PreTradeRiskLimits {
+            max_total_position_percent: 30.0,
+            ..PreTradeRiskLimits::default()
+        })
+        .with_snapshot_provider(Arc::new(provider));
+        let mut order = request(1);
+        order.inst_id = "BTC-USDT-SWAP".to_string();
+        order.proposed_notional = Some(1_000.0);
+        assert!(service.evaluate_pre_trade(&order).await.is_rejected());
+    }
+    #[tokio::test]
+    async fn strategy_equity_adds_unrealized_pnl_of_strategy_instrument() {
+        use crate::exchange::OrderPlacementRequest;
+        use crypto_exc_all::{Instrument, OrderSide, OrderType};
+        let exchange = Arc::new(SimulatedExchange::new(ExchangeId::Binance, 10_000.0));
+        let provider =
+            ExchangeStrategyEquityProvider::new(exchange.clone(), ExchangeId::Binance, 1_000.0, 0);
+        let instrument = Instrument::perp("ETH", "USDT").with_settlement("USDT");
+        exchange.set_price(&instrument, 2_000.0);
+        assert_eq!(
+            provider.strategy_equity("ETH-USDT-SWAP").await.unwrap(),
+            1_000.0
+        );
+        exchange
+            .place_order(OrderPlacementRequest {
+                exchange: ExchangeId::Binance,
+                instrument: instrument.clone(),
+                side: OrderSide::Sell,
+                order_type: OrderType::Market,
+                size: "1.5".to_string(),
+                price: None,
+                margin_mode: None,
+                margin_coin: None,
+                position_side: None,
+                trade_side: None,
+                client_order_id: Some("rqentry2".to_string()),
+                reduce_only: None,
+                time_in_force: None,
+                attached_stop_loss_price: None,
+            })
+            .await
+            .unwrap();
+        exchange.set_price(&instrument, 2_100.0);
+        // 空单 1.5 ETH 上涨 100，浮亏 150
+        let equity = provider.strategy_equity("ETH-USDT-SWAP").await.unwrap();
+        assert!((equity - 850.0).abs() < 1e-6);
+        // 其它品种不计入
+        let other = provider.strategy_equity("BTC-USDT-SWAP").await.unwrap();
+        assert_eq!(other, 1_000.0);
+        assert!(provider.strategy_equity("ETH-USDT").await.is_err());
+    }
+    #[test]
+    fn exchange_symbols_map_to_swap_inst_ids() {
+        assert_eq!(
+            swap_inst_id_from_exchange_symbol("ETHUSDT"),
+            "ETH-USDT-SWAP"
+        );
+        assert_eq!(
+            swap_inst_id_from_exchange_symbol("BTC-USDT-SWAP"),
+            "BTC-USDT-SWAP"
+        );
+    }
 }
@@ -0,0 +1,1171 @@
+//! 执行算法：把一笔开仓任务拆成多笔子订单。
+//!
+//! TWAP 在给定时长内等分下单；冰山单每次只挂出可见数量，成交后再挂下一笔；
+//! maker 追价以 post-only 挂在买一/卖一并按间隔重挂，价格偏离到达中间价超过
+//! 最大滑点后改用限价 IOC 吃单。算法只依赖 `ExecutionAlgoVenue`，实盘由执行 worker
+//! 提供带审计的实现，测试使用内存模拟交易所。
+use super::execution_order_filters::{
+    format_order_price_decimal, format_order_size_decimal, minimum_order_size,
+    parse_positive_decimal, quantize_limit_order_price, quantize_order_size, ExchangeOrderFilters,
+};
+use crate::exchange::OrderPlacementRequest;
+use anyhow::{anyhow, Result};
+use crypto_exc_all::{
+    CancelOrderRequest, Fill, Instrument, Order, OrderAck, OrderSide, OrderType, TimeInForce,
+};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::{future::Future, pin::Pin, time::Duration};
+/// TWAP 最长执行时长，分钟；更长的拆单应由策略层拆成多个任务。
+const MAX_TWAP_DURATION_MINUTES: u64 = 24 * 60;
+/// TWAP 最多切片数。
+const MAX_TWAP_SLICES: u64 = 500;
+/// maker 追价允许的最大滑点，基点。
+const MAX_MAKER_CHASE_SLIPPAGE_BPS: f64 = 500.0;
+/// maker 追价最多重挂次数。
+const MAX_MAKER_CHASE_REQUOTES: u64 = 100;
+/// 子订单轮询/等待的最长间隔，秒。
+const MAX_ALGO_WAIT_SECONDS: u64 = 3_600;
+/// 场地方法返回的异步结果。
+pub(super) type ExecutionAlgoFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+/// 子订单的下单、撤单、查询与行情能力；实盘实现需要为每笔 mutation 写审计。
+pub(super) trait ExecutionAlgoVenue: Sync {
+    /// 提交一笔子订单。
+    fn place_child_order<'a>(
+        &'a self,
+        request: OrderPlacementRequest,
+    ) -> ExecutionAlgoFuture<'a, crypto_exc_all::Result<OrderAck>>;
+    /// 撤销一笔子订单。
+    fn cancel_child_order<'a>(
+        &'a self,
+        request: CancelOrderRequest,
+    ) -> ExecutionAlgoFuture<'a, crypto_exc_all::Result<OrderAck>>;
+    /// 查询子订单当前状态与成交明细，不等待状态变化。
+    fn child_order_state<'a>(
+        &'a self,
+        ack: &'a OrderAck,
+    ) -> ExecutionAlgoFuture<'a, Result<(Order, Vec<Fill>)>>;
+    /// 读取当前买一/卖一。
+    fn best_quote<'a>(
+        &'a self,
+        instrument: &'a Instrument,
+    ) -> ExecutionAlgoFuture<'a, Result<ExecutionAlgoQuote>>;
+    /// 在子订单之间等待；实盘实现负责在等待前续租任务。
+    fn pause<'a>(&'a self, duration: Duration) -> ExecutionAlgoFuture<'a, Result<()>>;
+}
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionAlgo {
+    /// 在 `duration` 内等分为 `slices` 笔子订单。
+    Twap { slices: u32, duration: Duration },
+    /// 每次只挂出 `visible_size`，单笔超过 `clip_timeout` 未成交则撤单。
+    Iceberg {
+        visible_size: Decimal,
+        clip_timeout: Duration,
+        poll_interval: Duration,
+    },
+    /// post-only 挂买一/卖一，每 `requote_interval` 重挂一次，超过滑点或次数后吃单。
+    MakerChase {
+        max_slippage_bps: f64,
+        requote_interval: Duration,
+        max_requotes: u32,
+    },
+}
+impl ExecutionAlgo {
+    /// 算法名称，与任务载荷中的 `type` 一致。
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Twap { .. } => "twap",
+            Self::Iceberg { .. } => "iceberg",
+            Self::MakerChase { .. } => "maker_chase",
+        }
+    }
+    /// 写入任务结果的参数快照。
+    fn params_json(&self) -> Value {
+        match self {
+            Self::Twap { slices, duration } => json!({
+                "type": self.name(),
+                "slices": slices,
+                "duration_seconds": duration.as_secs(),
+            }),
+            Self::Iceberg {
+                visible_size,
+                clip_timeout,
+                poll_interval,
+            } => json!({
+                "type": self.name(),
+                "visible_size": visible_size.normalize().to_string(),
+                "clip_timeout_seconds": clip_timeout.as_secs(),
+                "poll_interval_seconds": poll_interval.as_secs(),
+            }),
+            Self::MakerChase {
+                max_slippage_bps,
+                requote_interval,
+                max_requotes,
+            } => json!({
+                "type": self.name(),
+                "max_slippage_bps": max_slippage_bps,
+                "requote_interval_seconds": requote_interval.as_secs(),
+                "max_requotes": max_requotes,
+            }),
+        }
+    }
+}
+/// 从任务载荷的 `execution_algo` 或 `execution.algo` 读取执行算法；未配置时返回 `None`。
+pub(super) fn parse_execution_algo(payload: &Value) -> Result<Option<ExecutionAlgo>> {
+    let Some(value) = payload
+        .get("execution_algo")
+        .or_else(|| payload.get("execution").and_then(|value| value.get("algo")))
+        .filter(|value| !value.is_null())
+    else {
+        return Ok(None);
+    };
+    let kind = value
+        .get("type")
+        .and_then(Value::as_str)
+        .map(|kind| kind.trim().to_ascii_lowercase())
+        .ok_or_else(|| anyhow!("execution_algo.type is required"))?;
+    let algo = match kind.as_str() {
+        "twap" => {
+            let slices = algo_u64(value, "slices")?
+                .ok_or_else(|| anyhow!("execution_algo.slices is required for twap"))?;
+            if slices == 0 || slices > MAX_TWAP_SLICES {
+                return Err(anyhow!(
+                    "execution_algo.slices must be between 1 and {MAX_TWAP_SLICES}"
+                ));
+            }
+            let minutes = algo_u64(value, "duration_minutes")?
+                .ok_or_else(|| anyhow!("execution_algo.duration_minutes is required for twap"))?;
+            if minutes == 0 || minutes > MAX_TWAP_DURATION_MINUTES {
+                return Err(anyhow!(
+                    "execution_algo.duration_minutes must be between 1 and {MAX_TWAP_DURATION_MINUTES}"
+                ));
+            }
+            ExecutionAlgo::Twap {
+                slices: slices as u32,
+                duration: Duration::from_secs(minutes * 60),
+            }
+        }
+        "iceberg" => {
+            let visible_size = match value.get("visible_size") {
+                Some(Value::String(raw)) => {
+                    parse_positive_decimal(raw, "execution_algo.visible_size")?
+                }
+                Some(Value::Number(raw)) => {
+                    parse_positive_decimal(&raw.to_string(), "execution_algo.visible_size")?
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "execution_algo.visible_size is required for iceberg"
+                    ))
+                }
+            };
+            let clip_timeout = algo_seconds(value, "clip_timeout_seconds", 60)?;
+            let poll_interval = algo_seconds(value, "poll_interval_seconds", 5)?;
+            if poll_interval > clip_timeout {
+                return Err(anyhow!(
+                    "execution_algo.poll_interval_seconds must not exceed clip_timeout_seconds"
+                ));
+            }
+            ExecutionAlgo::Iceberg {
+                visible_size,
+                clip_timeout,
+                poll_interval,
+            }
+        }
+        "maker_chase" | "makerchase" => {
+            let max_slippage_bps = value
+                .get("max_slippage_bps")
+                .and_then(Value::as_f64)
+                .ok_or_else(|| {
+                    anyhow!("execution_algo.max_slippage_bps is required for maker_chase")
+                })?;
+            if !max_slippage_bps.is_finite()
+                || max_slippage_bps <= 0.0
+                || max_slippage_bps > MAX_MAKER_CHASE_SLIPPAGE_BPS
+            {
+                return Err(anyhow!(
+                    "execution_algo.max_slippage_bps must be in (0, {MAX_MAKER_CHASE_SLIPPAGE_BPS}]"
+                ));
+            }
+            let max_requotes = algo_u64(value, "max_requotes")?.unwrap_or(10);
+            if max_requotes > MAX_MAKER_CHASE_REQUOTES {
+                return Err(anyhow!(
+                    "execution_algo.max_requotes must not exceed {MAX_MAKER_CHASE_REQUOTES}"
+                ));
+            }
+            ExecutionAlgo::MakerChase {
+                max_slippage_bps,
+                requote_interval: algo_seconds(value, "requote_interval_seconds", 5)?,
+                max_requotes: max_requotes as u32,
+            }
+        }
+        other => return Err(anyhow!("unsupported execution_algo.type: {other}")),
+    };
+    Ok(Some(algo))
+}
+fn algo_u64(value: &Value, key: &str) -> Result<Option<u64>> {
+    match value.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(raw) => raw
+            .as_u64()
+            .or_else(|| raw.as_str().and_then(|raw| raw.trim().parse::<u64>().ok()))
+            .map(Some)
+            .ok_or_else(|| anyhow!("execution_algo.{key} must be a non-negative integer")),
+    }
+}
+fn algo_seconds(value: &Value, key: &str, default_seconds: u64) -> Result<Duration> {
+    let seconds = algo_u64(value, key)?.unwrap_or(default_seconds);
+    if seconds == 0 || seconds > MAX_ALGO_WAIT_SECONDS {
+        return Err(anyhow!(
+            "execution_algo.{key} must be between 1 and {MAX_ALGO_WAIT_SECONDS}"
+        ));
+    }
+    Ok(Duration::from_secs(seconds))
+}
+/// 买一/卖一快照。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct ExecutionAlgoQuote {
+    /// 买一价。
+    pub(super) bid: Decimal,
+    /// 卖一价。
+    pub(super) ask: Decimal,
+}
+impl ExecutionAlgoQuote {
+    /// 中间价。
+    pub(super) fn mid(&self) -> Decimal {
+        (self.bid + self.ask) / Decimal::TWO
+    }
+    /// 不吃单的挂单价：买单挂买一，卖单挂卖一。
+    pub(super) fn passive(&self, side: OrderSide) -> Decimal {
+        match side {
+            OrderSide::Buy => self.bid,
+            OrderSide::Sell => self.ask,
+        }
+    }
+}
+/// 按到达中间价和最大滑点计算可接受的最差成交价。
+pub(super) fn maker_chase_price_bound(
+    arrival_mid: Decimal,
+    side: OrderSide,
+    max_slippage_bps: f64,
+) -> Result<Decimal> {
+    let bps = Decimal::try_from(max_slippage_bps)
+        .map_err(|error| anyhow!("invalid max_slippage_bps {max_slippage_bps}: {error}"))?;
+    let offset = arrival_mid * bps / Decimal::from(10_000);
+    Ok(match side {
+        OrderSide::Buy => arrival_mid + offset,
+        OrderSide::Sell => arrival_mid - offset,
+    })
+}
+/// 挂单价是否已越过滑点边界。
+fn beyond_price_bound(price: Decimal, bound: Decimal, side: OrderSide) -> bool {
+    match side {
+        OrderSide::Buy => price > bound,
+        OrderSide::Sell => price < bound,
+    }
+}
+/// 计算下一笔子订单数量：按目标数量截取并对齐步长，剩余不足最小下单量时并入本笔。
+///
+/// 剩余数量本身低于最小下单量或无法对齐交易所过滤器时返回 `None`，调用方应停止拆单。
+pub(super) fn next_child_order_size(
+    remaining: Decimal,
+    target: Decimal,
+    minimum: Decimal,
+    reference_price: Decimal,
+    filters: &ExchangeOrderFilters,
+) -> Option<Decimal> {
+    if remaining <= Decimal::ZERO || remaining < minimum {
+        return None;
+    }
+    let mut size = target.min(remaining).max(minimum);
+    if remaining - size < minimum {
+        size = remaining;
+    }
+    if let Some(max_qty) = filters.max_qty.filter(|value| *value > Decimal::ZERO) {
+        size = size.min(max_qty);
+    }
+    quantize_order_size(size, reference_price, filters, false).ok()
+}
+/// 一笔子订单的请求与交易所事实。
+#[derive(Debug, Clone)]
+pub(super) struct ExecutionAlgoChild {
+    /// 子订单 client_order_id。
+    pub(super) client_order_id: String,
+    /// 订单类型：market 或 limit。
+    pub(super) order_type: &'static str,
+    /// 生效方式；市价单为空。
+    pub(super) time_in_force: Option<&'static str>,
+    /// 请求数量。
+    pub(super) size: Decimal,
+    /// 限价；市价单为空。
+    pub(super) price: Option<Decimal>,
+    /// 交易所受理回执；下单失败时为空。
+    pub(super) ack: Option<OrderAck>,
+    /// 最近一次查询到的订单详情。
+    pub(super) order: Option<Order>,
+    /// 最近一次查询到的成交明细。
+    pub(super) fills: Vec<Fill>,
+    /// 已成交数量。
+    pub(super) filled_qty: Decimal,
+    /// 已成交金额。
+    pub(super) filled_quote: Decimal,
+    /// 手续费合计。
+    pub(super) fee: Decimal,
+    /// 下单、撤单或查询错误。
+    pub(super) error: Option<String>,
+}
+impl ExecutionAlgoChild {
+    /// 订单是否已到终态，或查询到的成交已满足请求数量。
+    fn is_terminal(&self) -> bool {
+        if self.ack.is_none() || self.filled_qty >= self.size {
+            return true;
+        }
+        self.order
+            .as_ref()
+            .and_then(|order| order.status.as_deref())
+            .is_some_and(|status| {
+                matches!(
+                    status.trim().to_ascii_uppercase().as_str(),
+                    "FILLED" | "CANCELED" | "CANCELLED" | "EXPIRED" | "REJECTED"
+                )
+            })
+    }
+    /// 已占用的父单数量：未终态的子订单按请求数量计，避免成交回报延迟导致超量下单。
+    fn committed_qty(&self) -> Decimal {
+        if self.is_terminal() {
+            self.filled_qty
+        } else {
+            self.size
+        }
+    }
+    fn apply_state(&mut self, order: Order, fills: Vec<Fill>) {
+        let fill_qty = sum_decimal(fills.iter().map(|fill| fill.size.as_deref()));
+        self.filled_qty = parse_decimal(order.filled_size.as_deref())
+            .or(fill_qty)
+            .unwrap_or(Decimal::ZERO);
+        let fill_quote = fills.iter().try_fold(Decimal::ZERO, |total, fill| {
+            let price = parse_decimal(fill.price.as_deref())?;
+            let size = parse_decimal(fill.size.as_deref())?;
+            Some(total + price * size)
+        });
+        self.filled_quote = fill_quote
+            .filter(|quote| !fills.is_empty() && *quote > Decimal::ZERO)
+            .or_else(|| {
+                parse_decimal(order.average_price.as_deref()).map(|price| price * self.filled_qty)
+            })
+            .unwrap_or(Decimal::ZERO);
+        self.fee = sum_decimal(fills.iter().map(|fill| fill.fee.as_deref())).unwrap_or_default();
+        self.order = Some(order);
+        self.fills = fills;
+    }
+    fn summary_json(&self) -> Value {
+        json!({
+            "client_order_id": self.client_order_id,
+            "order_id": self.ack.as_ref().and_then(|ack| ack.order_id.clone()),
+            "order_type": self.order_type,
+            "time_in_force": self.time_in_force,
+            "size": self.size.normalize().to_string(),
+            "price": self.price.map(|price| price.normalize().to_string()),
+            "status": self
+                .order
+                .as_ref()
+                .and_then(|order| order.status.clone())
+                .or_else(|| self.ack.as_ref().and_then(|ack| ack.status.clone())),
+            "filled_qty": decimal_to_f64(self.filled_qty),
+            "average_fill_price": self.average_fill_price().map(decimal_to_f64),
+            "fee": decimal_to_f64(self.fee),
+            "error": self.error,
+        })
+    }
+    fn average_fill_price(&self) -> Option<Decimal> {
+        (self.filled_qty > Decimal::ZERO).then(|| self.filled_quote / self.filled_qty)
+    }
+}
+/// 执行算法结果：子订单明细与父单汇总。
+#[derive(Debug, Clone)]
+pub(super) struct ExecutionAlgoOutcome {
+    /// 执行的算法。
+    pub(super) algo: ExecutionAlgo,
+    /// 父单请求数量。
+    pub(super) requested_qty: Decimal,
+    /// 到达时的买一/卖一。
+    pub(super) arrival_quote: ExecutionAlgoQuote,
+    /// 子订单，按提交顺序。
+    pub(super) children: Vec<ExecutionAlgoChild>,
+    /// 提前结束原因；正常拆完为空。
+    pub(super) stop_reason: Option<String>,
+}
+impl ExecutionAlgoOutcome {
+    /// 累计成交数量。
+    pub(super) fn filled_qty(&self) -> Decimal {
+        self.children.iter().map(|child| child.filled_qty).sum()
+    }
+    /// 累计成交金额。
+    pub(super) fn filled_quote(&self) -> Decimal {
+        self.children.iter().map(|child| child.filled_quote).sum()
+    }
+    /// 是否有任何子订单成交。
+    pub(super) fn has_fills(&self) -> bool {
+        self.filled_qty() > Decimal::ZERO
+    }
+    /// 父单成交均价；无成交时为空。
+    pub(super) fn average_fill_price(&self) -> Option<Decimal> {
+        let filled_qty = self.filled_qty();
+        (filled_qty > Decimal::ZERO).then(|| self.filled_quote() / filled_qty)
+    }
+    fn committed_qty(&self) -> Decimal {
+        self.children
+            .iter()
+            .map(ExecutionAlgoChild::committed_qty)
+            .sum()
+    }
+    fn remaining_qty(&self) -> Decimal {
+        (self.requested_qty - self.committed_qty()).max(Decimal::ZERO)
+    }
+    /// 合成父单回执：订单号取最后一笔有成交的子订单，client_order_id 保持父单值。
+    ///
+    /// 回执与订单的 `raw` 以数组形式保留每笔子订单原文，保护单同步仍能从中识别附带止损。
+    pub(super) fn parent_ack(&self, parent: &OrderPlacementRequest) -> Option<OrderAck> {
+        let acked = self
+            .children
+            .iter()
+            .filter_map(|child| child.ack.as_ref())
+            .collect::<Vec<_>>();
+        let reference = self
+            .children
+            .iter()
+            .rev()
+            .filter(|child| child.filled_qty > Decimal::ZERO)
+            .find_map(|child| child.ack.as_ref())
+            .or_else(|| acked.last().copied())?;
+        Some(OrderAck {
+            exchange: reference.exchange,
+            instrument: reference.instrument.clone(),
+            exchange_symbol: reference.exchange_symbol.clone(),
+            order_id: reference.order_id.clone(),
+            client_order_id: parent.client_order_id.clone(),
+            status: Some(self.parent_status().to_string()),
+            raw: json!({
+                "execution_algo": self.algo.name(),
+                "children": acked.iter().map(|ack| ack.raw.clone()).collect::<Vec<_>>(),
+            }),
+        })
+    }
+    /// 合成父单订单详情，成交数量与均价为全部子订单汇总。
+    pub(super) fn parent_order(&self, parent: &OrderPlacementRequest, ack: &OrderAck) -> Order {
+        let orders = self
+            .children
+            .iter()
+            .filter_map(|child| child.order.as_ref())
+            .collect::<Vec<_>>();
+        Order {
+            exchange: ack.exchange,
+            instrument: ack.instrument.clone(),
+            exchange_symbol: ack.exchange_symbol.clone(),
+            order_id: ack.order_id.clone(),
+            client_order_id: parent.client_order_id.clone(),
+            side: orders.iter().find_map(|order| order.side.clone()),
+            order_type: Some(self.algo.name().to_string()),
+            price: parent.price.clone(),
+            size: Some(self.requested_qty.normalize().to_string()),
+            filled_size: Some(self.filled_qty().normalize().to_string()),
+            average_price: self
+                .average_fill_price()
+                .map(|price| price.normalize().to_string()),
+            status: Some(self.parent_status().to_string()),
+            created_at: orders.iter().find_map(|order| order.created_at),
+            updated_at: orders.iter().rev().find_map(|order| order.updated_at),
+            raw: json!({
+                "execution_algo": self.algo.name(),
+                "children": orders.iter().map(|order| order.raw.clone()).collect::<Vec<_>>(),
+            }),
+        }
+    }
+    /// 全部子订单的成交明细。
+    pub(super) fn parent_fills(&self) -> Vec<Fill> {
+        self.children
+            .iter()
+            .flat_map(|child| child.fills.iter().cloned())
+            .collect()
+    }
+    /// 有任何成交即视为父单已成交；未成交数量记录在汇总里，不再补单。
+    fn parent_status(&self) -> &'static str {
+        if self.has_fills() {
+            "FILLED"
+        } else {
+            "CANCELED"
+        }
+    }
+    /// 写入任务结果 `execution_algo` 字段的汇总。
+    pub(super) fn summary_json(&self) -> Value {
+        let filled_qty = self.filled_qty();
+        json!({
+            "algo": self.algo.params_json(),
+            "requested_qty": decimal_to_f64(self.requested_qty),
+            "filled_qty": decimal_to_f64(filled_qty),
+            "unfilled_qty": decimal_to_f64((self.requested_qty - filled_qty).max(Decimal::ZERO)),
+            "filled_quote": decimal_to_f64(self.filled_quote()),
+            "average_fill_price": self.average_fill_price().map(decimal_to_f64),
+            "arrival_bid": decimal_to_f64(self.arrival_quote.bid),
+            "arrival_ask": decimal_to_f64(self.arrival_quote.ask),
+            "child_order_count": self.children.len(),
+            "stop_reason": self.stop_reason,
+            "children": self
+                .children
+                .iter()
+                .map(ExecutionAlgoChild::summary_json)
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+/// 按算法把父单拆成子订单执行，返回全部子订单事实。
+///
+/// 子订单下单失败、等待期间续租失败或算法停滞时提前结束，已成交部分照常汇总；
+/// 返回错误只表示尚未提交任何子订单（如读取行情或过滤器失败）。
+pub(super) async fn run_execution_algo<V>(
+    venue: &V,
+    algo: &ExecutionAlgo,
+    parent: &OrderPlacementRequest,
+    filters: &ExchangeOrderFilters,
+) -> Result<ExecutionAlgoOutcome>
+where
+    V: ExecutionAlgoVenue + ?Sized,
+{
+    let requested_qty = parse_positive_decimal(&parent.size, "execution algo parent size")?;
+    let arrival_quote = venue.best_quote(&parent.instrument).await?;
+    if arrival_quote.bid <= Decimal::ZERO || arrival_quote.ask < arrival_quote.bid {
+        return Err(anyhow!(
+            "invalid arrival quote bid={} ask={}",
+            arrival_quote.bid,
+            arrival_quote.ask
+        ));
+    }
+    let minimum = minimum_order_size(arrival_quote.mid(), filters, true)?;
+    let mut run = AlgoRun {
+        venue,
+        parent,
+        filters,
+        minimum,
+        outcome: ExecutionAlgoOutcome {
+            algo: algo.clone(),
+            requested_qty,
+            arrival_quote,
+            children: Vec::new(),
+            stop_reason: None,
+        },
+    };
+    match algo {
+        ExecutionAlgo::Twap { slices, duration } => run.twap(*slices, *duration).await,
+        ExecutionAlgo::Iceberg {
+            visible_size,
+            clip_timeout,
+            poll_interval,
+        } => {
+            run.iceberg(*visible_size, *clip_timeout, *poll_interval)
+                .await
+        }
+        ExecutionAlgo::MakerChase {
+            max_slippage_bps,
+            requote_interval,
+            max_requotes,
+        } => {
+            run.maker_chase(*max_slippage_bps, *requote_interval, *max_requotes)
+                .await
+        }
+    }
+    run.refresh_open_children().await;
+    Ok(run.outcome)
+}
+struct AlgoRun<'a, V: ?Sized> {
+    venue: &'a V,
+    parent: &'a OrderPlacementRequest,
+    filters: &'a ExchangeOrderFilters,
+    /// 子订单最小下单量，已包含最小名义金额约束。
+    minimum: Decimal,
+    outcome: ExecutionAlgoOutcome,
+}
+impl<V: ExecutionAlgoVenue + ?Sized> AlgoRun<'_, V> {
+    async fn twap(&mut self, slices: u32, duration: Duration) {
+        let interval = duration / slices;
+        let limit_price = match self.parent_limit_price() {
+            Ok(price) => price,
+            Err(error) => return self.stop(error.to_string()),
+        };
+        for slice in 0..slices {
+            if slice > 0 && !self.pause(interval).await {
+                return;
+            }
+            let remaining = self.outcome.remaining_qty();
+            let target = remaining / Decimal::from(slices - slice);
+            let Some(size) = self.next_size(remaining, target) else {
+                return;
+            };
+            // 限价父单的切片以 IOC 按父单限价成交，未成交部分滚入后续切片
+            let placed = match limit_price {
+                Some(price) => {
+                    self.place_child(OrderType::Limit, Some(TimeInForce::Ioc), size, Some(price))
+                        .await
+                }
+                None => self.place_child(OrderType::Market, None, size, None).await,
+            };
+            if placed.is_none() {
+                return;
+            }
+        }
+    }
+    async fn iceberg(
+        &mut self,
+        visible_size: Decimal,
+        clip_timeout: Duration,
+        poll_interval: Duration,
+    ) {
+        let limit_price = match self.parent_limit_price() {
+            Ok(price) => price,
+            Err(error) => return self.stop(error.to_string()),
+        };
+        loop {
+            let remaining = self.outcome.remaining_qty();
+            let Some(size) = self.next_size(remaining, visible_size) else {
+                return;
+            };
+            // 市价父单的冰山单每次挂在当前买一/卖一
+            let price = match limit_price {
+                Some(price) => price,
+                None => match self.passive_price().await {
+                    Ok(price) => price,
+                    Err(error) => return self.stop(error.to_string()),
+                },
+            };
+            let Some(index) = self
+                .place_child(OrderType::Limit, Some(TimeInForce::Gtc), size, Some(price))
+                .await
+            else {
+                return;
+            };
+            if !self
+                .work_resting_child(index, poll_interval, clip_timeout)
+                .await
+            {
+                return;
+            }
+            if self.outcome.children[index].filled_qty <= Decimal::ZERO {
+                return self.stop(format!(
+                    "iceberg clip {} timed out without fills",
+                    self.outcome.children[index].client_order_id
+                ));
+            }
+        }
+    }
+    async fn maker_chase(
+        &mut self,
+        max_slippage_bps: f64,
+        requote_interval: Duration,
+        max_requotes: u32,
+    ) {
+        let side = self.parent.side;
+        let bound =
+            match maker_chase_price_bound(self.outcome.arrival_quote.mid(), side, max_slippage_bps)
+                .and_then(|bound| {
+                    quantize_limit_order_price(&bound.to_string(), side, self.filters)
+                }) {
+                Ok(bound) => bound,
+                Err(error) => return self.stop(error.to_string()),
+            };
+        for _ in 0..=max_requotes {
+            let remaining = self.outcome.remaining_qty();
+            let Some(size) = self.next_size(remaining, remaining) else {
+                return;
+            };
+            let price = match self.passive_price().await {
+                Ok(price) => price,
+                Err(error) => return self.stop(error.to_string()),
+            };
+            if beyond_price_bound(price, bound, side) {
+                break;
+            }
+            let Some(index) = self
+                .place_child(
+                    OrderType::Limit,
+                    Some(TimeInForce::PostOnly),
+                    size,
+                    Some(price),
+                )
+                .await
+            else {
+                return;
+            };
+            if !self
+                .work_resting_child(index, requote_interval, requote_interval)
+                .await
+            {
+                return;
+            }
+        }
+        // 挂单未能在次数或滑点内成交：以滑点边界价 IOC 吃掉剩余数量
+        let remaining = self.outcome.remaining_qty();
+        if let Some(size) = self.next_size(remaining, remaining) {
+            self.place_child(OrderType::Limit, Some(TimeInForce::Ioc), size, Some(bound))
+                .await;
+        }
+    }
+    fn next_size(&self, remaining: Decimal, target: Decimal) -> Option<Decimal> {
+        next_child_order_size(
+            remaining,
+            target,
+            self.minimum,
+            self.outcome.arrival_quote.mid(),
+            self.filters,
+        )
+    }
+    fn parent_limit_price(&self) -> Result<Option<Decimal>> {
+        if self.parent.order_type != OrderType::Limit {
+            return Ok(None);
+        }
+        let raw = self
+            .parent
+            .price
+            .as_deref()
+            .ok_or_else(|| anyhow!("limit parent order requires price for execution algo"))?;
+        parse_positive_decimal(raw, "execution algo limit price").map(Some)
+    }
+    async fn passive_price(&self) -> Result<Decimal> {
+        let quote = self.venue.best_quote(&self.parent.instrument).await?;
+        quantize_limit_order_price(
+            &quote.passive(self.parent.side).to_string(),
+            self.parent.side,
+            self.filters,
+        )
+    }
+    fn stop(&mut self, reason: String) {
+        self.outcome.stop_reason.get_or_insert(reason);
+    }
+    async fn pause(&mut self, duration: Duration) -> bool {
+        match self.venue.pause(duration).await {
+            Ok(()) => true,
+            Err(error) => {
+                self.stop(format!("execution algo pause failed: {error}"));
+                false
+            }
+        }
+    }
+    /// 提交子订单并立即查询一次状态；下单失败时记录原因并返回 `None`。
+    async fn place_child(
+        &mut self,
+        order_type: OrderType,
+        time_in_force: Option<TimeInForce>,
+        size: Decimal,
+        price: Option<Decimal>,
+    ) -> Option<usize> {
+        let index = self.outcome.children.len();
+        let client_order_id = format!(
+            "{}a{}",
+            self.parent.client_order_id.as_deref().unwrap_or("rqalgo"),
+            index + 1
+        );
+        let mut request = self.parent.clone();
+        request.order_type = order_type;
+        request.size = format_order_size_decimal(size, self.filters);
+        request.price = price.map(|price| format_order_price_decimal(price, self.filters));
+        request.time_in_force = time_in_force;
+        request.client_order_id = Some(client_order_id.clone());
+        self.outcome.children.push(ExecutionAlgoChild {
+            client_order_id,
+            order_type: if order_type == OrderType::Limit {
+                "limit"
+            } else {
+                "market"
+            },
+            time_in_force: time_in_force.map(time_in_force_label),
+            size,
+            price,
+            ack: None,
+            order: None,
+            fills: Vec::new(),
+            filled_qty: Decimal::ZERO,
+            filled_quote: Decimal::ZERO,
+            fee: Decimal::ZERO,
+            error: None,
+        });
+        match self.venue.place_child_order(request).await {
+            Ok(ack) => {
+                self.outcome.children[index].ack = Some(ack);
+                self.refresh_child(index).await;
+                Some(index)
+            }
+            Err(error) => {
+                let message = error.to_string();
+                self.stop(format!(
+                    "child order {} rejected: {message}",
+                    self.outcome.children[index].client_order_id
+                ));
+                self.outcome.children[index].error = Some(message);
+                None
+            }
+        }
+    }
+    async fn refresh_child(&mut self, index: usize) {
+        let Some(ack) = self.outcome.children[index].ack.clone() else {
+            return;
+        };
+        match self.venue.child_order_state(&ack).await {
+            Ok((order, fills)) => self.outcome.children[index].apply_state(order, fills),
+            Err(error) => self.outcome.children[index].error = Some(error.to_string()),
+        }
+    }
+    async fn refresh_open_children(&mut self) {
+        for index in 0..self.outcome.children.len() {
+            if !self.outcome.children[index].is_terminal() {
+                self.refresh_child(index).await;
+            }
+        }
+    }
+    /// 等待挂单成交，超时后撤单并读取最终成交；等待期间续租失败时撤单并返回 `false`。
+    async fn work_resting_child(
+        &mut self,
+        index: usize,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> bool {
+        let mut waited = Duration::ZERO;
+        while !self.outcome.children[index].is_terminal() {
+            if waited >= timeout {
+                self.cancel_child(index).await;
+                return true;
+            }
+            let step = poll_interval.min(timeout - waited);
+            if !self.pause(step).await {
+                self.cancel_child(index).await;
+                return false;
+            }
+            waited += step;
+            self.refresh_child(index).await;
+        }
+        true
+    }
+    async fn cancel_child(&mut self, index: usize) {
+        let child = &self.outcome.children[index];
+        let Some(ack) = child.ack.as_ref() else {
+            return;
+        };
+        let request = match ack.order_id.as_deref() {
+            Some(order_id) => {
+                CancelOrderRequest::by_order_id(ack.instrument.clone(), order_id.to_string())
+            }
+            None => CancelOrderRequest::by_client_order_id(
+                ack.instrument.clone(),
+                child.client_order_id.clone(),
+            ),
+        };
+        // 撤单失败通常是订单已成交或已撤，以随后的订单查询为准
+        if let Err(error) = self.venue.cancel_child_order(request).await {
+            self.outcome.children[index].error = Some(format!("cancel failed: {error}"));
+        }
+        self.refresh_child(index).await;
+    }
+}
+fn time_in_force_label(time_in_force: TimeInForce) -> &'static str {
+    if time_in_force == TimeInForce::PostOnly {
+        "post_only"
+    } else if time_in_force == TimeInForce::Ioc {
+        "ioc"
+    } else if time_in_force == TimeInForce::Fok {
+        "fok"
+    } else {
+        "gtc"
+    }
+}
+fn parse_decimal(raw: Option<&str>) -> Option<Decimal> {
+    raw.and_then(|raw| raw.trim().parse::<Decimal>().ok())
+}
+fn sum_decimal<'a>(values: impl Iterator<Item = Option<&'a str>>) -> Option<Decimal> {
+    let mut total = None;
+    for value in values.filter_map(parse_decimal) {
+        total = Some(total.unwrap_or(Decimal::ZERO) + value);
+    }
+    total
+}
+fn decimal_to_f64(value: Decimal) -> f64 {
+    value.normalize().to_string().parse::<f64>().unwrap_or(0.0)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::{ExchangeGateway, SimulatedExchange};
+    use crypto_exc_all::{ExchangeId, FillListQuery, OrderQuery};
+    use std::sync::Mutex;
+    fn instrument() -> Instrument {
+        Instrument::perp("ETH", "USDT").with_settlement("USDT")
+    }
+    fn filters() -> ExchangeOrderFilters {
+        ExchangeOrderFilters {
+            min_qty: Some(Decimal::new(1, 2)),
+            step_size: Some(Decimal::new(1, 2)),
+            tick_size: Some(Decimal::new(1, 2)),
+            ..ExchangeOrderFilters::default()
+        }
+    }
+    fn parent(side: OrderSide, size: &str) -> OrderPlacementRequest {
+        OrderPlacementRequest {
+            exchange: ExchangeId::Binance,
+            instrument: instrument(),
+            side,
+            order_type: OrderType::Market,
+            size: size.to_string(),
+            price: None,
+            margin_mode: None,
+            margin_coin: None,
+            position_side: None,
+            trade_side: None,
+            client_order_id: Some("rqtask7".to_string()),
+            reduce_only: None,
+            time_in_force: None,
+            attached_stop_loss_price: Some("1900".to_string()),
+        }
+    }
+    /// 模拟交易所之上的场地：每次等待推进一格脚本价格，盘口为最新价上下各 0.5。
+    struct ScriptedVenue {
+        exchange: SimulatedExchange,
+        prices: Mutex<Vec<f64>>,
+        last_price: Mutex<f64>,
+        pauses: Mutex<Vec<Duration>>,
+    }
+    impl ScriptedVenue {
+        fn new(start: f64, path: &[f64]) -> Self {
+            let exchange = SimulatedExchange::new(ExchangeId::Binance, 100_000.0);
+            exchange.set_price(&instrument(), start);
+            let mut prices = path.to_vec();
+            prices.reverse();
+            Self {
+                exchange,
+                prices: Mutex::new(prices),
+                last_price: Mutex::new(start),
+                pauses: Mutex::new(Vec::new()),
+            }
+        }
+        fn gateway(&self) -> &dyn ExchangeGateway {
+            &self.exchange
+        }
+    }
+    impl ExecutionAlgoVenue for ScriptedVenue {
+        fn place_child_order<'a>(
+            &'a self,
+            request: OrderPlacementRequest,
+        ) -> ExecutionAlgoFuture<'a, crypto_exc_all::Result<OrderAck>> {
+            Box::pin(self.gateway().place_order(request))
+        }
+        fn cancel_child_order<'a>(
+            &'a self,
+            request: CancelOrderRequest,
+        ) -> ExecutionAlgoFuture<'a, crypto_exc_all::Result<OrderAck>> {
+            Box::pin(self.gateway().cancel_order(ExchangeId::Binance, request))
+        }
+        fn child_order_state<'a>(
+            &'a self,
+            ack: &'a OrderAck,
+        ) -> ExecutionAlgoFuture<'a, Result<(Order, Vec<Fill>)>> {
+            Box::pin(async move {
+                let order_id = ack.order_id.clone().unwrap();
+                let order = self
+                    .gateway()
+                    .order(
+                        ack.exchange,
+                        OrderQuery::by_order_id(ack.instrument.clone(), order_id.clone()),
+                    )
+                    .await?;
+                let fills = self
+                    .gateway()
+                    .fills(
+                        ack.exchange,
+                        FillListQuery::for_instrument(ack.instrument.clone())
+                            .with_order_id(order_id),
+                    )
+                    .await?;
+                Ok((order, fills))
+            })
+        }
+        fn best_quote<'a>(
+            &'a self,
+            _instrument: &'a Instrument,
+        ) -> ExecutionAlgoFuture<'a, Result<ExecutionAlgoQuote>> {
+            let last = Decimal::try_from(*self.last_price.lock().unwrap()).unwrap();
+            let half_spread = Decimal::new(5, 1);
+            Box::pin(async move {
+                Ok(ExecutionAlgoQuote {
+                    bid: last - half_spread,
+                    ask: last + half_spread,
+                })
+            })
+        }
+        fn pause<'a>(&'a self, duration: Duration) -> ExecutionAlgoFuture<'a, Result<()>> {
+            self.pauses.lock().unwrap().push(duration);
+            if let Some(price) = self.prices.lock().unwrap().pop() {
+                *self.last_price.lock().unwrap() = price;
+                self.exchange.set_price(&instrument(), price);
+            }
+            Box::pin(async { Ok(()) })
+        }
+    }
+    #[test]
+    fn parses_algo_from_execution_block_and_rejects_bad_params() {
+        let twap = parse_execution_algo(&json!({
+            "execution": {"algo": {"type": "twap", "slices": 6, "duration_minutes": 30}}
+        }))
+        .unwrap();
+        assert_eq!(
+            twap,
+            Some(ExecutionAlgo::Twap {
+                slices: 6,
+                duration: Duration::from_secs(1_800),
+            })
+        );
+        let iceberg = parse_execution_algo(&json!({
+            "execution_algo": {"type": "iceberg", "visible_size": "0.5"}
+        }))
+        .unwrap()
+        .unwrap();
+        assert_eq!(iceberg.name(), "iceberg");
+        assert!(parse_execution_algo(&json!({})).unwrap().is_none());
+        assert!(parse_execution_algo(&json!({
+            "execution_algo": {"type": "twap", "slices": 0, "duration_minutes": 5}
+        }))
+        .is_err());
+        assert!(parse_execution_algo(&json!({
+            "execution_algo": {"type": "maker_chase", "max_slippage_bps": 900}
+        }))
+        .is_err());
+        assert!(parse_execution_algo(&json!({"execution_algo": {"type": "vwap"}})).is_err());
+    }
+    #[test]
+    fn child_size_merges_remainder_below_minimum() {
+        let filters = filters();
+        let minimum = Decimal::new(5, 2);
+        let price = Decimal::from(2_000);
+        let size = |remaining: i64, target: i64| {
+            next_child_order_size(
+                Decimal::new(remaining, 2),
+                Decimal::new(target, 2),
+                minimum,
+                price,
+                &filters,
+            )
+        };
+        assert_eq!(size(100, 33), Some(Decimal::new(33, 2)));
+        assert_eq!(size(36, 33), Some(Decimal::new(36, 2)));
+        assert_eq!(size(100, 0), Some(Decimal::new(5, 2)));
+        assert_eq!(size(4, 33), None);
+        assert_eq!(
+            maker_chase_price_bound(Decimal::from(2_000), OrderSide::Sell, 10.0).unwrap(),
+            Decimal::from(1_998)
+        );
+    }
+    #[tokio::test]
+    async fn twap_splits_evenly_and_reports_average_price() {
+        let venue = ScriptedVenue::new(2_000.0, &[2_010.0, 2_020.0]);
+        let algo = ExecutionAlgo::Twap {
+            slices: 3,
+            duration: Duration::from_secs(90),
+        };
+        let outcome = run_execution_algo(&venue, &algo, &parent(OrderSide::Buy, "1"), &filters())
+            .await
+            .unwrap();
+        let sizes = outcome
+            .children
+            .iter()
+            .map(|child| child.size)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sizes,
+            vec![
+                Decimal::new(33, 2),
+                Decimal::new(33, 2),
+                Decimal::new(34, 2)
+            ]
+        );
+        assert_eq!(outcome.filled_qty(), Decimal::ONE);
+        let expected = (2_000.0 * 0.33 + 2_010.0 * 0.33 + 2_020.0 * 0.34) / 1.0;
+        let average = decimal_to_f64(outcome.average_fill_price().unwrap());
+        assert!((average - expected).abs() < 1e-9);
+        assert_eq!(
+            *venue.pauses.lock().unwrap(),
+            vec![Duration::from_secs(30); 2]
+        );
+        assert_eq!(outcome.children[2].client_order_id, "rqtask7a3");
+        let ack = outcome.parent_ack(&parent(OrderSide::Buy, "1")).unwrap();
+        assert_eq!(ack.client_order_id.as_deref(), Some("rqtask7"));
+        assert_eq!(ack.status.as_deref(), Some("FILLED"));
+        let order = outcome.parent_order(&parent(OrderSide::Buy, "1"), &ack);
+        assert_eq!(order.filled_size.as_deref(), Some("1"));
+        assert_eq!(outcome.parent_fills().len(), 3);
+        let summary = outcome.summary_json();
+        assert_eq!(summary["child_order_count"], json!(3));
+        assert!((summary["average_fill_price"].as_f64().unwrap() - expected).abs() < 1e-9);
+    }
+    #[tokio::test]
+    async fn iceberg_posts_visible_clips_and_stops_when_a_clip_stalls() {
+        // 买单挂在买一 1999.5；价格先回落成交两笔，随后上行导致第三笔超时撤单
+        let venue = ScriptedVenue::new(
+            2_000.0,
+            &[1_999.0, 2_000.0, 1_998.0, 2_005.0, 2_006.0, 2_007.0],
+        );
+        let algo = ExecutionAlgo::Iceberg {
+            visible_size: Decimal::new(4, 1),
+            clip_timeout: Duration::from_secs(2),
+            poll_interval: Duration::from_secs(1),
+        };
+        let outcome = run_execution_algo(&venue, &algo, &parent(OrderSide::Buy, "1"), &filters())
+            .await
+            .unwrap();
+        assert_eq!(outcome.children.len(), 3);
+        assert_eq!(outcome.children[0].size, Decimal::new(4, 1));
+        assert_eq!(outcome.children[2].size, Decimal::new(2, 1));
+        assert_eq!(outcome.filled_qty(), Decimal::new(8, 1));
+        assert_eq!(
+            outcome.children[2]
+                .order
+                .as_ref()
+                .unwrap()
+                .status
+                .as_deref(),
+            Some("CANCELED")
+        );
+        assert!(outcome
+            .stop_reason
+            .as_deref()
+            .unwrap()
+            .contains("timed out without fills"));
+        assert_eq!(outcome.summary_json()["unfilled_qty"].as_f64(), Some(0.2));
+    }
+    #[tokio::test]
+    async fn maker_chase_requotes_then_falls_back_to_taker_within_slippage() {
+        // 卖单挂卖一，价格持续下行无人吃单；重挂两次后以滑点边界价 IOC 成交
+        let venue = ScriptedVenue::new(2_000.0, &[1_999.0, 1_998.5, 1_998.2]);
+        let algo = ExecutionAlgo::MakerChase {
+            max_slippage_bps: 10.0,
+            requote_interval: Duration::from_secs(5),
+            max_requotes: 1,
+        };
+        let outcome =
+            run_execution_algo(&venue, &algo, &parent(OrderSide::Sell, "0.5"), &filters())
+                .await
+                .unwrap();
+        let kinds = outcome
+            .children
+            .iter()
+            .map(|child| (child.time_in_force, child.price))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (Some("post_only"), Some(Decimal::new(20005, 1))),
+                (Some("post_only"), Some(Decimal::new(19995, 1))),
+                (Some("ioc"), Some(Decimal::from(1_998))),
+            ]
+        );
+        assert_eq!(outcome.filled_qty(), Decimal::new(5, 1));
+        assert!(outcome.children[2].fills[0].role.as_deref() == Some("taker"));
+        assert!(outcome.stop_reason.is_none());
+        assert!(outcome.children[..2]
+            .iter()
+            .all(|child| child.filled_qty == Decimal::ZERO));
+    }
+}
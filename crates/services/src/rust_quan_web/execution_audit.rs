@@ -725,24 +725,31 @@ impl PostgresExecutionAuditRepository {
     }
     /// 从外部输入转换为内部模型，隔离 Web 商业、会员和执行准备度 的字段适配细节。
     pub fn from_env() -> Result<Option<Self>> {
-        let database_url = std::env::var("QUANT_CORE_DATABASE_URL")
-            .or_else(|_| std::env::var("QUANT_CORE_POSTGRES_URL"))
-            .or_else(|_| std::env::var("POSTGRES_QUANT_CORE_DATABASE_URL"))
-            .ok();
-        let Some(database_url) = database_url else {
+        let Some(database_url) = quant_core_database_url_from_env() else {
             return Ok(None);
         };
-        let max_connections = std::env::var("QUANT_CORE_DB_MAX_CONNECTIONS")
-            .ok()
-            .and_then(|value| value.parse::<u32>().ok())
-            .unwrap_or(5);
-        let pool = PgPoolOptions::new()
-            .max_connections(max_connections)
-            .acquire_timeout(Duration::from_secs(5))
-            .connect_lazy(&database_url)?;
-        Ok(Some(Self::new(pool)))
+        Ok(Some(Self::new(quant_core_pool_from_url(&database_url)?)))
     }
 }
+/// 读取 quant-core Postgres 连接串，兼容历史环境变量名。
+pub(super) fn quant_core_database_url_from_env() -> Option<String> {
+    std::env::var("QUANT_CORE_DATABASE_URL")
+        .or_else(|_| std::env::var("QUANT_CORE_POSTGRES_URL"))
+        .or_else(|_| std::env::var("POSTGRES_QUANT_CORE_DATABASE_URL"))
+        .ok()
+}
+/// 按 `QUANT_CORE_DB_MAX_CONNECTIONS` 创建延迟连接的 quant-core 连接池。
+pub(super) fn quant_core_pool_from_url(database_url: &str) -> Result<PgPool> {
+    let max_connections = std::env::var("QUANT_CORE_DB_MAX_CONNECTIONS")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(5);
+    let pool = PgPoolOptions::new()
+        .max_connections(max_connections)
+        .acquire_timeout(Duration::from_secs(5))
+        .connect_lazy(database_url)?;
+    Ok(pool)
+}
 #[async_trait]
 impl ExecutionAuditRepository for PostgresExecutionAuditRepository {
     fn can_audit_live_mutations(&self) -> bool {
@@ -23,12 +23,21 @@ pub(super) struct ExchangeOrderFilters {
     /// contract值currency；为空时表示该条件不启用。
     pub(super) contract_value_currency: Option<String>,
 }
+#[cfg(test)]
+tokio::task_local! {
+    /// 测试注入的过滤器；在作用域内替代 exchange_symbols 查询，使整条实盘链路可以离线运行。
+    pub(super) static TEST_EXCHANGE_ORDER_FILTERS: ExchangeOrderFilters;
+}
 /// 封装当前函数，减少Web 商业链路调用方重复实现相同细节。
 /// 采用 async 以便与数据库/网络 I/O 协调，减少阻塞并提升并发吞吐。
 pub(super) async fn load_exchange_order_filters(
     exchange: ExchangeId,
     symbol: &str,
 ) -> Result<Option<ExchangeOrderFilters>> {
+    #[cfg(test)]
+    if let Ok(filters) = TEST_EXCHANGE_ORDER_FILTERS.try_with(Clone::clone) {
+        return Ok(Some(filters));
+    }
     let database_url = std::env::var("QUANT_CORE_DATABASE_URL")
         .or_else(|_| std::env::var("POSTGRES_QUANT_CORE_DATABASE_URL"))
         .map_err(|_| anyhow!("QUANT_CORE_DATABASE_URL is required for live order filter checks"))?;
@@ -56,6 +56,22 @@ pub(super) fn payload_f64(payload: &Value, key: &str) -> Option<f64> {
         _ => None,
     })
 }
+/// 读取下单前风控缩仓比例，缺失或非法时按 1 处理，结果限制在 (0, 1]。
+pub(super) fn payload_position_size_ratio(payload: &Value) -> f64 {
+    payload_f64(payload, "position_size_ratio")
+        .filter(|ratio| ratio.is_finite() && *ratio > 0.0)
+        .map_or(1.0, |ratio| ratio.min(1.0))
+}
+/// 按缩仓比例下调下单数量；比例为 1 时保留原始数量字符串。
+pub(super) fn scale_order_size(size: String, ratio: f64) -> String {
+    if ratio >= 1.0 {
+        return size;
+    }
+    match size.trim().parse::<f64>() {
+        Ok(value) if value.is_finite() && value > 0.0 => format_order_size(value * ratio),
+        _ => size,
+    }
+}
 /// 封装嵌套载荷f64，减少Web 商业链路调用方重复实现相同细节。
 pub(super) fn nested_payload_f64(payload: &Value, parent: &str, key: &str) -> Option<f64> {
     payload
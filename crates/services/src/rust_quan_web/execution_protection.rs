@@ -8,7 +8,7 @@ use super::execution_payload::{
     protective_stop_loss_required, risk_plan_direction_raw, selected_stop_loss_price,
 };
 use super::execution_worker::ExecutionOrderTask;
-use crate::exchange::CryptoExcAllGateway;
+use crate::exchange::{CryptoExcAllGateway, ExchangeGateway};
 use crate::rust_quan_web::{ExecutionTask, ExecutionTaskReportRequest};
 use anyhow::{anyhow, Result};
 use crypto_exc_all::{
@@ -24,7 +24,7 @@ pub(super) trait ProtectiveOrderMutator {
     fn audit_place_protective<'a>(
         &'a self,
         task: &'a ExecutionTask,
-        gateway: &'a CryptoExcAllGateway,
+        gateway: &'a dyn ExchangeGateway,
         exchange: ExchangeId,
         request: ProtectiveOrderRequest,
     ) -> Pin<Box<dyn Future<Output = crypto_exc_all::Result<OrderAck>> + Send + 'a>>;
@@ -32,7 +32,7 @@ pub(super) trait ProtectiveOrderMutator {
     fn audit_cancel_protective<'a>(
         &'a self,
         task: &'a ExecutionTask,
-        gateway: &'a CryptoExcAllGateway,
+        gateway: &'a dyn ExchangeGateway,
         exchange: ExchangeId,
         request: CancelOrderRequest,
     ) -> Pin<Box<dyn Future<Output = crypto_exc_all::Result<OrderAck>> + Send + 'a>>;
@@ -312,7 +312,7 @@ impl PrearmedProtectiveOrder {
     pub(super) async fn cancel_after_main_order_failure(
         &self,
         task: &ExecutionTask,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
         mutator: &impl ProtectiveOrderMutator,
     ) -> crypto_exc_all::Result<OrderAck> {
         mutator
@@ -756,7 +756,7 @@ fn fixed_size_prearmed_protective_order_request(
 }
 /// 提供prearmprotective订单ifrequired的集中实现，避免Web 商业链路调用方重复处理相同细节。
 pub(super) async fn prearm_protective_order_if_required(
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     order_task: &ExecutionOrderTask,
     protection: Option<&ProtectionSyncContract>,
     task: &ExecutionTask,
@@ -921,7 +921,7 @@ pub(super) fn protective_order_result_to_sync_outcome(
 }
 /// 提供placeandconfirmprotective订单的集中实现，避免Web 商业链路调用方重复处理相同细节。
 pub(super) async fn place_and_confirm_protective_order(
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     exchange: ExchangeId,
     request: ProtectiveOrderRequest,
     task: &ExecutionTask,
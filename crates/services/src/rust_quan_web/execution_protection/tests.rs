@@ -32,9 +32,11 @@ fn binance_buy_order_task() -> ExecutionOrderTask {
         reduce_only: None,
         time_in_force: None,
         size_usdt: None,
+        position_size_ratio: 1.0,
         risk_reserved: false,
         attached_stop_loss_price: Some("2100".to_string()),
         take_profit_legs: Vec::new(),
+        execution_algo: None,
     }
 }
 fn long_protection() -> ProtectionSyncContract {
@@ -64,9 +66,11 @@ fn attached_stop_loss_order_task(exchange: ExchangeId) -> ExecutionOrderTask {
         reduce_only: None,
         time_in_force: None,
         size_usdt: None,
+        position_size_ratio: 1.0,
         risk_reserved: false,
         attached_stop_loss_price: Some("2100".to_string()),
         take_profit_legs: Vec::new(),
+        execution_algo: None,
     }
 }
 fn attached_stop_loss_ack(exchange: ExchangeId, raw: Value) -> OrderAck {
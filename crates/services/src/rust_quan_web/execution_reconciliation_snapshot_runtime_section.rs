@@ -810,6 +810,7 @@ async fn report_reconciliation(
                 redact_error_message(error.to_string())
             )
         })?;
+    rust_quant_core::metrics::record_reconciliation_mismatch(request.issue_type.as_str());
     Ok(json!({
         "combo_id": response.combo_id,
         "symbol": response.symbol,
@@ -135,9 +135,11 @@ mod tests {
             reduce_only: None,
             time_in_force: None,
             size_usdt: None,
+            position_size_ratio: 1.0,
             risk_reserved: false,
             attached_stop_loss_price: None,
             take_profit_legs: Vec::new(),
+            execution_algo: None,
         };
         let mut report = ExecutionTaskReportRequest::success(
             42,
@@ -16,7 +16,7 @@ use super::execution_protection::{
     ProtectionSyncContract, ProtectionSyncOutcome, ProtectiveDirection, ProtectiveOrderMutator,
 };
 use super::execution_worker::ExecutionOrderTask;
-use crate::exchange::{CryptoExcAllGateway, OrderPlacementRequest};
+use crate::exchange::{CryptoExcAllGateway, ExchangeGateway, OrderPlacementRequest};
 use crate::rust_quan_web::{ExecutionTask, ExecutionTaskReportRequest};
 use anyhow::{anyhow, Result};
 use crypto_exc_all::{
@@ -31,7 +31,7 @@ pub(super) trait TakeProfitOrderPlacer {
     fn place_take_profit_order<'a>(
         &'a self,
         task: &'a ExecutionTask,
-        gateway: &'a CryptoExcAllGateway,
+        gateway: &'a dyn ExchangeGateway,
         request: OrderPlacementRequest,
     ) -> Pin<Box<dyn Future<Output = crypto_exc_all::Result<OrderAck>> + Send + 'a>>;
 }
@@ -320,7 +320,7 @@ pub(super) fn build_take_profit_stop_reset_plan_with_tracking(
 }
 /// 提供placeandconfirmtake盈利订单的集中实现，避免Web 商业链路调用方重复处理相同细节。
 pub(super) async fn place_and_confirm_take_profit_orders(
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     requests: Vec<OrderPlacementRequest>,
     task: &ExecutionTask,
     placer: &impl TakeProfitOrderPlacer,
@@ -480,7 +480,7 @@ fn response_take_profit_client_order_id(client_order_id: Option<&str>) -> Option
 }
 /// 同步 Web 商业、会员和执行准备度 数据，保证本地状态与外部事实源保持一致。
 pub(super) async fn sync_take_profit_orders_after_main_fill(
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     order_task: &ExecutionOrderTask,
     filled_qty: Option<f64>,
     task: &ExecutionTask,
@@ -531,7 +531,7 @@ pub(super) async fn sync_take_profit_orders_after_main_fill(
 }
 /// 同步 Web 商业、会员和执行准备度 数据，保证本地状态与外部事实源保持一致。
 pub(super) async fn sync_take_profit_stop_reset_after_fills(
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     order_task: &ExecutionOrderTask,
     filters: &ExchangeOrderFilters,
     previous_raw_payload_json: Option<&str>,
@@ -763,7 +763,7 @@ pub(super) fn take_profit_stop_reset_monitor_required(raw_payload_json: &str) ->
 }
 /// 执行 Web 商业、会员和执行准备度 主流程，并把外部依赖调用、状态推进和错误返回串起来。
 async fn execute_take_profit_stop_reset_plan(
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     exchange: ExchangeId,
     plan: TakeProfitStopResetPlan,
     checked_orders: Vec<Value>,
@@ -1458,7 +1458,7 @@ fn take_profit_order_is_filled(order: &Order) -> bool {
 }
 /// 提供existingtake盈利订单的集中实现，避免Web 商业链路调用方重复处理相同细节。
 async fn existing_take_profit_order(
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     request: &OrderPlacementRequest,
 ) -> Result<Option<crypto_exc_all::Order>> {
     let Some(client_order_id) = request.client_order_id.as_deref() else {
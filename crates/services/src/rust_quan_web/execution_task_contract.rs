@@ -685,6 +685,26 @@ pub struct StrategySignalDispatchResponse {
     /// 列表数据。
     pub generated_tasks: Vec<ExecutionTask>,
 }
+/// 批量撤销待执行任务请求；由回撤熔断等风控动作触发。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct ExecutionTaskCancelPendingRequest {
+    /// 数据来源。
+    pub source: String,
+    /// 撤销原因。
+    pub reason: String,
+    /// 策略Key（`strategy_type:inst_id:period:config_id`）；为空时不按策略过滤。
+    pub strategy_key: Option<String>,
+    /// 交易对或资产符号；为空时不按交易对过滤。
+    pub symbol: Option<String>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct ExecutionTaskCancelPendingResponse {
+    #[serde(default)]
+    /// 已撤销的任务 ID 列表。
+    pub cancelled_task_ids: Vec<i64>,
+}
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub struct MarketVelocityPaperOutcomeRequest {
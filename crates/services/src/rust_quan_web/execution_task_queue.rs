@@ -0,0 +1,987 @@
+use super::execution_audit::{quant_core_database_url_from_env, quant_core_pool_from_url};
+use super::execution_payload::{
+    order_payload, payload_f64, payload_string, protection_entry_price, selected_stop_loss_price,
+};
+use super::execution_task_contract::{
+    ApiCredentialCheckSummary, ApiCredentialExecutionReadiness, ExchangeOrderResult,
+    ExchangeReconciliationReportRequest, ExchangeReconciliationReportResponse,
+    ExecutionRiskReservationRequest, ExecutionRiskReservationResponse, ExecutionTask,
+    ExecutionTaskCancelPendingRequest, ExecutionTaskCancelPendingResponse,
+    ExecutionTaskConfirmationLease, ExecutionTaskConfirmationLeaseItem, ExecutionTaskLease,
+    ExecutionTaskLeaseExtendRequest, ExecutionTaskLeaseExtendResponse, ExecutionTaskLeaseRequest,
+    ExecutionTaskReportRequest, ExecutionTaskReportResponse, StrategySignalDispatchResponse,
+    StrategySignalInbox, StrategySignalSubmitRequest, UserExchangeConfig,
+};
+use super::execution_task_source::ExecutionTaskSource;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
+use sqlx::{postgres::PgRow, PgPool, Row};
+use std::collections::HashMap;
+const DEFAULT_LOCAL_BUYER_EMAIL: &str = "local@quant-core";
+const DEFAULT_LEASE_SECONDS: i64 = 120;
+const DEFAULT_CONFIRMATION_LEASE_OWNER: &str = "quant_core_confirmation";
+const LOCAL_ALLOCATION_MODE: &str = "local_fixed_notional";
+const FAILED_EXTERNAL_ORDER_ID_PREFIX: &str = "failed-task-";
+/// 租约请求未指定状态时只领取新任务，避免把终态或确认中的任务交给执行通道。
+const DEFAULT_LEASE_TASK_STATUSES: &[&str] = &["pending", "pending_close"];
+/// 确认通道负责的状态：订单确认、保护单补挂和止盈后移止损监控。
+const CONFIRMATION_TASK_STATUSES: &[&str] = &[
+    "pending_confirmation",
+    "pending_protection_sync",
+    "pending_take_profit_monitor",
+];
+const LEASE_EXECUTION_TASKS_SQL: &str = r#"
+            WITH candidates AS (
+                SELECT id
+                FROM execution_tasks
+                WHERE task_status = ANY($2::text[])
+                  AND (cardinality($3::text[]) = 0 OR task_type = ANY($3::text[]))
+                  AND (cardinality($4::bigint[]) = 0 OR id = ANY($4::bigint[]))
+                  AND scheduled_at <= NOW()
+                  AND (lease_until IS NULL OR lease_until < NOW())
+                ORDER BY priority DESC, scheduled_at ASC, id ASC
+                LIMIT $5
+                FOR UPDATE SKIP LOCKED
+            ),
+            leased AS (
+                UPDATE execution_tasks task
+                SET lease_owner = $1,
+                    lease_until = NOW() + ($6::bigint * INTERVAL '1 second'),
+                    lease_count = task.lease_count + 1,
+                    updated_at = NOW()
+                FROM candidates
+                WHERE task.id = candidates.id
+                RETURNING task.*
+            )
+            SELECT * FROM leased
+            ORDER BY priority DESC, scheduled_at ASC, id ASC
+            "#;
+const EXTEND_EXECUTION_TASK_LEASE_SQL: &str = r#"
+            UPDATE execution_tasks
+            SET lease_until = GREATEST(lease_until, NOW() + ($3::bigint * INTERVAL '1 second')),
+                updated_at = NOW()
+            WHERE id = $1
+              AND lease_owner = $2
+              AND lease_until >= NOW()
+            RETURNING *
+            "#;
+const LEASE_CONFIRMATION_TASKS_SQL: &str = r#"
+            WITH candidates AS (
+                SELECT task.id
+                FROM execution_tasks task
+                WHERE task.task_status = ANY($2::text[])
+                  AND (cardinality($3::bigint[]) = 0 OR task.id = ANY($3::bigint[]))
+                  AND (task.lease_until IS NULL OR task.lease_until < NOW())
+                  AND EXISTS (
+                      SELECT 1
+                      FROM exchange_order_results result
+                      WHERE result.execution_task_id = task.id
+                  )
+                ORDER BY task.updated_at ASC, task.id ASC
+                LIMIT $4
+                FOR UPDATE SKIP LOCKED
+            ),
+            leased AS (
+                UPDATE execution_tasks task
+                SET lease_owner = $1,
+                    lease_until = NOW() + ($5::bigint * INTERVAL '1 second'),
+                    lease_count = task.lease_count + 1,
+                    updated_at = NOW()
+                FROM candidates
+                WHERE task.id = candidates.id
+                RETURNING task.*
+            )
+            SELECT * FROM leased
+            ORDER BY id ASC
+            "#;
+const SELECT_LATEST_ORDER_RESULTS_SQL: &str = r#"
+            SELECT DISTINCT ON (execution_task_id) *
+            FROM exchange_order_results
+            WHERE execution_task_id = ANY($1::bigint[])
+            ORDER BY execution_task_id, updated_at DESC, id DESC
+            "#;
+const SELECT_EXECUTION_TASK_SQL: &str = r#"
+            SELECT *
+            FROM execution_tasks
+            WHERE id = $1
+            "#;
+const SELECT_EXECUTION_TASK_FOR_UPDATE_SQL: &str = r#"
+            SELECT *
+            FROM execution_tasks
+            WHERE id = $1
+            FOR UPDATE
+            "#;
+const UPSERT_RISK_RESERVATION_SQL: &str = r#"
+            INSERT INTO execution_risk_reservations (
+                execution_task_id,
+                buyer_email,
+                exchange,
+                api_credential_id,
+                allocation_mode,
+                allowed_notional_usdt,
+                required_margin_usdt,
+                stop_risk_usdt,
+                leverage,
+                margin_mode,
+                position_mode
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (execution_task_id) DO UPDATE SET
+                updated_at = NOW()
+            RETURNING *
+            "#;
+const INSERT_EXECUTION_TASK_ATTEMPT_SQL: &str = r#"
+            INSERT INTO execution_task_attempts (
+                execution_task_id,
+                execution_status,
+                exchange,
+                external_order_id,
+                order_side,
+                order_status,
+                filled_qty,
+                filled_quote,
+                fee_amount,
+                profit_usdt,
+                executed_at,
+                error_message,
+                raw_payload_json
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING id, created_at
+            "#;
+const UPSERT_EXCHANGE_ORDER_RESULT_SQL: &str = r#"
+            INSERT INTO exchange_order_results (
+                execution_task_id,
+                combo_id,
+                buyer_email,
+                exchange,
+                external_order_id,
+                order_side,
+                order_status,
+                filled_qty,
+                filled_quote,
+                fee_amount,
+                raw_payload_json
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (execution_task_id, external_order_id) DO UPDATE SET
+                order_status = EXCLUDED.order_status,
+                filled_qty = COALESCE(EXCLUDED.filled_qty, exchange_order_results.filled_qty),
+                filled_quote = COALESCE(EXCLUDED.filled_quote, exchange_order_results.filled_quote),
+                fee_amount = COALESCE(EXCLUDED.fee_amount, exchange_order_results.fee_amount),
+                raw_payload_json = EXCLUDED.raw_payload_json,
+                updated_at = NOW()
+            RETURNING *
+            "#;
+const COMPLETE_EXECUTION_TASK_REPORT_SQL: &str = r#"
+            UPDATE execution_tasks
+            SET task_status = $2,
+                last_error = $3,
+                lease_owner = NULL,
+                lease_until = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#;
+const INSERT_RECONCILIATION_ISSUE_SQL: &str = r#"
+            INSERT INTO exchange_reconciliation_issues (
+                combo_id,
+                buyer_email,
+                symbol,
+                exchange,
+                api_credential_id,
+                issue_type,
+                source_ref,
+                message,
+                detected_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, NOW()))
+            RETURNING id, detected_at
+            "#;
+const CANCEL_PENDING_EXECUTION_TASKS_SQL: &str = r#"
+            UPDATE execution_tasks
+            SET task_status = 'cancelled',
+                last_error = $1,
+                updated_at = NOW()
+            WHERE task_status = 'pending'
+              AND (lease_until IS NULL OR lease_until < NOW())
+              AND ($2::text IS NULL OR request_payload_json ->> 'strategy_key' = $2)
+              AND ($3::text IS NULL OR symbol = $3)
+            RETURNING id
+            "#;
+const INSERT_STRATEGY_SIGNAL_SQL: &str = r#"
+            INSERT INTO strategy_signal_inbox (
+                source,
+                external_id,
+                strategy_slug,
+                strategy_key,
+                symbol,
+                signal_type,
+                direction,
+                title,
+                summary,
+                confidence,
+                payload_json,
+                generated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, COALESCE($12, NOW()))
+            ON CONFLICT (source, external_id) DO NOTHING
+            RETURNING *
+            "#;
+const SELECT_STRATEGY_SIGNAL_SQL: &str = r#"
+            SELECT *
+            FROM strategy_signal_inbox
+            WHERE source = $1
+              AND external_id = $2
+            "#;
+const INSERT_SIGNAL_EXECUTION_TASK_SQL: &str = r#"
+            INSERT INTO execution_tasks (
+                strategy_signal_id,
+                combo_id,
+                buyer_email,
+                strategy_slug,
+                symbol,
+                task_type,
+                task_status,
+                request_payload_json
+            )
+            VALUES ($1, $2, $3, $4, $5, 'execute_signal', 'pending', $6)
+            RETURNING *
+            "#;
+const SELECT_API_CREDENTIAL_SQL: &str = r#"
+            SELECT
+                id,
+                exchange_name,
+                api_key,
+                api_secret,
+                passphrase,
+                is_sandbox,
+                is_enabled,
+                create_time,
+                update_time
+            FROM exchange_apikey_config
+            WHERE id = $1
+              AND is_deleted = 0
+            "#;
+/// 本地任务队列的运行参数。
+///
+/// 单团队部署没有 Web 的会员与组合概念，信号生成的任务统一挂在同一账户和凭证下。
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostgresExecutionTaskSourceConfig {
+    /// 信号生成任务时写入的账户标识。
+    pub buyer_email: String,
+    /// 信号生成任务时写入的组合 ID。
+    pub combo_id: i64,
+    /// 载荷未指定交易所时使用的交易所。
+    pub exchange: Option<String>,
+    /// 载荷未指定凭证时使用的 `exchange_apikey_config.id`。
+    pub api_credential_id: Option<i64>,
+    /// 单任务名义仓位上限 USDT；为空时按任务载荷的 size_usdt 预留。
+    pub max_notional_usdt: Option<f64>,
+    /// 确认租约的持有者标识。
+    pub confirmation_lease_owner: String,
+    /// 新租约的有效秒数。
+    pub lease_seconds: i64,
+}
+impl Default for PostgresExecutionTaskSourceConfig {
+    fn default() -> Self {
+        Self {
+            buyer_email: DEFAULT_LOCAL_BUYER_EMAIL.to_string(),
+            combo_id: 0,
+            exchange: None,
+            api_credential_id: None,
+            max_notional_usdt: None,
+            confirmation_lease_owner: DEFAULT_CONFIRMATION_LEASE_OWNER.to_string(),
+            lease_seconds: DEFAULT_LEASE_SECONDS,
+        }
+    }
+}
+impl PostgresExecutionTaskSourceConfig {
+    /// 从 `EXECUTION_TASK_QUEUE_*` 环境变量读取，未设置的字段保持默认值。
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_string = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        Self {
+            buyer_email: env_string("EXECUTION_TASK_QUEUE_BUYER_EMAIL")
+                .unwrap_or(defaults.buyer_email),
+            combo_id: env_string("EXECUTION_TASK_QUEUE_COMBO_ID")
+                .and_then(|value| value.parse::<i64>().ok())
+                .unwrap_or(defaults.combo_id),
+            exchange: env_string("EXECUTION_TASK_QUEUE_EXCHANGE")
+                .map(|value| value.to_ascii_lowercase()),
+            api_credential_id: env_string("EXECUTION_TASK_QUEUE_API_CREDENTIAL_ID")
+                .and_then(|value| value.parse::<i64>().ok())
+                .filter(|id| *id > 0),
+            max_notional_usdt: env_string("EXECUTION_TASK_QUEUE_MAX_NOTIONAL_USDT")
+                .and_then(|value| value.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value > 0.0),
+            confirmation_lease_owner: env_string("EXECUTION_TASK_QUEUE_CONFIRMATION_LEASE_OWNER")
+                .unwrap_or(defaults.confirmation_lease_owner),
+            lease_seconds: env_string("EXECUTION_TASK_QUEUE_LEASE_SECONDS")
+                .and_then(|value| value.parse::<i64>().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(defaults.lease_seconds),
+        }
+    }
+}
+/// 直接存放在 quant-core Postgres 的执行任务队列。
+///
+/// 租约用 `FOR UPDATE SKIP LOCKED` + `lease_until` 保证多个 worker 不会同时领取同一任务；
+/// 执行报告写入 `execution_task_attempts` 与 `exchange_order_results` 并释放租约，
+/// 状态进入 pending_confirmation 等确认状态后由确认通道继续租约。
+#[derive(Debug, Clone)]
+pub struct PostgresExecutionTaskSource {
+    /// 数据库连接池。
+    pool: PgPool,
+    /// 运行配置。
+    config: PostgresExecutionTaskSourceConfig,
+}
+impl PostgresExecutionTaskSource {
+    pub fn new(pool: PgPool, config: PostgresExecutionTaskSourceConfig) -> Self {
+        Self { pool, config }
+    }
+    /// 本地队列必须有 quant-core 数据库，缺失时直接报错。
+    pub fn from_env() -> Result<Self> {
+        let database_url = quant_core_database_url_from_env().ok_or_else(|| {
+            anyhow!("QUANT_CORE_DATABASE_URL is required for EXECUTION_TASK_SOURCE=postgres")
+        })?;
+        Ok(Self::new(
+            quant_core_pool_from_url(&database_url)?,
+            PostgresExecutionTaskSourceConfig::from_env(),
+        ))
+    }
+    pub fn config(&self) -> &PostgresExecutionTaskSourceConfig {
+        &self.config
+    }
+    /// 批量撤销尚未被租约的待执行任务（回撤熔断触发时调用）。
+    pub async fn cancel_pending_tasks(
+        &self,
+        request: ExecutionTaskCancelPendingRequest,
+    ) -> Result<ExecutionTaskCancelPendingResponse> {
+        let rows = sqlx::query(CANCEL_PENDING_EXECUTION_TASKS_SQL)
+            .bind(format!("{}: {}", request.source, request.reason))
+            .bind(&request.strategy_key)
+            .bind(&request.symbol)
+            .fetch_all(&self.pool)
+            .await?;
+        let cancelled_task_ids = rows
+            .iter()
+            .map(|row| row.try_get::<i64, _>("id"))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(ExecutionTaskCancelPendingResponse { cancelled_task_ids })
+    }
+    async fn load_api_credential_row(&self, credential_id: i64) -> Result<PgRow> {
+        let id = i32::try_from(credential_id)
+            .map_err(|_| anyhow!("api_credential_id {credential_id} is out of range"))?;
+        sqlx::query(SELECT_API_CREDENTIAL_SQL)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| {
+                anyhow!("api_credential_not_found: exchange_apikey_config {credential_id}")
+            })
+    }
+}
+#[async_trait]
+impl ExecutionTaskSource for PostgresExecutionTaskSource {
+    async fn lease_tasks(&self, request: ExecutionTaskLeaseRequest) -> Result<ExecutionTaskLease> {
+        let task_statuses = if request.task_statuses.is_empty() {
+            DEFAULT_LEASE_TASK_STATUSES
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        } else {
+            request.task_statuses
+        };
+        let rows = sqlx::query(LEASE_EXECUTION_TASKS_SQL)
+            .bind(&request.worker_id)
+            .bind(task_statuses)
+            .bind(request.task_types)
+            .bind(request.task_ids)
+            .bind(i64::from(request.limit.clamp(1, 100)))
+            .bind(self.config.lease_seconds)
+            .fetch_all(&self.pool)
+            .await
+            .context("lease execution_tasks")?;
+        let tasks = rows
+            .iter()
+            .map(execution_task_from_row)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ExecutionTaskLease { tasks })
+    }
+    async fn extend_task_lease(
+        &self,
+        task_id: i64,
+        request: ExecutionTaskLeaseExtendRequest,
+    ) -> Result<ExecutionTaskLeaseExtendResponse> {
+        let extend_seconds = request
+            .extend_seconds
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(self.config.lease_seconds);
+        let row = sqlx::query(EXTEND_EXECUTION_TASK_LEASE_SQL)
+            .bind(task_id)
+            .bind(&request.worker_id)
+            .bind(extend_seconds)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| {
+                anyhow!(
+                    "execution task {task_id} lease is not held by {} or has expired",
+                    request.worker_id
+                )
+            })?;
+        let task = execution_task_from_row(&row)?;
+        let lease_until = task.lease_until.clone().unwrap_or_default();
+        Ok(ExecutionTaskLeaseExtendResponse { task, lease_until })
+    }
+    async fn lease_confirmation_tasks(
+        &self,
+        limit: u32,
+        task_ids: &[i64],
+    ) -> Result<ExecutionTaskConfirmationLease> {
+        let task_ids: Vec<i64> = task_ids.iter().copied().filter(|id| *id > 0).collect();
+        let statuses: Vec<String> = CONFIRMATION_TASK_STATUSES
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let task_rows = sqlx::query(LEASE_CONFIRMATION_TASKS_SQL)
+            .bind(&self.config.confirmation_lease_owner)
+            .bind(statuses)
+            .bind(task_ids)
+            .bind(i64::from(limit.clamp(1, 100)))
+            .bind(self.config.lease_seconds)
+            .fetch_all(&self.pool)
+            .await
+            .context("lease confirmation execution_tasks")?;
+        let tasks = task_rows
+            .iter()
+            .map(execution_task_from_row)
+            .collect::<Result<Vec<_>>>()?;
+        if tasks.is_empty() {
+            return Ok(ExecutionTaskConfirmationLease { items: Vec::new() });
+        }
+        let leased_ids: Vec<i64> = tasks.iter().map(|task| task.id).collect();
+        let mut order_results = sqlx::query(SELECT_LATEST_ORDER_RESULTS_SQL)
+            .bind(leased_ids)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(|row| {
+                exchange_order_result_from_row(row).map(|result| (result.execution_task_id, result))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+        let items = tasks
+            .into_iter()
+            .filter_map(|task| {
+                order_results
+                    .remove(&task.id)
+                    .map(|order_result| ExecutionTaskConfirmationLeaseItem { task, order_result })
+            })
+            .collect();
+        Ok(ExecutionTaskConfirmationLease { items })
+    }
+    async fn reserve_execution_risk_budget(
+        &self,
+        task_id: i64,
+        request: ExecutionRiskReservationRequest,
+    ) -> Result<ExecutionRiskReservationResponse> {
+        let row = sqlx::query(SELECT_EXECUTION_TASK_SQL)
+            .bind(task_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| anyhow!("execution task {task_id} not found"))?;
+        let task = execution_task_from_row(&row)?;
+        let reservation =
+            local_risk_reservation(&task, request.minimum_notional_usdt, &self.config)?;
+        let row = sqlx::query(UPSERT_RISK_RESERVATION_SQL)
+            .bind(reservation.task_id)
+            .bind(&reservation.buyer_email)
+            .bind(&reservation.exchange)
+            .bind(reservation.api_credential_id)
+            .bind(&reservation.allocation_mode)
+            .bind(reservation.allowed_notional_usdt)
+            .bind(reservation.required_margin_usdt)
+            .bind(reservation.stop_risk_usdt)
+            .bind(reservation.leverage)
+            .bind(&reservation.margin_mode)
+            .bind(&reservation.position_mode)
+            .fetch_one(&self.pool)
+            .await
+            .context("write execution_risk_reservations")?;
+        Ok(ExecutionRiskReservationResponse {
+            task_id: row.try_get("execution_task_id")?,
+            buyer_email: row.try_get("buyer_email")?,
+            exchange: row.try_get("exchange")?,
+            api_credential_id: row.try_get("api_credential_id")?,
+            risk_budget_batch_id: None,
+            allocation_mode: row.try_get("allocation_mode")?,
+            allowed_notional_usdt: row.try_get("allowed_notional_usdt")?,
+            required_margin_usdt: row.try_get("required_margin_usdt")?,
+            stop_risk_usdt: row.try_get("stop_risk_usdt")?,
+            leverage: row.try_get("leverage")?,
+            margin_mode: row.try_get("margin_mode")?,
+            position_mode: row.try_get("position_mode")?,
+        })
+    }
+    /// 同一事务内写入尝试记录、订单结果并推进任务状态，报告重放时按订单 ID 幂等覆盖。
+    async fn report_result(
+        &self,
+        request: ExecutionTaskReportRequest,
+    ) -> Result<ExecutionTaskReportResponse> {
+        let mut tx = self.pool.begin().await?;
+        let task_row = sqlx::query(SELECT_EXECUTION_TASK_FOR_UPDATE_SQL)
+            .bind(request.task_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow!("execution task {} not found", request.task_id))?;
+        let task = execution_task_from_row(&task_row)?;
+        let executed_at = request.executed_at.as_deref().and_then(parse_timestamp);
+        let attempt_row = sqlx::query(INSERT_EXECUTION_TASK_ATTEMPT_SQL)
+            .bind(request.task_id)
+            .bind(&request.execution_status)
+            .bind(&request.exchange)
+            .bind(&request.external_order_id)
+            .bind(&request.order_side)
+            .bind(&request.order_status)
+            .bind(request.filled_qty)
+            .bind(request.filled_quote)
+            .bind(request.fee_amount)
+            .bind(request.profit_usdt)
+            .bind(executed_at)
+            .bind(&request.error_message)
+            .bind(&request.raw_payload_json)
+            .fetch_one(&mut *tx)
+            .await
+            .context("write execution_task_attempts")?;
+        let attempt_id: i64 = attempt_row.try_get("id")?;
+        let attempt_created_at: DateTime<Utc> = attempt_row.try_get("created_at")?;
+        let order_result = if records_exchange_order_result(&request) {
+            let row = sqlx::query(UPSERT_EXCHANGE_ORDER_RESULT_SQL)
+                .bind(request.task_id)
+                .bind(task.combo_id)
+                .bind(&task.buyer_email)
+                .bind(&request.exchange)
+                .bind(&request.external_order_id)
+                .bind(&request.order_side)
+                .bind(&request.order_status)
+                .bind(request.filled_qty)
+                .bind(request.filled_quote)
+                .bind(request.fee_amount)
+                .bind(request.raw_payload_json.as_deref().unwrap_or("{}"))
+                .fetch_one(&mut *tx)
+                .await
+                .context("write exchange_order_results")?;
+            Some(serde_json::to_value(exchange_order_result_from_row(&row)?)?)
+        } else {
+            None
+        };
+        let task_row = sqlx::query(COMPLETE_EXECUTION_TASK_REPORT_SQL)
+            .bind(request.task_id)
+            .bind(&request.execution_status)
+            .bind(&request.error_message)
+            .fetch_one(&mut *tx)
+            .await?;
+        let task = execution_task_from_row(&task_row)?;
+        tx.commit().await?;
+        Ok(ExecutionTaskReportResponse {
+            task,
+            attempt: json!({
+                "id": attempt_id,
+                "execution_task_id": request.task_id,
+                "execution_status": request.execution_status,
+                "order_status": request.order_status,
+                "error_message": request.error_message,
+                "created_at": format_timestamp(attempt_created_at),
+            }),
+            order_result,
+            trade_record: None,
+        })
+    }
+    async fn report_exchange_reconciliation(
+        &self,
+        request: ExchangeReconciliationReportRequest,
+    ) -> Result<ExchangeReconciliationReportResponse> {
+        let detected_at = request.detected_at.as_deref().and_then(parse_timestamp);
+        let row = sqlx::query(INSERT_RECONCILIATION_ISSUE_SQL)
+            .bind(request.combo_id)
+            .bind(&request.buyer_email)
+            .bind(&request.symbol)
+            .bind(&request.exchange)
+            .bind(request.api_credential_id)
+            .bind(request.issue_type.as_str())
+            .bind(&request.source_ref)
+            .bind(&request.message)
+            .bind(detected_at)
+            .fetch_one(&self.pool)
+            .await
+            .context("write exchange_reconciliation_issues")?;
+        let issue_id: i64 = row.try_get("id")?;
+        let detected_at: DateTime<Utc> = row.try_get("detected_at")?;
+        Ok(ExchangeReconciliationReportResponse {
+            combo_id: request.combo_id,
+            buyer_email: request.buyer_email,
+            symbol: request.symbol,
+            signal_id: request
+                .source_ref
+                .clone()
+                .unwrap_or_else(|| format!("reconciliation-{issue_id}")),
+            issue_type: request.issue_type.as_str().to_string(),
+            api_execution_status: "recorded".to_string(),
+            log: json!({
+                "id": issue_id,
+                "exchange": request.exchange,
+                "api_credential_id": request.api_credential_id,
+                "source_ref": request.source_ref,
+                "message": request.message,
+                "detected_at": format_timestamp(detected_at),
+            }),
+        })
+    }
+    /// 信号按 source + external_id 幂等入库，首次写入时为本地账户生成一条 execute_signal 任务。
+    async fn submit_strategy_signal(
+        &self,
+        request: StrategySignalSubmitRequest,
+    ) -> Result<StrategySignalDispatchResponse> {
+        let generated_at = request.generated_at.as_deref().and_then(parse_timestamp);
+        let mut tx = self.pool.begin().await?;
+        let inserted = sqlx::query(INSERT_STRATEGY_SIGNAL_SQL)
+            .bind(&request.source)
+            .bind(&request.external_id)
+            .bind(&request.strategy_slug)
+            .bind(&request.strategy_key)
+            .bind(&request.symbol)
+            .bind(&request.signal_type)
+            .bind(&request.direction)
+            .bind(&request.title)
+            .bind(&request.summary)
+            .bind(request.confidence)
+            .bind(&request.payload_json)
+            .bind(generated_at)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("write strategy_signal_inbox")?;
+        let Some(inbox_row) = inserted else {
+            let existing = sqlx::query(SELECT_STRATEGY_SIGNAL_SQL)
+                .bind(&request.source)
+                .bind(&request.external_id)
+                .fetch_one(&mut *tx)
+                .await?;
+            let inbox = strategy_signal_inbox_from_row(&existing)?;
+            tx.commit().await?;
+            return Ok(StrategySignalDispatchResponse {
+                inbox,
+                generated_tasks: Vec::new(),
+            });
+        };
+        let inbox = strategy_signal_inbox_from_row(&inbox_row)?;
+        let task_row = sqlx::query(INSERT_SIGNAL_EXECUTION_TASK_SQL)
+            .bind(inbox.id)
+            .bind(self.config.combo_id)
+            .bind(&self.config.buyer_email)
+            .bind(&request.strategy_slug)
+            .bind(&request.symbol)
+            .bind(local_signal_task_payload(&request, &self.config))
+            .fetch_one(&mut *tx)
+            .await
+            .context("write execution_tasks for strategy signal")?;
+        let task = execution_task_from_row(&task_row)?;
+        tx.commit().await?;
+        Ok(StrategySignalDispatchResponse {
+            inbox,
+            generated_tasks: vec![task],
+        })
+    }
+    async fn resolve_user_exchange_config_for_credential(
+        &self,
+        buyer_email: &str,
+        exchange: &str,
+        credential_id: i64,
+    ) -> Result<UserExchangeConfig> {
+        let row = self.load_api_credential_row(credential_id).await?;
+        let exchange_name: String = row.try_get("exchange_name")?;
+        if !exchange_name.trim().eq_ignore_ascii_case(exchange.trim()) {
+            return Err(anyhow!(
+                "api credential {credential_id} belongs to {exchange_name}, not {exchange}"
+            ));
+        }
+        let is_enabled: i16 = row.try_get("is_enabled")?;
+        if is_enabled == 0 {
+            return Err(anyhow!("api credential {credential_id} is disabled"));
+        }
+        let is_sandbox: i16 = row.try_get("is_sandbox")?;
+        Ok(UserExchangeConfig {
+            buyer_email: buyer_email.to_string(),
+            exchange: exchange_name.trim().to_ascii_lowercase(),
+            api_key: row.try_get("api_key")?,
+            api_secret: row.try_get("api_secret")?,
+            passphrase: row
+                .try_get::<Option<String>, _>("passphrase")?
+                .filter(|value| !value.trim().is_empty()),
+            simulated: is_sandbox != 0,
+        })
+    }
+    /// 本地凭证没有 Web 的签名预检记录，启用状态即执行准备度。
+    async fn check_internal_api_credential(
+        &self,
+        credential_id: i64,
+    ) -> Result<ApiCredentialCheckSummary> {
+        let row = self.load_api_credential_row(credential_id).await?;
+        let api_key: String = row.try_get("api_key")?;
+        let is_enabled: i16 = row.try_get("is_enabled")?;
+        let created_at: NaiveDateTime = row.try_get("create_time")?;
+        let updated_at: NaiveDateTime = row.try_get("update_time")?;
+        Ok(ApiCredentialCheckSummary {
+            id: credential_id,
+            exchange: row
+                .try_get::<String, _>("exchange_name")?
+                .trim()
+                .to_ascii_lowercase(),
+            api_key_mask: mask_api_key(&api_key),
+            permission_scope: "trade".to_string(),
+            status: if is_enabled != 0 {
+                "enabled"
+            } else {
+                "disabled"
+            }
+            .to_string(),
+            credential_envelope_ready: !api_key.trim().is_empty(),
+            last_check_at: None,
+            last_check_code: None,
+            last_check_message: None,
+            created_at: format_timestamp(created_at.and_utc()),
+            updated_at: format_timestamp(updated_at.and_utc()),
+            execution_readiness: local_api_credential_readiness(is_enabled != 0, &api_key),
+        })
+    }
+}
+/// 失败报告使用占位订单 ID，不代表交易所侧存在订单，只保留在尝试记录中。
+fn records_exchange_order_result(request: &ExecutionTaskReportRequest) -> bool {
+    let external_order_id = request.external_order_id.trim();
+    !external_order_id.is_empty() && !external_order_id.starts_with(FAILED_EXTERNAL_ORDER_ID_PREFIX)
+}
+/// 按任务载荷在本地计算最终风险预算：名义仓位受配置上限约束，且不能低于交易所最小名义金额。
+fn local_risk_reservation(
+    task: &ExecutionTask,
+    minimum_notional_usdt: Option<f64>,
+    config: &PostgresExecutionTaskSourceConfig,
+) -> Result<ExecutionRiskReservationResponse> {
+    let payload = order_payload(&task.request_payload_json);
+    let requested_notional = task
+        .request_payload_json
+        .get("execution")
+        .and_then(|value| payload_f64(value, "size_usdt"))
+        .or_else(|| payload_f64(&payload, "size_usdt"))
+        .filter(|value| value.is_finite() && *value > 0.0)
+        .ok_or_else(|| {
+            anyhow!(
+                "execution task {} has no positive size_usdt for local risk reservation",
+                task.id
+            )
+        })?;
+    let allowed_notional_usdt = config
+        .max_notional_usdt
+        .map_or(requested_notional, |cap| requested_notional.min(cap));
+    if let Some(minimum) = minimum_notional_usdt.filter(|value| value.is_finite()) {
+        if allowed_notional_usdt < minimum {
+            return Err(anyhow!(
+                "local risk reservation {:.4} USDT is below exchange minimum notional {:.4} USDT",
+                allowed_notional_usdt,
+                minimum
+            ));
+        }
+    }
+    let leverage = payload_f64(&payload, "leverage")
+        .filter(|value| value.is_finite() && *value > 0.0)
+        .unwrap_or(1.0);
+    let margin_mode = payload_string(&payload, "margin_mode")
+        .unwrap_or_else(|| "isolated".to_string())
+        .to_ascii_lowercase();
+    if margin_mode != "isolated" {
+        return Err(anyhow!(
+            "local risk reservation requires isolated margin_mode, got {margin_mode}"
+        ));
+    }
+    let exchange = payload_string(&payload, "exchange")
+        .or_else(|| config.exchange.clone())
+        .ok_or_else(|| anyhow!("execution task {} has no exchange", task.id))?
+        .to_ascii_lowercase();
+    let stop_risk_usdt = match (
+        protection_entry_price(&payload),
+        selected_stop_loss_price(&payload),
+    ) {
+        (Some(entry), Some(stop)) if entry.is_finite() && stop.is_finite() && entry > 0.0 => {
+            allowed_notional_usdt * (entry - stop).abs() / entry
+        }
+        _ => 0.0,
+    };
+    Ok(ExecutionRiskReservationResponse {
+        task_id: task.id,
+        buyer_email: task.buyer_email.clone(),
+        exchange,
+        api_credential_id: payload_string(&payload, "api_credential_id")
+            .and_then(|raw| raw.parse::<i64>().ok())
+            .filter(|id| *id > 0),
+        risk_budget_batch_id: None,
+        allocation_mode: LOCAL_ALLOCATION_MODE.to_string(),
+        allowed_notional_usdt,
+        required_margin_usdt: allowed_notional_usdt / leverage,
+        stop_risk_usdt,
+        leverage,
+        margin_mode,
+        position_mode: payload_string(&payload, "position_mode")
+            .unwrap_or_else(|| "one_way".to_string()),
+    })
+}
+/// 信号载荷原样作为任务载荷，只补齐本地账户的交易所、凭证和策略Key，已有字段不覆盖。
+fn local_signal_task_payload(
+    request: &StrategySignalSubmitRequest,
+    config: &PostgresExecutionTaskSourceConfig,
+) -> Value {
+    let mut payload = match serde_json::from_str::<Value>(&request.payload_json) {
+        Ok(Value::Object(map)) => map,
+        Ok(other) => Map::from_iter([("raw_payload".to_string(), other)]),
+        Err(_) => Map::from_iter([(
+            "raw_payload".to_string(),
+            Value::String(request.payload_json.clone()),
+        )]),
+    };
+    payload
+        .entry("strategy_key")
+        .or_insert_with(|| json!(request.strategy_key));
+    payload
+        .entry("signal_external_id")
+        .or_insert_with(|| json!(request.external_id));
+    if let Some(exchange) = config.exchange.as_deref() {
+        if payload.get("exchange").unwrap_or(&Value::Null).is_null() {
+            payload.insert("exchange".to_string(), json!(exchange));
+        }
+    }
+    if let Some(credential_id) = config.api_credential_id {
+        if payload
+            .get("api_credential_id")
+            .unwrap_or(&Value::Null)
+            .is_null()
+        {
+            payload.insert("api_credential_id".to_string(), json!(credential_id));
+        }
+    }
+    Value::Object(payload)
+}
+fn local_api_credential_readiness(
+    is_enabled: bool,
+    api_key: &str,
+) -> ApiCredentialExecutionReadiness {
+    let blocker = if !is_enabled {
+        Some((
+            "api_credential_disabled",
+            "exchange_apikey_config is disabled",
+        ))
+    } else if api_key.trim().is_empty() {
+        Some((
+            "api_credential_empty",
+            "exchange_apikey_config api_key is empty",
+        ))
+    } else {
+        None
+    };
+    ApiCredentialExecutionReadiness {
+        can_execute: blocker.is_none(),
+        blocker_code: blocker.map(|(code, _)| code.to_string()),
+        blocker_message: blocker.map(|(_, message)| message.to_string()),
+        next_action_label: None,
+        next_action_href: None,
+    }
+}
+fn mask_api_key(api_key: &str) -> String {
+    let chars: Vec<char> = api_key.trim().chars().collect();
+    if chars.len() <= 8 {
+        return "****".to_string();
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{head}****{tail}")
+}
+fn format_timestamp(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+/// 兼容 RFC3339 与 Web 使用的无时区时间字符串（按 UTC 解释）。
+fn parse_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    DateTime::parse_from_rfc3339(raw)
+        .map(|value| value.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+                .map(|value| value.and_utc())
+        })
+}
+fn execution_task_from_row(row: &PgRow) -> Result<ExecutionTask> {
+    Ok(ExecutionTask {
+        id: row.try_get("id")?,
+        news_signal_id: row.try_get("news_signal_id")?,
+        strategy_signal_id: row.try_get("strategy_signal_id")?,
+        combo_id: row.try_get("combo_id")?,
+        buyer_email: row.try_get("buyer_email")?,
+        strategy_slug: row.try_get("strategy_slug")?,
+        symbol: row.try_get("symbol")?,
+        task_type: row.try_get("task_type")?,
+        task_status: row.try_get("task_status")?,
+        priority: row.try_get("priority")?,
+        lease_owner: row.try_get("lease_owner")?,
+        lease_until: row
+            .try_get::<Option<DateTime<Utc>>, _>("lease_until")?
+            .map(format_timestamp),
+        scheduled_at: format_timestamp(row.try_get("scheduled_at")?),
+        request_payload_json: row.try_get("request_payload_json")?,
+        created_at: format_timestamp(row.try_get("created_at")?),
+        updated_at: format_timestamp(row.try_get("updated_at")?),
+    })
+}
+fn exchange_order_result_from_row(row: &PgRow) -> Result<ExchangeOrderResult> {
+    Ok(ExchangeOrderResult {
+        id: row.try_get("id")?,
+        execution_task_id: row.try_get("execution_task_id")?,
+        combo_id: row.try_get("combo_id")?,
+        buyer_email: row.try_get("buyer_email")?,
+        exchange: row.try_get("exchange")?,
+        external_order_id: row.try_get("external_order_id")?,
+        order_side: row.try_get("order_side")?,
+        order_status: row.try_get("order_status")?,
+        filled_qty: row.try_get("filled_qty")?,
+        filled_quote: row.try_get("filled_quote")?,
+        fee_amount: row.try_get("fee_amount")?,
+        raw_payload_json: row.try_get("raw_payload_json")?,
+        created_at: format_timestamp(row.try_get("created_at")?),
+        updated_at: format_timestamp(row.try_get("updated_at")?),
+    })
+}
+fn strategy_signal_inbox_from_row(row: &PgRow) -> Result<StrategySignalInbox> {
+    Ok(StrategySignalInbox {
+        id: row.try_get("id")?,
+        source: row.try_get("source")?,
+        external_id: row.try_get("external_id")?,
+        strategy_slug: row.try_get("strategy_slug")?,
+        strategy_key: row.try_get("strategy_key")?,
+        symbol: row.try_get("symbol")?,
+        signal_type: row.try_get("signal_type")?,
+        direction: row.try_get("direction")?,
+        title: row.try_get("title")?,
+        summary: row.try_get("summary")?,
+        confidence: row.try_get("confidence")?,
+        payload_json: row.try_get("payload_json")?,
+        generated_at: format_timestamp(row.try_get("generated_at")?),
+        created_at: format_timestamp(row.try_get("created_at")?),
+        updated_at: format_timestamp(row.try_get("updated_at")?),
+    })
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,196 @@
+use super::*;
+use serde_json::json;
+fn task(payload: Value) -> ExecutionTask {
+    ExecutionTask {
+        id: 42,
+        news_signal_id: None,
+        strategy_signal_id: Some(7),
+        combo_id: 0,
+        buyer_email: "local@quant-core".to_string(),
+        strategy_slug: "vegas".to_string(),
+        symbol: "ETH-USDT-SWAP".to_string(),
+        task_type: "execute_signal".to_string(),
+        task_status: "pending".to_string(),
+        priority: 0,
+        lease_owner: Some("worker-a".to_string()),
+        lease_until: Some("2026-10-17T12:02:00Z".to_string()),
+        scheduled_at: "2026-10-17T12:00:00Z".to_string(),
+        request_payload_json: payload,
+        created_at: "2026-10-17T12:00:00Z".to_string(),
+        updated_at: "2026-10-17T12:00:00Z".to_string(),
+    }
+}
+fn signal_request(payload_json: &str) -> StrategySignalSubmitRequest {
+    StrategySignalSubmitRequest {
+        source: "strategy_execution_service".to_string(),
+        external_id: "vegas:ETH-USDT-SWAP:4H:1:1760000000000".to_string(),
+        strategy_slug: "vegas".to_string(),
+        strategy_key: "vegas:ETH-USDT-SWAP:4H:1".to_string(),
+        symbol: "ETH-USDT-SWAP".to_string(),
+        signal_type: "entry".to_string(),
+        direction: "long".to_string(),
+        title: "vegas entry".to_string(),
+        summary: None,
+        confidence: None,
+        payload_json: payload_json.to_string(),
+        generated_at: None,
+    }
+}
+#[test]
+fn local_risk_reservation_caps_notional_and_derives_margin_and_stop_risk() {
+    let config = PostgresExecutionTaskSourceConfig {
+        max_notional_usdt: Some(500.0),
+        ..PostgresExecutionTaskSourceConfig::default()
+    };
+    let task = task(json!({
+        "exchange": "OKX",
+        "api_credential_id": "3",
+        "leverage": "5",
+        "execution": {"size_usdt": 800.0},
+        "risk_plan": {"entry_price": 2000.0, "selected_stop_loss_price": 1900.0},
+    }));
+    let reservation = local_risk_reservation(&task, Some(20.0), &config).unwrap();
+    assert_eq!(reservation.task_id, 42);
+    assert_eq!(reservation.exchange, "okx");
+    assert_eq!(reservation.api_credential_id, Some(3));
+    assert_eq!(reservation.allocation_mode, LOCAL_ALLOCATION_MODE);
+    assert_eq!(reservation.allowed_notional_usdt, 500.0);
+    assert_eq!(reservation.required_margin_usdt, 100.0);
+    assert!((reservation.stop_risk_usdt - 25.0).abs() < 1e-9);
+    assert_eq!(reservation.margin_mode, "isolated");
+    assert_eq!(reservation.position_mode, "one_way");
+}
+#[test]
+fn local_risk_reservation_fails_closed_on_missing_size_minimum_or_cross_margin() {
+    let config = PostgresExecutionTaskSourceConfig {
+        exchange: Some("binance".to_string()),
+        ..PostgresExecutionTaskSourceConfig::default()
+    };
+    let missing_size = local_risk_reservation(&task(json!({})), None, &config).unwrap_err();
+    assert!(missing_size.to_string().contains("size_usdt"));
+    let below_minimum =
+        local_risk_reservation(&task(json!({"size_usdt": 10.0})), Some(20.0), &config).unwrap_err();
+    assert!(below_minimum.to_string().contains("minimum notional"));
+    let cross_margin = local_risk_reservation(
+        &task(json!({"size_usdt": 100.0, "margin_mode": "cross"})),
+        None,
+        &config,
+    )
+    .unwrap_err();
+    assert!(cross_margin.to_string().contains("isolated"));
+    let fallback_exchange =
+        local_risk_reservation(&task(json!({"size_usdt": 100.0})), None, &config).unwrap();
+    assert_eq!(fallback_exchange.exchange, "binance");
+    assert_eq!(fallback_exchange.leverage, 1.0);
+    assert_eq!(fallback_exchange.stop_risk_usdt, 0.0);
+}
+#[test]
+fn local_signal_task_payload_fills_account_defaults_without_overriding_signal() {
+    let config = PostgresExecutionTaskSourceConfig {
+        exchange: Some("okx".to_string()),
+        api_credential_id: Some(11),
+        ..PostgresExecutionTaskSourceConfig::default()
+    };
+    let payload = local_signal_task_payload(
+        &signal_request(r#"{"side":"buy","exchange":"binance","api_credential_id":null}"#),
+        &config,
+    );
+    assert_eq!(payload["side"], "buy");
+    assert_eq!(payload["exchange"], "binance");
+    assert_eq!(payload["api_credential_id"], 11);
+    assert_eq!(payload["strategy_key"], "vegas:ETH-USDT-SWAP:4H:1");
+    assert_eq!(
+        payload["signal_external_id"],
+        "vegas:ETH-USDT-SWAP:4H:1:1760000000000"
+    );
+    let raw = local_signal_task_payload(&signal_request("not-json"), &config);
+    assert_eq!(raw["raw_payload"], "not-json");
+    assert_eq!(raw["exchange"], "okx");
+}
+#[test]
+fn failed_reports_do_not_create_exchange_order_results() {
+    let failed = ExecutionTaskReportRequest::failed(42, "okx", "buy", "blocked", json!({}));
+    assert!(!records_exchange_order_result(&failed));
+    let submitted =
+        ExecutionTaskReportRequest::success(42, "okx", "123456", "buy", "live", json!({}));
+    assert!(records_exchange_order_result(&submitted));
+}
+#[test]
+fn parses_web_and_rfc3339_timestamps_as_utc() {
+    let expected = "2026-10-17T12:00:00Z";
+    for raw in [
+        "2026-10-17T12:00:00Z",
+        "2026-10-17T20:00:00+08:00",
+        "2026-10-17T12:00:00",
+        "2026-10-17 12:00:00.000",
+    ] {
+        assert_eq!(
+            parse_timestamp(raw).map(format_timestamp).as_deref(),
+            Some(expected),
+            "{raw}"
+        );
+    }
+    assert!(parse_timestamp("yesterday").is_none());
+}
+#[test]
+fn api_credential_readiness_blocks_disabled_or_empty_keys() {
+    assert!(local_api_credential_readiness(true, "abcd1234efgh").can_execute);
+    let disabled = local_api_credential_readiness(false, "abcd1234efgh");
+    assert!(!disabled.can_execute);
+    assert_eq!(
+        disabled.blocker_code.as_deref(),
+        Some("api_credential_disabled")
+    );
+    assert_eq!(
+        local_api_credential_readiness(true, " ")
+            .blocker_code
+            .as_deref(),
+        Some("api_credential_empty")
+    );
+    assert_eq!(mask_api_key("abcd1234efgh"), "abcd****efgh");
+    assert_eq!(mask_api_key("short"), "****");
+}
+#[test]
+fn lease_sql_skips_locked_rows_and_expired_leases_only() {
+    for sql in [LEASE_EXECUTION_TASKS_SQL, LEASE_CONFIRMATION_TASKS_SQL] {
+        for required in [
+            "FOR UPDATE SKIP LOCKED",
+            "lease_until IS NULL OR lease_until < NOW()",
+            "lease_owner = $1",
+        ] {
+            assert!(
+                sql.replace("task.lease_until", "lease_until")
+                    .contains(required),
+                "lease SQL must contain `{required}`"
+            );
+        }
+    }
+    assert!(EXTEND_EXECUTION_TASK_LEASE_SQL.contains("lease_owner = $2"));
+    assert!(COMPLETE_EXECUTION_TASK_REPORT_SQL.contains("lease_until = NULL"));
+}
+#[test]
+fn local_execution_task_queue_tables_are_part_of_quant_core_schema() {
+    let ddl = include_str!("../../../../../sql/postgres_quant_core.sql");
+    let migration = include_str!(
+        "../../../../../migrations/20261017090000_create_local_execution_task_queue.sql"
+    );
+    for table in [
+        "strategy_signal_inbox",
+        "execution_tasks",
+        "execution_task_attempts",
+        "exchange_order_results",
+        "execution_risk_reservations",
+        "exchange_reconciliation_issues",
+    ] {
+        for required in [
+            format!("CREATE TABLE IF NOT EXISTS {table}"),
+            format!("COMMENT ON TABLE {table}"),
+        ] {
+            assert!(ddl.contains(&required), "schema must include `{required}`");
+            assert!(
+                migration.contains(&required),
+                "migration must include `{required}`"
+            );
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use super::execution_task_client::ExecutionTaskClient;
+use super::execution_task_contract::{
+    ApiCredentialCheckSummary, ExchangeReconciliationReportRequest,
+    ExchangeReconciliationReportResponse, ExecutionRiskReservationRequest,
+    ExecutionRiskReservationResponse, ExecutionTaskConfirmationLease, ExecutionTaskLease,
+    ExecutionTaskLeaseExtendRequest, ExecutionTaskLeaseExtendResponse, ExecutionTaskLeaseRequest,
+    ExecutionTaskReportRequest, ExecutionTaskReportResponse, StrategySignalDispatchResponse,
+    StrategySignalSubmitRequest, UserExchangeConfig,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+const EXECUTION_TASK_SOURCE_ENV: &str = "EXECUTION_TASK_SOURCE";
+/// 执行任务的来源与回写端。
+///
+/// 默认由 `ExecutionTaskClient` 通过 HTTP 调用 rust_quan_web 的任务状态机；单团队部署可改用
+/// `PostgresExecutionTaskSource`，把任务、租约、确认租约和执行报告直接落在 quant-core Postgres。
+/// 两种实现共享同一套请求/响应结构，worker 不感知任务来自哪里。
+#[async_trait]
+pub trait ExecutionTaskSource: Send + Sync {
+    /// 租约待执行任务；同一任务在租约有效期内只会交给一个 worker。
+    async fn lease_tasks(&self, request: ExecutionTaskLeaseRequest) -> Result<ExecutionTaskLease>;
+    /// 为仍由当前 worker 持有的任务续租。
+    async fn extend_task_lease(
+        &self,
+        task_id: i64,
+        request: ExecutionTaskLeaseExtendRequest,
+    ) -> Result<ExecutionTaskLeaseExtendResponse>;
+    /// 租约等待交易所确认的任务及其最近一次订单结果。
+    async fn lease_confirmation_tasks(
+        &self,
+        limit: u32,
+        task_ids: &[i64],
+    ) -> Result<ExecutionTaskConfirmationLease>;
+    /// 下主单前原子预留最终风险预算。
+    async fn reserve_execution_risk_budget(
+        &self,
+        task_id: i64,
+        request: ExecutionRiskReservationRequest,
+    ) -> Result<ExecutionRiskReservationResponse>;
+    /// 回写执行结果并释放任务租约。
+    async fn report_result(
+        &self,
+        request: ExecutionTaskReportRequest,
+    ) -> Result<ExecutionTaskReportResponse>;
+    /// 记录交易所对账发现的问题。
+    async fn report_exchange_reconciliation(
+        &self,
+        request: ExchangeReconciliationReportRequest,
+    ) -> Result<ExchangeReconciliationReportResponse>;
+    /// 提交策略信号并生成对应的执行任务。
+    async fn submit_strategy_signal(
+        &self,
+        request: StrategySignalSubmitRequest,
+    ) -> Result<StrategySignalDispatchResponse>;
+    /// 按任务携带的凭证 ID 解析交易所 API 配置。
+    async fn resolve_user_exchange_config_for_credential(
+        &self,
+        buyer_email: &str,
+        exchange: &str,
+        credential_id: i64,
+    ) -> Result<UserExchangeConfig>;
+    /// 复核凭证是否允许实盘执行，不返回明文 secret。
+    async fn check_internal_api_credential(
+        &self,
+        credential_id: i64,
+    ) -> Result<ApiCredentialCheckSummary>;
+}
+#[async_trait]
+impl ExecutionTaskSource for ExecutionTaskClient {
+    async fn lease_tasks(&self, request: ExecutionTaskLeaseRequest) -> Result<ExecutionTaskLease> {
+        ExecutionTaskClient::lease_tasks(self, request).await
+    }
+    async fn extend_task_lease(
+        &self,
+        task_id: i64,
+        request: ExecutionTaskLeaseExtendRequest,
+    ) -> Result<ExecutionTaskLeaseExtendResponse> {
+        ExecutionTaskClient::extend_task_lease(self, task_id, request).await
+    }
+    async fn lease_confirmation_tasks(
+        &self,
+        limit: u32,
+        task_ids: &[i64],
+    ) -> Result<ExecutionTaskConfirmationLease> {
+        ExecutionTaskClient::lease_confirmation_tasks(self, limit, task_ids).await
+    }
+    async fn reserve_execution_risk_budget(
+        &self,
+        task_id: i64,
+        request: ExecutionRiskReservationRequest,
+    ) -> Result<ExecutionRiskReservationResponse> {
+        ExecutionTaskClient::reserve_execution_risk_budget(self, task_id, request).await
+    }
+    async fn report_result(
+        &self,
+        request: ExecutionTaskReportRequest,
+    ) -> Result<ExecutionTaskReportResponse> {
+        ExecutionTaskClient::report_result(self, request).await
+    }
+    async fn report_exchange_reconciliation(
+        &self,
+        request: ExchangeReconciliationReportRequest,
+    ) -> Result<ExchangeReconciliationReportResponse> {
+        ExecutionTaskClient::report_exchange_reconciliation(self, request).await
+    }
+    async fn submit_strategy_signal(
+        &self,
+        request: StrategySignalSubmitRequest,
+    ) -> Result<StrategySignalDispatchResponse> {
+        ExecutionTaskClient::submit_strategy_signal(self, request).await
+    }
+    async fn resolve_user_exchange_config_for_credential(
+        &self,
+        buyer_email: &str,
+        exchange: &str,
+        credential_id: i64,
+    ) -> Result<UserExchangeConfig> {
+        ExecutionTaskClient::resolve_user_exchange_config_for_credential(
+            self,
+            buyer_email,
+            exchange,
+            credential_id,
+        )
+        .await
+    }
+    async fn check_internal_api_credential(
+        &self,
+        credential_id: i64,
+    ) -> Result<ApiCredentialCheckSummary> {
+        ExecutionTaskClient::check_internal_api_credential(self, credential_id).await
+    }
+}
+/// 执行任务来源类型，由 `EXECUTION_TASK_SOURCE` 选择。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionTaskSourceKind {
+    /// 通过 HTTP 使用 rust_quan_web 的任务状态机（默认）。
+    Web,
+    /// 任务队列直接存放在 quant-core Postgres。
+    Postgres,
+}
+impl ExecutionTaskSourceKind {
+    /// 读取 `EXECUTION_TASK_SOURCE`；未设置时保持 Web，未知取值直接报错而不是静默回退。
+    pub fn from_env() -> Result<Self> {
+        match std::env::var(EXECUTION_TASK_SOURCE_ENV) {
+            Ok(value) => Self::parse(&value),
+            Err(_) => Ok(Self::Web),
+        }
+    }
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "web" | "rust_quan_web" | "http" => Ok(Self::Web),
+            "postgres" | "postgresql" | "pg" | "local" => Ok(Self::Postgres),
+            other => Err(anyhow!(
+                "{EXECUTION_TASK_SOURCE_ENV} must be web or postgres, got {other}"
+            )),
+        }
+    }
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Web => "web",
+            Self::Postgres => "postgres",
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parses_execution_task_source_kind() {
+        assert_eq!(
+            ExecutionTaskSourceKind::parse("").unwrap(),
+            ExecutionTaskSourceKind::Web
+        );
+        assert_eq!(
+            ExecutionTaskSourceKind::parse(" Postgres ").unwrap(),
+            ExecutionTaskSourceKind::Postgres
+        );
+        assert_eq!(
+            ExecutionTaskSourceKind::parse("rust_quan_web").unwrap(),
+            ExecutionTaskSourceKind::Web
+        );
+        let error = ExecutionTaskSourceKind::parse("mysql").unwrap_err();
+        assert!(error.to_string().contains("EXECUTION_TASK_SOURCE"));
+    }
+}
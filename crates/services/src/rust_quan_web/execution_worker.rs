@@ -1,4 +1,8 @@
-use crate::exchange::{CryptoExcAllGateway, OrderPlacementRequest};
+use crate::exchange::{CryptoExcAllGateway, ExchangeGateway, OrderPlacementRequest};
+use crate::rust_quan_web::execution_algo::{
+    parse_execution_algo, run_execution_algo, ExecutionAlgo, ExecutionAlgoFuture,
+    ExecutionAlgoQuote, ExecutionAlgoVenue,
+};
 use crate::rust_quan_web::execution_order_filters::{
     decimal_from_f64, format_order_price_decimal, format_order_size_decimal,
     format_protective_stop_price_decimal, load_exchange_order_filters, minimum_order_notional_usdt,
@@ -11,14 +15,14 @@ use crate::rust_quan_web::execution_payload::{
     is_duplicate_client_order_id_error, is_pending_close_task, is_zero_order_size, order_payload,
     order_side_lower, parse_env_list, parse_env_u32, parse_env_u64, parse_exchange,
     parse_instrument, parse_order_type, parse_position_mode, parse_side, parse_time_in_force,
-    payload_bool, payload_f64, payload_string, protection_entry_price, selected_stop_loss_price,
-    validate_execute_signal_risk_contract,
+    payload_bool, payload_f64, payload_position_size_ratio, payload_string, protection_entry_price,
+    scale_order_size, selected_stop_loss_price, validate_execute_signal_risk_contract,
 };
 use crate::rust_quan_web::execution_protection::{
     apply_post_close_protection_cancel_result, attached_stop_loss_order_ack_outcome,
     build_protective_stop_market_order_request, place_and_confirm_protective_order,
-    prearm_protective_order_if_required, ProtectionSyncContract, ProtectionSyncOutcome,
-    ProtectiveDirection, ProtectiveOrderMutator,
+    prearm_protective_order_if_required, PrearmedProtectiveOrder, ProtectionSyncContract,
+    ProtectionSyncOutcome, ProtectiveDirection, ProtectiveOrderMutator,
 };
 use crate::rust_quan_web::execution_rollback::{
     apply_protective_failure_rollback_error, apply_protective_failure_rollback_report,
@@ -47,17 +51,19 @@ use crate::rust_quan_web::{
     ExecutionRiskReservationRequest, ExecutionRiskReservationResponse, ExecutionTask,
     ExecutionTaskClient, ExecutionTaskConfig, ExecutionTaskConfirmationLeaseItem,
     ExecutionTaskLeaseExtendRequest, ExecutionTaskLeaseRequest, ExecutionTaskReportRequest,
-    ExecutionWorkerCheckpoint, NoopExecutionAuditRepository, PostgresExecutionAuditRepository,
+    ExecutionTaskSource, ExecutionTaskSourceKind, ExecutionWorkerCheckpoint,
+    NoopExecutionAuditRepository, PostgresExecutionAuditRepository, PostgresExecutionTaskSource,
     ProtectionPlacementMode, QuantWebClientError,
 };
 use anyhow::{anyhow, Result};
 use crypto_exc_all::{
-    CancelOrderRequest, Error as CryptoExchangeError, ExchangeId, Fill, FillListQuery, MarginMode,
-    MaxOrderSizeRequest, Order, OrderAck, OrderBook, OrderBookLevel, OrderBookQuery,
+    CancelOrderRequest, Error as CryptoExchangeError, ExchangeId, Fill, FillListQuery, Instrument,
+    MarginMode, MaxOrderSizeRequest, Order, OrderAck, OrderBook, OrderBookLevel, OrderBookQuery,
     OrderListQuery, OrderQuery, OrderSide, OrderType, Position, PositionMode,
     PrepareOrderSettingsRequest, PrepareOrderSettingsResult, ProtectiveOrderRequest, Ticker,
     TimeInForce,
 };
+use rust_quant_core::metrics;
 use serde_json::{json, Value};
 use std::{
     sync::{Arc, Mutex},
@@ -310,10 +316,12 @@ fn live_audit_write_error(error: anyhow::Error) -> CryptoExchangeError {
     CryptoExchangeError::Config(format!("live execution audit write failed: {error}"))
 }
 pub struct ExecutionWorker {
-    /// 外部服务客户端。
-    client: ExecutionTaskClient,
+    /// 任务来源：rust_quan_web HTTP 客户端或本地 Postgres 队列。
+    task_source: Arc<dyn ExecutionTaskSource>,
     /// gateway，用于记录交易或执行状态。
-    gateway: CryptoExcAllGateway,
+    gateway: Arc<dyn ExchangeGateway>,
+    /// 实盘任务固定使用的网关；为空时按任务凭证构建单交易所网关。
+    live_gateway: Option<Arc<dyn ExchangeGateway>>,
     /// 运行配置。
     config: ExecutionWorkerConfig,
     /// 构造时冻结的职责通道，运行期间不再读取 mode 环境变量。
@@ -326,14 +334,23 @@ pub struct ExecutionWorker {
 impl ExecutionWorker {
     /// 构建 Web 商业、会员和执行准备度 所需实例，并集中初始化依赖和默认状态。
     pub fn new(
-        client: ExecutionTaskClient,
-        gateway: CryptoExcAllGateway,
+        task_source: impl ExecutionTaskSource + 'static,
+        gateway: impl ExchangeGateway + 'static,
+        config: ExecutionWorkerConfig,
+    ) -> Self {
+        Self::with_shared_task_source(Arc::new(task_source), gateway, config)
+    }
+    /// 使用已共享的任务来源构建 worker，供按环境选择来源的入口复用。
+    pub fn with_shared_task_source(
+        task_source: Arc<dyn ExecutionTaskSource>,
+        gateway: impl ExchangeGateway + 'static,
         config: ExecutionWorkerConfig,
     ) -> Self {
         let lane = config.lane();
         Self {
-            client,
-            gateway,
+            task_source,
+            gateway: Arc::new(gateway),
+            live_gateway: None,
             config,
             lane,
             audit_repository: Arc::new(NoopExecutionAuditRepository),
@@ -370,6 +387,11 @@ impl ExecutionWorker {
         self.audit_repository = audit_repository;
         self
     }
+    /// 让实盘任务统一走指定网关，不再按任务凭证构建；供离线演练接入 `SimulatedExchange`。
+    pub fn with_live_gateway(mut self, live_gateway: Arc<dyn ExchangeGateway>) -> Self {
+        self.live_gateway = Some(live_gateway);
+        self
+    }
     fn live_order_mode_requires_audit(&self) -> bool {
         !self.config.dry_run
     }
@@ -439,11 +461,21 @@ impl ExecutionWorker {
         Self::from_env_with_lane(Some(lane))
     }
 
+    /// `EXECUTION_TASK_SOURCE=postgres` 时任务直接来自 quant-core Postgres，不再需要 rust_quan_web。
     fn from_env_with_lane(lane: Option<ExecutionWorkerLane>) -> Result<Self> {
-        let base_url = std::env::var("RUST_QUAN_WEB_BASE_URL")
-            .or_else(|_| std::env::var("QUANT_WEB_BASE_URL"))
-            .map_err(|_| anyhow!("RUST_QUAN_WEB_BASE_URL is required"))?;
-        let internal_secret = required_internal_secret_from_env()?;
+        let task_source: Arc<dyn ExecutionTaskSource> = match ExecutionTaskSourceKind::from_env()? {
+            ExecutionTaskSourceKind::Web => {
+                let base_url = std::env::var("RUST_QUAN_WEB_BASE_URL")
+                    .or_else(|_| std::env::var("QUANT_WEB_BASE_URL"))
+                    .map_err(|_| anyhow!("RUST_QUAN_WEB_BASE_URL is required"))?;
+                let internal_secret = required_internal_secret_from_env()?;
+                Arc::new(ExecutionTaskClient::new(ExecutionTaskConfig {
+                    base_url,
+                    internal_secret,
+                })?)
+            }
+            ExecutionTaskSourceKind::Postgres => Arc::new(PostgresExecutionTaskSource::from_env()?),
+        };
         let config = match lane {
             Some(lane) => {
                 validate_bool_env_value("EXECUTION_WORKER_DRY_RUN")?;
@@ -455,10 +487,6 @@ impl ExecutionWorker {
             }
         };
         config.validate_lease_limit()?;
-        let client = ExecutionTaskClient::new(ExecutionTaskConfig {
-            base_url,
-            internal_secret,
-        })?;
         let audit_repository = PostgresExecutionAuditRepository::from_env()?;
         let live_order_mode = !config.dry_run;
         if live_order_mode && audit_repository.is_none() {
@@ -471,7 +499,7 @@ impl ExecutionWorker {
         } else {
             CryptoExcAllGateway::dry_run()
         };
-        let mut worker = Self::new(client, gateway, config);
+        let mut worker = Self::with_shared_task_source(task_source, gateway, config);
         if let Some(repository) = audit_repository {
             worker = worker.with_audit_repository(Arc::new(repository));
         }
@@ -486,6 +514,7 @@ include!("execution_worker_live_execution_support_section.rs");
 include!("execution_worker_reconciliation_section.rs");
 include!("execution_worker_order_task_section.rs");
 include!("execution_worker_confirmation_section.rs");
+include!("execution_worker_execution_algo_section.rs");
 #[cfg(test)]
 #[path = "execution_worker_env_tests.rs"]
 mod execution_worker_env_tests;
@@ -499,5 +528,8 @@ mod execution_worker_reconciliation_tests;
 #[path = "execution_worker_reporting_tests.rs"]
 mod execution_worker_reporting_tests;
 #[cfg(test)]
+#[path = "execution_worker_simulated_exchange_tests.rs"]
+mod execution_worker_simulated_exchange_tests;
+#[cfg(test)]
 #[path = "execution_worker_test_support.rs"]
 mod execution_worker_test_support;
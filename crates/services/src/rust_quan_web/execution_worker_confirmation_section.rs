@@ -139,7 +139,7 @@ fn duplicate_client_order_id_reconciliation_ack(
 }
 /// 提供confirmlive订单的集中实现，避免Web 商业链路调用方重复处理相同细节。
 async fn confirm_live_order(
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     ack: &OrderAck,
 ) -> Result<(Order, Vec<Fill>)> {
     let query = if let Some(order_id) = ack.order_id.as_deref() {
@@ -165,8 +165,17 @@ async fn confirm_live_order(
         sleep(Duration::from_millis(250)).await;
     }
     let order = confirmed_order.expect("confirmation loop must set order");
+    let fills = load_live_order_fills(gateway, ack, &order).await;
+    Ok((order, fills))
+}
+/// 读取订单成交明细；查询失败只记录告警并返回空列表，由订单详情中的成交数量兜底。
+async fn load_live_order_fills(
+    gateway: &dyn ExchangeGateway,
+    ack: &OrderAck,
+    order: &Order,
+) -> Vec<Fill> {
     let order_id = order.order_id.as_deref().or(ack.order_id.as_deref());
-    let fills = if let Some(order_id) = order_id {
+    if let Some(order_id) = order_id {
         match CryptoExcAllGateway::with_signed_read_only_scope(gateway.fills(
                 ack.exchange,
                 FillListQuery::for_instrument(ack.instrument.clone())
@@ -186,8 +195,7 @@ async fn confirm_live_order(
         }
     } else {
         Vec::new()
-    };
-    Ok((order, fills))
+    }
 }
 /// 构建 Web 商业、会员和执行准备度 请求或响应载荷，把字段组装规则集中在同一入口。
 fn build_confirmed_order_report(
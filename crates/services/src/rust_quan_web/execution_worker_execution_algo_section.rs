@@ -0,0 +1,255 @@
+/// 执行算法在实盘上的场地：子订单下单/撤单逐笔写审计，等待前按等待时长续租任务。
+struct LiveExecutionAlgoVenue<'a> {
+    /// 执行 worker，提供审计下单与任务续租。
+    worker: &'a ExecutionWorker,
+    /// 父任务。
+    task: &'a ExecutionTask,
+    /// 交易所网关。
+    gateway: &'a dyn ExchangeGateway,
+    /// 交易所名称。
+    exchange: ExchangeId,
+}
+impl ExecutionAlgoVenue for LiveExecutionAlgoVenue<'_> {
+    fn place_child_order<'a>(
+        &'a self,
+        request: OrderPlacementRequest,
+    ) -> ExecutionAlgoFuture<'a, crypto_exc_all::Result<OrderAck>> {
+        Box::pin(
+            self.worker
+                .place_order_with_audit(self.task, self.gateway, request),
+        )
+    }
+    fn cancel_child_order<'a>(
+        &'a self,
+        request: CancelOrderRequest,
+    ) -> ExecutionAlgoFuture<'a, crypto_exc_all::Result<OrderAck>> {
+        Box::pin(self.worker.cancel_order_with_audit(
+            self.task,
+            self.gateway,
+            self.exchange,
+            request,
+        ))
+    }
+    fn child_order_state<'a>(
+        &'a self,
+        ack: &'a OrderAck,
+    ) -> ExecutionAlgoFuture<'a, Result<(Order, Vec<Fill>)>> {
+        Box::pin(async move {
+            let query = match (ack.order_id.as_deref(), ack.client_order_id.as_deref()) {
+                (Some(order_id), _) => OrderQuery::by_order_id(ack.instrument.clone(), order_id),
+                (None, Some(client_order_id)) => {
+                    OrderQuery::by_client_order_id(ack.instrument.clone(), client_order_id)
+                }
+                (None, None) => {
+                    return Err(anyhow!(
+                        "child order ack missing order_id and client_order_id"
+                    ))
+                }
+            };
+            let order = CryptoExcAllGateway::with_signed_read_only_scope(
+                self.gateway.order(ack.exchange, query),
+            )
+            .await?;
+            let fills = load_live_order_fills(self.gateway, ack, &order).await;
+            Ok((order, fills))
+        })
+    }
+    fn best_quote<'a>(
+        &'a self,
+        instrument: &'a Instrument,
+    ) -> ExecutionAlgoFuture<'a, Result<ExecutionAlgoQuote>> {
+        Box::pin(async move {
+            let orderbook = self
+                .gateway
+                .orderbook(
+                    self.exchange,
+                    OrderBookQuery::new(instrument.clone()).with_limit(LIVE_ORDERBOOK_DEPTH_LIMIT),
+                )
+                .await?;
+            Ok(ExecutionAlgoQuote {
+                bid: decimal_from_f64(first_live_orderbook_level_price(&orderbook.bids, "bid")?)?,
+                ask: decimal_from_f64(first_live_orderbook_level_price(&orderbook.asks, "ask")?)?,
+            })
+        })
+    }
+    fn pause<'a>(&'a self, duration: Duration) -> ExecutionAlgoFuture<'a, Result<()>> {
+        Box::pin(self.worker.pause_execution_algo(self.task, duration))
+    }
+}
+impl ExecutionWorker {
+    /// 子订单之间等待前先续租；续租失败时停止拆单，避免租约过期后任务被其他 worker 重复执行。
+    async fn pause_execution_algo(&self, task: &ExecutionTask, duration: Duration) -> Result<()> {
+        let worker_id = task
+            .lease_owner
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or(self.config.worker_id.as_str());
+        let extend_seconds = i64::try_from(duration.as_secs())
+            .unwrap_or(i64::MAX)
+            .saturating_add(120);
+        self.task_source
+            .extend_task_lease(
+                task.id,
+                ExecutionTaskLeaseExtendRequest {
+                    worker_id: worker_id.to_string(),
+                    extend_seconds: Some(extend_seconds),
+                },
+            )
+            .await
+            .map_err(|error| anyhow!("execution task lease heartbeat failed: {error}"))?;
+        sleep(duration).await;
+        Ok(())
+    }
+    /// 按执行算法拆单提交主订单，并以合成父单走与整笔下单相同的保护单、止盈同步。
+    ///
+    /// 任务结果的成交数量、金额与手续费为全部子订单汇总，`raw_payload_json.execution_algo`
+    /// 记录每笔子订单与父单成交均价；没有任何成交时按主订单失败处理并撤销预挂保护单。
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_main_order_with_algo(
+        &self,
+        task: &ExecutionTask,
+        gateway: &dyn ExchangeGateway,
+        order_task: &ExecutionOrderTask,
+        algo: &ExecutionAlgo,
+        request: &OrderPlacementRequest,
+        prearmed_protection: Option<&PrearmedProtectiveOrder>,
+        post_fill_protection: Option<ProtectionSyncContract>,
+    ) -> ExecutionTaskReportRequest {
+        let venue = LiveExecutionAlgoVenue {
+            worker: self,
+            task,
+            gateway,
+            exchange: order_task.exchange,
+        };
+        let outcome = match load_exchange_order_filters(order_task.exchange, &order_task.symbol)
+            .await
+        {
+            Ok(Some(filters)) => run_execution_algo(&venue, algo, request, &filters).await,
+            Ok(None) => Err(anyhow!(
+                "missing exchange symbol filters for {} on {}; run exchange symbol sync before execution algo",
+                order_task.symbol,
+                order_task.exchange.as_str()
+            )),
+            Err(error) => Err(error),
+        };
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                return self
+                    .execution_algo_failure_report(
+                        task,
+                        gateway,
+                        order_task,
+                        prearmed_protection,
+                        error.to_string(),
+                        json!({
+                            "task_id": task.id,
+                            "stage": "execution_algo_prepare",
+                            "execution_algo": algo.name(),
+                            "prearmed_protective_order": prearmed_protection.is_some(),
+                        }),
+                    )
+                    .await;
+            }
+        };
+        let summary = outcome.summary_json();
+        info!(
+            worker_id = %self.config.worker_id,
+            execution_task_id = task.id,
+            exchange = %order_task.exchange.as_str(),
+            symbol = %order_task.symbol.as_str(),
+            execution_algo = algo.name(),
+            child_order_count = outcome.children.len(),
+            filled_qty = %outcome.filled_qty(),
+            average_fill_price = ?outcome.average_fill_price(),
+            stop_reason = ?outcome.stop_reason.as_deref(),
+            "execution worker finished execution algo"
+        );
+        let Some(ack) = outcome.parent_ack(request).filter(|_| outcome.has_fills()) else {
+            let message = outcome
+                .stop_reason
+                .clone()
+                .unwrap_or_else(|| "execution algo finished without fills".to_string());
+            return self
+                .execution_algo_failure_report(
+                    task,
+                    gateway,
+                    order_task,
+                    prearmed_protection,
+                    message,
+                    json!({
+                        "task_id": task.id,
+                        "stage": "execution_algo",
+                        "execution_algo": summary,
+                        "prearmed_protective_order": prearmed_protection.is_some(),
+                    }),
+                )
+                .await;
+        };
+        let order = outcome.parent_order(request, &ack);
+        let report = build_confirmed_order_report_for_task(
+            task,
+            order_side_lower(order_task.side),
+            &ack,
+            Some(order.clone()),
+            outcome.parent_fills(),
+            None,
+            post_fill_protection.clone(),
+        );
+        let mut report = self
+            .sync_protection_after_main_order_report(
+                task,
+                gateway,
+                Some(order_task),
+                &ack,
+                Some(&order),
+                post_fill_protection,
+                report,
+            )
+            .await;
+        if let Some(prearmed) = prearmed_protection {
+            prearmed.apply_after_main_order_report(&mut report);
+        }
+        attach_execution_algo_summary_to_report(&mut report, summary);
+        report
+    }
+    /// 执行算法未产生成交时的失败报告；预挂保护单按主订单失败路径撤销。
+    async fn execution_algo_failure_report(
+        &self,
+        task: &ExecutionTask,
+        gateway: &dyn ExchangeGateway,
+        order_task: &ExecutionOrderTask,
+        prearmed_protection: Option<&PrearmedProtectiveOrder>,
+        message: String,
+        raw_payload: Value,
+    ) -> ExecutionTaskReportRequest {
+        let mut report = ExecutionTaskReportRequest::failed(
+            task.id,
+            order_task.exchange.as_str(),
+            order_side_lower(order_task.side),
+            message.clone(),
+            raw_payload,
+        );
+        if let Some(prearmed) = prearmed_protection {
+            let cancel_result = prearmed
+                .cancel_after_main_order_failure(task, gateway, self)
+                .await;
+            prearmed.apply_main_order_failure_cancel_result(&mut report, &message, cancel_result);
+        }
+        report
+    }
+}
+/// 把执行算法汇总写入任务结果原始载荷。
+fn attach_execution_algo_summary_to_report(
+    report: &mut ExecutionTaskReportRequest,
+    summary: Value,
+) {
+    let mut raw_payload = report
+        .raw_payload_json
+        .as_deref()
+        .and_then(|raw| serde_json::from_str::<Value>(raw).ok())
+        .unwrap_or_else(|| json!({}));
+    raw_payload["execution_algo"] = summary;
+    report.raw_payload_json = Some(raw_payload.to_string());
+}
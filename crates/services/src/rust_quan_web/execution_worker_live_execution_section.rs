@@ -79,7 +79,7 @@ impl ExecutionWorker {
             // 因此带保护合同的任务会保持 uncertain，提醒前端不要展示为实盘保护完成。
             return match order_task.to_order_request() {
                 Ok(request) => match self
-                    .place_order_with_audit(task, &self.gateway, request)
+                    .place_order_with_audit(task, self.gateway.as_ref(), request)
                     .await
                 {
                     Ok(ack) => {
@@ -162,7 +162,7 @@ impl ExecutionWorker {
         };
         // 对账发生在生成最终订单请求之前，保证 request sizing 不会建立在已失效的账户状态上。
         match self
-            .check_exchange_reconciliation_before_live_order(task, &order_task, &gateway)
+            .check_exchange_reconciliation_before_live_order(task, &order_task, gateway.as_ref())
             .await
         {
             Ok(Some(report)) => return report,
@@ -176,7 +176,7 @@ impl ExecutionWorker {
             }
         }
         let minimum_notional_usdt = match self
-            .live_order_minimum_notional_usdt(&gateway, &order_task)
+            .live_order_minimum_notional_usdt(gateway.as_ref(), &order_task)
             .await
         {
             Ok(value) => value,
@@ -255,7 +255,7 @@ impl ExecutionWorker {
             );
         }
         // live_order_request 会按交易所过滤器、最新价格和合约规则修正数量/价格，是 mutation 前的最后本地成单模型。
-        let mut request = match self.live_order_request(&gateway, &order_task).await {
+        let mut request = match self.live_order_request(gateway.as_ref(), &order_task).await {
             Ok(request) => request,
             Err(error) => {
                 return ExecutionTaskReportRequest::failed(
@@ -272,6 +272,8 @@ impl ExecutionWorker {
                 );
             }
         };
+        // 风险预留后 order_task 只保留 size_usdt；回写成单数量，预挂保护单按同一数量构建。
+        order_task.size = request.size.clone();
         info!(
             worker_id = %self.config.worker_id,
             execution_task_id = task.id,
@@ -290,7 +292,7 @@ impl ExecutionWorker {
         match self
             .pre_place_client_order_report(
                 task,
-                &gateway,
+                gateway.as_ref(),
                 Some(&order_task),
                 order_side_lower(order_task.side),
                 &request,
@@ -320,7 +322,7 @@ impl ExecutionWorker {
         }
         // 先应用策略对应的账户设置；后续交易所 max-size 查询必须基于已生效的杠杆/保证金模式。
         if let Err(error) = self
-            .prepare_order_settings_for_live_order(task, &gateway, &order_task)
+            .prepare_order_settings_for_live_order(task, gateway.as_ref(), &order_task)
             .await
         {
             let blocker = prepare_order_settings_blocker(&error);
@@ -350,7 +352,7 @@ impl ExecutionWorker {
         }
         // 杠杆/保证金设置完成后，再读取账户当前最大可下单数量；失败或低于最小交易单位时 fail closed。
         if let Err(error) = self
-            .apply_live_max_order_size_gate(task, &gateway, &mut order_task, &mut request)
+            .apply_live_max_order_size_gate(task, gateway.as_ref(), &mut order_task, &mut request)
             .await
         {
             return ExecutionTaskReportRequest::failed(
@@ -371,7 +373,7 @@ impl ExecutionWorker {
         }
         // 对要求保护单的实盘开仓，先尝试预挂保护单；保护单未确认时拒绝主订单，避免裸仓进入市场。
         let prearmed_protection = match prearm_protective_order_if_required(
-            &gateway,
+            gateway.as_ref(),
             &order_task,
             protection.as_ref(),
             task,
@@ -406,6 +408,20 @@ impl ExecutionWorker {
         } else {
             protection.clone()
         };
+        // 配置了执行算法时由子订单分批成交，父单结果同样只提交一次，不走下面的整笔下单。
+        if let Some(algo) = order_task.execution_algo.as_ref() {
+            return self
+                .execute_main_order_with_algo(
+                    task,
+                    gateway.as_ref(),
+                    &order_task,
+                    algo,
+                    &request,
+                    prearmed_protection.as_ref(),
+                    post_fill_protection,
+                )
+                .await;
+        }
         // 主订单是整条链路唯一的开仓 mutation 点；失败时只做预挂保护单清理，不重新提交主订单。
         info!(
             worker_id = %self.config.worker_id,
@@ -419,14 +435,14 @@ impl ExecutionWorker {
             "execution worker submitting live main order"
         );
         match self
-            .place_order_with_audit(task, &gateway, request.clone())
+            .place_order_with_audit(task, gateway.as_ref(), request.clone())
             .await
         {
             Ok(ack) => {
                 let mut report = self
                     .confirmed_live_order_report(
                         task,
-                        &gateway,
+                        gateway.as_ref(),
                         Some(&order_task),
                         order_side_lower(order_task.side),
                         ack,
@@ -442,7 +458,7 @@ impl ExecutionWorker {
                 let mut report = self
                     .duplicate_client_order_id_report(
                         task,
-                        &gateway,
+                        gateway.as_ref(),
                         Some(&order_task),
                         order_side_lower(order_task.side),
                         &request,
@@ -468,7 +484,7 @@ impl ExecutionWorker {
                 );
                 if let Some(prearmed) = &prearmed_protection {
                     let cancel_result = prearmed
-                        .cancel_after_main_order_failure(task, &gateway, self)
+                        .cancel_after_main_order_failure(task, gateway.as_ref(), self)
                         .await;
                     prearmed.apply_main_order_failure_cancel_result(
                         &mut report,
@@ -497,7 +513,7 @@ impl ExecutionWorker {
         if self.config.dry_run {
             return match close_task.to_order_request() {
                 Ok(Some(request)) => match self
-                    .place_order_with_audit(task, &self.gateway, request.clone())
+                    .place_order_with_audit(task, self.gateway.as_ref(), request.clone())
                     .await
                 {
                     Ok(ack) => ExecutionTaskReportRequest::success(
@@ -618,7 +634,7 @@ impl ExecutionWorker {
             .check_exchange_read_only_before_pending_close(
                 task,
                 &request,
-                &gateway,
+                gateway.as_ref(),
                 planned_protective_cancel.as_ref(),
             )
             .await
@@ -667,7 +683,7 @@ impl ExecutionWorker {
         match self
             .pre_place_client_order_report(
                 task,
-                &gateway,
+                gateway.as_ref(),
                 None,
                 order_side_lower(request.side),
                 &request,
@@ -696,14 +712,14 @@ impl ExecutionWorker {
             }
         }
         match self
-            .place_order_with_audit(task, &gateway, request.clone())
+            .place_order_with_audit(task, gateway.as_ref(), request.clone())
             .await
         {
             Ok(ack) => {
                 let mut report = self
                     .confirmed_live_order_report(
                         task,
-                        &gateway,
+                        gateway.as_ref(),
                         None,
                         order_side_lower(request.side),
                         ack,
@@ -713,7 +729,7 @@ impl ExecutionWorker {
                 if report.order_status.trim().eq_ignore_ascii_case("FILLED") {
                     if let Some((exchange, cancel_request)) = planned_protective_cancel {
                         let cancel_result = self
-                            .cancel_order_with_audit(task, &gateway, exchange, cancel_request)
+                            .cancel_order_with_audit(task, gateway.as_ref(), exchange, cancel_request)
                             .await;
                         apply_post_close_protection_cancel_result(&mut report, cancel_result);
                     }
@@ -723,7 +739,7 @@ impl ExecutionWorker {
             Err(error) if is_duplicate_client_order_id_error(&error.to_string()) => {
                 self.duplicate_client_order_id_report(
                     task,
-                    &gateway,
+                    gateway.as_ref(),
                     None,
                     order_side_lower(request.side),
                     &request,
@@ -889,7 +905,7 @@ impl ExecutionWorker {
                     };
                 if take_profit_retry_required {
                     let outcome = sync_take_profit_orders_after_main_fill(
-                        &gateway,
+                        gateway.as_ref(),
                         &order_task,
                         report.filled_qty,
                         &item.task,
@@ -904,7 +920,7 @@ impl ExecutionWorker {
                 {
                     Ok(Some(filters)) => {
                         sync_take_profit_stop_reset_after_fills(
-                            &gateway,
+                            gateway.as_ref(),
                             &order_task,
                             &filters,
                             Some(item.order_result.raw_payload_json.as_str()),
@@ -6,7 +6,7 @@ impl ExecutionWorker {
         _order_task: &ExecutionOrderTask,
         minimum_notional_usdt: Option<f64>,
     ) -> Result<ExecutionRiskReservationResponse> {
-        self.client
+        self.task_source
             .reserve_execution_risk_budget(
                 task.id,
                 ExecutionRiskReservationRequest {
@@ -18,7 +18,7 @@ impl ExecutionWorker {
     /// 下单前根据交易所过滤器派生最小名义金额，避免预留一笔最终无法成交的风险预算。
     async fn live_order_minimum_notional_usdt(
         &self,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
         order_task: &ExecutionOrderTask,
     ) -> Result<Option<f64>> {
         let enforce_min_notional = !order_task.reduce_only.unwrap_or(false)
@@ -52,7 +52,7 @@ impl ExecutionWorker {
     /// 采用 async 以便与数据库/网络 I/O 协调，减少阻塞并提升并发吞吐。
     async fn live_order_request(
         &self,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
         order_task: &ExecutionOrderTask,
     ) -> Result<OrderPlacementRequest> {
         let instrument = parse_instrument(&order_task.symbol)?;
@@ -89,7 +89,7 @@ impl ExecutionWorker {
     async fn apply_live_max_order_size_gate(
         &self,
         task: &ExecutionTask,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
         order_task: &mut ExecutionOrderTask,
         request: &mut OrderPlacementRequest,
     ) -> Result<Option<MaxOrderSizeGateOutcome>> {
@@ -196,26 +196,29 @@ impl ExecutionWorker {
         buyer_email: &str,
         exchange: ExchangeId,
         credential_id: i64,
-    ) -> Result<CryptoExcAllGateway> {
+    ) -> Result<Arc<dyn ExchangeGateway>> {
         let config = self
-            .client
+            .task_source
             .resolve_user_exchange_config_for_credential(buyer_email, exchange.as_str(), credential_id)
             .await?;
-        CryptoExcAllGateway::from_single_exchange_credentials(
+        if let Some(live_gateway) = &self.live_gateway {
+            return Ok(Arc::clone(live_gateway));
+        }
+        let gateway = CryptoExcAllGateway::from_single_exchange_credentials(
             exchange,
             config.api_key,
             config.api_secret,
             config.passphrase,
             config.simulated,
-        )
-        .map_err(Into::into)
+        )?;
+        Ok(Arc::new(gateway))
     }
     /// 选择 Web 商业、会员和执行准备度 的最佳候选结果，避免选择规则分散在调用方。
     async fn resolve_live_gateway_for_task(
         &self,
         task: &ExecutionTask,
         exchange: ExchangeId,
-    ) -> Result<CryptoExcAllGateway> {
+    ) -> Result<Arc<dyn ExchangeGateway>> {
         let credential_id = api_credential_id_from_task(task).ok_or_else(|| {
             anyhow!(
                 "api_credential_id_missing: execution task does not carry api_credential_id; place_order_allowed=false; mutation_allowed=false"
@@ -267,7 +270,7 @@ impl ExecutionWorker {
         };
         // 这里读取的是 Web 的内部凭证状态摘要，不返回明文 secret；Core 只根据 readiness 决定是否继续。
         let checked = match self
-            .client
+            .task_source
             .check_internal_api_credential(credential_id)
             .await
         {
@@ -360,7 +363,7 @@ impl ExecutionWorker {
     async fn prepare_order_settings_for_live_order(
         &self,
         task: &ExecutionTask,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
         order_task: &ExecutionOrderTask,
     ) -> Result<()> {
         if order_task.margin_mode.is_none()
@@ -387,7 +390,7 @@ impl ExecutionWorker {
     async fn confirmed_live_order_report(
         &self,
         task: &ExecutionTask,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
         order_task: Option<&ExecutionOrderTask>,
         order_side: &str,
         ack: OrderAck,
@@ -396,7 +399,7 @@ impl ExecutionWorker {
         let task_id = task.id;
         let mut confirmed_order = None;
         // place_order ack 只证明交易所接收请求；再次查询订单和成交明细，才能确定是否已成交、部分成交或等待确认。
-        let mut report = match confirm_live_order(gateway, &ack).await {
+        let report = match confirm_live_order(gateway, &ack).await {
             Ok((order, fills)) => {
                 confirmed_order = Some(order.clone());
                 build_confirmed_order_report_for_task(
@@ -429,13 +432,36 @@ impl ExecutionWorker {
                 )
             }
         };
+        self.sync_protection_after_main_order_report(
+            task,
+            gateway,
+            order_task,
+            &ack,
+            confirmed_order.as_ref(),
+            protection,
+            report,
+        )
+        .await
+    }
+    /// 主订单报告生成后同步保护单与止盈单；整笔下单和执行算法合成的父单共用这一段。
+    #[allow(clippy::too_many_arguments)]
+    async fn sync_protection_after_main_order_report(
+        &self,
+        task: &ExecutionTask,
+        gateway: &dyn ExchangeGateway,
+        order_task: Option<&ExecutionOrderTask>,
+        ack: &OrderAck,
+        confirmed_order: Option<&Order>,
+        protection: Option<ProtectionSyncContract>,
+        mut report: ExecutionTaskReportRequest,
+    ) -> ExecutionTaskReportRequest {
         // 保护单状态跟随确认后的主订单结果同步；如果保护失败，报告会保持阻塞或触发回滚，而不是直接完成任务。
         if let (Some(order_task), Some(protection)) = (
             order_task,
             ProtectionSyncContract::from_task_result(&report, protection),
         ) {
             let outcome = if let Some(outcome) =
-                attached_stop_loss_order_ack_outcome(order_task, &ack, confirmed_order.as_ref())
+                attached_stop_loss_order_ack_outcome(order_task, ack, confirmed_order)
             {
                 outcome
             } else {
@@ -501,7 +527,7 @@ impl ExecutionWorker {
     async fn rollback_after_protective_failure(
         &self,
         task: &ExecutionTask,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
         order_task: &ExecutionOrderTask,
         report: &mut ExecutionTaskReportRequest,
     ) {
@@ -558,7 +584,7 @@ impl ExecutionWorker {
     async fn duplicate_client_order_id_report(
         &self,
         task: &ExecutionTask,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
         order_task: Option<&ExecutionOrderTask>,
         order_side: &str,
         request: &OrderPlacementRequest,
@@ -590,7 +616,7 @@ impl ExecutionWorker {
     async fn pre_place_client_order_report(
         &self,
         task: &ExecutionTask,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
         order_task: Option<&ExecutionOrderTask>,
         order_side: &str,
         request: &OrderPlacementRequest,
@@ -107,7 +107,7 @@ async fn record_exchange_request_control_outcome(
 async fn place_order_with_audit(
     &self,
     task: &ExecutionTask,
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     request: OrderPlacementRequest,
 ) -> crypto_exc_all::Result<OrderAck> {
     // 实盘写操作要求审计仓库先可用；如果审计不可写，直接阻断，避免交易所 mutation 没有本地证据。
@@ -173,7 +173,7 @@ async fn place_order_with_audit(
 pub(super) async fn place_take_profit_order_with_audit(
     &self,
     task: &ExecutionTask,
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     request: OrderPlacementRequest,
 ) -> crypto_exc_all::Result<OrderAck> {
     self.place_order_with_audit(task, gateway, request).await
@@ -182,7 +182,7 @@ pub(super) async fn place_take_profit_order_with_audit(
 pub(super) async fn cancel_order_with_audit(
     &self,
     task: &ExecutionTask,
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     exchange: ExchangeId,
     request: CancelOrderRequest,
 ) -> crypto_exc_all::Result<OrderAck> {
@@ -253,7 +253,7 @@ pub(super) async fn cancel_order_with_audit(
 pub(super) async fn prepare_order_settings_with_audit(
     &self,
     task: &ExecutionTask,
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     exchange: ExchangeId,
     request: PrepareOrderSettingsRequest,
 ) -> crypto_exc_all::Result<PrepareOrderSettingsResult> {
@@ -324,7 +324,7 @@ pub(super) async fn prepare_order_settings_with_audit(
 pub(super) async fn place_protective_order_with_audit(
     &self,
     task: &ExecutionTask,
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     exchange: ExchangeId,
     request: ProtectiveOrderRequest,
 ) -> crypto_exc_all::Result<OrderAck> {
@@ -395,7 +395,7 @@ pub(super) async fn place_protective_order_with_audit(
 pub(super) async fn cancel_protective_order_with_audit(
     &self,
     task: &ExecutionTask,
-    gateway: &CryptoExcAllGateway,
+    gateway: &dyn ExchangeGateway,
     exchange: ExchangeId,
     request: CancelOrderRequest,
 ) -> crypto_exc_all::Result<OrderAck> {
@@ -470,7 +470,7 @@ impl TakeProfitOrderPlacer for ExecutionWorker {
     fn place_take_profit_order<'a>(
         &'a self,
         task: &'a ExecutionTask,
-        gateway: &'a CryptoExcAllGateway,
+        gateway: &'a dyn ExchangeGateway,
         request: OrderPlacementRequest,
     ) -> std::pin::Pin<
         Box<dyn std::future::Future<Output = crypto_exc_all::Result<OrderAck>> + Send + 'a>,
@@ -482,7 +482,7 @@ impl ProtectiveOrderMutator for ExecutionWorker {
     fn audit_place_protective<'a>(
         &'a self,
         task: &'a ExecutionTask,
-        gateway: &'a CryptoExcAllGateway,
+        gateway: &'a dyn ExchangeGateway,
         exchange: ExchangeId,
         request: ProtectiveOrderRequest,
     ) -> std::pin::Pin<
@@ -493,7 +493,7 @@ impl ProtectiveOrderMutator for ExecutionWorker {
     fn audit_cancel_protective<'a>(
         &'a self,
         task: &'a ExecutionTask,
-        gateway: &'a CryptoExcAllGateway,
+        gateway: &'a dyn ExchangeGateway,
         exchange: ExchangeId,
         request: CancelOrderRequest,
     ) -> std::pin::Pin<
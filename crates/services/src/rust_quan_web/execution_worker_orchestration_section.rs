@@ -13,7 +13,7 @@ impl ExecutionWorker {
         // 租约由 Web owner service 发放，Core 只处理拿到租约的任务；这样同一任务不会被多个 worker
         // 并发执行，也能把 task_types/statuses 的筛选规则集中在 Web 的状态机里。
         let leased = match self
-            .client
+            .task_source
             .lease_tasks(ExecutionTaskLeaseRequest {
                 worker_id: self.config.worker_id.clone(),
                 limit: self.config.lease_limit,
@@ -23,7 +23,10 @@ impl ExecutionWorker {
             })
             .await
         {
-            Ok(leased) => leased,
+            Ok(leased) => {
+                metrics::record_execution_task_leases(self.lane.as_str(), leased.tasks.len());
+                leased
+            }
             Err(error) => {
                 self.record_checkpoint(
                     "failed",
@@ -100,7 +103,7 @@ impl ExecutionWorker {
                 "execution worker starts leased task"
             );
             if let Err(error) = self
-                .client
+                .task_source
                 .extend_task_lease(
                     task.id,
                     ExecutionTaskLeaseExtendRequest {
@@ -122,7 +125,7 @@ impl ExecutionWorker {
                         "place_order_allowed": false,
                     }),
                 );
-                if let Err(report_error) = self.client.report_result(report.clone()).await {
+                if let Err(report_error) = self.task_source.report_result(report.clone()).await {
                     error!(
                         task_id = task.id,
                         "回写执行任务租约续租失败结果失败: {}", report_error
@@ -161,9 +164,10 @@ impl ExecutionWorker {
             )
             .await;
             let report = self.execute_task(&task).await;
+            record_execution_report_metrics(&report, !self.config.dry_run);
             let report_status = report.execution_status.clone();
             // 交易执行结果必须回写 Web；回写失败不重试下单，只记录可重放证据，避免重复 mutation。
-            if let Err(error) = self.client.report_result(report.clone()).await {
+            if let Err(error) = self.task_source.report_result(report.clone()).await {
                 error!(task_id = task.id, "回写执行任务结果失败: {}", error);
                 self.record_report_result_failure(
                     task.id,
@@ -216,9 +220,10 @@ impl ExecutionWorker {
         let request =
             build_exchange_reconciliation_report_request(task, issue_type, detected_at, message);
         let response = self
-            .client
+            .task_source
             .report_exchange_reconciliation(request.clone())
             .await?;
+        metrics::record_reconciliation_mismatch(request.issue_type.as_str());
         self.record_checkpoint(
             "exchange_reconciliation_reported",
             Some(task.id),
@@ -240,7 +245,7 @@ impl ExecutionWorker {
         &self,
         task: &ExecutionTask,
         order_task: &ExecutionOrderTask,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
     ) -> Result<Option<ExecutionTaskReportRequest>> {
         let instrument = parse_instrument(&order_task.symbol)?;
         // 真实下单前先做 signed read-only 对账，用交易所当前仓位阻断重复开仓或脏状态；
@@ -277,9 +282,10 @@ impl ExecutionWorker {
             return Ok(None);
         }
         for request in &requests {
-            self.client
+            self.task_source
                 .report_exchange_reconciliation(request.clone())
                 .await?;
+            metrics::record_reconciliation_mismatch(request.issue_type.as_str());
             self.record_checkpoint(
                 "exchange_reconciliation_read_only_blocker_reported",
                 Some(task.id),
@@ -305,7 +311,7 @@ impl ExecutionWorker {
         &self,
         task: &ExecutionTask,
         request: &OrderPlacementRequest,
-        gateway: &CryptoExcAllGateway,
+        gateway: &dyn ExchangeGateway,
         planned_protective_cancel: Option<&(ExchangeId, CancelOrderRequest)>,
     ) -> Result<()> {
         let instrument = request.instrument.clone();
@@ -427,11 +433,14 @@ impl ExecutionWorker {
     /// 执行 Web 商业、会员和执行准备度 主流程，并把外部依赖调用、状态推进和错误返回串起来。
     async fn run_confirmation_once(&self) -> Result<usize> {
         let leased = match self
-            .client
+            .task_source
             .lease_confirmation_tasks(self.config.lease_limit, &[])
             .await
         {
-            Ok(leased) => leased,
+            Ok(leased) => {
+                metrics::record_execution_task_leases(self.lane.as_str(), leased.items.len());
+                leased
+            }
             Err(error) => {
                 self.record_checkpoint(
                     "failed",
@@ -472,8 +481,10 @@ impl ExecutionWorker {
         let mut last_task_id = None;
         for item in leased.items {
             let report = self.execute_pending_confirmation_item(&item).await;
+            // 确认通道回写的是原主单，不重复计入下单次数，只统计失败。
+            record_execution_report_metrics(&report, false);
             let report_status = report.execution_status.clone();
-            if let Err(error) = self.client.report_result(report.clone()).await {
+            if let Err(error) = self.task_source.report_result(report.clone()).await {
                 error!(task_id = item.task.id, "回写执行确认结果失败: {}", error);
                 self.record_report_result_failure(
                     item.task.id,
@@ -583,7 +594,7 @@ impl ExecutionWorker {
             }
             let task_id = candidate.report.task_id;
             let started_at = Instant::now();
-            match self.client.report_result(candidate.report.clone()).await {
+            match self.task_source.report_result(candidate.report.clone()).await {
                 Ok(response) => {
                     replayed += 1;
                     replayed_request_ids.push(candidate.request_id.clone());
@@ -856,3 +867,11 @@ fn normalized_position_side(side: Option<&str>, size: f64) -> Option<String> {
         _ => None,
     }
 }
+/// 按执行报告记录下单与失败指标；失败状态以 `failed` 结尾，失败占位订单号不计入下单。
+fn record_execution_report_metrics(report: &ExecutionTaskReportRequest, count_placement: bool) {
+    if report.execution_status.ends_with("failed") {
+        metrics::record_execution_task_failure(&report.exchange, &report.execution_status);
+    } else if count_placement && !report.external_order_id.starts_with("failed-task-") {
+        metrics::record_execution_order_placement(&report.exchange);
+    }
+}
@@ -351,11 +351,27 @@ impl ExecutionOrderTask {
             .transpose()?
             .unwrap_or(OrderSide::Buy);
         let take_profit_legs = parse_take_profit_legs(payload, direction_from_order_side(side))?;
+        let execution_algo = parse_execution_algo(payload)?;
+        let reduce_only = payload_bool(payload, "reduce_only");
+        let trade_side = payload_string(payload, "trade_side");
+        // 拆单只用于开仓；平仓/只减仓任务需要一次性处理，避免子订单之间仓位已被其他路径改变
+        if execution_algo.is_some()
+            && (reduce_only == Some(true)
+                || trade_side
+                    .as_deref()
+                    .is_some_and(|value| value.eq_ignore_ascii_case("close")))
+        {
+            return Err(anyhow!(
+                "execution_algo is only supported for opening orders, not reduce_only or close tasks"
+            ));
+        }
         let order_type = payload_string(payload, "order_type")
             .map(|value| parse_order_type(&value))
             .transpose()?
             .unwrap_or(OrderType::Market);
-        let size_usdt = payload_f64(payload, "size_usdt");
+        // 缩仓比例在这里一次性作用于下单规模，本地队列与 Web owner 两条路径共用
+        let position_size_ratio = payload_position_size_ratio(payload);
+        let size_usdt = payload_f64(payload, "size_usdt").map(|value| value * position_size_ratio);
         let execution_size_usdt = task
             .request_payload_json
             .get("execution")
@@ -368,6 +384,12 @@ impl ExecutionOrderTask {
                 && entry_price > 0.0)
                 .then(|| format_order_size(size_usdt / entry_price))
         });
+        let size = payload_string(payload, "size")
+            .or_else(|| payload_string(payload, "quantity"))
+            .or_else(|| payload_string(payload, "qty"))
+            .or(derived_size)
+            .map(|size| scale_order_size(size, position_size_ratio))
+            .unwrap_or_else(|| "0".to_string());
         Ok(Self {
             task_id: task.id,
             exchange,
@@ -375,11 +397,7 @@ impl ExecutionOrderTask {
             side,
             order_type,
             size_usdt,
-            size: payload_string(payload, "size")
-                .or_else(|| payload_string(payload, "quantity"))
-                .or_else(|| payload_string(payload, "qty"))
-                .or(derived_size)
-                .unwrap_or_else(|| "0".to_string()),
+            size,
             price: payload_string(payload, "price"),
             margin_mode: payload_string(payload, "margin_mode").map(MarginMode::from),
             leverage: payload_string(payload, "leverage"),
@@ -389,18 +407,20 @@ impl ExecutionOrderTask {
             margin_coin: payload_string(payload, "margin_coin")
                 .or_else(|| Some("USDT".to_string())),
             position_side: payload_string(payload, "position_side"),
-            trade_side: payload_string(payload, "trade_side"),
+            trade_side,
             client_order_id: payload_string(payload, "client_order_id")
                 .or_else(|| Some(format!("rqtask{}", task.id))),
-            reduce_only: payload_bool(payload, "reduce_only"),
+            reduce_only,
             time_in_force: payload_string(payload, "time_in_force")
                 .map(|value| parse_time_in_force(&value))
                 .transpose()?,
+            position_size_ratio,
             risk_reserved: false,
             attached_stop_loss_price: selected_stop_loss_price(payload)
                 .filter(|price| price.is_finite() && *price > 0.0)
                 .map(format_order_price),
             take_profit_legs,
+            execution_algo,
         })
     }
     /// 应用 Web owner service 在下单前原子分配的最终实盘仓位预算。
@@ -438,7 +458,7 @@ impl ExecutionOrderTask {
         {
             return Err(anyhow!("risk reservation margin_mode must be isolated"));
         }
-        self.size_usdt = Some(reservation.allowed_notional_usdt);
+        self.size_usdt = Some(reservation.allowed_notional_usdt * self.position_size_ratio);
         self.size = "0".to_string();
         self.leverage = Some(format_order_size(reservation.leverage));
         self.margin_mode = Some(MarginMode::from(reservation.margin_mode.clone()));
@@ -367,10 +367,14 @@ pub struct ExecutionOrderTask {
     pub time_in_force: Option<TimeInForce>,
     /// size USDT 金额；为空时使用默认值或表示不限制。
     pub size_usdt: Option<f64>,
+    /// 下单前风控缩仓比例；下单规模与风险预算都按该比例下调。
+    pub position_size_ratio: f64,
     /// 是否已应用 Web owner service 的最终风险预算预留。
     pub risk_reserved: bool,
     /// 止损价格。
     pub attached_stop_loss_price: Option<String>,
     /// 列表数据。
     pub take_profit_legs: Vec<TakeProfitLeg>,
+    /// 执行算法；为空时整笔下单。
+    pub execution_algo: Option<ExecutionAlgo>,
 }
@@ -144,6 +144,52 @@ fn risk_reservation_overrides_payload_derived_live_order_size() {
     assert_eq!(order.size, "2.5");
 }
 #[test]
+fn pre_trade_shrink_ratio_scales_order_size_and_risk_reservation() {
+    let task = task(json!({
+        "source": "rust_quan_web",
+        "symbol": "TEST-USDT-SWAP",
+        "signal_type": "buy",
+        "risk_decision": { "action": "shrink", "size_ratio": 0.5, "reasons": ["drawdown"] },
+        "position_size_ratio": 0.5,
+        "execution": {
+            "exchange": "binance",
+            "symbol": "TEST-USDT-SWAP",
+            "side": "buy",
+            "order_type": "market",
+            "size_usdt": 500.0,
+            "leverage": 3.0
+        },
+        "risk_plan": {
+            "entry_price": 100.0,
+            "selected_stop_loss_price": 90.0,
+            "direction": "long"
+        }
+    }));
+    let mut request = ExecutionOrderTask::from_task(&task).unwrap();
+    assert_eq!(request.size, "2.5");
+    assert_eq!(request.size_usdt, Some(250.0));
+    // 预算预留按原始 size_usdt 计算，缩仓在 worker 应用预留时只作用一次
+    request
+        .apply_risk_reservation(&ExecutionRiskReservationResponse {
+            task_id: task.id,
+            buyer_email: task.buyer_email.clone(),
+            exchange: "binance".to_string(),
+            api_credential_id: None,
+            risk_budget_batch_id: None,
+            allocation_mode: "local_fixed_notional".to_string(),
+            allowed_notional_usdt: 500.0,
+            required_margin_usdt: 500.0 / 3.0,
+            stop_risk_usdt: 50.0,
+            leverage: 3.0,
+            margin_mode: "isolated".to_string(),
+            position_mode: "one_way".to_string(),
+        })
+        .unwrap();
+    assert_eq!(request.size_usdt, Some(250.0));
+    let order = request.to_order_request_with_last_price(Some(100.0)).unwrap();
+    assert_eq!(order.size, "2.5");
+}
+#[test]
 fn risk_reservation_exchange_mismatch_fails_closed() {
     let task = task(json!({
         "exchange": "binance",
@@ -1110,6 +1156,33 @@ fn take_profit_existing_order_record_keeps_requested_client_order_id_when_exchan
     assert_eq!(record["price"], "106");
 }
 #[test]
+fn execution_algo_parses_for_open_orders_and_rejects_reduce_only_tasks() {
+    let task_with = |reduce_only: bool| {
+        task(json!({
+            "exchange": "binance",
+            "symbol": "ETHUSDT",
+            "side": "buy",
+            "order_type": "market",
+            "size": "1",
+            "reduce_only": reduce_only,
+            "execution": {
+                "algo": {"type": "twap", "slices": 4, "duration_minutes": 20}
+            }
+        }))
+    };
+    let order_task = ExecutionOrderTask::from_task(&task_with(false)).unwrap();
+    assert_eq!(
+        order_task.execution_algo,
+        Some(ExecutionAlgo::Twap {
+            slices: 4,
+            duration: Duration::from_secs(1_200),
+        })
+    );
+    let error = ExecutionOrderTask::from_task(&task_with(true))
+        .expect_err("reduce-only tasks must not be split into child orders");
+    assert!(error.to_string().contains("execution_algo"));
+}
+#[test]
 fn take_profit_legs_reject_duplicate_leg_index_before_order_requests() {
     let task = task(json!({
         "exchange": "binance",
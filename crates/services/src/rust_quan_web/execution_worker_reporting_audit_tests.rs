@@ -179,7 +179,7 @@ async fn dry_run_worker_records_audit_and_checkpoint_through_repository() {
         )
         .await;
     let ack = worker
-        .place_order_with_audit(&task, &worker.gateway, request)
+        .place_order_with_audit(&task, worker.gateway.as_ref(), request)
         .await
         .unwrap();
     assert_eq!(ack.status.as_deref(), Some("dry_run"));
@@ -759,7 +759,7 @@ async fn default_noop_audit_repository_does_not_block_worker_audit_paths() {
         )
         .await;
     let ack = worker
-        .place_order_with_audit(&task, &worker.gateway, request)
+        .place_order_with_audit(&task, worker.gateway.as_ref(), request)
         .await
         .unwrap();
     assert_eq!(ack.exchange.as_str(), "okx");
@@ -802,7 +802,7 @@ async fn live_order_audit_write_failure_blocks_order_result() {
         .to_order_request()
         .unwrap();
     let error = worker
-        .place_order_with_audit(&task, &worker.gateway, request)
+        .place_order_with_audit(&task, worker.gateway.as_ref(), request)
         .await
         .expect_err("live order must fail closed when audit write is unavailable");
     assert!(error
@@ -889,3 +889,43 @@ fn confirmed_live_order_report_keeps_unfilled_order_pending_confirmation() {
 }
 include!("execution_worker_reporting_client_order_tests.rs");
 include!("execution_worker_reporting_audit_tests.rs");
+#[test]
+fn execution_report_metrics_count_placements_and_failures_separately() {
+    let labels = [("exchange", "metrics-test-exchange")];
+    let placement = ExecutionTaskReportRequest::success(
+        7,
+        "metrics-test-exchange",
+        "123456",
+        "buy",
+        "live",
+        json!({}),
+    );
+    record_execution_report_metrics(&placement, true);
+    record_execution_report_metrics(&placement, false);
+    let failed = ExecutionTaskReportRequest::failed(
+        7,
+        "metrics-test-exchange",
+        "buy",
+        "blocked",
+        json!({}),
+    );
+    record_execution_report_metrics(&failed, true);
+    let registry = rust_quant_core::metrics::global();
+    assert_eq!(
+        registry.scalar_value(
+            rust_quant_core::metrics::EXECUTION_ORDER_PLACEMENTS_TOTAL,
+            &labels
+        ),
+        Some(1.0)
+    );
+    assert_eq!(
+        registry.scalar_value(
+            rust_quant_core::metrics::EXECUTION_TASK_FAILURES_TOTAL,
+            &[
+                ("exchange", "metrics-test-exchange"),
+                ("execution_status", "failed"),
+            ],
+        ),
+        Some(1.0)
+    );
+}
@@ -0,0 +1,272 @@
+use super::execution_worker_test_support::*;
+use super::*;
+use crate::exchange::SimulatedExchange;
+use crate::rust_quan_web::execution_order_filters::TEST_EXCHANGE_ORDER_FILTERS;
+use crate::rust_quan_web::{
+    ApiCredentialCheckSummary, ExchangeReconciliationReportRequest, ExecutionTaskConfirmationLease,
+    ExecutionTaskLease, ExecutionTaskLeaseExtendResponse, ExecutionTaskReportResponse,
+    StrategySignalDispatchResponse, StrategySignalSubmitRequest, UserExchangeConfig,
+};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+/// 内存任务来源：租出预置任务，记录回写的执行报告与对账问题。
+#[derive(Default)]
+struct SimulatedTaskSource {
+    pending: Mutex<Vec<ExecutionTask>>,
+    reports: Mutex<Vec<ExecutionTaskReportRequest>>,
+    reconciliations: Mutex<Vec<ExchangeReconciliationReportRequest>>,
+}
+impl SimulatedTaskSource {
+    fn with_task(task: ExecutionTask) -> Self {
+        Self {
+            pending: Mutex::new(vec![task]),
+            ..Self::default()
+        }
+    }
+}
+#[async_trait]
+impl ExecutionTaskSource for SimulatedTaskSource {
+    async fn lease_tasks(&self, _request: ExecutionTaskLeaseRequest) -> Result<ExecutionTaskLease> {
+        Ok(ExecutionTaskLease {
+            tasks: std::mem::take(&mut *self.pending.lock().unwrap()),
+        })
+    }
+    async fn extend_task_lease(
+        &self,
+        task_id: i64,
+        _request: ExecutionTaskLeaseExtendRequest,
+    ) -> Result<ExecutionTaskLeaseExtendResponse> {
+        let mut task = task_with_metadata("execute_signal", "running", json!({}));
+        task.id = task_id;
+        Ok(ExecutionTaskLeaseExtendResponse {
+            task,
+            lease_until: "2026-04-23T12:02:00".to_string(),
+        })
+    }
+    async fn lease_confirmation_tasks(
+        &self,
+        _limit: u32,
+        _task_ids: &[i64],
+    ) -> Result<ExecutionTaskConfirmationLease> {
+        Ok(ExecutionTaskConfirmationLease { items: Vec::new() })
+    }
+    async fn reserve_execution_risk_budget(
+        &self,
+        task_id: i64,
+        _request: ExecutionRiskReservationRequest,
+    ) -> Result<ExecutionRiskReservationResponse> {
+        Ok(ExecutionRiskReservationResponse {
+            task_id,
+            buyer_email: "buyer@example.com".to_string(),
+            exchange: "binance".to_string(),
+            api_credential_id: Some(7788),
+            risk_budget_batch_id: None,
+            allocation_mode: "fixed".to_string(),
+            allowed_notional_usdt: 350.0,
+            required_margin_usdt: 70.0,
+            stop_risk_usdt: 10.0,
+            leverage: 5.0,
+            margin_mode: "isolated".to_string(),
+            position_mode: "one_way".to_string(),
+        })
+    }
+    async fn report_result(
+        &self,
+        request: ExecutionTaskReportRequest,
+    ) -> Result<ExecutionTaskReportResponse> {
+        let mut task = task_with_metadata("execute_signal", "completed", json!({}));
+        task.id = request.task_id;
+        self.reports.lock().unwrap().push(request);
+        Ok(ExecutionTaskReportResponse {
+            task,
+            attempt: json!({}),
+            order_result: None,
+            trade_record: None,
+        })
+    }
+    async fn report_exchange_reconciliation(
+        &self,
+        request: ExchangeReconciliationReportRequest,
+    ) -> Result<ExchangeReconciliationReportResponse> {
+        self.reconciliations.lock().unwrap().push(request.clone());
+        Ok(ExchangeReconciliationReportResponse {
+            combo_id: request.combo_id,
+            buyer_email: request.buyer_email,
+            symbol: request.symbol,
+            signal_id: String::new(),
+            issue_type: request.issue_type.as_str().to_string(),
+            api_execution_status: "blocked".to_string(),
+            log: json!({}),
+        })
+    }
+    async fn submit_strategy_signal(
+        &self,
+        _request: StrategySignalSubmitRequest,
+    ) -> Result<StrategySignalDispatchResponse> {
+        Err(anyhow!(
+            "simulated task source does not accept strategy signals"
+        ))
+    }
+    async fn resolve_user_exchange_config_for_credential(
+        &self,
+        buyer_email: &str,
+        exchange: &str,
+        _credential_id: i64,
+    ) -> Result<UserExchangeConfig> {
+        Ok(UserExchangeConfig {
+            buyer_email: buyer_email.to_string(),
+            exchange: exchange.to_string(),
+            api_key: "sim-key".to_string(),
+            api_secret: "sim-secret".to_string(),
+            passphrase: None,
+            simulated: true,
+        })
+    }
+    async fn check_internal_api_credential(
+        &self,
+        credential_id: i64,
+    ) -> Result<ApiCredentialCheckSummary> {
+        Ok(serde_json::from_value(json!({
+            "id": credential_id,
+            "exchange": "binance",
+            "api_key_mask": "sim_***_key",
+            "permission_scope": "trade",
+            "status": "active",
+            "credential_envelope_ready": true,
+            "created_at": "2026-04-23T12:00:00",
+            "updated_at": "2026-04-23T12:00:00",
+            "execution_readiness": {"can_execute": true}
+        }))?)
+    }
+}
+#[tokio::test]
+async fn live_task_runs_end_to_end_against_simulated_exchange() {
+    let instrument = Instrument::perp("ETH", "USDT");
+    let exchange = Arc::new(SimulatedExchange::new(ExchangeId::Binance, 1_000.0));
+    exchange.set_clock_ms(current_time_millis_u64());
+    exchange.set_price(&instrument, 3_500.0);
+    let mut task = task(json!({
+        "source": "rust_quan_web",
+        "api_credential_id": 7788,
+        "exchange": "binance",
+        "symbol": "ETHUSDT",
+        "side": "buy",
+        "order_type": "market",
+        "size_usdt": 35.0,
+        "position_side": "long",
+        "client_order_id": "rq-open-42",
+        "risk_plan": {
+            "protective_stop_loss_required": true,
+            "entry_price": 3_500.0,
+            "selected_stop_loss_price": 3_400.0,
+            "direction": "long",
+            "take_profit_legs": [
+                {"leg_index": 1, "target_r": 2.0, "fraction": 1.0, "role": "base_take_profit"}
+            ]
+        }
+    }));
+    task.symbol = "ETHUSDT".to_string();
+    let source = Arc::new(SimulatedTaskSource::with_task(task.clone()));
+    let repository = Arc::new(CapturingAuditRepository::default());
+    let worker = ExecutionWorker::with_shared_task_source(
+        source.clone(),
+        CryptoExcAllGateway::dry_run(),
+        ExecutionWorkerConfig {
+            worker_id: "worker-simulated-exchange".to_string(),
+            lease_limit: 1,
+            dry_run: false,
+            default_exchange: ExchangeId::Binance,
+            task_types: vec!["execute_signal".to_string()],
+            task_statuses: vec!["pending".to_string()],
+            target_task_ids: Vec::new(),
+            confirmation_mode: false,
+            report_replay_mode: false,
+            report_replay_max_per_run: 1,
+            report_replay_failure_backoff_seconds: 300,
+            report_replay_throttle_ms: 0,
+        },
+    )
+    .with_audit_repository(repository.clone())
+    .with_live_gateway(exchange.clone());
+
+    // 租约 → 风险预留 → 下单 → 挂保护止损 → 挂止盈，一次 run_once 走完并回写报告
+    let handled = TEST_EXCHANGE_ORDER_FILTERS
+        .scope(binance_eth_filters(), worker.run_once())
+        .await
+        .unwrap();
+    assert_eq!(handled, 1);
+    let report = source.reports.lock().unwrap().pop().expect("task report");
+    assert_eq!(report.task_id, 42);
+    assert_eq!(report.execution_status, "completed", "{report:?}");
+    assert_eq!(report.exchange, "binance");
+    assert_eq!(report.order_side, "buy");
+    let raw_payload =
+        serde_json::from_str::<Value>(report.raw_payload_json.as_deref().expect("raw payload"))
+            .expect("raw payload json");
+    assert_eq!(
+        raw_payload["protection_sync"]["protective_order_confirmed"],
+        true
+    );
+    assert_eq!(
+        raw_payload["protection_sync"]["prearmed_protective_order"],
+        true
+    );
+    assert_eq!(
+        raw_payload["take_profit_sync"]["take_profit_order_confirmed"],
+        true
+    );
+    assert_eq!(
+        raw_payload["take_profit_sync"]["orders"][0]["price"],
+        "3700"
+    );
+    assert!(!repository.audits.lock().unwrap().is_empty());
+
+    let positions = exchange
+        .positions(ExchangeId::Binance, Some(&instrument))
+        .await
+        .unwrap();
+    assert_eq!(positions.len(), 1);
+    let entry_size = positions[0].size.clone();
+    let open_orders = exchange
+        .open_orders(
+            ExchangeId::Binance,
+            OrderListQuery::for_instrument(instrument.clone()),
+        )
+        .await
+        .unwrap();
+    let mut open_order_types = open_orders
+        .iter()
+        .filter_map(|order| order.order_type.clone())
+        .collect::<Vec<_>>();
+    open_order_types.sort();
+    assert_eq!(open_order_types, vec!["LIMIT", "STOP_MARKET"]);
+
+    // 价格推到 2R 止盈位，止盈单成交；保护止损为 closePosition，随持仓归零失效
+    let fills = exchange.replay_prices(&instrument, [3_600.0, 3_700.0]);
+    assert_eq!(fills.len(), 1);
+    assert_eq!(fills[0].side.as_deref(), Some("SELL"));
+    assert_eq!(fills[0].size.as_deref(), Some(entry_size.as_str()));
+    assert!(exchange
+        .positions(ExchangeId::Binance, Some(&instrument))
+        .await
+        .unwrap()
+        .is_empty());
+
+    // 对账：平仓后交易所侧无持仓无挂单，read-only 对账不应阻断下一次开仓
+    let remaining_orders = exchange
+        .open_orders(
+            ExchangeId::Binance,
+            OrderListQuery::for_instrument(instrument.clone()),
+        )
+        .await
+        .unwrap();
+    assert!(remaining_orders.is_empty(), "{remaining_orders:?}");
+    let order_task = ExecutionOrderTask::from_task(&task).unwrap();
+    let blocker = worker
+        .check_exchange_reconciliation_before_live_order(&task, &order_task, exchange.as_ref())
+        .await
+        .unwrap();
+    assert!(blocker.is_none(), "{blocker:?}");
+    assert!(source.reconciliations.lock().unwrap().is_empty());
+}
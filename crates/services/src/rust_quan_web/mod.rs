@@ -1,3 +1,4 @@
+mod execution_algo;
 mod execution_audit;
 mod execution_capability;
 mod execution_order_filters;
@@ -9,6 +10,8 @@ mod execution_rollback;
 mod execution_take_profit;
 mod execution_task_client;
 mod execution_task_contract;
+mod execution_task_queue;
+mod execution_task_source;
 mod execution_worker;
 mod market_velocity_live_readiness;
 pub use execution_audit::{
@@ -36,7 +39,8 @@ pub use execution_task_contract::{
     ExchangeCloseFillWritebackRequest, ExchangeCloseFillWritebackResponse, ExchangeOrderResult,
     ExchangeReconciliationIssueType, ExchangeReconciliationReportRequest,
     ExchangeReconciliationReportResponse, ExecutionRiskReservationRequest,
-    ExecutionRiskReservationResponse, ExecutionTask, ExecutionTaskConfirmationLease,
+    ExecutionRiskReservationResponse, ExecutionTask, ExecutionTaskCancelPendingRequest,
+    ExecutionTaskCancelPendingResponse, ExecutionTaskConfirmationLease,
     ExecutionTaskConfirmationLeaseItem, ExecutionTaskLease, ExecutionTaskLeaseExtendRequest,
     ExecutionTaskLeaseExtendResponse, ExecutionTaskLeaseRequest, ExecutionTaskReportRequest,
     ExecutionTaskReportResponse, MarketVelocityExecutionTaskCreationPreviewCheck,
@@ -47,6 +51,8 @@ pub use execution_task_contract::{
     MarketVelocityPaperOutcomeResponse, StrategySignalDispatchResponse,
     StrategySignalSubmitRequest, UserExchangeConfig,
 };
+pub use execution_task_queue::{PostgresExecutionTaskSource, PostgresExecutionTaskSourceConfig};
+pub use execution_task_source::{ExecutionTaskSource, ExecutionTaskSourceKind};
 pub use execution_worker::{
     ExecutionOrderTask, ExecutionWorker, ExecutionWorkerConfig, ExecutionWorkerLane,
 };
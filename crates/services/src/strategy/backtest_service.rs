@@ -88,6 +88,7 @@ impl BacktestService {
         log_entity.four_bar_after_win_rate = 0.0;
         log_entity.five_bar_after_win_rate = 0.0;
         log_entity.ten_bar_after_win_rate = 0.0;
+        log_entity.total_funding_fee = back_test_result.total_funding_fee;
         let back_test_id = self.repository.insert_log(&log_entity).await?;
         // 随机测试只用于压力/扰动验证，不能污染正式的交易明细和绩效指标表。
         if !random_backtest_is_enabled() {
@@ -374,6 +375,16 @@ impl BacktestService {
             total_return: metrics.total_return,
             max_drawdown: metrics.max_drawdown,
             volatility: metrics.volatility,
+            sortino_ratio: metrics.sortino_ratio,
+            calmar_ratio: metrics.calmar_ratio,
+            profit_factor: metrics.profit_factor,
+            expectancy_r: metrics.expectancy_r,
+            average_hold_hours: metrics.average_hold_hours,
+            exposure: metrics.exposure,
+            longest_drawdown_days: metrics.longest_drawdown_days,
+            max_win_streak: i32::try_from(metrics.max_win_streak).unwrap_or(i32::MAX),
+            max_loss_streak: i32::try_from(metrics.max_loss_streak).unwrap_or(i32::MAX),
+            tail_ratio: metrics.tail_ratio,
         };
         // 更新数据库
         let affected = self
@@ -381,12 +392,16 @@ impl BacktestService {
             .update_performance_metrics(back_test_id, &domain_metrics)
             .await?;
         info!(
-            "绩效指标更新成功: back_test_id={}, sharpe={:.4}, annual_return={:.2}%, max_drawdown={:.2}%, volatility={:.2}%",
+            "绩效指标更新成功: back_test_id={}, sharpe={:.4}, sortino={:.4}, calmar={:.4}, profit_factor={:.4}, annual_return={:.2}%, max_drawdown={:.2}%, volatility={:.2}%, exposure={:.2}%",
             back_test_id,
             metrics.sharpe_ratio,
+            metrics.sortino_ratio,
+            metrics.calmar_ratio,
+            metrics.profit_factor,
             metrics.annual_return * 100.0,
             metrics.max_drawdown * 100.0,
-            metrics.volatility * 100.0
+            metrics.volatility * 100.0,
+            metrics.exposure * 100.0
         );
         Ok(affected)
     }
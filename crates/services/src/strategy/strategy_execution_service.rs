@@ -4,6 +4,7 @@
 use super::live_decision::{apply_live_decision, approx_eq_opt};
 use super::strategy_signal_payload::{self, StrategySignalPayloadBuildOptions};
 use super::StrategyDataService;
+use crate::risk::RiskManagementService;
 use crate::rust_quan_web::{ExecutionTaskClient, ExecutionTaskConfig, StrategySignalSubmitRequest};
 use anyhow::{anyhow, Result};
 use dashmap::DashMap;
@@ -12,9 +13,11 @@ use okx::enums::account_enums::AccountType;
 use redis::AsyncCommands;
 use rust_quant_common::CandleItem;
 use rust_quant_core::cache::get_redis_connection;
+use rust_quant_core::metrics;
 use rust_quant_domain::entities::SwapOrder;
 use rust_quant_domain::traits::SwapOrderRepository;
 use rust_quant_domain::{OrderSide, PositionSide, StrategyConfig};
+use rust_quant_risk::policies::{PreTradeRiskRequest, RiskDecision};
 use rust_quant_strategies::framework::backtest::{
     compute_current_targets, BasicRiskStrategyConfig, ExitTargets, TradingState,
 };
@@ -70,7 +73,7 @@ struct GuardTestState {
 /// - StrategyRegistry: 获取策略实现
 /// - SwapOrderRepository: 订单持久化
 /// - TradingService: 创建订单（待实现）
-/// - RiskService: 风控检查（待实现）
+/// - RiskManagementService: 下单前风控（放行 / 缩仓 / 拒绝）
 pub struct StrategyExecutionService {
     /// 合约订单仓储（依赖注入）
     swap_order_repository: Arc<dyn SwapOrderRepository>,
@@ -78,6 +81,8 @@ pub struct StrategyExecutionService {
     live_states: DashMap<i64, TradingState>,
     /// 实盘止盈止损目标缓存
     live_exit_targets: DashMap<i64, LiveExitTargets>,
+    /// 下单前风控服务；拒绝时不创建任何订单任务
+    risk_service: Arc<RiskManagementService>,
     #[cfg(test)]
     /// 状态值。
     guard_test_state: Arc<GuardTestState>,
@@ -90,10 +95,19 @@ impl StrategyExecutionService {
             swap_order_repository,
             live_states: DashMap::new(),
             live_exit_targets: DashMap::new(),
+            risk_service: Arc::new(RiskManagementService::from_env().unwrap_or_else(|e| {
+                warn!("加载下单前风控配置失败，下单前风控保持关闭: {}", e);
+                RiskManagementService::new()
+            })),
             #[cfg(test)]
             guard_test_state: Arc::new(GuardTestState::default()),
         }
     }
+    /// 注入下单前风控服务（便于共享交易频次账本或测试替换）
+    pub fn with_risk_service(mut self, risk_service: Arc<RiskManagementService>) -> Self {
+        self.risk_service = risk_service;
+        self
+    }
     /// 判断K 线entitytoitem，给交易执行流程提供布尔结果。
     fn candle_entity_to_item(c: &rust_quant_market::models::CandlesEntity) -> Result<CandleItem> {
         let o =
@@ -209,6 +223,7 @@ impl StrategyExecutionService {
             Some(c) => Some(Self::candle_entity_to_item(c)?),
             None => None,
         };
+        let analysis_started_at = std::time::Instant::now();
         let mut signal = match strategy_executor
             .execute(inst_id, period, config, snap_item.clone())
             .await
@@ -251,6 +266,10 @@ impl StrategyExecutionService {
                 return Err(anyhow!("策略分析失败: {}", error));
             }
         };
+        metrics::record_strategy_execution_latency(
+            config.strategy_type.as_str(),
+            analysis_started_at.elapsed(),
+        );
         if Self::smoke_forced_signal_side_from_env().is_some() {
             let trigger_candle = snap_item.as_ref().ok_or_else(|| {
                 anyhow!("RUST_QUANT_SMOKE_FORCE_SIGNAL requires a confirmed trigger candle")
@@ -276,6 +295,10 @@ impl StrategyExecutionService {
                 signal.should_sell,
                 signal.ts
             );
+            metrics::record_strategy_signal(
+                config.strategy_type.as_str(),
+                if signal.should_buy { "buy" } else { "sell" },
+            );
             // 6. 异步记录信号日志（不阻塞下单）
             self.save_signal_log_async(inst_id, period, &signal, config);
         }
@@ -658,6 +681,20 @@ impl StrategyExecutionService {
         let side = order_side.as_str();
         let pos_side = position_side.as_str();
         info!("交易方向: side={}, pos_side={}", side, pos_side);
+        // 2. 下单前风控：拒绝时直接返回错误（由调用方回滚实盘状态），缩仓时按比例下调
+        let risk_decision = self
+            .risk_service
+            .evaluate_pre_trade(&PreTradeRiskRequest {
+                inst_id: inst_id.to_string(),
+                side: position_side,
+                proposed_notional: self.risk_service.order_notional_usdt(),
+                leverage: risk_config.max_leverage,
+                signal_ts: signal.ts,
+            })
+            .await;
+        if let RiskDecision::Reject { reasons } = &risk_decision {
+            return Err(anyhow!("下单前风控拒绝: {}", reasons.join("; ")));
+        }
         if Self::should_dispatch_strategy_signal_to_quant_web() {
             info!(
                 "策略信号改由 rust_quan_web 分发执行任务: inst_id={}, period={}, config_id={}, side={}, pos_side={}",
@@ -675,8 +712,10 @@ impl StrategyExecutionService {
                 side,
                 pos_side,
                 &client_order_id,
+                &risk_decision,
             )
             .await?;
+            self.risk_service.record_trade(inst_id, signal.ts);
             return Ok(());
         }
         Self::ensure_legacy_direct_live_exchange_order_allowed()?;
@@ -767,7 +806,7 @@ impl StrategyExecutionService {
             "最大可用数量: side={}, max_available={}, safety_factor={}",
             side, max_available, safety_factor
         );
-        let order_size_f64 = max_available * safety_factor;
+        let order_size_f64 = max_available * safety_factor * risk_decision.size_ratio();
         let order_size = if order_size_f64 < 1.0 {
             "0".to_string()
         } else {
@@ -835,6 +874,7 @@ impl StrategyExecutionService {
                 String::new()
             }
         };
+        self.risk_service.record_trade(inst_id, signal.ts);
         info!(
             "✅ 下单成功: inst_id={}, order_id={}, size={}",
             inst_id, out_order_id, order_size
@@ -844,6 +884,7 @@ impl StrategyExecutionService {
             "entry_price": entry_price,
             "stop_loss": final_stop_loss,
             "take_profit": take_profit_trigger_px,
+            "risk_decision": &risk_decision,
             "signal": {
                 "should_buy": signal.should_buy,
                 "should_sell": signal.should_sell,
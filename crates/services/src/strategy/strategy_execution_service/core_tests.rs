@@ -148,6 +148,7 @@
             dynamic_adjustments: vec![],
             dynamic_config_snapshot: None,
             direction: rust_quant_domain::SignalDirection::Long,
+            entry_order: None,
         }
     }
     /// 创建测试用的SignalResult - 卖出信号
@@ -175,6 +176,7 @@
             dynamic_adjustments: vec![],
             dynamic_config_snapshot: None,
             direction: rust_quant_domain::SignalDirection::Short,
+            entry_order: None,
         }
     }
     fn create_trigger_candle(close: f64, ts: i64) -> CandleItem {
@@ -282,6 +284,8 @@
             "buy",
             "long",
             "rq421704067200000",
+            &RiskDecision::Allow,
+            None,
         )
         .unwrap();
         assert_eq!(request.source, "rust_quant");
@@ -336,6 +340,8 @@
             "sell",
             "short",
             "rq421704067200000",
+            &RiskDecision::Allow,
+            None,
         )
         .unwrap();
         let payload: serde_json::Value = serde_json::from_str(&request.payload_json).unwrap();
@@ -346,6 +352,35 @@
         assert_eq!(payload["risk_plan"]["protective_stop_loss_required"], true);
     }
     #[test]
+    fn strategy_signal_request_carries_pre_trade_shrink_decision() {
+        let signal = create_sell_signal(3500.0, 1704067200000);
+        let risk_config = create_test_risk_config(0.02, Some(true));
+        let request = StrategyExecutionService::build_strategy_signal_submit_request(
+            "ETH-USDT-SWAP",
+            "4H",
+            &signal,
+            &risk_config,
+            42,
+            "vegas",
+            "eth_4h_id102_live_v2",
+            Some("binance"),
+            "sell",
+            "short",
+            "rq421704067200000",
+            &RiskDecision::Shrink {
+                size_ratio: 0.5,
+                reasons: vec!["drawdown warning".to_string()],
+            },
+            Some(400.0),
+        )
+        .unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&request.payload_json).unwrap();
+        assert_eq!(payload["risk_decision"]["action"], "shrink");
+        assert_eq!(payload["position_size_ratio"], 0.5);
+        // 下发缩仓前的名义价值，由执行 worker 按比例下调
+        assert_eq!(payload["size_usdt"], 400.0);
+    }
+    #[test]
     fn strategy_signal_request_rejects_out_of_range_max_loss_percent() {
         let signal = create_sell_signal(3500.0, 1704067200000);
         let risk_config = create_test_risk_config(1.5, Some(false));
@@ -361,6 +396,8 @@
             "sell",
             "short",
             "rq421704067200000",
+            &RiskDecision::Allow,
+            None,
         )
         .expect_err("strategy signal payload must reject out-of-range max loss");
         assert!(error.to_string().contains("max_loss_percent"));
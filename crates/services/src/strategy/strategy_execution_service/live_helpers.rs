@@ -173,7 +173,19 @@ impl StrategyExecutionService {
         side: &str,
         pos_side: &str,
         client_order_id: &str,
+        risk_decision: &RiskDecision,
+        order_notional_usdt: Option<f64>,
     ) -> Result<StrategySignalSubmitRequest> {
+        let mut payload_overlay = serde_json::json!({
+            "strategy_version": strategy_version,
+            "entry_rule_version": strategy_version,
+            "risk_decision": risk_decision,
+            "position_size_ratio": risk_decision.size_ratio(),
+        });
+        // 下发缩仓前的名义价值，缩仓比例由执行 worker 统一作用到下单规模
+        if let Some(size_usdt) = order_notional_usdt {
+            payload_overlay["size_usdt"] = serde_json::json!(size_usdt);
+        }
         strategy_signal_payload::build_strategy_signal_submit_request(
             inst_id,
             period,
@@ -186,10 +198,7 @@ impl StrategyExecutionService {
             pos_side,
             client_order_id,
             StrategySignalPayloadBuildOptions {
-                payload_overlay: Some(serde_json::json!({
-                    "strategy_version": strategy_version,
-                    "entry_rule_version": strategy_version,
-                })),
+                payload_overlay: Some(payload_overlay),
                 ..Default::default()
             },
         )
@@ -227,6 +236,7 @@ impl StrategyExecutionService {
         side: &str,
         pos_side: &str,
         client_order_id: &str,
+        risk_decision: &RiskDecision,
     ) -> Result<()> {
         let client = ExecutionTaskClient::new(Self::quant_web_execution_task_config_from_env()?)?;
         let request = Self::build_strategy_signal_submit_request(
@@ -241,6 +251,8 @@ impl StrategyExecutionService {
             side,
             pos_side,
             client_order_id,
+            risk_decision,
+            self.risk_service.order_notional_usdt(),
         )?;
         let external_id = request.external_id.clone();
         let response = client.submit_strategy_signal(request).await.map_err(|e| {
@@ -24,6 +24,7 @@
             is_used_signal_k_line_stop_loss: use_signal_kline_stop_loss,
             max_hold_time: None,
             max_leverage: None,
+            realtime_rules: None,
         }
     }
     #[tokio::test]
@@ -254,6 +255,7 @@
             is_used_signal_k_line_stop_loss: Some(true), // 使用信号K线止损
             max_hold_time: None,
             max_leverage: None,
+            realtime_rules: None,
         };
         let _config = StrategyConfig {
             id: config_id,
@@ -297,6 +299,7 @@
             dynamic_adjustments: vec![],
             dynamic_config_snapshot: None,
             direction: rust_quant_domain::SignalDirection::Long,
+            entry_order: None,
         };
         println!(
             "📊 交易信号: should_buy={}, open_price={}, stop_loss={:?}",
@@ -578,6 +581,7 @@
             dynamic_adjustments: vec![],
             dynamic_config_snapshot: None,
             direction: rust_quant_domain::SignalDirection::Long,
+            entry_order: None,
         };
         let risk_config = create_test_risk_config(0.02, Some(true));
         service
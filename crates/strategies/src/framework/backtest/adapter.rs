@@ -1,4 +1,5 @@
 use super::engine::run_back_test;
+use super::higher_timeframe::{HigherTimeframeView, HigherTimeframes};
 use super::types::{BackTestResult, BasicRiskStrategyConfig, SignalResult};
 use crate::CandleItem;
 /// 通用的“指标驱动”策略回测适配器接口
@@ -19,6 +20,22 @@ pub trait IndicatorStrategyBacktest {
         values: &mut Self::IndicatorValues,
         risk_config: &BasicRiskStrategyConfig,
     ) -> SignalResult;
+    /// 声明需要因果对齐的高周期；默认只使用主周期
+    fn higher_timeframes(&self) -> Option<HigherTimeframes> {
+        None
+    }
+    /// 带高周期上下文生成信号；`higher` 只包含截至当前K线收盘已收盘的高周期K线与指标，
+    /// 顺序与 `higher_timeframes` 声明一致。默认忽略高周期上下文。
+    fn generate_signal_with_higher_timeframes(
+        &mut self,
+        candles: &[CandleItem],
+        values: &mut Self::IndicatorValues,
+        risk_config: &BasicRiskStrategyConfig,
+        higher: &[HigherTimeframeView<'_, Self::IndicatorValues>],
+    ) -> SignalResult {
+        let _ = higher;
+        self.generate_signal(candles, values, risk_config)
+    }
 }
 pub fn run_indicator_strategy_backtest<S>(
     inst_id: &str,
@@ -31,7 +48,15 @@ where
     S::IndicatorCombine: Send + Sync + 'static,
     S::IndicatorValues: Send + Sync + 'static,
 {
-    run_back_test(inst_id, strategy, candles_list, risk_config)
+    run_back_test(
+        inst_id,
+        strategy,
+        candles_list,
+        risk_config,
+        None,
+        None,
+        None,
+    )
 }
 #[cfg(test)]
 mod tests {
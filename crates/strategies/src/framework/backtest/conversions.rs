@@ -24,6 +24,7 @@ pub fn convert_domain_signal(domain_signal: rust_quant_domain::SignalResult) ->
         dynamic_adjustments: domain_signal.dynamic_adjustments,
         dynamic_config_snapshot: domain_signal.dynamic_config_snapshot,
         direction: domain_signal.direction,
+        entry_order: None,
     }
 }
 /// 将 strategies 的基础风控配置转换为 domain 层所需结构
@@ -38,5 +39,6 @@ pub fn to_domain_basic_risk_config(
         is_move_stop_loss: None,
         max_hold_time: None,
         max_leverage: None,
+        realtime_rules: None,
     }
 }
@@ -0,0 +1,341 @@
+//! # 回测成交成本模型
+//!
+//! 回测默认按信号价成交、按 `trade_fee_rate` 双边扣费。[`CostModel`] 把滑点、
+//! 手续费档位和延迟成交抽象为可替换的实现，由 `BasicRiskStrategyConfig::cost_model`
+//! 按需启用，使回测盈亏与 `net_profit_r` 反映真实执行成本。
+//!
+//! ## 实现
+//! - [`FixedBpsSlippage`] - 固定 bps 滑点
+//! - [`AtrSlippage`] - 按 ATR 比例滑点
+//! - [`VolumeParticipationImpact`] - 按成交量参与率的平方根冲击
+//! - [`MakerTakerFees`] - maker/taker 手续费档位
+//! - [`BarDelayFill`] - 入场延迟 N 根 K 线按开盘价成交
+//! - [`CostModelConfig`] - 可序列化的组合配置，挂在风控配置上
+use super::super::types::TradeSide;
+use crate::CandleItem;
+use serde::{Deserialize, Serialize};
+
+/// 成交流动性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Liquidity {
+    /// 挂单成交：最优价挂单入场、止盈限价单离场。
+    Maker,
+    /// 吃单成交：市价入场、止损与信号平仓。
+    Taker,
+}
+
+/// 离场订单类型
+///
+/// 平仓成交的流动性由离场订单决定，而不是由平仓原因的展示文案决定。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExitOrderType {
+    /// 预挂的止盈限价单，触达目标价时按 maker 成交。
+    TakeProfitLimit,
+    /// 止损触发单、信号平仓与强制平仓，按市价吃单成交。
+    #[default]
+    Market,
+}
+impl ExitOrderType {
+    /// 离场订单对应的成交流动性。
+    pub fn liquidity(self) -> Liquidity {
+        match self {
+            ExitOrderType::TakeProfitLimit => Liquidity::Maker,
+            ExitOrderType::Market => Liquidity::Taker,
+        }
+    }
+}
+
+/// 成交动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillAction {
+    /// 开仓
+    Entry,
+    /// 平仓
+    Exit,
+}
+
+/// 单次成交的定价输入
+#[derive(Debug, Clone, Copy)]
+pub struct FillRequest<'a> {
+    /// 仓位方向。
+    pub side: TradeSide,
+    /// 开仓或平仓。
+    pub action: FillAction,
+    /// 未计成本的参考成交价（信号价或止盈止损价）。
+    pub reference_price: f64,
+    /// 成交数量。
+    pub quantity: f64,
+    /// 成交所在 K 线。
+    pub candle: &'a CandleItem,
+    /// 当前 ATR；为空时由模型自行降级。
+    pub atr: Option<f64>,
+    /// 成交流动性。
+    pub liquidity: Liquidity,
+}
+impl FillRequest<'_> {
+    /// 本次成交是否为买入方向（多头开仓或空头平仓）。
+    pub fn is_buy(&self) -> bool {
+        matches!(
+            (self.side, self.action),
+            (TradeSide::Long, FillAction::Entry) | (TradeSide::Short, FillAction::Exit)
+        )
+    }
+
+    /// 按不利方向把价格偏移 `offset`：买入抬高、卖出压低，且不低于 0。
+    fn adverse_price(&self, offset: f64) -> f64 {
+        if !offset.is_finite() || offset <= 0.0 {
+            return self.reference_price;
+        }
+        if self.is_buy() {
+            self.reference_price + offset
+        } else {
+            (self.reference_price - offset).max(0.0)
+        }
+    }
+}
+
+/// 回测成交成本模型
+///
+/// 所有方法都有“无成本”的默认实现，单一实现只覆盖自己关心的维度。
+pub trait CostModel {
+    /// 返回计入滑点后的实际成交价。
+    fn fill_price(&self, request: &FillRequest<'_>) -> f64 {
+        request.reference_price
+    }
+    /// 返回该流动性下的单边手续费率；None 表示沿用 `trade_fee_rate`。
+    fn fee_rate(&self, _liquidity: Liquidity) -> Option<f64> {
+        None
+    }
+    /// 入场信号延迟成交的 K 线根数；0 表示按信号价即时成交。
+    fn fill_delay_bars(&self) -> usize {
+        0
+    }
+}
+
+/// 固定 bps 滑点，只作用于吃单成交。
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct FixedBpsSlippage {
+    /// 单边滑点，单位 bps。
+    pub bps: f64,
+}
+impl CostModel for FixedBpsSlippage {
+    fn fill_price(&self, request: &FillRequest<'_>) -> f64 {
+        if request.liquidity == Liquidity::Maker {
+            return request.reference_price;
+        }
+        request.adverse_price(request.reference_price * self.bps / 10_000.0)
+    }
+}
+
+/// ATR 比例滑点；ATR 未就绪时用当前 K 线振幅近似。
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct AtrSlippage {
+    /// 单边滑点占 ATR 的比例，例如 0.05 表示 5% ATR。
+    pub atr_fraction: f64,
+}
+impl CostModel for AtrSlippage {
+    fn fill_price(&self, request: &FillRequest<'_>) -> f64 {
+        if request.liquidity == Liquidity::Maker {
+            return request.reference_price;
+        }
+        let atr = request
+            .atr
+            .filter(|atr| atr.is_finite() && *atr > 0.0)
+            .unwrap_or_else(|| (request.candle.h - request.candle.l).abs());
+        request.adverse_price(atr * self.atr_fraction)
+    }
+}
+
+/// 成交量参与率冲击：`impact_bps * sqrt(quantity / bar_volume)`。
+///
+/// 参与率按 K 线成交量计算并封顶为 1；K 线无成交量时按满参与率计价。
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct VolumeParticipationImpact {
+    /// 满参与率（成交量与 K 线成交量相等）时的单边冲击，单位 bps。
+    pub impact_bps: f64,
+}
+impl CostModel for VolumeParticipationImpact {
+    fn fill_price(&self, request: &FillRequest<'_>) -> f64 {
+        if request.liquidity == Liquidity::Maker {
+            return request.reference_price;
+        }
+        let participation = if request.candle.v > 0.0 {
+            (request.quantity.abs() / request.candle.v).min(1.0)
+        } else {
+            1.0
+        };
+        let bps = self.impact_bps * participation.sqrt();
+        request.adverse_price(request.reference_price * bps / 10_000.0)
+    }
+}
+
+/// maker/taker 手续费档位。
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct MakerTakerFees {
+    /// 挂单手续费率，例如 0.0002。
+    pub maker_fee_rate: f64,
+    /// 吃单手续费率，例如 0.0005。
+    pub taker_fee_rate: f64,
+}
+impl CostModel for MakerTakerFees {
+    fn fee_rate(&self, liquidity: Liquidity) -> Option<f64> {
+        Some(match liquidity {
+            Liquidity::Maker => self.maker_fee_rate,
+            Liquidity::Taker => self.taker_fee_rate,
+        })
+    }
+}
+
+/// 入场延迟成交：信号后第 `bars` 根 K 线按开盘价成交。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BarDelayFill {
+    /// 延迟根数。
+    pub bars: usize,
+}
+impl CostModel for BarDelayFill {
+    fn fill_delay_bars(&self) -> usize {
+        self.bars
+    }
+}
+
+/// 滑点模型选择
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlippageModel {
+    /// 固定 bps。
+    FixedBps(FixedBpsSlippage),
+    /// ATR 比例。
+    AtrProportional(AtrSlippage),
+    /// 成交量参与率冲击。
+    VolumeParticipation(VolumeParticipationImpact),
+}
+impl CostModel for SlippageModel {
+    fn fill_price(&self, request: &FillRequest<'_>) -> f64 {
+        match self {
+            SlippageModel::FixedBps(model) => model.fill_price(request),
+            SlippageModel::AtrProportional(model) => model.fill_price(request),
+            SlippageModel::VolumeParticipation(model) => model.fill_price(request),
+        }
+    }
+}
+
+/// 风控配置上的成本模型组合；各维度独立可选，全部为空时等价于历史口径。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub struct CostModelConfig {
+    /// 滑点模型。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slippage: Option<SlippageModel>,
+    /// 手续费档位；为空时沿用 `trade_fee_rate`。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_tier: Option<MakerTakerFees>,
+    /// 入场延迟成交。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill_delay: Option<BarDelayFill>,
+}
+impl CostModel for CostModelConfig {
+    fn fill_price(&self, request: &FillRequest<'_>) -> f64 {
+        self.slippage
+            .map(|model| model.fill_price(request))
+            .filter(|price| price.is_finite() && *price > 0.0)
+            .unwrap_or(request.reference_price)
+    }
+    fn fee_rate(&self, liquidity: Liquidity) -> Option<f64> {
+        self.fee_tier.and_then(|tier| tier.fee_rate(liquidity))
+    }
+    fn fill_delay_bars(&self) -> usize {
+        self.fill_delay
+            .map(|delay| delay.fill_delay_bars())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(volume: f64) -> CandleItem {
+        CandleItem {
+            o: 100.0,
+            h: 102.0,
+            l: 98.0,
+            c: 100.0,
+            v: volume,
+            ts: 0,
+            confirm: 1,
+        }
+    }
+
+    fn request<'a>(
+        candle: &'a CandleItem,
+        side: TradeSide,
+        action: FillAction,
+        liquidity: Liquidity,
+    ) -> FillRequest<'a> {
+        FillRequest {
+            side,
+            action,
+            reference_price: 100.0,
+            quantity: 25.0,
+            candle,
+            atr: Some(2.0),
+            liquidity,
+        }
+    }
+
+    #[test]
+    fn fixed_bps_slippage_is_adverse_for_both_directions() {
+        let bar = candle(100.0);
+        let model = FixedBpsSlippage { bps: 10.0 };
+        let long_entry = request(&bar, TradeSide::Long, FillAction::Entry, Liquidity::Taker);
+        let long_exit = request(&bar, TradeSide::Long, FillAction::Exit, Liquidity::Taker);
+        let short_entry = request(&bar, TradeSide::Short, FillAction::Entry, Liquidity::Taker);
+        assert!((model.fill_price(&long_entry) - 100.1).abs() < 1e-9);
+        assert!((model.fill_price(&long_exit) - 99.9).abs() < 1e-9);
+        assert!((model.fill_price(&short_entry) - 99.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn maker_fills_skip_slippage() {
+        let bar = candle(100.0);
+        let model = AtrSlippage { atr_fraction: 0.5 };
+        let maker = request(&bar, TradeSide::Long, FillAction::Entry, Liquidity::Maker);
+        let taker = request(&bar, TradeSide::Long, FillAction::Entry, Liquidity::Taker);
+        assert_eq!(model.fill_price(&maker), 100.0);
+        assert_eq!(model.fill_price(&taker), 101.0);
+    }
+
+    #[test]
+    fn volume_participation_uses_square_root_impact() {
+        let bar = candle(100.0);
+        let model = VolumeParticipationImpact { impact_bps: 20.0 };
+        let buy = request(&bar, TradeSide::Long, FillAction::Entry, Liquidity::Taker);
+        // 参与率 25% -> sqrt = 0.5 -> 10bps
+        assert!((model.fill_price(&buy) - 100.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cost_model_config_round_trips_and_combines_dimensions() {
+        let config = CostModelConfig {
+            slippage: Some(SlippageModel::FixedBps(FixedBpsSlippage { bps: 5.0 })),
+            fee_tier: Some(MakerTakerFees {
+                maker_fee_rate: 0.0002,
+                taker_fee_rate: 0.0005,
+            }),
+            fill_delay: Some(BarDelayFill { bars: 1 }),
+        };
+        let json = serde_json::to_string(&config).expect("serialize cost model");
+        let parsed: CostModelConfig = serde_json::from_str(&json).expect("parse cost model");
+        assert_eq!(parsed, config);
+        assert_eq!(parsed.fee_rate(Liquidity::Maker), Some(0.0002));
+        assert_eq!(parsed.fee_rate(Liquidity::Taker), Some(0.0005));
+        assert_eq!(parsed.fill_delay_bars(), 1);
+        assert_eq!(CostModelConfig::default().fee_rate(Liquidity::Taker), None);
+    }
+
+    #[test]
+    fn take_profit_limit_exits_are_maker() {
+        assert_eq!(ExitOrderType::TakeProfitLimit.liquidity(), Liquidity::Maker);
+        assert_eq!(ExitOrderType::Market.liquidity(), Liquidity::Taker);
+    }
+}
@@ -0,0 +1,371 @@
+//! 经济日历停牌窗口
+//!
+//! 与实盘 `StrategyExecutionService::check_economic_event_window` 对齐：重要经济事件发布前后
+//! 的窗口内不开新仓，可按重要性配置是否同时平掉已有持仓。事件来自
+//! `EconomicEventRepository` 或 JSON fixture 文件。
+use rust_quant_domain::entities::{EconomicEvent, EventImportance};
+use rust_quant_domain::traits::EconomicEventRepository;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+/// 经济事件窗口内被拦截的信号在 `filtered_signals` 中记录的原因
+pub const ECONOMIC_EVENT_WINDOW_FILTER_REASON: &str = "ECONOMIC_EVENT_WINDOW";
+/// 单个重要性级别的停牌窗口
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EconomicEventWindow {
+    /// 事件前多少分钟开始生效
+    pub before_minutes: i64,
+    /// 事件后多少分钟仍生效
+    pub after_minutes: i64,
+    /// 窗口内是否平掉已有持仓；false 时只拦截新开仓
+    pub flatten_positions: bool,
+}
+impl Default for EconomicEventWindow {
+    /// 与实盘 `ECON_EVENT_WINDOW_BEFORE_MIN` / `ECON_EVENT_WINDOW_AFTER_MIN` 默认值一致。
+    fn default() -> Self {
+        Self {
+            before_minutes: 30,
+            after_minutes: 60,
+            flatten_positions: false,
+        }
+    }
+}
+/// 按事件重要性配置的停牌窗口；为空的级别不生效
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EconomicBlackoutConfig {
+    pub low: Option<EconomicEventWindow>,
+    pub medium: Option<EconomicEventWindow>,
+    pub high: Option<EconomicEventWindow>,
+}
+impl Default for EconomicBlackoutConfig {
+    /// 默认只对高重要性事件生效，与实盘口径一致
+    fn default() -> Self {
+        Self {
+            low: None,
+            medium: None,
+            high: Some(EconomicEventWindow::default()),
+        }
+    }
+}
+impl EconomicBlackoutConfig {
+    /// 读取与实盘相同的窗口环境变量，并用 `BACKTEST_ECON_EVENT_FLATTEN` 控制是否平仓
+    pub fn from_env() -> Self {
+        let defaults = EconomicEventWindow::default();
+        let minutes = |key: &str, default: i64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            high: Some(EconomicEventWindow {
+                before_minutes: minutes("ECON_EVENT_WINDOW_BEFORE_MIN", defaults.before_minutes),
+                after_minutes: minutes("ECON_EVENT_WINDOW_AFTER_MIN", defaults.after_minutes),
+                flatten_positions: rust_quant_core::config::env_is_true(
+                    "BACKTEST_ECON_EVENT_FLATTEN",
+                    false,
+                ),
+            }),
+            ..Self::default()
+        }
+    }
+    pub fn window(&self, importance: EventImportance) -> Option<EconomicEventWindow> {
+        match importance {
+            EventImportance::Low => self.low,
+            EventImportance::Medium => self.medium,
+            EventImportance::High => self.high,
+        }
+    }
+    /// 生效的最低重要性，用于缩小仓储查询范围
+    fn min_importance(&self) -> Option<i32> {
+        [
+            (EventImportance::Low, self.low),
+            (EventImportance::Medium, self.medium),
+            (EventImportance::High, self.high),
+        ]
+        .into_iter()
+        .find(|(_, window)| window.is_some())
+        .map(|(importance, _)| importance as i32)
+    }
+    /// 所有级别中最长的前后窗口（毫秒），用于扩展仓储查询范围
+    fn max_padding_ms(&self) -> (i64, i64) {
+        [self.low, self.medium, self.high]
+            .into_iter()
+            .flatten()
+            .fold((0, 0), |(before, after), window| {
+                (
+                    before.max(window.before_minutes * 60_000),
+                    after.max(window.after_minutes * 60_000),
+                )
+            })
+    }
+}
+/// 单个事件展开后的停牌区间
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlackoutWindow {
+    /// 区间起点（含），Unix 毫秒时间戳
+    pub start_ms: i64,
+    /// 区间终点（含），Unix 毫秒时间戳
+    pub end_ms: i64,
+    /// 是否平掉已有持仓
+    pub flatten_positions: bool,
+    /// 事件描述，如 `US CPI YoY`
+    pub label: String,
+}
+/// 回测使用的经济事件停牌日历
+#[derive(Debug, Clone, Default)]
+pub struct EconomicBlackout {
+    /// 按起点升序排列的停牌区间
+    windows: Vec<BlackoutWindow>,
+    /// 主周期K线时长；信号在K线收盘时判定，与实盘在收盘后执行一致
+    bar_duration_ms: i64,
+    /// 最长区间跨度，用于限制每次查询的回溯范围
+    max_span_ms: i64,
+}
+impl EconomicBlackout {
+    /// 按配置把事件展开为停牌区间
+    pub fn new(
+        events: &[EconomicEvent],
+        config: &EconomicBlackoutConfig,
+        bar_duration_ms: i64,
+    ) -> Self {
+        let mut windows: Vec<BlackoutWindow> = events
+            .iter()
+            .filter_map(|event| {
+                let window = config.window(EventImportance::from(event.importance))?;
+                Some(BlackoutWindow {
+                    start_ms: event.event_time - window.before_minutes * 60_000,
+                    end_ms: event.event_time + window.after_minutes * 60_000,
+                    flatten_positions: window.flatten_positions,
+                    label: format!("{} {}", event.region, event.event),
+                })
+            })
+            .collect();
+        windows.sort_by_key(|window| window.start_ms);
+        let max_span_ms = windows
+            .iter()
+            .map(|window| window.end_ms - window.start_ms)
+            .max()
+            .unwrap_or(0);
+        Self {
+            windows,
+            bar_duration_ms,
+            max_span_ms,
+        }
+    }
+    /// 从仓储加载 `[start_ms, end_ms]` 回测区间内会影响K线的事件
+    pub async fn load(
+        repository: &dyn EconomicEventRepository,
+        start_ms: i64,
+        end_ms: i64,
+        config: &EconomicBlackoutConfig,
+        bar_duration_ms: i64,
+    ) -> anyhow::Result<Self> {
+        let Some(min_importance) = config.min_importance() else {
+            return Ok(Self::default());
+        };
+        // 事件在回测区间外但窗口覆盖区间边界时同样生效
+        let (before, after) = config.max_padding_ms();
+        let events = repository
+            .find_by_time_range(
+                start_ms - after,
+                end_ms + bar_duration_ms + before,
+                Some(min_importance),
+            )
+            .await?;
+        Ok(Self::new(&events, config, bar_duration_ms))
+    }
+    /// 从 JSON fixture 文件加载事件，文件内容为 `EconomicEvent` 数组
+    pub fn from_fixture_file(
+        path: impl AsRef<Path>,
+        config: &EconomicBlackoutConfig,
+        bar_duration_ms: i64,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("读取经济事件 fixture 失败 {}: {}", path.display(), e))?;
+        let events: Vec<EconomicEvent> = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("解析经济事件 fixture 失败 {}: {}", path.display(), e))?;
+        Ok(Self::new(&events, config, bar_duration_ms))
+    }
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+    /// 指定时刻命中的停牌区间；多个区间重叠时优先返回需要平仓的区间
+    pub fn active_at(&self, ts: i64) -> Option<&BlackoutWindow> {
+        let candidates = self.windows.partition_point(|window| window.start_ms <= ts);
+        let mut active = self.windows[..candidates]
+            .iter()
+            .rev()
+            .take_while(|window| window.start_ms >= ts - self.max_span_ms)
+            .filter(|window| window.end_ms >= ts);
+        let first = active.next()?;
+        if first.flatten_positions {
+            return Some(first);
+        }
+        Some(
+            active
+                .find(|window| window.flatten_positions)
+                .unwrap_or(first),
+        )
+    }
+    /// 开仓判定：K线收盘时刻是否处于停牌窗口
+    pub fn entry_blocked_at(&self, candle_ts: i64) -> Option<&BlackoutWindow> {
+        self.active_at(candle_ts + self.bar_duration_ms)
+    }
+    /// 平仓判定：K线开盘时刻是否处于需要平仓的停牌窗口，命中时以开盘价离场
+    pub fn flatten_at(&self, candle_ts: i64) -> Option<&BlackoutWindow> {
+        self.active_at(candle_ts)
+            .filter(|window| window.flatten_positions)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const HOUR_MS: i64 = 60 * 60_000;
+    fn event(event_time: i64, importance: i32, name: &str) -> EconomicEvent {
+        EconomicEvent::new(
+            name.to_string(),
+            event_time,
+            "US".to_string(),
+            "Inflation".to_string(),
+            name.to_string(),
+            "2024-01".to_string(),
+            importance,
+            event_time,
+            "USD".to_string(),
+        )
+    }
+    #[test]
+    fn windows_follow_importance_config() {
+        let config = EconomicBlackoutConfig {
+            medium: Some(EconomicEventWindow {
+                before_minutes: 0,
+                after_minutes: 15,
+                flatten_positions: false,
+            }),
+            high: Some(EconomicEventWindow {
+                before_minutes: 30,
+                after_minutes: 60,
+                flatten_positions: true,
+            }),
+            ..EconomicBlackoutConfig::default()
+        };
+        let blackout = EconomicBlackout::new(
+            &[
+                event(10 * HOUR_MS, 3, "CPI"),
+                event(20 * HOUR_MS, 2, "PMI"),
+                event(30 * HOUR_MS, 1, "Claims"),
+            ],
+            &config,
+            HOUR_MS,
+        );
+        assert_eq!(config.min_importance(), Some(2));
+        // 9:00 开盘的1H K线在 10:00 收盘，落在 CPI 前 30 分钟窗口内
+        assert_eq!(
+            blackout
+                .entry_blocked_at(9 * HOUR_MS)
+                .map(|w| w.label.as_str()),
+            Some("US CPI")
+        );
+        assert!(blackout.entry_blocked_at(8 * HOUR_MS).is_none());
+        assert!(blackout.flatten_at(9 * HOUR_MS).is_none());
+        assert!(blackout.flatten_at(10 * HOUR_MS).is_some());
+        assert!(blackout.flatten_at(11 * HOUR_MS).is_some());
+        assert!(blackout.flatten_at(11 * HOUR_MS + 1).is_none());
+        assert!(blackout.entry_blocked_at(19 * HOUR_MS).is_some());
+        assert!(blackout.flatten_at(20 * HOUR_MS).is_none());
+        assert!(blackout.entry_blocked_at(29 * HOUR_MS).is_none());
+    }
+    #[test]
+    fn fixture_file_round_trips_events() {
+        let path = std::env::temp_dir().join(format!(
+            "economic_events_fixture_{}.json",
+            uuid::Uuid::new_v4().simple()
+        ));
+        std::fs::write(
+            &path,
+            serde_json::to_string(&vec![event(5 * HOUR_MS, 3, "FOMC")]).unwrap(),
+        )
+        .unwrap();
+        let blackout =
+            EconomicBlackout::from_fixture_file(&path, &EconomicBlackoutConfig::default(), HOUR_MS)
+                .unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(!blackout.is_empty());
+        assert!(blackout.entry_blocked_at(4 * HOUR_MS).is_some());
+        assert!(blackout.flatten_at(5 * HOUR_MS).is_none());
+    }
+    #[test]
+    fn backtest_flattens_and_blocks_entries_inside_event_window() {
+        use crate::framework::backtest::adapter::IndicatorStrategyBacktest;
+        use crate::framework::backtest::engine::run_back_test;
+        use crate::framework::backtest::types::{BasicRiskStrategyConfig, SignalResult};
+        use crate::CandleItem;
+        struct AlwaysLong;
+        impl IndicatorStrategyBacktest for AlwaysLong {
+            type IndicatorCombine = ();
+            type IndicatorValues = ();
+            fn min_data_length(&self) -> usize {
+                2
+            }
+            fn init_indicator_combine(&self) -> Self::IndicatorCombine {}
+            fn build_indicator_values(_: &mut Self::IndicatorCombine, _: &CandleItem) {}
+            fn generate_signal(
+                &mut self,
+                candles: &[CandleItem],
+                _: &mut Self::IndicatorValues,
+                _: &BasicRiskStrategyConfig,
+            ) -> SignalResult {
+                let last = candles.last().unwrap();
+                SignalResult {
+                    ts: last.ts,
+                    open_price: last.c,
+                    should_buy: true,
+                    ..SignalResult::default()
+                }
+            }
+        }
+        let candles: Vec<CandleItem> = (0..600)
+            .map(|i| CandleItem {
+                o: 100.0,
+                h: 101.0,
+                l: 99.0,
+                c: 100.0,
+                v: 1.0,
+                ts: i * HOUR_MS,
+                confirm: 1,
+            })
+            .collect();
+        let config = EconomicBlackoutConfig {
+            high: Some(EconomicEventWindow {
+                flatten_positions: true,
+                ..EconomicEventWindow::default()
+            }),
+            ..EconomicBlackoutConfig::default()
+        };
+        let blackout = EconomicBlackout::new(&[event(550 * HOUR_MS, 3, "CPI")], &config, HOUR_MS);
+        let result = run_back_test(
+            "TEST",
+            AlwaysLong,
+            &candles,
+            BasicRiskStrategyConfig {
+                max_loss_percent: 1.0,
+                ..BasicRiskStrategyConfig::default()
+            },
+            None,
+            None,
+            Some(blackout),
+        );
+        assert!(result
+            .trade_records
+            .iter()
+            .any(|record| record.close_type == "经济事件平仓"));
+        assert!(result.filtered_signals.iter().any(|signal| signal
+            .filter_reasons
+            .iter()
+            .any(|reason| reason == ECONOMIC_EVENT_WINDOW_FILTER_REASON)));
+        assert!(result.open_trades >= 2, "窗口结束后应重新开仓");
+    }
+}
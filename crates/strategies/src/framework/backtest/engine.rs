@@ -1,17 +1,28 @@
 use super::adapter::IndicatorStrategyBacktest;
+use super::economic_calendar::EconomicBlackout;
+use super::funding::FundingSeries;
+use super::intrabar::IntrabarCandles;
 use super::pipeline::stages::{FilterStage, PositionStage, SignalStage};
 use super::pipeline::PipelineRunner;
 use super::types::{BackTestResult, BasicRiskStrategyConfig};
 use crate::CandleItem;
+use std::sync::Arc;
 /// 回测引擎：仅保留 Pipeline 架构
 /// 提供组件化的回测执行 Pipeline，降低代码阅读复杂性。
 /// # 类型参数
 /// - `S`: 实现 `IndicatorStrategyBacktest` trait 的策略
+///
+/// `funding` 为永续合约资金费率序列，传 None 时不计提资金费。
+/// `intrabar` 为低周期 K 线，传入后止损止盈同时触达的 K 线按盘中回放确定先后。
+/// `economic_blackout` 为经济事件停牌日历，传 None 时不做经济事件过滤。
 pub fn run_back_test<S>(
     inst_id: &str,
     strategy: S,
     candles_list: &[CandleItem],
     basic_risk_config: BasicRiskStrategyConfig,
+    funding: Option<FundingSeries>,
+    intrabar: Option<Arc<IntrabarCandles>>,
+    economic_blackout: Option<EconomicBlackout>,
 ) -> BackTestResult
 where
     S: IndicatorStrategyBacktest + Send + Sync + 'static,
@@ -30,7 +41,11 @@ where
             strategy,
             !fast_mode && !random_mode,
         ))
-        .add_stage(FilterStage::with_shadow_trading(!random_mode))
-        .add_stage(PositionStage::new());
+        .add_stage(
+            FilterStage::with_shadow_trading(!random_mode)
+                .with_economic_blackout(economic_blackout),
+        )
+        .add_stage(PositionStage::new().with_intrabar(intrabar))
+        .with_funding(funding);
     pipeline.run(candles_list, inst_id, basic_risk_config, min_data_length)
 }
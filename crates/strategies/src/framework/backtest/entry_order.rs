@@ -0,0 +1,609 @@
+//! # 回测入场委托模拟
+//!
+//! 回测默认按信号价以市价入场。信号通过 [`EntryOrder`] 声明与执行 worker 一致的
+//! 委托类型（市价/限价/条件单）与有效期（GTC/IOC/FOK/Post-only）后，入场委托挂在
+//! `TradingState::resting_entry`，由后续 K 线的最高/最低价判定是否触达：
+//!
+//! - 限价单：买单最低价、卖单最高价触及委托价时按委托价以 maker 成交；下单时已穿价的
+//!   限价单按信号价以 taker 即时成交，Post-only 则直接拒单
+//! - 条件单：价格突破触发价后按触发价与开盘价中不利的一方以 taker 成交
+//! - IOC/FOK：只在下单时撮合，剩余数量立即撤销；FOK 不能全部成交时拒单
+//! - 设置 `max_volume_share` 时单根 K 线最多成交该 K 线成交量的对应比例，剩余数量继续挂单，
+//!   后续成交作为同向分批仓位单独记账
+//! - 挂单等待 `expire_bars` 根 K 线仍未成交完毕时撤销剩余数量
+//!
+//! 未声明委托但设置了 `best_open_price` 的信号按 GTC 限价单处理，兼容原有的最优价挂单。
+use super::super::types::TradeSide;
+use super::cost_model::Liquidity;
+use super::position::{open_long_position_now, open_short_position_now, position_size_multiplier};
+use super::pyramiding::build_lot;
+use super::recording::record_position_entry;
+use super::types::{BasicRiskStrategyConfig, SignalResult, TradingState};
+use crate::CandleItem;
+use rust_quant_domain::enums::PositionSide;
+use serde::{Deserialize, Serialize};
+
+/// 剩余数量低于该值时视为已全部成交，吸收数量换算的浮点误差。
+const FILL_QUANTITY_EPSILON: f64 = 1e-9;
+
+/// 入场委托类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryOrderType {
+    /// 市价单：按信号价即时成交，沿用成本模型的延迟成交。
+    #[default]
+    Market,
+    /// 限价单：按委托价或更优价格成交。
+    Limit,
+    /// 条件市价单：价格突破触发价后按市价成交，用于突破入场。
+    StopMarket,
+}
+
+/// 入场委托有效期
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryTimeInForce {
+    /// 一直有效，直到成交、到期或被新信号替换。
+    #[default]
+    Gtc,
+    /// 下单时立即成交，剩余数量撤销。
+    Ioc,
+    /// 下单时全部成交，否则拒单。
+    Fok,
+    /// 只做 maker；只作用于限价单，下单即穿价时拒单。
+    PostOnly,
+}
+
+/// 信号声明的入场委托
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EntryOrder {
+    /// 委托类型。
+    pub order_type: EntryOrderType,
+    /// 委托有效期。
+    pub time_in_force: EntryTimeInForce,
+    /// 限价单委托价或条件单触发价；为空时取信号价。
+    pub price: Option<f64>,
+    /// 挂单最多等待的 K 线根数；为空表示不过期。
+    pub expire_bars: Option<usize>,
+    /// 单根 K 线最多成交该 K 线成交量的比例，例如 0.1；为空表示不限。
+    pub max_volume_share: Option<f64>,
+}
+impl EntryOrder {
+    /// GTC 限价单。
+    pub fn limit(price: f64) -> Self {
+        Self {
+            order_type: EntryOrderType::Limit,
+            price: Some(price),
+            ..Self::default()
+        }
+    }
+
+    /// 只做 maker 的限价单。
+    pub fn post_only(price: f64) -> Self {
+        Self {
+            time_in_force: EntryTimeInForce::PostOnly,
+            ..Self::limit(price)
+        }
+    }
+
+    /// GTC 条件市价单。
+    pub fn stop_market(trigger_price: f64) -> Self {
+        Self {
+            order_type: EntryOrderType::StopMarket,
+            price: Some(trigger_price),
+            ..Self::default()
+        }
+    }
+
+    /// 设置委托有效期。
+    pub fn with_time_in_force(mut self, time_in_force: EntryTimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
+    /// 设置挂单最多等待的 K 线根数。
+    pub fn with_expire_bars(mut self, bars: usize) -> Self {
+        self.expire_bars = Some(bars);
+        self
+    }
+
+    /// 设置单根 K 线的成交量占比上限。
+    pub fn with_max_volume_share(mut self, share: f64) -> Self {
+        self.max_volume_share = Some(share);
+        self
+    }
+
+    /// 本根 K 线允许成交的最大数量；未限制时返回 None。
+    fn volume_cap(&self, candle: &CandleItem) -> Option<f64> {
+        self.max_volume_share
+            .filter(|share| share.is_finite() && *share > 0.0)
+            .map(|share| candle.v.max(0.0) * share)
+    }
+
+    /// 下单时刻的参考价是否已越过委托价，即下单即可成交。
+    fn is_marketable(&self, trade_side: TradeSide, price: f64, reference_price: f64) -> bool {
+        match (self.order_type, trade_side) {
+            (EntryOrderType::Market, _) => true,
+            (EntryOrderType::Limit, TradeSide::Long) => price >= reference_price,
+            (EntryOrderType::Limit, TradeSide::Short) => price <= reference_price,
+            (EntryOrderType::StopMarket, TradeSide::Long) => reference_price >= price,
+            (EntryOrderType::StopMarket, TradeSide::Short) => reference_price <= price,
+        }
+    }
+}
+impl SignalResult {
+    /// 解析入场委托：显式声明优先，其次把 `best_open_price` 视为 GTC 限价单，否则为市价单。
+    pub fn resolved_entry_order(&self) -> EntryOrder {
+        self.entry_order
+            .unwrap_or_else(|| match self.best_open_price {
+                Some(price) => EntryOrder::limit(price),
+                None => EntryOrder::default(),
+            })
+    }
+}
+
+/// 挂单中的入场委托
+#[derive(Debug, Clone)]
+pub struct RestingEntryOrder {
+    /// 原始入场信号，成交时沿用其止损止盈设置。
+    pub signal: SignalResult,
+    /// 入场方向。
+    pub trade_side: TradeSide,
+    /// 委托声明。
+    pub order: EntryOrder,
+    /// 生效的委托价或触发价。
+    pub price: f64,
+    /// 下单时按资金计算的目标数量。
+    pub target_quantity: f64,
+    /// 已成交数量。
+    pub filled_quantity: f64,
+    /// 条件单是否已触发；触发后剩余数量按后续 K 线开盘价以市价成交。
+    pub triggered: bool,
+    /// 下单后已经过的 K 线根数。
+    pub bars_waited: usize,
+}
+impl RestingEntryOrder {
+    fn remaining_quantity(&self) -> f64 {
+        (self.target_quantity - self.filled_quantity).max(0.0)
+    }
+
+    fn is_filled(&self) -> bool {
+        self.remaining_quantity() <= FILL_QUANTITY_EPSILON
+    }
+}
+
+/// 挂单入场统计；每笔委托只计入一个终态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EntryOrderStats {
+    /// 提交的非市价委托数。
+    pub submitted: usize,
+    /// 全部成交的委托数。
+    pub filled: usize,
+    /// 部分成交后剩余数量到期、撤单或因仓位已平而撤销的委托数。
+    pub partially_filled: usize,
+    /// 没有任何成交即到期的委托数，含未成交的 IOC。
+    pub expired: usize,
+    /// Post-only 穿价、FOK 无法全部成交或委托价无效被拒的委托数。
+    pub rejected: usize,
+    /// 没有任何成交即被新信号替换或被过滤撤销的委托数。
+    pub cancelled: usize,
+}
+impl EntryOrderStats {
+    /// 有成交（含部分成交）的委托占已提交委托的比例；没有委托时为 0。
+    pub fn fill_rate(&self) -> f64 {
+        if self.submitted == 0 {
+            return 0.0;
+        }
+        (self.filled + self.partially_filled) as f64 / self.submitted as f64
+    }
+
+    /// 按成交进度记录挂单终态，`unfilled` 为没有任何成交时的归类。
+    fn record_terminal(&mut self, order: &RestingEntryOrder, unfilled: UnfilledOutcome) {
+        if order.is_filled() {
+            self.filled += 1;
+        } else if order.filled_quantity > FILL_QUANTITY_EPSILON {
+            self.partially_filled += 1;
+        } else {
+            match unfilled {
+                UnfilledOutcome::Expired => self.expired += 1,
+                UnfilledOutcome::Cancelled => self.cancelled += 1,
+            }
+        }
+    }
+}
+
+/// 没有任何成交的挂单终态
+#[derive(Debug, Clone, Copy)]
+enum UnfilledOutcome {
+    Expired,
+    Cancelled,
+}
+
+impl TradingState {
+    /// 撤销尚未成交完毕的入场挂单，返回是否存在挂单。
+    pub fn cancel_resting_entry(&mut self) -> bool {
+        let Some(order) = self.resting_entry.take() else {
+            return false;
+        };
+        self.entry_order_stats
+            .record_terminal(&order, UnfilledOutcome::Cancelled);
+        true
+    }
+}
+
+/// 提交入场委托；市价单返回 false 由调用方按原口径开仓，其余委托由挂单模拟接管并返回 true。
+///
+/// 新委托会替换尚未成交完毕的旧挂单。
+pub(crate) fn submit_entry_order(
+    risk_config: &BasicRiskStrategyConfig,
+    state: &mut TradingState,
+    candle: &CandleItem,
+    signal: &SignalResult,
+    trade_side: TradeSide,
+) -> bool {
+    let order = signal.resolved_entry_order();
+    if order.order_type == EntryOrderType::Market {
+        return false;
+    }
+    state.cancel_resting_entry();
+    state.entry_order_stats.submitted += 1;
+    let price = order
+        .price
+        .filter(|price| price.is_finite() && *price > 0.0)
+        .unwrap_or(signal.open_price);
+    let target_quantity = state.funds / price * position_size_multiplier(risk_config);
+    if !price.is_finite() || price <= 0.0 || !target_quantity.is_finite() || target_quantity <= 0.0
+    {
+        state.entry_order_stats.rejected += 1;
+        return true;
+    }
+    let mut resting = RestingEntryOrder {
+        signal: signal.clone(),
+        trade_side,
+        order,
+        price,
+        target_quantity,
+        filled_quantity: 0.0,
+        triggered: false,
+        bars_waited: 0,
+    };
+    let immediate_only = matches!(
+        order.time_in_force,
+        EntryTimeInForce::Ioc | EntryTimeInForce::Fok
+    );
+    if order.is_marketable(trade_side, price, signal.open_price) {
+        let is_limit = order.order_type == EntryOrderType::Limit;
+        if is_limit && order.time_in_force == EntryTimeInForce::PostOnly {
+            state.entry_order_stats.rejected += 1;
+            return true;
+        }
+        let volume_cap = order.volume_cap(candle);
+        if order.time_in_force == EntryTimeInForce::Fok
+            && volume_cap.is_some_and(|cap| cap + FILL_QUANTITY_EPSILON < target_quantity)
+        {
+            state.entry_order_stats.rejected += 1;
+            return true;
+        }
+        // 穿价的限价单与已触发的条件单都在信号价吃单成交，不能按委托价虚增收益
+        resting.triggered = !is_limit;
+        fill_resting_entry(
+            risk_config,
+            state,
+            candle,
+            &mut resting,
+            signal.open_price,
+            Liquidity::Taker,
+            volume_cap,
+        );
+    }
+    if immediate_only || resting.is_filled() {
+        state
+            .entry_order_stats
+            .record_terminal(&resting, UnfilledOutcome::Expired);
+    } else {
+        state.resting_entry = Some(resting);
+    }
+    true
+}
+
+/// 用本根 K 线推进入场挂单：触达即按成交量上限成交，到期撤销剩余数量。
+///
+/// 只处理信号 K 线之后的 K 线；部分成交后仓位已被平掉时不再追加剩余数量。
+pub(crate) fn advance_resting_entry(
+    risk_config: &BasicRiskStrategyConfig,
+    state: &mut TradingState,
+    candle: &CandleItem,
+) {
+    let Some(mut resting) = state.resting_entry.take() else {
+        return;
+    };
+    if candle.ts <= resting.signal.ts {
+        state.resting_entry = Some(resting);
+        return;
+    }
+    let has_fill = resting.filled_quantity > FILL_QUANTITY_EPSILON;
+    let position_matches = match state.trade_position.as_ref() {
+        Some(position) => has_fill && position.trade_side == resting.trade_side,
+        None => !has_fill,
+    };
+    if !position_matches {
+        state
+            .entry_order_stats
+            .record_terminal(&resting, UnfilledOutcome::Cancelled);
+        return;
+    }
+    resting.bars_waited += 1;
+    if let Some((fill_price, liquidity)) = touch_fill(&resting, candle) {
+        resting.triggered |= resting.order.order_type == EntryOrderType::StopMarket;
+        let volume_cap = resting.order.volume_cap(candle);
+        fill_resting_entry(
+            risk_config,
+            state,
+            candle,
+            &mut resting,
+            fill_price,
+            liquidity,
+            volume_cap,
+        );
+    }
+    let expired = resting
+        .order
+        .expire_bars
+        .is_some_and(|bars| resting.bars_waited >= bars);
+    if resting.is_filled() || expired {
+        state
+            .entry_order_stats
+            .record_terminal(&resting, UnfilledOutcome::Expired);
+    } else {
+        state.resting_entry = Some(resting);
+    }
+}
+
+/// 本根 K 线上挂单的成交价与流动性；未触达返回 None。
+fn touch_fill(resting: &RestingEntryOrder, candle: &CandleItem) -> Option<(f64, Liquidity)> {
+    let price = resting.price;
+    if resting.triggered {
+        return Some((candle.o, Liquidity::Taker));
+    }
+    match (resting.order.order_type, resting.trade_side) {
+        (EntryOrderType::Market, _) => Some((candle.o, Liquidity::Taker)),
+        (EntryOrderType::Limit, TradeSide::Long) => {
+            (candle.l <= price).then_some((price, Liquidity::Maker))
+        }
+        (EntryOrderType::Limit, TradeSide::Short) => {
+            (candle.h >= price).then_some((price, Liquidity::Maker))
+        }
+        // 跳空越过触发价时只能按开盘价成交
+        (EntryOrderType::StopMarket, TradeSide::Long) => {
+            (candle.h >= price).then(|| (price.max(candle.o), Liquidity::Taker))
+        }
+        (EntryOrderType::StopMarket, TradeSide::Short) => {
+            (candle.l <= price).then(|| (price.min(candle.o), Liquidity::Taker))
+        }
+    }
+}
+
+/// 成交挂单剩余数量中不超过成交量上限的部分；首笔成交开主仓，后续成交追加同向分批仓位。
+fn fill_resting_entry(
+    risk_config: &BasicRiskStrategyConfig,
+    state: &mut TradingState,
+    candle: &CandleItem,
+    resting: &mut RestingEntryOrder,
+    fill_price: f64,
+    liquidity: Liquidity,
+    volume_cap: Option<f64>,
+) {
+    let remaining = resting.remaining_quantity();
+    let quantity = volume_cap.map_or(remaining, |cap| cap.min(remaining));
+    if quantity <= FILL_QUANTITY_EPSILON || !fill_price.is_finite() || fill_price <= 0.0 {
+        return;
+    }
+    let signal = SignalResult {
+        open_price: fill_price,
+        ..resting.signal.clone()
+    };
+    match state.trade_position.as_ref() {
+        None => {
+            let signal_open_time = (candle.ts > resting.signal.ts)
+                .then(|| {
+                    rust_quant_common::utils::time::mill_time_to_datetime(resting.signal.ts).ok()
+                })
+                .flatten();
+            match resting.trade_side {
+                TradeSide::Long => open_long_position_now(
+                    *risk_config,
+                    state,
+                    candle,
+                    &signal,
+                    signal_open_time,
+                    liquidity,
+                    Some(quantity),
+                ),
+                TradeSide::Short => open_short_position_now(
+                    *risk_config,
+                    state,
+                    candle,
+                    &signal,
+                    signal_open_time,
+                    liquidity,
+                    Some(quantity),
+                ),
+            }
+        }
+        Some(primary) => {
+            let lot = build_lot(risk_config, primary, candle, fill_price, quantity);
+            let option_type = match lot.trade_side {
+                TradeSide::Long => PositionSide::Long.as_str().to_owned(),
+                TradeSide::Short => PositionSide::Short.as_str().to_owned(),
+            };
+            record_position_entry(state, &lot, option_type, &signal);
+            state.scale_in_lots.push(lot);
+        }
+    }
+    resting.filled_quantity += quantity;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::backtest::signal::deal_signal;
+    use rust_quant_domain::SignalDirection;
+
+    const HOUR_MS: i64 = 3_600_000;
+
+    fn candle(index: i64, open: f64, high: f64, low: f64, close: f64, volume: f64) -> CandleItem {
+        CandleItem {
+            ts: index * HOUR_MS,
+            o: open,
+            h: high,
+            l: low,
+            c: close,
+            v: volume,
+            confirm: 1,
+        }
+    }
+
+    fn long_signal(bar: &CandleItem, order: EntryOrder) -> SignalResult {
+        SignalResult {
+            should_buy: true,
+            open_price: bar.c,
+            ts: bar.ts,
+            direction: SignalDirection::Long,
+            entry_order: Some(order),
+            ..Default::default()
+        }
+    }
+
+    fn risk() -> BasicRiskStrategyConfig {
+        BasicRiskStrategyConfig {
+            max_loss_percent: 0.5,
+            trade_fee_rate: Some(0.0),
+            ..Default::default()
+        }
+    }
+
+    /// 依次推进 K 线；第一根 K 线收盘发出信号，其余 K 线无新信号。
+    fn replay(bars: &[CandleItem], order: EntryOrder) -> TradingState {
+        let mut state = TradingState::default();
+        for (index, bar) in bars.iter().enumerate() {
+            let mut signal = if index == 0 {
+                long_signal(bar, order)
+            } else {
+                SignalResult {
+                    ts: bar.ts,
+                    open_price: bar.c,
+                    ..Default::default()
+                }
+            };
+            state = deal_signal(state, &mut signal, bar, risk(), bars, index);
+        }
+        state
+    }
+
+    #[test]
+    fn limit_order_rests_until_touched_and_fills_as_maker_at_limit() {
+        let bars = [
+            candle(0, 100.0, 101.0, 99.5, 100.0, 1_000.0),
+            candle(1, 100.0, 100.5, 99.2, 100.2, 1_000.0),
+            candle(2, 100.0, 100.5, 98.5, 99.5, 1_000.0),
+        ];
+        let state = replay(&bars[..2], EntryOrder::limit(99.0));
+        assert!(state.trade_position.is_none());
+        assert!(state.resting_entry.is_some());
+
+        let state = replay(&bars, EntryOrder::limit(99.0));
+        let position = state.trade_position.expect("limit filled");
+        assert_eq!(position.open_price, 99.0);
+        assert!((position.position_nums - 100.0 / 99.0).abs() < 1e-9);
+        assert!(position.signal_open_position_time.is_some());
+        assert!(state.resting_entry.is_none());
+        assert_eq!(state.entry_order_stats.submitted, 1);
+        assert_eq!(state.entry_order_stats.filled, 1);
+    }
+
+    #[test]
+    fn post_only_crossing_market_is_rejected_and_limit_expires() {
+        let bars = [
+            candle(0, 100.0, 101.0, 99.5, 100.0, 1_000.0),
+            candle(1, 100.0, 100.5, 99.5, 100.2, 1_000.0),
+            candle(2, 100.0, 100.5, 99.5, 99.8, 1_000.0),
+            candle(3, 100.0, 100.5, 98.0, 99.0, 1_000.0),
+        ];
+        let rejected = replay(&bars, EntryOrder::post_only(100.5));
+        assert!(rejected.trade_position.is_none());
+        assert_eq!(rejected.entry_order_stats.rejected, 1);
+
+        let expired = replay(&bars, EntryOrder::limit(99.0).with_expire_bars(2));
+        assert!(expired.trade_position.is_none(), "第3根才触达，挂单已过期");
+        assert!(expired.resting_entry.is_none());
+        assert_eq!(expired.entry_order_stats.expired, 1);
+        assert_eq!(expired.entry_order_stats.fill_rate(), 0.0);
+    }
+
+    #[test]
+    fn volume_share_splits_fill_into_primary_and_scale_in_lot() {
+        let bars = [
+            candle(0, 100.0, 101.0, 99.5, 100.0, 1_000.0),
+            candle(1, 100.0, 100.5, 98.0, 99.5, 6.0),
+            candle(2, 99.5, 100.0, 98.5, 99.8, 2.0),
+            candle(3, 99.8, 100.0, 98.5, 99.8, 1_000.0),
+        ];
+        let order = EntryOrder::limit(99.0).with_max_volume_share(0.1);
+        let state = replay(&bars[..3], order);
+        let primary = state.trade_position.as_ref().expect("partial fill");
+        assert!((primary.position_nums - 0.6).abs() < 1e-9);
+        assert_eq!(state.scale_in_lots.len(), 1);
+        assert!((state.scale_in_lots[0].position_nums - 0.2).abs() < 1e-9);
+        assert_eq!(state.scale_in_lots[0].open_price, 99.0);
+        assert!(state.resting_entry.is_some());
+
+        let state = replay(&bars, order);
+        let filled = state.trade_position.as_ref().unwrap().position_nums
+            + state
+                .scale_in_lots
+                .iter()
+                .map(|lot| lot.position_nums)
+                .sum::<f64>();
+        assert!((filled - 100.0 / 99.0).abs() < 1e-9);
+        assert!(state.resting_entry.is_none());
+        assert_eq!(state.entry_order_stats.filled, 1);
+
+        let expiring = replay(&bars, order.with_expire_bars(2));
+        assert!(expiring.resting_entry.is_none());
+        assert_eq!(expiring.scale_in_lots.len(), 1);
+        assert_eq!(expiring.entry_order_stats.partially_filled, 1);
+        assert_eq!(expiring.entry_order_stats.fill_rate(), 1.0);
+    }
+
+    #[test]
+    fn stop_market_fills_at_worse_of_trigger_and_open_and_ioc_does_not_rest() {
+        let bars = [
+            candle(0, 100.0, 101.0, 99.5, 100.0, 1_000.0),
+            candle(1, 100.5, 101.0, 100.2, 100.8, 1_000.0),
+            candle(2, 102.0, 103.0, 101.5, 102.5, 1_000.0),
+        ];
+        let state = replay(&bars, EntryOrder::stop_market(101.5));
+        let position = state.trade_position.expect("stop triggered");
+        assert_eq!(position.open_price, 102.0, "跳空越过触发价按开盘价成交");
+
+        let ioc = EntryOrder::limit(99.0).with_time_in_force(EntryTimeInForce::Ioc);
+        let state = replay(&bars, ioc);
+        assert!(state.trade_position.is_none());
+        assert!(state.resting_entry.is_none());
+        assert_eq!(state.entry_order_stats.expired, 1);
+    }
+
+    #[test]
+    fn best_open_price_maps_to_gtc_limit_order() {
+        let signal = SignalResult {
+            best_open_price: Some(99.0),
+            ..Default::default()
+        };
+        assert_eq!(signal.resolved_entry_order(), EntryOrder::limit(99.0));
+        assert_eq!(
+            SignalResult::default().resolved_entry_order().order_type,
+            EntryOrderType::Market
+        );
+    }
+}
@@ -86,6 +86,7 @@ pub fn process_fibonacci_levels(
                     initial_stop_price: None,
                     initial_risk_amount: None,
                     net_profit_r: None,
+                    funding_fee: None,
                 });
                 triggered_fib_levels.insert(idx);
                 tracing::info!(
@@ -151,6 +152,7 @@ pub fn close_remaining_position(
         initial_stop_price: None,
         initial_risk_amount: None,
         net_profit_r: None,
+        funding_fee: None,
     });
     *position = 0.0;
     triggered_fib_levels.clear();
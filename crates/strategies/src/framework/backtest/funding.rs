@@ -0,0 +1,250 @@
+//! 永续合约资金费结算
+//!
+//! 回测按交易所结算周期在整点时刻对持仓计提资金费：多头在费率为正时支付、
+//! 为负时收取，空头相反。费率取结算时刻（含）之前最近一条历史记录。
+use super::super::types::TradeSide;
+use super::types::{TradePosition, TradingState};
+use crate::CandleItem;
+use rust_quant_domain::entities::FundingRate;
+
+/// 默认资金费结算周期：8 小时
+pub const DEFAULT_FUNDING_INTERVAL_MS: i64 = 8 * 60 * 60 * 1000;
+
+/// 单条资金费率
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FundingRatePoint {
+    /// 资金费时间 (Unix时间戳, 毫秒)
+    pub ts: i64,
+    /// 资金费率
+    pub rate: f64,
+}
+
+/// 回测使用的资金费率序列
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundingSeries {
+    /// 按时间升序排列的费率
+    points: Vec<FundingRatePoint>,
+    /// 结算周期（毫秒），结算时刻按 Unix 纪元对齐
+    interval_ms: i64,
+}
+
+impl FundingSeries {
+    /// 按默认 8 小时周期构建
+    pub fn new(points: Vec<FundingRatePoint>) -> Self {
+        Self::with_interval(points, DEFAULT_FUNDING_INTERVAL_MS)
+    }
+
+    /// 按交易所自身的结算周期构建（如 Hyperliquid 为 1 小时）
+    pub fn with_interval(mut points: Vec<FundingRatePoint>, interval_ms: i64) -> Self {
+        points.retain(|point| point.rate.is_finite());
+        points.sort_by_key(|point| point.ts);
+        Self {
+            points,
+            interval_ms: interval_ms.max(1),
+        }
+    }
+
+    /// 从已落库的资金费率历史构建
+    pub fn from_funding_rates(rates: &[FundingRate], interval_ms: i64) -> Self {
+        let points = rates
+            .iter()
+            .map(|rate| FundingRatePoint {
+                ts: rate.funding_time,
+                rate: rate.funding_rate,
+            })
+            .collect();
+        Self::with_interval(points, interval_ms)
+    }
+
+    pub fn interval_ms(&self) -> i64 {
+        self.interval_ms
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// 结算时刻生效的费率；早于第一条记录时不计提
+    pub fn rate_at(&self, ts: i64) -> Option<f64> {
+        let index = self.points.partition_point(|point| point.ts <= ts);
+        index.checked_sub(1).map(|i| self.points[i].rate)
+    }
+
+    /// 区间 `(from_ts, to_ts]` 内的全部结算时刻
+    pub fn settlements_between(&self, from_ts: i64, to_ts: i64) -> Vec<i64> {
+        if to_ts <= from_ts {
+            return Vec::new();
+        }
+        let first = (from_ts.div_euclid(self.interval_ms) + 1) * self.interval_ms;
+        (0..)
+            .map(|step| first + step * self.interval_ms)
+            .take_while(|ts| *ts <= to_ts)
+            .collect()
+    }
+}
+
+/// 单个仓位在一次结算中的资金费，正数表示支付
+fn position_funding_fee(position: &TradePosition, mark_price: f64, rate: f64) -> f64 {
+    let notional = position.position_nums * mark_price;
+    match position.trade_side {
+        TradeSide::Long => notional * rate,
+        TradeSide::Short => -notional * rate,
+    }
+}
+
+/// 对进入当前 K 线前已持有的仓位结算 `(previous_ts, candle.ts]` 内的资金费。
+///
+/// 结算价取当前 K 线开盘价；资金费即时计入资金，并累积到仓位上，
+/// 在下一条出场记录中单独列出。
+pub fn accrue_funding(
+    state: &mut TradingState,
+    series: &FundingSeries,
+    previous_ts: i64,
+    candle: &CandleItem,
+) {
+    if state.trade_position.is_none() || series.is_empty() {
+        return;
+    }
+    for settlement_ts in series.settlements_between(previous_ts, candle.ts) {
+        let Some(rate) = series.rate_at(settlement_ts) else {
+            continue;
+        };
+        let mut total_fee = 0.0;
+        let positions = state
+            .trade_position
+            .iter_mut()
+            .chain(state.scale_in_lots.iter_mut());
+        for position in positions {
+            let fee = position_funding_fee(position, candle.o, rate);
+            position.funding_fee += fee;
+            total_fee += fee;
+        }
+        state.funds -= total_fee;
+        state.total_profit_loss -= total_fee;
+        state.total_funding_fee += total_fee;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: i64 = 60 * 60 * 1000;
+
+    fn candle(ts: i64, open: f64) -> CandleItem {
+        CandleItem {
+            ts,
+            o: open,
+            h: open + 1.0,
+            l: open - 1.0,
+            c: open,
+            v: 1.0,
+            confirm: 1,
+        }
+    }
+
+    fn state_with(side: TradeSide, quantity: f64) -> TradingState {
+        TradingState {
+            funds: 100.0,
+            trade_position: Some(TradePosition {
+                position_nums: quantity,
+                open_price: 100.0,
+                trade_side: side,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn settlements_align_to_interval_boundaries() {
+        let series = FundingSeries::new(vec![]);
+        assert_eq!(
+            series.settlements_between(HOUR_MS, 8 * HOUR_MS),
+            vec![8 * HOUR_MS]
+        );
+        assert!(series
+            .settlements_between(8 * HOUR_MS, 9 * HOUR_MS)
+            .is_empty());
+        assert_eq!(
+            series.settlements_between(7 * HOUR_MS, 24 * HOUR_MS),
+            vec![8 * HOUR_MS, 16 * HOUR_MS, 24 * HOUR_MS]
+        );
+    }
+
+    #[test]
+    fn long_pays_and_short_receives_positive_funding() {
+        let series = FundingSeries::new(vec![FundingRatePoint {
+            ts: 0,
+            rate: 0.0001,
+        }]);
+
+        let mut long = state_with(TradeSide::Long, 2.0);
+        accrue_funding(&mut long, &series, 7 * HOUR_MS, &candle(8 * HOUR_MS, 100.0));
+        assert!((long.funds - 99.98).abs() < 1e-9);
+        assert!((long.total_funding_fee - 0.02).abs() < 1e-9);
+        assert!((long.trade_position.unwrap().funding_fee - 0.02).abs() < 1e-9);
+
+        let mut short = state_with(TradeSide::Short, 2.0);
+        accrue_funding(
+            &mut short,
+            &series,
+            7 * HOUR_MS,
+            &candle(8 * HOUR_MS, 100.0),
+        );
+        assert!((short.funds - 100.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rate_uses_latest_point_and_exchange_interval() {
+        let series = FundingSeries::with_interval(
+            vec![
+                FundingRatePoint {
+                    ts: 2 * HOUR_MS,
+                    rate: -0.0002,
+                },
+                FundingRatePoint {
+                    ts: HOUR_MS,
+                    rate: 0.0001,
+                },
+            ],
+            HOUR_MS,
+        );
+        assert_eq!(series.rate_at(0), None);
+        assert_eq!(series.rate_at(HOUR_MS), Some(0.0001));
+        assert_eq!(series.rate_at(3 * HOUR_MS), Some(-0.0002));
+
+        let mut state = state_with(TradeSide::Long, 1.0);
+        accrue_funding(&mut state, &series, 0, &candle(3 * HOUR_MS, 100.0));
+        // 1h: 0.01 支付，2h、3h 各收取 0.02
+        assert!((state.total_funding_fee + 0.03).abs() < 1e-9);
+    }
+
+    #[test]
+    fn exit_record_carries_accrued_funding() {
+        let series = FundingSeries::new(vec![FundingRatePoint {
+            ts: 0,
+            rate: 0.0001,
+        }]);
+        let mut state = state_with(TradeSide::Long, 2.0);
+        accrue_funding(&mut state, &series, 0, &candle(16 * HOUR_MS, 100.0));
+
+        let exit = candle(17 * HOUR_MS, 100.0);
+        let signal = crate::framework::backtest::types::SignalResult {
+            open_price: 100.0,
+            ts: exit.ts,
+            ..Default::default()
+        };
+        crate::framework::backtest::position::close_position(
+            &mut state, &exit, &signal, "test", 0.0,
+        );
+
+        let record = state
+            .trade_records
+            .iter()
+            .find(|record| record.full_close)
+            .expect("close record");
+        assert!((record.funding_fee.unwrap() - 0.04).abs() < 1e-9);
+        assert!((state.total_funding_fee - 0.04).abs() < 1e-9);
+    }
+}
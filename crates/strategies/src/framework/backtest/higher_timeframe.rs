@@ -0,0 +1,250 @@
+//! 因果对齐的高周期上下文
+//!
+//! 回测按主周期逐根推进，高周期 K 线由主周期 K 线经 `rust_quant_market` 的分桶逻辑聚合，
+//! 与实盘 `ConfirmedCandleAggregator` 共用同一套收盘判定：只有桶内主周期 K 线连续且完整、
+//! 并在桶内最后一根主周期 K 线收盘时才产出高周期 K 线。因此策略在某根主周期 K 线上
+//! 只能看到截至该根收盘已经收盘的高周期 K 线及其指标，不会引入未来数据。
+use super::adapter::IndicatorStrategyBacktest;
+use crate::CandleItem;
+use rust_quant_domain::Timeframe;
+use rust_quant_market::streams::{update_candle_bucket, CandleBucketSpec, PartialCandleBucket};
+use tracing::warn;
+/// 回测声明的主周期与高周期
+#[derive(Debug, Clone, PartialEq)]
+pub struct HigherTimeframes {
+    /// 回测输入K线的周期
+    pub primary: Timeframe,
+    /// 需要聚合的高周期；必须是主周期的整数倍，周线/月线不按 UTC 纪元对齐，不支持
+    pub higher: Vec<Timeframe>,
+}
+impl HigherTimeframes {
+    pub fn new(primary: Timeframe, higher: Vec<Timeframe>) -> Self {
+        Self { primary, higher }
+    }
+}
+/// 某个高周期在当前主周期K线收盘时的只读视图
+#[derive(Debug)]
+pub struct HigherTimeframeView<'a, V> {
+    /// 高周期
+    pub timeframe: Timeframe,
+    /// 已收盘的高周期K线，按时间升序，最多保留策略的 `min_data_length` 根
+    pub candles: &'a [CandleItem],
+    /// 最近一根已收盘高周期K线对应的指标值；尚无收盘K线时为空
+    pub values: Option<&'a V>,
+}
+/// 单个高周期的聚合与指标状态
+pub(crate) struct HigherTimeframeState<S: IndicatorStrategyBacktest> {
+    timeframe: Timeframe,
+    spec: CandleBucketSpec,
+    partial: Option<PartialCandleBucket<CandleItem>>,
+    indicator_combine: S::IndicatorCombine,
+    candles: Vec<CandleItem>,
+    values: Option<S::IndicatorValues>,
+}
+impl<S: IndicatorStrategyBacktest> HigherTimeframeState<S> {
+    /// 按策略声明创建各高周期状态，跳过无法从主周期聚合的周期
+    pub(crate) fn from_strategy(strategy: &S) -> Vec<Self> {
+        let Some(declared) = strategy.higher_timeframes() else {
+            return Vec::new();
+        };
+        let base_ms = timeframe_ms(declared.primary);
+        declared
+            .higher
+            .into_iter()
+            .filter_map(|timeframe| {
+                let spec = (!matches!(timeframe, Timeframe::W1 | Timeframe::MN1))
+                    .then(|| CandleBucketSpec::new(base_ms, timeframe_ms(timeframe)))
+                    .flatten();
+                if spec.is_none() {
+                    warn!(
+                        "高周期 {} 无法由主周期 {} 聚合，已忽略",
+                        timeframe.as_str(),
+                        declared.primary.as_str()
+                    );
+                }
+                spec.map(|spec| Self {
+                    timeframe,
+                    spec,
+                    partial: None,
+                    indicator_combine: strategy.init_indicator_combine(),
+                    candles: Vec::new(),
+                    values: None,
+                })
+            })
+            .collect()
+    }
+    /// 推进一根已收盘的主周期K线；高周期桶完整收盘时更新K线与指标
+    pub(crate) fn push(&mut self, candle: &CandleItem, max_candles: usize) {
+        let Some(closed) = update_candle_bucket(&mut self.partial, self.spec, candle) else {
+            return;
+        };
+        self.values = Some(S::build_indicator_values(
+            &mut self.indicator_combine,
+            &closed,
+        ));
+        self.candles.push(closed);
+        if self.candles.len() > max_candles.max(1) * 2 {
+            let remove_count = self.candles.len() - max_candles.max(1);
+            self.candles.drain(0..remove_count);
+        }
+    }
+    pub(crate) fn view(&self, max_candles: usize) -> HigherTimeframeView<'_, S::IndicatorValues> {
+        let start = self.candles.len().saturating_sub(max_candles.max(1));
+        HigherTimeframeView {
+            timeframe: self.timeframe,
+            candles: &self.candles[start..],
+            values: self.values.as_ref(),
+        }
+    }
+}
+fn timeframe_ms(timeframe: Timeframe) -> i64 {
+    timeframe.to_minutes() * 60_000
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::backtest::types::{BasicRiskStrategyConfig, SignalResult};
+    const FIFTEEN_MINUTES_MS: i64 = 15 * 60_000;
+    /// 以收盘价作为指标值，便于核对高周期指标对应的K线
+    struct CloseEcho;
+    impl IndicatorStrategyBacktest for CloseEcho {
+        type IndicatorCombine = ();
+        type IndicatorValues = f64;
+        fn min_data_length(&self) -> usize {
+            3
+        }
+        fn init_indicator_combine(&self) -> Self::IndicatorCombine {}
+        fn build_indicator_values(_: &mut Self::IndicatorCombine, candle: &CandleItem) -> f64 {
+            candle.c
+        }
+        fn generate_signal(
+            &mut self,
+            candles: &[CandleItem],
+            _: &mut f64,
+            _: &BasicRiskStrategyConfig,
+        ) -> SignalResult {
+            SignalResult {
+                ts: candles.last().unwrap().ts,
+                ..SignalResult::default()
+            }
+        }
+        fn higher_timeframes(&self) -> Option<HigherTimeframes> {
+            Some(HigherTimeframes::new(
+                Timeframe::M15,
+                vec![Timeframe::H1, Timeframe::M15, Timeframe::W1],
+            ))
+        }
+    }
+    fn quarter(index: i64) -> CandleItem {
+        CandleItem {
+            o: index as f64,
+            h: index as f64 + 0.5,
+            l: index as f64 - 0.5,
+            c: index as f64,
+            v: 1.0,
+            ts: index * FIFTEEN_MINUTES_MS,
+            confirm: 1,
+        }
+    }
+    #[test]
+    fn higher_timeframe_closes_only_on_last_primary_bar_of_bucket() {
+        let mut states = HigherTimeframeState::from_strategy(&CloseEcho);
+        assert_eq!(states.len(), 1, "周期不大于主周期或为周线时应被忽略");
+        let state = &mut states[0];
+        for index in 0..3 {
+            state.push(&quarter(index), 3);
+            assert!(state.view(3).candles.is_empty());
+            assert!(state.view(3).values.is_none());
+        }
+        state.push(&quarter(3), 3);
+        let view = state.view(3);
+        assert_eq!(view.timeframe, Timeframe::H1);
+        assert_eq!(view.candles.len(), 1);
+        assert_eq!(view.candles[0].ts, 0);
+        assert_eq!(view.candles[0].c, 3.0);
+        assert_eq!(view.values, Some(&3.0));
+        for index in 4..7 {
+            state.push(&quarter(index), 3);
+            assert_eq!(state.view(3).values, Some(&3.0), "未收盘的高周期不可见");
+        }
+    }
+    #[test]
+    fn higher_timeframe_window_is_bounded() {
+        let mut states = HigherTimeframeState::from_strategy(&CloseEcho);
+        let state = &mut states[0];
+        for index in 0..40 {
+            state.push(&quarter(index), 3);
+        }
+        let view = state.view(3);
+        assert_eq!(view.candles.len(), 3);
+        assert_eq!(view.candles[2].ts, 36 * FIFTEEN_MINUTES_MS);
+        assert!(state.candles.len() <= 6);
+    }
+    #[test]
+    fn backtest_passes_only_closed_higher_timeframe_candles_to_strategy() {
+        use crate::framework::backtest::adapter::run_indicator_strategy_backtest;
+        /// 每根主周期K线校验可见的高周期K线均已收盘
+        struct CausalCheck {
+            checked: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        }
+        impl IndicatorStrategyBacktest for CausalCheck {
+            type IndicatorCombine = ();
+            type IndicatorValues = f64;
+            fn min_data_length(&self) -> usize {
+                3
+            }
+            fn init_indicator_combine(&self) -> Self::IndicatorCombine {}
+            fn build_indicator_values(_: &mut Self::IndicatorCombine, candle: &CandleItem) -> f64 {
+                candle.c
+            }
+            fn generate_signal(
+                &mut self,
+                _: &[CandleItem],
+                _: &mut f64,
+                _: &BasicRiskStrategyConfig,
+            ) -> SignalResult {
+                unreachable!("声明高周期后应走带上下文的入口")
+            }
+            fn higher_timeframes(&self) -> Option<HigherTimeframes> {
+                Some(HigherTimeframes::new(Timeframe::M15, vec![Timeframe::H4]))
+            }
+            fn generate_signal_with_higher_timeframes(
+                &mut self,
+                candles: &[CandleItem],
+                _: &mut f64,
+                _: &BasicRiskStrategyConfig,
+                higher: &[HigherTimeframeView<'_, f64>],
+            ) -> SignalResult {
+                let current = candles.last().unwrap();
+                let current_close = current.ts + FIFTEEN_MINUTES_MS;
+                let h4_ms = 16 * FIFTEEN_MINUTES_MS;
+                for candle in higher[0].candles {
+                    assert!(candle.ts + h4_ms <= current_close, "高周期K线尚未收盘");
+                }
+                if let Some(last) = higher[0].candles.last() {
+                    assert_eq!(higher[0].values, Some(&last.c));
+                    // 最近一根已收盘的4H必须是当前K线收盘时刻之前的最后一个完整桶
+                    assert_eq!(last.ts, (current_close / h4_ms - 1) * h4_ms);
+                }
+                self.checked
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                SignalResult {
+                    ts: current.ts,
+                    open_price: current.c,
+                    ..SignalResult::default()
+                }
+            }
+        }
+        let checked = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let candles: Vec<CandleItem> = (0..600).map(quarter).collect();
+        run_indicator_strategy_backtest(
+            "TEST",
+            CausalCheck {
+                checked: checked.clone(),
+            },
+            &candles,
+            BasicRiskStrategyConfig::default(),
+        );
+        assert_eq!(checked.load(std::sync::atomic::Ordering::Relaxed), 598);
+    }
+}
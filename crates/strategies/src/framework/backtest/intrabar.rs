@@ -0,0 +1,262 @@
+//! 盘中成交顺序解析
+//!
+//! 高周期 K 线同时触达止损与止盈时，OHLC 无法判断先后，默认口径是止损优先。
+//! 开启解析后，对这类 K 线用预先加载的低周期 K 线（1m/5m）逐根回放风控检查，
+//! 还原止损、止盈、移动止损的真实触发顺序，并统计结果与默认口径不一致的次数。
+use super::risk::{check_risk_config, is_exit_order_ambiguous};
+use super::types::{BasicRiskStrategyConfig, SignalResult, TradingState};
+use crate::CandleItem;
+use serde::{Deserialize, Serialize};
+
+/// 高周期回测使用的低周期 K 线
+#[derive(Debug, Clone, Default)]
+pub struct IntrabarCandles {
+    /// 按时间升序排列的低周期 K 线
+    candles: Vec<CandleItem>,
+    /// 高周期单根 K 线覆盖的时长（毫秒）
+    bar_duration_ms: i64,
+}
+
+impl IntrabarCandles {
+    pub fn new(mut candles: Vec<CandleItem>, bar_duration_ms: i64) -> Self {
+        candles.sort_by_key(|candle| candle.ts);
+        candles.dedup_by_key(|candle| candle.ts);
+        Self {
+            candles,
+            bar_duration_ms: bar_duration_ms.max(1),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candles.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.candles.len()
+    }
+
+    /// 落在高周期 K 线 `[bar_ts, bar_ts + bar_duration_ms)` 内的低周期 K 线
+    pub fn bar_candles(&self, bar_ts: i64) -> &[CandleItem] {
+        let end_ts = bar_ts + self.bar_duration_ms;
+        let start = self.candles.partition_point(|candle| candle.ts < bar_ts);
+        let end = self.candles.partition_point(|candle| candle.ts < end_ts);
+        &self.candles[start..end]
+    }
+}
+
+/// 盘中解析统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntrabarReport {
+    /// 同时触达止损与止盈的 K 线数
+    pub ambiguous_bars: usize,
+    /// 用低周期 K 线完成回放的 K 线数
+    pub resolved_bars: usize,
+    /// 缺少低周期数据、退回默认口径的 K 线数
+    pub missing_data_bars: usize,
+    /// 回放结果与默认口径不一致的交易数
+    pub changed_outcomes: usize,
+    /// 结果发生变化的高周期 K 线时间戳
+    pub changed_bar_ts: Vec<i64>,
+}
+
+/// 一次风控检查的结果摘要，用于比较两种口径
+#[derive(Debug, PartialEq)]
+struct ExitOutcome {
+    close_types: Vec<String>,
+    remaining_quantity: Option<f64>,
+    wins: i64,
+    losses: i64,
+}
+
+impl ExitOutcome {
+    fn capture(state: &TradingState, records_before: usize) -> Self {
+        Self {
+            close_types: state
+                .trade_records
+                .iter()
+                .skip(records_before)
+                .map(|record| record.close_type.clone())
+                .collect(),
+            remaining_quantity: state
+                .trade_position
+                .as_ref()
+                .map(|position| position.position_nums),
+            wins: state.wins,
+            losses: state.losses,
+        }
+    }
+}
+
+/// 带盘中解析的风控检查；非歧义 K 线与默认口径完全一致。
+pub fn check_risk_config_intrabar(
+    risk_config: &BasicRiskStrategyConfig,
+    trading_state: TradingState,
+    signal: &SignalResult,
+    candle: &CandleItem,
+    intrabar: &IntrabarCandles,
+    report: &mut IntrabarReport,
+) -> TradingState {
+    let ambiguous = trading_state
+        .trade_position
+        .as_ref()
+        .is_some_and(|position| is_exit_order_ambiguous(risk_config, position, candle));
+    if !ambiguous {
+        return check_risk_config(risk_config, trading_state, signal, candle);
+    }
+    report.ambiguous_bars += 1;
+    let sub_candles = intrabar.bar_candles(candle.ts);
+    if sub_candles.is_empty() {
+        report.missing_data_bars += 1;
+        return check_risk_config(risk_config, trading_state, signal, candle);
+    }
+    report.resolved_bars += 1;
+
+    let records_before = trading_state.trade_records.len();
+    let heuristic = check_risk_config(risk_config, trading_state.clone(), signal, candle);
+    let heuristic_outcome = ExitOutcome::capture(&heuristic, records_before);
+
+    let mut state = trading_state;
+    for sub_candle in sub_candles {
+        if state.trade_position.is_none() {
+            break;
+        }
+        state = check_risk_config(risk_config, state, signal, sub_candle);
+    }
+    if ExitOutcome::capture(&state, records_before) != heuristic_outcome {
+        report.changed_outcomes += 1;
+        report.changed_bar_ts.push(candle.ts);
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::backtest::types::TradePosition;
+    use crate::framework::types::TradeSide;
+
+    const MINUTE_MS: i64 = 60 * 1000;
+    const BAR_MS: i64 = 240 * MINUTE_MS;
+
+    fn candle(ts: i64, o: f64, h: f64, l: f64, c: f64) -> CandleItem {
+        CandleItem {
+            ts,
+            o,
+            h,
+            l,
+            c,
+            v: 1.0,
+            confirm: 1,
+        }
+    }
+
+    /// 多头持仓：止损 95，固定止盈 110。
+    fn long_state() -> TradingState {
+        TradingState {
+            trade_position: Some(TradePosition {
+                position_nums: 1.0,
+                open_price: 100.0,
+                trade_side: TradeSide::Long,
+                signal_kline_stop_close_price: Some(95.0),
+                fixed_take_profit_price: Some(110.0),
+                open_position_time: "2024-01-01 00:00:00".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn risk() -> BasicRiskStrategyConfig {
+        BasicRiskStrategyConfig {
+            max_loss_percent: 0.5,
+            dynamic_max_loss: Some(false),
+            is_used_signal_k_line_stop_loss: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn signal(ts: i64) -> SignalResult {
+        SignalResult {
+            ts,
+            open_price: 100.0,
+            ..Default::default()
+        }
+    }
+
+    fn close_types(state: &TradingState) -> Vec<&str> {
+        state
+            .trade_records
+            .iter()
+            .filter(|record| record.full_close)
+            .map(|record| record.close_type.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn bar_candles_select_half_open_window() {
+        let intrabar = IntrabarCandles::new(
+            vec![
+                candle(BAR_MS, 1.0, 1.0, 1.0, 1.0),
+                candle(0, 1.0, 1.0, 1.0, 1.0),
+                candle(BAR_MS - MINUTE_MS, 1.0, 1.0, 1.0, 1.0),
+            ],
+            BAR_MS,
+        );
+        let window = intrabar.bar_candles(0);
+        assert_eq!(window.len(), 2);
+        assert_eq!(window[0].ts, 0);
+        assert_eq!(window[1].ts, BAR_MS - MINUTE_MS);
+    }
+
+    #[test]
+    fn replay_takes_profit_when_target_is_hit_before_stop() {
+        let bar = candle(0, 100.0, 111.0, 94.0, 100.0);
+        let intrabar = IntrabarCandles::new(
+            vec![
+                candle(0, 100.0, 111.0, 99.0, 109.0),
+                candle(MINUTE_MS, 109.0, 109.0, 94.0, 95.0),
+            ],
+            BAR_MS,
+        );
+        let mut report = IntrabarReport::default();
+
+        let heuristic = check_risk_config(&risk(), long_state(), &signal(0), &bar);
+        let resolved = check_risk_config_intrabar(
+            &risk(),
+            long_state(),
+            &signal(0),
+            &bar,
+            &intrabar,
+            &mut report,
+        );
+
+        assert!(heuristic.trade_position.is_none());
+        assert!(resolved.trade_position.is_none());
+        assert_ne!(close_types(&heuristic), close_types(&resolved));
+        assert!(close_types(&resolved)[0].contains("止盈"));
+        assert_eq!(report.ambiguous_bars, 1);
+        assert_eq!(report.resolved_bars, 1);
+        assert_eq!(report.changed_outcomes, 1);
+        assert_eq!(report.changed_bar_ts, vec![0]);
+    }
+
+    #[test]
+    fn missing_lower_timeframe_data_falls_back_to_heuristic() {
+        let bar = candle(0, 100.0, 111.0, 94.0, 100.0);
+        let mut report = IntrabarReport::default();
+
+        let resolved = check_risk_config_intrabar(
+            &risk(),
+            long_state(),
+            &signal(0),
+            &bar,
+            &IntrabarCandles::default(),
+            &mut report,
+        );
+
+        assert!(resolved.trade_position.is_none());
+        assert_eq!(report.ambiguous_bars, 1);
+        assert_eq!(report.missing_data_bars, 1);
+        assert_eq!(report.changed_outcomes, 0);
+    }
+}
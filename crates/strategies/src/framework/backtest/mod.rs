@@ -1,10 +1,18 @@
 pub mod adapter;
 pub mod conversions;
+pub mod cost_model;
+pub mod economic_calendar;
 pub mod engine;
+pub mod entry_order;
 pub mod fibonacci;
+pub mod funding;
+pub mod higher_timeframe;
 pub mod indicators;
+pub mod intrabar;
 pub mod pipeline;
+pub mod portfolio;
 pub mod position;
+pub mod pyramiding;
 pub mod r_system;
 pub mod recording;
 pub mod risk;
@@ -17,11 +25,29 @@ pub mod utils;
 pub use crate::framework::risk::{StopLossCalculator, StopLossSide};
 pub use adapter::{run_indicator_strategy_backtest, IndicatorStrategyBacktest};
 pub use conversions::{convert_domain_signal, to_domain_basic_risk_config};
+pub use cost_model::{
+    AtrSlippage, BarDelayFill, CostModel, CostModelConfig, ExitOrderType, FixedBpsSlippage,
+    Liquidity, MakerTakerFees, SlippageModel, VolumeParticipationImpact,
+};
+pub use economic_calendar::{
+    BlackoutWindow, EconomicBlackout, EconomicBlackoutConfig, EconomicEventWindow,
+    ECONOMIC_EVENT_WINDOW_FILTER_REASON,
+};
 pub use engine::run_back_test;
+pub use entry_order::{EntryOrder, EntryOrderStats, EntryOrderType, EntryTimeInForce};
+pub use funding::{FundingRatePoint, FundingSeries, DEFAULT_FUNDING_INTERVAL_MS};
+pub use higher_timeframe::{HigherTimeframeView, HigherTimeframes};
 pub use indicators::{calculate_ema, get_multi_indicator_values};
+pub use intrabar::{check_risk_config_intrabar, IntrabarCandles, IntrabarReport};
+pub use portfolio::{
+    run_portfolio_back_test, PortfolioBacktestConfig, PortfolioBacktestResult,
+    PortfolioEquityPoint, PortfolioSymbolAttribution, PortfolioSymbolInput,
+};
 pub use position::{
-    close_position, finalize_trading_state, open_long_position, open_short_position,
+    close_position, close_position_with_order, finalize_trading_state, open_long_position,
+    open_short_position,
 };
+pub use pyramiding::try_scale_in;
 pub use r_system::{
     check_tiered_take_profit, check_time_stop, create_r_state_from_position,
     update_position_stop_from_r_state, update_r_system_trailing_stop, RSystemConfig, RSystemState,
@@ -31,13 +57,13 @@ pub use r_system::{
 pub use recording::{record_trade_entry, record_trade_exit};
 pub use risk::{
     check_risk_config, check_risk_config_with_r_system, compute_current_targets,
-    init_r_system_state, ExitTargets, RSystemRiskConfig, RSystemRuntime,
+    init_r_system_state, is_exit_order_ambiguous, ExitTargets, RSystemRiskConfig, RSystemRuntime,
 };
-pub use signal::deal_signal;
+pub use signal::{deal_signal, deal_signal_with_intrabar};
 pub use trait_impl::BackTestAbleStrategyTrait;
 pub use types::{
-    BackTestResult, BasicRiskStrategyConfig, MoveStopLoss, SignalResult, TradePosition,
-    TradeRecord, TradingState,
+    BackTestResult, BasicRiskStrategyConfig, LotCloseOrder, MoveStopLoss, PyramidingConfig,
+    ScaleInRule, SignalResult, TradePosition, TradeRecord, TradingState,
 };
 pub use utils::{
     calculate_profit_loss, calculate_win_rate, parse_candle_to_data_item, parse_price,
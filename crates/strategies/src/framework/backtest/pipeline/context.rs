@@ -1,6 +1,7 @@
 //! Pipeline上下文定义
 //!
 //! 集中管理回测过程中的所有状态，避免状态在函数间隐式传递
+use crate::framework::backtest::intrabar::IntrabarReport;
 use crate::framework::backtest::shadow_trading::ShadowTradeManager;
 use crate::framework::backtest::types::{
     BasicRiskStrategyConfig, SignalResult, TradePosition, TradingState,
@@ -47,6 +48,8 @@ pub struct BacktestContext {
     pub shadow_manager: ShadowTradeManager,
     /// 审计链路（信号/风控/订单/持仓）
     pub audit_trail: AuditTrail,
+    /// 盘中成交顺序解析统计（PositionStage 开启解析时产出）
+    pub intrabar_report: Option<IntrabarReport>,
     // ========================================================================
     // 控制标志
     // ========================================================================
@@ -56,6 +59,8 @@ pub struct BacktestContext {
     pub closed_position: bool,
     /// 平仓原因
     pub close_reason: Option<String>,
+    /// 是否允许新开仓（组合回测按资金池容量逐K线设置；单标的回测恒为 true）
+    pub entry_allowed: bool,
 }
 impl BacktestContext {
     /// 创建新的上下文
@@ -82,9 +87,11 @@ impl BacktestContext {
             current_position,
             shadow_manager: ShadowTradeManager::new(),
             audit_trail: AuditTrail::new(run_id),
+            intrabar_report: None,
             opened_position: false,
             closed_position: false,
             close_reason: None,
+            entry_allowed: true,
         }
     }
     /// 重置单K线相关状态（用于下一根K线）
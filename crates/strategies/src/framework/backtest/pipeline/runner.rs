@@ -1,6 +1,7 @@
 //! Pipeline执行器
 use super::context::BacktestContext;
 use super::stage::{BacktestStage, StageResult};
+use crate::framework::backtest::funding::{accrue_funding, FundingSeries};
 use crate::framework::backtest::position::finalize_trading_state;
 use crate::framework::backtest::types::{
     BackTestResult, BasicRiskStrategyConfig, DynamicConfigLog, TradingState,
@@ -13,10 +14,20 @@ use crate::CandleItem;
 pub struct PipelineRunner {
     /// 列表数据。
     stages: Vec<Box<dyn BacktestStage>>,
+    /// 永续合约资金费率序列；为空时不计提资金费。
+    funding: Option<FundingSeries>,
 }
 impl PipelineRunner {
     pub fn new() -> Self {
-        Self { stages: Vec::new() }
+        Self {
+            stages: Vec::new(),
+            funding: None,
+        }
+    }
+    /// 设置资金费率序列
+    pub fn with_funding(mut self, funding: Option<FundingSeries>) -> Self {
+        self.funding = funding;
+        self
     }
     /// 添加Stage
     pub fn add_stage<S: BacktestStage + 'static>(mut self, stage: S) -> Self {
@@ -59,41 +70,84 @@ impl PipelineRunner {
         }
         // BacktestContext 是整次回测的唯一可变状态容器；资金、持仓、过滤信号和审计轨迹
         // 都从这里推进，避免多个 stage 各自维护副本导致收盘结算不一致。
-        let mut ctx = BacktestContext::new(
+        let mut ctx = Self::new_context(candles, inst_id, risk_config, TradingState::default());
+        let mut dynamic_config_logs: Vec<DynamicConfigLog> = Vec::new();
+        let collect_dynamic_config_logs = Self::collect_dynamic_config_logs();
+        // 按原始 K 线顺序逐根推进，保证信号、过滤和成交记录共享同一个时间轴；
+        // reset_for_next_candle 只切换当前 candle，不清空跨 candle 的持仓状态。
+        for i in 0..candles.len() {
+            self.advance(
+                &mut ctx,
+                candles,
+                i,
+                collect_dynamic_config_logs.then_some(&mut dynamic_config_logs),
+            );
+        }
+        Self::finish(ctx, candles, dynamic_config_logs)
+    }
+    /// 以首根K线创建回测上下文
+    pub fn new_context(
+        candles: &[CandleItem],
+        inst_id: &str,
+        risk_config: BasicRiskStrategyConfig,
+        trading_state: TradingState,
+    ) -> BacktestContext {
+        BacktestContext::new(
             candles[0].clone(),
             0,
             inst_id.to_string(),
             risk_config,
-            TradingState::default(),
-        );
-        let collect_dynamic_config_logs =
-            !rust_quant_core::config::env_is_true("BACKTEST_FAST_MODE", false)
-                && !rust_quant_core::config::random_backtest_is_enabled();
-        let mut dynamic_config_logs: Vec<DynamicConfigLog> = Vec::new();
-        // 按原始 K 线顺序逐根推进，保证信号、过滤和成交记录共享同一个时间轴；
-        // reset_for_next_candle 只切换当前 candle，不清空跨 candle 的持仓状态。
-        for (i, candle) in candles.iter().enumerate() {
-            if i > 0 {
-                ctx.reset_for_next_candle(candle.clone(), i);
+            trading_state,
+        )
+    }
+    /// 是否逐K线收集动态参数调整日志（快速迭代/随机筛选模式关闭）
+    pub fn collect_dynamic_config_logs() -> bool {
+        !rust_quant_core::config::env_is_true("BACKTEST_FAST_MODE", false)
+            && !rust_quant_core::config::random_backtest_is_enabled()
+    }
+    /// 推进第 `i` 根K线：结算资金费、切换当前K线并执行各阶段
+    ///
+    /// 多标的组合回测按对齐后的时间轴逐标的调用，单标的回测由 `run` 顺序调用。
+    pub fn advance(
+        &mut self,
+        ctx: &mut BacktestContext,
+        candles: &[CandleItem],
+        i: usize,
+        dynamic_config_logs: Option<&mut Vec<DynamicConfigLog>>,
+    ) -> StageResult {
+        let candle = &candles[i];
+        if i > 0 {
+            // 资金费在阶段处理前结算，只作用于上一根 K 线结束时仍持有的仓位。
+            if let Some(funding) = &self.funding {
+                accrue_funding(&mut ctx.trading_state, funding, candles[i - 1].ts, candle);
             }
-            let _result = self.process_candle(&mut ctx);
-            // 动态参数调整属于解释性证据，不参与成交计算，但需要随 candle 保存，
-            // 否则回放某笔交易时无法判断当时使用的是哪一份策略配置；快速迭代模式
-            // 只关闭这类逐 K 线诊断产物，不改变信号与成交状态推进。
-            if collect_dynamic_config_logs {
-                if let Some(signal) = &ctx.signal {
-                    if signal.dynamic_config_snapshot.is_some()
-                        || !signal.dynamic_adjustments.is_empty()
-                    {
-                        dynamic_config_logs.push(DynamicConfigLog {
-                            ts: ctx.candle.ts,
-                            adjustments: signal.dynamic_adjustments.clone(),
-                            config_snapshot: signal.dynamic_config_snapshot.clone(),
-                        });
-                    }
+            ctx.reset_for_next_candle(candle.clone(), i);
+        }
+        let result = self.process_candle(ctx);
+        // 动态参数调整属于解释性证据，不参与成交计算，但需要随 candle 保存，
+        // 否则回放某笔交易时无法判断当时使用的是哪一份策略配置；快速迭代模式
+        // 只关闭这类逐 K 线诊断产物，不改变信号与成交状态推进。
+        if let Some(dynamic_config_logs) = dynamic_config_logs {
+            if let Some(signal) = &ctx.signal {
+                if signal.dynamic_config_snapshot.is_some()
+                    || !signal.dynamic_adjustments.is_empty()
+                {
+                    dynamic_config_logs.push(DynamicConfigLog {
+                        ts: ctx.candle.ts,
+                        adjustments: signal.dynamic_adjustments.clone(),
+                        config_snapshot: signal.dynamic_config_snapshot.clone(),
+                    });
                 }
             }
         }
+        result
+    }
+    /// 收尾未平仓与 shadow 记录，拆开上下文生成回测结果
+    pub fn finish(
+        mut ctx: BacktestContext,
+        candles: &[CandleItem],
+        dynamic_config_logs: Vec<DynamicConfigLog>,
+    ) -> BackTestResult {
         // 最后一根 K 线后统一收尾未平仓和 shadow 记录，避免统计口径把仍在场内的仓位漏掉。
         if let Some(last_candle) = candles.last() {
             ctx.shadow_manager.finalize(last_candle);
@@ -105,6 +159,7 @@ impl PipelineRunner {
             trading_state,
             shadow_manager,
             audit_trail,
+            intrabar_report,
             ..
         } = ctx;
         BackTestResult {
@@ -115,6 +170,9 @@ impl PipelineRunner {
             filtered_signals: shadow_manager.into_filtered_signals(),
             dynamic_config_logs,
             audit_trail,
+            total_funding_fee: trading_state.total_funding_fee,
+            intrabar_report,
+            entry_order_stats: trading_state.entry_order_stats,
         }
     }
 }
@@ -1,11 +1,21 @@
 //! FilterStage - 信号过滤与Shadow Trading阶段
+use crate::framework::backtest::economic_calendar::{
+    EconomicBlackout, ECONOMIC_EVENT_WINDOW_FILTER_REASON,
+};
 use crate::framework::backtest::pipeline::{BacktestContext, BacktestStage, StageResult};
+use crate::framework::backtest::position::close_position;
+use crate::framework::backtest::types::SignalResult;
+use crate::framework::types::TradeSide;
+/// 经济事件窗口内强制平仓的平仓类型
+const ECONOMIC_EVENT_CLOSE_TYPE: &str = "经济事件平仓";
 /// 信号过滤阶段
 ///
 /// 处理被过滤的信号，创建Shadow Trade
 pub struct FilterStage {
     /// 随机筛选不消费过滤信号诊断，关闭后只省略 shadow 产物，不改变真实持仓路径。
     collect_shadow_trades: bool,
+    /// 经济事件停牌日历；为空时不做经济事件过滤。
+    economic_blackout: Option<EconomicBlackout>,
 }
 impl FilterStage {
     pub fn new() -> Self {
@@ -16,8 +26,76 @@ impl FilterStage {
     pub fn with_shadow_trading(collect_shadow_trades: bool) -> Self {
         Self {
             collect_shadow_trades,
+            economic_blackout: None,
         }
     }
+    /// 开启经济事件停牌：窗口内拦截开仓，配置平仓的窗口在K线开盘时以开盘价平掉持仓。
+    pub fn with_economic_blackout(mut self, economic_blackout: Option<EconomicBlackout>) -> Self {
+        self.economic_blackout = economic_blackout.filter(|blackout| !blackout.is_empty());
+        self
+    }
+    /// 平掉停牌窗口内的持仓，返回是否发生平仓
+    fn flatten_in_event_window(&self, ctx: &mut BacktestContext) -> bool {
+        let Some(blackout) = &self.economic_blackout else {
+            return false;
+        };
+        let Some(position) = ctx.trading_state.trade_position.as_ref() else {
+            return false;
+        };
+        let Some(window) = blackout.flatten_at(ctx.candle.ts) else {
+            return false;
+        };
+        let price = ctx.candle.o;
+        let profit = match position.trade_side {
+            TradeSide::Long => (price - position.open_price) * position.position_nums,
+            TradeSide::Short => (position.open_price - price) * position.position_nums,
+        };
+        let signal = SignalResult {
+            ts: ctx.candle.ts,
+            open_price: price,
+            should_sell: true,
+            single_value: Some(window.label.clone()),
+            single_result: Some(ECONOMIC_EVENT_CLOSE_TYPE.to_string()),
+            ..SignalResult::default()
+        };
+        close_position(
+            &mut ctx.trading_state,
+            &ctx.candle,
+            &signal,
+            ECONOMIC_EVENT_CLOSE_TYPE,
+            profit,
+        );
+        ctx.current_position = None;
+        ctx.closed_position = true;
+        ctx.close_reason = Some(ECONOMIC_EVENT_CLOSE_TYPE.to_string());
+        true
+    }
+    /// 停牌窗口内拦截开仓信号与挂单，返回被拦截的信号（保留原方向供影子交易使用）
+    fn block_entry_in_event_window(&self, ctx: &mut BacktestContext) -> Option<SignalResult> {
+        let blackout = self.economic_blackout.as_ref()?;
+        if ctx.trading_state.trade_position.is_some() {
+            return None;
+        }
+        blackout.entry_blocked_at(ctx.candle.ts)?;
+        ctx.trading_state.cancel_resting_entry();
+        ctx.trading_state.delayed_entry = None;
+        let signal = ctx.signal.as_mut()?;
+        if !(signal.should_buy || signal.should_sell) {
+            return None;
+        }
+        signal
+            .filter_reasons
+            .push(ECONOMIC_EVENT_WINDOW_FILTER_REASON.to_string());
+        let blocked = signal.clone();
+        signal.should_buy = false;
+        signal.should_sell = false;
+        signal.best_open_price = None;
+        signal.entry_order = None;
+        ctx.is_signal_filtered = true;
+        ctx.filter_reasons
+            .push(ECONOMIC_EVENT_WINDOW_FILTER_REASON.to_string());
+        Some(blocked)
+    }
 }
 impl Default for FilterStage {
     fn default() -> Self {
@@ -30,6 +108,9 @@ impl BacktestStage for FilterStage {
     }
     /// 执行当前回测阶段，把阶段输入转换为下一阶段上下文。
     fn process(&mut self, ctx: &mut BacktestContext) -> StageResult {
+        // 经济事件平仓先于开仓拦截：刚平掉的仓位在同一窗口内同样不允许重新开仓。
+        self.flatten_in_event_window(ctx);
+        let event_blocked = self.block_entry_in_event_window(ctx);
         if !self.collect_shadow_trades {
             return StageResult::Continue;
         }
@@ -37,7 +118,7 @@ impl BacktestStage for FilterStage {
         ctx.shadow_manager.update_trades(&ctx.candle);
         // 如果有信号但被过滤，创建Shadow Trade
         if ctx.is_signal_filtered {
-            if let Some(ref signal) = ctx.signal {
+            if let Some(signal) = event_blocked.as_ref().or(ctx.signal.as_ref()) {
                 ctx.shadow_manager
                     .process_filtered_signal(signal, &ctx.candle, &ctx.inst_id);
             }
@@ -1,9 +1,11 @@
 //! Pipeline阶段实现
 mod filter;
+mod portfolio_gate;
 mod position;
 mod risk;
 mod signal;
 pub use filter::FilterStage;
+pub use portfolio_gate::{PortfolioGateStage, PORTFOLIO_CAPACITY_FILTER_REASON};
 pub use position::PositionStage;
 pub use risk::RiskStage;
 pub use signal::SignalStage;
@@ -0,0 +1,41 @@
+//! PortfolioGateStage - 组合资金池容量闸门
+use crate::framework::backtest::pipeline::{BacktestContext, BacktestStage, StageResult};
+/// 因组合容量不足被拒绝开仓时写入的过滤原因
+pub const PORTFOLIO_CAPACITY_FILTER_REASON: &str = "PORTFOLIO_CAPACITY";
+/// 组合容量闸门阶段
+///
+/// 位于 FilterStage 与 PositionStage 之间：空仓且 `entry_allowed == false` 时撤销本K线
+/// 的开仓信号和挂单，持仓中的平仓/风控路径不受影响；被拒绝的K线在 `filter_reasons`
+/// 中留下 [`PORTFOLIO_CAPACITY_FILTER_REASON`]，供组合层统计。
+#[derive(Debug, Default)]
+pub struct PortfolioGateStage;
+impl PortfolioGateStage {
+    pub fn new() -> Self {
+        Self
+    }
+}
+impl BacktestStage for PortfolioGateStage {
+    fn name(&self) -> &'static str {
+        "PortfolioGateStage"
+    }
+    /// 容量不足时清除开仓意图，避免 PositionStage 以零资金开仓。
+    fn process(&mut self, ctx: &mut BacktestContext) -> StageResult {
+        if ctx.entry_allowed || ctx.trading_state.trade_position.is_some() {
+            return StageResult::Continue;
+        }
+        let had_pending = ctx.trading_state.cancel_resting_entry()
+            | ctx.trading_state.delayed_entry.take().is_some();
+        let had_signal = ctx.has_signal();
+        if let Some(signal) = ctx.signal.as_mut() {
+            signal.should_buy = false;
+            signal.should_sell = false;
+            signal.best_open_price = None;
+            signal.entry_order = None;
+        }
+        if had_signal || had_pending {
+            ctx.filter_reasons
+                .push(PORTFOLIO_CAPACITY_FILTER_REASON.to_string());
+        }
+        StageResult::Continue
+    }
+}
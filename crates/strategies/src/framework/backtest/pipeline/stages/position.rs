@@ -1,15 +1,32 @@
 //! PositionStage - 仓位管理阶段
+use crate::framework::backtest::intrabar::IntrabarCandles;
 use crate::framework::backtest::pipeline::{BacktestContext, BacktestStage, StageResult};
-use crate::framework::backtest::signal::deal_signal;
+use crate::framework::backtest::signal::deal_signal_with_intrabar;
 use crate::framework::types::TradeSide;
+use rust_quant_indicators::volatility::ATR;
 use rust_quant_trading::audit::{OrderDecision, RiskDecision};
+use std::sync::Arc;
+/// 成本模型 ATR 滑点使用的周期
+const COST_MODEL_ATR_PERIOD: usize = 14;
 /// 仓位管理阶段
 ///
-/// 处理开仓/更新仓位
-pub struct PositionStage;
+/// 处理开仓/更新仓位，并维护成本模型使用的市场 ATR
+pub struct PositionStage {
+    atr: ATR,
+    /// 盘中解析使用的低周期 K 线；为空时沿用止损优先口径，参数扫描时各组合共享同一份
+    intrabar: Option<Arc<IntrabarCandles>>,
+}
 impl PositionStage {
     pub fn new() -> Self {
-        Self
+        Self {
+            atr: ATR::new(COST_MODEL_ATR_PERIOD).expect("ATR 周期必须大于 0"),
+            intrabar: None,
+        }
+    }
+    /// 开启盘中成交顺序解析
+    pub fn with_intrabar(mut self, intrabar: Option<Arc<IntrabarCandles>>) -> Self {
+        self.intrabar = intrabar;
+        self
     }
 }
 impl Default for PositionStage {
@@ -23,10 +40,13 @@ impl BacktestStage for PositionStage {
     }
     /// 执行当前回测阶段，把阶段输入转换为下一阶段上下文。
     fn process(&mut self, ctx: &mut BacktestContext) -> StageResult {
+        self.atr.next(ctx.candle.h, ctx.candle.l, ctx.candle.c);
+        ctx.trading_state.market_atr = self.atr.value_optional();
         // 对齐 legacy engine.rs 的 should_process_signal 判断：
         // 仅当存在交易信号/持仓/挂单时才进入 deal_signal
         let has_position = ctx.trading_state.trade_position.is_some();
-        let has_pending = ctx.trading_state.last_signal_result.is_some();
+        let has_pending =
+            ctx.trading_state.resting_entry.is_some() || ctx.trading_state.delayed_entry.is_some();
         let has_signal = ctx
             .signal
             .as_ref()
@@ -48,18 +68,22 @@ impl BacktestStage for PositionStage {
         // deal_signal 处理了：
         // 1. 开仓 (Open Position)
         // 2. 平仓/反手 (Close/Reversal)
-        // 3. 挂单触发 (Limit Orders / last_signal_result)
+        // 3. 挂单触发 (Limit / StopMarket / best_open_price 挂单)
         // 4. 风控检查 (Risk Management via check_risk_config)
         // 5. 止盈止损更新 (Stop Loss / Take Profit updates)
         let prev_position = ctx.trading_state.trade_position.clone();
+        let prev_lot_count = ctx.trading_state.scale_in_lots.len();
         let trading_state = std::mem::take(&mut ctx.trading_state);
-        ctx.trading_state = deal_signal(
+        let intrabar = self.intrabar.as_deref().map(|candles| {
+            let report = ctx.intrabar_report.get_or_insert_with(Default::default);
+            (candles, report)
+        });
+        ctx.trading_state = deal_signal_with_intrabar(
             trading_state,
             &mut signal,
             &ctx.candle,
             ctx.risk_config,
-            &[], // candle_item_list 未被使用
-            ctx.candle_index,
+            intrabar,
         );
         // 更新 Context 中的状态以供后续 Stage 使用（虽然 RiskStage 主要依赖 context check，但保持状态同步是个好习惯）
         ctx.current_position = ctx.trading_state.trade_position.clone();
@@ -99,6 +123,21 @@ impl BacktestStage for PositionStage {
                     risk_json: None,
                 });
             }
+            (Some(_), Some(pos)) if ctx.trading_state.scale_in_lots.len() > prev_lot_count => {
+                let side = match pos.trade_side {
+                    TradeSide::Long => "ADD_LONG",
+                    TradeSide::Short => "ADD_SHORT",
+                };
+                if let Some(lot) = ctx.trading_state.scale_in_lots.last() {
+                    ctx.audit_trail.record_order_decision(OrderDecision {
+                        ts: ctx.candle.ts,
+                        side: side.to_string(),
+                        size: lot.position_nums,
+                        price: lot.open_price,
+                        decision_json: None,
+                    });
+                }
+            }
             _ => {}
         }
         StageResult::Continue
@@ -1,5 +1,6 @@
 //! SignalStage - 信号生成阶段
 use crate::framework::backtest::adapter::IndicatorStrategyBacktest;
+use crate::framework::backtest::higher_timeframe::HigherTimeframeState;
 use crate::framework::backtest::pipeline::{BacktestContext, BacktestStage, StageResult};
 use crate::CandleItem;
 use rust_quant_trading::audit::SignalSnapshot;
@@ -14,6 +15,8 @@ pub struct SignalStage<S: IndicatorStrategyBacktest> {
     capacity: usize,
     /// false 表示结果不会持久化审计，此时跳过每根 K 线的 JSON 分配与快照缓存。
     collect_audit: bool,
+    /// 策略声明的高周期聚合状态；为空时只使用主周期。
+    higher_timeframes: Vec<HigherTimeframeState<S>>,
 }
 impl<S: IndicatorStrategyBacktest> SignalStage<S> {
     /// 初始化new，确保回测策略依赖和内部状态可直接使用。
@@ -24,6 +27,7 @@ impl<S: IndicatorStrategyBacktest> SignalStage<S> {
     /// 按运行模式控制逐 K 线审计；策略信号和交易决策本身不受该开关影响。
     pub fn with_audit(strategy: S, collect_audit: bool) -> Self {
         let indicator_combine = strategy.init_indicator_combine();
+        let higher_timeframes = HigherTimeframeState::from_strategy(&strategy);
         let min_data_length = strategy.min_data_length();
         let window_size = min_data_length;
         let capacity = if window_size > 0 {
@@ -38,6 +42,7 @@ impl<S: IndicatorStrategyBacktest> SignalStage<S> {
             min_data_length,
             capacity,
             collect_audit,
+            higher_timeframes,
         }
     }
 }
@@ -56,6 +61,10 @@ where
         // 构建指标值
         let mut indicator_values =
             S::build_indicator_values(&mut self.indicator_combine, &ctx.candle);
+        // 高周期在预热期也要逐根推进，否则分桶会缺少样本；当前K线收盘时才完成的高周期K线此处已可见。
+        for higher in &mut self.higher_timeframes {
+            higher.push(&ctx.candle, self.min_data_length);
+        }
         // ⚠️ 严格对齐 engine.rs 的逻辑：
         // 1) 先缓冲数据并计算指标
         // 2) 缓冲不足直接跳过
@@ -71,9 +80,22 @@ where
             .len()
             .saturating_sub(self.min_data_length);
         let current_slice = &self.candle_buffer[start_index..];
-        let signal =
+        let signal = if self.higher_timeframes.is_empty() {
             self.strategy
-                .generate_signal(current_slice, &mut indicator_values, &ctx.risk_config);
+                .generate_signal(current_slice, &mut indicator_values, &ctx.risk_config)
+        } else {
+            let higher: Vec<_> = self
+                .higher_timeframes
+                .iter()
+                .map(|higher| higher.view(self.min_data_length))
+                .collect();
+            self.strategy.generate_signal_with_higher_timeframes(
+                current_slice,
+                &mut indicator_values,
+                &ctx.risk_config,
+                &higher,
+            )
+        };
         // 预热期跳过（engine.rs: if i < 500 { continue; }）
         if ctx.candle_index < 500 {
             // 管理缓冲区大小对齐 legacy 行为
@@ -0,0 +1,433 @@
+//! 多标的组合回测
+//!
+//! 多个标的共享同一个资金池，按所有标的K线时间戳的并集逐时刻推进。每个标的在
+//! 空仓时从资金池领取一份资金（受单标的占比上限约束），平仓后连同盈亏归还资金池；
+//! 同时持仓（含挂单）的标的数达到上限后，其余标的的开仓信号被 [`PortfolioGateStage`]
+//! 拒绝。组合权益 = 现金 + 各标的已领取资金 + 按最新收盘价计算的浮动盈亏。
+use super::adapter::IndicatorStrategyBacktest;
+use super::funding::FundingSeries;
+use super::pipeline::stages::{
+    FilterStage, PortfolioGateStage, PositionStage, SignalStage, PORTFOLIO_CAPACITY_FILTER_REASON,
+};
+use super::pipeline::{BacktestContext, PipelineRunner};
+use super::types::{
+    BackTestResult, BasicRiskStrategyConfig, DynamicConfigLog, TradePosition, TradingState,
+};
+use crate::framework::types::TradeSide;
+use crate::CandleItem;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+/// 组合回测配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortfolioBacktestConfig {
+    /// 初始资金
+    pub initial_capital: f64,
+    /// 同时持仓（含挂单）的最大标的数
+    pub max_concurrent_positions: usize,
+    /// 单标的开仓时可领取的资金占组合权益的比例上限（0~1]
+    pub max_symbol_allocation: f64,
+}
+impl Default for PortfolioBacktestConfig {
+    fn default() -> Self {
+        Self {
+            initial_capital: 100.0,
+            max_concurrent_positions: 3,
+            max_symbol_allocation: 1.0,
+        }
+    }
+}
+/// 组合回测的单个标的输入
+pub struct PortfolioSymbolInput<S> {
+    /// 交易对标识
+    pub inst_id: String,
+    /// 该标的使用的策略
+    pub strategy: S,
+    /// 按时间升序排列的K线
+    pub candles: Vec<CandleItem>,
+    /// 风控配置
+    pub risk_config: BasicRiskStrategyConfig,
+    /// 资金费率序列；为空时不计提资金费
+    pub funding: Option<FundingSeries>,
+}
+/// 组合权益曲线上的一个点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioEquityPoint {
+    /// 时间戳（毫秒）
+    pub ts: i64,
+    /// 组合权益（含浮动盈亏）
+    pub equity: f64,
+    /// 资金池中未分配的现金
+    pub cash: f64,
+    /// 当前持仓的标的数
+    pub open_positions: usize,
+}
+/// 单标的收益归因
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PortfolioSymbolAttribution {
+    /// 交易对标识
+    pub inst_id: String,
+    /// 已实现盈亏（含资金费）
+    pub realized_pnl: f64,
+    /// 累计资金费，正数表示净支付
+    pub funding_fee: f64,
+    /// 开仓次数
+    pub trades: usize,
+    /// 盈利次数
+    pub wins: i64,
+    /// 亏损次数
+    pub losses: i64,
+    /// 因组合容量不足被拒绝的开仓次数
+    pub blocked_entries: usize,
+    /// 对组合收益率的贡献（已实现盈亏 / 初始资金）
+    pub contribution: f64,
+    /// 该标的的完整回测结果；`funds` 为归还资金池前的最后一份领取资金
+    pub result: BackTestResult,
+}
+/// 组合回测结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PortfolioBacktestResult {
+    /// 初始资金
+    pub initial_capital: f64,
+    /// 期末权益
+    pub final_equity: f64,
+    /// 最大回撤（按组合权益曲线计算的比例）
+    pub max_drawdown: f64,
+    /// 组合权益曲线，每个时间戳一个点
+    pub equity_curve: Vec<PortfolioEquityPoint>,
+    /// 各标的收益归因，顺序与输入一致
+    pub symbols: Vec<PortfolioSymbolAttribution>,
+}
+/// 组合中单个标的的运行态
+struct PortfolioSleeve {
+    inst_id: String,
+    candles: Vec<CandleItem>,
+    pipeline: PipelineRunner,
+    /// 无K线的标的为空
+    ctx: Option<BacktestContext>,
+    /// 下一根待推进的K线索引
+    next_index: usize,
+    dynamic_config_logs: Vec<DynamicConfigLog>,
+    blocked_entries: usize,
+    /// 累计领取减去累计归还的资金；全部归还后取反即为已实现盈亏
+    net_allocated: f64,
+}
+impl PortfolioSleeve {
+    /// 持仓或存在挂单/延迟成交时占用组合容量，资金留在该标的内
+    fn is_committed(&self) -> bool {
+        self.ctx.as_ref().is_some_and(|ctx| {
+            let state = &ctx.trading_state;
+            state.trade_position.is_some()
+                || state.resting_entry.is_some()
+                || state.delayed_entry.is_some()
+        })
+    }
+    fn funds(&self) -> f64 {
+        self.ctx.as_ref().map_or(0.0, |ctx| ctx.trading_state.funds)
+    }
+    /// 按最新收盘价计算主仓与加仓的浮动盈亏
+    fn unrealized_pnl(&self) -> f64 {
+        let Some(ctx) = self.ctx.as_ref() else {
+            return 0.0;
+        };
+        let mark = ctx.candle.c;
+        let state = &ctx.trading_state;
+        state
+            .trade_position
+            .iter()
+            .chain(state.scale_in_lots.iter())
+            .map(|position| position_pnl(position, mark))
+            .sum()
+    }
+    fn has_position(&self) -> bool {
+        self.ctx
+            .as_ref()
+            .is_some_and(|ctx| ctx.trading_state.trade_position.is_some())
+    }
+}
+fn position_pnl(position: &TradePosition, mark: f64) -> f64 {
+    match position.trade_side {
+        TradeSide::Long => (mark - position.open_price) * position.position_nums,
+        TradeSide::Short => (position.open_price - mark) * position.position_nums,
+    }
+}
+/// 执行多标的共享资金池回测
+///
+/// 各标的沿用单标的回测的 Pipeline（信号 → 过滤 → 容量闸门 → 持仓），仓位规模由
+/// 领取到的资金决定；同一时间戳内按输入顺序处理，先到的标的先占用容量。
+pub fn run_portfolio_back_test<S>(
+    symbols: Vec<PortfolioSymbolInput<S>>,
+    config: &PortfolioBacktestConfig,
+) -> PortfolioBacktestResult
+where
+    S: IndicatorStrategyBacktest + Send + Sync + 'static,
+    S::IndicatorCombine: Send + Sync + 'static,
+    S::IndicatorValues: Send + Sync + 'static,
+{
+    let fast_mode = rust_quant_core::config::env_is_true("BACKTEST_FAST_MODE", false);
+    let random_mode = rust_quant_core::config::random_backtest_is_enabled();
+    let collect_dynamic_config_logs = PipelineRunner::collect_dynamic_config_logs();
+    let max_symbol_allocation = config.max_symbol_allocation.clamp(0.0, 1.0);
+    let mut sleeves: Vec<PortfolioSleeve> = symbols
+        .into_iter()
+        .map(|input| PortfolioSleeve {
+            ctx: (!input.candles.is_empty()).then(|| {
+                PipelineRunner::new_context(
+                    &input.candles,
+                    &input.inst_id,
+                    input.risk_config,
+                    TradingState {
+                        funds: 0.0,
+                        ..TradingState::default()
+                    },
+                )
+            }),
+            inst_id: input.inst_id,
+            candles: input.candles,
+            pipeline: PipelineRunner::new()
+                .add_stage(SignalStage::with_audit(
+                    input.strategy,
+                    !fast_mode && !random_mode,
+                ))
+                .add_stage(FilterStage::with_shadow_trading(!random_mode))
+                .add_stage(PortfolioGateStage::new())
+                .add_stage(PositionStage::new())
+                .with_funding(input.funding),
+            next_index: 0,
+            dynamic_config_logs: Vec::new(),
+            blocked_entries: 0,
+            net_allocated: 0.0,
+        })
+        .collect();
+    let timeline: BTreeSet<i64> = sleeves
+        .iter()
+        .flat_map(|sleeve| sleeve.candles.iter().map(|candle| candle.ts))
+        .collect();
+    let mut cash = config.initial_capital;
+    let mut equity_curve = Vec::with_capacity(timeline.len());
+    for ts in timeline {
+        for idx in 0..sleeves.len() {
+            let i = sleeves[idx].next_index;
+            if sleeves[idx].candles.get(i).map(|candle| candle.ts) != Some(ts) {
+                continue;
+            }
+            let equity = portfolio_equity(cash, &sleeves);
+            let committed = sleeves.iter().filter(|s| s.is_committed()).count();
+            let sleeve = &mut sleeves[idx];
+            let committed_before = sleeve.is_committed();
+            let ctx = sleeve.ctx.as_mut().expect("标的存在K线时已创建上下文");
+            if !committed_before {
+                // 空仓标的每根K线重新领取资金，未开仓则在推进后归还，保证资金池口径一致。
+                let allowed = committed < config.max_concurrent_positions;
+                let allocation = if allowed {
+                    cash.min(equity * max_symbol_allocation).max(0.0)
+                } else {
+                    0.0
+                };
+                ctx.trading_state.funds = allocation;
+                ctx.entry_allowed = allocation > 0.0;
+                cash -= allocation;
+                sleeve.net_allocated += allocation;
+            }
+            sleeve.pipeline.advance(
+                ctx,
+                &sleeve.candles,
+                i,
+                collect_dynamic_config_logs.then_some(&mut sleeve.dynamic_config_logs),
+            );
+            if ctx
+                .filter_reasons
+                .iter()
+                .any(|reason| reason == PORTFOLIO_CAPACITY_FILTER_REASON)
+            {
+                sleeve.blocked_entries += 1;
+            }
+            sleeve.next_index += 1;
+            if !sleeve.is_committed() {
+                let ctx = sleeve.ctx.as_mut().expect("标的存在K线时已创建上下文");
+                cash += ctx.trading_state.funds;
+                sleeve.net_allocated -= ctx.trading_state.funds;
+                ctx.trading_state.funds = 0.0;
+                ctx.entry_allowed = true;
+            }
+        }
+        equity_curve.push(PortfolioEquityPoint {
+            ts,
+            equity: portfolio_equity(cash, &sleeves),
+            cash,
+            open_positions: sleeves.iter().filter(|s| s.has_position()).count(),
+        });
+    }
+    // 期末按各标的最后收盘价平掉剩余仓位，结算后的资金全部归还资金池。
+    let mut attributions = Vec::with_capacity(sleeves.len());
+    for sleeve in sleeves {
+        let result = match sleeve.ctx {
+            Some(ctx) => {
+                let candles = &sleeve.candles[..sleeve.next_index];
+                PipelineRunner::finish(ctx, candles, sleeve.dynamic_config_logs)
+            }
+            None => BackTestResult::default(),
+        };
+        cash += result.funds;
+        let realized_pnl = result.funds - sleeve.net_allocated;
+        // 出场记录上的胜负次数是截至该笔的累计值
+        let wins = result.trade_records.iter().map(|r| r.win_num).max();
+        let losses = result.trade_records.iter().map(|r| r.loss_num).max();
+        attributions.push(PortfolioSymbolAttribution {
+            inst_id: sleeve.inst_id,
+            realized_pnl,
+            funding_fee: result.total_funding_fee,
+            trades: result.open_trades,
+            wins: wins.unwrap_or(0),
+            losses: losses.unwrap_or(0),
+            blocked_entries: sleeve.blocked_entries,
+            contribution: if config.initial_capital > 0.0 {
+                realized_pnl / config.initial_capital
+            } else {
+                0.0
+            },
+            result,
+        });
+    }
+    if let Some(last) = equity_curve.last_mut() {
+        last.equity = cash;
+        last.cash = cash;
+        last.open_positions = 0;
+    }
+    PortfolioBacktestResult {
+        initial_capital: config.initial_capital,
+        final_equity: cash,
+        max_drawdown: max_drawdown(&equity_curve),
+        equity_curve,
+        symbols: attributions,
+    }
+}
+fn portfolio_equity(cash: f64, sleeves: &[PortfolioSleeve]) -> f64 {
+    cash + sleeves
+        .iter()
+        .map(|sleeve| sleeve.funds() + sleeve.unrealized_pnl())
+        .sum::<f64>()
+}
+fn max_drawdown(curve: &[PortfolioEquityPoint]) -> f64 {
+    let mut peak = f64::MIN;
+    let mut max_drawdown = 0.0_f64;
+    for point in curve {
+        peak = peak.max(point.equity);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max((peak - point.equity) / peak);
+        }
+    }
+    max_drawdown
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::backtest::types::SignalResult;
+    /// 每根K线都发出做多信号的测试策略
+    #[derive(Debug, Clone, Default)]
+    struct AlwaysLong;
+    impl IndicatorStrategyBacktest for AlwaysLong {
+        type IndicatorCombine = ();
+        type IndicatorValues = ();
+        fn min_data_length(&self) -> usize {
+            2
+        }
+        fn init_indicator_combine(&self) -> Self::IndicatorCombine {}
+        fn build_indicator_values(_: &mut Self::IndicatorCombine, _: &CandleItem) {}
+        fn generate_signal(
+            &mut self,
+            candles: &[CandleItem],
+            _: &mut Self::IndicatorValues,
+            _: &BasicRiskStrategyConfig,
+        ) -> SignalResult {
+            let last = candles.last().unwrap();
+            SignalResult {
+                ts: last.ts,
+                open_price: last.c,
+                should_buy: true,
+                ..SignalResult::default()
+            }
+        }
+    }
+    fn rising_candles(start_ts: i64, count: i64) -> Vec<CandleItem> {
+        (0..count)
+            .map(|i| {
+                let price = 100.0 + (start_ts + i) as f64;
+                CandleItem {
+                    o: price,
+                    h: price + 0.5,
+                    l: price - 0.5,
+                    c: price,
+                    v: 1.0,
+                    ts: start_ts + i,
+                    confirm: 1,
+                }
+            })
+            .collect()
+    }
+    fn symbol(inst_id: &str, candles: Vec<CandleItem>) -> PortfolioSymbolInput<AlwaysLong> {
+        PortfolioSymbolInput {
+            inst_id: inst_id.to_string(),
+            strategy: AlwaysLong,
+            candles,
+            risk_config: BasicRiskStrategyConfig {
+                max_loss_percent: 1.0,
+                ..BasicRiskStrategyConfig::default()
+            },
+            funding: None,
+        }
+    }
+    #[test]
+    fn capacity_limit_blocks_entries_beyond_max_concurrent_positions() {
+        let config = PortfolioBacktestConfig {
+            initial_capital: 1_000.0,
+            max_concurrent_positions: 1,
+            max_symbol_allocation: 0.5,
+        };
+        let result = run_portfolio_back_test(
+            vec![
+                symbol("BTC-USDT-SWAP", rising_candles(0, 520)),
+                symbol("ETH-USDT-SWAP", rising_candles(0, 520)),
+            ],
+            &config,
+        );
+        let btc = &result.symbols[0];
+        let eth = &result.symbols[1];
+        assert!(btc.trades > 0);
+        assert_eq!(eth.trades, 0);
+        assert!(eth.blocked_entries > 0);
+        assert_eq!(eth.realized_pnl, 0.0);
+        assert!(result
+            .equity_curve
+            .iter()
+            .all(|point| point.open_positions <= 1));
+        // 单标的最多领取一半权益，另一半始终留在资金池
+        assert!(result.equity_curve.iter().all(|point| point.cash >= 499.0));
+    }
+    #[test]
+    fn equity_reconciles_with_symbol_attribution_on_misaligned_timelines() {
+        let config = PortfolioBacktestConfig {
+            initial_capital: 1_000.0,
+            max_concurrent_positions: 2,
+            max_symbol_allocation: 0.5,
+        };
+        let result = run_portfolio_back_test(
+            vec![
+                symbol("BTC-USDT-SWAP", rising_candles(0, 520)),
+                symbol("ETH-USDT-SWAP", rising_candles(10, 520)),
+            ],
+            &config,
+        );
+        assert_eq!(result.equity_curve.len(), 530);
+        assert!(result
+            .symbols
+            .iter()
+            .all(|s| s.trades > 0 && s.blocked_entries == 0));
+        let attributed: f64 = result.symbols.iter().map(|s| s.realized_pnl).sum();
+        assert!((result.final_equity - config.initial_capital - attributed).abs() < 1e-6);
+        assert!(result.final_equity > config.initial_capital);
+        let contribution: f64 = result.symbols.iter().map(|s| s.contribution).sum();
+        assert!((contribution - (result.final_equity / config.initial_capital - 1.0)).abs() < 1e-9);
+        assert!(result.max_drawdown >= 0.0);
+    }
+}
@@ -1,7 +1,14 @@
 use super::super::types::TradeSide;
-use super::recording::{record_trade_entry, record_trade_exit_with_full_close};
+use super::cost_model::{CostModel, ExitOrderType, FillAction, FillRequest, Liquidity};
+use super::entry_order::submit_entry_order;
+use super::pyramiding::lot_close_sequence;
+use super::recording::{
+    record_position_exit, record_trade_entry, record_trade_exit_with_full_close,
+};
 use super::risk::compute_initial_stop_price;
-use super::types::{BasicRiskStrategyConfig, SignalResult, TradePosition, TradingState};
+use super::types::{
+    BasicRiskStrategyConfig, DelayedEntry, LotCloseOrder, SignalResult, TradePosition, TradingState,
+};
 use crate::CandleItem;
 use rust_quant_domain::enums::PositionSide;
 use tracing::debug;
@@ -9,14 +16,155 @@ use tracing::debug;
 /// Historical backtest fee rate used when a strategy has not opted into a newer cost model.
 const LEGACY_BACKTEST_TRADE_FEE_RATE: f64 = 0.0007;
 
+/// 分批仓位剩余数量低于该值时视为已全部平掉，吸收比例换算的浮点误差。
+pub(crate) const LOT_QUANTITY_EPSILON: f64 = 1e-9;
+
 /// 返回回测仓位乘数；允许 0 到 1 之间的值用于非全仓标准化回测。
-fn position_size_multiplier(risk_config: &BasicRiskStrategyConfig) -> f64 {
+pub(crate) fn position_size_multiplier(risk_config: &BasicRiskStrategyConfig) -> f64 {
     risk_config
         .position_leverage
         .filter(|value| value.is_finite() && *value > 0.0)
         .unwrap_or(1.0)
 }
 
+/// 计算开仓成交价；未配置成本模型时按信号价成交。
+fn entry_fill_price(
+    risk_config: &BasicRiskStrategyConfig,
+    state: &TradingState,
+    candle: &CandleItem,
+    signal: &SignalResult,
+    trade_side: TradeSide,
+    liquidity: Liquidity,
+    quantity: Option<f64>,
+) -> f64 {
+    let Some(model) = risk_config.cost_model else {
+        return signal.open_price;
+    };
+    let quantity = quantity
+        .unwrap_or_else(|| state.funds / signal.open_price * position_size_multiplier(risk_config));
+    model.fill_price(&FillRequest {
+        side: trade_side,
+        action: FillAction::Entry,
+        reference_price: signal.open_price,
+        quantity,
+        candle,
+        atr: state.market_atr,
+        liquidity,
+    })
+}
+
+/// 计算平仓成交价；成本模型随仓位冻结，未配置时按参考价成交。
+fn exit_fill_price(
+    position: &TradePosition,
+    candle: &CandleItem,
+    reference_price: f64,
+    quantity: f64,
+    atr: Option<f64>,
+    liquidity: Liquidity,
+) -> f64 {
+    let Some(model) = position.cost_model else {
+        return reference_price;
+    };
+    model.fill_price(&FillRequest {
+        side: position.trade_side,
+        action: FillAction::Exit,
+        reference_price,
+        quantity,
+        candle,
+        atr,
+        liquidity,
+    })
+}
+
+/// 计算往返手续费：入场费率在开仓时冻结，离场费率按平仓流动性取档。
+fn round_trip_fee(
+    position: &TradePosition,
+    quantity: f64,
+    close_price: f64,
+    liquidity: Liquidity,
+) -> f64 {
+    let default_rate = position
+        .trade_fee_rate
+        .unwrap_or(LEGACY_BACKTEST_TRADE_FEE_RATE);
+    let entry_rate = position.entry_fee_rate.unwrap_or(default_rate);
+    let exit_rate = position
+        .cost_model
+        .and_then(|model| model.fee_rate(liquidity))
+        .unwrap_or(default_rate);
+    quantity * (position.open_price * entry_rate + close_price * exit_rate)
+}
+
+/// 成本模型要求延迟成交时登记入场信号，返回 true 表示本次不即时开仓。
+fn defer_entry(
+    risk_config: &BasicRiskStrategyConfig,
+    state: &mut TradingState,
+    signal: &SignalResult,
+    trade_side: TradeSide,
+) -> bool {
+    let delay = risk_config
+        .cost_model
+        .map(|model| model.fill_delay_bars())
+        .unwrap_or(0);
+    if delay == 0 {
+        return false;
+    }
+    state.delayed_entry = Some(DelayedEntry {
+        signal: signal.clone(),
+        trade_side,
+        remaining_bars: delay,
+    });
+    true
+}
+
+/// 推进延迟入场：到期时在当前 K 线开盘价按吃单成交，已有持仓时放弃该信号。
+pub fn fill_delayed_entry(
+    risk_config: BasicRiskStrategyConfig,
+    state: &mut TradingState,
+    candle: &CandleItem,
+) {
+    let Some(mut delayed) = state.delayed_entry.take() else {
+        return;
+    };
+    if state.trade_position.is_some() {
+        return;
+    }
+    if candle.ts <= delayed.signal.ts {
+        state.delayed_entry = Some(delayed);
+        return;
+    }
+    delayed.remaining_bars = delayed.remaining_bars.saturating_sub(1);
+    if delayed.remaining_bars > 0 {
+        state.delayed_entry = Some(delayed);
+        return;
+    }
+    let signal_open_time =
+        rust_quant_common::utils::time::mill_time_to_datetime(delayed.signal.ts).ok();
+    let signal = SignalResult {
+        open_price: candle.o,
+        ..delayed.signal
+    };
+    match delayed.trade_side {
+        TradeSide::Long => open_long_position_now(
+            risk_config,
+            state,
+            candle,
+            &signal,
+            signal_open_time,
+            Liquidity::Taker,
+            None,
+        ),
+        TradeSide::Short => open_short_position_now(
+            risk_config,
+            state,
+            candle,
+            &signal,
+            signal_open_time,
+            Liquidity::Taker,
+            None,
+        ),
+    }
+}
+
 /// 最终平仓处理
 pub fn finalize_trading_state(trading_state: &mut TradingState, candle_item_list: &[CandleItem]) {
     let mut trade_position = match trading_state.trade_position.clone() {
@@ -59,12 +207,15 @@ pub fn finalize_trading_state(trading_state: &mut TradingState, candle_item_list
             dynamic_adjustments: vec![],
             dynamic_config_snapshot: None,
             direction: rust_quant_domain::SignalDirection::None,
+            entry_order: None,
         },
         "结束平仓",
         profit,
     );
 }
 /// 开多仓
+///
+/// `signal_open_time` 只在挂单已成交时传入，此时按 maker 成交且不延迟、不再挂单。
 pub fn open_long_position(
     risk_config: BasicRiskStrategyConfig,
     state: &mut TradingState,
@@ -72,19 +223,70 @@ pub fn open_long_position(
     signal: &SignalResult,
     signal_open_time: Option<String>,
 ) {
-    // 判断是否需要等待最优开仓位置
-    if state.last_signal_result.is_some() {
-        return;
-    }
+    let liquidity = if signal_open_time.is_some() {
+        Liquidity::Maker
+    } else {
+        // 限价、条件单与最优价挂单进入挂单模拟，按后续 K 线触达情况成交
+        if submit_entry_order(&risk_config, state, candle, signal, TradeSide::Long) {
+            return;
+        }
+        if defer_entry(&risk_config, state, signal, TradeSide::Long) {
+            return;
+        }
+        Liquidity::Taker
+    };
+    open_long_position_now(
+        risk_config,
+        state,
+        candle,
+        signal,
+        signal_open_time,
+        liquidity,
+        None,
+    );
+}
+/// 按成本模型定价后立即开多仓；`quantity` 为空时按全部资金计算数量。
+pub(crate) fn open_long_position_now(
+    risk_config: BasicRiskStrategyConfig,
+    state: &mut TradingState,
+    candle: &CandleItem,
+    signal: &SignalResult,
+    signal_open_time: Option<String>,
+    liquidity: Liquidity,
+    quantity: Option<f64>,
+) {
+    let fill_price = entry_fill_price(
+        &risk_config,
+        state,
+        candle,
+        signal,
+        TradeSide::Long,
+        liquidity,
+        quantity,
+    );
+    let filled_signal;
+    let signal = if fill_price != signal.open_price {
+        filled_signal = SignalResult {
+            open_price: fill_price,
+            ..signal.clone()
+        };
+        &filled_signal
+    } else {
+        signal
+    };
     let leverage = position_size_multiplier(&risk_config);
     let mut temp_trade_position = TradePosition {
-        position_nums: (state.funds / signal.open_price) * leverage,
+        position_nums: quantity.unwrap_or((state.funds / signal.open_price) * leverage),
         open_price: signal.open_price,
         open_position_time: rust_quant_common::utils::time::mill_time_to_datetime(candle.ts)
             .unwrap_or_default(),
         signal_open_position_time: signal_open_time,
         trade_side: TradeSide::Long,
         trade_fee_rate: risk_config.trade_fee_rate,
+        cost_model: risk_config.cost_model,
+        entry_fee_rate: risk_config
+            .cost_model
+            .and_then(|model| model.fee_rate(liquidity)),
         ..Default::default()
     };
     // 记录入场K线振幅，用于固定比例止盈计算
@@ -107,7 +309,6 @@ pub fn open_long_position(
     }
     state.trade_position = Some(temp_trade_position);
     state.open_position_times += 1;
-    state.last_signal_result = None;
     record_trade_entry(state, PositionSide::Long.as_str().to_owned(), signal);
 }
 // ============================================================================
@@ -214,6 +415,8 @@ pub fn set_long_stop_close_price(
     set_stop_close_price_common(&risk_config, signal, temp_trade_position);
 }
 /// 开空仓
+///
+/// `signal_open_time` 只在挂单已成交时传入，此时按 maker 成交且不延迟、不再挂单。
 pub fn open_short_position(
     risk_config: BasicRiskStrategyConfig,
     state: &mut TradingState,
@@ -221,18 +424,70 @@ pub fn open_short_position(
     signal: &SignalResult,
     signal_open_time: Option<String>,
 ) {
-    if state.last_signal_result.is_some() {
-        return;
-    }
+    let liquidity = if signal_open_time.is_some() {
+        Liquidity::Maker
+    } else {
+        // 限价、条件单与最优价挂单进入挂单模拟，按后续 K 线触达情况成交
+        if submit_entry_order(&risk_config, state, candle, signal, TradeSide::Short) {
+            return;
+        }
+        if defer_entry(&risk_config, state, signal, TradeSide::Short) {
+            return;
+        }
+        Liquidity::Taker
+    };
+    open_short_position_now(
+        risk_config,
+        state,
+        candle,
+        signal,
+        signal_open_time,
+        liquidity,
+        None,
+    );
+}
+/// 按成本模型定价后立即开空仓；`quantity` 为空时按全部资金计算数量。
+pub(crate) fn open_short_position_now(
+    risk_config: BasicRiskStrategyConfig,
+    state: &mut TradingState,
+    candle: &CandleItem,
+    signal: &SignalResult,
+    signal_open_time: Option<String>,
+    liquidity: Liquidity,
+    quantity: Option<f64>,
+) {
+    let fill_price = entry_fill_price(
+        &risk_config,
+        state,
+        candle,
+        signal,
+        TradeSide::Short,
+        liquidity,
+        quantity,
+    );
+    let filled_signal;
+    let signal = if fill_price != signal.open_price {
+        filled_signal = SignalResult {
+            open_price: fill_price,
+            ..signal.clone()
+        };
+        &filled_signal
+    } else {
+        signal
+    };
     let leverage = position_size_multiplier(&risk_config);
     let mut temp_trade_position = TradePosition {
-        position_nums: (state.funds / signal.open_price) * leverage,
+        position_nums: quantity.unwrap_or((state.funds / signal.open_price) * leverage),
         open_price: signal.open_price,
         open_position_time: rust_quant_common::utils::time::mill_time_to_datetime(candle.ts)
             .unwrap_or_default(),
         signal_open_position_time: signal_open_time,
         trade_side: TradeSide::Short,
         trade_fee_rate: risk_config.trade_fee_rate,
+        cost_model: risk_config.cost_model,
+        entry_fee_rate: risk_config
+            .cost_model
+            .and_then(|model| model.fee_rate(liquidity)),
         ..Default::default()
     };
     // 记录入场K线振幅，用于固定比例止盈计算
@@ -251,7 +506,6 @@ pub fn open_short_position(
     apply_short_profit_protection(signal, &mut temp_trade_position);
     state.trade_position = Some(temp_trade_position);
     state.open_position_times += 1;
-    state.last_signal_result = None;
     record_trade_entry(state, PositionSide::Short.as_str().to_owned(), signal);
 }
 
@@ -439,15 +693,35 @@ pub fn set_short_stop_close_price(
     // ============ 公共逻辑 ============
     set_stop_close_price_common(&risk_config, signal, temp_trade_position);
 }
-/// 平仓
+/// 平仓；信号平仓、止损与强制平仓均按市价离场。
 pub fn close_position(
     state: &mut TradingState,
     candle: &CandleItem,
     signal: &SignalResult,
     close_type: &str,
     profit: f64,
+) {
+    close_position_with_order(
+        state,
+        candle,
+        signal,
+        close_type,
+        profit,
+        ExitOrderType::Market,
+    );
+}
+
+/// 按指定离场订单类型平仓，成本模型据此选择 maker/taker 费率与滑点。
+pub fn close_position_with_order(
+    state: &mut TradingState,
+    candle: &CandleItem,
+    signal: &SignalResult,
+    close_type: &str,
+    profit: f64,
+    order_type: ExitOrderType,
 ) {
     use super::recording::record_trade_exit;
+    let liquidity = order_type.liquidity();
     let exit_time =
         rust_quant_common::utils::time::mill_time_to_datetime(candle.ts).unwrap_or_default();
     let mut trade_position = match state.trade_position.clone() {
@@ -455,11 +729,36 @@ pub fn close_position(
         None => return,
     };
     let quantity = trade_position.position_nums;
-    let fee_rate = trade_position
-        .trade_fee_rate
-        .unwrap_or(LEGACY_BACKTEST_TRADE_FEE_RATE);
-    let close_price = trade_position.close_price.unwrap_or(signal.open_price);
-    let fee = quantity * (trade_position.open_price + close_price) * fee_rate;
+    let reference_close_price = trade_position.close_price.unwrap_or(signal.open_price);
+    let close_price = exit_fill_price(
+        &trade_position,
+        candle,
+        reference_close_price,
+        quantity,
+        state.market_atr,
+        liquidity,
+    );
+    // 调用方按参考价计算毛利，这里补上离场滑点造成的差额。
+    let profit = profit
+        + match trade_position.trade_side {
+            TradeSide::Long => (close_price - reference_close_price) * quantity,
+            TradeSide::Short => (reference_close_price - close_price) * quantity,
+        };
+    if close_price != reference_close_price {
+        trade_position.close_price = Some(close_price);
+    }
+    let lot_exit = LotExit {
+        exit_time: &exit_time,
+        signal,
+        close_type,
+        close_price,
+        liquidity,
+    };
+    // 加仓分批与主仓同价离场；后开先平时先于主仓落记录。
+    if state.lot_close_order == LotCloseOrder::Lifo {
+        close_scale_in_lots(state, &lot_exit);
+    }
+    let fee = round_trip_fee(&trade_position, quantity, close_price, liquidity);
     let profit_after_fee = profit - fee;
     let trade_profit_after_fee = trade_position.profit_loss + profit_after_fee;
     trade_position.profit_loss = profit_after_fee;
@@ -474,9 +773,142 @@ pub fn close_position(
         state.losses += 1;
     }
     // 根据平仓原因和盈亏设置正确的平仓类型
-    record_trade_exit(state, exit_time, signal, close_type, quantity);
+    record_trade_exit(state, exit_time.clone(), signal, close_type, quantity);
     // 更新总利润和资金
     state.trade_position = None;
+    if state.lot_close_order == LotCloseOrder::Fifo {
+        close_scale_in_lots(state, &lot_exit);
+    }
+}
+
+/// 一次离场在各分批仓位间共享的成交信息。
+struct LotExit<'a> {
+    exit_time: &'a str,
+    signal: &'a SignalResult,
+    close_type: &'a str,
+    close_price: f64,
+    liquidity: Liquidity,
+}
+
+/// 按平仓顺序全部结算加仓分批，每笔单独产生出场记录。
+fn close_scale_in_lots(state: &mut TradingState, exit: &LotExit) {
+    if state.scale_in_lots.is_empty() {
+        return;
+    }
+    let lots = std::mem::take(&mut state.scale_in_lots);
+    let mut lots: Vec<Option<TradePosition>> = lots.into_iter().map(Some).collect();
+    for index in lot_close_sequence(state.lot_close_order, lots.len()) {
+        if let Some(lot) = lots[index].take() {
+            let quantity = lot.position_nums;
+            settle_lot(state, lot, exit, quantity);
+        }
+    }
+}
+
+/// 按给定数量结算一笔分批仓位，更新资金与胜负并写出场记录。
+///
+/// 返回结算后的仓位：剩余数量为 0 表示该笔已全部平掉，`profit_loss` 为累计净盈亏。
+fn settle_lot(
+    state: &mut TradingState,
+    mut lot: TradePosition,
+    exit: &LotExit,
+    quantity: f64,
+) -> TradePosition {
+    let close_price = exit.close_price;
+    let quantity = quantity.min(lot.position_nums).max(0.0);
+    if quantity <= 0.0 {
+        return lot;
+    }
+    let gross_profit = match lot.trade_side {
+        TradeSide::Long => (close_price - lot.open_price) * quantity,
+        TradeSide::Short => (lot.open_price - close_price) * quantity,
+    };
+    let fee = round_trip_fee(&lot, quantity, close_price, exit.liquidity);
+    let profit_after_fee = gross_profit - fee;
+    let cumulative_profit_after_fee = lot.profit_loss + profit_after_fee;
+    lot.position_nums -= quantity;
+    let full_close = lot.position_nums <= LOT_QUANTITY_EPSILON;
+    if full_close {
+        lot.position_nums = 0.0;
+    }
+    state.total_profit_loss += profit_after_fee;
+    state.funds += profit_after_fee;
+    if full_close {
+        if cumulative_profit_after_fee > 0.0 {
+            state.wins += 1;
+        } else if cumulative_profit_after_fee < 0.0 {
+            state.losses += 1;
+        }
+    }
+    lot.close_price = Some(close_price);
+    lot.profit_loss = profit_after_fee;
+    record_position_exit(
+        state,
+        &lot,
+        exit.exit_time.to_string(),
+        exit.signal,
+        exit.close_type,
+        quantity,
+        full_close,
+    );
+    lot.profit_loss = cumulative_profit_after_fee;
+    lot.funding_fee = 0.0;
+    lot
+}
+
+/// 主仓全部平掉后，由最早的剩余分批接替；止损止盈等风控状态沿用原主仓。
+fn promote_lot(anchor: &TradePosition, lot: TradePosition) -> TradePosition {
+    TradePosition {
+        position_nums: lot.position_nums,
+        open_price: lot.open_price,
+        close_price: None,
+        profit_loss: lot.profit_loss,
+        trade_fee_rate: lot.trade_fee_rate,
+        entry_fee_rate: lot.entry_fee_rate,
+        funding_fee: lot.funding_fee,
+        signal_open_position_time: lot.signal_open_position_time,
+        open_position_time: lot.open_position_time,
+        initial_stop_price: lot.initial_stop_price,
+        ..anchor.clone()
+    }
+}
+
+/// 存在加仓分批时的部分平仓：按主仓比例换算总平仓数量，再按平仓顺序逐笔消耗。
+fn partial_close_lots(state: &mut TradingState, exit: &LotExit, closing_quantity: f64) {
+    let Some(primary) = state.trade_position.take() else {
+        return;
+    };
+    if primary.position_nums <= 0.0 {
+        state.trade_position = Some(primary);
+        return;
+    }
+    let close_ratio = (closing_quantity / primary.position_nums).clamp(0.0, 1.0);
+    let anchor = primary.clone();
+    let mut chain: Vec<TradePosition> = std::iter::once(primary)
+        .chain(std::mem::take(&mut state.scale_in_lots))
+        .collect();
+    let total_quantity: f64 = chain.iter().map(|lot| lot.position_nums).sum();
+    let mut remaining = total_quantity * close_ratio;
+    for index in lot_close_sequence(state.lot_close_order, chain.len()) {
+        if remaining <= LOT_QUANTITY_EPSILON {
+            break;
+        }
+        let lot = chain[index].clone();
+        let take = remaining.min(lot.position_nums);
+        remaining -= take;
+        chain[index] = settle_lot(state, lot, exit, take);
+    }
+    let mut survivors = chain.into_iter().filter(|lot| lot.position_nums > 0.0);
+    state.trade_position = survivors.next().map(|first| {
+        if first.open_position_time == anchor.open_position_time
+            && first.open_price == anchor.open_price
+        {
+            first
+        } else {
+            promote_lot(&anchor, first)
+        }
+    });
+    state.scale_in_lots = survivors.collect();
 }
 
 /// 部分平仓并保留剩余仓位，供显式启用的分批止盈回测使用。
@@ -487,9 +919,33 @@ pub fn partial_close_position(
     close_type: &str,
     close_price: f64,
     closing_quantity: f64,
+    order_type: ExitOrderType,
 ) {
+    let liquidity = order_type.liquidity();
     let exit_time =
         rust_quant_common::utils::time::mill_time_to_datetime(candle.ts).unwrap_or_default();
+    let close_price = match state.trade_position.as_ref() {
+        Some(position) => exit_fill_price(
+            position,
+            candle,
+            close_price,
+            closing_quantity,
+            state.market_atr,
+            liquidity,
+        ),
+        None => return,
+    };
+    if !state.scale_in_lots.is_empty() {
+        let lot_exit = LotExit {
+            exit_time: &exit_time,
+            signal,
+            close_type,
+            close_price,
+            liquidity,
+        };
+        partial_close_lots(state, &lot_exit, closing_quantity);
+        return;
+    }
     let mut trade_position = match state.trade_position.clone() {
         Some(p) => p,
         None => return,
@@ -498,14 +954,11 @@ pub fn partial_close_position(
     if quantity <= 0.0 || trade_position.position_nums <= 0.0 {
         return;
     }
-    let fee_rate = trade_position
-        .trade_fee_rate
-        .unwrap_or(LEGACY_BACKTEST_TRADE_FEE_RATE);
     let gross_profit = match trade_position.trade_side {
         TradeSide::Long => (close_price - trade_position.open_price) * quantity,
         TradeSide::Short => (trade_position.open_price - close_price) * quantity,
     };
-    let fee = quantity * (trade_position.open_price + close_price) * fee_rate;
+    let fee = round_trip_fee(&trade_position, quantity, close_price, liquidity);
     let profit_after_fee = gross_profit - fee;
     let cumulative_profit_after_fee = trade_position.profit_loss + profit_after_fee;
     trade_position.position_nums -= quantity;
@@ -522,6 +975,9 @@ pub fn partial_close_position(
 
 #[cfg(test)]
 mod tests {
+    use super::super::cost_model::{
+        BarDelayFill, CostModelConfig, FixedBpsSlippage, MakerTakerFees, SlippageModel,
+    };
     use super::*;
     use rust_quant_domain::SignalDirection;
 
@@ -961,4 +1417,123 @@ mod tests {
         assert_eq!(state.wins, 0);
         assert_eq!(state.losses, 1);
     }
+
+    fn cost_risk(cost_model: CostModelConfig) -> BasicRiskStrategyConfig {
+        BasicRiskStrategyConfig {
+            cost_model: Some(cost_model),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fixed_bps_slippage_worsens_entry_and_exit_prices() {
+        let mut state = TradingState::default();
+        let risk = cost_risk(CostModelConfig {
+            slippage: Some(SlippageModel::FixedBps(FixedBpsSlippage { bps: 10.0 })),
+            fee_tier: Some(MakerTakerFees {
+                maker_fee_rate: 0.0,
+                taker_fee_rate: 0.0,
+            }),
+            ..Default::default()
+        });
+        open_long_position(
+            risk,
+            &mut state,
+            &candle(1, 100.0),
+            &signal(1, 100.0, SignalDirection::Long),
+            None,
+        );
+        let position = state.trade_position.clone().expect("position should open");
+        assert!((position.open_price - 100.1).abs() < 1e-9);
+        state.trade_position.as_mut().unwrap().close_price = Some(110.0);
+
+        let gross = (110.0 - position.open_price) * position.position_nums;
+        close_position(
+            &mut state,
+            &candle(2, 110.0),
+            &signal(2, 110.0, SignalDirection::Long),
+            "test",
+            gross,
+        );
+
+        let close_record = state
+            .trade_records
+            .iter()
+            .find(|record| record.full_close)
+            .expect("close record");
+        let expected = (110.0 * 0.999 - 100.1) * position.position_nums;
+        assert!((close_record.profit_loss - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn maker_take_profit_uses_maker_fee_and_skips_slippage() {
+        let mut state = TradingState::default();
+        let risk = cost_risk(CostModelConfig {
+            slippage: Some(SlippageModel::FixedBps(FixedBpsSlippage { bps: 10.0 })),
+            fee_tier: Some(MakerTakerFees {
+                maker_fee_rate: 0.0001,
+                taker_fee_rate: 0.0005,
+            }),
+            ..Default::default()
+        });
+        // 挂单最优价路径按 maker 入场
+        open_long_position(
+            risk,
+            &mut state,
+            &candle(1, 100.0),
+            &signal(1, 100.0, SignalDirection::Long),
+            Some("2024-01-01 00:00:00".to_string()),
+        );
+        let position = state.trade_position.clone().expect("position should open");
+        assert_eq!(position.open_price, 100.0);
+        assert_eq!(position.entry_fee_rate, Some(0.0001));
+        state.trade_position.as_mut().unwrap().close_price = Some(110.0);
+
+        close_position_with_order(
+            &mut state,
+            &candle(2, 110.0),
+            &signal(2, 110.0, SignalDirection::Long),
+            "固定止盈",
+            10.0,
+            ExitOrderType::TakeProfitLimit,
+        );
+
+        let close_record = state
+            .trade_records
+            .iter()
+            .find(|record| record.full_close)
+            .expect("close record");
+        let expected = 10.0 - (100.0 * 0.0001 + 110.0 * 0.0001);
+        assert!((close_record.profit_loss - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bar_delay_fills_market_entry_at_next_bar_open() {
+        let mut state = TradingState::default();
+        let risk = cost_risk(CostModelConfig {
+            fill_delay: Some(BarDelayFill { bars: 1 }),
+            ..Default::default()
+        });
+        open_long_position(
+            risk,
+            &mut state,
+            &candle(1, 100.0),
+            &signal(1, 100.0, SignalDirection::Long),
+            None,
+        );
+        assert!(state.trade_position.is_none());
+        assert!(state.delayed_entry.is_some());
+
+        fill_delayed_entry(risk, &mut state, &candle(1, 100.0));
+        assert!(state.trade_position.is_none());
+
+        let mut next = candle(2, 103.0);
+        next.o = 101.5;
+        fill_delayed_entry(risk, &mut state, &next);
+
+        let position = state.trade_position.expect("delayed entry should fill");
+        assert_eq!(position.open_price, 101.5);
+        assert!(state.delayed_entry.is_none());
+        assert_eq!(state.open_position_times, 1);
+    }
 }
@@ -0,0 +1,450 @@
+//! # 同向加仓（金字塔）
+//!
+//! 主仓 `TradingState::trade_position` 继续驱动止损止盈检查；满足加仓规则时，
+//! 在 `TradingState::scale_in_lots` 中追加同向分批仓位。每笔分批仓位独立冻结
+//! 初始止损、独立产生 `TradeRecord` 与 R 指标，平仓顺序由 [`LotCloseOrder`] 决定。
+//! 加仓占用的保证金受 `TradingState::funds` 约束，可用保证金不足时缩量或放弃加仓。
+use super::super::types::TradeSide;
+use super::position::{position_size_multiplier, LOT_QUANTITY_EPSILON};
+use super::recording::record_position_entry;
+use super::risk::compute_initial_stop_price;
+use super::types::{
+    BasicRiskStrategyConfig, LotCloseOrder, PyramidingConfig, ScaleInRule, SignalResult,
+    TradePosition, TradingState,
+};
+use crate::CandleItem;
+use rust_quant_domain::enums::PositionSide;
+
+/// 返回生效的加仓配置；未配置、只允许一笔或加仓比例非法时视为关闭。
+fn active_config(risk_config: &BasicRiskStrategyConfig) -> Option<PyramidingConfig> {
+    risk_config.pyramiding.filter(|config| {
+        config.max_lots_per_side > 1
+            && config.lot_size_ratio.is_finite()
+            && config.lot_size_ratio > 0.0
+    })
+}
+
+/// 计算本根 K 线的加仓成交价；未触发返回 None。
+///
+/// 只参考最近一笔仓位，保证每次加仓都建立在上一笔已经走出的利润或回踩之上。
+fn scale_in_fill_price(
+    rule: ScaleInRule,
+    latest: &TradePosition,
+    candle: &CandleItem,
+) -> Option<f64> {
+    match rule {
+        ScaleInRule::ProfitR { r_multiple } => {
+            if !r_multiple.is_finite() || r_multiple <= 0.0 {
+                return None;
+            }
+            let initial_stop = latest.initial_stop_price?;
+            let initial_risk = (latest.open_price - initial_stop).abs();
+            if !initial_risk.is_finite() || initial_risk <= 0.0 {
+                return None;
+            }
+            match latest.trade_side {
+                TradeSide::Long => {
+                    let trigger = latest.open_price + initial_risk * r_multiple;
+                    // 跳空越过触发价时只能按开盘价成交，不能按更优的触发价虚增收益。
+                    (candle.h >= trigger).then(|| trigger.max(candle.o))
+                }
+                TradeSide::Short => {
+                    let trigger = latest.open_price - initial_risk * r_multiple;
+                    (candle.l <= trigger).then(|| trigger.min(candle.o))
+                }
+            }
+        }
+        ScaleInRule::Retest => {
+            let level = latest.open_price;
+            match latest.trade_side {
+                TradeSide::Long => {
+                    (candle.o > level && candle.l <= level && candle.c > level).then_some(level)
+                }
+                TradeSide::Short => {
+                    (candle.o < level && candle.h >= level && candle.c < level).then_some(level)
+                }
+            }
+        }
+    }
+}
+
+/// 按成交价估算的可用保证金：资金 + 全部持仓浮动盈亏 - 已占用的初始保证金。
+///
+/// 初始保证金按各笔仓位开仓名义价值 / 杠杆倍数计算，与开仓时按 `funds × 杠杆` 定仓的口径一致。
+fn available_margin(state: &TradingState, leverage: f64, mark_price: f64) -> f64 {
+    let lots = state
+        .trade_position
+        .iter()
+        .chain(state.scale_in_lots.iter());
+    let (unrealized, used_margin) = lots.fold((0.0, 0.0), |(unrealized, used), lot| {
+        let pnl = match lot.trade_side {
+            TradeSide::Long => (mark_price - lot.open_price) * lot.position_nums,
+            TradeSide::Short => (lot.open_price - mark_price) * lot.position_nums,
+        };
+        (
+            unrealized + pnl,
+            used + lot.open_price * lot.position_nums / leverage,
+        )
+    });
+    state.funds + unrealized - used_margin
+}
+
+/// 持仓期间按加仓规则尝试追加一笔同向仓位，返回本根 K 线是否完成加仓。
+///
+/// 新仓位沿用主仓当前的止损止盈状态，但按自己的成交价重新冻结初始止损，
+/// 因此每笔分批仓位的 R 只反映自身入场时的风险。
+/// 加仓数量不超过可用保证金 × 杠杆可开的数量；没有可用保证金时放弃本次加仓。
+pub fn try_scale_in(
+    risk_config: &BasicRiskStrategyConfig,
+    state: &mut TradingState,
+    candle: &CandleItem,
+    signal: &SignalResult,
+) -> bool {
+    let Some(config) = active_config(risk_config) else {
+        return false;
+    };
+    let Some(primary) = state.trade_position.as_ref() else {
+        return false;
+    };
+    if 1 + state.scale_in_lots.len() >= config.max_lots_per_side {
+        return false;
+    }
+    let candle_time =
+        rust_quant_common::utils::time::mill_time_to_datetime(candle.ts).unwrap_or_default();
+    let latest = state.scale_in_lots.last().unwrap_or(primary);
+    // 同一根 K 线内无法确认入场与加仓触发的先后，最近一笔入场当根不再加仓。
+    if latest.open_position_time == candle_time {
+        return false;
+    }
+    let Some(fill_price) = scale_in_fill_price(config.scale_in_rule, latest, candle) else {
+        return false;
+    };
+    if !fill_price.is_finite() || fill_price <= 0.0 {
+        return false;
+    }
+    let leverage = position_size_multiplier(risk_config);
+    let margin = available_margin(state, leverage, fill_price);
+    if !margin.is_finite() || margin <= 0.0 {
+        return false;
+    }
+    let quantity =
+        (primary.position_nums * config.lot_size_ratio).min(margin * leverage / fill_price);
+    if !quantity.is_finite() || quantity <= LOT_QUANTITY_EPSILON {
+        return false;
+    }
+
+    let lot = build_lot(risk_config, primary, candle, fill_price, quantity);
+    let option_type = match lot.trade_side {
+        TradeSide::Long => PositionSide::Long.as_str().to_owned(),
+        TradeSide::Short => PositionSide::Short.as_str().to_owned(),
+    };
+    state.lot_close_order = config.close_order;
+    state.open_position_times += 1;
+    record_position_entry(state, &lot, option_type, signal);
+    state.scale_in_lots.push(lot);
+    true
+}
+
+/// 以主仓当前止损止盈状态为模板构造一笔同向分批仓位，按自身成交价重新冻结初始止损。
+pub(crate) fn build_lot(
+    risk_config: &BasicRiskStrategyConfig,
+    primary: &TradePosition,
+    candle: &CandleItem,
+    fill_price: f64,
+    quantity: f64,
+) -> TradePosition {
+    let candle_time =
+        rust_quant_common::utils::time::mill_time_to_datetime(candle.ts).unwrap_or_default();
+    let mut lot = TradePosition {
+        position_nums: quantity,
+        open_price: fill_price,
+        close_price: None,
+        profit_loss: 0.0,
+        signal_open_position_time: None,
+        open_position_time: candle_time,
+        stop_loss_updates: Vec::new(),
+        initial_stop_price: None,
+        funding_fee: 0.0,
+        ..primary.clone()
+    };
+    let raw_range = (candle.h - candle.l).abs();
+    lot.entry_kline_amplitude =
+        (raw_range > 0.0 && candle.l > 0.0).then(|| raw_range / candle.l.max(1e-9));
+    lot.entry_kline_close_pos = (raw_range > 0.0).then(|| (candle.c - candle.l) / raw_range);
+    // 主仓移动后的止损可能已越过新成交价，这类价格不能作为新仓位的保护价。
+    let side = lot.trade_side;
+    lot.signal_kline_stop_close_price =
+        lot.signal_kline_stop_close_price.filter(|stop| match side {
+            TradeSide::Long => *stop < fill_price,
+            TradeSide::Short => *stop > fill_price,
+        });
+    lot.initial_stop_price = compute_initial_stop_price(&lot, risk_config);
+    lot
+}
+
+/// 按平仓顺序返回分批仓位下标；下标 0 为主仓，其余依次为 `scale_in_lots`。
+pub(crate) fn lot_close_sequence(order: LotCloseOrder, lot_count: usize) -> Vec<usize> {
+    match order {
+        LotCloseOrder::Fifo => (0..lot_count).collect(),
+        LotCloseOrder::Lifo => (0..lot_count).rev().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::backtest::cost_model::ExitOrderType;
+    use crate::framework::backtest::position::{
+        close_position, open_long_position, partial_close_position,
+    };
+    use rust_quant_domain::SignalDirection;
+
+    fn candle(ts: i64, open: f64, high: f64, low: f64, close: f64) -> CandleItem {
+        CandleItem {
+            ts,
+            o: open,
+            h: high,
+            l: low,
+            c: close,
+            v: 1.0,
+            confirm: 1,
+        }
+    }
+
+    fn long_signal(ts: i64, price: f64) -> SignalResult {
+        SignalResult {
+            should_buy: true,
+            open_price: price,
+            ts,
+            direction: SignalDirection::Long,
+            ..Default::default()
+        }
+    }
+
+    fn risk(rule: ScaleInRule, close_order: LotCloseOrder) -> BasicRiskStrategyConfig {
+        BasicRiskStrategyConfig {
+            max_loss_percent: 0.02,
+            dynamic_max_loss: Some(false),
+            trade_fee_rate: Some(0.0),
+            pyramiding: Some(PyramidingConfig {
+                max_lots_per_side: 3,
+                scale_in_rule: rule,
+                lot_size_ratio: 0.5,
+                close_order,
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// 以默认资金 100 在 100 开多，初始止损 98（1R = 2），之后把账户资金设为 `funds`，
+    /// 模拟主仓之外仍有可用保证金的账户。
+    fn opened_long_with_funds(risk_config: BasicRiskStrategyConfig, funds: f64) -> TradingState {
+        let mut state = TradingState::default();
+        let entry = candle(0, 100.0, 100.5, 99.5, 100.0);
+        open_long_position(
+            risk_config,
+            &mut state,
+            &entry,
+            &long_signal(0, 100.0),
+            None,
+        );
+        state.funds = funds;
+        state
+    }
+
+    /// 留有 100 可用保证金的多头主仓，加仓不受资金约束。
+    fn opened_long(risk_config: BasicRiskStrategyConfig) -> TradingState {
+        opened_long_with_funds(risk_config, 200.0)
+    }
+
+    #[test]
+    fn profit_r_rule_adds_lot_at_trigger_with_own_initial_stop() {
+        let risk_config = risk(
+            ScaleInRule::ProfitR { r_multiple: 1.0 },
+            LotCloseOrder::Fifo,
+        );
+        let mut state = opened_long(risk_config);
+        let bar = candle(3_600_000, 101.0, 103.0, 100.5, 102.5);
+
+        assert!(try_scale_in(
+            &risk_config,
+            &mut state,
+            &bar,
+            &long_signal(bar.ts, 102.5)
+        ));
+
+        let lot = state.scale_in_lots.first().expect("scale-in lot");
+        assert_eq!(lot.open_price, 102.0);
+        assert!((lot.position_nums - 0.5).abs() < 1e-9);
+        assert_eq!(lot.initial_stop_price, Some(102.0 * 0.98));
+        assert_eq!(state.open_position_times, 2);
+        assert_eq!(state.trade_records.len(), 2);
+    }
+
+    #[test]
+    fn scale_in_respects_max_lots_and_same_bar_guard() {
+        let risk_config = risk(
+            ScaleInRule::ProfitR { r_multiple: 1.0 },
+            LotCloseOrder::Fifo,
+        );
+        let mut state = opened_long(risk_config);
+        let same_bar = candle(0, 100.0, 110.0, 99.5, 109.0);
+        assert!(!try_scale_in(
+            &risk_config,
+            &mut state,
+            &same_bar,
+            &long_signal(0, 109.0)
+        ));
+
+        let first = candle(3_600_000, 101.0, 103.0, 100.5, 102.5);
+        let second = candle(7_200_000, 103.0, 106.5, 102.5, 106.0);
+        let third = candle(10_800_000, 106.0, 120.0, 105.5, 119.0);
+        assert!(try_scale_in(
+            &risk_config,
+            &mut state,
+            &first,
+            &long_signal(first.ts, 102.5)
+        ));
+        assert!(try_scale_in(
+            &risk_config,
+            &mut state,
+            &second,
+            &long_signal(second.ts, 106.0)
+        ));
+        assert!(!try_scale_in(
+            &risk_config,
+            &mut state,
+            &third,
+            &long_signal(third.ts, 119.0)
+        ));
+        assert_eq!(state.scale_in_lots.len(), 2);
+    }
+
+    #[test]
+    fn retest_rule_adds_at_previous_entry() {
+        let risk_config = risk(ScaleInRule::Retest, LotCloseOrder::Fifo);
+        let mut state = opened_long(risk_config);
+        let no_retest = candle(3_600_000, 101.0, 102.0, 100.4, 101.5);
+        assert!(!try_scale_in(
+            &risk_config,
+            &mut state,
+            &no_retest,
+            &long_signal(no_retest.ts, 101.5)
+        ));
+
+        let retest = candle(7_200_000, 101.0, 101.5, 99.8, 100.8);
+        assert!(try_scale_in(
+            &risk_config,
+            &mut state,
+            &retest,
+            &long_signal(retest.ts, 100.8)
+        ));
+        assert_eq!(state.scale_in_lots[0].open_price, 100.0);
+    }
+
+    #[test]
+    fn scale_in_is_capped_by_available_margin_and_skipped_once_funds_run_out() {
+        let mut risk_config = risk(ScaleInRule::Retest, LotCloseOrder::Fifo);
+        risk_config.pyramiding.as_mut().unwrap().max_lots_per_side = 4;
+        // 主仓占用 100，只剩 30 可用保证金，不够一笔 0.5 张（50）的加仓。
+        let mut state = opened_long_with_funds(risk_config, 130.0);
+
+        let first = candle(3_600_000, 101.0, 101.5, 99.8, 100.8);
+        assert!(try_scale_in(
+            &risk_config,
+            &mut state,
+            &first,
+            &long_signal(first.ts, 100.8)
+        ));
+        let lot = &state.scale_in_lots[0];
+        assert_eq!(lot.open_price, 100.0);
+        assert!((lot.position_nums - 0.3).abs() < 1e-9);
+
+        // 回踩到同一价位时没有浮盈补充保证金，阶梯在此停止。
+        let second = candle(7_200_000, 101.0, 101.5, 99.9, 100.6);
+        assert!(!try_scale_in(
+            &risk_config,
+            &mut state,
+            &second,
+            &long_signal(second.ts, 100.6)
+        ));
+        assert_eq!(state.scale_in_lots.len(), 1);
+        assert_eq!(state.open_position_times, 2);
+        assert_eq!(state.trade_records.len(), 2);
+        assert_eq!(state.funds, 130.0);
+
+        // 2 倍杠杆：主仓 2 张、目标加仓 1 张，同样 30 可用保证金按杠杆可开 0.6 张。
+        let mut levered = risk_config;
+        levered.position_leverage = Some(2.0);
+        let mut state = opened_long_with_funds(levered, 130.0);
+        assert!(try_scale_in(
+            &levered,
+            &mut state,
+            &first,
+            &long_signal(first.ts, 100.8)
+        ));
+        assert!((state.scale_in_lots[0].position_nums - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn full_close_records_each_lot_in_lifo_order() {
+        let risk_config = risk(
+            ScaleInRule::ProfitR { r_multiple: 1.0 },
+            LotCloseOrder::Lifo,
+        );
+        let mut state = opened_long(risk_config);
+        let bar = candle(3_600_000, 101.0, 103.0, 100.5, 102.5);
+        try_scale_in(&risk_config, &mut state, &bar, &long_signal(bar.ts, 102.5));
+
+        let exit = candle(7_200_000, 104.0, 104.5, 103.5, 104.0);
+        state.trade_position.as_mut().unwrap().close_price = Some(104.0);
+        close_position(&mut state, &exit, &long_signal(exit.ts, 104.0), "test", 4.0);
+
+        assert!(state.trade_position.is_none());
+        assert!(state.scale_in_lots.is_empty());
+        let exits: Vec<_> = state
+            .trade_records
+            .iter()
+            .filter(|record| record.option_type == "close")
+            .collect();
+        assert_eq!(exits.len(), 2);
+        assert_eq!(exits[0].open_price, 102.0);
+        assert!((exits[0].profit_loss - 1.0).abs() < 1e-9);
+        assert!((exits[0].net_profit_r.unwrap() - 2.0 / (102.0 * 0.02)).abs() < 1e-9);
+        assert_eq!(exits[1].open_price, 100.0);
+        assert_eq!(exits[1].net_profit_r, Some(2.0));
+        assert_eq!(state.wins, 2);
+        assert!((state.funds - 205.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fifo_partial_close_consumes_primary_and_promotes_next_lot() {
+        let risk_config = risk(
+            ScaleInRule::ProfitR { r_multiple: 1.0 },
+            LotCloseOrder::Fifo,
+        );
+        let mut state = opened_long(risk_config);
+        let bar = candle(3_600_000, 101.0, 103.0, 100.5, 102.5);
+        try_scale_in(&risk_config, &mut state, &bar, &long_signal(bar.ts, 102.5));
+
+        // 主仓 1.0 + 加仓 0.5；按主仓 2/3 比例部分止盈，总计平掉 1.0。
+        let exit = candle(7_200_000, 104.0, 104.5, 103.5, 104.0);
+        partial_close_position(
+            &mut state,
+            &exit,
+            &long_signal(exit.ts, 104.0),
+            "分批止盈(级别1)",
+            104.0,
+            2.0 / 3.0,
+            ExitOrderType::TakeProfitLimit,
+        );
+
+        let primary = state.trade_position.as_ref().expect("promoted lot");
+        assert_eq!(primary.open_price, 102.0);
+        assert!((primary.position_nums - 0.5).abs() < 1e-9);
+        assert!(state.scale_in_lots.is_empty());
+        let last = state.trade_records.last().expect("exit record");
+        assert_eq!(last.open_price, 100.0);
+        assert!(last.full_close);
+    }
+}
@@ -1,4 +1,4 @@
-use super::types::{SignalResult, TradeRecord, TradingState};
+use super::types::{SignalResult, TradePosition, TradeRecord, TradingState};
 use rust_quant_core::config::random_backtest_is_enabled;
 
 /// 按记录数量计算冻结初始止损对应的价格风险金额。
@@ -16,6 +16,15 @@ fn initial_risk_amount(
 pub fn record_trade_entry(state: &mut TradingState, option_type: String, signal: &SignalResult) {
     // 批量回测的时候不进行记录
     let trade_position = state.trade_position.clone().unwrap();
+    record_position_entry(state, &trade_position, option_type, signal);
+}
+/// 记录指定仓位的入场，主仓与加仓分批共用同一记录口径。
+pub fn record_position_entry(
+    state: &mut TradingState,
+    trade_position: &TradePosition,
+    option_type: String,
+    signal: &SignalResult,
+) {
     // 随机测试的时候不记录详情日志了
     if random_backtest_is_enabled() {
         return;
@@ -45,6 +54,7 @@ pub fn record_trade_entry(state: &mut TradingState, option_type: String, signal:
             trade_position.position_nums,
         ),
         net_profit_r: None,
+        funding_fee: None,
     });
 }
 /// 记录交易出场
@@ -68,6 +78,31 @@ pub fn record_trade_exit_with_full_close(
     full_close: bool,
 ) {
     let trade_position = state.trade_position.clone().unwrap();
+    record_position_exit(
+        state,
+        &trade_position,
+        exit_time,
+        signal,
+        close_type,
+        closing_quantity,
+        full_close,
+    );
+    // 已结转到出场记录的资金费清零，部分平仓后的剩余仓位重新累计。
+    if let Some(position) = state.trade_position.as_mut() {
+        position.funding_fee = 0.0;
+    }
+}
+
+/// 记录指定仓位的出场；`trade_position.profit_loss` 需为本次出场的净盈亏。
+pub fn record_position_exit(
+    state: &mut TradingState,
+    trade_position: &TradePosition,
+    exit_time: String,
+    signal: &SignalResult,
+    close_type: &str,
+    closing_quantity: f64,
+    full_close: bool,
+) {
     // 随机测试的时候不记录详情日志了
     if random_backtest_is_enabled() {
         return;
@@ -108,5 +143,6 @@ pub fn record_trade_exit_with_full_close(
         initial_stop_price: trade_position.initial_stop_price,
         initial_risk_amount: risk_amount,
         net_profit_r: risk_amount.map(|risk| trade_position.profit_loss / risk),
+        funding_fee: Some(trade_position.funding_fee),
     });
 }
@@ -1,5 +1,6 @@
 use super::super::types::TradeSide;
-use super::position::{close_position, partial_close_position};
+use super::cost_model::ExitOrderType;
+use super::position::{close_position_with_order, partial_close_position};
 use super::types::{BasicRiskStrategyConfig, SignalResult, TradePosition, TradingState};
 use crate::CandleItem;
 // ============================================================================
@@ -615,6 +616,27 @@ fn run_take_profit_checks(
     update_profit_protection(ctx, position);
     ExitResult::None
 }
+/// 同一根 K 线是否同时触达止损与止盈（含部分止盈）。
+///
+/// 默认口径下止损优先；返回 true 时 OHLC 无法确定真实先后，需要低周期 K 线回放。
+pub fn is_exit_order_ambiguous(
+    risk_config: &BasicRiskStrategyConfig,
+    position: &TradePosition,
+    candle: &CandleItem,
+) -> bool {
+    let ctx = ExitContext::new(position, candle);
+    if !matches!(
+        run_stop_loss_checks(&ctx, risk_config, position),
+        ExitResult::Exit { .. } | ExitResult::ExitDynamic { .. }
+    ) {
+        return false;
+    }
+    let mut probe = position.clone();
+    matches!(
+        run_take_profit_checks(&ctx, risk_config, &mut probe),
+        ExitResult::Exit { .. } | ExitResult::PartialExit { .. }
+    )
+}
 // ============================================================================
 // 主函数
 // ============================================================================
@@ -656,6 +678,7 @@ pub fn check_risk_config(
             signal,
             &ctx,
             stop_result,
+            ExitOrderType::Market,
         );
     }
     // 止盈检查
@@ -668,6 +691,7 @@ pub fn check_risk_config(
             signal,
             &ctx,
             tp_result,
+            ExitOrderType::TakeProfitLimit,
         );
     }
     if let ExitResult::PartialExit { .. } = tp_result {
@@ -758,7 +782,15 @@ pub fn check_risk_config_with_r_system(
         check_base_protective_stop(&ctx, &trade_position, risk_config)
     {
         r_runtime.r_state = None; // 平仓后清除R系统状态
-        return finalize_exit(trading_state, trade_position, candle, signal, &ctx, result);
+        return finalize_exit(
+            trading_state,
+            trade_position,
+            candle,
+            signal,
+            &ctx,
+            result,
+            ExitOrderType::Market,
+        );
     }
     // 2. R系统移动止损（新增）
     if r_risk_config.enable_r_system {
@@ -786,6 +818,7 @@ pub fn check_risk_config_with_r_system(
                         price: stop_price,
                         reason,
                     },
+                    ExitOrderType::Market,
                 );
             }
             // 同步R系统止损到仓位
@@ -821,6 +854,7 @@ pub fn check_risk_config_with_r_system(
                             price: candle.c,
                             reason,
                         },
+                        ExitOrderType::Market,
                     );
                 }
                 TimeStopAction::Reduce50 { reason } => {
@@ -841,7 +875,15 @@ pub fn check_risk_config_with_r_system(
         check_atr_trailing_stop(&ctx, &trade_position)
     {
         r_runtime.r_state = None;
-        return finalize_exit(trading_state, trade_position, candle, signal, &ctx, result);
+        return finalize_exit(
+            trading_state,
+            trade_position,
+            candle,
+            signal,
+            &ctx,
+            result,
+            ExitOrderType::Market,
+        );
     }
     // ========================================================================
     // 止盈检查（复用公共检查链）
@@ -856,6 +898,7 @@ pub fn check_risk_config_with_r_system(
             signal,
             &ctx,
             tp_result,
+            ExitOrderType::TakeProfitLimit,
         );
     }
     if let ExitResult::PartialExit { .. } = tp_result {
@@ -883,6 +926,7 @@ fn finalize_exit(
     signal: &SignalResult,
     ctx: &ExitContext,
     result: ExitResult,
+    order_type: ExitOrderType,
 ) -> TradingState {
     let (price, reason) = match result {
         ExitResult::Exit { price, reason } => (price, reason.to_string()),
@@ -893,7 +937,14 @@ fn finalize_exit(
     trade_position.close_price = Some(price);
     trading_state.trade_position = Some(trade_position);
     let profit = ctx.profit(price);
-    close_position(&mut trading_state, candle, signal, &reason, profit);
+    close_position_with_order(
+        &mut trading_state,
+        candle,
+        signal,
+        &reason,
+        profit,
+        order_type,
+    );
     trading_state
 }
 
@@ -923,6 +974,7 @@ fn finalize_partial_exit(
         reason,
         price,
         closing_quantity,
+        ExitOrderType::TakeProfitLimit,
     );
     trading_state
 }
@@ -278,3 +278,70 @@ fn short_profit_protection_becomes_active_only_on_the_next_candle() {
     assert_eq!(close.close_type, "空头盈利保护止损");
     assert_eq!(close.close_price, Some(100.0));
 }
+
+/// 以 maker/taker 分档费率持有一笔 100 开仓的多单，入场按 maker 成交。
+fn fee_tier_long_state(fixed_take_profit_price: Option<f64>) -> TradingState {
+    let cost_model = super::super::cost_model::CostModelConfig {
+        fee_tier: Some(super::super::cost_model::MakerTakerFees {
+            maker_fee_rate: 0.0001,
+            taker_fee_rate: 0.0005,
+        }),
+        ..Default::default()
+    };
+    TradingState {
+        trade_position: Some(TradePosition {
+            trade_side: TradeSide::Long,
+            open_price: 100.0,
+            position_nums: 1.0,
+            fixed_take_profit_price,
+            cost_model: Some(cost_model),
+            entry_fee_rate: Some(0.0001),
+            ..TradePosition::default()
+        }),
+        ..TradingState::default()
+    }
+}
+
+#[test]
+fn take_profit_exit_charges_maker_fee_and_stop_exit_charges_taker_fee() {
+    let signal = SignalResult {
+        open_price: 100.0,
+        ..Default::default()
+    };
+    let take_profit_candle = CandleItem {
+        o: 100.0,
+        h: 106.0,
+        l: 99.5,
+        c: 105.5,
+        v: 1.0,
+        ts: 1,
+        confirm: 1,
+    };
+    let closed = check_risk_config(
+        &BasicRiskStrategyConfig::default(),
+        fee_tier_long_state(Some(105.0)),
+        &signal,
+        &take_profit_candle,
+    );
+    let close = closed.trade_records.last().expect("take profit record");
+    let expected = 5.0 - (100.0 * 0.0001 + 105.0 * 0.0001);
+    assert!((close.profit_loss - expected).abs() < 1e-9);
+
+    let stop_candle = CandleItem {
+        o: 100.0,
+        h: 100.5,
+        l: 97.0,
+        c: 97.5,
+        v: 1.0,
+        ts: 1,
+        confirm: 1,
+    };
+    let risk = BasicRiskStrategyConfig {
+        max_loss_percent: 0.02,
+        ..Default::default()
+    };
+    let closed = check_risk_config(&risk, fee_tier_long_state(None), &signal, &stop_candle);
+    let close = closed.trade_records.last().expect("stop loss record");
+    let expected = -2.0 - (100.0 * 0.0001 + 98.0 * 0.0005);
+    assert!((close.profit_loss - expected).abs() < 1e-9);
+}
@@ -1,8 +1,11 @@
 use super::super::types::TradeSide;
+use super::entry_order::advance_resting_entry;
+use super::intrabar::{check_risk_config_intrabar, IntrabarCandles, IntrabarReport};
 use super::position::{
-    close_position, open_long_position, open_short_position, set_long_stop_close_price,
-    set_short_stop_close_price,
+    close_position, fill_delayed_entry, open_long_position, open_short_position,
+    set_long_stop_close_price, set_short_stop_close_price,
 };
+use super::pyramiding::try_scale_in;
 use super::risk::check_risk_config;
 use super::types::{BasicRiskStrategyConfig, SignalResult, TradingState};
 use crate::CandleItem;
@@ -68,22 +71,50 @@ fn has_rebound_hammer_long_protect(signal: &SignalResult) -> bool {
 }
 /// 处理交易信号
 pub fn deal_signal(
-    mut trading_state: TradingState,
+    trading_state: TradingState,
     signal: &mut SignalResult,
     candle: &CandleItem,
     risk_config: BasicRiskStrategyConfig,
     _candle_item_list: &[CandleItem],
     _i: usize,
+) -> TradingState {
+    deal_signal_with_intrabar(trading_state, signal, candle, risk_config, None)
+}
+/// 处理交易信号；传入低周期 K 线时，止损止盈同时触达的 K 线按盘中回放确定先后。
+pub fn deal_signal_with_intrabar(
+    mut trading_state: TradingState,
+    signal: &mut SignalResult,
+    candle: &CandleItem,
+    risk_config: BasicRiskStrategyConfig,
+    intrabar: Option<(&IntrabarCandles, &mut IntrabarReport)>,
 ) -> TradingState {
     //先检查设置了是否预止损价格
     // if signal.ts == 1762747200000 {
     //     println!("signal: {:#?}", signal);
     //     println!("trading_state: {:#?}", trading_state.trade_position);
     // }
+    // 0. 成本模型延迟成交：上一根及更早的入场信号在本根 K 线开盘价成交
+    if trading_state.delayed_entry.is_some() {
+        fill_delayed_entry(risk_config, &mut trading_state, candle);
+    }
     // 1. 优先进行风控检查 (确保每根K线的最高/最低价都能触发止损/止盈)
     // 即使当前K线产生了新信号，也必须先检查由于K线波动导致的止损
     if trading_state.trade_position.is_some() {
-        trading_state = check_risk_config(&risk_config, trading_state, signal, candle);
+        trading_state = match intrabar {
+            Some((intrabar, report)) => check_risk_config_intrabar(
+                &risk_config,
+                trading_state,
+                signal,
+                candle,
+                intrabar,
+                report,
+            ),
+            None => check_risk_config(&risk_config, trading_state, signal, candle),
+        };
+    }
+    // 2. 推进入场挂单：本根 K 线的价格走势发生在收盘信号之前
+    if trading_state.resting_entry.is_some() {
+        advance_resting_entry(&risk_config, &mut trading_state, candle);
     }
     if let Some(mut trade_position) = trading_state.trade_position.clone() {
         if trade_position.trade_side == TradeSide::Short && has_rebound_hammer_long_protect(signal)
@@ -149,6 +180,7 @@ pub fn deal_signal(
         signal.should_buy = false;
         signal.should_sell = false;
         signal.best_open_price = None;
+        signal.entry_order = None;
         has_entry_signal = false;
     }
     if has_entry_signal {
@@ -170,12 +202,8 @@ pub fn deal_signal(
                 trading_state.trade_position = Some(trade_position);
             }
         }
-        // 使用更优点位开仓
-        if signal.best_open_price.is_some() {
-            trading_state.last_signal_result = Some(signal.clone());
-        } else {
-            trading_state.last_signal_result = None;
-        }
+        // 新入场信号替换尚未成交完毕的挂单
+        trading_state.cancel_resting_entry();
         // 处理策略信号
         if signal.should_buy {
             handle_buy_signal_logic(
@@ -194,69 +222,10 @@ pub fn deal_signal(
                 block_short_entry,
             );
         }
-    } else {
-        // 如果没有新信号
-        if trading_state.trade_position.is_some() {
-            // 风控已在顶部检查过，此处无需再次检查
-        } else if trading_state.last_signal_result.is_some() {
-            // 要确保大于信号的开仓时间
-            if candle.ts >= trading_state.last_signal_result.clone().unwrap().ts {
-                let last_signal_result = trading_state.last_signal_result.clone().unwrap();
-                if last_signal_result.should_buy {
-                    // 如果信号是买，但是当前价格低于信号的最优开仓价格，则使用信号的最优开仓价格
-                    if let Some(best_price) = last_signal_result.best_open_price {
-                        if candle.l <= best_price {
-                            signal.open_price = best_price;
-                            signal.should_buy = true;
-                            signal.signal_kline_stop_loss_price =
-                                last_signal_result.signal_kline_stop_loss_price;
-                            signal.single_value = last_signal_result.single_value;
-                            signal.single_result = last_signal_result.single_result;
-                            trading_state.last_signal_result = None;
-                            let signal_open_position_time = Some(
-                                rust_quant_common::utils::time::mill_time_to_datetime(
-                                    last_signal_result.ts,
-                                )
-                                .unwrap(),
-                            );
-                            open_long_position(
-                                risk_config,
-                                &mut trading_state,
-                                candle,
-                                signal,
-                                signal_open_position_time,
-                            );
-                        }
-                    }
-                } else if last_signal_result.should_sell {
-                    // 如果信号是卖，但是当前价格高于信号的最优开仓价格，则使用信号的最优开仓价格
-                    if let Some(best_price) = last_signal_result.best_open_price {
-                        if candle.h > best_price {
-                            signal.open_price = best_price;
-                            signal.should_sell = true;
-                            signal.signal_kline_stop_loss_price =
-                                last_signal_result.signal_kline_stop_loss_price;
-                            signal.single_value = last_signal_result.single_value;
-                            signal.single_result = last_signal_result.single_result;
-                            trading_state.last_signal_result = None;
-                            let signal_open_position_time = Some(
-                                rust_quant_common::utils::time::mill_time_to_datetime(
-                                    last_signal_result.ts,
-                                )
-                                .unwrap(),
-                            );
-                            open_short_position(
-                                risk_config,
-                                &mut trading_state,
-                                candle,
-                                signal,
-                                signal_open_position_time,
-                            );
-                        }
-                    }
-                }
-            }
-        }
+    }
+    // 持仓仍在场内时按加仓规则追加同向分批；未配置金字塔时保持单仓位口径。
+    if trading_state.trade_position.is_some() {
+        try_scale_in(&risk_config, &mut trading_state, candle, signal);
     }
     trading_state
 }
@@ -1,8 +1,13 @@
 use super::adapter::{run_indicator_strategy_backtest, IndicatorStrategyBacktest};
+use super::economic_calendar::EconomicBlackout;
+use super::engine::run_back_test;
+use super::funding::FundingSeries;
+use super::intrabar::IntrabarCandles;
 use super::types::{BackTestResult, BasicRiskStrategyConfig};
 use crate::implementations::nwe_strategy::NweStrategy;
 use crate::implementations::vegas_backtest::VegasBacktestAdapter;
 use crate::CandleItem;
+use std::sync::Arc;
 /// 通用回测策略能力接口，便于不同策略复用统一回测与落库流程
 pub trait BackTestAbleStrategyTrait:
     IndicatorStrategyBacktest + Sized + Send + Sync + 'static
@@ -20,6 +25,43 @@ where
     ) -> BackTestResult {
         run_indicator_strategy_backtest(inst_id, self, candles, risk_strategy_config)
     }
+    /// 开启盘中成交顺序解析的回测，结果附带 `intrabar_report`。
+    fn run_test_with_intrabar(
+        self,
+        inst_id: &str,
+        candles: &[CandleItem],
+        risk_strategy_config: BasicRiskStrategyConfig,
+        intrabar: IntrabarCandles,
+    ) -> BackTestResult {
+        self.run_test_with_context(
+            inst_id,
+            candles,
+            risk_strategy_config,
+            None,
+            Some(Arc::new(intrabar)),
+            None,
+        )
+    }
+    /// 按需开启资金费计提、盘中成交顺序解析与经济事件停牌的回测；低周期 K 线以 `Arc` 传入，参数扫描时各组合共享。
+    fn run_test_with_context(
+        self,
+        inst_id: &str,
+        candles: &[CandleItem],
+        risk_strategy_config: BasicRiskStrategyConfig,
+        funding: Option<FundingSeries>,
+        intrabar: Option<Arc<IntrabarCandles>>,
+        economic_blackout: Option<EconomicBlackout>,
+    ) -> BackTestResult {
+        run_back_test(
+            inst_id,
+            self,
+            candles,
+            risk_strategy_config,
+            funding,
+            intrabar,
+            economic_blackout,
+        )
+    }
 }
 impl BackTestAbleStrategyTrait for NweStrategy {
     fn strategy_type(&self) -> crate::StrategyType {
@@ -17,8 +17,13 @@
 //!
 //! ## 配置类型
 //! - [`BasicRiskStrategyConfig`] - 风控配置
+//! - [`PyramidingConfig`] - 同向加仓（金字塔）配置
+//! - [`CostModelConfig`] - 滑点、手续费档位与延迟成交配置
 //! - [`MoveStopLoss`] - 移动止损
 use super::super::types::TradeSide;
+use super::cost_model::CostModelConfig;
+use super::entry_order::{EntryOrder, EntryOrderStats, RestingEntryOrder};
+use super::intrabar::IntrabarReport;
 use rust_quant_trading::audit::AuditTrail;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -42,6 +47,15 @@ pub struct BackTestResult {
     pub dynamic_config_logs: Vec<DynamicConfigLog>,
     /// 回测审计轨迹，用于还原关键计算步骤。
     pub audit_trail: AuditTrail,
+    /// 累计资金费，正数表示净支付；未提供资金费率序列时为 0。
+    #[serde(default)]
+    pub total_funding_fee: f64,
+    /// 盘中成交顺序解析统计；未开启解析时为空。
+    #[serde(default)]
+    pub intrabar_report: Option<IntrabarReport>,
+    /// 限价、条件单等挂单入场的成交统计。
+    #[serde(default)]
+    pub entry_order_stats: EntryOrderStats,
 }
 impl Default for BackTestResult {
     /// 提供默认参数，保证 回测与策略研究 在未显式配置时仍有稳定初始值。
@@ -54,6 +68,9 @@ impl Default for BackTestResult {
             filtered_signals: vec![],
             dynamic_config_logs: vec![],
             audit_trail: AuditTrail::default(),
+            total_funding_fee: 0.0,
+            intrabar_report: None,
+            entry_order_stats: EntryOrderStats::default(),
         }
     }
 }
@@ -100,6 +117,8 @@ pub struct TradeRecord {
     pub initial_risk_amount: Option<f64>,
     /// 扣除回测手续费后的本条出场收益除以初始风险金额；入场记录为空。
     pub net_profit_r: Option<f64>,
+    /// 自上一条出场记录以来该仓位累计的资金费，正数表示支付；不计入 `profit_loss`，入场记录为空。
+    pub funding_fee: Option<f64>,
 }
 // ============================================================================
 // 信号类型
@@ -152,6 +171,9 @@ pub struct SignalResult {
     pub dynamic_config_snapshot: Option<String>,
     /// 信号方向
     pub direction: rust_quant_domain::SignalDirection,
+    /// 入场委托；为空时设置了 `best_open_price` 按 GTC 限价单挂出，否则按市价成交
+    #[serde(default)]
+    pub entry_order: Option<EntryOrder>,
 }
 // ============================================================================
 // 过滤信号与影子交易类型
@@ -238,6 +260,7 @@ impl Default for SignalResult {
             dynamic_adjustments: vec![],
             dynamic_config_snapshot: None,
             direction: rust_quant_domain::SignalDirection::None,
+            entry_order: None,
         }
     }
 }
@@ -312,6 +335,12 @@ pub struct TradePosition {
     pub stop_loss_updates: Vec<rust_quant_domain::value_objects::StopLossUpdate>,
     /// 入场时冻结的有效保护价，是整笔交易 R 的唯一分母来源。
     pub initial_stop_price: Option<f64>,
+    /// 入场时从风控配置冻结的成本模型，平仓滑点与离场手续费按它计算。
+    pub cost_model: Option<CostModelConfig>,
+    /// 入场单边手续费率；None 表示沿用 `trade_fee_rate`。
+    pub entry_fee_rate: Option<f64>,
+    /// 自上一条出场记录以来累计的资金费，正数表示支付。
+    pub funding_fee: f64,
 }
 /// 交易状态
 #[derive(Debug, Clone)]
@@ -324,14 +353,37 @@ pub struct TradingState {
     pub losses: i64,
     //开仓次数
     pub open_position_times: usize,
-    //上一次信号结果
-    pub last_signal_result: Option<SignalResult>,
+    /// 尚未成交完毕的入场挂单。
+    pub resting_entry: Option<RestingEntryOrder>,
+    /// 挂单入场的成交统计。
+    pub entry_order_stats: EntryOrderStats,
     //总盈亏
     pub total_profit_loss: f64,
     //持仓记录
     pub trade_records: Vec<TradeRecord>,
     //交易持仓
     pub trade_position: Option<TradePosition>,
+    /// 主仓之后按加仓规则追加的同向分批仓位，按开仓先后排列。
+    /// 主仓始终是最早的一笔，负责驱动止损止盈检查；每笔加仓单独记账和计算 R。
+    pub scale_in_lots: Vec<TradePosition>,
+    /// 分批仓位的平仓顺序，在首次加仓时从风控配置冻结。
+    pub lot_close_order: LotCloseOrder,
+    /// 最近一根 K 线的 ATR，供 ATR 比例滑点使用；为空时按 K 线振幅近似。
+    pub market_atr: Option<f64>,
+    /// 等待延迟成交的入场信号。
+    pub delayed_entry: Option<DelayedEntry>,
+    /// 累计资金费，正数表示净支付；已计入 `funds` 与 `total_profit_loss`。
+    pub total_funding_fee: f64,
+}
+/// 延迟成交的入场信号
+#[derive(Debug, Clone)]
+pub struct DelayedEntry {
+    /// 原始入场信号。
+    pub signal: SignalResult,
+    /// 入场方向。
+    pub trade_side: TradeSide,
+    /// 距离成交还需经过的 K 线根数。
+    pub remaining_bars: usize,
 }
 impl Default for TradingState {
     /// 提供默认参数，保证 回测与策略研究 在未显式配置时仍有稳定初始值。
@@ -341,10 +393,16 @@ impl Default for TradingState {
             wins: 0,
             losses: 0,
             open_position_times: 0,
-            last_signal_result: None,
+            resting_entry: None,
+            entry_order_stats: EntryOrderStats::default(),
             total_profit_loss: 0.0,
             trade_records: Vec::with_capacity(3000),
             trade_position: None,
+            scale_in_lots: Vec::new(),
+            lot_close_order: LotCloseOrder::default(),
+            market_atr: None,
+            delayed_entry: None,
+            total_funding_fee: 0.0,
         }
     }
 }
@@ -398,6 +456,12 @@ pub struct BasicRiskStrategyConfig {
     /// 第二档止盈触发时的部分平仓比例，按当前剩余仓位计算。
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tiered_take_profit_level_2_close_ratio: Option<f64>,
+    /// 同向加仓配置；None 表示保持单仓位口径。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pyramiding: Option<PyramidingConfig>,
+    /// 成交成本模型；None 表示按信号价成交并只扣 `trade_fee_rate`。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_model: Option<CostModelConfig>,
 }
 impl Default for BasicRiskStrategyConfig {
     /// 提供默认参数，保证 回测与策略研究 在未显式配置时仍有稳定初始值。
@@ -417,6 +481,51 @@ impl Default for BasicRiskStrategyConfig {
             position_leverage: None,
             tiered_take_profit_level_1_close_ratio: None,
             tiered_take_profit_level_2_close_ratio: None,
+            pyramiding: None,
+            cost_model: None,
+        }
+    }
+}
+/// 加仓触发规则
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScaleInRule {
+    /// 最近一笔仓位浮盈达到 `r_multiple` 倍初始风险时，在触发价追加一笔。
+    ProfitR { r_multiple: f64 },
+    /// 价格回踩最近一笔仓位的开仓价并收回其上方（空头为下方）时，在开仓价追加一笔。
+    Retest,
+}
+/// 分批仓位平仓顺序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LotCloseOrder {
+    /// 先开先平：主仓优先，部分止盈先消耗最早的仓位。
+    #[default]
+    Fifo,
+    /// 后开先平：部分止盈先消耗最近追加的仓位。
+    Lifo,
+}
+/// 同向加仓（金字塔）配置
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct PyramidingConfig {
+    /// 单方向最多同时持有的仓位笔数（含主仓）。
+    pub max_lots_per_side: usize,
+    /// 加仓触发规则。
+    pub scale_in_rule: ScaleInRule,
+    /// 每笔加仓数量相对主仓开仓数量的比例。
+    pub lot_size_ratio: f64,
+    /// 分批仓位平仓顺序。
+    #[serde(default)]
+    pub close_order: LotCloseOrder,
+}
+impl Default for PyramidingConfig {
+    /// 提供默认参数，保证 回测与策略研究 在未显式配置时仍有稳定初始值。
+    fn default() -> Self {
+        Self {
+            max_lots_per_side: 1,
+            scale_in_rule: ScaleInRule::ProfitR { r_multiple: 1.0 },
+            lot_size_ratio: 1.0,
+            close_order: LotCloseOrder::Fifo,
         }
     }
 }
@@ -452,6 +561,15 @@ mod tests {
             .is_none());
     }
     #[test]
+    fn risk_config_omits_pyramiding_when_disabled() {
+        let value = serde_json::to_value(BasicRiskStrategyConfig::default())
+            .expect("serialize BasicRiskStrategyConfig");
+        assert!(value.get("pyramiding").is_none());
+        let parsed: BasicRiskStrategyConfig =
+            serde_json::from_value(value).expect("deserialize BasicRiskStrategyConfig");
+        assert!(parsed.pyramiding.is_none());
+    }
+    #[test]
     fn risk_config_has_no_validate_or_tighten_flags() {
         let value = serde_json::to_value(BasicRiskStrategyConfig::default())
             .expect("serialize BasicRiskStrategyConfig");
@@ -83,6 +83,7 @@ impl EngulfingStrategy {
             dynamic_adjustments: vec![],
             dynamic_config_snapshot: None,
             direction: rust_quant_domain::SignalDirection::None,
+            entry_order: None,
         }
     }
     // /// 运行回测
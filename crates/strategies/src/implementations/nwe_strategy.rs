@@ -505,6 +505,7 @@ impl NweStrategy {
             dynamic_adjustments: vec![],
             dynamic_config_snapshot: None,
             direction: rust_quant_domain::SignalDirection::None,
+            entry_order: None,
         };
         let current_price = candles.last().unwrap().c;
         let o = candles.last().unwrap().o;
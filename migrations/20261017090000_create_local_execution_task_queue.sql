@@ -0,0 +1,223 @@
+CREATE TABLE IF NOT EXISTS strategy_signal_inbox (
+    id BIGSERIAL PRIMARY KEY,
+    source VARCHAR(64) NOT NULL,
+    external_id VARCHAR(255) NOT NULL,
+    strategy_slug VARCHAR(128) NOT NULL,
+    strategy_key VARCHAR(255) NOT NULL,
+    symbol VARCHAR(64) NOT NULL,
+    signal_type VARCHAR(32) NOT NULL,
+    direction VARCHAR(32) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    summary TEXT,
+    confidence DOUBLE PRECISION,
+    payload_json TEXT NOT NULL,
+    generated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uniq_strategy_signal_inbox_source_external
+        UNIQUE (source, external_id)
+);
+
+COMMENT ON TABLE strategy_signal_inbox IS '本地执行任务队列的策略信号收件箱，按 source + external_id 幂等去重';
+COMMENT ON COLUMN strategy_signal_inbox.id IS '自增主键';
+COMMENT ON COLUMN strategy_signal_inbox.source IS '信号来源，如 strategy_execution_service';
+COMMENT ON COLUMN strategy_signal_inbox.external_id IS '来源侧的信号幂等标识';
+COMMENT ON COLUMN strategy_signal_inbox.strategy_slug IS '策略标识';
+COMMENT ON COLUMN strategy_signal_inbox.strategy_key IS '策略Key，格式 strategy_type:inst_id:period:config_id';
+COMMENT ON COLUMN strategy_signal_inbox.symbol IS '交易对';
+COMMENT ON COLUMN strategy_signal_inbox.signal_type IS '信号类型，如 buy、sell';
+COMMENT ON COLUMN strategy_signal_inbox.direction IS '信号方向，如 long、short';
+COMMENT ON COLUMN strategy_signal_inbox.title IS '信号标题';
+COMMENT ON COLUMN strategy_signal_inbox.summary IS '信号摘要';
+COMMENT ON COLUMN strategy_signal_inbox.confidence IS '信号置信度';
+COMMENT ON COLUMN strategy_signal_inbox.payload_json IS '信号原始载荷 JSON 字符串';
+COMMENT ON COLUMN strategy_signal_inbox.generated_at IS '信号生成时间';
+COMMENT ON COLUMN strategy_signal_inbox.created_at IS '记录创建时间';
+COMMENT ON COLUMN strategy_signal_inbox.updated_at IS '记录更新时间';
+
+CREATE TABLE IF NOT EXISTS execution_tasks (
+    id BIGSERIAL PRIMARY KEY,
+    news_signal_id BIGINT,
+    strategy_signal_id BIGINT REFERENCES strategy_signal_inbox(id) ON DELETE SET NULL,
+    combo_id BIGINT NOT NULL DEFAULT 0,
+    buyer_email VARCHAR(255) NOT NULL,
+    strategy_slug VARCHAR(128) NOT NULL,
+    symbol VARCHAR(64) NOT NULL,
+    task_type VARCHAR(64) NOT NULL DEFAULT 'execute_signal',
+    task_status VARCHAR(64) NOT NULL DEFAULT 'pending',
+    priority INTEGER NOT NULL DEFAULT 0,
+    lease_owner VARCHAR(128),
+    lease_until TIMESTAMPTZ,
+    lease_count INTEGER NOT NULL DEFAULT 0,
+    scheduled_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    request_payload_json JSONB NOT NULL DEFAULT '{}'::jsonb,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_execution_tasks_leasable
+    ON execution_tasks (task_status, priority DESC, scheduled_at, id);
+CREATE INDEX IF NOT EXISTS idx_execution_tasks_lease_until
+    ON execution_tasks (lease_until)
+    WHERE lease_until IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_execution_tasks_strategy_signal
+    ON execution_tasks (strategy_signal_id)
+    WHERE strategy_signal_id IS NOT NULL;
+
+COMMENT ON TABLE execution_tasks IS '本地执行任务队列；EXECUTION_TASK_SOURCE=postgres 时替代 rust_quan_web 的任务状态机';
+COMMENT ON COLUMN execution_tasks.id IS '自增主键，即 execution_task_id';
+COMMENT ON COLUMN execution_tasks.news_signal_id IS '关联的新闻信号 ID，可为空';
+COMMENT ON COLUMN execution_tasks.strategy_signal_id IS '关联的 strategy_signal_inbox.id，可为空';
+COMMENT ON COLUMN execution_tasks.combo_id IS '策略组合 ID；单团队部署使用固定值';
+COMMENT ON COLUMN execution_tasks.buyer_email IS '任务所属账户标识';
+COMMENT ON COLUMN execution_tasks.strategy_slug IS '策略标识';
+COMMENT ON COLUMN execution_tasks.symbol IS '交易对';
+COMMENT ON COLUMN execution_tasks.task_type IS '任务类型，如 execute_signal、risk_control_close_candidate';
+COMMENT ON COLUMN execution_tasks.task_status IS '任务状态，如 pending、pending_close、pending_confirmation、completed、failed、cancelled';
+COMMENT ON COLUMN execution_tasks.priority IS '租约优先级，数值越大越先执行';
+COMMENT ON COLUMN execution_tasks.lease_owner IS '当前持有租约的 worker ID';
+COMMENT ON COLUMN execution_tasks.lease_until IS '租约到期时间；过期后任务可被重新租约';
+COMMENT ON COLUMN execution_tasks.lease_count IS '累计被租约的次数';
+COMMENT ON COLUMN execution_tasks.scheduled_at IS '最早可执行时间';
+COMMENT ON COLUMN execution_tasks.request_payload_json IS '执行请求载荷，与 rust_quan_web 下发的任务载荷结构一致';
+COMMENT ON COLUMN execution_tasks.last_error IS '最近一次回写的错误信息';
+COMMENT ON COLUMN execution_tasks.created_at IS '记录创建时间';
+COMMENT ON COLUMN execution_tasks.updated_at IS '记录更新时间';
+
+CREATE TABLE IF NOT EXISTS execution_task_attempts (
+    id BIGSERIAL PRIMARY KEY,
+    execution_task_id BIGINT NOT NULL REFERENCES execution_tasks(id) ON DELETE CASCADE,
+    execution_status VARCHAR(64) NOT NULL,
+    exchange VARCHAR(32) NOT NULL,
+    external_order_id VARCHAR(128) NOT NULL,
+    order_side VARCHAR(16) NOT NULL,
+    order_status VARCHAR(64) NOT NULL,
+    filled_qty DOUBLE PRECISION,
+    filled_quote DOUBLE PRECISION,
+    fee_amount DOUBLE PRECISION,
+    profit_usdt DOUBLE PRECISION,
+    executed_at TIMESTAMPTZ,
+    error_message TEXT,
+    raw_payload_json TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_execution_task_attempts_task
+    ON execution_task_attempts (execution_task_id, created_at DESC, id DESC);
+
+COMMENT ON TABLE execution_task_attempts IS '本地执行任务每次回写的执行报告，保留完整尝试历史';
+COMMENT ON COLUMN execution_task_attempts.id IS '自增主键';
+COMMENT ON COLUMN execution_task_attempts.execution_task_id IS '关联的 execution_tasks.id';
+COMMENT ON COLUMN execution_task_attempts.execution_status IS '本次回写的执行状态';
+COMMENT ON COLUMN execution_task_attempts.exchange IS '交易所';
+COMMENT ON COLUMN execution_task_attempts.external_order_id IS '交易所订单 ID 或 clientOrderId；失败时为 failed-task-{id}';
+COMMENT ON COLUMN execution_task_attempts.order_side IS '订单方向';
+COMMENT ON COLUMN execution_task_attempts.order_status IS '订单状态';
+COMMENT ON COLUMN execution_task_attempts.filled_qty IS '已成交数量';
+COMMENT ON COLUMN execution_task_attempts.filled_quote IS '已成交计价金额';
+COMMENT ON COLUMN execution_task_attempts.fee_amount IS '手续费';
+COMMENT ON COLUMN execution_task_attempts.profit_usdt IS '平仓收益 USDT';
+COMMENT ON COLUMN execution_task_attempts.executed_at IS '交易所执行时间';
+COMMENT ON COLUMN execution_task_attempts.error_message IS '错误信息';
+COMMENT ON COLUMN execution_task_attempts.raw_payload_json IS '执行报告原始载荷 JSON 字符串';
+COMMENT ON COLUMN execution_task_attempts.created_at IS '记录创建时间';
+
+CREATE TABLE IF NOT EXISTS exchange_order_results (
+    id BIGSERIAL PRIMARY KEY,
+    execution_task_id BIGINT NOT NULL REFERENCES execution_tasks(id) ON DELETE CASCADE,
+    combo_id BIGINT NOT NULL DEFAULT 0,
+    buyer_email VARCHAR(255) NOT NULL,
+    exchange VARCHAR(32) NOT NULL,
+    external_order_id VARCHAR(128) NOT NULL,
+    order_side VARCHAR(16) NOT NULL,
+    order_status VARCHAR(64) NOT NULL,
+    filled_qty DOUBLE PRECISION,
+    filled_quote DOUBLE PRECISION,
+    fee_amount DOUBLE PRECISION,
+    raw_payload_json TEXT NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uniq_exchange_order_results_task_order
+        UNIQUE (execution_task_id, external_order_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_exchange_order_results_task_latest
+    ON exchange_order_results (execution_task_id, updated_at DESC, id DESC);
+
+COMMENT ON TABLE exchange_order_results IS '本地执行任务的交易所订单结果，确认租约读取每个任务最近一条结果';
+COMMENT ON COLUMN exchange_order_results.id IS '自增主键';
+COMMENT ON COLUMN exchange_order_results.execution_task_id IS '关联的 execution_tasks.id';
+COMMENT ON COLUMN exchange_order_results.combo_id IS '策略组合 ID';
+COMMENT ON COLUMN exchange_order_results.buyer_email IS '任务所属账户标识';
+COMMENT ON COLUMN exchange_order_results.exchange IS '交易所';
+COMMENT ON COLUMN exchange_order_results.external_order_id IS '交易所订单 ID 或 clientOrderId';
+COMMENT ON COLUMN exchange_order_results.order_side IS '订单方向';
+COMMENT ON COLUMN exchange_order_results.order_status IS '最近一次回写的订单状态';
+COMMENT ON COLUMN exchange_order_results.filled_qty IS '已成交数量';
+COMMENT ON COLUMN exchange_order_results.filled_quote IS '已成交计价金额';
+COMMENT ON COLUMN exchange_order_results.fee_amount IS '手续费';
+COMMENT ON COLUMN exchange_order_results.raw_payload_json IS '最近一次执行报告原始载荷 JSON 字符串';
+COMMENT ON COLUMN exchange_order_results.created_at IS '记录创建时间';
+COMMENT ON COLUMN exchange_order_results.updated_at IS '记录更新时间';
+
+CREATE TABLE IF NOT EXISTS execution_risk_reservations (
+    execution_task_id BIGINT PRIMARY KEY REFERENCES execution_tasks(id) ON DELETE CASCADE,
+    buyer_email VARCHAR(255) NOT NULL,
+    exchange VARCHAR(32) NOT NULL,
+    api_credential_id BIGINT,
+    allocation_mode VARCHAR(64) NOT NULL,
+    allowed_notional_usdt DOUBLE PRECISION NOT NULL,
+    required_margin_usdt DOUBLE PRECISION NOT NULL,
+    stop_risk_usdt DOUBLE PRECISION NOT NULL,
+    leverage DOUBLE PRECISION NOT NULL,
+    margin_mode VARCHAR(16) NOT NULL,
+    position_mode VARCHAR(16) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE execution_risk_reservations IS '本地执行任务下主单前预留的风险预算，每个任务只预留一次';
+COMMENT ON COLUMN execution_risk_reservations.execution_task_id IS '关联的 execution_tasks.id';
+COMMENT ON COLUMN execution_risk_reservations.buyer_email IS '任务所属账户标识';
+COMMENT ON COLUMN execution_risk_reservations.exchange IS '交易所';
+COMMENT ON COLUMN execution_risk_reservations.api_credential_id IS '任务携带的 exchange_apikey_config.id';
+COMMENT ON COLUMN execution_risk_reservations.allocation_mode IS '预算分配方式';
+COMMENT ON COLUMN execution_risk_reservations.allowed_notional_usdt IS '最终允许名义仓位 USDT';
+COMMENT ON COLUMN execution_risk_reservations.required_margin_usdt IS '最终逐仓保证金 USDT';
+COMMENT ON COLUMN execution_risk_reservations.stop_risk_usdt IS '按入场价与止损价计算的止损风险 USDT';
+COMMENT ON COLUMN execution_risk_reservations.leverage IS '杠杆倍数';
+COMMENT ON COLUMN execution_risk_reservations.margin_mode IS '保证金模式';
+COMMENT ON COLUMN execution_risk_reservations.position_mode IS '持仓模式';
+COMMENT ON COLUMN execution_risk_reservations.created_at IS '记录创建时间';
+COMMENT ON COLUMN execution_risk_reservations.updated_at IS '记录更新时间';
+
+CREATE TABLE IF NOT EXISTS exchange_reconciliation_issues (
+    id BIGSERIAL PRIMARY KEY,
+    combo_id BIGINT NOT NULL DEFAULT 0,
+    buyer_email VARCHAR(255) NOT NULL,
+    symbol VARCHAR(64) NOT NULL,
+    exchange VARCHAR(32),
+    api_credential_id BIGINT,
+    issue_type VARCHAR(64) NOT NULL,
+    source_ref VARCHAR(255),
+    message TEXT,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_exchange_reconciliation_issues_scope
+    ON exchange_reconciliation_issues (buyer_email, symbol, detected_at DESC, id DESC);
+
+COMMENT ON TABLE exchange_reconciliation_issues IS '本地执行任务队列记录的交易所对账问题';
+COMMENT ON COLUMN exchange_reconciliation_issues.id IS '自增主键';
+COMMENT ON COLUMN exchange_reconciliation_issues.combo_id IS '策略组合 ID';
+COMMENT ON COLUMN exchange_reconciliation_issues.buyer_email IS '账户标识';
+COMMENT ON COLUMN exchange_reconciliation_issues.symbol IS '交易对';
+COMMENT ON COLUMN exchange_reconciliation_issues.exchange IS '交易所账户范围';
+COMMENT ON COLUMN exchange_reconciliation_issues.api_credential_id IS 'API 凭证 ID';
+COMMENT ON COLUMN exchange_reconciliation_issues.issue_type IS '问题类型：exchange_position_stale、exchange_open_order_conflict、exchange_position_flat';
+COMMENT ON COLUMN exchange_reconciliation_issues.source_ref IS '问题来源引用，如执行任务 ID';
+COMMENT ON COLUMN exchange_reconciliation_issues.message IS '问题说明';
+COMMENT ON COLUMN exchange_reconciliation_issues.detected_at IS '问题发现时间';
+COMMENT ON COLUMN exchange_reconciliation_issues.created_at IS '记录创建时间';
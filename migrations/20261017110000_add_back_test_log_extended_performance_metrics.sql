@@ -0,0 +1,27 @@
+BEGIN;
+
+-- 回测汇总表补充扩展绩效指标，便于跨批次比较下行风险、交易质量和资金占用。
+ALTER TABLE IF EXISTS back_test_log
+    ADD COLUMN IF NOT EXISTS sortino_ratio DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS calmar_ratio DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS profit_factor DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS expectancy_r DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS average_hold_hours DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS exposure DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS longest_drawdown_days DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS max_win_streak INTEGER,
+    ADD COLUMN IF NOT EXISTS max_loss_streak INTEGER,
+    ADD COLUMN IF NOT EXISTS tail_ratio DOUBLE PRECISION;
+
+COMMENT ON COLUMN back_test_log.sortino_ratio IS '索提诺比率';
+COMMENT ON COLUMN back_test_log.calmar_ratio IS '卡玛比率，年化收益率除以最大回撤';
+COMMENT ON COLUMN back_test_log.profit_factor IS '盈亏因子，总盈利除以总亏损绝对值';
+COMMENT ON COLUMN back_test_log.expectancy_r IS '平仓记录 net_profit_r 均值，无 R 口径时为空';
+COMMENT ON COLUMN back_test_log.average_hold_hours IS '平均持仓时长（小时）';
+COMMENT ON COLUMN back_test_log.exposure IS '有持仓时间占回测区间的比例';
+COMMENT ON COLUMN back_test_log.longest_drawdown_days IS '最长回撤持续时间（天）';
+COMMENT ON COLUMN back_test_log.max_win_streak IS '最大连胜笔数';
+COMMENT ON COLUMN back_test_log.max_loss_streak IS '最大连亏笔数';
+COMMENT ON COLUMN back_test_log.tail_ratio IS '尾部比率，单笔收益率95分位除以5分位绝对值';
+
+COMMIT;
@@ -0,0 +1,44 @@
+BEGIN;
+
+-- K线数据质量巡检结果：缺口、重复、连续零成交量、OHLC 不一致、遗留未确认和异常影线。
+CREATE TABLE IF NOT EXISTS candle_quality_findings (
+    id BIGSERIAL PRIMARY KEY,
+    inst_id VARCHAR(64) NOT NULL,
+    period VARCHAR(16) NOT NULL,
+    issue VARCHAR(32) NOT NULL,
+    start_ts BIGINT NOT NULL,
+    end_ts BIGINT NOT NULL,
+    bar_count BIGINT NOT NULL DEFAULT 1,
+    detail TEXT NOT NULL DEFAULT '',
+    status VARCHAR(16) NOT NULL DEFAULT 'open',
+    repair_attempts INTEGER NOT NULL DEFAULT 0,
+    last_repair_at TIMESTAMPTZ,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ,
+    CONSTRAINT uniq_candle_quality_findings_issue
+        UNIQUE (inst_id, period, issue, start_ts),
+    CONSTRAINT chk_candle_quality_findings_status
+        CHECK (status IN ('open', 'resolved', 'ignored'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_candle_quality_findings_open_range
+    ON candle_quality_findings (inst_id, period, status, start_ts, end_ts);
+
+COMMENT ON TABLE candle_quality_findings IS 'K线数据质量巡检问题，按 inst_id + period + issue + start_ts 去重';
+COMMENT ON COLUMN candle_quality_findings.id IS '自增主键';
+COMMENT ON COLUMN candle_quality_findings.inst_id IS '交易对';
+COMMENT ON COLUMN candle_quality_findings.period IS 'K线周期，如 1m、1H、4H';
+COMMENT ON COLUMN candle_quality_findings.issue IS '问题类型：gap、duplicate、zero_volume_run、ohlc_inconsistent、stale_unconfirmed、outlier_wick';
+COMMENT ON COLUMN candle_quality_findings.start_ts IS '受影响K线开盘时间起点（毫秒，含）';
+COMMENT ON COLUMN candle_quality_findings.end_ts IS '受影响K线开盘时间终点（毫秒，含）';
+COMMENT ON COLUMN candle_quality_findings.bar_count IS '受影响K线根数，缺口为缺失根数';
+COMMENT ON COLUMN candle_quality_findings.detail IS '问题说明';
+COMMENT ON COLUMN candle_quality_findings.status IS '状态：open 未解决、resolved 重新扫描后已消失、ignored 人工确认为真实行情';
+COMMENT ON COLUMN candle_quality_findings.repair_attempts IS '自动重新拉取修复的尝试次数';
+COMMENT ON COLUMN candle_quality_findings.last_repair_at IS '最近一次修复尝试时间';
+COMMENT ON COLUMN candle_quality_findings.first_seen_at IS '首次发现时间';
+COMMENT ON COLUMN candle_quality_findings.last_seen_at IS '最近一次扫描仍存在的时间';
+COMMENT ON COLUMN candle_quality_findings.resolved_at IS '标记为 resolved 的时间';
+
+COMMIT;
@@ -0,0 +1,9 @@
+BEGIN;
+
+-- 回测汇总表记录持仓跨越结算时刻累计的永续资金费，与价差盈亏、手续费分开核对。
+ALTER TABLE IF EXISTS back_test_log
+    ADD COLUMN IF NOT EXISTS total_funding_fee DOUBLE PRECISION NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN back_test_log.total_funding_fee IS '回测期间累计资金费，正数为净支付、负数为净收取';
+
+COMMIT;